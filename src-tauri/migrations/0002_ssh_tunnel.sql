@@ -0,0 +1,7 @@
+-- connections 表增加 SSH 隧道配置
+ALTER TABLE connections ADD COLUMN ssh_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE connections ADD COLUMN ssh_host TEXT;
+ALTER TABLE connections ADD COLUMN ssh_port INTEGER DEFAULT 22;
+ALTER TABLE connections ADD COLUMN ssh_username TEXT;
+ALTER TABLE connections ADD COLUMN ssh_password TEXT;
+ALTER TABLE connections ADD COLUMN ssh_key_path TEXT;
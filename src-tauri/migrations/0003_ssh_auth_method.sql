@@ -0,0 +1,5 @@
+-- SSH 隧道认证方式：password / key / agent
+ALTER TABLE connections ADD COLUMN ssh_auth_method TEXT NOT NULL DEFAULT 'password';
+
+-- 已配置私钥路径的连接沿用私钥认证
+UPDATE connections SET ssh_auth_method = 'key' WHERE ssh_key_path IS NOT NULL AND ssh_key_path != '';
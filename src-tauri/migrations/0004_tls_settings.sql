@@ -0,0 +1,5 @@
+-- connections 表增加 TLS 配置（CA 证书、客户端证书与私钥，用于双向 TLS）
+ALTER TABLE connections ADD COLUMN ssl_mode TEXT;
+ALTER TABLE connections ADD COLUMN ssl_ca_path TEXT;
+ALTER TABLE connections ADD COLUMN ssl_cert_path TEXT;
+ALTER TABLE connections ADD COLUMN ssl_key_path TEXT;
@@ -0,0 +1,7 @@
+-- 主密码：只保存 argon2 盐值和校验密文，不保存主密码本身
+CREATE TABLE IF NOT EXISTS master_password (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    salt TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,5 @@
+-- 分组支持嵌套，parent_id 为空表示顶层分组
+ALTER TABLE connection_groups ADD COLUMN parent_id INTEGER REFERENCES connection_groups(id) ON DELETE SET NULL;
+
+-- 连接标签，JSON 字符串数组
+ALTER TABLE connections ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,2 @@
+-- 只读连接：后端拒绝所有写操作
+ALTER TABLE connections ADD COLUMN read_only BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- 连接的扩展配置（字符集、时区、超时等），JSON 对象，新增配置项无需再改表结构
+ALTER TABLE connections ADD COLUMN options TEXT NOT NULL DEFAULT '{}';
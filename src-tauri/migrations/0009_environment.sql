@@ -0,0 +1,2 @@
+-- 连接所属环境（dev / staging / prod），prod 连接上的破坏性操作需要二次确认
+ALTER TABLE connections ADD COLUMN environment TEXT;
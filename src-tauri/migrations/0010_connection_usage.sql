@@ -0,0 +1,3 @@
+-- 连接的最近使用时间和累计执行次数，用于按最近使用排序和清理不再使用的连接
+ALTER TABLE connections ADD COLUMN last_used_at DATETIME;
+ALTER TABLE connections ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+-- 执行过的 SQL / Redis 命令历史，pinned 的记录不会被清理
+CREATE TABLE IF NOT EXISTS query_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER NOT NULL,
+    query_text TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    row_count INTEGER,
+    affected_rows INTEGER,
+    success BOOLEAN NOT NULL DEFAULT 1,
+    error TEXT,
+    pinned BOOLEAN NOT NULL DEFAULT 0,
+    executed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_query_history_connection ON query_history (connection_id, executed_at);
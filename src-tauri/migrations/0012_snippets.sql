@@ -0,0 +1,14 @@
+-- 保存的 SQL / Redis 片段，connection_id 为空表示全局片段；folder 为 "/" 分隔的目录路径
+CREATE TABLE IF NOT EXISTS snippets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    language TEXT NOT NULL DEFAULT 'sql',
+    folder TEXT,
+    description TEXT,
+    connection_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_snippets_connection ON snippets (connection_id);
@@ -0,0 +1,15 @@
+-- 数据表格 UPDATE / DELETE 执行前的行快照，用于 undo_last_change；
+-- columns / primary_key 为加好引号的列名，rows 为各行的 SQL 字面量（均为 JSON 数组）
+CREATE TABLE IF NOT EXISTS undo_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER NOT NULL,
+    db_name TEXT,
+    operation TEXT NOT NULL,
+    table_ref TEXT NOT NULL,
+    columns TEXT NOT NULL,
+    primary_key TEXT NOT NULL,
+    rows TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_undo_snapshots_connection ON undo_snapshots (connection_id, id);
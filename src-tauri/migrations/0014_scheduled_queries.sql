@@ -0,0 +1,34 @@
+-- 定时执行的查询：sql 与 snippet_id 二选一，schedule 为 5 段 cron 表达式；
+-- next_run_at / last_run_at 为 UTC 时间，停用时 next_run_at 为空
+CREATE TABLE IF NOT EXISTS scheduled_queries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    connection_id INTEGER NOT NULL,
+    db_name TEXT,
+    sql TEXT,
+    snippet_id INTEGER,
+    schedule TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    next_run_at DATETIME,
+    last_run_at DATETIME,
+    last_status TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_queries_next_run ON scheduled_queries (enabled, next_run_at);
+
+-- 每次执行的状态和结果，rows 为结果集前若干行（JSON 数组）
+CREATE TABLE IF NOT EXISTS scheduled_query_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    schedule_id INTEGER NOT NULL,
+    started_at DATETIME NOT NULL,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL,
+    affected_rows INTEGER NOT NULL DEFAULT 0,
+    row_count INTEGER NOT NULL DEFAULT 0,
+    rows TEXT,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_query_runs_schedule ON scheduled_query_runs (schedule_id, id);
@@ -0,0 +1,785 @@
+use crate::autocomplete::invalidate_metadata;
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::{ensure_sql_allowed, ensure_writable};
+use crate::models::{AlterOperation, AlterTableArgs, AlterTableResult, ColumnDefinition};
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::result_cache::invalidate_results;
+use crate::result_export::sql_literal;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::table_structure::{tokenize, Token, TokenKind};
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::Value;
+use sqlx::{Acquire, Row};
+use std::time::Instant;
+use tauri::{command, State};
+
+pub const FOREIGN_KEYS_OFF: &str = "PRAGMA foreign_keys = OFF";
+pub const FOREIGN_KEYS_ON: &str = "PRAGMA foreign_keys = ON";
+// MySQL 的外键检查是会话变量，关闭后无论成功与否都要在归还连接前恢复
+pub const FOREIGN_KEY_CHECKS_OFF: &str = "SET FOREIGN_KEY_CHECKS = 0";
+pub const FOREIGN_KEY_CHECKS_ON: &str = "SET FOREIGN_KEY_CHECKS = 1";
+const FOREIGN_KEY_CHECK: &str = "PRAGMA foreign_key_check";
+
+// CREATE TABLE 括号内以这些关键字开头的是表级约束，其余为列定义
+const TABLE_CONSTRAINT_KEYWORDS: &[&str] = &["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+
+pub fn quote_identifier(name: &str, flavor: SqlFlavor) -> String {
+    match flavor {
+        SqlFlavor::MySql => format!("`{}`", name.replace('`', "``")),
+        SqlFlavor::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+// 列名列表，用于主键、索引和外键的 (a, b)
+pub fn quote_list(columns: &[String], flavor: SqlFlavor) -> String {
+    columns
+        .iter()
+        .map(|c| quote_identifier(c, flavor))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// MySQL 指定库时为 `db`.`name`；SQLite 忽略库名
+pub fn qualified_name(db_name: Option<&str>, name: &str, flavor: SqlFlavor) -> String {
+    match (db_name, flavor) {
+        (Some(db), SqlFlavor::MySql) => format!(
+            "{}.{}",
+            quote_identifier(db, flavor),
+            quote_identifier(name, flavor)
+        ),
+        _ => quote_identifier(name, flavor),
+    }
+}
+
+// 类型和默认值按原样拼入 DDL，只拒绝会截断或追加语句的内容
+pub fn check_fragment(label: &str, text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err(format!("{} cannot be empty", label));
+    }
+    if text.contains(';') || text.contains("--") || text.contains("/*") {
+        return Err(format!(
+            "{} must not contain ';' or comments: {}",
+            label, text
+        ));
+    }
+    Ok(())
+}
+
+pub fn column_definition_sql(
+    definition: &ColumnDefinition,
+    flavor: SqlFlavor,
+) -> Result<String, String> {
+    check_fragment("Column data type", &definition.data_type)?;
+    let mut sql = format!(
+        "{} {}",
+        quote_identifier(&definition.name, flavor),
+        definition.data_type.trim()
+    );
+    match flavor {
+        SqlFlavor::MySql => sql.push_str(if definition.not_null {
+            " NOT NULL"
+        } else {
+            " NULL"
+        }),
+        SqlFlavor::Sqlite if definition.not_null => sql.push_str(" NOT NULL"),
+        SqlFlavor::Sqlite => {}
+    }
+    if let Some(default_value) = &definition.default_value {
+        sql.push_str(&default_clause(default_value, flavor)?);
+    }
+    match flavor {
+        SqlFlavor::MySql => {
+            if definition.auto_increment {
+                sql.push_str(" AUTO_INCREMENT");
+            }
+            if let Some(comment) = &definition.comment {
+                sql.push_str(&format!(
+                    " COMMENT {}",
+                    sql_literal(&Value::String(comment.clone()), SqlFlavor::MySql)
+                ));
+            }
+        }
+        SqlFlavor::Sqlite => {
+            if definition.auto_increment {
+                return Err(
+                    "SQLite only supports AUTOINCREMENT on an INTEGER PRIMARY KEY".to_string(),
+                );
+            }
+            if definition.comment.is_some() {
+                return Err("SQLite does not support column comments".to_string());
+            }
+        }
+    }
+    Ok(sql)
+}
+
+// SQLite 的非字面量默认值必须加括号，统一加上
+fn default_clause(default_value: &str, flavor: SqlFlavor) -> Result<String, String> {
+    check_fragment("Default value", default_value)?;
+    Ok(match flavor {
+        SqlFlavor::MySql => format!(" DEFAULT {}", default_value.trim()),
+        SqlFlavor::Sqlite => format!(" DEFAULT ({})", default_value.trim()),
+    })
+}
+
+fn mysql_statements(args: &AlterTableArgs) -> Result<Vec<String>, String> {
+    let flavor = SqlFlavor::MySql;
+    let table_ref = |table: &str| match &args.db_name {
+        Some(db) => format!(
+            "{}.{}",
+            quote_identifier(db, flavor),
+            quote_identifier(table, flavor)
+        ),
+        None => quote_identifier(table, flavor),
+    };
+    let position = |first: bool, after: &Option<String>| match (first, after) {
+        (true, _) => " FIRST".to_string(),
+        (false, Some(column)) => format!(" AFTER {}", quote_identifier(column, flavor)),
+        (false, None) => String::new(),
+    };
+
+    let mut table = args.table.clone();
+    let mut statements = Vec::with_capacity(args.operations.len());
+    for operation in &args.operations {
+        let alter = format!("ALTER TABLE {}", table_ref(&table));
+        statements.push(match operation {
+            AlterOperation::AddColumn {
+                definition,
+                first,
+                after,
+            } => format!(
+                "{} ADD COLUMN {}{}",
+                alter,
+                column_definition_sql(definition, flavor)?,
+                position(*first, after)
+            ),
+            AlterOperation::DropColumn { column } => {
+                format!("{} DROP COLUMN {}", alter, quote_identifier(column, flavor))
+            }
+            AlterOperation::ModifyColumn { column, definition } => format!(
+                "{} CHANGE COLUMN {} {}",
+                alter,
+                quote_identifier(column, flavor),
+                column_definition_sql(definition, flavor)?
+            ),
+            // RENAME COLUMN 需要 MySQL 8.0 / MariaDB 10.5，旧版本可用 modify_column 改名
+            AlterOperation::RenameColumn { column, new_name } => format!(
+                "{} RENAME COLUMN {} TO {}",
+                alter,
+                quote_identifier(column, flavor),
+                quote_identifier(new_name, flavor)
+            ),
+            AlterOperation::RenameTable { new_name } => {
+                let statement = format!("{} RENAME TO {}", alter, table_ref(new_name));
+                table = new_name.clone();
+                statement
+            }
+            AlterOperation::SetDefault {
+                column,
+                default_value,
+            } => format!(
+                "{} ALTER COLUMN {} SET{}",
+                alter,
+                quote_identifier(column, flavor),
+                default_clause(default_value, flavor)?
+            ),
+            AlterOperation::DropDefault { column } => format!(
+                "{} ALTER COLUMN {} DROP DEFAULT",
+                alter,
+                quote_identifier(column, flavor)
+            ),
+        });
+    }
+    Ok(statements)
+}
+
+// CREATE TABLE 括号内的一项。列记录其数据来自原表的哪一列（新增列为 None），用于重建时搬迁数据
+enum TableItem {
+    Column {
+        name: String,
+        source: Option<String>,
+        sql: String,
+    },
+    Constraint(String),
+}
+
+impl TableItem {
+    fn sql(&self) -> &str {
+        match self {
+            TableItem::Column { sql, .. } | TableItem::Constraint(sql) => sql,
+        }
+    }
+
+    fn is_column(&self, column: &str) -> bool {
+        matches!(self, TableItem::Column { name, .. } if name.eq_ignore_ascii_case(column))
+    }
+}
+
+// 标识符（非字符串字面量）是否为 name
+fn is_identifier(sql: &str, token: &Token, name: &str) -> bool {
+    match token.kind {
+        TokenKind::Word => token.value.eq_ignore_ascii_case(name),
+        TokenKind::Quoted => {
+            sql.as_bytes()[token.start] != b'\'' && token.value.eq_ignore_ascii_case(name)
+        }
+        TokenKind::Punct(_) => false,
+    }
+}
+
+fn references_identifier(sql: &str, name: &str) -> bool {
+    tokenize(sql).iter().any(|t| is_identifier(sql, t, name))
+}
+
+// 把语句中的标识符 old 替换为 new（已加引号）
+fn rename_identifier(sql: &str, old: &str, new: &str) -> String {
+    let mut renamed = sql.to_string();
+    for token in tokenize(sql).iter().rev() {
+        if is_identifier(sql, token, old) {
+            renamed.replace_range(token.start..token.end, new);
+        }
+    }
+    renamed
+}
+
+// 去掉列定义中的 DEFAULT 子句：DEFAULT 后为括号表达式、带符号的数字或单个字面量
+fn remove_default(sql: &str) -> String {
+    let tokens = tokenize(sql);
+    let Some(index) = tokens
+        .iter()
+        .position(|t| t.kind == TokenKind::Word && t.value.eq_ignore_ascii_case("DEFAULT"))
+    else {
+        return sql.to_string();
+    };
+    let mut end = index + 1;
+    match tokens.get(end).map(|t| &t.kind) {
+        Some(TokenKind::Punct(b'(')) => {
+            let mut depth = 0;
+            for (i, token) in tokens.iter().enumerate().skip(end) {
+                match token.kind {
+                    TokenKind::Punct(b'(') => depth += 1,
+                    TokenKind::Punct(b')') => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = i;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Some(TokenKind::Punct(b'+' | b'-')) => end += 1,
+        _ => {}
+    }
+    // 小数被切成 数字 . 数字
+    while matches!(
+        tokens.get(end + 1).map(|t| &t.kind),
+        Some(TokenKind::Punct(b'.'))
+    ) && tokens.get(end + 2).is_some()
+    {
+        end += 2;
+    }
+    let Some(last) = tokens.get(end) else {
+        return sql[..tokens[index].start].trim_end().to_string();
+    };
+    format!(
+        "{} {}",
+        sql[..tokens[index].start].trim_end(),
+        sql[last.end..].trim_start()
+    )
+    .trim()
+    .to_string()
+}
+
+// 拆出 CREATE TABLE 括号内的各项和括号后的表选项（WITHOUT ROWID、STRICT）
+fn parse_create_table(sql: &str) -> Result<(Vec<TableItem>, String), String> {
+    let tokens = tokenize(sql);
+    let open = tokens
+        .iter()
+        .position(|t| t.kind == TokenKind::Punct(b'('))
+        .ok_or("Unsupported table definition: CREATE TABLE ... AS SELECT")?;
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut segment = tokens[open].end;
+    let mut close = None;
+    for token in &tokens[open..] {
+        match token.kind {
+            TokenKind::Punct(b'(') => depth += 1,
+            TokenKind::Punct(b')') => {
+                depth -= 1;
+                if depth == 0 {
+                    items.push(sql[segment..token.start].trim().to_string());
+                    close = Some(token.end);
+                    break;
+                }
+            }
+            TokenKind::Punct(b',') if depth == 1 => {
+                items.push(sql[segment..token.start].trim().to_string());
+                segment = token.end;
+            }
+            _ => {}
+        }
+    }
+    let close = close.ok_or("Unterminated table definition")?;
+    let items = items
+        .into_iter()
+        .map(|item| {
+            let first = tokenize(&item).into_iter().next();
+            match first {
+                Some(token)
+                    if token.kind == TokenKind::Word
+                        && TABLE_CONSTRAINT_KEYWORDS
+                            .iter()
+                            .any(|k| token.value.eq_ignore_ascii_case(k)) =>
+                {
+                    TableItem::Constraint(item)
+                }
+                Some(token) => TableItem::Column {
+                    source: Some(token.value.clone()),
+                    name: token.value,
+                    sql: item,
+                },
+                None => TableItem::Constraint(item),
+            }
+        })
+        .collect();
+    Ok((items, sql[close..].trim().to_string()))
+}
+
+// 原表的索引和触发器：(类型, 名称, 建立语句)
+type SqliteSchemaObject = (String, String, String);
+
+// SQLite 的 ALTER TABLE 只支持改名、加列（末尾）和删列，其余操作按官方文档的步骤重建表：
+// 建新表 -> 复制数据 -> 删旧表 -> 新表改名 -> 重建索引和触发器 -> 检查外键
+fn sqlite_statements(
+    table: &str,
+    create_sql: &str,
+    objects: &[SqliteSchemaObject],
+    args: &AlterTableArgs,
+    foreign_keys_enabled: bool,
+) -> Result<Vec<String>, String> {
+    let flavor = SqlFlavor::Sqlite;
+    let (mut items, options) = parse_create_table(create_sql)?;
+    let mut name = table.to_string();
+    let mut renamed_columns: Vec<(String, String)> = Vec::new();
+    let mut dropped_columns: Vec<String> = Vec::new();
+    let mut native = Vec::new();
+    let mut rebuild = false;
+
+    let find = |items: &[TableItem], column: &str| {
+        items
+            .iter()
+            .position(|item| item.is_column(column))
+            .ok_or_else(|| format!("Unknown column: {}", column))
+    };
+    for operation in &args.operations {
+        let alter = format!("ALTER TABLE {}", quote_identifier(&name, flavor));
+        match operation {
+            AlterOperation::AddColumn {
+                definition,
+                first,
+                after,
+            } => {
+                let sql = column_definition_sql(definition, flavor)?;
+                let index = match (first, after) {
+                    (true, _) => items
+                        .iter()
+                        .position(|i| matches!(i, TableItem::Column { .. })),
+                    (false, Some(column)) => Some(find(&items, column)? + 1),
+                    (false, None) => None,
+                };
+                rebuild |= index.is_some();
+                native.push(format!("{} ADD COLUMN {}", alter, sql));
+                let item = TableItem::Column {
+                    name: definition.name.clone(),
+                    source: None,
+                    sql,
+                };
+                let last_column = items
+                    .iter()
+                    .rposition(|i| matches!(i, TableItem::Column { .. }))
+                    .map_or(0, |i| i + 1);
+                items.insert(index.unwrap_or(last_column), item);
+            }
+            AlterOperation::DropColumn { column } => {
+                let index = find(&items, column)?;
+                native.push(format!(
+                    "{} DROP COLUMN {}",
+                    alter,
+                    quote_identifier(column, flavor)
+                ));
+                // 索引引用的是原表中的列名
+                if let TableItem::Column {
+                    source: Some(source),
+                    ..
+                } = items.remove(index)
+                {
+                    dropped_columns.push(source);
+                }
+            }
+            AlterOperation::ModifyColumn { column, definition } => {
+                let index = find(&items, column)?;
+                rebuild = true;
+                let sql = column_definition_sql(definition, flavor)?;
+                if let TableItem::Column { name, source, .. } = &items[index] {
+                    if !name.eq_ignore_ascii_case(&definition.name) {
+                        renamed_columns.push((name.clone(), definition.name.clone()));
+                    }
+                    items[index] = TableItem::Column {
+                        name: definition.name.clone(),
+                        source: source.clone(),
+                        sql,
+                    };
+                }
+            }
+            AlterOperation::RenameColumn { column, new_name } => {
+                find(&items, column)?;
+                native.push(format!(
+                    "{} RENAME COLUMN {} TO {}",
+                    alter,
+                    quote_identifier(column, flavor),
+                    quote_identifier(new_name, flavor)
+                ));
+                renamed_columns.push((column.clone(), new_name.clone()));
+                let quoted = quote_identifier(new_name, flavor);
+                for item in items.iter_mut() {
+                    match item {
+                        TableItem::Column { name, sql, .. } => {
+                            if name.eq_ignore_ascii_case(column) {
+                                *name = new_name.clone();
+                            }
+                            *sql = rename_identifier(sql, column, &quoted);
+                        }
+                        TableItem::Constraint(sql) => {
+                            *sql = rename_identifier(sql, column, &quoted)
+                        }
+                    }
+                }
+            }
+            AlterOperation::RenameTable { new_name } => {
+                native.push(format!(
+                    "{} RENAME TO {}",
+                    alter,
+                    quote_identifier(new_name, flavor)
+                ));
+                name = new_name.clone();
+            }
+            AlterOperation::SetDefault {
+                column,
+                default_value,
+            } => {
+                let index = find(&items, column)?;
+                rebuild = true;
+                let clause = default_clause(default_value, flavor)?;
+                if let TableItem::Column { sql, .. } = &mut items[index] {
+                    *sql = format!("{}{}", remove_default(sql), clause);
+                }
+            }
+            AlterOperation::DropDefault { column } => {
+                let index = find(&items, column)?;
+                rebuild = true;
+                if let TableItem::Column { sql, .. } = &mut items[index] {
+                    *sql = remove_default(sql);
+                }
+            }
+        }
+    }
+    if !rebuild {
+        return Ok(native);
+    }
+
+    // 被删除的列仍被表级约束或索引引用时，原生 DROP COLUMN 同样会失败，这里提前报错而不是悄悄丢掉索引
+    for item in &items {
+        if let TableItem::Constraint(sql) = item {
+            if let Some(column) = dropped_columns
+                .iter()
+                .find(|column| references_identifier(sql, column))
+            {
+                return Err(format!(
+                    "Column {} is used by table constraint \"{}\"; remove the constraint first",
+                    column, sql
+                ));
+            }
+        }
+    }
+    for (kind, object, sql) in objects {
+        if kind == "index" {
+            if let Some(column) = dropped_columns
+                .iter()
+                .find(|column| references_identifier(sql, column))
+            {
+                return Err(format!(
+                    "Column {} is used by index {}; drop the index first",
+                    column, object
+                ));
+            }
+        }
+    }
+
+    let original = quote_identifier(table, flavor);
+    let staging = quote_identifier(&format!("xdb_new_{}", name), flavor);
+    let body: Vec<&str> = items.iter().map(TableItem::sql).collect();
+    let (targets, sources): (Vec<String>, Vec<String>) = items
+        .iter()
+        .filter_map(|item| match item {
+            TableItem::Column {
+                name,
+                source: Some(source),
+                ..
+            } => Some((
+                quote_identifier(name, flavor),
+                quote_identifier(source, flavor),
+            )),
+            _ => None,
+        })
+        .unzip();
+
+    let mut statements = Vec::new();
+    if foreign_keys_enabled {
+        statements.push(FOREIGN_KEYS_OFF.to_string());
+    }
+    statements.push(format!(
+        "CREATE TABLE {} (\n  {}\n){}",
+        staging,
+        body.join(",\n  "),
+        if options.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", options)
+        }
+    ));
+    if !targets.is_empty() {
+        statements.push(format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {}",
+            staging,
+            targets.join(", "),
+            sources.join(", "),
+            original
+        ));
+    }
+    statements.push(format!("DROP TABLE {}", original));
+    statements.push(format!(
+        "ALTER TABLE {} RENAME TO {}",
+        staging,
+        quote_identifier(&name, flavor)
+    ));
+    for (_, _, sql) in objects {
+        let mut sql = rename_identifier(sql, table, &quote_identifier(&name, flavor));
+        for (old, new) in &renamed_columns {
+            sql = rename_identifier(&sql, old, &quote_identifier(new, flavor));
+        }
+        statements.push(sql);
+    }
+    if foreign_keys_enabled {
+        statements.push(FOREIGN_KEY_CHECK.to_string());
+        statements.push(FOREIGN_KEYS_ON.to_string());
+    }
+    Ok(statements)
+}
+
+// 逐条执行。MySQL 的 DDL 会隐式提交，中途失败时前面的语句已生效。
+// CREATE TRIGGER / ALTER VIEW 等不能作为预处理语句执行，按文本协议发送
+async fn execute_ddl_mysql(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    statements: &[String],
+) -> Result<(), String> {
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, db_name).await?;
+    let mut result = Ok(());
+    for (i, statement) in statements.iter().enumerate() {
+        result = sqlx::raw_sql(statement)
+            .execute(&mut *conn)
+            .await
+            .map(|_| ())
+            .map_err(|e| match i {
+                0 => format!("Failed to execute DDL: {}", e),
+                _ => format!(
+                    "Failed to execute DDL at statement {} (earlier statements were applied): {}",
+                    i + 1,
+                    e
+                ),
+            });
+        if result.is_err() {
+            break;
+        }
+    }
+    if result.is_err() && statements.first().map(String::as_str) == Some(FOREIGN_KEY_CHECKS_OFF) {
+        let _ = sqlx::raw_sql(FOREIGN_KEY_CHECKS_ON)
+            .execute(&mut *conn)
+            .await;
+    }
+    result
+}
+
+// SQLite 的 DDL 支持事务：除 foreign_keys 开关（事务中无效）外整体在一个事务中执行
+async fn execute_ddl_sqlite(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    statements: &[String],
+) -> Result<(), String> {
+    let pool = sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+    let disable_foreign_keys = statements.first().map(String::as_str) == Some(FOREIGN_KEYS_OFF);
+    if disable_foreign_keys {
+        sqlx::query(FOREIGN_KEYS_OFF)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+    }
+    let result = async {
+        let mut tx = conn
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        for statement in statements {
+            match statement.as_str() {
+                FOREIGN_KEYS_OFF | FOREIGN_KEYS_ON => {}
+                FOREIGN_KEY_CHECK => {
+                    let violations = sqlx::query(FOREIGN_KEY_CHECK)
+                        .fetch_all(&mut *tx)
+                        .await
+                        .map_err(|e| format!("Failed to check foreign keys: {}", e))?;
+                    if let Some(row) = violations.first() {
+                        let parent: String = row.try_get(2).unwrap_or_default();
+                        return Err(format!(
+                            "Foreign key check failed: {} row(s) reference missing rows in {}",
+                            violations.len(),
+                            parent
+                        ));
+                    }
+                }
+                _ => {
+                    sqlx::query(statement)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| format!("Failed to execute DDL: {}", e))?;
+                }
+            }
+        }
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit: {}", e))
+    }
+    .await;
+    if disable_foreign_keys {
+        let _ = sqlx::query(FOREIGN_KEYS_ON).execute(&mut *conn).await;
+    }
+    result
+}
+
+// 表结构修改命令共用的执行流程：检查只读与高危语句，执行后记录统计和历史，并清空结果缓存
+pub async fn apply_ddl(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    flavor: SqlFlavor,
+    db_name: Option<String>,
+    statements: &[String],
+    confirm_token: Option<&str>,
+) -> Result<(), String> {
+    ensure_writable(db_state, connection_id).await?;
+    let script = format!("{};", statements.join(";\n"));
+    ensure_sql_allowed(db_state, connection_id, &script, confirm_token, true).await?;
+    let started = Instant::now();
+    let result = match flavor {
+        SqlFlavor::MySql => {
+            execute_ddl_mysql(app_state, db_state, connection_id, db_name, statements).await
+        }
+        SqlFlavor::Sqlite => {
+            execute_ddl_sqlite(app_state, db_state, connection_id, statements).await
+        }
+    };
+    let elapsed = started.elapsed();
+    record_query(app_state, db_state, connection_id, elapsed).await;
+    let outcome = HistoryOutcome::of(&result, |_| HistoryOutcome::Done);
+    record_history(db_state, connection_id, &script, elapsed, outcome).await;
+    invalidate_results(app_state, connection_id).await;
+    invalidate_metadata(app_state, connection_id).await;
+    result
+}
+
+// 按结构化的操作列表生成 ALTER TABLE 语句（SQLite 不支持的操作生成重建表的完整步骤）。
+// 未确认时只返回语句供预览；confirmed 为 true 时执行，生产环境的 DROP 需要确认令牌
+#[command]
+pub async fn alter_table(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: AlterTableArgs,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<AlterTableResult, String> {
+    if args.operations.is_empty() {
+        return Err("No operations to apply".to_string());
+    }
+    let flavor = connection_flavor(&db_state, connection_id, "Altering tables").await?;
+
+    let statements = match flavor {
+        SqlFlavor::MySql => mysql_statements(&args)?,
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let create_sql = sqlx::query_scalar::<_, Option<String>>(
+                "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?",
+            )
+            .bind(&args.table)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| format!("Failed to fetch table definition: {}", e))?
+            .flatten()
+            .ok_or_else(|| format!("Table {} not found", args.table))?;
+            let objects = sqlx::query_as::<_, SqliteSchemaObject>(
+                "SELECT type, name, sql FROM sqlite_master \
+                 WHERE tbl_name = ? AND type IN ('index', 'trigger') AND sql IS NOT NULL \
+                 ORDER BY type, name",
+            )
+            .bind(&args.table)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to fetch indexes and triggers: {}", e))?;
+            let foreign_keys_enabled = sqlx::query_scalar::<_, i64>("PRAGMA foreign_keys")
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| format!("Failed to read foreign_keys: {}", e))?
+                != 0;
+            sqlite_statements(
+                &args.table,
+                &create_sql,
+                &objects,
+                &args,
+                foreign_keys_enabled,
+            )?
+        }
+    };
+    if confirmed != Some(true) {
+        return Ok(AlterTableResult {
+            statements,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        args.db_name.clone(),
+        &statements,
+        confirm_token.as_deref(),
+    )
+    .await?;
+
+    Ok(AlterTableResult {
+        statements,
+        executed: true,
+    })
+}
@@ -0,0 +1,289 @@
+use crate::alter_table::quote_identifier;
+use crate::db::{connection_flavor, DbState};
+use crate::models::{AutocompleteColumn, AutocompleteMetadata, AutocompleteTable, ColumnInfo};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{command, State};
+
+// 在应用外修改的结构不会触发失效，缓存超过该时间后重新读取
+const METADATA_TTL: Duration = Duration::from_secs(300);
+
+struct SchemaMetadata {
+    tables: Vec<AutocompleteTable>,
+    routines: Vec<String>,
+    loaded_at: Instant,
+}
+
+// 单个连接的元数据：库列表和各个库的表 / 列 / 例程分别加载和过期
+#[derive(Default)]
+pub struct MetadataCache {
+    databases: Option<(Vec<String>, Instant)>,
+    schemas: HashMap<Option<String>, SchemaMetadata>,
+    // 单表查询结果列的来源定义（可空、主键、默认值等），按 (库, 表) 缓存，
+    // 避免每次执行查询都读取 INFORMATION_SCHEMA
+    table_columns: HashMap<(Option<String>, String), (Vec<ColumnInfo>, Instant)>,
+}
+
+fn fresh(loaded_at: Instant) -> bool {
+    loaded_at.elapsed() < METADATA_TTL
+}
+
+// 连接上执行了 DDL 或连接被关闭时，丢弃该连接的元数据
+pub async fn invalidate_metadata(app_state: &AppState, connection_id: i64) {
+    app_state
+        .autocomplete_cache
+        .lock()
+        .await
+        .remove(&connection_id);
+}
+
+// 读取缓存的表列定义，不存在或已过期时返回 None
+pub async fn cached_table_columns(
+    app_state: &AppState,
+    connection_id: i64,
+    schema: Option<&str>,
+    table: &str,
+) -> Option<Vec<ColumnInfo>> {
+    let cache = app_state.autocomplete_cache.lock().await;
+    let key = (schema.map(str::to_string), table.to_string());
+    cache
+        .get(&connection_id)?
+        .table_columns
+        .get(&key)
+        .filter(|(_, loaded_at)| fresh(*loaded_at))
+        .map(|(columns, _)| columns.clone())
+}
+
+pub async fn store_table_columns(
+    app_state: &AppState,
+    connection_id: i64,
+    schema: Option<&str>,
+    table: &str,
+    columns: Vec<ColumnInfo>,
+) {
+    let mut cache = app_state.autocomplete_cache.lock().await;
+    cache
+        .entry(connection_id)
+        .or_default()
+        .table_columns
+        .insert(
+            (schema.map(str::to_string), table.to_string()),
+            (columns, Instant::now()),
+        );
+}
+
+fn text(row: &MySqlRow, index: usize) -> String {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+// 按 (表名, 列名, 类型) 的有序行组装表
+fn group_columns(
+    rows: impl IntoIterator<Item = (String, String, String)>,
+) -> Vec<AutocompleteTable> {
+    let mut tables: Vec<AutocompleteTable> = Vec::new();
+    for (table, name, data_type) in rows {
+        let column = AutocompleteColumn { name, data_type };
+        match tables.last_mut() {
+            Some(last) if last.name == table => last.columns.push(column),
+            _ => tables.push(AutocompleteTable {
+                name: table,
+                columns: vec![column],
+            }),
+        }
+    }
+    tables
+}
+
+async fn mysql_metadata(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    with_databases: bool,
+) -> Result<(Option<Vec<String>>, Vec<AutocompleteTable>, Vec<String>), String> {
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, db_name.clone())
+            .await?;
+    let databases = match with_databases {
+        true => Some(
+            sqlx::query(
+                "SELECT CAST(SCHEMA_NAME AS CHAR) FROM information_schema.SCHEMATA \
+                 ORDER BY SCHEMA_NAME",
+            )
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to fetch databases: {}", e))?
+            .iter()
+            .map(|row| text(row, 0))
+            .collect(),
+        ),
+        false => None,
+    };
+    let rows = sqlx::query(
+        "SELECT CAST(TABLE_NAME AS CHAR), CAST(COLUMN_NAME AS CHAR), CAST(DATA_TYPE AS CHAR) \
+         FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) \
+         ORDER BY TABLE_NAME, ORDINAL_POSITION",
+    )
+    .bind(db_name.as_deref())
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let tables = group_columns(
+        rows.iter()
+            .map(|row| (text(row, 0), text(row, 1), text(row, 2))),
+    );
+    let routines = sqlx::query(
+        "SELECT CAST(ROUTINE_NAME AS CHAR) FROM information_schema.ROUTINES \
+         WHERE ROUTINE_SCHEMA = COALESCE(?, DATABASE()) ORDER BY ROUTINE_NAME",
+    )
+    .bind(db_name.as_deref())
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch routines: {}", e))?
+    .iter()
+    .map(|row| text(row, 0))
+    .collect();
+    Ok((databases, tables, routines))
+}
+
+// SQLite 的“库”为 main / temp 和 ATTACH 的库
+async fn sqlite_metadata(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    with_databases: bool,
+) -> Result<(Option<Vec<String>>, Vec<AutocompleteTable>, Vec<String>), String> {
+    let pool = sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+    let databases = match with_databases {
+        true => Some(
+            sqlx::query_scalar::<_, String>("SELECT name FROM pragma_database_list ORDER BY seq")
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| format!("Failed to fetch databases: {}", e))?,
+        ),
+        false => None,
+    };
+    let schema = db_name.unwrap_or_else(|| "main".to_string());
+    let rows = sqlx::query_as::<_, (String, String, String)>(&format!(
+        "SELECT m.name, p.name, p.type FROM {}.sqlite_master m \
+         JOIN pragma_table_info(m.name, ?) p \
+         WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%' \
+         ORDER BY m.name, p.cid",
+        quote_identifier(&schema, SqlFlavor::Sqlite)
+    ))
+    .bind(&schema)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    Ok((databases, group_columns(rows), Vec::new()))
+}
+
+// 返回 SQL 编辑器自动补全用的库、表、列和存储过程 / 函数名。元数据按连接缓存，
+// 库列表和每个库的内容按需分别加载；连接上执行 DDL 后失效，refresh 为 true 时重新读取指定库
+#[command]
+pub async fn get_autocomplete_metadata(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    refresh: Option<bool>,
+) -> Result<AutocompleteMetadata, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Autocomplete metadata").await?;
+    // MySQL 未指定库时使用 use_database 设置的当前库
+    let database = match (db_name, flavor) {
+        (None, SqlFlavor::MySql) => app_state
+            .active_databases
+            .lock()
+            .await
+            .get(&connection_id)
+            .cloned(),
+        (db_name, _) => db_name,
+    };
+    let refresh = refresh.unwrap_or(false);
+
+    let need_databases = {
+        let mut cache = app_state.autocomplete_cache.lock().await;
+        let entry = cache.entry(connection_id).or_default();
+        if refresh {
+            entry.databases = None;
+            entry.schemas.remove(&database);
+        }
+        let databases = entry
+            .databases
+            .as_ref()
+            .filter(|(_, loaded_at)| fresh(*loaded_at));
+        let schema = entry
+            .schemas
+            .get(&database)
+            .filter(|schema| fresh(schema.loaded_at));
+        if let (Some((databases, _)), Some(schema)) = (databases, schema) {
+            return Ok(AutocompleteMetadata {
+                databases: databases.clone(),
+                database,
+                tables: schema.tables.clone(),
+                routines: schema.routines.clone(),
+                cached: true,
+            });
+        }
+        databases.is_none()
+    };
+
+    let (databases, tables, routines) = match flavor {
+        SqlFlavor::MySql => {
+            mysql_metadata(
+                &app_state,
+                &db_state,
+                connection_id,
+                database.clone(),
+                need_databases,
+            )
+            .await?
+        }
+        SqlFlavor::Sqlite => {
+            sqlite_metadata(
+                &app_state,
+                &db_state,
+                connection_id,
+                database.clone(),
+                need_databases,
+            )
+            .await?
+        }
+    };
+
+    let now = Instant::now();
+    let mut cache = app_state.autocomplete_cache.lock().await;
+    let entry = cache.entry(connection_id).or_default();
+    if let Some(databases) = databases {
+        entry.databases = Some((databases, now));
+    }
+    entry.schemas.insert(
+        database.clone(),
+        SchemaMetadata {
+            tables: tables.clone(),
+            routines: routines.clone(),
+            loaded_at: now,
+        },
+    );
+    let databases = entry
+        .databases
+        .as_ref()
+        .map(|(databases, _)| databases.clone())
+        .unwrap_or_default();
+    Ok(AutocompleteMetadata {
+        databases,
+        database,
+        tables,
+        routines,
+        cached: false,
+    })
+}
@@ -0,0 +1,333 @@
+use crate::alter_table::quote_identifier;
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::{ensure_sql_allowed, ensure_writable};
+use crate::models::{BulkUpdateArgs, BulkUpdateResult, BulkUpdateSample};
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::result_cache::invalidate_results;
+use crate::sql_classifier::{single_table_write, SqlFlavor, WriteTarget};
+use crate::state::AppState;
+use crate::undo::{capture_mysql_snapshot, capture_sqlite_snapshot, save_snapshot};
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::{Map, Value};
+use sqlx::Connection as _;
+use std::time::Instant;
+use tauri::{command, State};
+
+const BULK_UPDATE_DEFAULT_SAMPLE_SIZE: u32 = 20;
+const BULK_UPDATE_MAX_SAMPLE_SIZE: u32 = 500;
+// 预览查询中新值列的别名前缀，后接 SET 的序号
+const NEW_VALUE_PREFIX: &str = "__xdb_new_";
+
+// 拼出 UPDATE 并重新解析：必须是单条单表 UPDATE，防止表达式或条件中夹带其他语句
+fn build_update(args: &BulkUpdateArgs, flavor: SqlFlavor) -> Result<(String, WriteTarget), String> {
+    if args.set.is_empty() {
+        return Err("At least one column to update is required".to_string());
+    }
+    let table = args
+        .table
+        .split('.')
+        .map(|part| quote_identifier(part.trim(), flavor))
+        .collect::<Vec<_>>()
+        .join(".");
+    let assignments: Vec<String> = args
+        .set
+        .iter()
+        .map(|set| {
+            format!(
+                "{} = {}",
+                quote_identifier(&set.column, flavor),
+                set.expression
+            )
+        })
+        .collect();
+    let mut sql = format!("UPDATE {} SET {}", table, assignments.join(", "));
+    if let Some(condition) = args
+        .where_clause
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    {
+        sql.push_str(&format!(" WHERE {}", condition));
+    }
+    match single_table_write(&sql, flavor) {
+        Some(target) if target.operation == "UPDATE" => Ok((sql, target)),
+        _ => {
+            Err("The SET expressions or WHERE clause do not form a single-table UPDATE".to_string())
+        }
+    }
+}
+
+fn where_sql(target: &WriteTarget) -> String {
+    target
+        .selection
+        .as_ref()
+        .map_or(String::new(), |selection| format!(" WHERE {}", selection))
+}
+
+fn count_sql(target: &WriteTarget, flavor: SqlFlavor) -> String {
+    let mut sql = format!(
+        "SELECT COUNT(*) FROM {}{}",
+        target.from_clause,
+        where_sql(target)
+    );
+    // 执行前锁住满足条件的行，统计到的行数与随后更新的行一致
+    if matches!(flavor, SqlFlavor::MySql) {
+        sql.push_str(" FOR UPDATE");
+    }
+    sql
+}
+
+fn sample_sql(args: &BulkUpdateArgs, target: &WriteTarget, sample_size: u32) -> String {
+    let new_values: Vec<String> = args
+        .set
+        .iter()
+        .enumerate()
+        .map(|(i, set)| format!("{} AS {}{}", set.expression, NEW_VALUE_PREFIX, i))
+        .collect();
+    format!(
+        "SELECT *, {} FROM {}{} LIMIT {}",
+        new_values.join(", "),
+        target.from_clause,
+        where_sql(target),
+        sample_size
+    )
+}
+
+// 把预览行中的新值列拆出来，得到更新前后的两行
+fn split_sample(args: &BulkUpdateArgs, mut row: Map<String, Value>) -> BulkUpdateSample {
+    let new_values: Vec<Value> = (0..args.set.len())
+        .map(|i| {
+            row.remove(&format!("{}{}", NEW_VALUE_PREFIX, i))
+                .unwrap_or(Value::Null)
+        })
+        .collect();
+    let mut after = row.clone();
+    for (set, value) in args.set.iter().zip(new_values) {
+        after.insert(set.column.clone(), value);
+    }
+    BulkUpdateSample { before: row, after }
+}
+
+async fn preview(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    args: &BulkUpdateArgs,
+    flavor: SqlFlavor,
+    target: &WriteTarget,
+) -> Result<(u64, Vec<Map<String, Value>>), String> {
+    let count = format!(
+        "SELECT COUNT(*) FROM {}{}",
+        target.from_clause,
+        where_sql(target)
+    );
+    let sample_size = args
+        .sample_size
+        .unwrap_or(BULK_UPDATE_DEFAULT_SAMPLE_SIZE)
+        .min(BULK_UPDATE_MAX_SAMPLE_SIZE);
+    let sample = sample_sql(args, target, sample_size);
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                connection_id,
+                args.db_name.clone(),
+            )
+            .await?;
+            let matched = sqlx::query_scalar::<_, i64>(&count)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to count matching rows: {}", e))?;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(app_state, connection_id).await;
+            let rows = sqlx::query(&sample)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to preview rows: {}", e))?
+                .iter()
+                .map(|row| mysql_manager::row_to_json(row, timestamp_display))
+                .collect();
+            Ok((matched as u64, rows))
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let matched = sqlx::query_scalar::<_, i64>(&count)
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| format!("Failed to count matching rows: {}", e))?;
+            let rows = sqlx::query(&sample)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| format!("Failed to preview rows: {}", e))?
+                .iter()
+                .map(sqlite_manager::row_to_json)
+                .collect();
+            Ok((matched as u64, rows))
+        }
+    }
+}
+
+fn check_expected(matched: i64, expected_rows: Option<u64>) -> Result<(), String> {
+    match expected_rows {
+        Some(expected) if matched as u64 != expected => Err(format!(
+            "{} rows now match the WHERE clause but {} were previewed; preview again before updating",
+            matched, expected
+        )),
+        _ => Ok(()),
+    }
+}
+
+// 在事务中统计并更新；expected_rows 与预览时的行数不一致时回滚
+async fn execute(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    args: &BulkUpdateArgs,
+    flavor: SqlFlavor,
+    sql: &str,
+    target: &WriteTarget,
+    expected_rows: Option<u64>,
+    snapshot: bool,
+) -> Result<(u64, u64), String> {
+    let count = count_sql(target, flavor);
+    let (matched, affected, change_snapshot) = match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                connection_id,
+                args.db_name.clone(),
+            )
+            .await?;
+            let mut tx = conn
+                .begin()
+                .await
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+            let matched = sqlx::query_scalar::<_, i64>(&count)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to count matching rows: {}", e))?;
+            check_expected(matched, expected_rows)?;
+            let change_snapshot = match snapshot {
+                true => capture_mysql_snapshot(&mut tx, sql).await,
+                false => None,
+            };
+            let result = sqlx::query(sql)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Statement execution failed: {}", e))?;
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+            (matched, result.rows_affected(), change_snapshot)
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+            let matched = sqlx::query_scalar::<_, i64>(&count)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to count matching rows: {}", e))?;
+            check_expected(matched, expected_rows)?;
+            let change_snapshot = match snapshot {
+                true => capture_sqlite_snapshot(&mut tx, sql).await,
+                false => None,
+            };
+            let result = sqlx::query(sql)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Statement execution failed: {}", e))?;
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+            (matched, result.rows_affected(), change_snapshot)
+        }
+    };
+    if let Some(change_snapshot) = change_snapshot {
+        save_snapshot(
+            db_state,
+            connection_id,
+            args.db_name.as_deref(),
+            change_snapshot,
+        )
+        .await;
+    }
+    Ok((matched as u64, affected))
+}
+
+// 批量更新向导：未 confirmed 时只预览，返回满足条件的行数和部分行更新前后的值；
+// confirmed 后在事务中执行 UPDATE。传入预览得到的 expected_rows 时，执行前满足条件的行数
+// 与之不一致则回滚。snapshot 为 true 时保存快照，可用 undo_last_change 撤销
+#[command]
+pub async fn bulk_update(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: BulkUpdateArgs,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+    expected_rows: Option<u64>,
+    snapshot: Option<bool>,
+) -> Result<BulkUpdateResult, String> {
+    ensure_writable(&db_state, connection_id).await?;
+    let flavor = connection_flavor(&db_state, connection_id, "Bulk update").await?;
+    let (sql, target) = build_update(&args, flavor)?;
+
+    let execution_id = new_execution_id();
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    if confirmed != Some(true) {
+        let (matched_rows, rows) =
+            preview(&app_state, &db_state, connection_id, &args, flavor, &target).await?;
+        return Ok(BulkUpdateResult {
+            sql,
+            matched_rows,
+            sample: rows
+                .into_iter()
+                .map(|row| split_sample(&args, row))
+                .collect(),
+            affected_rows: None,
+        });
+    }
+
+    ensure_sql_allowed(
+        &db_state,
+        connection_id,
+        &sql,
+        confirm_token.as_deref(),
+        true,
+    )
+    .await?;
+    let started = Instant::now();
+    let result = execute(
+        &app_state,
+        &db_state,
+        connection_id,
+        &args,
+        flavor,
+        &sql,
+        &target,
+        expected_rows,
+        snapshot.unwrap_or(false),
+    )
+    .await;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let outcome = HistoryOutcome::of(&result, |(_, affected)| HistoryOutcome::Affected(*affected));
+    record_history(&db_state, connection_id, &sql, elapsed, outcome).await;
+    let (matched_rows, affected_rows) = result?;
+    invalidate_results(&app_state, connection_id).await;
+
+    Ok(BulkUpdateResult {
+        sql,
+        matched_rows,
+        sample: Vec::new(),
+        affected_rows: Some(affected_rows),
+    })
+}
@@ -0,0 +1,535 @@
+use crate::connection_stats::record_query;
+use crate::db::DbState;
+use crate::models::{ColumnInfo, Connection};
+use crate::ssh_tunnel::resolve_endpoint;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta};
+use scylla::client::session::Session;
+use scylla::client::session_builder::SessionBuilder;
+use scylla::cluster::metadata::{CollectionType, ColumnType};
+use scylla::response::{PagingState, PagingStateResponse};
+use scylla::statement::Statement;
+use scylla::value::{CqlValue, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tauri::{command, State};
+use tokio::time::{Duration, Instant};
+
+const CASSANDRA_CONNECT_TIMEOUT_SECS: u64 = 10;
+const CASSANDRA_DEFAULT_PAGE_SIZE: i32 = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CqlPageResult {
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Map<String, Value>>,
+    // 十六进制编码的分页状态，为空表示没有更多数据
+    pub paging_state: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CassandraColumn {
+    pub name: String,
+    pub type_name: String,
+    pub kind: String, // partition_key / clustering / regular / static
+    pub position: i32,
+}
+
+// 辅助函数：获取或创建 Cassandra/ScyllaDB 会话
+async fn get_or_create_session(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Arc<Session>, String> {
+    {
+        let sessions = app_state.cassandra_sessions.lock().await;
+        if let Some(session) = sessions.get(&connection_id) {
+            return Ok(session.clone());
+        }
+    }
+
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
+
+    if connection.db_type != "cassandra" {
+        return Err("Only Cassandra is supported for this operation".to_string());
+    }
+
+    let host = connection.host.clone().ok_or("Host is required")?;
+    let port = connection.port.unwrap_or(9042) as u16;
+    // 启用 SSH 时经隧道连接（只转发该节点，驱动发现的其他节点无法直连）
+    let (host, port) = resolve_endpoint(app_state, &connection, &host, port).await?;
+
+    let mut builder = SessionBuilder::new()
+        .known_node(format!("{}:{}", host, port))
+        .connection_timeout(Duration::from_secs(CASSANDRA_CONNECT_TIMEOUT_SECS));
+    if let Some(username) = connection.username.filter(|u| !u.is_empty()) {
+        builder = builder.user(username, connection.password.unwrap_or_default());
+    }
+    // connection.database 作为默认 keyspace
+    if let Some(keyspace) = connection.database.filter(|k| !k.is_empty()) {
+        builder = builder.use_keyspace(keyspace, false);
+    }
+
+    let session = Arc::new(
+        builder
+            .build()
+            .await
+            .map_err(|e| format!("Failed to connect to Cassandra: {}", e))?,
+    );
+
+    let mut sessions = app_state.cassandra_sessions.lock().await;
+    sessions.insert(connection_id, session.clone());
+
+    Ok(session)
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err("Invalid paging state".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "Invalid paging state".to_string())
+        })
+        .collect()
+}
+
+// 大端有符号补码 → 十进制字符串；超出 i128 的极大值退化为 hex
+fn signed_be_bytes_to_string(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "0".to_string();
+    }
+    if bytes.len() > 16 {
+        return format!("0x{}", bytes_to_hex(bytes));
+    }
+    let fill = if bytes[0] & 0x80 != 0 { 0xFF } else { 0x00 };
+    let mut buf = [fill; 16];
+    buf[16 - bytes.len()..].copy_from_slice(bytes);
+    i128::from_be_bytes(buf).to_string()
+}
+
+fn apply_decimal_scale(digits: String, scale: i32) -> String {
+    if scale == 0 || digits == "0" || digits.starts_with("0x") {
+        return digits;
+    }
+    if scale < 0 {
+        return format!("{}{}", digits, "0".repeat(scale.unsigned_abs() as usize));
+    }
+    let (sign, abs) = match digits.strip_prefix('-') {
+        Some(rest) => ("-", rest.to_string()),
+        None => ("", digits),
+    };
+    let scale = scale as usize;
+    let padded = format!("{:0>width$}", abs, width = scale + 1);
+    let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+    format!("{}{}.{}", sign, int_part, frac_part)
+}
+
+fn column_type_name(typ: &ColumnType) -> String {
+    match typ {
+        ColumnType::Native(native) => format!("{:?}", native).to_lowercase(),
+        ColumnType::Collection { frozen, typ } => {
+            let inner = match typ {
+                CollectionType::List(t) => format!("list<{}>", column_type_name(t)),
+                CollectionType::Set(t) => format!("set<{}>", column_type_name(t)),
+                CollectionType::Map(k, v) => {
+                    format!("map<{}, {}>", column_type_name(k), column_type_name(v))
+                }
+                _ => "collection".to_string(),
+            };
+            if *frozen {
+                format!("frozen<{}>", inner)
+            } else {
+                inner
+            }
+        }
+        ColumnType::Vector { typ, dimensions } => {
+            format!("vector<{}, {}>", column_type_name(typ), dimensions)
+        }
+        ColumnType::UserDefinedType { definition, .. } => definition.name.to_string(),
+        ColumnType::Tuple(types) => format!(
+            "tuple<{}>",
+            types
+                .iter()
+                .map(column_type_name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => "unknown".to_string(),
+    }
+}
+
+// 将 CQL 值转换为 JSON：bigint/varint/decimal/counter 用字符串保精度，集合递归转换
+fn cql_value_to_json(value: CqlValue) -> Value {
+    match value {
+        CqlValue::Ascii(s) | CqlValue::Text(s) => Value::String(s),
+        CqlValue::Boolean(b) => Value::Bool(b),
+        CqlValue::Blob(bytes) => Value::String(format!("0x{}", bytes_to_hex(&bytes))),
+        CqlValue::Counter(c) => Value::String(c.0.to_string()),
+        CqlValue::BigInt(v) => Value::String(v.to_string()),
+        CqlValue::Int(v) => Value::Number(v.into()),
+        CqlValue::SmallInt(v) => Value::Number(v.into()),
+        CqlValue::TinyInt(v) => Value::Number(v.into()),
+        CqlValue::Float(v) => v
+            .to_string()
+            .parse::<f64>()
+            .ok()
+            .map(Value::from)
+            .unwrap_or(Value::Null),
+        CqlValue::Double(v) => Value::from(v),
+        CqlValue::Varint(v) => {
+            Value::String(signed_be_bytes_to_string(v.as_signed_bytes_be_slice()))
+        }
+        CqlValue::Decimal(v) => {
+            let (bytes, scale) = v.as_signed_be_bytes_slice_and_exponent();
+            Value::String(apply_decimal_scale(signed_be_bytes_to_string(bytes), scale))
+        }
+        CqlValue::Timestamp(ts) => DateTime::from_timestamp_millis(ts.0)
+            .map(|dt| Value::String(dt.format("%Y-%m-%d %H:%M:%S%.3f").to_string()))
+            .unwrap_or_else(|| Value::String(ts.0.to_string())),
+        CqlValue::Date(date) => {
+            // CQL date 以 2^31 为纪元(1970-01-01)
+            let days = date.0 as i64 - (1i64 << 31);
+            NaiveDate::from_ymd_opt(1970, 1, 1)
+                .and_then(|epoch| epoch.checked_add_signed(TimeDelta::try_days(days)?))
+                .map(|d| Value::String(d.to_string()))
+                .unwrap_or_else(|| Value::String(days.to_string()))
+        }
+        CqlValue::Time(time) => NaiveTime::from_num_seconds_from_midnight_opt(
+            (time.0 / 1_000_000_000) as u32,
+            (time.0 % 1_000_000_000) as u32,
+        )
+        .map(|t| Value::String(t.to_string()))
+        .unwrap_or(Value::Null),
+        CqlValue::Duration(d) => Value::String(format!(
+            "{}mo{}d{}ns",
+            d.months, d.days, d.nanoseconds
+        )),
+        CqlValue::Inet(addr) => Value::String(addr.to_string()),
+        CqlValue::Uuid(uuid) => Value::String(uuid.to_string()),
+        CqlValue::Timeuuid(uuid) => Value::String(uuid.to_string()),
+        CqlValue::Empty => Value::Null,
+        CqlValue::List(items) | CqlValue::Set(items) | CqlValue::Vector(items) => {
+            Value::Array(items.into_iter().map(cql_value_to_json).collect())
+        }
+        CqlValue::Map(entries) => {
+            let mut map = Map::new();
+            for (k, v) in entries {
+                let key = match cql_value_to_json(k) {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                map.insert(key, cql_value_to_json(v));
+            }
+            Value::Object(map)
+        }
+        CqlValue::UserDefinedType { fields, .. } => {
+            let mut map = Map::new();
+            for (name, v) in fields {
+                map.insert(name, v.map(cql_value_to_json).unwrap_or(Value::Null));
+            }
+            Value::Object(map)
+        }
+        CqlValue::Tuple(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|v| v.map(cql_value_to_json).unwrap_or(Value::Null))
+                .collect(),
+        ),
+        other => Value::String(format!("{:?}", other)),
+    }
+}
+
+// 执行单条 CQL 并把结果第一列收集为字符串列表（用于 system_schema 元数据查询）
+async fn query_string_column(
+    session: &Session,
+    cql: &str,
+    values: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let rows = session
+        .query_unpaged(cql, values)
+        .await
+        .map_err(|e| format!("Query execution failed: {}", e))?
+        .into_rows_result()
+        .map_err(|e| format!("Query execution failed: {}", e))?;
+
+    rows.rows::<(String,)>()
+        .map_err(|e| format!("Failed to decode rows: {}", e))?
+        .map(|row| row.map(|(s,)| s).map_err(|e| format!("Failed to decode row: {}", e)))
+        .collect()
+}
+
+#[command]
+pub async fn list_cassandra_keyspaces(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<String>, String> {
+    let session = get_or_create_session(&app_state, &db_state, connection_id).await?;
+    let mut keyspaces = query_string_column(
+        &session,
+        "SELECT keyspace_name FROM system_schema.keyspaces",
+        vec![],
+    )
+    .await?;
+    keyspaces.sort();
+    Ok(keyspaces)
+}
+
+#[command]
+pub async fn list_cassandra_tables(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    keyspace: String,
+) -> Result<Vec<String>, String> {
+    let session = get_or_create_session(&app_state, &db_state, connection_id).await?;
+    let mut tables = query_string_column(
+        &session,
+        "SELECT table_name FROM system_schema.tables WHERE keyspace_name = ?",
+        vec![keyspace],
+    )
+    .await?;
+    tables.sort();
+    Ok(tables)
+}
+
+#[command]
+pub async fn get_cassandra_table_columns(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    keyspace: String,
+    table: String,
+) -> Result<Vec<CassandraColumn>, String> {
+    let session = get_or_create_session(&app_state, &db_state, connection_id).await?;
+
+    let rows = session
+        .query_unpaged(
+            "SELECT column_name, type, kind, position FROM system_schema.columns \
+             WHERE keyspace_name = ? AND table_name = ?",
+            (keyspace, table),
+        )
+        .await
+        .map_err(|e| format!("Query execution failed: {}", e))?
+        .into_rows_result()
+        .map_err(|e| format!("Query execution failed: {}", e))?;
+
+    let mut columns = rows
+        .rows::<(String, String, String, i32)>()
+        .map_err(|e| format!("Failed to decode rows: {}", e))?
+        .map(|row| {
+            row.map(|(name, type_name, kind, position)| CassandraColumn {
+                name,
+                type_name,
+                kind,
+                position,
+            })
+            .map_err(|e| format!("Failed to decode row: {}", e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 按主键顺序排列：分区键 → 聚簇键 → 其余列
+    let kind_order = |kind: &str| match kind {
+        "partition_key" => 0,
+        "clustering" => 1,
+        "static" => 2,
+        _ => 3,
+    };
+    columns.sort_by(|a, b| {
+        kind_order(&a.kind)
+            .cmp(&kind_order(&b.kind))
+            .then(a.position.cmp(&b.position))
+            .then(a.name.cmp(&b.name))
+    });
+
+    Ok(columns)
+}
+
+fn skip_quoted(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            // 连续两个引号为转义
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    i
+}
+
+fn followed_by_dot(bytes: &[u8], mut i: usize) -> bool {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    bytes.get(i) == Some(&b'.')
+}
+
+// 给 FROM / INTO / UPDATE / TABLE / TRUNCATE 之后未带 keyspace 的表名加上 keyspace 前缀，
+// 跳过字符串、带引号的标识符和注释
+fn qualify_cql(cql: &str, keyspace: &str) -> String {
+    let bytes = cql.as_bytes();
+    let mut positions = Vec::new();
+    let mut expect_table = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let next = bytes.get(i + 1).copied();
+        if c == b'\'' || c == b'"' {
+            let start = i;
+            i = skip_quoted(bytes, i);
+            if c == b'"' && expect_table && !followed_by_dot(bytes, i) {
+                positions.push(start);
+            }
+            expect_table = false;
+        } else if (c == b'-' && next == Some(b'-')) || (c == b'/' && next == Some(b'/')) {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        } else if c == b'/' && next == Some(b'*') {
+            i += 2;
+            while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(bytes.len());
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            let word = cql[start..i].to_ascii_uppercase();
+            if !expect_table {
+                expect_table = matches!(
+                    word.as_str(),
+                    "FROM" | "INTO" | "UPDATE" | "TABLE" | "TRUNCATE"
+                );
+            } else if !matches!(word.as_str(), "TABLE" | "IF" | "NOT" | "EXISTS") {
+                if !followed_by_dot(bytes, i) {
+                    positions.push(start);
+                }
+                expect_table = false;
+            }
+        } else {
+            if !c.is_ascii_whitespace() {
+                expect_table = false;
+            }
+            i += 1;
+        }
+    }
+
+    let prefix = format!("\"{}\".", keyspace.replace('"', "\"\""));
+    let mut qualified = String::with_capacity(cql.len() + positions.len() * prefix.len());
+    let mut last = 0;
+    for position in positions {
+        qualified.push_str(&cql[last..position]);
+        qualified.push_str(&prefix);
+        last = position;
+    }
+    qualified.push_str(&cql[last..]);
+    qualified
+}
+
+#[command]
+pub async fn execute_cql(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    cql: String,
+    keyspace: Option<String>,
+    page_size: Option<i32>,
+    paging_state: Option<String>,
+) -> Result<CqlPageResult, String> {
+    let session = get_or_create_session(&app_state, &db_state, connection_id).await?;
+
+    // 会话在各标签页间共享，不能用 USE 切换；改为在语句中限定 keyspace
+    let cql = match keyspace.filter(|k| !k.is_empty()) {
+        Some(ks) => qualify_cql(&cql, &ks),
+        None => cql,
+    };
+
+    let statement = Statement::new(cql)
+        .with_page_size(page_size.unwrap_or(CASSANDRA_DEFAULT_PAGE_SIZE).max(1));
+    let paging_state = match paging_state.filter(|s| !s.is_empty()) {
+        Some(hex) => PagingState::new_from_raw_bytes(hex_to_bytes(&hex)?),
+        None => PagingState::start(),
+    };
+
+    let started = Instant::now();
+    let result = session
+        .query_single_page(statement, &[], paging_state)
+        .await;
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
+    let (result, paging_response) =
+        result.map_err(|e| format!("Query execution failed: {}", e))?;
+
+    let next_paging_state = match paging_response {
+        PagingStateResponse::HasMorePages { state } => {
+            state.as_bytes_slice().map(|bytes| bytes_to_hex(bytes))
+        }
+        PagingStateResponse::NoMorePages => None,
+    };
+
+    // 非查询语句（INSERT/DDL 等）没有结果集
+    if !result.is_rows() {
+        return Ok(CqlPageResult {
+            columns: vec![],
+            rows: vec![],
+            paging_state: None,
+        });
+    }
+
+    let rows_result = result
+        .into_rows_result()
+        .map_err(|e| format!("Query execution failed: {}", e))?;
+
+    let columns: Vec<ColumnInfo> = rows_result
+        .column_specs()
+        .iter()
+        .map(|spec| ColumnInfo {
+            name: spec.name().to_string(),
+            type_name: column_type_name(spec.typ()),
+            ..Default::default()
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for row in rows_result
+        .rows::<Row>()
+        .map_err(|e| format!("Failed to decode rows: {}", e))?
+    {
+        let row = row.map_err(|e| format!("Failed to decode row: {}", e))?;
+        let mut json_row = Map::new();
+        for (column, value) in columns.iter().zip(row.columns) {
+            json_row.insert(
+                column.name.clone(),
+                value.map(cql_value_to_json).unwrap_or(Value::Null),
+            );
+        }
+        rows.push(json_row);
+    }
+
+    Ok(CqlPageResult {
+        columns,
+        rows,
+        paging_state: next_paging_state,
+    })
+}
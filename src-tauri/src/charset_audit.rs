@@ -0,0 +1,249 @@
+use crate::alter_table::{apply_ddl, qualified_name, quote_identifier};
+use crate::db::{connection_flavor, DbState};
+use crate::models::{CharsetAudit, ColumnCharset, TableCharset};
+use crate::mysql_manager;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use sqlx::mysql::MySqlRow;
+use sqlx::pool::PoolConnection;
+use sqlx::{MySql, Row};
+use std::collections::HashMap;
+use tauri::{command, State};
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 字符集和排序规则只有 MySQL 系有
+async fn ensure_mysql(db_state: &DbState, connection_id: i64) -> Result<(), String> {
+    match connection_flavor(db_state, connection_id, "Charset audit").await? {
+        SqlFlavor::MySql => Ok(()),
+        SqlFlavor::Sqlite => Err("Charset audit is not supported for sqlite".to_string()),
+    }
+}
+
+// 目标 (字符集, 排序规则)：指定排序规则时由它确定字符集，只指定字符集时取其默认排序规则，
+// 都未指定时为库的默认值。名称以服务端返回的为准，可直接拼入语句
+async fn resolve_target(
+    conn: &mut PoolConnection<MySql>,
+    charset: Option<String>,
+    collation: Option<String>,
+    defaults: (String, String),
+) -> Result<(String, String), String> {
+    let charset = charset.filter(|c| !c.trim().is_empty());
+    let collation = collation.filter(|c| !c.trim().is_empty());
+    let row = match (&charset, &collation) {
+        (_, Some(collation)) => sqlx::query(
+            "SELECT CAST(CHARACTER_SET_NAME AS CHAR), CAST(COLLATION_NAME AS CHAR) \
+             FROM information_schema.COLLATIONS WHERE COLLATION_NAME = ?",
+        )
+        .bind(collation.trim())
+        .fetch_optional(&mut **conn)
+        .await
+        .map_err(|e| format!("Failed to fetch collations: {}", e))?
+        .ok_or_else(|| format!("Unknown collation: {}", collation))?,
+        (Some(charset), None) => sqlx::query(
+            "SELECT CAST(CHARACTER_SET_NAME AS CHAR), CAST(DEFAULT_COLLATE_NAME AS CHAR) \
+             FROM information_schema.CHARACTER_SETS WHERE CHARACTER_SET_NAME = ?",
+        )
+        .bind(charset.trim())
+        .fetch_optional(&mut **conn)
+        .await
+        .map_err(|e| format!("Failed to fetch character sets: {}", e))?
+        .ok_or_else(|| format!("Unknown character set: {}", charset))?,
+        (None, None) => return Ok(defaults),
+    };
+    let (target_charset, target_collation) = (
+        text(&row, 0).unwrap_or_default(),
+        text(&row, 1).unwrap_or_default(),
+    );
+    if let Some(charset) = charset {
+        if !charset.trim().eq_ignore_ascii_case(&target_charset) {
+            return Err(format!(
+                "Collation {} does not belong to character set {}",
+                target_collation, charset
+            ));
+        }
+    }
+    Ok((target_charset, target_collation))
+}
+
+fn mismatch(
+    charset: Option<&str>,
+    collation: Option<&str>,
+    target: &(String, String),
+) -> Option<String> {
+    match (charset, collation) {
+        (Some(charset), _) if !charset.eq_ignore_ascii_case(&target.0) => {
+            Some("charset".to_string())
+        }
+        (_, Some(collation)) if !collation.eq_ignore_ascii_case(&target.1) => {
+            Some("collation".to_string())
+        }
+        _ => None,
+    }
+}
+
+// 列出库中每张表及其字符类型列的字符集和排序规则，标出与目标（默认为库的默认字符集）不一致的，
+// 并生成转换语句：有列不一致的表用 CONVERT TO 转换全部列，只有表默认值不一致时修改默认值。
+// 转为 utf8mb4 等更宽的字符集时 TEXT 类型可能升级、索引长度可能超限，执行前应先预览。
+// confirmed 为 true 时执行，生产环境需要确认令牌
+#[command]
+pub async fn audit_charsets(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    target_charset: Option<String>,
+    target_collation: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<CharsetAudit, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, db_name.clone())
+            .await?;
+    let schema = sqlx::query(
+        "SELECT CAST(SCHEMA_NAME AS CHAR), CAST(DEFAULT_CHARACTER_SET_NAME AS CHAR), \
+         CAST(DEFAULT_COLLATION_NAME AS CHAR) FROM information_schema.SCHEMATA \
+         WHERE SCHEMA_NAME = COALESCE(?, DATABASE())",
+    )
+    .bind(db_name.as_deref())
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch database info: {}", e))?
+    .ok_or("Database not found or no database selected")?;
+    let database = text(&schema, 0).unwrap_or_default();
+    let defaults = (
+        text(&schema, 1).unwrap_or_default(),
+        text(&schema, 2).unwrap_or_default(),
+    );
+    let target = resolve_target(
+        &mut conn,
+        target_charset,
+        target_collation,
+        defaults.clone(),
+    )
+    .await?;
+
+    let rows = sqlx::query(
+        "SELECT CAST(t.TABLE_NAME AS CHAR), CAST(l.CHARACTER_SET_NAME AS CHAR), \
+         CAST(t.TABLE_COLLATION AS CHAR) FROM information_schema.TABLES t \
+         LEFT JOIN information_schema.COLLATIONS l ON l.COLLATION_NAME = t.TABLE_COLLATION \
+         WHERE t.TABLE_SCHEMA = ? AND t.TABLE_TYPE = 'BASE TABLE' ORDER BY t.TABLE_NAME",
+    )
+    .bind(&database)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch tables: {}", e))?;
+    let mut tables: Vec<TableCharset> = rows
+        .iter()
+        .map(|row| {
+            let (charset, collation) = (text(row, 1), text(row, 2));
+            TableCharset {
+                name: text(row, 0).unwrap_or_default(),
+                mismatch: mismatch(charset.as_deref(), collation.as_deref(), &target),
+                charset,
+                collation,
+                columns: Vec::new(),
+            }
+        })
+        .collect();
+    let positions: HashMap<String, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, table)| (table.name.clone(), i))
+        .collect();
+
+    let rows = sqlx::query(
+        "SELECT CAST(TABLE_NAME AS CHAR), CAST(COLUMN_NAME AS CHAR), CAST(COLUMN_TYPE AS CHAR), \
+         CAST(CHARACTER_SET_NAME AS CHAR), CAST(COLLATION_NAME AS CHAR) \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = ? AND COLLATION_NAME IS NOT NULL \
+         ORDER BY TABLE_NAME, ORDINAL_POSITION",
+    )
+    .bind(&database)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    for row in &rows {
+        // 视图的列不在 tables 中
+        let Some(&position) = text(row, 0).and_then(|table| positions.get(&table)) else {
+            continue;
+        };
+        let (charset, collation) = (
+            text(row, 3).unwrap_or_default(),
+            text(row, 4).unwrap_or_default(),
+        );
+        tables[position].columns.push(ColumnCharset {
+            name: text(row, 1).unwrap_or_default(),
+            column_type: text(row, 2).unwrap_or_default(),
+            mismatch: mismatch(Some(&charset), Some(&collation), &target),
+            charset,
+            collation,
+        });
+    }
+    drop(conn);
+
+    let mut statements = Vec::new();
+    if mismatch(Some(&defaults.0), Some(&defaults.1), &target).is_some() {
+        statements.push(format!(
+            "ALTER DATABASE {} CHARACTER SET {} COLLATE {}",
+            quote_identifier(&database, SqlFlavor::MySql),
+            target.0,
+            target.1
+        ));
+    }
+    let mut mismatched_columns = 0;
+    for table in &tables {
+        let columns = table
+            .columns
+            .iter()
+            .filter(|c| c.mismatch.is_some())
+            .count();
+        mismatched_columns += columns;
+        let name = qualified_name(Some(&database), &table.name, SqlFlavor::MySql);
+        if columns > 0 {
+            statements.push(format!(
+                "ALTER TABLE {} CONVERT TO CHARACTER SET {} COLLATE {}",
+                name, target.0, target.1
+            ));
+        } else if table.mismatch.is_some() {
+            statements.push(format!(
+                "ALTER TABLE {} DEFAULT CHARACTER SET {} COLLATE {}",
+                name, target.0, target.1
+            ));
+        }
+    }
+
+    let executed = confirmed == Some(true) && !statements.is_empty();
+    if executed {
+        apply_ddl(
+            &app_state,
+            &db_state,
+            connection_id,
+            SqlFlavor::MySql,
+            Some(database.clone()),
+            &statements,
+            confirm_token.as_deref(),
+        )
+        .await?;
+    }
+    Ok(CharsetAudit {
+        database,
+        default_charset: defaults.0,
+        default_collation: defaults.1,
+        target_charset: target.0,
+        target_collation: target.1,
+        mismatched_tables: tables
+            .iter()
+            .filter(|t| t.mismatch.is_some() || t.columns.iter().any(|c| c.mismatch.is_some()))
+            .count(),
+        mismatched_columns,
+        tables,
+        statements,
+        executed,
+    })
+}
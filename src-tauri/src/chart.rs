@@ -0,0 +1,166 @@
+use crate::alter_table::quote_identifier;
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::models::{ChartQueryArgs, ChartSeries};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::sql_classifier::{is_read_query, SqlFlavor};
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::{Map, Value};
+use std::time::Instant;
+use tauri::{command, State};
+
+// 返回的最大分组数
+const CHART_MAX_POINTS: usize = 1_000;
+
+// 按时间分桶后的分组表达式，结果为可排序的文本（周以周一为起点）
+fn bucket_expression(column: &str, bucket: &str, flavor: SqlFlavor) -> Result<String, String> {
+    let expression = match (flavor, bucket) {
+        (SqlFlavor::MySql, "minute") => format!("DATE_FORMAT({}, '%Y-%m-%d %H:%i')", column),
+        (SqlFlavor::MySql, "hour") => format!("DATE_FORMAT({}, '%Y-%m-%d %H:00')", column),
+        (SqlFlavor::MySql, "day") => format!("DATE_FORMAT({}, '%Y-%m-%d')", column),
+        (SqlFlavor::MySql, "week") => format!(
+            "DATE_FORMAT(DATE_SUB({0}, INTERVAL WEEKDAY({0}) DAY), '%Y-%m-%d')",
+            column
+        ),
+        (SqlFlavor::MySql, "month") => format!("DATE_FORMAT({}, '%Y-%m')", column),
+        (SqlFlavor::MySql, "year") => format!("DATE_FORMAT({}, '%Y')", column),
+        (SqlFlavor::Sqlite, "minute") => format!("strftime('%Y-%m-%d %H:%M', {})", column),
+        (SqlFlavor::Sqlite, "hour") => format!("strftime('%Y-%m-%d %H:00', {})", column),
+        (SqlFlavor::Sqlite, "day") => format!("date({})", column),
+        (SqlFlavor::Sqlite, "week") => format!("date({}, '-6 days', 'weekday 1')", column),
+        (SqlFlavor::Sqlite, "month") => format!("strftime('%Y-%m', {})", column),
+        (SqlFlavor::Sqlite, "year") => format!("strftime('%Y', {})", column),
+        (_, other) => return Err(format!("Unsupported bucket: {}", other)),
+    };
+    Ok(expression)
+}
+
+fn aggregate_expression(
+    aggregate: &str,
+    value_column: Option<&str>,
+    flavor: SqlFlavor,
+) -> Result<String, String> {
+    let column = value_column.map(|c| quote_identifier(c, flavor));
+    let expression = match (aggregate, column) {
+        ("count", None) => "COUNT(*)".to_string(),
+        ("count", Some(column)) => format!("COUNT({})", column),
+        ("count_distinct", Some(column)) => format!("COUNT(DISTINCT {})", column),
+        ("sum" | "avg" | "min" | "max", Some(column)) => {
+            format!("{}({})", aggregate.to_uppercase(), column)
+        }
+        ("count_distinct" | "sum" | "avg" | "min" | "max", None) => {
+            return Err(format!("Aggregate {} requires a value column", aggregate))
+        }
+        (other, _) => return Err(format!("Unsupported aggregate: {}", other)),
+    };
+    Ok(expression)
+}
+
+// 生成聚合语句：SELECT 分组 AS chart_label, 聚合 AS chart_value ... GROUP BY ... ORDER BY ...
+fn chart_sql(args: &ChartQueryArgs, flavor: SqlFlavor) -> Result<String, String> {
+    let source = match (&args.table, &args.sql) {
+        (Some(table), None) => table
+            .split('.')
+            .map(|part| quote_identifier(part.trim(), flavor))
+            .collect::<Vec<_>>()
+            .join("."),
+        (None, Some(sql)) => {
+            if !is_read_query(sql, flavor) {
+                return Err("Only read-only queries can be charted".to_string());
+            }
+            format!("({}) AS chart_source", sql.trim().trim_end_matches(';'))
+        }
+        _ => return Err("Either table or sql is required".to_string()),
+    };
+    let group_by = quote_identifier(&args.group_by, flavor);
+    let label = match args.bucket.as_deref() {
+        Some(bucket) => bucket_expression(&group_by, bucket, flavor)?,
+        None => group_by,
+    };
+    let value = aggregate_expression(&args.aggregate, args.value_column.as_deref(), flavor)?;
+    Ok(format!(
+        "SELECT {} AS chart_label, {} AS chart_value FROM {} \
+         GROUP BY chart_label ORDER BY chart_label LIMIT {}",
+        label,
+        value,
+        source,
+        CHART_MAX_POINTS + 1
+    ))
+}
+
+// 聚合值可能是数字或 DECIMAL 文本，非数值（如字符串列的 MIN）返回 None
+fn numeric_value(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn series_of(rows: Vec<Map<String, Value>>) -> ChartSeries {
+    let truncated = rows.len() > CHART_MAX_POINTS;
+    let (labels, values) = rows
+        .into_iter()
+        .take(CHART_MAX_POINTS)
+        .map(|mut row| {
+            let value = numeric_value(row.get("chart_value"));
+            (row.remove("chart_label").unwrap_or(Value::Null), value)
+        })
+        .unzip();
+    ChartSeries {
+        labels,
+        values,
+        truncated,
+    }
+}
+
+// 在服务端按列（可按时间分桶）分组聚合，只返回图表需要的数据点，不传输原始结果集
+#[command]
+pub async fn run_chart_query(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: ChartQueryArgs,
+) -> Result<ChartSeries, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Charts").await?;
+    let sql = chart_sql(&args, flavor)?;
+
+    let execution_id = new_execution_id();
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let started = Instant::now();
+    let rows = match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                args.db_name.clone(),
+            )
+            .await?;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(&app_state, connection_id).await;
+            sqlx::query(&sql)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| format!("Chart query failed: {}", e))?
+                .iter()
+                .map(|row| mysql_manager::row_to_json(row, timestamp_display))
+                .collect()
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            sqlx::query(&sql)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| format!("Chart query failed: {}", e))?
+                .iter()
+                .map(sqlite_manager::row_to_json)
+                .collect()
+        }
+    };
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
+    Ok(series_of(rows))
+}
@@ -0,0 +1,415 @@
+use crate::connection_stats::record_query;
+use crate::db::DbState;
+use crate::models::{ColumnInfo, Connection, SqlResult};
+use crate::ssh_tunnel::resolve_endpoint;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::time::Instant;
+use tauri::{command, AppHandle, Emitter, State};
+use urlencoding::encode;
+
+const CLICKHOUSE_CONNECT_TIMEOUT_SECS: u64 = 10;
+const CLICKHOUSE_DEFAULT_BLOCK_SIZE: usize = 1000;
+const CLICKHOUSE_RESULT_BLOCK_EVENT: &str = "clickhouse-result-block";
+// 首行列名、次行列类型，之后每行一个 JSON 数组，便于按行流式解析
+const CLICKHOUSE_OUTPUT_FORMAT: &str = "JSONCompactEachRowWithNamesAndTypes";
+
+#[derive(Clone)]
+pub struct ClickHouseClient {
+    http: reqwest::Client,
+    base_url: String,
+    username: String,
+    password: String,
+    database: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ClickHouseBlock {
+    pub stream_id: String,
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Map<String, Value>>,
+    pub done: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClickHouseStreamSummary {
+    pub stream_id: String,
+    pub total_rows: u64,
+}
+
+// 辅助函数：获取或创建 ClickHouse HTTP 客户端
+async fn get_or_create_client(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<ClickHouseClient, String> {
+    {
+        let clients = app_state.clickhouse_clients.lock().await;
+        if let Some(client) = clients.get(&connection_id) {
+            return Ok(client.clone());
+        }
+    }
+
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
+
+    if connection.db_type != "clickhouse" {
+        return Err("Only ClickHouse is supported for this operation".to_string());
+    }
+
+    // host 可直接填写 http(s):// 地址，否则默认走 HTTP 接口 8123 端口
+    let host = connection.host.as_deref().ok_or("Host is required")?;
+    let mut url = match host.contains("://") {
+        true => reqwest::Url::parse(host),
+        false => reqwest::Url::parse(&format!(
+            "http://{}:{}",
+            host,
+            connection.port.unwrap_or(8123)
+        )),
+    }
+    .map_err(|e| format!("Invalid ClickHouse host: {}", e))?;
+    let url_host = url.host_str().ok_or("Host is required")?.to_string();
+    let url_port = url.port_or_known_default().ok_or("Port is required")?;
+    let (endpoint_host, endpoint_port) =
+        resolve_endpoint(app_state, &connection, &url_host, url_port).await?;
+
+    let mut http = reqwest::Client::builder().connect_timeout(
+        connection
+            .options
+            .connect_timeout(CLICKHOUSE_CONNECT_TIMEOUT_SECS),
+    );
+    // 经 SSH 隧道时请求发往隧道的本地端口。地址为域名时保留域名（TLS 证书校验和 Host 头），
+    // 只把域名解析到隧道
+    if (endpoint_host.as_str(), endpoint_port) != (url_host.as_str(), url_port) {
+        url.set_port(Some(endpoint_port))
+            .map_err(|_| "Invalid ClickHouse host".to_string())?;
+        match url.domain().map(str::to_string) {
+            Some(domain) => {
+                let addr = format!("{}:{}", endpoint_host, endpoint_port)
+                    .parse()
+                    .map_err(|e| format!("Invalid SSH tunnel address: {}", e))?;
+                http = http.resolve(&domain, addr);
+            }
+            None => url
+                .set_host(Some(&endpoint_host))
+                .map_err(|e| format!("Invalid SSH tunnel address: {}", e))?,
+        }
+    }
+    let http = http
+        .build()
+        .map_err(|e| format!("Failed to create ClickHouse client: {}", e))?;
+    let base_url = url.as_str().trim_end_matches('/').to_string();
+
+    let client = ClickHouseClient {
+        http,
+        base_url,
+        username: connection.username.unwrap_or_else(|| "default".to_string()),
+        password: connection.password.unwrap_or_default(),
+        database: connection.database.filter(|db| !db.is_empty()),
+    };
+
+    let mut clients = app_state.clickhouse_clients.lock().await;
+    clients.insert(connection_id, client.clone());
+
+    Ok(client)
+}
+
+// 去掉 Nullable(...) / LowCardinality(...) 包装，得到实际类型
+fn unwrap_type_name(type_name: &str) -> &str {
+    let mut current = type_name.trim();
+    loop {
+        let inner = current
+            .strip_prefix("Nullable(")
+            .or_else(|| current.strip_prefix("LowCardinality("))
+            .and_then(|t| t.strip_suffix(')'));
+        match inner {
+            Some(t) => current = t.trim(),
+            None => return current,
+        }
+    }
+}
+
+// 按 ClickHouse 列类型转换 JSON 值：64 位以上整数与 Decimal 保持字符串以免丢失精度
+fn clickhouse_value_to_json(type_name: &str, value: Value) -> Value {
+    if value.is_null() {
+        return Value::Null;
+    }
+
+    let type_name = unwrap_type_name(type_name);
+
+    if let Some(element_type) = type_name
+        .strip_prefix("Array(")
+        .and_then(|t| t.strip_suffix(')'))
+    {
+        return match value {
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(|v| clickhouse_value_to_json(element_type, v))
+                    .collect(),
+            ),
+            other => other,
+        };
+    }
+
+    let base_type = type_name.split('(').next().unwrap_or(type_name);
+    match base_type {
+        "Int64" | "UInt64" | "Int128" | "UInt128" | "Int256" | "UInt256" | "Decimal"
+        | "Decimal32" | "Decimal64" | "Decimal128" | "Decimal256" => match value {
+            Value::Number(n) => Value::String(n.to_string()),
+            other => other,
+        },
+        "Bool" => match value {
+            Value::Number(n) => Value::Bool(n.as_u64().unwrap_or(0) != 0),
+            other => other,
+        },
+        // Date / DateTime / DateTime64 / UUID / Enum 等均以字符串输出，原样返回
+        _ => value,
+    }
+}
+
+fn parse_affected_rows(response: &reqwest::Response) -> u64 {
+    response
+        .headers()
+        .get("X-ClickHouse-Summary")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| serde_json::from_str::<Value>(s).ok())
+        .and_then(|summary| {
+            let written = summary.get("written_rows")?;
+            written
+                .as_str()
+                .and_then(|s| s.parse::<u64>().ok())
+                .or_else(|| written.as_u64())
+        })
+        .unwrap_or(0)
+}
+
+// 按行增量读取 HTTP 响应体，避免把整个结果集一次性读入内存
+struct ClickHouseReader {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    finished: bool,
+    columns: Vec<ColumnInfo>,
+    affected_rows: u64,
+}
+
+impl ClickHouseReader {
+    async fn start(
+        client: &ClickHouseClient,
+        sql: &str,
+        db_name: Option<&str>,
+    ) -> Result<Self, String> {
+        let mut url = format!(
+            "{}/?default_format={}&output_format_json_quote_64bit_integers=1&output_format_json_quote_decimals=1",
+            client.base_url, CLICKHOUSE_OUTPUT_FORMAT
+        );
+        if let Some(db) = db_name.or(client.database.as_deref()) {
+            url.push_str(&format!("&database={}", encode(db)));
+        }
+
+        let response = client
+            .http
+            .post(url)
+            .header("X-ClickHouse-User", &client.username)
+            .header("X-ClickHouse-Key", &client.password)
+            .body(sql.to_string())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to ClickHouse: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "Query execution failed ({}): {}",
+                status,
+                body.trim()
+            ));
+        }
+
+        let affected_rows = parse_affected_rows(&response);
+        let mut reader = Self {
+            response,
+            buffer: Vec::new(),
+            finished: false,
+            columns: Vec::new(),
+            affected_rows,
+        };
+        reader.read_header().await?;
+        Ok(reader)
+    }
+
+    async fn next_line(&mut self) -> Result<Option<Vec<u8>>, String> {
+        loop {
+            if let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+                return Ok(Some(self.buffer.drain(..=pos).collect()));
+            }
+            if self.finished {
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(std::mem::take(&mut self.buffer)));
+            }
+            match self
+                .response
+                .chunk()
+                .await
+                .map_err(|e| format!("Failed to read ClickHouse response: {}", e))?
+            {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None => self.finished = true,
+            }
+        }
+    }
+
+    async fn next_array(&mut self) -> Result<Option<Vec<Value>>, String> {
+        while let Some(line) = self.next_line().await? {
+            let line = line.trim_ascii();
+            if line.is_empty() {
+                continue;
+            }
+            // 查询中途出错时 ClickHouse 会在数据流里直接写入异常文本
+            return serde_json::from_slice::<Vec<Value>>(line)
+                .map(Some)
+                .map_err(|_| {
+                    format!(
+                        "Query execution failed: {}",
+                        String::from_utf8_lossy(line).trim()
+                    )
+                });
+        }
+        Ok(None)
+    }
+
+    async fn read_header(&mut self) -> Result<(), String> {
+        // DDL / INSERT 等语句没有响应体
+        let Some(names) = self.next_array().await? else {
+            return Ok(());
+        };
+        let types = self.next_array().await?.unwrap_or_default();
+
+        self.columns = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| ColumnInfo {
+                name: name.as_str().map(str::to_string).unwrap_or_else(|| name.to_string()),
+                type_name: types
+                    .get(i)
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                ..Default::default()
+            })
+            .collect();
+        Ok(())
+    }
+
+    async fn next_rows(&mut self, max_rows: usize) -> Result<Vec<Map<String, Value>>, String> {
+        let mut rows = Vec::new();
+        while rows.len() < max_rows {
+            let Some(values) = self.next_array().await? else {
+                break;
+            };
+            let mut json_row = Map::new();
+            for (column, value) in self.columns.iter().zip(values) {
+                json_row.insert(
+                    column.name.clone(),
+                    clickhouse_value_to_json(&column.type_name, value),
+                );
+            }
+            rows.push(json_row);
+        }
+        Ok(rows)
+    }
+}
+
+#[command]
+pub async fn execute_clickhouse_sql(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+    db_name: Option<String>,
+) -> Result<SqlResult, String> {
+    let client = get_or_create_client(&app_state, &db_state, connection_id).await?;
+    let started = Instant::now();
+    let mut reader = ClickHouseReader::start(&client, &sql, db_name.as_deref()).await?;
+
+    let rows = reader.next_rows(usize::MAX).await;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let rows = rows?;
+
+    Ok(SqlResult {
+        columns: reader.columns,
+        rows,
+        affected_rows: reader.affected_rows,
+        duration_ms: elapsed.as_millis() as u64,
+        warnings: vec![],
+        last_insert_id: None,
+        applied_limit: None,
+        from_cache: false,
+        spilled: None,
+    })
+}
+
+// 大结果集按块推送：每读满 block_size 行即通过事件发送给前端
+#[command]
+pub async fn stream_clickhouse_sql(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+    db_name: Option<String>,
+    stream_id: String,
+    block_size: Option<usize>,
+) -> Result<ClickHouseStreamSummary, String> {
+    let client = get_or_create_client(&app_state, &db_state, connection_id).await?;
+    let started = Instant::now();
+    let mut reader = ClickHouseReader::start(&client, &sql, db_name.as_deref()).await?;
+    let block_size = block_size
+        .unwrap_or(CLICKHOUSE_DEFAULT_BLOCK_SIZE)
+        .max(1);
+
+    let mut total_rows = 0u64;
+    let streamed: Result<(), String> = async {
+        loop {
+            let rows = reader.next_rows(block_size).await?;
+            let done = rows.len() < block_size;
+            total_rows += rows.len() as u64;
+
+            app.emit(
+                CLICKHOUSE_RESULT_BLOCK_EVENT,
+                ClickHouseBlock {
+                    stream_id: stream_id.clone(),
+                    columns: reader.columns.clone(),
+                    rows,
+                    done,
+                },
+            )
+            .map_err(|e| format!("Failed to emit result block: {}", e))?;
+
+            if done {
+                return Ok(());
+            }
+        }
+    }
+    .await;
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
+    streamed?;
+
+    Ok(ClickHouseStreamSummary {
+        stream_id,
+        total_rows,
+    })
+}
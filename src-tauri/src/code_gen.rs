@@ -0,0 +1,713 @@
+use crate::db::{connection_db_type, DbState};
+use crate::fake_data::{enum_values, sqlite_data_type};
+use crate::models::GeneratedCode;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::Value;
+use sqlx::Row;
+use std::collections::BTreeSet;
+use tauri::{command, State};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while",
+];
+const PYTHON_KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield", "metadata",
+];
+
+// 与具体语言无关的列类型
+#[derive(Clone)]
+enum Kind {
+    Bool,
+    Int { bits: u8, unsigned: bool },
+    Float,
+    Double,
+    Decimal,
+    String,
+    Text,
+    Enum(Vec<String>),
+    Date,
+    DateTime,
+    Timestamp,
+    Time,
+    Year,
+    Json,
+    Binary,
+    Unknown,
+}
+
+struct Column {
+    name: String,
+    kind: Kind,
+    // 小写的类型名，如 varchar、decimal
+    data_type: String,
+    nullable: bool,
+    primary_key: bool,
+    auto_increment: bool,
+    max_length: Option<u64>,
+    precision: Option<u64>,
+    scale: Option<u64>,
+    comment: Option<String>,
+}
+
+fn column_kind(data_type: &str, column_type: &str, sqlite: bool) -> Kind {
+    let unsigned = column_type.contains("unsigned");
+    match data_type {
+        "tinyint" if column_type.starts_with("tinyint(1)") => Kind::Bool,
+        "bit" if column_type == "bit(1)" => Kind::Bool,
+        "bool" | "boolean" => Kind::Bool,
+        "tinyint" => Kind::Int { bits: 8, unsigned },
+        "smallint" => Kind::Int { bits: 16, unsigned },
+        // SQLite 的整数都是 64 位
+        "integer" if sqlite => Kind::Int {
+            bits: 64,
+            unsigned: false,
+        },
+        "mediumint" | "int" | "integer" => Kind::Int { bits: 32, unsigned },
+        "bigint" | "bit" => Kind::Int { bits: 64, unsigned },
+        "decimal" | "numeric" => Kind::Decimal,
+        "float" => Kind::Float,
+        "double" | "real" => Kind::Double,
+        "char" | "varchar" => Kind::String,
+        "tinytext" | "text" | "mediumtext" | "longtext" => Kind::Text,
+        "enum" => Kind::Enum(
+            enum_values(column_type)
+                .into_iter()
+                .filter_map(|value| match value {
+                    Value::String(text) => Some(text),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        "set" => Kind::String,
+        "date" => Kind::Date,
+        "datetime" => Kind::DateTime,
+        "timestamp" => Kind::Timestamp,
+        "time" => Kind::Time,
+        "year" => Kind::Year,
+        "json" => Kind::Json,
+        "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => Kind::Binary,
+        _ => Kind::Unknown,
+    }
+}
+
+// 拆分列名 / 表名中的单词：按非字母数字字符和驼峰边界
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn pascal_case(name: &str) -> String {
+    let pascal: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect();
+    match pascal.chars().next() {
+        Some(c) if c.is_alphabetic() => pascal,
+        _ => format!("T{}", pascal),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let snake = words(name).join("_");
+    match snake.chars().next() {
+        Some(c) if c.is_alphabetic() => snake,
+        _ => format!("f_{}", snake),
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+// JSON 字符串字面量同时是合法的 TypeScript / Python 字符串字面量
+fn string_literal(text: &str) -> String {
+    Value::String(text.to_string()).to_string()
+}
+
+fn ts_property(name: &str) -> String {
+    match is_identifier(name) {
+        true => name.to_string(),
+        false => string_literal(name),
+    }
+}
+
+fn ts_type(kind: &Kind) -> String {
+    match kind {
+        Kind::Bool => "boolean".to_string(),
+        Kind::Int { .. } | Kind::Float | Kind::Double | Kind::Year => "number".to_string(),
+        // DECIMAL 以字符串传输以保留精度
+        Kind::Decimal => "string".to_string(),
+        Kind::String | Kind::Text | Kind::Unknown => "string".to_string(),
+        Kind::Enum(values) if !values.is_empty() => values
+            .iter()
+            .map(|value| string_literal(value))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Kind::Enum(_) => "string".to_string(),
+        Kind::Date | Kind::DateTime | Kind::Timestamp | Kind::Time => "string".to_string(),
+        Kind::Json => "unknown".to_string(),
+        Kind::Binary => "Uint8Array".to_string(),
+    }
+}
+
+fn typescript(table: &str, columns: &[Column]) -> String {
+    let mut out = format!("export interface {} {{\n", pascal_case(table));
+    for column in columns {
+        if let Some(comment) = &column.comment {
+            out.push_str(&format!("  /** {} */\n", comment.replace("*/", "* /")));
+        }
+        let nullable = if column.nullable { " | null" } else { "" };
+        out.push_str(&format!(
+            "  {}: {}{};\n",
+            ts_property(&column.name),
+            ts_type(&column.kind),
+            nullable
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn rust_type(kind: &Kind, imports: &mut BTreeSet<&'static str>) -> String {
+    let name = match kind {
+        Kind::Bool => "bool",
+        Kind::Int { bits, unsigned } => match (bits, unsigned) {
+            (8, false) => "i8",
+            (8, true) => "u8",
+            (16, false) => "i16",
+            (16, true) => "u16",
+            (32, false) => "i32",
+            (32, true) => "u32",
+            (_, false) => "i64",
+            (_, true) => "u64",
+        },
+        Kind::Float => "f32",
+        Kind::Double => "f64",
+        Kind::Decimal => {
+            imports.insert("rust_decimal::Decimal");
+            "Decimal"
+        }
+        Kind::String | Kind::Text | Kind::Enum(_) | Kind::Unknown => "String",
+        Kind::Date => {
+            imports.insert("chrono::NaiveDate");
+            "NaiveDate"
+        }
+        Kind::DateTime => {
+            imports.insert("chrono::NaiveDateTime");
+            "NaiveDateTime"
+        }
+        // MySQL 的 TIMESTAMP 在 sqlx 中解码为 UTC 时间
+        Kind::Timestamp => {
+            imports.insert("chrono::DateTime");
+            imports.insert("chrono::Utc");
+            "DateTime<Utc>"
+        }
+        Kind::Time => {
+            imports.insert("chrono::NaiveTime");
+            "NaiveTime"
+        }
+        Kind::Year => "u16",
+        Kind::Json => "serde_json::Value",
+        Kind::Binary => "Vec<u8>",
+    };
+    name.to_string()
+}
+
+fn rust_struct(table: &str, columns: &[Column]) -> String {
+    let mut imports = BTreeSet::new();
+    let mut fields = String::new();
+    for column in columns {
+        if let Some(comment) = &column.comment {
+            fields.push_str(&format!("    /// {}\n", comment));
+        }
+        let field = snake_case(&column.name);
+        if field != column.name {
+            fields.push_str(&format!(
+                "    #[serde(rename = {0})]\n    #[sqlx(rename = {0})]\n",
+                string_literal(&column.name)
+            ));
+        }
+        let field = match RUST_KEYWORDS.contains(&field.as_str()) {
+            true => format!("r#{}", field),
+            false => field,
+        };
+        let ty = rust_type(&column.kind, &mut imports);
+        let ty = match column.nullable {
+            true => format!("Option<{}>", ty),
+            false => ty,
+        };
+        fields.push_str(&format!("    pub {}: {},\n", field, ty));
+    }
+
+    // 同一 crate 的类型合并为一条 use
+    let mut out = String::new();
+    let mut crates: Vec<(&str, Vec<&str>)> = Vec::new();
+    for import in &imports {
+        let (krate, name) = import.split_once("::").unwrap_or_default();
+        match crates.iter_mut().find(|(k, _)| *k == krate) {
+            Some((_, names)) => names.push(name),
+            None => crates.push((krate, vec![name])),
+        }
+    }
+    for (krate, names) in crates {
+        match names.as_slice() {
+            [name] => out.push_str(&format!("use {}::{};\n", krate, name)),
+            _ => out.push_str(&format!("use {}::{{{}}};\n", krate, names.join(", "))),
+        }
+    }
+    out.push_str("use serde::{Deserialize, Serialize};\nuse sqlx::FromRow;\n\n");
+    out.push_str("#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]\n");
+    out.push_str(&format!(
+        "pub struct {} {{\n{}}}\n",
+        pascal_case(table),
+        fields
+    ));
+    out
+}
+
+fn typeorm_options(column: &Column) -> String {
+    let mut options = vec![format!("type: {}", string_literal(&column.data_type))];
+    if !is_identifier(&column.name) {
+        options.insert(0, format!("name: {}", string_literal(&column.name)));
+    }
+    if let (Kind::String, Some(length)) = (&column.kind, column.max_length) {
+        options.push(format!("length: {}", length));
+    }
+    if let Kind::Decimal = column.kind {
+        if let Some(precision) = column.precision {
+            options.push(format!("precision: {}", precision));
+        }
+        if let Some(scale) = column.scale {
+            options.push(format!("scale: {}", scale));
+        }
+    }
+    if let Kind::Enum(values) = &column.kind {
+        let values: Vec<String> = values.iter().map(|value| string_literal(value)).collect();
+        options.push(format!("enum: [{}]", values.join(", ")));
+    }
+    if column.nullable {
+        options.push("nullable: true".to_string());
+    }
+    if let Some(comment) = &column.comment {
+        options.push(format!("comment: {}", string_literal(comment)));
+    }
+    format!("{{ {} }}", options.join(", "))
+}
+
+fn typeorm(table: &str, columns: &[Column]) -> String {
+    let mut decorators = BTreeSet::from(["Entity"]);
+    let mut fields = Vec::new();
+    for column in columns {
+        let decorator = match (column.primary_key, column.auto_increment) {
+            (true, true) => "PrimaryGeneratedColumn",
+            (true, false) => "PrimaryColumn",
+            _ => "Column",
+        };
+        decorators.insert(decorator);
+        let property = match is_identifier(&column.name) {
+            true => column.name.clone(),
+            false => words(&column.name).join("_"),
+        };
+        let nullable = if column.nullable { " | null" } else { "" };
+        fields.push(format!(
+            "  @{}({})\n  {}!: {}{};\n",
+            decorator,
+            typeorm_options(column),
+            property,
+            ts_type(&column.kind),
+            nullable
+        ));
+    }
+    format!(
+        "import {{ {} }} from \"typeorm\";\n\n@Entity({{ name: {} }})\nexport class {} {{\n{}}}\n",
+        decorators.into_iter().collect::<Vec<_>>().join(", "),
+        string_literal(table),
+        pascal_case(table),
+        fields.join("\n")
+    )
+}
+
+// (Python 类型, SQLAlchemy 类型)
+fn sqlalchemy_type(
+    column: &Column,
+    imports: &mut BTreeSet<String>,
+    python_imports: &mut BTreeSet<&'static str>,
+) -> (&'static str, String) {
+    let (python, sql_type) = match &column.kind {
+        Kind::Bool => ("bool", "Boolean".to_string()),
+        Kind::Int { bits: 64, .. } => ("int", "BigInteger".to_string()),
+        Kind::Int { bits: 8 | 16, .. } => ("int", "SmallInteger".to_string()),
+        Kind::Int { .. } | Kind::Year => ("int", "Integer".to_string()),
+        Kind::Float | Kind::Double => ("float", "Float".to_string()),
+        Kind::Decimal => {
+            python_imports.insert("from decimal import Decimal");
+            let sql_type = match (column.precision, column.scale) {
+                (Some(precision), Some(scale)) => format!("Numeric({}, {})", precision, scale),
+                _ => "Numeric".to_string(),
+            };
+            ("Decimal", sql_type)
+        }
+        Kind::String => (
+            "str",
+            column
+                .max_length
+                .map_or("String".to_string(), |length| format!("String({})", length)),
+        ),
+        Kind::Text => ("str", "Text".to_string()),
+        Kind::Enum(values) => {
+            let values: Vec<String> = values.iter().map(|value| string_literal(value)).collect();
+            ("str", format!("Enum({})", values.join(", ")))
+        }
+        Kind::Date => {
+            python_imports.insert("from datetime import date");
+            ("date", "Date".to_string())
+        }
+        Kind::DateTime | Kind::Timestamp => {
+            python_imports.insert("from datetime import datetime");
+            ("datetime", "DateTime".to_string())
+        }
+        Kind::Time => {
+            python_imports.insert("from datetime import time");
+            ("time", "Time".to_string())
+        }
+        Kind::Json => {
+            python_imports.insert("from typing import Any");
+            ("Any", "JSON".to_string())
+        }
+        Kind::Binary => ("bytes", "LargeBinary".to_string()),
+        Kind::Unknown => ("str", "String".to_string()),
+    };
+    let base = sql_type.split('(').next().unwrap_or_default().to_string();
+    imports.insert(base);
+    (python, sql_type)
+}
+
+fn sqlalchemy(table: &str, columns: &[Column]) -> String {
+    let mut imports = BTreeSet::new();
+    let mut python_imports = BTreeSet::new();
+    let mut fields = String::new();
+    for column in columns {
+        let (python, sql_type) = sqlalchemy_type(column, &mut imports, &mut python_imports);
+        let mut attribute = snake_case(&column.name);
+        if PYTHON_KEYWORDS.contains(&attribute.as_str()) {
+            attribute.push('_');
+        }
+        let mut arguments = Vec::new();
+        if attribute != column.name {
+            arguments.push(string_literal(&column.name));
+        }
+        arguments.push(sql_type);
+        if column.primary_key {
+            arguments.push("primary_key=True".to_string());
+        }
+        if column.auto_increment {
+            arguments.push("autoincrement=True".to_string());
+        }
+        if let Some(comment) = &column.comment {
+            arguments.push(format!("comment={}", string_literal(comment)));
+        }
+        let python = match column.nullable {
+            true => {
+                python_imports.insert("from typing import Optional");
+                format!("Optional[{}]", python)
+            }
+            false => python.to_string(),
+        };
+        fields.push_str(&format!(
+            "    {}: Mapped[{}] = mapped_column({})\n",
+            attribute,
+            python,
+            arguments.join(", ")
+        ));
+    }
+
+    // 标准库的导入合并为一行一个模块
+    let mut standard: Vec<(String, Vec<String>)> = Vec::new();
+    for import in python_imports {
+        let (module, name) = import
+            .strip_prefix("from ")
+            .and_then(|rest| rest.split_once(" import "))
+            .unwrap_or_default();
+        match standard.iter_mut().find(|(m, _)| m == module) {
+            Some((_, names)) => names.push(name.to_string()),
+            None => standard.push((module.to_string(), vec![name.to_string()])),
+        }
+    }
+    let mut out = String::new();
+    for (module, names) in &standard {
+        out.push_str(&format!("from {} import {}\n", module, names.join(", ")));
+    }
+    if !standard.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "from sqlalchemy import {}\n\
+         from sqlalchemy.orm import DeclarativeBase, Mapped, mapped_column\n\n\n\
+         class Base(DeclarativeBase):\n    pass\n\n\n\
+         class {}(Base):\n    __tablename__ = {}\n\n{}",
+        imports.into_iter().collect::<Vec<_>>().join(", "),
+        pascal_case(table),
+        string_literal(table),
+        fields
+    ));
+    out
+}
+
+fn prisma_type(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::Bool => "Boolean",
+        Kind::Int { bits: 64, .. } => "BigInt",
+        Kind::Int { .. } | Kind::Year => "Int",
+        Kind::Float | Kind::Double => "Float",
+        Kind::Decimal => "Decimal",
+        Kind::String | Kind::Text | Kind::Enum(_) | Kind::Unknown => "String",
+        Kind::Date | Kind::DateTime | Kind::Timestamp | Kind::Time => "DateTime",
+        Kind::Json => "Json",
+        Kind::Binary => "Bytes",
+    }
+}
+
+fn prisma_field(name: &str) -> String {
+    match is_identifier(name) && !name.contains('$') {
+        true => name.to_string(),
+        false => snake_case(name),
+    }
+}
+
+fn prisma(table: &str, columns: &[Column]) -> String {
+    let single_key = columns.iter().filter(|c| c.primary_key).count() == 1;
+    let mut lines = Vec::new();
+    for column in columns {
+        let field = prisma_field(&column.name);
+        let mut attributes = Vec::new();
+        if column.primary_key && single_key {
+            attributes.push("@id".to_string());
+        }
+        if column.auto_increment {
+            attributes.push("@default(autoincrement())".to_string());
+        }
+        if let (Kind::String, Some(length)) = (&column.kind, column.max_length) {
+            attributes.push(format!("@db.VarChar({})", length));
+        }
+        if field != column.name {
+            attributes.push(format!("@map({})", string_literal(&column.name)));
+        }
+        let optional = if column.nullable { "?" } else { "" };
+        lines.push(
+            format!(
+                "  {} {}{} {}",
+                field,
+                prisma_type(&column.kind),
+                optional,
+                attributes.join(" ")
+            )
+            .trim_end()
+            .to_string(),
+        );
+    }
+    if !single_key {
+        let keys: Vec<String> = columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| prisma_field(&c.name))
+            .collect();
+        if !keys.is_empty() {
+            lines.push(String::new());
+            lines.push(format!("  @@id([{}])", keys.join(", ")));
+        }
+    }
+    lines.push(format!("  @@map({})", string_literal(table)));
+    format!(
+        "model {} {{\n{}\n}}\n",
+        pascal_case(table),
+        lines.join("\n")
+    )
+}
+
+async fn mysql_columns(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    table: &str,
+) -> Result<Vec<Column>, String> {
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, db_name.clone())
+            .await?;
+    let rows = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR), CAST(DATA_TYPE AS CHAR), CAST(COLUMN_TYPE AS CHAR), \
+         CAST(IS_NULLABLE AS CHAR), CAST(COLUMN_KEY AS CHAR), CAST(EXTRA AS CHAR), \
+         CHARACTER_MAXIMUM_LENGTH, NUMERIC_PRECISION, NUMERIC_SCALE, CAST(COLUMN_COMMENT AS CHAR) \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(db_name)
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let text = |i: usize| {
+                row.try_get_unchecked::<Option<String>, _>(i)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
+            };
+            let number = |i: usize| row.try_get_unchecked::<Option<u64>, _>(i).ok().flatten();
+            let data_type = text(1).to_lowercase();
+            let column_type = text(2).to_lowercase();
+            let comment = text(9);
+            Column {
+                name: text(0),
+                kind: column_kind(&data_type, &column_type, false),
+                data_type,
+                nullable: text(3) == "YES",
+                primary_key: text(4) == "PRI",
+                auto_increment: text(5).contains("auto_increment"),
+                max_length: number(6),
+                precision: number(7),
+                scale: number(8),
+                comment: (!comment.is_empty()).then_some(comment),
+            }
+        })
+        .collect())
+}
+
+async fn sqlite_columns(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    table: &str,
+) -> Result<Vec<Column>, String> {
+    let pool = sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+    let rows =
+        sqlx::query("SELECT name, type, \"notnull\", pk FROM pragma_table_info(?) ORDER BY cid")
+            .bind(table)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let key_count = rows
+        .iter()
+        .filter(|row| row.try_get::<i64, _>(3).unwrap_or(0) > 0)
+        .count();
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let declared: String = row.try_get(1).unwrap_or_default();
+            let declared = declared.to_lowercase();
+            let data_type = sqlite_data_type(&declared);
+            let primary_key = row.try_get::<i64, _>(3).unwrap_or(0) > 0;
+            // VARCHAR(255) / DECIMAL(10, 2) 中的长度 / 精度
+            let arguments: Vec<u64> = declared
+                .split_once('(')
+                .map_or("", |(_, rest)| rest.trim_end_matches(')'))
+                .split(',')
+                .filter_map(|argument| argument.trim().parse().ok())
+                .collect();
+            let is_decimal = data_type == "decimal";
+            Column {
+                name: row.try_get(0).unwrap_or_default(),
+                kind: column_kind(&data_type, &declared, true),
+                nullable: row.try_get::<i64, _>(2).unwrap_or(0) == 0 && !primary_key,
+                primary_key,
+                // INTEGER PRIMARY KEY 是 rowid 的别名，自动分配
+                auto_increment: primary_key && key_count == 1 && declared == "integer",
+                max_length: arguments.first().copied().filter(|_| !is_decimal),
+                precision: arguments.first().copied().filter(|_| is_decimal),
+                scale: arguments.get(1).copied().filter(|_| is_decimal),
+                comment: None,
+                data_type,
+            }
+        })
+        .collect())
+}
+
+// 根据表结构生成代码：target 为 "typescript"（interface）、"rust"（sqlx + serde 结构体）、
+// "typeorm"（实体类）、"sqlalchemy"（2.0 声明式模型）或 "prisma"（model 定义）
+#[command]
+pub async fn generate_code(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    target: String,
+    db_name: Option<String>,
+) -> Result<GeneratedCode, String> {
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    let columns = match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            mysql_columns(&app_state, &db_state, connection_id, db_name, &table).await?
+        }
+        "sqlite" => sqlite_columns(&app_state, &db_state, connection_id, &table).await?,
+        other => return Err(format!("Code generation is not supported for {}", other)),
+    };
+    if columns.is_empty() {
+        return Err(format!("Table {} not found", table));
+    }
+
+    let (file_name, content) = match target.as_str() {
+        "typescript" => (
+            format!("{}.ts", snake_case(&table)),
+            typescript(&table, &columns),
+        ),
+        "rust" => (
+            format!("{}.rs", snake_case(&table)),
+            rust_struct(&table, &columns),
+        ),
+        "typeorm" => (
+            format!("{}.ts", pascal_case(&table)),
+            typeorm(&table, &columns),
+        ),
+        "sqlalchemy" => (
+            format!("{}.py", snake_case(&table)),
+            sqlalchemy(&table, &columns),
+        ),
+        "prisma" => (
+            format!("{}.prisma", snake_case(&table)),
+            prisma(&table, &columns),
+        ),
+        other => return Err(format!("Unsupported code generation target: {}", other)),
+    };
+    Ok(GeneratedCode {
+        target,
+        file_name,
+        content,
+    })
+}
@@ -0,0 +1,171 @@
+use crate::alter_table::quote_identifier;
+use crate::db::{connection_flavor, DbState};
+use crate::models::{ColumnProfile, ValueFrequency};
+use crate::query_queue::{
+    acquire_query_slot, finish_running_query, new_execution_id, register_running_query,
+    RunningQuery,
+};
+use crate::sql_classifier::SqlFlavor;
+use crate::sqlite_manager::SqliteInterruptHandle;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::{Map, Value};
+use tauri::{command, State};
+
+const PROFILE_DEFAULT_TOP_N: u32 = 10;
+const PROFILE_MAX_TOP_N: u32 = 100;
+
+// 计数和平均值可能是数字或 DECIMAL 文本
+fn numeric_value(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn count_value(value: Option<&Value>) -> u64 {
+    numeric_value(value).unwrap_or(0.0) as u64
+}
+
+// 汇总统计与 Top-N 两条语句
+fn profile_sql(table: &str, column: &str, flavor: SqlFlavor, top_n: u32) -> (String, String) {
+    let table = table
+        .split('.')
+        .map(|part| quote_identifier(part.trim(), flavor))
+        .collect::<Vec<_>>()
+        .join(".");
+    let column = quote_identifier(column, flavor);
+    // MySQL 的 LENGTH 为字节数，字符数用 CHAR_LENGTH
+    let length = match flavor {
+        SqlFlavor::MySql => format!("CHAR_LENGTH({})", column),
+        SqlFlavor::Sqlite => format!("length({})", column),
+    };
+    let summary = format!(
+        "SELECT COUNT(*) AS total_rows, COUNT({0}) AS non_null, \
+         COUNT(DISTINCT {0}) AS distinct_count, MIN({0}) AS min_value, MAX({0}) AS max_value, \
+         AVG({1}) AS avg_length FROM {2}",
+        column, length, table
+    );
+    let top = format!(
+        "SELECT {0} AS value, COUNT(*) AS frequency FROM {1} WHERE {0} IS NOT NULL \
+         GROUP BY {0} ORDER BY frequency DESC, value LIMIT {2}",
+        column, table, top_n
+    );
+    (summary, top)
+}
+
+fn profile_of(mut summary: Map<String, Value>, top: Vec<Map<String, Value>>) -> ColumnProfile {
+    let total_rows = count_value(summary.get("total_rows"));
+    let null_count = total_rows.saturating_sub(count_value(summary.get("non_null")));
+    ColumnProfile {
+        total_rows,
+        null_count,
+        null_rate: match total_rows {
+            0 => 0.0,
+            total => null_count as f64 / total as f64,
+        },
+        distinct_count: count_value(summary.get("distinct_count")),
+        min: summary.remove("min_value").unwrap_or(Value::Null),
+        max: summary.remove("max_value").unwrap_or(Value::Null),
+        avg_length: numeric_value(summary.get("avg_length")),
+        top_values: top
+            .into_iter()
+            .map(|mut row| ValueFrequency {
+                count: count_value(row.get("frequency")),
+                value: row.remove("value").unwrap_or(Value::Null),
+            })
+            .collect(),
+    }
+}
+
+// 在服务端统计一列的空值率、不同值个数、最小 / 最大值、平均长度和出现最多的 top_n 个值。
+// 需要扫描整张表，大表上耗时较长，可通过 cancel_query(execution_id) 取消
+#[command]
+pub async fn profile_column(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    column: String,
+    db_name: Option<String>,
+    top_n: Option<u32>,
+    execution_id: Option<String>,
+) -> Result<ColumnProfile, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Column profiling").await?;
+    let top_n = top_n
+        .unwrap_or(PROFILE_DEFAULT_TOP_N)
+        .clamp(1, PROFILE_MAX_TOP_N);
+    let (summary_sql, top_sql) = profile_sql(&table, &column, flavor, top_n);
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let (summary, top) = match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            mysql_manager::register_mysql_query(
+                &app_state,
+                &mut conn,
+                &execution_id,
+                connection_id,
+                db_name,
+            )
+            .await;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(&app_state, connection_id).await;
+            let result = async {
+                let summary = sqlx::query(&summary_sql)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| format!("Failed to profile column: {}", e))?;
+                let top = sqlx::query(&top_sql)
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| format!("Failed to profile column: {}", e))?;
+                Ok::<_, String>((
+                    mysql_manager::row_to_json(&summary, timestamp_display),
+                    top.iter()
+                        .map(|row| mysql_manager::row_to_json(row, timestamp_display))
+                        .collect(),
+                ))
+            }
+            .await;
+            finish_running_query(&app_state, &execution_id).await;
+            result?
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            let handle = SqliteInterruptHandle::of(&mut conn).await?;
+            register_running_query(&app_state, &execution_id, RunningQuery::Sqlite(handle)).await;
+            let result = async {
+                let summary = sqlx::query(&summary_sql)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| format!("Failed to profile column: {}", e))?;
+                let top = sqlx::query(&top_sql)
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| format!("Failed to profile column: {}", e))?;
+                Ok::<_, String>((
+                    sqlite_manager::row_to_json(&summary),
+                    top.iter().map(sqlite_manager::row_to_json).collect(),
+                ))
+            }
+            .await;
+            finish_running_query(&app_state, &execution_id).await;
+            result?
+        }
+    };
+    Ok(profile_of(summary, top))
+}
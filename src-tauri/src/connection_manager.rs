@@ -0,0 +1,696 @@
+use crate::autocomplete::invalidate_metadata;
+use crate::db::DbState;
+use crate::memcached_manager::{get_memcached_endpoint, get_memcached_url};
+use crate::models::{Connection, CreateConnectionArgs};
+use crate::mysql_manager::{build_connect_options, close_connection_streams};
+use crate::redact::redact_error;
+use crate::redis_manager::{create_redis_client, redis_value_to_json};
+use crate::result_cache::invalidate_results;
+use crate::result_spill::{release_all_results, release_connection_results};
+use crate::session::close_connection_sessions;
+use crate::ssh_tunnel::close_tunnels;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use serde::{Deserialize, Serialize};
+use sqlx::mysql::MySqlConnection;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection as _, Row};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Instant;
+use tauri::{command, State};
+use tokio::time::{timeout, Duration};
+
+const CONNECTION_TEST_TIMEOUT_SECS: u64 = 5;
+// 诊断时连续 ping 的次数，延迟取平均值
+const DIAGNOSE_PING_ROUNDS: u32 = 3;
+// 未保存的连接使用的临时 id（用于 SSH 隧道缓存的 key）
+const UNSAVED_CONNECTION_ID: i64 = 0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConnectionTestError {
+    // "config" / "ssh" / "auth" / "connect" / "timeout" / "unsupported"
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    pub latency_ms: u64,
+    pub error: Option<ConnectionTestError>,
+}
+
+// 连接诊断报告，单项探测失败时记录到 warnings 而不是整体失败
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ConnectionDiagnostics {
+    pub server_version: Option<String>,
+    pub latency_ms: u64,
+    pub auth_method: Option<String>,
+    pub current_user: Option<String>,
+    pub privileges: Vec<String>,
+    pub limits: BTreeMap<String, String>,
+    pub warnings: Vec<String>,
+}
+
+fn test_error(kind: &str, message: impl Into<String>) -> ConnectionTestError {
+    ConnectionTestError {
+        kind: kind.to_string(),
+        message: message.into(),
+    }
+}
+
+// 辅助函数：读取已保存的连接配置
+async fn fetch_connection(
+    app_state: &AppState,
+    db_state: &DbState,
+    connection_id: i64,
+) -> Result<Connection, String> {
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    reveal_secrets(app_state, connection).await
+}
+
+// 辅助函数：SSH 隧道失败归为 ssh 错误，其余按配置错误处理
+fn endpoint_error(connection: &Connection, message: String) -> ConnectionTestError {
+    if connection.ssh_enabled {
+        test_error("ssh", message)
+    } else {
+        test_error("config", message)
+    }
+}
+
+async fn test_mysql(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<(), ConnectionTestError> {
+    let options = build_connect_options(app_state, connection, None)
+        .await
+        .map_err(|e| endpoint_error(connection, e))?;
+
+    let mut conn = MySqlConnection::connect_with(&options)
+        .await
+        .map_err(|e| match &e {
+            // SQLSTATE 28000: Access denied
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("28000") => {
+                test_error("auth", format!("Failed to connect to MySQL: {}", e))
+            }
+            _ => test_error("connect", format!("Failed to connect to MySQL: {}", e)),
+        })?;
+    conn.ping()
+        .await
+        .map_err(|e| test_error("connect", format!("MySQL ping failed: {}", e)))?;
+    let _ = conn.close().await;
+    Ok(())
+}
+
+async fn test_sqlite(connection: &Connection) -> Result<(), ConnectionTestError> {
+    let db_path = connection
+        .database
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| test_error("config", "Database path is required"))?;
+
+    // 测试时不自动创建文件，路径写错应当报错
+    let mut conn = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path))
+        .map_err(|e| test_error("config", format!("Invalid SQLite path: {}", e)))?
+        .create_if_missing(false)
+        .connect()
+        .await
+        .map_err(|e| test_error("connect", format!("Failed to connect to SQLite: {}", e)))?;
+    conn.ping()
+        .await
+        .map_err(|e| test_error("connect", format!("SQLite ping failed: {}", e)))?;
+    let _ = conn.close().await;
+    Ok(())
+}
+
+async fn test_redis(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<(), ConnectionTestError> {
+    let db_index = connection.options.redis_db.unwrap_or_else(|| {
+        connection
+            .database
+            .as_deref()
+            .unwrap_or("0")
+            .parse::<u32>()
+            .unwrap_or(0)
+    });
+    let client = create_redis_client(app_state, connection, db_index)
+        .await
+        .map_err(|e| test_error("connect", e))?;
+
+    let mut conn = client
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| {
+            let kind = if e.kind() == redis::ErrorKind::AuthenticationFailed {
+                "auth"
+            } else {
+                "connect"
+            };
+            test_error(kind, format!("Failed to connect to Redis: {}", e))
+        })?;
+    redis::cmd("PING")
+        .query_async::<String>(&mut conn)
+        .await
+        .map_err(|e| test_error("connect", format!("Redis PING failed: {}", e)))?;
+    Ok(())
+}
+
+async fn test_memcached(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<(), ConnectionTestError> {
+    let (host, port) = get_memcached_endpoint(app_state, connection)
+        .await
+        .map_err(|e| endpoint_error(connection, e))?;
+    let url = get_memcached_url(&host, port);
+
+    // memcache 客户端是同步的，放到阻塞线程池中执行
+    tauri::async_runtime::spawn_blocking(move || {
+        let client = memcache::Client::connect(url)
+            .map_err(|e| test_error("connect", format!("Failed to connect to Memcached: {}", e)))?;
+        client
+            .version()
+            .map_err(|e| test_error("connect", format!("Memcached ping failed: {}", e)))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| test_error("connect", e.to_string()))?
+}
+
+async fn run_connection_test(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<(), ConnectionTestError> {
+    match connection.db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => test_mysql(app_state, connection).await,
+        "sqlite" => test_sqlite(connection).await,
+        "redis" => test_redis(app_state, connection).await,
+        "memcached" => test_memcached(app_state, connection).await,
+        other => Err(test_error(
+            "unsupported",
+            format!("Connection test is not supported for {}", other),
+        )),
+    }
+}
+
+// 测试连接：传入已保存连接的 id，或传入尚未保存的连接参数
+#[command]
+pub async fn test_connection(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: Option<i64>,
+    args: Option<CreateConnectionArgs>,
+) -> Result<ConnectionTestResult, String> {
+    let connection = match (connection_id, args) {
+        (_, Some(args)) => args.into_connection(UNSAVED_CONNECTION_ID),
+        (Some(id), None) => fetch_connection(&app_state, &db_state, id).await?,
+        (None, None) => return Err("Either connection_id or args is required".to_string()),
+    };
+
+    let started = Instant::now();
+    let outcome = timeout(
+        Duration::from_secs(CONNECTION_TEST_TIMEOUT_SECS),
+        run_connection_test(&app_state, &connection),
+    )
+    .await
+    .unwrap_or_else(|_| {
+        Err(test_error(
+            "timeout",
+            format!(
+                "Connection timed out after {} seconds",
+                CONNECTION_TEST_TIMEOUT_SECS
+            ),
+        ))
+    });
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    // 未保存连接的隧道不再复用，测试结束即关闭
+    if connection.id == UNSAVED_CONNECTION_ID {
+        close_tunnels(&app_state, UNSAVED_CONNECTION_ID).await;
+    }
+
+    Ok(ConnectionTestResult {
+        success: outcome.is_ok(),
+        latency_ms,
+        error: outcome.err().map(|e| ConnectionTestError {
+            message: redact_error(&e.message, &connection),
+            ..e
+        }),
+    })
+}
+
+async fn diagnose_mysql(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<ConnectionDiagnostics, String> {
+    let options = build_connect_options(app_state, connection, None).await?;
+    let mut conn = MySqlConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("Failed to connect to MySQL: {}", e))?;
+    let mut report = ConnectionDiagnostics::default();
+
+    let started = Instant::now();
+    for _ in 0..DIAGNOSE_PING_ROUNDS {
+        conn.ping()
+            .await
+            .map_err(|e| format!("MySQL ping failed: {}", e))?;
+    }
+    report.latency_ms = (started.elapsed() / DIAGNOSE_PING_ROUNDS).as_millis() as u64;
+
+    match sqlx::query_as::<_, (String, String)>("SELECT VERSION(), CURRENT_USER()")
+        .fetch_one(&mut conn)
+        .await
+    {
+        Ok((version, user)) => {
+            report.server_version = Some(version);
+            report.current_user = Some(user);
+        }
+        Err(e) => report
+            .warnings
+            .push(format!("Failed to read version: {}", e)),
+    }
+
+    // 认证插件存放在 mysql.user 中，普通账号通常没有读取权限
+    match sqlx::query_scalar::<_, String>(
+        "SELECT plugin FROM mysql.user WHERE CONCAT(user, '@', host) = CURRENT_USER()",
+    )
+    .fetch_optional(&mut conn)
+    .await
+    {
+        Ok(plugin) => report.auth_method = plugin,
+        Err(e) => report
+            .warnings
+            .push(format!("Failed to read authentication plugin: {}", e)),
+    }
+
+    match sqlx::query("SHOW GRANTS").fetch_all(&mut conn).await {
+        Ok(rows) => {
+            report.privileges = rows
+                .iter()
+                .filter_map(|row| row.try_get::<String, _>(0).ok())
+                .collect()
+        }
+        Err(e) => report
+            .warnings
+            .push(format!("Failed to read grants: {}", e)),
+    }
+
+    match sqlx::query(
+        "SHOW VARIABLES WHERE Variable_name IN ('max_allowed_packet', 'max_connections', \
+         'max_user_connections', 'wait_timeout', 'net_read_timeout', 'net_write_timeout')",
+    )
+    .fetch_all(&mut conn)
+    .await
+    {
+        Ok(rows) => {
+            for row in rows {
+                if let (Ok(name), Ok(value)) =
+                    (row.try_get::<String, _>(0), row.try_get::<String, _>(1))
+                {
+                    report.limits.insert(name, value);
+                }
+            }
+        }
+        Err(e) => report
+            .warnings
+            .push(format!("Failed to read server limits: {}", e)),
+    }
+
+    // 当前会话是否走了 TLS
+    if let Ok(row) = sqlx::query("SHOW SESSION STATUS LIKE 'Ssl_cipher'")
+        .fetch_one(&mut conn)
+        .await
+    {
+        if let Ok(cipher) = row.try_get::<String, _>(1) {
+            report.limits.insert("ssl_cipher".to_string(), cipher);
+        }
+    }
+
+    let _ = conn.close().await;
+    Ok(report)
+}
+
+async fn diagnose_sqlite(connection: &Connection) -> Result<ConnectionDiagnostics, String> {
+    let db_path = connection
+        .database
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .ok_or("Database path is required")?;
+    let mut conn = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path))
+        .map_err(|e| format!("Invalid SQLite path: {}", e))?
+        .create_if_missing(false)
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to connect to SQLite: {}", e))?;
+    let mut report = ConnectionDiagnostics {
+        auth_method: Some("none".to_string()),
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    for _ in 0..DIAGNOSE_PING_ROUNDS {
+        conn.ping()
+            .await
+            .map_err(|e| format!("SQLite ping failed: {}", e))?;
+    }
+    report.latency_ms = (started.elapsed() / DIAGNOSE_PING_ROUNDS).as_millis() as u64;
+
+    report.server_version = sqlx::query_scalar::<_, String>("SELECT sqlite_version()")
+        .fetch_one(&mut conn)
+        .await
+        .ok();
+
+    // SQLite 没有账号体系，权限取决于数据库文件本身
+    match std::fs::metadata(db_path) {
+        Ok(metadata) if metadata.permissions().readonly() => {
+            report.privileges = vec!["read".to_string()];
+        }
+        Ok(_) => report.privileges = vec!["read".to_string(), "write".to_string()],
+        Err(e) => report
+            .warnings
+            .push(format!("Failed to read file permissions: {}", e)),
+    }
+
+    for pragma in ["page_size", "max_page_count", "journal_mode"] {
+        if let Ok(row) = sqlx::query(&format!("PRAGMA {}", pragma))
+            .fetch_one(&mut conn)
+            .await
+        {
+            let value = row
+                .try_get::<i64, _>(0)
+                .map(|v| v.to_string())
+                .or_else(|_| row.try_get::<String, _>(0));
+            if let Ok(value) = value {
+                report.limits.insert(pragma.to_string(), value);
+            }
+        }
+    }
+
+    let _ = conn.close().await;
+    Ok(report)
+}
+
+async fn diagnose_redis(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<ConnectionDiagnostics, String> {
+    let client = create_redis_client(app_state, connection, 0).await?;
+    let mut conn = client
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| format!("Failed to connect to Redis: {}", e))?;
+    let username = connection.username.as_deref().unwrap_or_default();
+    let password = connection.password.as_deref().unwrap_or_default();
+    let mut report = ConnectionDiagnostics {
+        auth_method: Some(
+            if !username.is_empty() {
+                "acl"
+            } else if !password.is_empty() {
+                "password"
+            } else {
+                "none"
+            }
+            .to_string(),
+        ),
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    for _ in 0..DIAGNOSE_PING_ROUNDS {
+        redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .map_err(|e| format!("Redis PING failed: {}", e))?;
+    }
+    report.latency_ms = (started.elapsed() / DIAGNOSE_PING_ROUNDS).as_millis() as u64;
+
+    match redis::cmd("INFO")
+        .arg("server")
+        .query_async::<String>(&mut conn)
+        .await
+    {
+        Ok(info) => {
+            report.server_version = info
+                .lines()
+                .find_map(|l| l.strip_prefix("redis_version:"))
+                .map(|v| v.trim().to_string())
+        }
+        Err(e) => report
+            .warnings
+            .push(format!("Failed to read server info: {}", e)),
+    }
+
+    // ACL 从 Redis 6 开始提供，旧版本会返回未知命令
+    match redis::cmd("ACL")
+        .arg("WHOAMI")
+        .query_async::<String>(&mut conn)
+        .await
+    {
+        Ok(user) => {
+            match redis::cmd("ACL")
+                .arg("GETUSER")
+                .arg(&user)
+                .query_async::<Vec<redis::Value>>(&mut conn)
+                .await
+            {
+                Ok(fields) => {
+                    for pair in fields.chunks(2) {
+                        let [name, value] = pair else { continue };
+                        let name = redis_value_to_json(name.clone());
+                        let value = redis_value_to_json(value.clone());
+                        let name = name.as_str().unwrap_or_default();
+                        if matches!(name, "flags" | "commands" | "keys" | "channels") {
+                            let value = match value {
+                                serde_json::Value::String(s) => s,
+                                other => other.to_string(),
+                            };
+                            report.privileges.push(format!("{}: {}", name, value));
+                        }
+                    }
+                }
+                Err(e) => report
+                    .warnings
+                    .push(format!("Failed to read ACL rules: {}", e)),
+            }
+            report.current_user = Some(user);
+        }
+        Err(e) => report.warnings.push(format!("ACL is not available: {}", e)),
+    }
+
+    // CONFIG 可能被 rename-command 禁用
+    for name in ["maxclients", "maxmemory", "proto-max-bulk-len", "timeout"] {
+        match redis::cmd("CONFIG")
+            .arg("GET")
+            .arg(name)
+            .query_async::<Vec<String>>(&mut conn)
+            .await
+        {
+            Ok(values) => {
+                if let [key, value] = values.as_slice() {
+                    report.limits.insert(key.clone(), value.clone());
+                }
+            }
+            Err(e) => {
+                report
+                    .warnings
+                    .push(format!("Failed to read server limits: {}", e));
+                break;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+async fn diagnose_memcached(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<ConnectionDiagnostics, String> {
+    let (host, port) = get_memcached_endpoint(app_state, connection).await?;
+    let url = get_memcached_url(&host, port);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let client = memcache::Client::connect(url)
+            .map_err(|e| format!("Failed to connect to Memcached: {}", e))?;
+        // memcache crate 不支持 SASL，连接不做认证
+        let mut report = ConnectionDiagnostics {
+            auth_method: Some("none".to_string()),
+            ..Default::default()
+        };
+
+        let started = Instant::now();
+        for _ in 0..DIAGNOSE_PING_ROUNDS {
+            client
+                .version()
+                .map_err(|e| format!("Memcached ping failed: {}", e))?;
+        }
+        report.latency_ms = (started.elapsed() / DIAGNOSE_PING_ROUNDS).as_millis() as u64;
+
+        match client.stats() {
+            Ok(servers) => {
+                if let Some((_, stats)) = servers.into_iter().next() {
+                    report.server_version = stats.get("version").cloned();
+                    for name in [
+                        "limit_maxbytes",
+                        "max_connections",
+                        "item_size_max",
+                        "curr_connections",
+                    ] {
+                        if let Some(value) = stats.get(name) {
+                            report.limits.insert(name.to_string(), value.clone());
+                        }
+                    }
+                }
+            }
+            Err(e) => report.warnings.push(format!("Failed to get stats: {}", e)),
+        }
+
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 连接诊断：用于排查“能连上但查询失败”一类问题
+#[command]
+pub async fn diagnose_connection(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<ConnectionDiagnostics, String> {
+    let connection = fetch_connection(&app_state, &db_state, connection_id).await?;
+
+    let report = match connection.db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => diagnose_mysql(&app_state, &connection).await,
+        "sqlite" => diagnose_sqlite(&connection).await,
+        "redis" => diagnose_redis(&app_state, &connection).await,
+        "memcached" => diagnose_memcached(&app_state, &connection).await,
+        other => Err(format!(
+            "Connection diagnostics are not supported for {}",
+            other
+        )),
+    };
+    let mut report = report.map_err(|e| redact_error(e, &connection))?;
+    report.warnings = report
+        .warnings
+        .iter()
+        .map(|w| redact_error(w, &connection))
+        .collect();
+    Ok(report)
+}
+
+// 缓存 key 为 "{id}" 或 "{id}:{db}" 的连接池是否属于该连接
+fn is_cache_key_of(key: &str, connection_id: i64) -> bool {
+    let id = connection_id.to_string();
+    key == id || key.starts_with(&format!("{}:", id))
+}
+
+// 释放某个连接缓存的连接池、客户端和 SSH 隧道，下次使用时会按最新配置重新建立
+pub async fn release_connection(app_state: &AppState, connection_id: i64) {
+    // 未读完的结果句柄占用着连接，连接池要等所有连接归还后才能关闭
+    close_connection_streams(app_state, connection_id).await;
+    let mysql_pools: Vec<_> = {
+        let mut pools = app_state.pools.lock().await;
+        let keys: Vec<String> = pools
+            .keys()
+            .filter(|key| is_cache_key_of(key, connection_id))
+            .cloned()
+            .collect();
+        keys.iter().filter_map(|key| pools.remove(key)).collect()
+    };
+    for pool in mysql_pools {
+        pool.close().await;
+    }
+
+    app_state.active_databases.lock().await.remove(&connection_id);
+
+    let sqlite_pool = app_state.sqlite_pools.lock().await.remove(&connection_id);
+    if let Some(pool) = sqlite_pool {
+        pool.close().await;
+    }
+
+    app_state
+        .redis_clients
+        .lock()
+        .await
+        .retain(|key, _| !is_cache_key_of(key, connection_id));
+    app_state.memcached_clients.lock().await.remove(&connection_id);
+    app_state.mongo_clients.lock().await.remove(&connection_id);
+    app_state.clickhouse_clients.lock().await.remove(&connection_id);
+    app_state.cassandra_sessions.lock().await.remove(&connection_id);
+    app_state.duckdb_connections.lock().await.remove(&connection_id);
+    app_state.elastic_clients.lock().await.remove(&connection_id);
+    app_state.dynamo_clients.lock().await.remove(&connection_id);
+    app_state.couchbase_clients.lock().await.remove(&connection_id);
+    app_state.rocksdb_stores.lock().await.remove(&connection_id);
+    app_state.neo4j_graphs.lock().await.remove(&connection_id);
+    app_state.server_profiles.lock().await.remove(&connection_id);
+    app_state.timestamp_displays.lock().await.remove(&connection_id);
+    app_state.query_stats.lock().await.remove(&connection_id);
+    invalidate_results(app_state, connection_id).await;
+    invalidate_metadata(app_state, connection_id).await;
+    release_connection_results(app_state, connection_id).await;
+    close_connection_sessions(app_state, Some(connection_id)).await;
+
+    close_tunnels(app_state, connection_id).await;
+}
+
+// 断开某个连接：关闭连接池并释放客户端
+#[command]
+pub async fn close_connection(
+    app_state: State<'_, AppState>,
+    connection_id: i64,
+) -> Result<(), String> {
+    release_connection(&app_state, connection_id).await;
+    Ok(())
+}
+
+// 断开全部连接
+#[command]
+pub async fn close_all_connections(app_state: State<'_, AppState>) -> Result<(), String> {
+    close_connection_sessions(&app_state, None).await;
+    let mysql_pools: Vec<_> = app_state.pools.lock().await.drain().map(|(_, p)| p).collect();
+    for pool in mysql_pools {
+        pool.close().await;
+    }
+    let sqlite_pools: Vec<_> = app_state
+        .sqlite_pools
+        .lock()
+        .await
+        .drain()
+        .map(|(_, p)| p)
+        .collect();
+    for pool in sqlite_pools {
+        pool.close().await;
+    }
+
+    app_state.active_databases.lock().await.clear();
+    app_state.redis_clients.lock().await.clear();
+    app_state.memcached_clients.lock().await.clear();
+    app_state.mongo_clients.lock().await.clear();
+    app_state.clickhouse_clients.lock().await.clear();
+    app_state.cassandra_sessions.lock().await.clear();
+    app_state.duckdb_connections.lock().await.clear();
+    app_state.elastic_clients.lock().await.clear();
+    app_state.dynamo_clients.lock().await.clear();
+    app_state.couchbase_clients.lock().await.clear();
+    app_state.rocksdb_stores.lock().await.clear();
+    app_state.neo4j_graphs.lock().await.clear();
+    app_state.server_profiles.lock().await.clear();
+    app_state.timestamp_displays.lock().await.clear();
+    app_state.ssh_tunnels.lock().await.clear();
+    app_state.query_stats.lock().await.clear();
+    app_state.result_cache.lock().await.clear();
+    release_all_results(&app_state).await;
+    Ok(())
+}
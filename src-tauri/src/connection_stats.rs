@@ -0,0 +1,216 @@
+use crate::db::DbState;
+use crate::state::AppState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{command, State};
+
+// 最近使用时间和累计次数先记在内存中，延迟合并写入 connections 表，避免每条命令都写一次本地库
+const USAGE_FLUSH_DELAY: Duration = Duration::from_secs(5);
+
+// 某个连接累计的查询统计（内存中，重启或断开连接后清零）
+#[derive(Debug, Clone, Default)]
+pub struct QueryStats {
+    pub queries_executed: u64,
+    pub total_latency: Duration,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// 尚未写入 connections 表的使用记录（断开连接时不清除，等下次写入）
+#[derive(Debug, Clone)]
+pub struct PendingUsage {
+    pub count: i64,
+    pub last_used_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    pub connection_id: i64,
+    // "mysql" / "sqlite" / "redis" / "memcached" / "mongodb" ...
+    pub kind: String,
+    // 缓存 key，MySQL / Redis 按库区分（如 "3:app_db"）
+    pub cache_key: String,
+    // 驱动不暴露连接数时为空
+    pub open_connections: Option<u32>,
+    pub idle_connections: Option<u32>,
+    pub queries_executed: u64,
+    pub avg_latency_ms: f64,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// 记录一次查询的耗时，最近使用时间和累计次数由延迟任务写入 connections 表
+pub async fn record_query(
+    app_state: &AppState,
+    db_state: &DbState,
+    connection_id: i64,
+    elapsed: Duration,
+) {
+    let now = Utc::now();
+    {
+        let mut stats = app_state.query_stats.lock().await;
+        let entry = stats.entry(connection_id).or_default();
+        entry.queries_executed += 1;
+        entry.total_latency += elapsed;
+        entry.last_used_at = Some(now);
+    }
+
+    let schedule_flush = {
+        let mut pending = app_state.pending_usage.lock().await;
+        // 队列为空说明没有等待中的写入任务
+        let schedule_flush = pending.is_empty();
+        pending
+            .entry(connection_id)
+            .and_modify(|usage| {
+                usage.count += 1;
+                usage.last_used_at = now;
+            })
+            .or_insert(PendingUsage {
+                count: 1,
+                last_used_at: now,
+            });
+        schedule_flush
+    };
+    if schedule_flush {
+        let app_state = app_state.clone();
+        let db_state = db_state.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(USAGE_FLUSH_DELAY).await;
+            flush_usage(&app_state, &db_state).await;
+        });
+    }
+}
+
+// 把积累的使用记录写入 connections 表，退出程序前也会调用一次
+pub async fn flush_usage(app_state: &AppState, db_state: &DbState) {
+    let pending = std::mem::take(&mut *app_state.pending_usage.lock().await);
+    for (connection_id, usage) in pending {
+        // 统计写入失败不影响查询本身
+        let _ = sqlx::query(
+            "UPDATE connections SET last_used_at = ?, use_count = use_count + ? WHERE id = ?",
+        )
+        .bind(usage.last_used_at.naive_utc())
+        .bind(usage.count)
+        .bind(connection_id)
+        .execute(&db_state.pool)
+        .await;
+    }
+}
+
+// 缓存 key 形如 "{id}" 或 "{id}:{db}"
+fn connection_id_of(cache_key: &str) -> Option<i64> {
+    cache_key.split(':').next()?.parse().ok()
+}
+
+fn cached_ids<V>(cache: &HashMap<i64, V>) -> Vec<i64> {
+    cache.keys().copied().collect()
+}
+
+#[command]
+pub async fn get_connection_stats(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<ConnectionStats>, String> {
+    let query_stats = app_state.query_stats.lock().await.clone();
+    let build = |connection_id: i64,
+                 kind: &str,
+                 cache_key: String,
+                 open: Option<u32>,
+                 idle: Option<u32>| {
+        let stats = query_stats.get(&connection_id).cloned().unwrap_or_default();
+        let avg_latency_ms = if stats.queries_executed > 0 {
+            stats.total_latency.as_secs_f64() * 1000.0 / stats.queries_executed as f64
+        } else {
+            0.0
+        };
+        ConnectionStats {
+            connection_id,
+            kind: kind.to_string(),
+            cache_key,
+            open_connections: open,
+            idle_connections: idle,
+            queries_executed: stats.queries_executed,
+            avg_latency_ms,
+            last_used_at: stats.last_used_at,
+        }
+    };
+
+    let mut result = Vec::new();
+
+    for (key, pool) in app_state.pools.lock().await.iter() {
+        if let Some(id) = connection_id_of(key) {
+            result.push(build(
+                id,
+                "mysql",
+                key.clone(),
+                Some(pool.size()),
+                Some(pool.num_idle() as u32),
+            ));
+        }
+    }
+    for (id, pool) in app_state.sqlite_pools.lock().await.iter() {
+        result.push(build(
+            *id,
+            "sqlite",
+            id.to_string(),
+            Some(pool.size()),
+            Some(pool.num_idle() as u32),
+        ));
+    }
+    for key in app_state.redis_clients.lock().await.keys() {
+        if let Some(id) = connection_id_of(key) {
+            result.push(build(id, "redis", key.clone(), None, None));
+        }
+    }
+    // memcache::Client 不暴露内部 r2d2 连接池的状态
+    for id in app_state.memcached_clients.lock().await.keys() {
+        result.push(build(*id, "memcached", id.to_string(), None, None));
+    }
+
+    let others: [(&str, Vec<i64>); 9] = [
+        (
+            "mongodb",
+            cached_ids(&*app_state.mongo_clients.lock().await),
+        ),
+        (
+            "clickhouse",
+            cached_ids(&*app_state.clickhouse_clients.lock().await),
+        ),
+        (
+            "cassandra",
+            cached_ids(&*app_state.cassandra_sessions.lock().await),
+        ),
+        (
+            "duckdb",
+            cached_ids(&*app_state.duckdb_connections.lock().await),
+        ),
+        (
+            "elasticsearch",
+            cached_ids(&*app_state.elastic_clients.lock().await),
+        ),
+        (
+            "dynamodb",
+            cached_ids(&*app_state.dynamo_clients.lock().await),
+        ),
+        (
+            "couchbase",
+            cached_ids(&*app_state.couchbase_clients.lock().await),
+        ),
+        (
+            "rocksdb",
+            cached_ids(&*app_state.rocksdb_stores.lock().await),
+        ),
+        ("neo4j", cached_ids(&*app_state.neo4j_graphs.lock().await)),
+    ];
+    for (kind, ids) in others {
+        for id in ids {
+            result.push(build(id, kind, id.to_string(), None, None));
+        }
+    }
+
+    result.sort_by(|a, b| {
+        a.connection_id
+            .cmp(&b.connection_id)
+            .then_with(|| a.cache_key.cmp(&b.cache_key))
+    });
+    Ok(result)
+}
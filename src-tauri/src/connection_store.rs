@@ -0,0 +1,856 @@
+use crate::connection_manager::release_connection;
+use crate::db::{DbPool, DbState};
+use crate::models::{Connection, ConnectionGroup, CreateConnectionArgs};
+use crate::state::AppState;
+use crate::vault::{reveal_secrets, seal_secret, ExportCipher};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tauri::{command, State};
+use url::Url;
+
+const EXPORT_FORMAT_VERSION: u32 = 1;
+// connections.environment 的可选值
+const ENVIRONMENTS: &[&str] = &["dev", "staging", "prod"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedConnection {
+    #[serde(flatten)]
+    pub args: CreateConnectionArgs,
+    // 分组按名称导出，分组 id 在不同机器上不通用
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportEncryption {
+    pub salt: String,
+    pub verifier: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConnectionExportFile {
+    pub version: u32,
+    pub exported_at: String,
+    // 密码使用导出口令加密时才有
+    pub encryption: Option<ExportEncryption>,
+    pub connections: Vec<ExportedConnection>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    // 与已有连接重复而跳过的连接名称
+    pub skipped: Vec<String>,
+}
+
+// 辅助函数：标签去空白、去重并排序
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    tags.into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+async fn fetch_connection_row(pool: &DbPool, connection_id: i64) -> Result<Connection, String> {
+    sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or_else(|| "Connection not found".to_string())
+}
+
+async fn fetch_group_row(pool: &DbPool, group_id: i64) -> Result<ConnectionGroup, String> {
+    sqlx::query_as::<_, ConnectionGroup>("SELECT * FROM connection_groups WHERE id = ?")
+        .bind(group_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch group info: {}", e))?
+        .ok_or_else(|| "Group not found".to_string())
+}
+
+// 辅助函数：取分组内下一个排序号，新建的连接/分组排在最后
+async fn next_sort_order(
+    pool: &DbPool,
+    table: &str,
+    column: &str,
+    parent: Option<i64>,
+) -> Result<i32, String> {
+    sqlx::query_scalar::<_, i32>(&format!(
+        "SELECT COALESCE(MAX(sort_order), -1) + 1 FROM {} WHERE {} IS ?",
+        table, column
+    ))
+    .bind(parent)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to compute sort order: {}", e))
+}
+
+fn validate_args(args: &CreateConnectionArgs) -> Result<(), String> {
+    if args.name.trim().is_empty() {
+        return Err("Connection name is required".to_string());
+    }
+    if args.db_type.trim().is_empty() {
+        return Err("Database type is required".to_string());
+    }
+    if let Some(env) = args.environment.as_deref() {
+        if !ENVIRONMENTS.contains(&env) {
+            return Err(format!("Unknown environment: {}", env));
+        }
+    }
+    Ok(())
+}
+
+#[command]
+pub async fn list_connections(db_state: State<'_, DbState>) -> Result<Vec<Connection>, String> {
+    sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections ORDER BY sort_order ASC, created_at DESC",
+    )
+    .fetch_all(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to list connections: {}", e))
+}
+
+// 辅助函数：写入新连接（按需加密密码），返回新连接 id
+async fn insert_connection(
+    app_state: &AppState,
+    pool: &DbPool,
+    args: CreateConnectionArgs,
+) -> Result<i64, String> {
+    validate_args(&args)?;
+    // 密码由外部密钥服务提供时不在本地保存
+    let password = if args.options.password_source.is_some() {
+        None
+    } else {
+        seal_secret(app_state, pool, args.password).await?
+    };
+    let ssh_password = seal_secret(app_state, pool, args.ssh_password).await?;
+    let sort_order = next_sort_order(pool, "connections", "group_id", args.group_id).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO connections (name, db_type, host, port, username, password, database, \
+         group_id, sort_order, ssh_enabled, ssh_host, ssh_port, ssh_username, ssh_password, \
+         ssh_key_path, ssh_auth_method, ssl_mode, ssl_ca_path, ssl_cert_path, ssl_key_path, tags, \
+         read_only, options, environment) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(args.name.trim())
+    .bind(&args.db_type)
+    .bind(args.host)
+    .bind(args.port)
+    .bind(args.username)
+    .bind(password)
+    .bind(args.database)
+    .bind(args.group_id)
+    .bind(sort_order)
+    .bind(args.ssh_enabled)
+    .bind(args.ssh_host)
+    .bind(args.ssh_port)
+    .bind(args.ssh_username)
+    .bind(ssh_password)
+    .bind(args.ssh_key_path)
+    .bind(
+        args.ssh_auth_method
+            .unwrap_or_else(|| "password".to_string()),
+    )
+    .bind(args.ssl_mode)
+    .bind(args.ssl_ca_path)
+    .bind(args.ssl_cert_path)
+    .bind(args.ssl_key_path)
+    .bind(Json(normalize_tags(args.tags)))
+    .bind(args.read_only)
+    .bind(Json(args.options))
+    .bind(args.environment)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create connection: {}", e))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+#[command]
+pub async fn create_connection(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    args: CreateConnectionArgs,
+) -> Result<Connection, String> {
+    let id = insert_connection(&app_state, &db_state.pool, args).await?;
+    fetch_connection_row(&db_state.pool, id).await
+}
+
+// 全量更新连接配置；password / ssh_password 传空时保留原值
+#[command]
+pub async fn update_connection(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: CreateConnectionArgs,
+) -> Result<Connection, String> {
+    validate_args(&args)?;
+    let pool = &db_state.pool;
+    let external_password = args.options.password_source.is_some();
+    let password = if external_password {
+        None
+    } else {
+        seal_secret(&app_state, pool, args.password).await?
+    };
+    let ssh_password = seal_secret(&app_state, pool, args.ssh_password).await?;
+
+    let result = sqlx::query(
+        "UPDATE connections SET name = ?, db_type = ?, host = ?, port = ?, username = ?, \
+         password = COALESCE(?, password), database = ?, group_id = ?, ssh_enabled = ?, \
+         ssh_host = ?, ssh_port = ?, ssh_username = ?, ssh_password = COALESCE(?, ssh_password), \
+         ssh_key_path = ?, ssh_auth_method = ?, ssl_mode = ?, ssl_ca_path = ?, \
+         ssl_cert_path = ?, ssl_key_path = ?, tags = ?, read_only = ?, \
+         options = ?, environment = ? WHERE id = ?",
+    )
+    .bind(args.name.trim())
+    .bind(&args.db_type)
+    .bind(args.host)
+    .bind(args.port)
+    .bind(args.username)
+    .bind(password)
+    .bind(args.database)
+    .bind(args.group_id)
+    .bind(args.ssh_enabled)
+    .bind(args.ssh_host)
+    .bind(args.ssh_port)
+    .bind(args.ssh_username)
+    .bind(ssh_password)
+    .bind(args.ssh_key_path)
+    .bind(
+        args.ssh_auth_method
+            .unwrap_or_else(|| "password".to_string()),
+    )
+    .bind(args.ssl_mode)
+    .bind(args.ssl_ca_path)
+    .bind(args.ssl_cert_path)
+    .bind(args.ssl_key_path)
+    .bind(Json(normalize_tags(args.tags)))
+    .bind(args.read_only)
+    .bind(Json(args.options))
+    .bind(args.environment)
+    .bind(connection_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update connection: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err("Connection not found".to_string());
+    }
+    // 改为外部密码来源后清掉本地保存的旧密码
+    if external_password {
+        sqlx::query("UPDATE connections SET password = NULL WHERE id = ?")
+            .bind(connection_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to update connection: {}", e))?;
+    }
+
+    // 连接配置可能已变化，旧的连接池、客户端和隧道不再复用
+    release_connection(&app_state, connection_id).await;
+    fetch_connection_row(pool, connection_id).await
+}
+
+#[command]
+pub async fn delete_connection(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM connections WHERE id = ?")
+        .bind(connection_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete connection: {}", e))?;
+    sqlx::query("DELETE FROM query_history WHERE connection_id = ?")
+        .bind(connection_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete connection history: {}", e))?;
+    sqlx::query("DELETE FROM snippets WHERE connection_id = ?")
+        .bind(connection_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete connection snippets: {}", e))?;
+    sqlx::query("DELETE FROM undo_snapshots WHERE connection_id = ?")
+        .bind(connection_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete connection undo snapshots: {}", e))?;
+    sqlx::query(
+        "DELETE FROM scheduled_query_runs WHERE schedule_id IN \
+         (SELECT id FROM scheduled_queries WHERE connection_id = ?)",
+    )
+    .bind(connection_id)
+    .execute(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to delete connection scheduled query runs: {}", e))?;
+    sqlx::query("DELETE FROM scheduled_queries WHERE connection_id = ?")
+        .bind(connection_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete connection scheduled queries: {}", e))?;
+
+    release_connection(&app_state, connection_id).await;
+    Ok(())
+}
+
+// 把连接移动到另一个分组（group_id 为空表示移出分组），未指定位置时排在最后
+#[command]
+pub async fn move_connection(
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    group_id: Option<i64>,
+    sort_order: Option<i32>,
+) -> Result<(), String> {
+    let pool = &db_state.pool;
+    if let Some(group_id) = group_id {
+        fetch_group_row(pool, group_id).await?;
+    }
+    let sort_order = match sort_order {
+        Some(order) => order,
+        None => next_sort_order(pool, "connections", "group_id", group_id).await?,
+    };
+
+    let result = sqlx::query("UPDATE connections SET group_id = ?, sort_order = ? WHERE id = ?")
+        .bind(group_id)
+        .bind(sort_order)
+        .bind(connection_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to move connection: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err("Connection not found".to_string());
+    }
+    Ok(())
+}
+
+// 批量更新排序：[(id, sort_order)]
+#[command]
+pub async fn reorder_connections(
+    db_state: State<'_, DbState>,
+    orders: Vec<(i64, i32)>,
+) -> Result<(), String> {
+    let mut tx = db_state.pool.begin().await.map_err(|e| e.to_string())?;
+    for (id, order) in orders {
+        sqlx::query("UPDATE connections SET sort_order = ? WHERE id = ?")
+            .bind(order)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to reorder connections: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+// 所有连接中出现过的标签，供前端筛选
+#[command]
+pub async fn list_connection_tags(db_state: State<'_, DbState>) -> Result<Vec<String>, String> {
+    let rows = sqlx::query_scalar::<_, Json<Vec<String>>>("SELECT tags FROM connections")
+        .fetch_all(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to list tags: {}", e))?;
+    Ok(normalize_tags(rows.into_iter().flat_map(|t| t.0).collect()))
+}
+
+#[command]
+pub async fn list_connection_groups(
+    db_state: State<'_, DbState>,
+) -> Result<Vec<ConnectionGroup>, String> {
+    sqlx::query_as::<_, ConnectionGroup>(
+        "SELECT * FROM connection_groups ORDER BY sort_order ASC, created_at DESC",
+    )
+    .fetch_all(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to list groups: {}", e))
+}
+
+#[command]
+pub async fn create_connection_group(
+    db_state: State<'_, DbState>,
+    name: String,
+    description: Option<String>,
+    color: Option<String>,
+    parent_id: Option<i64>,
+) -> Result<ConnectionGroup, String> {
+    let pool = &db_state.pool;
+    if name.trim().is_empty() {
+        return Err("Group name is required".to_string());
+    }
+    if let Some(parent_id) = parent_id {
+        fetch_group_row(pool, parent_id).await?;
+    }
+    let sort_order = next_sort_order(pool, "connection_groups", "parent_id", parent_id).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO connection_groups (name, description, color, sort_order, parent_id) \
+         VALUES (?, ?, COALESCE(?, '#3b82f6'), ?, ?)",
+    )
+    .bind(name.trim())
+    .bind(description)
+    .bind(color)
+    .bind(sort_order)
+    .bind(parent_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create group: {}", e))?;
+
+    fetch_group_row(pool, result.last_insert_rowid()).await
+}
+
+#[command]
+pub async fn update_connection_group(
+    db_state: State<'_, DbState>,
+    group_id: i64,
+    name: String,
+    description: Option<String>,
+    color: Option<String>,
+) -> Result<ConnectionGroup, String> {
+    let pool = &db_state.pool;
+    if name.trim().is_empty() {
+        return Err("Group name is required".to_string());
+    }
+
+    let result = sqlx::query(
+        "UPDATE connection_groups SET name = ?, description = ?, color = COALESCE(?, color) \
+         WHERE id = ?",
+    )
+    .bind(name.trim())
+    .bind(description)
+    .bind(color)
+    .bind(group_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update group: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err("Group not found".to_string());
+    }
+
+    fetch_group_row(pool, group_id).await
+}
+
+// 移动分组到另一个父分组下（parent_id 为空表示移到顶层），不允许移到自己的子孙分组下
+#[command]
+pub async fn move_connection_group(
+    db_state: State<'_, DbState>,
+    group_id: i64,
+    parent_id: Option<i64>,
+    sort_order: Option<i32>,
+) -> Result<(), String> {
+    let pool = &db_state.pool;
+    fetch_group_row(pool, group_id).await?;
+
+    let mut ancestor = parent_id;
+    while let Some(id) = ancestor {
+        if id == group_id {
+            return Err("Cannot move a group into itself or one of its subgroups".to_string());
+        }
+        ancestor = fetch_group_row(pool, id).await?.parent_id;
+    }
+
+    let sort_order = match sort_order {
+        Some(order) => order,
+        None => next_sort_order(pool, "connection_groups", "parent_id", parent_id).await?,
+    };
+    sqlx::query("UPDATE connection_groups SET parent_id = ?, sort_order = ? WHERE id = ?")
+        .bind(parent_id)
+        .bind(sort_order)
+        .bind(group_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to move group: {}", e))?;
+    Ok(())
+}
+
+// 批量更新分组排序：[(id, sort_order)]
+#[command]
+pub async fn reorder_connection_groups(
+    db_state: State<'_, DbState>,
+    orders: Vec<(i64, i32)>,
+) -> Result<(), String> {
+    let mut tx = db_state.pool.begin().await.map_err(|e| e.to_string())?;
+    for (id, order) in orders {
+        sqlx::query("UPDATE connection_groups SET sort_order = ? WHERE id = ?")
+            .bind(order)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to reorder groups: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+// 删除分组：子分组和其中的连接上移到被删除分组的父分组
+#[command]
+pub async fn delete_connection_group(
+    db_state: State<'_, DbState>,
+    group_id: i64,
+) -> Result<(), String> {
+    let pool = &db_state.pool;
+    let group = fetch_group_row(pool, group_id).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE connection_groups SET parent_id = ? WHERE parent_id = ?")
+        .bind(group.parent_id)
+        .bind(group_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete group: {}", e))?;
+    sqlx::query("UPDATE connections SET group_id = ? WHERE group_id = ?")
+        .bind(group.parent_id)
+        .bind(group_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete group: {}", e))?;
+    sqlx::query("DELETE FROM connection_groups WHERE id = ?")
+        .bind(group_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete group: {}", e))?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+fn to_args(connection: Connection) -> CreateConnectionArgs {
+    CreateConnectionArgs {
+        name: connection.name,
+        db_type: connection.db_type,
+        host: connection.host,
+        port: connection.port,
+        username: connection.username,
+        password: connection.password,
+        database: connection.database,
+        group_id: connection.group_id,
+        ssh_enabled: connection.ssh_enabled,
+        ssh_host: connection.ssh_host,
+        ssh_port: connection.ssh_port,
+        ssh_username: connection.ssh_username,
+        ssh_password: connection.ssh_password,
+        ssh_key_path: connection.ssh_key_path,
+        ssh_auth_method: Some(connection.ssh_auth_method),
+        ssl_mode: connection.ssl_mode,
+        ssl_ca_path: connection.ssl_ca_path,
+        ssl_cert_path: connection.ssl_cert_path,
+        ssl_key_path: connection.ssl_key_path,
+        tags: connection.tags.0,
+        read_only: connection.read_only,
+        options: connection.options.0,
+        environment: connection.environment,
+    }
+}
+
+// 判断重复连接用的指纹：类型、地址、账号和默认库都相同即视为同一个连接
+fn connection_fingerprint(args: &CreateConnectionArgs) -> String {
+    format!(
+        "{}|{}|{}|{}|{}",
+        args.db_type,
+        args.host.as_deref().unwrap_or_default().to_lowercase(),
+        args.port.unwrap_or_default(),
+        args.username.as_deref().unwrap_or_default(),
+        args.database.as_deref().unwrap_or_default()
+    )
+}
+
+// 导出连接到 JSON 文件；password_mode: "exclude"（默认）/ "include" / "encrypt"
+#[command]
+pub async fn export_connections(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    file_path: String,
+    connection_ids: Option<Vec<i64>>,
+    password_mode: Option<String>,
+    export_password: Option<String>,
+) -> Result<usize, String> {
+    let pool = &db_state.pool;
+    let password_mode = password_mode.unwrap_or_else(|| "exclude".to_string());
+    let (cipher, encryption) = match password_mode.as_str() {
+        "exclude" | "include" => (None, None),
+        "encrypt" => {
+            let password = export_password
+                .filter(|p| !p.is_empty())
+                .ok_or("Export password is required")?;
+            let (cipher, salt, verifier) = ExportCipher::create(password).await?;
+            (Some(cipher), Some(ExportEncryption { salt, verifier }))
+        }
+        other => return Err(format!("Unsupported password mode: {}", other)),
+    };
+
+    let group_names: HashMap<i64, String> =
+        sqlx::query_as::<_, (i64, String)>("SELECT id, name FROM connection_groups")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to list groups: {}", e))?
+            .into_iter()
+            .collect();
+
+    let mut connections = list_connections(db_state.clone()).await?;
+    if let Some(ids) = connection_ids {
+        connections.retain(|c| ids.contains(&c.id));
+    }
+
+    let mut exported = Vec::with_capacity(connections.len());
+    for connection in connections {
+        let connection = if password_mode == "exclude" {
+            connection
+        } else {
+            reveal_secrets(&app_state, connection).await?
+        };
+        let mut args = to_args(connection);
+        let group = args
+            .group_id
+            .take()
+            .and_then(|id| group_names.get(&id).cloned());
+
+        for field in [&mut args.password, &mut args.ssh_password] {
+            *field = match (&cipher, field.take()) {
+                _ if password_mode == "exclude" => None,
+                (Some(cipher), Some(value)) if !value.is_empty() => Some(cipher.encrypt(&value)?),
+                (_, value) => value,
+            };
+        }
+        exported.push(ExportedConnection { args, group });
+    }
+
+    let count = exported.len();
+    let file = ConnectionExportFile {
+        version: EXPORT_FORMAT_VERSION,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        encryption,
+        connections: exported,
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize connections: {}", e))?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| format!("Failed to write {}: {}", file_path, e))?;
+
+    Ok(count)
+}
+
+// 从 JSON 文件导入连接，与已有连接重复的跳过；分组按名称匹配，不存在时新建
+#[command]
+pub async fn import_connections(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    file_path: String,
+    export_password: Option<String>,
+) -> Result<ImportSummary, String> {
+    let pool = &db_state.pool;
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    let file: ConnectionExportFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid connection export file: {}", e))?;
+    if file.version > EXPORT_FORMAT_VERSION {
+        return Err(format!("Unsupported export file version: {}", file.version));
+    }
+
+    let cipher = match &file.encryption {
+        Some(encryption) => {
+            let password = export_password
+                .filter(|p| !p.is_empty())
+                .ok_or("This export file is password protected")?;
+            Some(ExportCipher::open(password, &encryption.salt, &encryption.verifier).await?)
+        }
+        None => None,
+    };
+
+    let mut fingerprints: HashSet<String> = list_connections(db_state.clone())
+        .await?
+        .into_iter()
+        .map(|c| connection_fingerprint(&to_args(c)))
+        .collect();
+    let mut groups: HashMap<String, i64> =
+        sqlx::query_as::<_, (String, i64)>("SELECT name, id FROM connection_groups")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to list groups: {}", e))?
+            .into_iter()
+            .collect();
+
+    let mut summary = ImportSummary {
+        imported: 0,
+        skipped: Vec::new(),
+    };
+    for ExportedConnection { mut args, group } in file.connections {
+        let fingerprint = connection_fingerprint(&args);
+        if !fingerprints.insert(fingerprint) {
+            summary.skipped.push(args.name);
+            continue;
+        }
+
+        if let Some(cipher) = &cipher {
+            for field in [&mut args.password, &mut args.ssh_password] {
+                if let Some(value) = field.as_deref() {
+                    *field = Some(cipher.decrypt(value)?);
+                }
+            }
+        }
+
+        args.group_id = match group.filter(|g| !g.is_empty()) {
+            Some(name) => match groups.get(&name) {
+                Some(id) => Some(*id),
+                None => {
+                    let sort_order =
+                        next_sort_order(pool, "connection_groups", "parent_id", None).await?;
+                    let id = sqlx::query(
+                        "INSERT INTO connection_groups (name, sort_order) VALUES (?, ?)",
+                    )
+                    .bind(&name)
+                    .bind(sort_order)
+                    .execute(pool)
+                    .await
+                    .map_err(|e| format!("Failed to create group: {}", e))?
+                    .last_insert_rowid();
+                    groups.insert(name, id);
+                    Some(id)
+                }
+            },
+            None => None,
+        };
+
+        insert_connection(&app_state, pool, args).await?;
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+// 辅助函数：把各驱动的 sslmode 写法统一成 connections.ssl_mode 的取值
+fn normalize_ssl_mode(mode: &str) -> String {
+    match mode.to_lowercase().replace('-', "_").as_str() {
+        "disable" | "disabled" | "false" => "disabled".to_string(),
+        "prefer" | "preferred" => "preferred".to_string(),
+        "require" | "required" | "true" => "required".to_string(),
+        "verify_full" | "verify_identity" => "verify_identity".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// sqlite:path、sqlite://path、sqlite:///abs/path 都视为文件路径
+fn parse_sqlite_uri(rest: &str) -> Result<CreateConnectionArgs, String> {
+    let path = rest.split('?').next().unwrap_or_default();
+    let path = path.strip_prefix("//").unwrap_or(path);
+    if path.is_empty() {
+        return Err("SQLite URI must contain a file path".to_string());
+    }
+    let path = urlencoding::decode(path)
+        .map_err(|e| format!("Invalid SQLite path: {}", e))?
+        .into_owned();
+
+    let mut args = CreateConnectionArgs {
+        db_type: "sqlite".to_string(),
+        ..Default::default()
+    };
+    args.name = std::path::Path::new(&path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    args.database = Some(path);
+    Ok(args)
+}
+
+// 解析 mysql:// postgres:// redis:// redis+unix:// sqlite: 等 DSN，返回可直接用于新建连接的参数
+#[command]
+pub async fn parse_connection_uri(uri: String) -> Result<CreateConnectionArgs, String> {
+    let uri = uri.trim();
+    if let Some(rest) = uri.strip_prefix("sqlite:") {
+        return parse_sqlite_uri(rest);
+    }
+
+    let url = Url::parse(uri).map_err(|e| format!("Invalid connection URI: {}", e))?;
+    let (db_type, default_port) = match url.scheme() {
+        "mysql" => ("mysql", 3306),
+        "mariadb" => ("mariadb", 3306),
+        "postgres" | "postgresql" => ("postgres", 5432),
+        "redis" | "rediss" | "redis+unix" | "unix" => ("redis", 6379),
+        "memcache" | "memcached" => ("memcached", 11211),
+        other => return Err(format!("Unsupported URI scheme: {}", other)),
+    };
+
+    let decode = |s: &str| {
+        urlencoding::decode(s)
+            .map(|v| v.into_owned())
+            .map_err(|e| format!("Invalid connection URI: {}", e))
+    };
+
+    let mut args = CreateConnectionArgs {
+        db_type: db_type.to_string(),
+        ..Default::default()
+    };
+    if matches!(url.scheme(), "redis+unix" | "unix") {
+        // redis+unix:///run/redis.sock?db=1&user=..&pass=.. 的路径部分就是 socket 文件
+        let socket_path = decode(url.path())?;
+        if socket_path.is_empty() {
+            return Err("Unix socket URI must contain a socket path".to_string());
+        }
+        args.name = socket_path.clone();
+        args.options.socket_path = Some(socket_path);
+    } else {
+        let host = url
+            .host_str()
+            .map(|h| h.trim_start_matches('[').trim_end_matches(']').to_string())
+            .filter(|h| !h.is_empty())
+            .ok_or("Connection URI must contain a host")?;
+        let port = url.port().unwrap_or(default_port);
+        args.name = format!("{}:{}", host, port);
+        args.host = Some(host);
+        args.port = Some(port as i32);
+        let path = decode(url.path().trim_start_matches('/'))?;
+        if !path.is_empty() {
+            args.database = Some(path);
+        }
+    }
+    if !url.username().is_empty() {
+        args.username = Some(decode(url.username())?);
+    }
+    if let Some(password) = url.password() {
+        args.password = Some(decode(password)?);
+    }
+
+    if url.scheme() == "rediss" {
+        // rediss://host#insecure 表示加密但不校验证书
+        args.ssl_mode = Some(if url.fragment() == Some("insecure") {
+            "required".to_string()
+        } else {
+            "verify_identity".to_string()
+        });
+    }
+
+    for (key, value) in url.query_pairs() {
+        let value = value.into_owned();
+        match key.to_lowercase().replace('-', "_").as_str() {
+            "ssl_mode" | "sslmode" => args.ssl_mode = Some(normalize_ssl_mode(&value)),
+            "ssl_ca" | "sslrootcert" => args.ssl_ca_path = Some(value),
+            "ssl_cert" | "sslcert" => args.ssl_cert_path = Some(value),
+            "ssl_key" | "sslkey" => args.ssl_key_path = Some(value),
+            "user" | "username" if args.username.is_none() => args.username = Some(value),
+            "password" | "pass" if args.password.is_none() => args.password = Some(value),
+            // Redis 的库号也可以写在查询参数里：redis://host?db=2
+            "db" | "database" | "dbname" => args.database = Some(value),
+            "socket" | "unix_socket" => args.options.socket_path = Some(value),
+            "charset" => args.options.charset = Some(value),
+            "collation" => args.options.collation = Some(value),
+            "time_zone" | "timezone" => args.options.time_zone = Some(value),
+            "application_name" | "appname" => args.options.application_name = Some(value),
+            "connect_timeout" => {
+                let secs = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid connect_timeout: {}", value))?;
+                args.options.connect_timeout_secs = Some(secs);
+            }
+            _ => {}
+        }
+    }
+
+    // Redis 库号必须是数字
+    if db_type == "redis" {
+        if let Some(db) = args.database.as_deref() {
+            db.parse::<u32>()
+                .map_err(|_| format!("Invalid Redis database index: {}", db))?;
+        }
+    }
+
+    Ok(args)
+}
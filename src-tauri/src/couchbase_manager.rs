@@ -0,0 +1,367 @@
+use crate::connection_stats::record_query;
+use crate::db::DbState;
+use crate::models::{ColumnInfo, Connection, SqlResult};
+use crate::ssh_tunnel::resolve_endpoint;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+use tauri::{command, State};
+use tokio::time::{Duration, Instant};
+use urlencoding::encode;
+
+const COUCHBASE_REQUEST_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Clone)]
+pub struct CouchbaseClient {
+    http: reqwest::Client,
+    management_url: String,
+    query_url: String,
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CouchbaseScope {
+    pub name: String,
+    pub collections: Vec<String>,
+}
+
+async fn get_or_create_couchbase_client(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<CouchbaseClient, String> {
+    // 1. Check cache
+    {
+        let clients = app_state.couchbase_clients.lock().await;
+        if let Some(client) = clients.get(&connection_id) {
+            return Ok(client.clone());
+        }
+    }
+
+    // 2. Fetch connection info
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
+
+    if connection.db_type != "couchbase" {
+        return Err("Only Couchbase is supported for this operation".to_string());
+    }
+
+    // 3. Resolve service URLs: the stored port is the cluster manager port (8091, or
+    //    18091 for TLS); the query service listens on the matching 8093/18093 port.
+    let host = connection.host.as_deref().ok_or("Host is required")?;
+    let port = connection.port.unwrap_or(8091) as u16;
+    let (scheme, query_port) = if port == 18091 {
+        ("https", 18093)
+    } else {
+        ("http", 8093)
+    };
+    let host = host.trim_end_matches('/');
+    let (scheme, host) = match host.split_once("://") {
+        Some((s, h)) => (s, h),
+        None => (scheme, host),
+    };
+
+    // Plain HTTP services can go through the SSH tunnel; each port gets its own forward
+    let ((management_host, port), (query_host, query_port)) = if scheme == "http" {
+        (
+            resolve_endpoint(app_state, &connection, host, port).await?,
+            resolve_endpoint(app_state, &connection, host, query_port).await?,
+        )
+    } else {
+        ((host.to_string(), port), (host.to_string(), query_port))
+    };
+
+    // 4. Create Client
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(COUCHBASE_REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create Couchbase client: {}", e))?;
+
+    let client = CouchbaseClient {
+        http,
+        management_url: format!("{}://{}:{}", scheme, management_host, port),
+        query_url: format!("{}://{}:{}/query/service", scheme, query_host, query_port),
+        username: connection.username.unwrap_or_default(),
+        password: connection.password.unwrap_or_default(),
+    };
+
+    // 5. Cache client
+    let mut clients = app_state.couchbase_clients.lock().await;
+    clients.insert(connection_id, client.clone());
+
+    Ok(client)
+}
+
+impl CouchbaseClient {
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<JsonValue, String> {
+        let response = request
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .await
+            .map_err(|e| format!("Couchbase request failed: {}", e))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read Couchbase response: {}", e))?;
+        let body = serde_json::from_str::<JsonValue>(&text);
+
+        if !status.is_success() {
+            // The query service reports failures in an `errors` array
+            let message = body
+                .ok()
+                .and_then(|v| {
+                    v.get("errors")?.as_array().map(|errors| {
+                        errors
+                            .iter()
+                            .map(|e| {
+                                e.get("msg")
+                                    .and_then(|m| m.as_str())
+                                    .map(str::to_string)
+                                    .unwrap_or_else(|| e.to_string())
+                            })
+                            .collect::<Vec<_>>()
+                            .join("; ")
+                    })
+                })
+                .unwrap_or(text);
+            return Err(format!("Couchbase returned {}: {}", status, message));
+        }
+
+        body.map_err(|e| format!("Failed to parse Couchbase response: {}", e))
+    }
+
+    async fn management_get(&self, path: &str) -> Result<JsonValue, String> {
+        self.send(self.http.get(format!("{}{}", self.management_url, path)))
+            .await
+    }
+
+    async fn query(&self, body: JsonValue) -> Result<JsonValue, String> {
+        self.send(self.http.post(&self.query_url).json(&body)).await
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+fn json_type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+// Convert N1QL result documents into the standard column/row shape
+fn documents_to_sql_result(documents: Vec<JsonValue>, affected_rows: u64) -> SqlResult {
+    let mut columns: Vec<ColumnInfo> = Vec::new();
+    let mut rows = Vec::with_capacity(documents.len());
+
+    for document in documents {
+        // SELECT RAW returns bare values instead of objects
+        let row = match document {
+            JsonValue::Object(map) => map,
+            other => {
+                let mut map = Map::new();
+                map.insert("value".to_string(), other);
+                map
+            }
+        };
+        for (name, value) in &row {
+            if !columns.iter().any(|c| &c.name == name) {
+                columns.push(ColumnInfo {
+                    name: name.clone(),
+                    type_name: json_type_name(value).to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+        rows.push(row);
+    }
+
+    SqlResult {
+        columns,
+        rows,
+        affected_rows,
+        duration_ms: 0,
+        warnings: vec![],
+        last_insert_id: None,
+        applied_limit: None,
+        from_cache: false,
+        spilled: None,
+    }
+}
+
+#[command]
+pub async fn list_couchbase_buckets(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<String>, String> {
+    let client = get_or_create_couchbase_client(&app_state, &db_state, connection_id).await?;
+    let result = client.management_get("/pools/default/buckets").await?;
+
+    let mut buckets: Vec<String> = result
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|b| b.get("name")?.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    buckets.sort();
+    Ok(buckets)
+}
+
+#[command]
+pub async fn list_couchbase_collections(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    bucket: String,
+) -> Result<Vec<CouchbaseScope>, String> {
+    let client = get_or_create_couchbase_client(&app_state, &db_state, connection_id).await?;
+    let result = client
+        .management_get(&format!("/pools/default/buckets/{}/scopes", encode(&bucket)))
+        .await?;
+
+    let scopes = result
+        .get("scopes")
+        .and_then(|s| s.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|scope| {
+                    let name = scope.get("name")?.as_str()?.to_string();
+                    let mut collections: Vec<String> = scope
+                        .get("collections")
+                        .and_then(|c| c.as_array())
+                        .map(|cs| {
+                            cs.iter()
+                                .filter_map(|c| c.get("name")?.as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    collections.sort();
+                    Some(CouchbaseScope { name, collections })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(scopes)
+}
+
+#[command]
+pub async fn get_couchbase_document(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    bucket: String,
+    scope: Option<String>,
+    collection: Option<String>,
+    key: String,
+) -> Result<Option<JsonValue>, String> {
+    let client = get_or_create_couchbase_client(&app_state, &db_state, connection_id).await?;
+
+    let keyspace = format!(
+        "{}.{}.{}",
+        quote_identifier(&bucket),
+        quote_identifier(scope.as_deref().unwrap_or("_default")),
+        quote_identifier(collection.as_deref().unwrap_or("_default"))
+    );
+    let result = client
+        .query(json!({
+            "statement": format!("SELECT RAW d FROM {} AS d USE KEYS $1", keyspace),
+            "args": [key],
+        }))
+        .await?;
+
+    Ok(result
+        .get("results")
+        .and_then(|r| r.as_array())
+        .and_then(|r| r.first())
+        .cloned())
+}
+
+#[command]
+pub async fn execute_n1ql(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    statement: String,
+    positional_params: Option<Vec<JsonValue>>,
+    named_params: Option<Map<String, JsonValue>>,
+    query_context: Option<String>,
+) -> Result<SqlResult, String> {
+    let client = get_or_create_couchbase_client(&app_state, &db_state, connection_id).await?;
+
+    let mut body = Map::new();
+    body.insert("statement".to_string(), JsonValue::String(statement));
+    if let Some(args) = positional_params.filter(|a| !a.is_empty()) {
+        body.insert("args".to_string(), JsonValue::Array(args));
+    }
+    // Named parameters are sent as top-level `$name` fields
+    for (name, value) in named_params.unwrap_or_default() {
+        let name = if name.starts_with('$') {
+            name
+        } else {
+            format!("${}", name)
+        };
+        body.insert(name, value);
+    }
+    if let Some(context) = query_context.filter(|c| !c.is_empty()) {
+        body.insert("query_context".to_string(), JsonValue::String(context));
+    }
+
+    let started = Instant::now();
+    let result = client.query(JsonValue::Object(body)).await;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let result = result?;
+
+    let documents = result
+        .get("results")
+        .and_then(|r| r.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let affected_rows = result
+        .pointer("/metrics/mutationCount")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    // N1QL reports non-fatal issues as {"code": ..., "msg": ...} entries
+    let warnings = result
+        .get("warnings")
+        .and_then(|w| w.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .map(|w| {
+                    let code = w.get("code").and_then(|c| c.as_i64()).unwrap_or_default();
+                    let msg = w.get("msg").and_then(|m| m.as_str()).unwrap_or_default();
+                    format!("Warning {}: {}", code, msg)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut sql_result = documents_to_sql_result(documents, affected_rows);
+    sql_result.duration_ms = elapsed.as_millis() as u64;
+    sql_result.warnings = warnings;
+    Ok(sql_result)
+}
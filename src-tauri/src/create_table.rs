@@ -0,0 +1,289 @@
+use crate::alter_table::{
+    apply_ddl, column_definition_sql, qualified_name, quote_identifier, quote_list,
+};
+use crate::db::{connection_flavor, DbState};
+use crate::index_manager::{default_index_name, index_kind};
+use crate::models::{ColumnDefinition, CreateTableArgs, CreateTableResult, ForeignKeySpec};
+use crate::result_export::sql_literal;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use serde_json::Value;
+use tauri::{command, State};
+
+const REFERENTIAL_ACTIONS: &[&str] = &[
+    "CASCADE",
+    "SET NULL",
+    "SET DEFAULT",
+    "RESTRICT",
+    "NO ACTION",
+];
+
+// 主键、索引、外键引用的列必须在列定义中，返回定义中的列名
+fn resolve_columns(
+    args: &CreateTableArgs,
+    columns: &[String],
+    usage: &str,
+) -> Result<Vec<String>, String> {
+    if columns.is_empty() {
+        return Err(format!("{} requires at least one column", usage));
+    }
+    columns
+        .iter()
+        .map(|name| {
+            args.columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(name))
+                .map(|c| c.name.clone())
+                .ok_or_else(|| format!("{} references unknown column: {}", usage, name))
+        })
+        .collect()
+}
+
+// ENGINE / CHARSET / COLLATE 只接受名称，不带引号拼入
+pub fn option_name(label: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid {}: {}", label, value));
+    }
+    Ok(value.to_string())
+}
+
+fn referential_action(action: &Option<String>) -> Result<Option<&'static str>, String> {
+    let Some(action) = action else {
+        return Ok(None);
+    };
+    let normalized = action.split_whitespace().collect::<Vec<_>>().join(" ");
+    REFERENTIAL_ACTIONS
+        .iter()
+        .find(|a| a.eq_ignore_ascii_case(&normalized))
+        .copied()
+        .map(Some)
+        .ok_or_else(|| format!("Unsupported referential action: {}", action))
+}
+
+fn foreign_key_sql(
+    args: &CreateTableArgs,
+    key: &ForeignKeySpec,
+    flavor: SqlFlavor,
+) -> Result<String, String> {
+    let columns = resolve_columns(args, &key.columns, "Foreign key")?;
+    if key.referenced_columns.len() != columns.len() {
+        return Err(format!(
+            "Foreign key to {} has {} column(s) but references {}",
+            key.referenced_table,
+            columns.len(),
+            key.referenced_columns.len()
+        ));
+    }
+    let mut sql = String::new();
+    if let Some(name) = &key.name {
+        sql.push_str(&format!("CONSTRAINT {} ", quote_identifier(name, flavor)));
+    }
+    sql.push_str(&format!(
+        "FOREIGN KEY ({}) REFERENCES {} ({})",
+        quote_list(&columns, flavor),
+        quote_identifier(&key.referenced_table, flavor),
+        quote_list(&key.referenced_columns, flavor)
+    ));
+    if let Some(action) = referential_action(&key.on_delete)? {
+        sql.push_str(&format!(" ON DELETE {}", action));
+    }
+    if let Some(action) = referential_action(&key.on_update)? {
+        sql.push_str(&format!(" ON UPDATE {}", action));
+    }
+    Ok(sql)
+}
+
+// SQLite 的 AUTOINCREMENT 只能写在唯一的 INTEGER PRIMARY KEY 列上，此时主键内联在列定义中
+fn sqlite_column_sql(
+    definition: &ColumnDefinition,
+    primary_key: &[String],
+) -> Result<(String, bool), String> {
+    if !definition.auto_increment {
+        return Ok((column_definition_sql(definition, SqlFlavor::Sqlite)?, false));
+    }
+    let is_sole_key = primary_key.len() == 1 && primary_key[0] == definition.name;
+    if !is_sole_key || !definition.data_type.trim().eq_ignore_ascii_case("integer") {
+        return Err(format!(
+            "Column {}: SQLite only supports AUTOINCREMENT on a single INTEGER PRIMARY KEY",
+            definition.name
+        ));
+    }
+    let definition = ColumnDefinition {
+        auto_increment: false,
+        ..definition.clone()
+    };
+    Ok((
+        format!(
+            "{} PRIMARY KEY AUTOINCREMENT",
+            column_definition_sql(&definition, SqlFlavor::Sqlite)?
+        ),
+        true,
+    ))
+}
+
+fn build_statements(args: &CreateTableArgs, flavor: SqlFlavor) -> Result<Vec<String>, String> {
+    if args.table.trim().is_empty() {
+        return Err("Table name is required".to_string());
+    }
+    if args.columns.is_empty() {
+        return Err("A table needs at least one column".to_string());
+    }
+    for (i, column) in args.columns.iter().enumerate() {
+        if args.columns[..i]
+            .iter()
+            .any(|c| c.name.eq_ignore_ascii_case(&column.name))
+        {
+            return Err(format!("Duplicate column: {}", column.name));
+        }
+    }
+    let primary_key = match args.primary_key.is_empty() {
+        true => Vec::new(),
+        false => resolve_columns(args, &args.primary_key, "Primary key")?,
+    };
+    let table_ref = qualified_name(args.db_name.as_deref(), &args.table, flavor);
+
+    let mut items = Vec::new();
+    let mut key_inlined = false;
+    for column in &args.columns {
+        match flavor {
+            SqlFlavor::MySql => items.push(column_definition_sql(column, flavor)?),
+            SqlFlavor::Sqlite => {
+                let (sql, inlined) = sqlite_column_sql(column, &primary_key)?;
+                key_inlined |= inlined;
+                items.push(sql);
+            }
+        }
+    }
+    if !primary_key.is_empty() && !key_inlined {
+        items.push(format!(
+            "PRIMARY KEY ({})",
+            quote_list(&primary_key, flavor)
+        ));
+    }
+
+    // MySQL 的索引写在 CREATE TABLE 中，SQLite 需要单独的 CREATE INDEX
+    let mut index_statements = Vec::new();
+    for index in &args.indexes {
+        let columns = resolve_columns(args, &index.columns, "Index")?;
+        let name = index
+            .name
+            .clone()
+            .unwrap_or_else(|| default_index_name(&args.table, &columns, index.unique));
+        let (prefix, using) = index_kind(index, flavor)?;
+        match flavor {
+            SqlFlavor::MySql => items.push(format!(
+                "{}KEY {} ({}){}",
+                prefix,
+                quote_identifier(&name, flavor),
+                quote_list(&columns, flavor),
+                using
+            )),
+            SqlFlavor::Sqlite => index_statements.push(format!(
+                "CREATE {}INDEX {}{} ON {} ({})",
+                prefix,
+                if args.if_not_exists {
+                    "IF NOT EXISTS "
+                } else {
+                    ""
+                },
+                quote_identifier(&name, flavor),
+                table_ref,
+                quote_list(&columns, flavor)
+            )),
+        }
+    }
+    for key in &args.foreign_keys {
+        items.push(foreign_key_sql(args, key, flavor)?);
+    }
+
+    let mut options = Vec::new();
+    match flavor {
+        SqlFlavor::MySql => {
+            if let Some(engine) = &args.engine {
+                options.push(format!("ENGINE={}", option_name("engine", engine)?));
+            }
+            if let Some(charset) = &args.charset {
+                options.push(format!(
+                    "DEFAULT CHARSET={}",
+                    option_name("charset", charset)?
+                ));
+            }
+            if let Some(collation) = &args.collation {
+                options.push(format!("COLLATE={}", option_name("collation", collation)?));
+            }
+            if let Some(comment) = &args.comment {
+                options.push(format!(
+                    "COMMENT={}",
+                    sql_literal(&Value::String(comment.clone()), SqlFlavor::MySql)
+                ));
+            }
+        }
+        SqlFlavor::Sqlite => {
+            if args.engine.is_some()
+                || args.charset.is_some()
+                || args.collation.is_some()
+                || args.comment.is_some()
+            {
+                return Err(
+                    "SQLite does not support table engine, charset, collation or comment"
+                        .to_string(),
+                );
+            }
+        }
+    }
+
+    let mut statements = vec![format!(
+        "CREATE TABLE {}{} (\n  {}\n){}",
+        if args.if_not_exists {
+            "IF NOT EXISTS "
+        } else {
+            ""
+        },
+        table_ref,
+        items.join(",\n  "),
+        if options.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", options.join(" "))
+        }
+    )];
+    statements.extend(index_statements);
+    Ok(statements)
+}
+
+// 按结构化的定义生成 CREATE TABLE（SQLite 的索引为单独的 CREATE INDEX）。
+// 未确认时只返回语句供预览，confirmed 为 true 时执行
+#[command]
+pub async fn create_table(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: CreateTableArgs,
+    confirmed: Option<bool>,
+) -> Result<CreateTableResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Creating tables").await?;
+    let statements = build_statements(&args, flavor)?;
+    if confirmed != Some(true) {
+        return Ok(CreateTableResult {
+            statements,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        args.db_name.clone(),
+        &statements,
+        None,
+    )
+    .await?;
+
+    Ok(CreateTableResult {
+        statements,
+        executed: true,
+    })
+}
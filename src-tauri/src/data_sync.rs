@@ -0,0 +1,692 @@
+use crate::alter_table::{qualified_name, quote_identifier};
+use crate::connection_stats::record_query;
+use crate::data_transfer::{cell_literal, cell_rows, Cell};
+use crate::db::{connection_flavor, DbState};
+use crate::guard::{ensure_sql_allowed, ensure_writable};
+use crate::models::{SchemaTarget, TableStructure};
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::result_cache::invalidate_results;
+use crate::schema_diff::{is_generated, primary_key};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::table_structure::{mysql_structure, sqlite_structure};
+use crate::{mysql_manager, sqlite_manager};
+use futures_util::stream::BoxStream;
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::pool::PoolConnection;
+use sqlx::{Connection, MySql, Sqlite};
+use std::cmp::Ordering;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, Emitter, State};
+
+// 前端监听该事件展示对比进度
+pub const TABLE_COMPARE_PROGRESS_EVENT: &str = "table-compare-progress";
+
+// 发送进度事件的间隔（已对比的行数）
+const TABLE_COMPARE_PROGRESS_INTERVAL: u64 = 10_000;
+// 结果中附带的差异行数
+const TABLE_COMPARE_MAX_SAMPLES: usize = 1_000;
+// 生成的同步语句上限，超出后不再生成，也不能直接执行
+const TABLE_COMPARE_MAX_STATEMENTS: usize = 100_000;
+
+// MySQL 中按二进制排序的文本类型，使数据库的排序与这里的逐字节比较一致
+const MYSQL_TEXT_TYPES: &[&str] = &[
+    "char",
+    "varchar",
+    "tinytext",
+    "text",
+    "mediumtext",
+    "longtext",
+    "enum",
+    "set",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableCompareProgress {
+    pub execution_id: String,
+    pub compared_rows: u64,
+    pub differences: u64,
+    pub done: bool,
+}
+
+// kind 为 "missing"（只在源端）/ "extra"（只在目标端）/ "changed"
+#[derive(Debug, Serialize)]
+pub struct DataRowDifference {
+    pub kind: String,
+    pub key: Map<String, Value>,
+    pub source: Option<Map<String, Value>>,
+    pub target: Option<Map<String, Value>>,
+    pub changed_columns: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableDataComparison {
+    pub execution_id: String,
+    pub key_columns: Vec<String>,
+    // 参与对比的列：两端都有且不是生成列
+    pub columns: Vec<String>,
+    pub missing: u64,
+    pub extra: u64,
+    pub changed: u64,
+    pub unchanged: u64,
+    pub samples: Vec<DataRowDifference>,
+    // 让目标与源一致的 INSERT / UPDATE / DELETE
+    pub statements: Vec<String>,
+    pub statements_truncated: bool,
+    // 执行同步时的影响行数
+    pub affected_rows: Option<u64>,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+pub enum SideConnection {
+    MySql(PoolConnection<MySql>),
+    Sqlite(PoolConnection<Sqlite>),
+}
+
+// 一行按 columns 的顺序排列，二进制值按字节读取，对比和写回时不经过 JSON 转换
+type RowStream<'a> = BoxStream<'a, Result<Vec<Cell>, sqlx::Error>>;
+
+impl SideConnection {
+    pub async fn open(
+        app_state: &State<'_, AppState>,
+        db_state: &State<'_, DbState>,
+        side: &SchemaTarget,
+        flavor: SqlFlavor,
+    ) -> Result<Self, String> {
+        match flavor {
+            SqlFlavor::MySql => mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                side.connection_id,
+                side.db_name.clone(),
+            )
+            .await
+            .map(SideConnection::MySql),
+            SqlFlavor::Sqlite => {
+                let pool =
+                    sqlite_manager::get_or_create_pool(app_state, db_state, side.connection_id)
+                        .await?;
+                pool.acquire()
+                    .await
+                    .map(SideConnection::Sqlite)
+                    .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))
+            }
+        }
+    }
+}
+
+pub async fn load_structure(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    side: &SchemaTarget,
+    flavor: SqlFlavor,
+    table: &str,
+) -> Result<TableStructure, String> {
+    match SideConnection::open(app_state, db_state, side, flavor).await? {
+        SideConnection::MySql(conn) => mysql_structure(conn, side.db_name.as_deref(), table).await,
+        SideConnection::Sqlite(conn) => sqlite_structure(conn, table).await,
+    }
+}
+
+// 键的排序：NULL 最小，其次数值、文本和二进制值（逐字节），与 ORDER BY 的结果一致
+fn compare_values(a: &Cell, b: &Cell) -> Ordering {
+    fn rank(cell: &Cell) -> u8 {
+        match cell {
+            Cell::Json(Value::Null) => 0,
+            Cell::Json(Value::Bool(_) | Value::Number(_)) => 1,
+            Cell::Json(Value::String(_)) => 2,
+            Cell::Bytes(_) => 3,
+            Cell::Json(_) => 4,
+        }
+    }
+    match (a, b) {
+        (Cell::Json(Value::Number(x)), Cell::Json(Value::Number(y))) => {
+            match (x.as_i64(), y.as_i64()) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                _ => x
+                    .as_f64()
+                    .partial_cmp(&y.as_f64())
+                    .unwrap_or(Ordering::Equal),
+            }
+        }
+        (Cell::Json(Value::String(x)), Cell::Json(Value::String(y))) => x.cmp(y),
+        (Cell::Bytes(x), Cell::Bytes(y)) => x.cmp(y),
+        (Cell::Json(x), Cell::Json(y)) if rank(a) == rank(b) => x.to_string().cmp(&y.to_string()),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+// 差异样例返回给前端展示，二进制值显示为十六进制
+fn display_value(cell: &Cell) -> Value {
+    match cell {
+        Cell::Json(value) => value.clone(),
+        Cell::Bytes(bytes) => Value::String(format!(
+            "0x{}",
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        )),
+    }
+}
+
+fn compare_keys(a: &[Cell], b: &[Cell]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| compare_values(x, y))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn order_term(column: &str, structure: &TableStructure, flavor: SqlFlavor) -> String {
+    let quoted = quote_identifier(column, flavor);
+    match flavor {
+        SqlFlavor::MySql => {
+            let is_text = structure
+                .columns
+                .iter()
+                .find(|c| c.name == column)
+                .map(|c| c.column_type.to_ascii_lowercase())
+                .is_some_and(|t| {
+                    MYSQL_TEXT_TYPES
+                        .iter()
+                        .any(|p| t == *p || t.starts_with(&format!("{}(", p)))
+                });
+            if is_text {
+                format!("CAST({} AS BINARY)", quoted)
+            } else {
+                quoted
+            }
+        }
+        SqlFlavor::Sqlite => format!("{} COLLATE BINARY", quoted),
+    }
+}
+
+fn select_sql(
+    side: &SchemaTarget,
+    table: &str,
+    structure: &TableStructure,
+    flavor: SqlFlavor,
+    columns: &[String],
+    key_columns: &[String],
+) -> String {
+    format!(
+        "SELECT {} FROM {} ORDER BY {}",
+        columns
+            .iter()
+            .map(|c| quote_identifier(c, flavor))
+            .collect::<Vec<_>>()
+            .join(", "),
+        qualified_name(side.db_name.as_deref(), table, flavor),
+        key_columns
+            .iter()
+            .map(|c| order_term(c, structure, flavor))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+struct Comparison<'a> {
+    app_state: &'a AppState,
+    flavor: SqlFlavor,
+    table_sql: String,
+    columns: Vec<String>,
+    // 键列在 columns 中的位置
+    key_indices: Vec<usize>,
+    cancel: Arc<AtomicBool>,
+    progress: TableCompareProgress,
+    result: TableDataComparison,
+}
+
+impl Comparison<'_> {
+    fn emit(&self) {
+        if let Some(app) = self.app_state.app_handle.as_ref() {
+            let _ = app.emit(TABLE_COMPARE_PROGRESS_EVENT, self.progress.clone());
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(AtomicOrdering::Relaxed)
+    }
+
+    fn key(&self, row: &[Cell]) -> Vec<Cell> {
+        self.key_indices.iter().map(|&i| row[i].clone()).collect()
+    }
+
+    fn row_map(&self, row: &[Cell]) -> Map<String, Value> {
+        self.columns
+            .iter()
+            .zip(row)
+            .map(|(column, cell)| (column.clone(), display_value(cell)))
+            .collect()
+    }
+
+    // 超出内联大小的二进制值只有预览，无法写回
+    fn literal(&self, cell: &Cell) -> Result<String, String> {
+        if let Cell::Json(value) = cell {
+            if value.get("type").and_then(Value::as_str) == Some("blob")
+                && value.get("preview").is_some()
+            {
+                return Err("Binary values too large to load cannot be synchronized".to_string());
+            }
+        }
+        Ok(cell_literal(cell, self.flavor))
+    }
+
+    fn where_clause(&self, row: &[Cell]) -> Result<String, String> {
+        self.key_indices
+            .iter()
+            .map(|&i| {
+                let name = quote_identifier(&self.columns[i], self.flavor);
+                match &row[i] {
+                    Cell::Json(Value::Null) => Ok(format!("{} IS NULL", name)),
+                    cell => Ok(format!("{} = {}", name, self.literal(cell)?)),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map(|conditions| conditions.join(" AND "))
+    }
+
+    fn statement(
+        &self,
+        kind: &str,
+        source: Option<&[Cell]>,
+        target: Option<&[Cell]>,
+        changed: &[usize],
+    ) -> Result<String, String> {
+        match (kind, source, target) {
+            ("missing", Some(row), _) => {
+                let values = row
+                    .iter()
+                    .map(|cell| self.literal(cell))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    self.table_sql,
+                    self.columns
+                        .iter()
+                        .map(|c| quote_identifier(c, self.flavor))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    values.join(", ")
+                ))
+            }
+            ("changed", Some(row), Some(existing)) => {
+                let assignments = changed
+                    .iter()
+                    .map(|&i| {
+                        Ok(format!(
+                            "{} = {}",
+                            quote_identifier(&self.columns[i], self.flavor),
+                            self.literal(&row[i])?
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(format!(
+                    "UPDATE {} SET {} WHERE {}",
+                    self.table_sql,
+                    assignments.join(", "),
+                    self.where_clause(existing)?
+                ))
+            }
+            (_, _, Some(existing)) => Ok(format!(
+                "DELETE FROM {} WHERE {}",
+                self.table_sql,
+                self.where_clause(existing)?
+            )),
+            _ => Err(format!("Invalid row difference: {}", kind)),
+        }
+    }
+
+    fn record(
+        &mut self,
+        kind: &str,
+        source: Option<Vec<Cell>>,
+        target: Option<Vec<Cell>>,
+        changed: Vec<usize>,
+    ) -> Result<(), String> {
+        match kind {
+            "missing" => self.result.missing += 1,
+            "extra" => self.result.extra += 1,
+            _ => self.result.changed += 1,
+        }
+        self.progress.differences += 1;
+        if self.result.statements.len() < TABLE_COMPARE_MAX_STATEMENTS {
+            let statement = self.statement(kind, source.as_deref(), target.as_deref(), &changed)?;
+            self.result.statements.push(statement);
+        } else {
+            self.result.statements_truncated = true;
+        }
+        if self.result.samples.len() < TABLE_COMPARE_MAX_SAMPLES {
+            let row = source.as_deref().or(target.as_deref()).unwrap_or_default();
+            let key = self
+                .key_indices
+                .iter()
+                .map(|&i| (self.columns[i].clone(), display_value(&row[i])))
+                .collect();
+            self.result.samples.push(DataRowDifference {
+                kind: kind.to_string(),
+                key,
+                source: source.as_deref().map(|row| self.row_map(row)),
+                target: target.as_deref().map(|row| self.row_map(row)),
+                changed_columns: changed.iter().map(|&i| self.columns[i].clone()).collect(),
+            });
+        }
+        Ok(())
+    }
+
+    // 二进制值按字节比较，不会因预览相同而误判为未变化
+    fn compare(&mut self, source: Vec<Cell>, target: Vec<Cell>) -> Result<(), String> {
+        let changed: Vec<usize> = (0..self.columns.len())
+            .filter(|&i| source[i] != target[i])
+            .collect();
+        if changed.is_empty() {
+            self.result.unchanged += 1;
+            return Ok(());
+        }
+        self.record("changed", Some(source), Some(target), changed)
+    }
+
+    fn counted(&mut self, rows: u64) {
+        let before = self.progress.compared_rows / TABLE_COMPARE_PROGRESS_INTERVAL;
+        self.progress.compared_rows += rows;
+        if self.progress.compared_rows / TABLE_COMPARE_PROGRESS_INTERVAL != before {
+            self.emit();
+        }
+    }
+}
+
+// 读取下一行并检查键严格递增：键重复或数据库的排序与这里的比较不一致时无法归并
+async fn next_row(
+    stream: &mut RowStream<'_>,
+    last_key: &mut Option<Vec<Cell>>,
+    comparison: &Comparison<'_>,
+    side: &str,
+) -> Result<Option<(Vec<Cell>, Vec<Cell>)>, String> {
+    let Some(row) = stream
+        .try_next()
+        .await
+        .map_err(|e| format!("Failed to read {} rows: {}", side, e))?
+    else {
+        return Ok(None);
+    };
+    let key = comparison.key(&row);
+    if let Some(last) = last_key {
+        if compare_keys(&key, last) != Ordering::Greater {
+            return Err(format!(
+                "Key {} is duplicated or out of order in {}; key columns must uniquely identify rows",
+                Value::Array(key.iter().map(display_value).collect()),
+                side
+            ));
+        }
+    }
+    *last_key = Some(key.clone());
+    Ok(Some((key, row)))
+}
+
+// 两端按键有序，按归并方式逐行对比
+async fn merge(
+    comparison: &mut Comparison<'_>,
+    mut source: RowStream<'_>,
+    mut target: RowStream<'_>,
+) -> Result<(), String> {
+    let (mut source_key, mut target_key) = (None, None);
+    let mut left = next_row(&mut source, &mut source_key, comparison, "source").await?;
+    let mut right = next_row(&mut target, &mut target_key, comparison, "target").await?;
+    while !comparison.cancelled() {
+        let order = match (&left, &right) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((a, _)), Some((b, _))) => compare_keys(a, b),
+        };
+        match order {
+            Ordering::Less => {
+                let (_, row) = left.take().unwrap_or_default();
+                comparison.record("missing", Some(row), None, Vec::new())?;
+                comparison.counted(1);
+                left = next_row(&mut source, &mut source_key, comparison, "source").await?;
+            }
+            Ordering::Greater => {
+                let (_, row) = right.take().unwrap_or_default();
+                comparison.record("extra", None, Some(row), Vec::new())?;
+                comparison.counted(1);
+                right = next_row(&mut target, &mut target_key, comparison, "target").await?;
+            }
+            Ordering::Equal => {
+                let (_, before) = right.take().unwrap_or_default();
+                let (_, after) = left.take().unwrap_or_default();
+                comparison.compare(after, before)?;
+                comparison.counted(1);
+                left = next_row(&mut source, &mut source_key, comparison, "source").await?;
+                right = next_row(&mut target, &mut target_key, comparison, "target").await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// 在一个事务中执行同步语句，返回影响的行数
+async fn apply_statements(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    target: &SchemaTarget,
+    flavor: SqlFlavor,
+    statements: &[String],
+) -> Result<u64, String> {
+    let mut affected = 0;
+    match SideConnection::open(app_state, db_state, target, flavor).await? {
+        SideConnection::MySql(mut conn) => {
+            let mut tx = conn
+                .begin()
+                .await
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+            for statement in statements {
+                affected += sqlx::raw_sql(statement)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Statement failed: {}: {}", statement, e))?
+                    .rows_affected();
+            }
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        }
+        SideConnection::Sqlite(mut conn) => {
+            let mut tx = conn
+                .begin()
+                .await
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+            for statement in statements {
+                affected += sqlx::raw_sql(statement)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Statement failed: {}: {}", statement, e))?
+                    .rows_affected();
+            }
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        }
+    }
+    Ok(affected)
+}
+
+// 按 key_columns（为空时取源表主键）对比两端同名表的数据：两端按键排序后流式归并，报告只在源端、
+// 只在目标端和内容不同的行，并生成让目标与源一致的 INSERT / DELETE / UPDATE。apply 为 true 时在目标端
+// 的一个事务中执行（生产环境的 DELETE 需要确认令牌）。对比过程中每 10000 行发送 table-compare-progress
+// 事件，可通过 cancel_query(execution_id) 取消
+#[command]
+pub async fn compare_table_data(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    source: SchemaTarget,
+    target: SchemaTarget,
+    table: String,
+    key_columns: Vec<String>,
+    apply: Option<bool>,
+    confirm_token: Option<String>,
+    execution_id: Option<String>,
+) -> Result<TableDataComparison, String> {
+    let apply = apply == Some(true);
+    let source_flavor =
+        connection_flavor(&db_state, source.connection_id, "Data comparison").await?;
+    let target_flavor =
+        connection_flavor(&db_state, target.connection_id, "Data comparison").await?;
+    if apply {
+        ensure_writable(&db_state, target.connection_id).await?;
+    }
+    let source_structure =
+        load_structure(&app_state, &db_state, &source, source_flavor, &table).await?;
+    let target_structure =
+        load_structure(&app_state, &db_state, &target, target_flavor, &table).await?;
+
+    let key_columns = match key_columns.is_empty() {
+        true => primary_key(&source_structure)
+            .cloned()
+            .ok_or_else(|| format!("Table {} has no primary key; specify key columns", table))?,
+        false => key_columns,
+    };
+    // 生成列不能写入，只对比两端都有的普通列
+    let columns: Vec<String> = source_structure
+        .columns
+        .iter()
+        .filter(|column| !is_generated(column))
+        .filter(|column| {
+            target_structure
+                .columns
+                .iter()
+                .any(|c| c.name == column.name && !is_generated(c))
+        })
+        .map(|column| column.name.clone())
+        .collect();
+    if let Some(missing) = key_columns.iter().find(|k| !columns.contains(k)) {
+        return Err(format!(
+            "Key column {} does not exist on both sides",
+            missing
+        ));
+    }
+    let source_sql = select_sql(
+        &source,
+        &table,
+        &source_structure,
+        source_flavor,
+        &columns,
+        &key_columns,
+    );
+    let target_sql = select_sql(
+        &target,
+        &table,
+        &target_structure,
+        target_flavor,
+        &columns,
+        &key_columns,
+    );
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _source_permit =
+        acquire_query_slot(&app_state, source.connection_id, &execution_id).await?;
+    // 同一连接只占一个并发名额，避免连接只允许一个查询时互相等待
+    let _target_permit = match target.connection_id != source.connection_id {
+        true => Some(acquire_query_slot(&app_state, target.connection_id, &execution_id).await?),
+        false => None,
+    };
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(execution_id.clone(), cancel.clone());
+
+    let started = Instant::now();
+    let mut comparison = Comparison {
+        app_state: &app_state,
+        flavor: target_flavor,
+        table_sql: qualified_name(target.db_name.as_deref(), &table, target_flavor),
+        columns: columns.clone(),
+        key_indices: key_columns
+            .iter()
+            .filter_map(|k| columns.iter().position(|c| c == k))
+            .collect(),
+        cancel,
+        progress: TableCompareProgress {
+            execution_id: execution_id.clone(),
+            compared_rows: 0,
+            differences: 0,
+            done: false,
+        },
+        result: TableDataComparison {
+            execution_id: execution_id.clone(),
+            key_columns,
+            columns,
+            missing: 0,
+            extra: 0,
+            changed: 0,
+            unchanged: 0,
+            samples: Vec::new(),
+            statements: Vec::new(),
+            statements_truncated: false,
+            affected_rows: None,
+            cancelled: false,
+            duration_ms: 0,
+        },
+    };
+    let outcome = async {
+        let mut source_conn =
+            SideConnection::open(&app_state, &db_state, &source, source_flavor).await?;
+        let mut target_conn =
+            SideConnection::open(&app_state, &db_state, &target, target_flavor).await?;
+        let source_rows = cell_rows(&mut source_conn, &source_sql);
+        let target_rows = cell_rows(&mut target_conn, &target_sql);
+        merge(&mut comparison, source_rows, target_rows).await
+    }
+    .await;
+    app_state.cancel_flags.lock().await.remove(&execution_id);
+    comparison.progress.done = true;
+    comparison.emit();
+    outcome?;
+    let cancelled = comparison.cancelled();
+    let mut result = comparison.result;
+    result.cancelled = cancelled;
+
+    if apply && !cancelled && !result.statements.is_empty() {
+        if result.statements_truncated {
+            return Err(format!(
+                "More than {} rows differ; synchronize the table in smaller steps",
+                TABLE_COMPARE_MAX_STATEMENTS
+            ));
+        }
+        let script = result.statements.join(";\n");
+        ensure_sql_allowed(
+            &db_state,
+            target.connection_id,
+            &script,
+            confirm_token.as_deref(),
+            true,
+        )
+        .await?;
+        let applied_at = Instant::now();
+        let applied = apply_statements(
+            &app_state,
+            &db_state,
+            &target,
+            target_flavor,
+            &result.statements,
+        )
+        .await;
+        let elapsed = applied_at.elapsed();
+        record_query(&app_state, &db_state, target.connection_id, elapsed).await;
+        let outcome = HistoryOutcome::of(&applied, |affected| HistoryOutcome::Affected(*affected));
+        record_history(&db_state, target.connection_id, &script, elapsed, outcome).await;
+        result.affected_rows = Some(applied?);
+        invalidate_results(&app_state, target.connection_id).await;
+    }
+    result.duration_ms = started.elapsed().as_millis() as u64;
+    Ok(result)
+}
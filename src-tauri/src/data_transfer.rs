@@ -0,0 +1,575 @@
+use crate::alter_table::{qualified_name, quote_identifier};
+use crate::autocomplete::invalidate_metadata;
+use crate::data_sync::{load_structure, SideConnection};
+use crate::db::{connection_flavor, DbState};
+use crate::guard::{ensure_sql_allowed, ensure_writable};
+use crate::models::{
+    IndexInfo, SchemaTarget, TableColumn, TableStructure, TimestampDisplay, TransferArgs,
+};
+use crate::mysql_manager::BINARY_TYPES;
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::result_cache::invalidate_results;
+use crate::result_export::sql_literal;
+use crate::schema_diff::{create_index_sql, primary_key, secondary_indexes};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use futures_util::stream::BoxStream;
+use futures_util::{StreamExt, TryStreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::mysql::MySqlRow;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, Row, TypeInfo, ValueRef};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, Emitter, State};
+
+// 前端监听该事件展示传输进度
+pub const DATA_TRANSFER_PROGRESS_EVENT: &str = "data-transfer-progress";
+
+const DATA_TRANSFER_DEFAULT_BATCH_SIZE: usize = 1000;
+// 单条语句的参数个数上限
+const MYSQL_MAX_PARAMS: usize = 65_535;
+const SQLITE_MAX_PARAMS: usize = 32_766;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataTransferProgress {
+    pub execution_id: String,
+    pub table: String,
+    // 当前表的序号，从 1 开始
+    pub table_index: usize,
+    pub table_count: usize,
+    pub table_rows: u64,
+    pub total_rows: u64,
+    pub done: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableTransferResult {
+    pub table: String,
+    // 在目标端执行的 DROP / CREATE 语句，向已有表追加时为空
+    pub statements: Vec<String>,
+    pub rows_copied: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataTransferSummary {
+    pub execution_id: String,
+    pub tables: Vec<TableTransferResult>,
+    pub total_rows: u64,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+// 传输中的单元格：二进制列按字节读取和写入，其余按 JSON 值
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Json(Value),
+    Bytes(Vec<u8>),
+}
+
+fn mysql_cells(row: &MySqlRow) -> Vec<Cell> {
+    let json = mysql_manager::row_to_json(row, TimestampDisplay::Utc);
+    row.columns()
+        .iter()
+        .map(|column| {
+            if BINARY_TYPES.contains(&column.type_info().name()) {
+                if let Ok(Some(bytes)) = row.try_get::<Option<Vec<u8>>, _>(column.ordinal()) {
+                    return Cell::Bytes(bytes);
+                }
+            }
+            Cell::Json(json.get(column.name()).cloned().unwrap_or(Value::Null))
+        })
+        .collect()
+}
+
+// SQLite 按值的存储类型判断，BLOB 值不能经 JSON 转换
+fn sqlite_cells(row: &SqliteRow) -> Vec<Cell> {
+    let json = sqlite_manager::row_to_json(row);
+    row.columns()
+        .iter()
+        .map(|column| {
+            let is_blob = row
+                .try_get_raw(column.ordinal())
+                .is_ok_and(|value| !value.is_null() && value.type_info().name() == "BLOB");
+            if is_blob {
+                if let Ok(bytes) = row.try_get::<Vec<u8>, _>(column.ordinal()) {
+                    return Cell::Bytes(bytes);
+                }
+            }
+            Cell::Json(json.get(column.name()).cloned().unwrap_or(Value::Null))
+        })
+        .collect()
+}
+
+// 单元格的 SQL 字面量，二进制值写为 X'..'
+pub fn cell_literal(cell: &Cell, flavor: SqlFlavor) -> String {
+    match cell {
+        Cell::Json(value) => sql_literal(value, flavor),
+        Cell::Bytes(bytes) => format!(
+            "X'{}'",
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        ),
+    }
+}
+
+pub fn cell_rows<'a>(
+    conn: &'a mut SideConnection,
+    sql: &'a str,
+) -> BoxStream<'a, Result<Vec<Cell>, sqlx::Error>> {
+    match conn {
+        SideConnection::MySql(conn) => sqlx::query(sql)
+            .fetch(&mut **conn)
+            .map_ok(|row| mysql_cells(&row))
+            .boxed(),
+        SideConnection::Sqlite(conn) => sqlx::query(sql)
+            .fetch(&mut **conn)
+            .map_ok(|row| sqlite_cells(&row))
+            .boxed(),
+    }
+}
+
+// 多行 INSERT，参数按行依次绑定
+async fn insert_batch(
+    conn: &mut SideConnection,
+    insert_sql: &str,
+    column_count: usize,
+    rows: &[Vec<Cell>],
+) -> Result<(), String> {
+    let placeholders = format!("({})", vec!["?"; column_count].join(", "));
+    let sql = format!(
+        "{} VALUES {}",
+        insert_sql,
+        vec![placeholders; rows.len()].join(", ")
+    );
+    let result = match conn {
+        SideConnection::MySql(conn) => {
+            let mut query = sqlx::query(&sql);
+            for cell in rows.iter().flatten() {
+                query = match cell {
+                    Cell::Bytes(bytes) => query.bind(bytes.clone()),
+                    Cell::Json(value) => mysql_manager::bind_json_value(query, value),
+                };
+            }
+            query.execute(&mut **conn).await.map(|_| ())
+        }
+        SideConnection::Sqlite(conn) => {
+            let mut query = sqlx::query(&sql);
+            for cell in rows.iter().flatten() {
+                query = match cell {
+                    Cell::Bytes(bytes) => query.bind(bytes.clone()),
+                    Cell::Json(value) => sqlite_manager::bind_json_value(query, value),
+                };
+            }
+            query.execute(&mut **conn).await.map(|_| ())
+        }
+    };
+    result.map_err(|e| format!("Failed to insert rows: {}", e))
+}
+
+async fn table_exists(
+    conn: &mut SideConnection,
+    db_name: Option<&str>,
+    table: &str,
+) -> Result<bool, String> {
+    let count = match conn {
+        SideConnection::MySql(conn) => {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM information_schema.TABLES \
+                 WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?",
+            )
+            .bind(db_name)
+            .bind(table)
+            .fetch_one(&mut **conn)
+            .await
+        }
+        SideConnection::Sqlite(conn) => {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+            )
+            .bind(table)
+            .fetch_one(&mut **conn)
+            .await
+        }
+    };
+    count
+        .map(|count| count > 0)
+        .map_err(|e| format!("Failed to check table {}: {}", table, e))
+}
+
+async fn execute_statement(conn: &mut SideConnection, statement: &str) -> Result<(), String> {
+    let result = match conn {
+        SideConnection::MySql(conn) => sqlx::raw_sql(statement)
+            .execute(&mut **conn)
+            .await
+            .map(|_| ()),
+        SideConnection::Sqlite(conn) => sqlx::raw_sql(statement)
+            .execute(&mut **conn)
+            .await
+            .map(|_| ()),
+    };
+    result.map_err(|e| format!("Statement failed: {}: {}", statement, e))
+}
+
+// SQLite 的声明类型按亲和性规则换成 MySQL 类型；作为键的文本 / 二进制列需要限定长度
+fn sqlite_to_mysql_type(declared: &str, keyed: bool) -> String {
+    let lower = declared.to_ascii_lowercase();
+    let sized = lower.starts_with("varchar(") || lower.starts_with("char(");
+    if lower.contains("bool") {
+        "TINYINT(1)".to_string()
+    } else if lower.contains("int") {
+        "BIGINT".to_string()
+    } else if lower.contains("char") || lower.contains("clob") || lower.contains("text") {
+        match (sized, keyed) {
+            (true, _) => declared.to_ascii_uppercase(),
+            (false, true) => "VARCHAR(255)".to_string(),
+            (false, false) => "LONGTEXT".to_string(),
+        }
+    } else if lower.is_empty() || lower.contains("blob") {
+        if keyed { "VARBINARY(255)" } else { "LONGBLOB" }.to_string()
+    } else if lower.contains("real") || lower.contains("floa") || lower.contains("doub") {
+        "DOUBLE".to_string()
+    } else if lower.starts_with("datetime") || lower.starts_with("timestamp") {
+        "DATETIME".to_string()
+    } else if lower.starts_with("date") {
+        "DATE".to_string()
+    } else if lower.starts_with("time") {
+        "TIME".to_string()
+    } else if lower.starts_with("decimal(") || lower.starts_with("numeric(") {
+        declared.to_ascii_uppercase()
+    } else {
+        "DECIMAL(38, 10)".to_string()
+    }
+}
+
+fn mysql_to_sqlite_type(declared: &str) -> &'static str {
+    let base = declared
+        .to_ascii_lowercase()
+        .split(['(', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    match base.as_str() {
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "year" | "bit"
+        | "bool" | "boolean" => "INTEGER",
+        "float" | "double" | "real" => "REAL",
+        "decimal" | "numeric" => "NUMERIC",
+        "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => "BLOB",
+        _ => "TEXT",
+    }
+}
+
+fn target_type(column: &TableColumn, from: SqlFlavor, to: SqlFlavor, keyed: bool) -> String {
+    match (from, to) {
+        (SqlFlavor::MySql, SqlFlavor::Sqlite) => {
+            mysql_to_sqlite_type(&column.column_type).to_string()
+        }
+        (SqlFlavor::Sqlite, SqlFlavor::MySql) => sqlite_to_mysql_type(&column.column_type, keyed),
+        _ => column.column_type.clone(),
+    }
+}
+
+// 按源表结构生成目标端的建表和建索引语句：只保留列类型、非空、自增主键和普通 / 唯一索引，
+// 默认值、注释、外键和 CHECK 约束不迁移
+fn create_statements(
+    table: &str,
+    structure: &TableStructure,
+    from: SqlFlavor,
+    target: &SchemaTarget,
+    to: SqlFlavor,
+) -> Vec<String> {
+    let table_sql = qualified_name(target.db_name.as_deref(), table, to);
+    let key = primary_key(structure).cloned().unwrap_or_default();
+    let indexes = secondary_indexes(structure);
+    let auto_increment = structure
+        .columns
+        .iter()
+        .find(|c| {
+            key.len() == 1
+                && key[0] == c.name
+                && c.extra
+                    .as_deref()
+                    .is_some_and(|e| e.contains("auto_increment"))
+        })
+        .map(|c| c.name.clone());
+    let mut definitions = Vec::new();
+    for column in &structure.columns {
+        let keyed = key.contains(&column.name)
+            || indexes
+                .values()
+                .any(|index| index.columns.contains(&Some(column.name.clone())));
+        let name = quote_identifier(&column.name, to);
+        let mut definition = format!("{} {}", name, target_type(column, from, to, keyed));
+        // SQLite 的 INTEGER PRIMARY KEY 即 rowid，自动分配
+        if auto_increment.as_ref() == Some(&column.name) {
+            definition = match to {
+                SqlFlavor::MySql => format!("{} NOT NULL AUTO_INCREMENT", definition),
+                SqlFlavor::Sqlite => format!("{} INTEGER PRIMARY KEY", name),
+            };
+        } else if !column.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        definitions.push(definition);
+    }
+    let inline_key = auto_increment.is_some() && matches!(to, SqlFlavor::Sqlite);
+    if !key.is_empty() && !inline_key {
+        definitions.push(format!(
+            "PRIMARY KEY ({})",
+            key.iter()
+                .map(|c| quote_identifier(c, to))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let mut statements = vec![format!(
+        "CREATE TABLE {} (\n  {}\n)",
+        table_sql,
+        definitions.join(",\n  ")
+    )];
+    for index in indexes.values() {
+        // SQLite 的索引名在库内唯一，MySQL 只在表内唯一：迁到 SQLite 时加上表名前缀
+        let name = match (from, to) {
+            (SqlFlavor::MySql, SqlFlavor::Sqlite) => format!("{}_{}", table, index.name),
+            _ => index.name.clone(),
+        };
+        let index = IndexInfo {
+            name,
+            columns: index.columns.clone(),
+            unique: index.unique,
+            primary: false,
+            index_type: index.index_type.clone(),
+        };
+        if let Some(statement) = create_index_sql(&index, &table_sql, to) {
+            statements.push(statement);
+        }
+    }
+    statements
+}
+
+struct Transfer<'a> {
+    app_state: &'a AppState,
+    cancel: Arc<AtomicBool>,
+    progress: DataTransferProgress,
+}
+
+impl Transfer<'_> {
+    fn emit(&self) {
+        if let Some(app) = self.app_state.app_handle.as_ref() {
+            let _ = app.emit(DATA_TRANSFER_PROGRESS_EVENT, self.progress.clone());
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+// 流式读取源表并分批写入目标表，返回写入的行数
+async fn copy_rows(
+    transfer: &mut Transfer<'_>,
+    source_conn: &mut SideConnection,
+    target_conn: &mut SideConnection,
+    select_sql: &str,
+    insert_sql: &str,
+    column_count: usize,
+    batch_size: usize,
+) -> Result<u64, String> {
+    let mut rows = cell_rows(source_conn, select_sql);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut copied = 0;
+    while !transfer.cancelled() {
+        let row = rows
+            .try_next()
+            .await
+            .map_err(|e| format!("Failed to read source rows: {}", e))?;
+        let finished = row.is_none();
+        batch.extend(row);
+        if batch.len() >= batch_size || (finished && !batch.is_empty()) {
+            insert_batch(target_conn, insert_sql, column_count, &batch).await?;
+            copied += batch.len() as u64;
+            transfer.progress.table_rows += batch.len() as u64;
+            transfer.progress.total_rows += batch.len() as u64;
+            batch.clear();
+            transfer.emit();
+        }
+        if finished {
+            break;
+        }
+    }
+    Ok(copied)
+}
+
+// 在任意两个 MySQL 系 / SQLite 连接之间复制表数据。目标端没有的表按源表结构建表（跨引擎时转换列类型），
+// drop_existing 为 true 时先删除目标端的同名表（生产环境需要确认令牌），否则向已有表追加。
+// 每批写入后发送 data-transfer-progress 事件，可通过 cancel_query(execution_id) 在批次之间取消
+#[command]
+pub async fn transfer_data(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    args: TransferArgs,
+    confirm_token: Option<String>,
+    execution_id: Option<String>,
+) -> Result<DataTransferSummary, String> {
+    let (source, target) = (&args.source, &args.target);
+    if args.tables.is_empty() {
+        return Err("No tables selected".to_string());
+    }
+    if source.connection_id == target.connection_id && source.db_name == target.db_name {
+        return Err("Source and target are the same database".to_string());
+    }
+    let from = connection_flavor(&db_state, source.connection_id, "Data transfer").await?;
+    let to = connection_flavor(&db_state, target.connection_id, "Data transfer").await?;
+    ensure_writable(&db_state, target.connection_id).await?;
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _source_permit =
+        acquire_query_slot(&app_state, source.connection_id, &execution_id).await?;
+    let _target_permit = match target.connection_id != source.connection_id {
+        true => Some(acquire_query_slot(&app_state, target.connection_id, &execution_id).await?),
+        false => None,
+    };
+    let mut source_conn = SideConnection::open(&app_state, &db_state, source, from).await?;
+    let mut target_conn = SideConnection::open(&app_state, &db_state, target, to).await?;
+
+    // 先确认要删除的表，生产环境的确认令牌与全部 DROP 语句绑定
+    let mut existing = Vec::new();
+    for table in &args.tables {
+        existing.push(table_exists(&mut target_conn, target.db_name.as_deref(), table).await?);
+    }
+    let drops: Vec<String> = args
+        .tables
+        .iter()
+        .zip(&existing)
+        .filter(|(_, exists)| args.drop_existing && **exists)
+        .map(|(table, _)| {
+            format!(
+                "DROP TABLE {}",
+                qualified_name(target.db_name.as_deref(), table, to)
+            )
+        })
+        .collect();
+    if !drops.is_empty() {
+        ensure_sql_allowed(
+            &db_state,
+            target.connection_id,
+            &drops.join(";\n"),
+            confirm_token.as_deref(),
+            true,
+        )
+        .await?;
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(execution_id.clone(), cancel.clone());
+    let started = Instant::now();
+    let mut transfer = Transfer {
+        app_state: &app_state,
+        cancel,
+        progress: DataTransferProgress {
+            execution_id: execution_id.clone(),
+            table: String::new(),
+            table_index: 0,
+            table_count: args.tables.len(),
+            table_rows: 0,
+            total_rows: 0,
+            done: false,
+        },
+    };
+    let mut results = Vec::new();
+    let outcome: Result<(), String> = async {
+        for (index, (table, exists)) in args.tables.iter().zip(&existing).enumerate() {
+            if transfer.cancelled() {
+                break;
+            }
+            transfer.progress.table = table.clone();
+            transfer.progress.table_index = index + 1;
+            transfer.progress.table_rows = 0;
+            transfer.emit();
+
+            let structure = load_structure(&app_state, &db_state, source, from, table).await?;
+            let columns: Vec<&str> = structure.columns.iter().map(|c| c.name.as_str()).collect();
+            let mut statements = Vec::new();
+            if args.drop_existing && *exists {
+                statements.push(format!(
+                    "DROP TABLE {}",
+                    qualified_name(target.db_name.as_deref(), table, to)
+                ));
+            }
+            if args.drop_existing || !*exists {
+                statements.extend(create_statements(table, &structure, from, target, to));
+            }
+            for statement in &statements {
+                execute_statement(&mut target_conn, statement).await?;
+            }
+
+            let column_count = columns.len();
+            let max_params = match to {
+                SqlFlavor::MySql => MYSQL_MAX_PARAMS,
+                SqlFlavor::Sqlite => SQLITE_MAX_PARAMS,
+            };
+            let batch_size = args
+                .batch_size
+                .unwrap_or(DATA_TRANSFER_DEFAULT_BATCH_SIZE)
+                .clamp(1, (max_params / column_count.max(1)).max(1));
+            let select_sql = format!(
+                "SELECT {} FROM {}",
+                columns
+                    .iter()
+                    .map(|c| quote_identifier(c, from))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                qualified_name(source.db_name.as_deref(), table, from)
+            );
+            let insert_sql = format!(
+                "INSERT INTO {} ({})",
+                qualified_name(target.db_name.as_deref(), table, to),
+                columns
+                    .iter()
+                    .map(|c| quote_identifier(c, to))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            let rows_copied = copy_rows(
+                &mut transfer,
+                &mut source_conn,
+                &mut target_conn,
+                &select_sql,
+                &insert_sql,
+                column_count,
+                batch_size,
+            )
+            .await?;
+            results.push(TableTransferResult {
+                table: table.clone(),
+                statements,
+                rows_copied,
+            });
+        }
+        Ok(())
+    }
+    .await;
+
+    app_state.cancel_flags.lock().await.remove(&execution_id);
+    invalidate_results(&app_state, target.connection_id).await;
+    invalidate_metadata(&app_state, target.connection_id).await;
+    transfer.progress.done = true;
+    transfer.emit();
+    outcome?;
+
+    Ok(DataTransferSummary {
+        execution_id,
+        tables: results,
+        total_rows: transfer.progress.total_rows,
+        cancelled: transfer.cancelled(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
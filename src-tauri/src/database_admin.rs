@@ -0,0 +1,131 @@
+use crate::alter_table::{apply_ddl, quote_identifier};
+use crate::create_table::option_name;
+use crate::db::{connection_flavor, DbState};
+use crate::models::{CreateDatabaseArgs, DdlStatementResult};
+use crate::mysql_manager;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use tauri::{command, State};
+
+// 服务端自带的系统库，不允许删除
+const SYSTEM_DATABASES: &[&str] = &[
+    "mysql",
+    "information_schema",
+    "performance_schema",
+    "sys",
+    "metrics_schema",
+];
+
+// 目前只有 MySQL 协议的数据库支持建库 / 删库
+async fn ensure_mysql(db_state: &DbState, connection_id: i64) -> Result<(), String> {
+    match connection_flavor(db_state, connection_id, "Managing databases").await? {
+        SqlFlavor::MySql => Ok(()),
+        SqlFlavor::Sqlite => Err("Managing databases is not supported for sqlite".to_string()),
+    }
+}
+
+fn database_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Database name is required".to_string());
+    }
+    Ok(name)
+}
+
+// 未确认时只返回 CREATE DATABASE 语句供预览，confirmed 为 true 时执行
+#[command]
+pub async fn create_database(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: CreateDatabaseArgs,
+    confirmed: Option<bool>,
+) -> Result<DdlStatementResult, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let name = database_name(&args.name)?;
+    let mut statement = format!(
+        "CREATE DATABASE {}{}",
+        if args.if_not_exists {
+            "IF NOT EXISTS "
+        } else {
+            ""
+        },
+        quote_identifier(name, SqlFlavor::MySql)
+    );
+    if let Some(charset) = &args.charset {
+        statement.push_str(&format!(
+            " CHARACTER SET {}",
+            option_name("charset", charset)?
+        ));
+    }
+    if let Some(collation) = &args.collation {
+        statement.push_str(&format!(
+            " COLLATE {}",
+            option_name("collation", collation)?
+        ));
+    }
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        SqlFlavor::MySql,
+        None,
+        std::slice::from_ref(&statement),
+        None,
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
+
+// 删除整个库：未确认时只返回语句；生产环境还需要确认令牌。删除后关闭该库的连接池
+#[command]
+pub async fn drop_database(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let name = database_name(&name)?;
+    if SYSTEM_DATABASES
+        .iter()
+        .any(|system| system.eq_ignore_ascii_case(name))
+    {
+        return Err(format!("System database {} cannot be dropped", name));
+    }
+    let statement = format!("DROP DATABASE {}", quote_identifier(name, SqlFlavor::MySql));
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        SqlFlavor::MySql,
+        None,
+        std::slice::from_ref(&statement),
+        confirm_token.as_deref(),
+    )
+    .await?;
+    mysql_manager::release_database_pool(&app_state, connection_id, name).await;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
@@ -0,0 +1,452 @@
+use crate::alter_table::{qualified_name, quote_identifier};
+use crate::db::{connection_db_type, DbState};
+use crate::models::{DatabaseInfo, TableInfo, TableStats};
+use crate::query_queue::{
+    acquire_query_slot, finish_running_query, new_execution_id, register_running_query,
+    RunningQuery,
+};
+use crate::sql_classifier::SqlFlavor;
+use crate::sqlite_manager::SqliteInterruptHandle;
+use crate::state::AppState;
+use crate::{mongo_manager, mysql_manager, redis_manager, sqlite_manager};
+use sqlx::pool::PoolConnection;
+use sqlx::{MySql, Row, Sqlite};
+use std::collections::HashMap;
+use tauri::{command, State};
+
+// 汇总 information_schema.TABLES 得到每个库的数据 + 索引大小，没有表的库为 NULL
+const MYSQL_DATABASE_SIZES_SQL: &str = "SELECT CAST(s.SCHEMA_NAME AS CHAR) AS name, \
+     CAST(SUM(t.DATA_LENGTH + t.INDEX_LENGTH) AS UNSIGNED) AS size_bytes \
+     FROM information_schema.SCHEMATA s \
+     LEFT JOIN information_schema.TABLES t ON t.TABLE_SCHEMA = s.SCHEMA_NAME \
+     GROUP BY s.SCHEMA_NAME ORDER BY s.SCHEMA_NAME";
+
+async fn list_mysql_databases(
+    mut conn: PoolConnection<MySql>,
+) -> Result<Vec<DatabaseInfo>, String> {
+    match sqlx::query_as::<_, (String, Option<u64>)>(MYSQL_DATABASE_SIZES_SQL)
+        .fetch_all(&mut *conn)
+        .await
+    {
+        Ok(rows) => Ok(rows
+            .into_iter()
+            .map(|(name, size_bytes)| DatabaseInfo {
+                name,
+                size_bytes,
+                key_count: None,
+            })
+            .collect()),
+        // 权限受限或 information_schema 不可用时退回 SHOW DATABASES，只返回名称
+        Err(_) => {
+            let rows = sqlx::query("SHOW DATABASES")
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to list databases: {}", e))?;
+            // information_schema 等库名在部分版本中以 VARBINARY 返回
+            Ok(rows
+                .iter()
+                .filter_map(|row| {
+                    row.try_get::<String, _>(0).ok().or_else(|| {
+                        row.try_get::<Vec<u8>, _>(0)
+                            .ok()
+                            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+                    })
+                })
+                .map(|name| DatabaseInfo {
+                    name,
+                    size_bytes: None,
+                    key_count: None,
+                })
+                .collect())
+        }
+    }
+}
+
+// main、temp 以及 ATTACH 的库；大小为 page_count * page_size
+async fn list_sqlite_databases(
+    mut conn: PoolConnection<Sqlite>,
+) -> Result<Vec<DatabaseInfo>, String> {
+    let names =
+        sqlx::query_scalar::<_, String>("SELECT name FROM pragma_database_list ORDER BY seq")
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to list databases: {}", e))?;
+    let mut databases = Vec::with_capacity(names.len());
+    for name in names {
+        let schema = format!("\"{}\"", name.replace('"', "\"\""));
+        let page_count = sqlx::query_scalar::<_, i64>(&format!("PRAGMA {}.page_count", schema))
+            .fetch_one(&mut *conn)
+            .await;
+        let page_size = sqlx::query_scalar::<_, i64>(&format!("PRAGMA {}.page_size", schema))
+            .fetch_one(&mut *conn)
+            .await;
+        let size_bytes = match (page_count, page_size) {
+            (Ok(count), Ok(size)) => u64::try_from(count * size).ok(),
+            _ => None,
+        };
+        databases.push(DatabaseInfo {
+            name,
+            size_bytes,
+            key_count: None,
+        });
+    }
+    Ok(databases)
+}
+
+const MYSQL_TABLES_SQL: &str = "SELECT CAST(TABLE_NAME AS CHAR), CAST(TABLE_TYPE AS CHAR), \
+     CAST(ENGINE AS CHAR), CAST(TABLE_ROWS AS UNSIGNED), CAST(DATA_LENGTH AS UNSIGNED), \
+     CAST(TABLE_COMMENT AS CHAR) \
+     FROM information_schema.TABLES WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) \
+     ORDER BY TABLE_NAME";
+
+async fn list_mysql_tables(
+    mut conn: PoolConnection<MySql>,
+    database: Option<&str>,
+) -> Result<Vec<TableInfo>, String> {
+    let rows = sqlx::query(MYSQL_TABLES_SQL)
+        .bind(database)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to list tables: {}", e))?;
+    rows.iter()
+        .map(|row| {
+            let table_type: String = row.try_get_unchecked(1).map_err(|e| e.to_string())?;
+            let is_view = table_type.contains("VIEW");
+            // 视图的 TABLE_COMMENT 固定为 "VIEW"，不作为注释返回
+            let comment: Option<String> = row.try_get_unchecked(5).map_err(|e| e.to_string())?;
+            Ok(TableInfo {
+                name: row.try_get_unchecked(0).map_err(|e| e.to_string())?,
+                table_type: if is_view { "view" } else { "table" }.to_string(),
+                engine: row.try_get_unchecked(2).map_err(|e| e.to_string())?,
+                row_estimate: row.try_get_unchecked(3).map_err(|e| e.to_string())?,
+                data_size_bytes: row.try_get_unchecked(4).map_err(|e| e.to_string())?,
+                comment: comment.filter(|c| !c.is_empty() && !is_view),
+            })
+        })
+        .collect()
+}
+
+// sqlite_stat1 的 stat 列以表的行数开头，需要执行过 ANALYZE 才有数据
+async fn sqlite_row_estimates(
+    conn: &mut PoolConnection<Sqlite>,
+    schema: &str,
+) -> HashMap<String, u64> {
+    let sql = format!("SELECT tbl, stat FROM {}.sqlite_stat1", schema);
+    let rows = sqlx::query_as::<_, (String, String)>(&sql)
+        .fetch_all(&mut **conn)
+        .await
+        .unwrap_or_default();
+    let mut estimates = HashMap::new();
+    for (table, stat) in rows {
+        let Some(count) = stat
+            .split_whitespace()
+            .next()
+            .and_then(|n| n.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let entry = estimates.entry(table).or_insert(0);
+        *entry = (*entry).max(count);
+    }
+    estimates
+}
+
+// dbstat 虚拟表按 b-tree 统计页大小；编译时未启用 dbstat 时返回空
+async fn sqlite_table_sizes(
+    conn: &mut PoolConnection<Sqlite>,
+    schema: &str,
+) -> HashMap<String, u64> {
+    sqlx::query_as::<_, (String, i64)>(
+        "SELECT name, SUM(pgsize) FROM dbstat WHERE schema = ? GROUP BY name",
+    )
+    .bind(schema)
+    .fetch_all(&mut **conn)
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|(name, size)| Some((name, u64::try_from(size).ok()?)))
+    .collect()
+}
+
+async fn list_sqlite_tables(
+    mut conn: PoolConnection<Sqlite>,
+    schema: &str,
+) -> Result<Vec<TableInfo>, String> {
+    let quoted = format!("\"{}\"", schema.replace('"', "\"\""));
+    let objects = sqlx::query_as::<_, (String, String)>(&format!(
+        "SELECT name, type FROM {}.sqlite_master \
+         WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        quoted
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to list tables: {}", e))?;
+    let estimates = sqlite_row_estimates(&mut conn, &quoted).await;
+    let sizes = sqlite_table_sizes(&mut conn, schema).await;
+    Ok(objects
+        .into_iter()
+        .map(|(name, kind)| TableInfo {
+            row_estimate: estimates.get(&name).copied(),
+            data_size_bytes: sizes.get(&name).copied(),
+            name,
+            table_type: kind,
+            engine: None,
+            comment: None,
+        })
+        .collect())
+}
+
+// 列出库中的表和视图及其引擎、估算行数、数据大小和注释，供对象浏览器使用。
+// database 为空时 MySQL 使用连接当前的默认库，SQLite 使用 main
+#[command]
+pub async fn list_tables(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    database: Option<String>,
+) -> Result<Vec<TableInfo>, String> {
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    let database = database.filter(|db| !db.trim().is_empty());
+    match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            let conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                database.clone(),
+            )
+            .await?;
+            list_mysql_tables(conn, database.as_deref()).await
+        }
+        "sqlite" => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            list_sqlite_tables(conn, database.as_deref().unwrap_or("main")).await
+        }
+        other => Err(format!("Listing tables is not supported for {}", other)),
+    }
+}
+
+const MYSQL_TABLE_STATS_SQL: &str = "SELECT CAST(ENGINE AS CHAR), CAST(TABLE_ROWS AS UNSIGNED), \
+     CAST(DATA_LENGTH AS UNSIGNED), CAST(INDEX_LENGTH AS UNSIGNED), CAST(DATA_FREE AS UNSIGNED), \
+     CAST(AUTO_INCREMENT AS UNSIGNED), CAST(CREATE_TIME AS CHAR), CAST(UPDATE_TIME AS CHAR) \
+     FROM information_schema.TABLES \
+     WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?";
+
+async fn mysql_table_stats(
+    conn: &mut PoolConnection<MySql>,
+    database: Option<&str>,
+    table: &str,
+) -> Result<TableStats, String> {
+    // MySQL 8.0 默认缓存 information_schema 中的统计值（最长一天），读取时临时关闭缓存；
+    // 其他版本没有该变量
+    let no_expiry = sqlx::query("SET SESSION information_schema_stats_expiry = 0")
+        .execute(&mut **conn)
+        .await
+        .is_ok();
+    let row = sqlx::query(MYSQL_TABLE_STATS_SQL)
+        .bind(database)
+        .bind(table)
+        .fetch_optional(&mut **conn)
+        .await;
+    if no_expiry {
+        let _ = sqlx::query("SET SESSION information_schema_stats_expiry = DEFAULT")
+            .execute(&mut **conn)
+            .await;
+    }
+    let row = row
+        .map_err(|e| format!("Failed to fetch table stats: {}", e))?
+        .ok_or_else(|| format!("Table {} not found", table))?;
+    Ok(TableStats {
+        name: table.to_string(),
+        engine: row.try_get_unchecked(0).map_err(|e| e.to_string())?,
+        row_estimate: row.try_get_unchecked(1).map_err(|e| e.to_string())?,
+        data_size_bytes: row.try_get_unchecked(2).map_err(|e| e.to_string())?,
+        index_size_bytes: row.try_get_unchecked(3).map_err(|e| e.to_string())?,
+        free_bytes: row.try_get_unchecked(4).map_err(|e| e.to_string())?,
+        exact_rows: None,
+        auto_increment: row.try_get_unchecked(5).map_err(|e| e.to_string())?,
+        created_at: row.try_get_unchecked(6).map_err(|e| e.to_string())?,
+        // InnoDB 在重启后或表未修改过时为 NULL
+        updated_at: row.try_get_unchecked(7).map_err(|e| e.to_string())?,
+    })
+}
+
+async fn sqlite_table_stats(
+    conn: &mut PoolConnection<Sqlite>,
+    schema: &str,
+    table: &str,
+) -> Result<TableStats, String> {
+    let quoted = quote_identifier(schema, SqlFlavor::Sqlite);
+    let exists = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT COUNT(*) FROM {}.sqlite_master WHERE type = 'table' AND name = ?",
+        quoted
+    ))
+    .bind(table)
+    .fetch_one(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch table stats: {}", e))?;
+    if exists == 0 {
+        return Err(format!("Table {} not found", table));
+    }
+    // 表本身和属于该表的索引（含主键 / UNIQUE 自动创建的索引）分别汇总页大小
+    let sizes = sqlx::query_as::<_, (String, i64)>(&format!(
+        "SELECT m.type, SUM(d.pgsize) FROM {}.sqlite_master m JOIN dbstat(?) d ON d.name = m.name \
+         WHERE m.tbl_name = ? AND m.type IN ('table', 'index') GROUP BY m.type",
+        quoted
+    ))
+    .bind(schema)
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await;
+    let size_of = |kind: &str| {
+        sizes.as_ref().ok().map(|rows| {
+            rows.iter()
+                .find(|(t, _)| t == kind)
+                .and_then(|(_, size)| u64::try_from(*size).ok())
+                .unwrap_or(0)
+        })
+    };
+    // sqlite_sequence 保存已分配的最大值，只有 AUTOINCREMENT 表插入过数据后才有记录
+    let sequence = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT seq FROM {}.sqlite_sequence WHERE name = ?",
+        quoted
+    ))
+    .bind(table)
+    .fetch_optional(&mut **conn)
+    .await
+    .ok()
+    .flatten();
+    let estimates = sqlite_row_estimates(conn, &quoted).await;
+    Ok(TableStats {
+        name: table.to_string(),
+        engine: None,
+        data_size_bytes: size_of("table"),
+        index_size_bytes: size_of("index"),
+        free_bytes: None,
+        row_estimate: estimates.get(table).copied(),
+        exact_rows: None,
+        auto_increment: sequence.and_then(|seq| u64::try_from(seq + 1).ok()),
+        created_at: None,
+        updated_at: None,
+    })
+}
+
+// 返回单张表的数据 / 索引大小、估算行数、自增值和创建 / 更新时间，供对象浏览器的表详情使用。
+// exact 为 true 时再执行 COUNT(*) 得到准确行数，可通过 execution_id 取消
+#[command]
+pub async fn get_table_stats(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    database: Option<String>,
+    exact: Option<bool>,
+    execution_id: Option<String>,
+) -> Result<TableStats, String> {
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    let database = database.filter(|db| !db.trim().is_empty());
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let permit = match exact.unwrap_or(false) {
+        true => Some(acquire_query_slot(&app_state, connection_id, &execution_id).await?),
+        false => None,
+    };
+    let (mut stats, count) = match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                database.clone(),
+            )
+            .await?;
+            let stats = mysql_table_stats(&mut conn, database.as_deref(), &table).await?;
+            if permit.is_none() {
+                return Ok(stats);
+            }
+            mysql_manager::register_mysql_query(
+                &app_state,
+                &mut conn,
+                &execution_id,
+                connection_id,
+                database.clone(),
+            )
+            .await;
+            let count_sql = format!(
+                "SELECT COUNT(*) FROM {}",
+                qualified_name(database.as_deref(), &table, SqlFlavor::MySql)
+            );
+            let count = sqlx::query_scalar::<_, i64>(&count_sql)
+                .fetch_one(&mut *conn)
+                .await;
+            (stats, count)
+        }
+        "sqlite" => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            let schema = database.as_deref().unwrap_or("main");
+            let stats = sqlite_table_stats(&mut conn, schema, &table).await?;
+            if permit.is_none() {
+                return Ok(stats);
+            }
+            let handle = SqliteInterruptHandle::of(&mut conn).await?;
+            register_running_query(&app_state, &execution_id, RunningQuery::Sqlite(handle)).await;
+            let count_sql = format!(
+                "SELECT COUNT(*) FROM {}.{}",
+                quote_identifier(schema, SqlFlavor::Sqlite),
+                quote_identifier(&table, SqlFlavor::Sqlite)
+            );
+            let count = sqlx::query_scalar::<_, i64>(&count_sql)
+                .fetch_one(&mut *conn)
+                .await;
+            (stats, count)
+        }
+        other => return Err(format!("Table stats are not supported for {}", other)),
+    };
+    finish_running_query(&app_state, &execution_id).await;
+    let count = count.map_err(|e| format!("Failed to count rows: {}", e))?;
+    stats.exact_rows = u64::try_from(count).ok();
+    Ok(stats)
+}
+
+// 列出连接下的数据库（MySQL 库、SQLite 的 main/附加库、Redis 逻辑库、MongoDB 库），供侧边栏树使用。
+// Memcached 没有数据库的概念，不支持
+#[command]
+pub async fn list_databases(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<DatabaseInfo>, String> {
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            let conn =
+                mysql_manager::acquire_connection(&app_state, &db_state, connection_id, None)
+                    .await?;
+            list_mysql_databases(conn).await
+        }
+        "sqlite" => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            list_sqlite_databases(conn).await
+        }
+        "redis" => redis_manager::list_redis_databases(&app_state, &db_state, connection_id).await,
+        "mongodb" => {
+            mongo_manager::list_mongo_database_sizes(&app_state, &db_state, connection_id).await
+        }
+        "memcached" => Err("Memcached has no databases to list".to_string()),
+        other => Err(format!("Listing databases is not supported for {}", other)),
+    }
+}
@@ -0,0 +1,455 @@
+use crate::alter_table::quote_identifier;
+use crate::db::{connection_db_type, DbState};
+use crate::models::{DatabaseSearchOptions, TimestampDisplay};
+use crate::mysql_manager::{
+    acquire_connection, register_mysql_query, row_to_json as mysql_row_to_json,
+    timestamp_display_of,
+};
+use crate::query_queue::{
+    finish_running_query, new_execution_id, register_running_query, RunningQuery,
+};
+use crate::sql_classifier::SqlFlavor;
+use crate::sqlite_manager::{
+    get_or_create_pool, row_to_json as sqlite_row_to_json, SqliteInterruptHandle,
+};
+use crate::state::AppState;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::pool::PoolConnection;
+use sqlx::{MySql, Row, Sqlite};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+pub const DATABASE_SEARCH_EVENT: &str = "database-search";
+
+const SEARCH_DEFAULT_LIMIT_PER_TABLE: u32 = 10;
+const SEARCH_MAX_LIMIT_PER_TABLE: u32 = 1_000;
+const SEARCH_DEFAULT_COLUMN_TYPES: &[&str] = &["text", "json"];
+
+// 一张表中的匹配：columns 为包含搜索词的列
+#[derive(Debug, Clone, Serialize)]
+pub struct TableMatch {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseSearchEvent {
+    pub search_id: String,
+    // "running" / "done" / "cancelled" / "failed"
+    pub status: String,
+    pub tables_scanned: usize,
+    pub total_tables: usize,
+    // 刚扫描完的表中的匹配行，没有匹配时为空
+    pub matched: Option<TableMatch>,
+    // running 时为该表的查询错误（跳过该表继续），failed 时为整个搜索的错误
+    pub error: Option<String>,
+}
+
+struct SearchTable {
+    name: String,
+    columns: Vec<String>,
+}
+
+enum SearchConnection {
+    MySql(PoolConnection<MySql>, TimestampDisplay),
+    Sqlite(PoolConnection<Sqlite>),
+}
+
+impl SearchConnection {
+    fn flavor(&self) -> SqlFlavor {
+        match self {
+            SearchConnection::MySql(..) => SqlFlavor::MySql,
+            SearchConnection::Sqlite(_) => SqlFlavor::Sqlite,
+        }
+    }
+
+    // 库中各表的 (表名, 列名, 列类型)，按表和列的顺序
+    async fn columns(
+        &mut self,
+        db_name: Option<&str>,
+    ) -> Result<Vec<(String, String, String)>, String> {
+        match self {
+            SearchConnection::MySql(conn, _) => {
+                let rows = sqlx::query(
+                    "SELECT CAST(c.TABLE_NAME AS CHAR), CAST(c.COLUMN_NAME AS CHAR), \
+                     CAST(c.DATA_TYPE AS CHAR) \
+                     FROM information_schema.COLUMNS c JOIN information_schema.TABLES t \
+                     ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME \
+                     WHERE c.TABLE_SCHEMA = COALESCE(?, DATABASE()) \
+                     AND t.TABLE_TYPE = 'BASE TABLE' \
+                     ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION",
+                )
+                .bind(db_name)
+                .fetch_all(&mut **conn)
+                .await
+                .map_err(|e| format!("Failed to list columns: {}", e))?;
+                rows.iter()
+                    .map(|row| {
+                        Ok((
+                            row.try_get_unchecked(0).map_err(|e| e.to_string())?,
+                            row.try_get_unchecked(1).map_err(|e| e.to_string())?,
+                            row.try_get_unchecked(2).map_err(|e| e.to_string())?,
+                        ))
+                    })
+                    .collect()
+            }
+            SearchConnection::Sqlite(conn) => sqlx::query_as::<_, (String, String, String)>(
+                "SELECT m.name, c.name, c.type FROM sqlite_master m \
+                 JOIN pragma_table_info(m.name) c \
+                 WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' \
+                 ORDER BY m.name, c.cid",
+            )
+            .fetch_all(&mut **conn)
+            .await
+            .map_err(|e| format!("Failed to list columns: {}", e)),
+        }
+    }
+
+    async fn fetch(
+        &mut self,
+        sql: &str,
+        value: &str,
+        binds: usize,
+    ) -> Result<Vec<Map<String, Value>>, String> {
+        match self {
+            SearchConnection::MySql(conn, timestamp_display) => {
+                let mut query = sqlx::query(sql);
+                for _ in 0..binds {
+                    query = query.bind(value);
+                }
+                let rows = query
+                    .fetch_all(&mut **conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(rows
+                    .iter()
+                    .map(|row| mysql_row_to_json(row, *timestamp_display))
+                    .collect())
+            }
+            SearchConnection::Sqlite(conn) => {
+                let mut query = sqlx::query(sql);
+                for _ in 0..binds {
+                    query = query.bind(value);
+                }
+                let rows = query
+                    .fetch_all(&mut **conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(rows.iter().map(sqlite_row_to_json).collect())
+            }
+        }
+    }
+}
+
+// 列类型归类为 text / json / number / date，其他类型（二进制、空间类型等）不参与搜索
+fn column_category(data_type: &str, flavor: SqlFlavor) -> Option<&'static str> {
+    let data_type = data_type.to_lowercase();
+    match flavor {
+        SqlFlavor::MySql => match data_type.as_str() {
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum"
+            | "set" => Some("text"),
+            "json" => Some("json"),
+            "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "decimal"
+            | "numeric" | "float" | "double" => Some("number"),
+            "date" | "datetime" | "timestamp" | "time" | "year" => Some("date"),
+            _ => None,
+        },
+        // SQLite 按声明类型的亲和性规则归类，未声明类型的列可以存放文本
+        SqlFlavor::Sqlite => {
+            if data_type.contains("json") {
+                Some("json")
+            } else if data_type.contains("int") {
+                Some("number")
+            } else if data_type.is_empty()
+                || ["char", "clob", "text"]
+                    .iter()
+                    .any(|t| data_type.contains(t))
+            {
+                Some("text")
+            } else if data_type.contains("blob") {
+                None
+            } else if data_type.contains("date") || data_type.contains("time") {
+                Some("date")
+            } else {
+                Some("number")
+            }
+        }
+    }
+}
+
+fn search_tables(
+    columns: Vec<(String, String, String)>,
+    options: &DatabaseSearchOptions,
+    flavor: SqlFlavor,
+) -> Vec<SearchTable> {
+    let column_types: Vec<&str> = match &options.column_types {
+        Some(types) => types.iter().map(String::as_str).collect(),
+        None => SEARCH_DEFAULT_COLUMN_TYPES.to_vec(),
+    };
+    let mut tables: Vec<SearchTable> = Vec::new();
+    for (table, column, data_type) in columns {
+        if options
+            .tables
+            .as_ref()
+            .is_some_and(|names| !names.contains(&table))
+        {
+            continue;
+        }
+        if !column_category(&data_type, flavor).is_some_and(|c| column_types.contains(&c)) {
+            continue;
+        }
+        match tables.last_mut() {
+            Some(last) if last.name == table => last.columns.push(column),
+            _ => tables.push(SearchTable {
+                name: table,
+                columns: vec![column],
+            }),
+        }
+    }
+    tables
+}
+
+// LIKE 的转义字符用 '!'，MySQL 和 SQLite 中的写法相同
+pub fn like_pattern(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len() + 2);
+    escaped.push('%');
+    for c in term.chars() {
+        if matches!(c, '!' | '%' | '_') {
+            escaped.push('!');
+        }
+        escaped.push(c);
+    }
+    escaped.push('%');
+    escaped
+}
+
+fn table_query(table: &SearchTable, exact: bool, limit: u32, flavor: SqlFlavor) -> String {
+    let conditions: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| {
+            let column = quote_identifier(column, flavor);
+            match exact {
+                true => format!("{} = ?", column),
+                false => format!("{} LIKE ? ESCAPE '!'", column),
+            }
+        })
+        .collect();
+    format!(
+        "SELECT * FROM {} WHERE {} LIMIT {}",
+        quote_identifier(&table.name, flavor),
+        conditions.join(" OR "),
+        limit
+    )
+}
+
+// 按返回的值判断哪些列包含搜索词（忽略大小写），用于前端高亮
+fn matched_columns(
+    table: &SearchTable,
+    rows: &[Map<String, Value>],
+    term: &str,
+    exact: bool,
+) -> Vec<String> {
+    let term = term.to_lowercase();
+    table
+        .columns
+        .iter()
+        .filter(|column| {
+            rows.iter().any(|row| {
+                let text = match row.get(*column) {
+                    None | Some(Value::Null) => return false,
+                    Some(Value::String(s)) => s.to_lowercase(),
+                    Some(other) => other.to_string().to_lowercase(),
+                };
+                match exact {
+                    true => text == term,
+                    false => text.contains(&term),
+                }
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+struct Search {
+    app: AppHandle,
+    search_id: String,
+    cancel: Arc<AtomicBool>,
+    tables_scanned: usize,
+    total_tables: usize,
+}
+
+impl Search {
+    fn emit(&self, status: &str, matched: Option<TableMatch>, error: Option<String>) {
+        let _ = self.app.emit(
+            DATABASE_SEARCH_EVENT,
+            DatabaseSearchEvent {
+                search_id: self.search_id.clone(),
+                status: status.to_string(),
+                tables_scanned: self.tables_scanned,
+                total_tables: self.total_tables,
+                matched,
+                error,
+            },
+        );
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    async fn run(
+        &mut self,
+        conn: &mut SearchConnection,
+        term: &str,
+        options: &DatabaseSearchOptions,
+    ) -> Result<(), String> {
+        let flavor = conn.flavor();
+        let exact = options.exact.unwrap_or(false);
+        let limit = options
+            .limit_per_table
+            .unwrap_or(SEARCH_DEFAULT_LIMIT_PER_TABLE)
+            .clamp(1, SEARCH_MAX_LIMIT_PER_TABLE);
+        let value = match exact {
+            true => term.to_string(),
+            false => like_pattern(term),
+        };
+
+        let columns = conn.columns(options.db_name.as_deref()).await?;
+        let tables = search_tables(columns, options, flavor);
+        self.total_tables = tables.len();
+        for table in &tables {
+            if self.cancelled() {
+                break;
+            }
+            let sql = table_query(table, exact, limit, flavor);
+            let result = conn.fetch(&sql, &value, table.columns.len()).await;
+            self.tables_scanned += 1;
+            match result {
+                Ok(rows) if rows.is_empty() => self.emit("running", None, None),
+                Ok(rows) => {
+                    let matched = TableMatch {
+                        table: table.name.clone(),
+                        columns: matched_columns(table, &rows, term, exact),
+                        rows,
+                    };
+                    self.emit("running", Some(matched), None);
+                }
+                // 取消时正在执行的查询会报错，不作为表的错误
+                Err(_) if self.cancelled() => break,
+                Err(e) => self.emit("running", None, Some(format!("{}: {}", table.name, e))),
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn search_connection(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    search_id: &str,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Result<SearchConnection, String> {
+    let db_type = connection_db_type(db_state, connection_id).await?;
+    // 登记连接，cancel_query 可中断正在扫描的表
+    match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            let mut conn =
+                acquire_connection(app_state, db_state, connection_id, db_name.clone()).await?;
+            register_mysql_query(app_state, &mut conn, search_id, connection_id, db_name).await;
+            let timestamp_display = timestamp_display_of(app_state, connection_id).await;
+            Ok(SearchConnection::MySql(conn, timestamp_display))
+        }
+        "sqlite" => {
+            let pool = get_or_create_pool(app_state, db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            let handle = SqliteInterruptHandle::of(&mut conn).await?;
+            register_running_query(app_state, search_id, RunningQuery::Sqlite(handle)).await;
+            Ok(SearchConnection::Sqlite(conn))
+        }
+        other => Err(format!("Database search is not supported for {}", other)),
+    }
+}
+
+async fn run_database_search(
+    app: AppHandle,
+    search_id: String,
+    connection_id: i64,
+    term: String,
+    options: DatabaseSearchOptions,
+    cancel: Arc<AtomicBool>,
+) {
+    let app_state = app.state::<AppState>();
+    let db_state = app.state::<DbState>();
+    let mut search = Search {
+        app: app.clone(),
+        search_id: search_id.clone(),
+        cancel,
+        tables_scanned: 0,
+        total_tables: 0,
+    };
+    let outcome = match search_connection(
+        &app_state,
+        &db_state,
+        &search_id,
+        connection_id,
+        options.db_name.clone(),
+    )
+    .await
+    {
+        Ok(mut conn) => search.run(&mut conn, &term, &options).await,
+        Err(e) => Err(e),
+    };
+
+    finish_running_query(&app_state, &search_id).await;
+    app_state.cancel_flags.lock().await.remove(&search_id);
+    match outcome {
+        Ok(()) if search.cancelled() => search.emit("cancelled", None, None),
+        Ok(()) => search.emit("done", None, None),
+        Err(e) => search.emit("failed", None, Some(e)),
+    }
+}
+
+// 在库的所有表中搜索一个值（"哪张表里有这个邮箱？"）。后台逐表扫描文本类列，每张表最多返回
+// limit_per_table 行，结果通过 database-search 事件逐表返回；立即返回搜索 id，可用 cancel_query 取消。
+// 搜索不占用连接的查询并发槽位
+#[command]
+pub async fn search_database(
+    app_state: State<'_, AppState>,
+    connection_id: i64,
+    term: String,
+    options: Option<DatabaseSearchOptions>,
+    execution_id: Option<String>,
+) -> Result<String, String> {
+    if term.is_empty() {
+        return Err("Search term is required".to_string());
+    }
+    let app = app_state
+        .app_handle
+        .clone()
+        .ok_or("Application is not initialized")?;
+    let search_id = execution_id.unwrap_or_else(new_execution_id);
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(search_id.clone(), cancel.clone());
+
+    tauri::async_runtime::spawn(run_database_search(
+        app,
+        search_id.clone(),
+        connection_id,
+        term,
+        options.unwrap_or_default(),
+        cancel,
+    ));
+    Ok(search_id)
+}
@@ -1,3 +1,4 @@
+use crate::sql_classifier::SqlFlavor;
 use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
 use tauri::{AppHandle, Manager};
 
@@ -37,3 +38,27 @@ pub async fn init_db_pool(app: &AppHandle) -> Result<DbState, Box<dyn std::error
 
     Ok(DbState { pool })
 }
+
+// 读取连接的数据库类型
+pub async fn connection_db_type(db_state: &DbState, connection_id: i64) -> Result<String, String> {
+    sqlx::query_scalar::<_, String>("SELECT db_type FROM connections WHERE id = ?")
+        .bind(connection_id)
+        .fetch_optional(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+        .ok_or_else(|| "Connection not found".to_string())
+}
+
+// SQL 类命令只支持 MySQL 系和 SQLite，其他类型返回 "<action> is not supported for <db_type>"
+pub async fn connection_flavor(
+    db_state: &DbState,
+    connection_id: i64,
+    action: &str,
+) -> Result<SqlFlavor, String> {
+    let db_type = connection_db_type(db_state, connection_id).await?;
+    match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => Ok(SqlFlavor::MySql),
+        "sqlite" => Ok(SqlFlavor::Sqlite),
+        other => Err(format!("{} is not supported for {}", action, other)),
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+// MySQL 协议兼容的服务端类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerFlavor {
+    MySql,
+    MariaDb,
+    TiDb,
+}
+
+// 每个连接首次连接时探测出的服务端能力，供元数据查询和管理命令选择正确的语句
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerProfile {
+    pub flavor: ServerFlavor,
+    // 原始 VERSION() 字符串
+    pub version_string: String,
+    // 对应 flavor 的产品版本（TiDB 为 TiDB 自身版本，而不是兼容的 MySQL 版本）
+    pub version: (u32, u32, u32),
+    pub supports_cte: bool,
+    pub supports_window_functions: bool,
+    pub supports_json_type: bool,
+    pub supports_check_constraints: bool,
+    pub supports_sequences: bool,
+    pub supports_routines: bool,
+    pub supports_triggers: bool,
+    pub supports_events: bool,
+    // 是否有 performance_schema（可能在配置中关闭，使用前还需检查 @@performance_schema）
+    pub supports_performance_schema: bool,
+    pub global_variables_sql: String,
+    pub global_status_sql: String,
+}
+
+impl ServerProfile {
+    fn at_least(&self, major: u32, minor: u32) -> bool {
+        self.version >= (major, minor, 0)
+    }
+}
+
+// 辅助函数：从版本字符串中取出第一个 x.y.z
+fn parse_version(s: &str) -> (u32, u32, u32) {
+    let mut parts = s
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .find(|p| p.contains('.'))
+        .unwrap_or("")
+        .split('.')
+        .map(|p| p.parse::<u32>().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+fn build_profile(version_string: &str, version_comment: &str) -> ServerProfile {
+    let lower = version_string.to_lowercase();
+    let comment = version_comment.to_lowercase();
+
+    let (flavor, version) = if let Some(pos) = lower.find("-tidb-") {
+        // 例如 8.0.11-TiDB-v7.5.0
+        (ServerFlavor::TiDb, parse_version(&lower[pos + 6..]))
+    } else if lower.contains("mariadb") || comment.contains("mariadb") {
+        // 旧版复制协议会加上 5.5.5- 前缀，例如 5.5.5-10.6.12-MariaDB
+        let stripped = lower.strip_prefix("5.5.5-").unwrap_or(&lower);
+        (ServerFlavor::MariaDb, parse_version(stripped))
+    } else {
+        (ServerFlavor::MySql, parse_version(&lower))
+    };
+
+    let mut profile = ServerProfile {
+        flavor,
+        version_string: version_string.to_string(),
+        version,
+        supports_cte: false,
+        supports_window_functions: false,
+        supports_json_type: false,
+        supports_check_constraints: false,
+        supports_sequences: false,
+        supports_routines: true,
+        supports_triggers: true,
+        supports_events: true,
+        supports_performance_schema: true,
+        global_variables_sql: "SHOW GLOBAL VARIABLES".to_string(),
+        global_status_sql: "SHOW GLOBAL STATUS".to_string(),
+    };
+
+    match flavor {
+        ServerFlavor::MySql => {
+            profile.supports_cte = profile.at_least(8, 0);
+            profile.supports_window_functions = profile.at_least(8, 0);
+            profile.supports_json_type = profile.at_least(5, 7);
+            profile.supports_check_constraints = profile.version >= (8, 0, 16);
+        }
+        ServerFlavor::MariaDb => {
+            profile.supports_cte = profile.at_least(10, 2);
+            profile.supports_window_functions = profile.at_least(10, 2);
+            // MariaDB 的 JSON 只是 LONGTEXT 的别名
+            profile.supports_json_type = false;
+            profile.supports_check_constraints = profile.at_least(10, 2);
+            profile.supports_sequences = profile.at_least(10, 3);
+        }
+        ServerFlavor::TiDb => {
+            profile.supports_cte = profile.at_least(5, 1);
+            profile.supports_window_functions = true;
+            profile.supports_json_type = true;
+            profile.supports_check_constraints = profile.at_least(7, 2);
+            profile.supports_sequences = profile.at_least(4, 0);
+            // TiDB 不支持存储过程、触发器和事件
+            profile.supports_routines = false;
+            profile.supports_triggers = false;
+            profile.supports_events = false;
+            profile.supports_performance_schema = false;
+        }
+    }
+
+    profile
+}
+
+// 探测服务端类型和版本
+pub async fn detect_server_profile(pool: &MySqlPool) -> Result<ServerProfile, String> {
+    let (version_string, version_comment): (String, Option<String>) =
+        sqlx::query_as("SELECT VERSION(), @@version_comment")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to detect server version: {}", e))?;
+
+    Ok(build_profile(
+        &version_string,
+        version_comment.as_deref().unwrap_or_default(),
+    ))
+}
@@ -0,0 +1,256 @@
+use crate::connection_stats::record_query;
+use crate::db::DbState;
+use crate::models::{ColumnInfo, Connection, SqlResult};
+use crate::state::AppState;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta};
+use duckdb::types::Value as DuckValue;
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{command, State};
+
+pub type SharedDuckDbConnection = Arc<Mutex<duckdb::Connection>>;
+
+// 辅助函数：获取或打开 DuckDB 文件连接
+async fn get_or_create_connection(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<SharedDuckDbConnection, String> {
+    // 1. 先检查缓存
+    {
+        let connections = app_state.duckdb_connections.lock().await;
+        if let Some(conn) = connections.get(&connection_id) {
+            return Ok(conn.clone());
+        }
+    }
+
+    // 2. 从 SQLite 读取连接配置
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+
+    if connection.db_type != "duckdb" {
+        return Err("Only DuckDB is supported for this operation".to_string());
+    }
+
+    // 3. connection.database 存储 .duckdb 文件路径
+    let db_path = connection.database.ok_or("Database path is required")?;
+
+    // 4. 打开文件（阻塞操作）
+    let conn = tauri::async_runtime::spawn_blocking(move || duckdb::Connection::open(db_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to open DuckDB file: {}", e))?;
+    let conn = Arc::new(Mutex::new(conn));
+
+    // 5. 存入缓存
+    let mut connections = app_state.duckdb_connections.lock().await;
+    connections.insert(connection_id, conn.clone());
+
+    Ok(conn)
+}
+
+fn bytes_to_value(v: Vec<u8>) -> Value {
+    match String::from_utf8(v) {
+        Ok(s) => Value::String(s),
+        Err(e) => {
+            let hex: String = e.as_bytes().iter().map(|b| format!("{:02X}", b)).collect();
+            Value::String(format!("0x{}", hex))
+        }
+    }
+}
+
+// 将 DuckDB 的值转换为 JSON：64 位及以上整数、DECIMAL 转字符串保精度，LIST/STRUCT/MAP 递归转换
+fn duck_value_to_json(value: DuckValue) -> Value {
+    match value {
+        DuckValue::Null => Value::Null,
+        DuckValue::Boolean(b) => Value::Bool(b),
+        DuckValue::TinyInt(v) => Value::Number(v.into()),
+        DuckValue::SmallInt(v) => Value::Number(v.into()),
+        DuckValue::Int(v) => Value::Number(v.into()),
+        DuckValue::UTinyInt(v) => Value::Number(v.into()),
+        DuckValue::USmallInt(v) => Value::Number(v.into()),
+        DuckValue::UInt(v) => Value::Number(v.into()),
+        DuckValue::BigInt(v) => Value::String(v.to_string()),
+        DuckValue::UBigInt(v) => Value::String(v.to_string()),
+        DuckValue::HugeInt(v) => Value::String(v.to_string()),
+        DuckValue::UHugeInt(v) => Value::String(v.to_string()),
+        DuckValue::Float(v) => v
+            .to_string()
+            .parse::<f64>()
+            .ok()
+            .map(Value::from)
+            .unwrap_or(Value::Null),
+        DuckValue::Double(v) => Value::from(v),
+        DuckValue::Decimal(v) => Value::String(v.to_string()),
+        DuckValue::Timestamp(unit, v) => DateTime::from_timestamp_micros(unit.to_micros(v))
+            .map(|dt| Value::String(dt.naive_utc().format("%Y-%m-%d %H:%M:%S%.f").to_string()))
+            .unwrap_or(Value::Null),
+        DuckValue::Date32(days) => NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(TimeDelta::try_days(days as i64)?))
+            .map(|d| Value::String(d.to_string()))
+            .unwrap_or(Value::Null),
+        DuckValue::Time64(unit, v) => {
+            let micros = unit.to_micros(v);
+            NaiveTime::from_num_seconds_from_midnight_opt(
+                (micros / 1_000_000) as u32,
+                ((micros % 1_000_000) * 1000) as u32,
+            )
+            .map(|t| Value::String(t.to_string()))
+            .unwrap_or(Value::Null)
+        }
+        DuckValue::Interval {
+            months,
+            days,
+            nanos,
+        } => Value::String(format!(
+            "{} months {} days {} us",
+            months,
+            days,
+            nanos / 1000
+        )),
+        DuckValue::Text(s) | DuckValue::Enum(s) => Value::String(s),
+        DuckValue::Blob(bytes) => bytes_to_value(bytes),
+        DuckValue::Geometry(bytes) => {
+            let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+            Value::String(format!("0x{}", hex))
+        }
+        DuckValue::List(items) | DuckValue::Array(items) => {
+            Value::Array(items.into_iter().map(duck_value_to_json).collect())
+        }
+        DuckValue::Struct(fields) => {
+            let mut map = Map::new();
+            for (name, v) in fields.iter() {
+                map.insert(name.clone(), duck_value_to_json(v.clone()));
+            }
+            Value::Object(map)
+        }
+        DuckValue::Map(entries) => {
+            let mut map = Map::new();
+            for (k, v) in entries.iter() {
+                let key = match duck_value_to_json(k.clone()) {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                map.insert(key, duck_value_to_json(v.clone()));
+            }
+            Value::Object(map)
+        }
+        DuckValue::Union(inner) => duck_value_to_json(*inner),
+        other => Value::String(format!("{:?}", other)),
+    }
+}
+
+fn run_query(conn: &duckdb::Connection, sql: &str) -> Result<SqlResult, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Query execution failed: {}", e))?;
+    let mut rows = stmt
+        .query([])
+        .map_err(|e| format!("Query execution failed: {}", e))?;
+
+    // DuckDB 只有在执行后才能拿到结果集的列信息
+    let columns: Vec<ColumnInfo> = match rows.as_ref() {
+        Some(stmt) => stmt
+            .column_names()
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| ColumnInfo {
+                name,
+                type_name: stmt.column_type(i).to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut result_rows = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| format!("Query execution failed: {}", e))?
+    {
+        let mut json_row = Map::new();
+        for (i, column) in columns.iter().enumerate() {
+            let value = row
+                .get::<_, DuckValue>(i)
+                .map(duck_value_to_json)
+                .unwrap_or(Value::Null);
+            json_row.insert(column.name.clone(), value);
+        }
+        result_rows.push(json_row);
+    }
+
+    Ok(SqlResult {
+        columns,
+        rows: result_rows,
+        affected_rows: 0,
+        duration_ms: 0,
+        warnings: vec![],
+        last_insert_id: None,
+        applied_limit: None,
+        from_cache: false,
+        spilled: None,
+    })
+}
+
+#[command]
+pub async fn execute_duckdb_sql(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+) -> Result<SqlResult, String> {
+    let conn = get_or_create_connection(&app_state, &db_state, connection_id).await?;
+
+    // DuckDB 为同步 API，放到阻塞线程池中执行
+    let started = Instant::now();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let conn = conn
+            .lock()
+            .map_err(|_| "DuckDB connection is poisoned".to_string())?;
+
+        let sql_upper = sql.trim().to_uppercase();
+        if sql_upper.starts_with("SELECT")
+            || sql_upper.starts_with("WITH")
+            || sql_upper.starts_with("FROM")
+            || sql_upper.starts_with("VALUES")
+            || sql_upper.starts_with("SHOW")
+            || sql_upper.starts_with("DESCRIBE")
+            || sql_upper.starts_with("SUMMARIZE")
+            || sql_upper.starts_with("PRAGMA")
+            || sql_upper.starts_with("EXPLAIN")
+        {
+            run_query(&conn, &sql)
+        } else {
+            let affected_rows = conn
+                .execute(&sql, [])
+                .map_err(|e| format!("Statement execution failed: {}", e))?;
+
+            Ok(SqlResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: affected_rows as u64,
+                duration_ms: 0,
+                warnings: vec![],
+                last_insert_id: None,
+                applied_limit: None,
+                from_cache: false,
+                spilled: None,
+            })
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    result.map(|mut result| {
+        result.duration_ms = elapsed.as_millis() as u64;
+        result
+    })
+}
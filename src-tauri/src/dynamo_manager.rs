@@ -0,0 +1,501 @@
+use crate::connection_stats::record_query;
+use crate::db::DbState;
+use crate::models::Connection;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use aws_config::BehaviorVersion;
+use aws_sdk_dynamodb::config::{Credentials, Region};
+use aws_sdk_dynamodb::error::DisplayErrorContext;
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value as JsonValue};
+use std::collections::HashMap;
+use std::time::Instant;
+use tauri::{command, State};
+
+const DYNAMO_DEFAULT_PAGE_SIZE: i32 = 100;
+const DYNAMO_DEFAULT_REGION: &str = "us-east-1";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DynamoPageResult {
+    pub items: Vec<JsonValue>,
+    pub count: i32,
+    // Typed (DynamoDB JSON) key to pass back as `exclusive_start_key` for the next page
+    pub last_evaluated_key: Option<JsonValue>,
+}
+
+async fn get_or_create_dynamo_client(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Client, String> {
+    // 1. Check cache
+    {
+        let clients = app_state.dynamo_clients.lock().await;
+        if let Some(client) = clients.get(&connection_id) {
+            return Ok(client.clone());
+        }
+    }
+
+    // 2. Fetch connection info
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
+
+    if connection.db_type != "dynamodb" {
+        return Err("Only DynamoDB is supported for this operation".to_string());
+    }
+
+    // 3. Build SDK config: `database` holds the region, `host` an optional endpoint
+    //    (DynamoDB Local), username/password an optional static access key pair.
+    let region = connection
+        .database
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| DYNAMO_DEFAULT_REGION.to_string());
+    let mut loader = aws_config::defaults(BehaviorVersion::latest()).region(Region::new(region));
+
+    if let Some(host) = connection.host.filter(|h| !h.is_empty()) {
+        let endpoint = if host.contains("://") {
+            host
+        } else {
+            format!("http://{}:{}", host, connection.port.unwrap_or(8000))
+        };
+        loader = loader.endpoint_url(endpoint);
+    }
+
+    if let Some(access_key) = connection.username.filter(|u| !u.is_empty()) {
+        loader = loader.credentials_provider(Credentials::new(
+            access_key,
+            connection.password.unwrap_or_default(),
+            None,
+            None,
+            "xdb",
+        ));
+    }
+
+    // 4. Create Client
+    let config = loader.load().await;
+    let client = Client::new(&config);
+
+    // 5. Cache client
+    let mut clients = app_state.dynamo_clients.lock().await;
+    clients.insert(connection_id, client.clone());
+
+    Ok(client)
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+    format!("0x{}", hex)
+}
+
+fn hex_to_bytes(value: &str) -> Result<Vec<u8>, String> {
+    let hex = value.trim_start_matches("0x");
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!("Invalid binary value: {}", value));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| format!("Invalid binary value: {}", value))
+        })
+        .collect()
+}
+
+fn number_to_json(n: &str) -> JsonValue {
+    // Keep numbers as JSON numbers only when they survive the round-trip exactly
+    if let Ok(i) = n.parse::<i64>() {
+        if i.unsigned_abs() <= (1u64 << 53) {
+            return JsonValue::Number(i.into());
+        }
+        return JsonValue::String(n.to_string());
+    }
+    n.parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .filter(|num| num.to_string() == n)
+        .map(JsonValue::Number)
+        .unwrap_or_else(|| JsonValue::String(n.to_string()))
+}
+
+// Plain JSON view of an attribute value, for the grid
+fn attribute_to_json(value: &AttributeValue) -> JsonValue {
+    match value {
+        AttributeValue::S(s) => JsonValue::String(s.clone()),
+        AttributeValue::N(n) => number_to_json(n),
+        AttributeValue::Bool(b) => JsonValue::Bool(*b),
+        AttributeValue::Null(_) => JsonValue::Null,
+        AttributeValue::B(b) => JsonValue::String(bytes_to_hex(b.as_ref())),
+        AttributeValue::Ss(items) => {
+            JsonValue::Array(items.iter().cloned().map(JsonValue::String).collect())
+        }
+        AttributeValue::Ns(items) => {
+            JsonValue::Array(items.iter().map(|n| number_to_json(n)).collect())
+        }
+        AttributeValue::Bs(items) => JsonValue::Array(
+            items
+                .iter()
+                .map(|b| JsonValue::String(bytes_to_hex(b.as_ref())))
+                .collect(),
+        ),
+        AttributeValue::L(items) => JsonValue::Array(items.iter().map(attribute_to_json).collect()),
+        AttributeValue::M(map) => item_to_json(map),
+        _ => JsonValue::Null,
+    }
+}
+
+fn item_to_json(item: &HashMap<String, AttributeValue>) -> JsonValue {
+    let mut map: Vec<(&String, &AttributeValue)> = item.iter().collect();
+    map.sort_by(|a, b| a.0.cmp(b.0));
+    JsonValue::Object(
+        map.into_iter()
+            .map(|(k, v)| (k.clone(), attribute_to_json(v)))
+            .collect(),
+    )
+}
+
+// Plain JSON → attribute value, used for items and key maps entered in the UI
+fn json_to_attribute(value: &JsonValue) -> AttributeValue {
+    match value {
+        JsonValue::Null => AttributeValue::Null(true),
+        JsonValue::Bool(b) => AttributeValue::Bool(*b),
+        JsonValue::Number(n) => AttributeValue::N(n.to_string()),
+        JsonValue::String(s) => AttributeValue::S(s.clone()),
+        JsonValue::Array(items) => AttributeValue::L(items.iter().map(json_to_attribute).collect()),
+        JsonValue::Object(map) => AttributeValue::M(
+            map.iter()
+                .map(|(k, v)| (k.clone(), json_to_attribute(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn json_to_item(value: &JsonValue, what: &str) -> Result<HashMap<String, AttributeValue>, String> {
+    match value {
+        JsonValue::Object(map) => Ok(map
+            .iter()
+            .map(|(k, v)| (k.clone(), json_to_attribute(v)))
+            .collect()),
+        _ => Err(format!("{} must be a JSON object", what)),
+    }
+}
+
+// Typed DynamoDB JSON ({"S": "..."}) keeps pagination keys lossless
+fn attribute_to_typed_json(value: &AttributeValue) -> JsonValue {
+    let (tag, inner) = match value {
+        AttributeValue::S(s) => ("S", JsonValue::String(s.clone())),
+        AttributeValue::N(n) => ("N", JsonValue::String(n.clone())),
+        AttributeValue::B(b) => ("B", JsonValue::String(bytes_to_hex(b.as_ref()))),
+        AttributeValue::Bool(b) => ("BOOL", JsonValue::Bool(*b)),
+        AttributeValue::Null(b) => ("NULL", JsonValue::Bool(*b)),
+        AttributeValue::Ss(items) => (
+            "SS",
+            JsonValue::Array(items.iter().cloned().map(JsonValue::String).collect()),
+        ),
+        AttributeValue::Ns(items) => (
+            "NS",
+            JsonValue::Array(items.iter().cloned().map(JsonValue::String).collect()),
+        ),
+        AttributeValue::Bs(items) => (
+            "BS",
+            JsonValue::Array(
+                items
+                    .iter()
+                    .map(|b| JsonValue::String(bytes_to_hex(b.as_ref())))
+                    .collect(),
+            ),
+        ),
+        AttributeValue::L(items) => (
+            "L",
+            JsonValue::Array(items.iter().map(attribute_to_typed_json).collect()),
+        ),
+        AttributeValue::M(map) => (
+            "M",
+            JsonValue::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), attribute_to_typed_json(v)))
+                    .collect(),
+            ),
+        ),
+        _ => ("NULL", JsonValue::Bool(true)),
+    };
+    let mut obj = Map::new();
+    obj.insert(tag.to_string(), inner);
+    JsonValue::Object(obj)
+}
+
+fn typed_json_to_attribute(value: &JsonValue) -> Result<AttributeValue, String> {
+    let invalid = || format!("Invalid DynamoDB JSON value: {}", value);
+    let (tag, inner) = value
+        .as_object()
+        .filter(|obj| obj.len() == 1)
+        .and_then(|obj| obj.iter().next())
+        .ok_or_else(invalid)?;
+    let strings = |v: &JsonValue| -> Result<Vec<String>, String> {
+        v.as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|s| s.as_str().map(str::to_string).ok_or_else(invalid))
+            .collect()
+    };
+
+    Ok(match tag.as_str() {
+        "S" => AttributeValue::S(inner.as_str().ok_or_else(invalid)?.to_string()),
+        "N" => AttributeValue::N(match inner {
+            JsonValue::Number(n) => n.to_string(),
+            other => other.as_str().ok_or_else(invalid)?.to_string(),
+        }),
+        "B" => AttributeValue::B(Blob::new(hex_to_bytes(
+            inner.as_str().ok_or_else(invalid)?,
+        )?)),
+        "BOOL" => AttributeValue::Bool(inner.as_bool().ok_or_else(invalid)?),
+        "NULL" => AttributeValue::Null(true),
+        "SS" => AttributeValue::Ss(strings(inner)?),
+        "NS" => AttributeValue::Ns(strings(inner)?),
+        "BS" => AttributeValue::Bs(
+            strings(inner)?
+                .iter()
+                .map(|s| hex_to_bytes(s).map(Blob::new))
+                .collect::<Result<_, _>>()?,
+        ),
+        "L" => AttributeValue::L(
+            inner
+                .as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(typed_json_to_attribute)
+                .collect::<Result<_, _>>()?,
+        ),
+        "M" => AttributeValue::M(typed_json_to_item(inner)?),
+        _ => return Err(invalid()),
+    })
+}
+
+fn typed_json_to_item(value: &JsonValue) -> Result<HashMap<String, AttributeValue>, String> {
+    value
+        .as_object()
+        .ok_or("DynamoDB key must be a JSON object")?
+        .iter()
+        .map(|(k, v)| typed_json_to_attribute(v).map(|av| (k.clone(), av)))
+        .collect()
+}
+
+// Items and keys edited in the UI: typed DynamoDB JSON keeps B / SS / NS / BS and
+// numeric strings intact, plain JSON infers the types from the JSON values
+fn input_item(
+    value: &JsonValue,
+    what: &str,
+    typed: Option<bool>,
+) -> Result<HashMap<String, AttributeValue>, String> {
+    match typed {
+        Some(true) if value.is_object() => typed_json_to_item(value),
+        Some(true) => Err(format!("{} must be a JSON object", what)),
+        _ => json_to_item(value, what),
+    }
+}
+
+fn typed_key(key: Option<&HashMap<String, AttributeValue>>) -> Option<JsonValue> {
+    key.filter(|k| !k.is_empty()).map(|k| {
+        JsonValue::Object(
+            k.iter()
+                .map(|(name, v)| (name.clone(), attribute_to_typed_json(v)))
+                .collect(),
+        )
+    })
+}
+
+fn optional_start_key(
+    value: Option<JsonValue>,
+) -> Result<Option<HashMap<String, AttributeValue>>, String> {
+    match value {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(v) => typed_json_to_item(&v).map(Some),
+    }
+}
+
+fn optional_values(
+    value: Option<JsonValue>,
+) -> Result<Option<HashMap<String, AttributeValue>>, String> {
+    match value {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(v) => json_to_item(&v, "Expression attribute values").map(Some),
+    }
+}
+
+#[command]
+pub async fn list_dynamo_tables(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<String>, String> {
+    let client = get_or_create_dynamo_client(&app_state, &db_state, connection_id).await?;
+
+    let mut tables = Vec::new();
+    let mut start_table: Option<String> = None;
+    loop {
+        let output = client
+            .list_tables()
+            .set_exclusive_start_table_name(start_table)
+            .send()
+            .await
+            .map_err(|e| format!("List tables failed: {}", DisplayErrorContext(e)))?;
+
+        tables.extend(output.table_names.unwrap_or_default());
+        start_table = output.last_evaluated_table_name;
+        if start_table.is_none() {
+            break;
+        }
+    }
+
+    Ok(tables)
+}
+
+#[command]
+pub async fn scan_dynamo_table(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    limit: Option<i32>,
+    exclusive_start_key: Option<JsonValue>,
+    filter_expression: Option<String>,
+    expression_attribute_names: Option<HashMap<String, String>>,
+    expression_attribute_values: Option<JsonValue>,
+) -> Result<DynamoPageResult, String> {
+    let client = get_or_create_dynamo_client(&app_state, &db_state, connection_id).await?;
+
+    let output = client
+        .scan()
+        .table_name(table)
+        .limit(limit.unwrap_or(DYNAMO_DEFAULT_PAGE_SIZE))
+        .set_exclusive_start_key(optional_start_key(exclusive_start_key)?)
+        .set_filter_expression(filter_expression.filter(|f| !f.is_empty()))
+        .set_expression_attribute_names(expression_attribute_names)
+        .set_expression_attribute_values(optional_values(expression_attribute_values)?)
+        .send()
+        .await
+        .map_err(|e| format!("Scan failed: {}", DisplayErrorContext(e)))?;
+
+    Ok(DynamoPageResult {
+        items: output.items().iter().map(item_to_json).collect(),
+        count: output.count(),
+        last_evaluated_key: typed_key(output.last_evaluated_key()),
+    })
+}
+
+#[command]
+pub async fn query_dynamo_table(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    key_condition_expression: String,
+    expression_attribute_values: Option<JsonValue>,
+    expression_attribute_names: Option<HashMap<String, String>>,
+    index_name: Option<String>,
+    limit: Option<i32>,
+    exclusive_start_key: Option<JsonValue>,
+    scan_forward: Option<bool>,
+) -> Result<DynamoPageResult, String> {
+    let client = get_or_create_dynamo_client(&app_state, &db_state, connection_id).await?;
+
+    let started = Instant::now();
+    let output = client
+        .query()
+        .table_name(table)
+        .set_index_name(index_name.filter(|i| !i.is_empty()))
+        .key_condition_expression(key_condition_expression)
+        .set_expression_attribute_names(expression_attribute_names)
+        .set_expression_attribute_values(optional_values(expression_attribute_values)?)
+        .limit(limit.unwrap_or(DYNAMO_DEFAULT_PAGE_SIZE))
+        .set_exclusive_start_key(optional_start_key(exclusive_start_key)?)
+        .scan_index_forward(scan_forward.unwrap_or(true))
+        .send()
+        .await;
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
+    let output = output.map_err(|e| format!("Query failed: {}", DisplayErrorContext(e)))?;
+
+    Ok(DynamoPageResult {
+        items: output.items().iter().map(item_to_json).collect(),
+        count: output.count(),
+        last_evaluated_key: typed_key(output.last_evaluated_key()),
+    })
+}
+
+#[command]
+pub async fn get_dynamo_item(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    key: JsonValue,
+    typed: Option<bool>,
+) -> Result<Option<JsonValue>, String> {
+    let client = get_or_create_dynamo_client(&app_state, &db_state, connection_id).await?;
+
+    let output = client
+        .get_item()
+        .table_name(table)
+        .set_key(Some(input_item(&key, "Key", typed)?))
+        .send()
+        .await
+        .map_err(|e| format!("Get item failed: {}", DisplayErrorContext(e)))?;
+
+    Ok(match typed {
+        Some(true) => typed_key(output.item()),
+        _ => output.item().map(item_to_json),
+    })
+}
+
+#[command]
+pub async fn put_dynamo_item(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    item: JsonValue,
+    typed: Option<bool>,
+) -> Result<(), String> {
+    let client = get_or_create_dynamo_client(&app_state, &db_state, connection_id).await?;
+
+    client
+        .put_item()
+        .table_name(table)
+        .set_item(Some(input_item(&item, "Item", typed)?))
+        .send()
+        .await
+        .map_err(|e| format!("Put item failed: {}", DisplayErrorContext(e)))?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn delete_dynamo_item(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    key: JsonValue,
+    typed: Option<bool>,
+) -> Result<(), String> {
+    let client = get_or_create_dynamo_client(&app_state, &db_state, connection_id).await?;
+
+    client
+        .delete_item()
+        .table_name(table)
+        .set_key(Some(input_item(&key, "Key", typed)?))
+        .send()
+        .await
+        .map_err(|e| format!("Delete item failed: {}", DisplayErrorContext(e)))?;
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use crate::db::DbState;
+use crate::models::Connection;
+use crate::ssh_tunnel::resolve_endpoint;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+use tauri::{command, State};
+use tokio::time::Duration;
+use urlencoding::encode;
+
+const ELASTIC_REQUEST_TIMEOUT_SECS: u64 = 30;
+const ELASTIC_DEFAULT_PAGE_SIZE: u64 = 50;
+
+#[derive(Clone)]
+pub struct ElasticClient {
+    http: reqwest::Client,
+    base_url: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElasticIndex {
+    pub index: String,
+    pub health: Option<String>,
+    pub status: Option<String>,
+    pub docs_count: Option<u64>,
+    pub store_size: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElasticSearchResult {
+    pub total: u64,
+    pub took: u64,
+    pub hits: Vec<JsonValue>,
+}
+
+async fn get_or_create_elastic_client(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<ElasticClient, String> {
+    // 1. Check cache
+    {
+        let clients = app_state.elastic_clients.lock().await;
+        if let Some(client) = clients.get(&connection_id) {
+            return Ok(client.clone());
+        }
+    }
+
+    // 2. Fetch connection info
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
+
+    if connection.db_type != "elasticsearch" {
+        return Err("Only Elasticsearch is supported for this operation".to_string());
+    }
+
+    // 3. Resolve base URL (host may already carry an http/https scheme)
+    let host = connection.host.as_deref().ok_or("Host is required")?;
+    let base_url = if host.contains("://") {
+        host.trim_end_matches('/').to_string()
+    } else {
+        let port = connection.port.unwrap_or(9200) as u16;
+        let (host, port) = resolve_endpoint(app_state, &connection, host, port).await?;
+        format!("http://{}:{}", host, port)
+    };
+
+    // 4. Create Client
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(ELASTIC_REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create Elasticsearch client: {}", e))?;
+
+    let client = ElasticClient {
+        http,
+        base_url,
+        username: connection.username.filter(|u| !u.is_empty()),
+        password: connection.password,
+    };
+
+    // 5. Cache client
+    let mut clients = app_state.elastic_clients.lock().await;
+    clients.insert(connection_id, client.clone());
+
+    Ok(client)
+}
+
+impl ElasticClient {
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<JsonValue>,
+    ) -> Result<JsonValue, String> {
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path));
+        if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Elasticsearch request failed: {}", e))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read Elasticsearch response: {}", e))?;
+
+        if !status.is_success() {
+            // Prefer the structured error reason when the server returns one
+            let reason = serde_json::from_str::<JsonValue>(&text)
+                .ok()
+                .and_then(|v| {
+                    v.pointer("/error/reason")
+                        .and_then(|r| r.as_str())
+                        .map(str::to_string)
+                })
+                .unwrap_or(text);
+            return Err(format!("Elasticsearch returned {}: {}", status, reason));
+        }
+
+        serde_json::from_str(&text)
+            .map_err(|e| format!("Failed to parse Elasticsearch response: {}", e))
+    }
+}
+
+// _cat APIs return numbers as strings; accept both forms
+fn json_u64(value: Option<&JsonValue>) -> Option<u64> {
+    value.and_then(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()))
+}
+
+#[command]
+pub async fn list_elastic_indices(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<ElasticIndex>, String> {
+    let client = get_or_create_elastic_client(&app_state, &db_state, connection_id).await?;
+    let result = client
+        .request(
+            reqwest::Method::GET,
+            "/_cat/indices?format=json&bytes=b&s=index",
+            None,
+        )
+        .await?;
+
+    let indices = result
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .map(|item| ElasticIndex {
+                    index: item
+                        .get("index")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    health: item.get("health").and_then(|v| v.as_str()).map(str::to_string),
+                    status: item.get("status").and_then(|v| v.as_str()).map(str::to_string),
+                    docs_count: json_u64(item.get("docs.count")),
+                    store_size: json_u64(item.get("store.size")),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(indices)
+}
+
+#[command]
+pub async fn get_elastic_mapping(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    index: String,
+) -> Result<JsonValue, String> {
+    let client = get_or_create_elastic_client(&app_state, &db_state, connection_id).await?;
+    client
+        .request(
+            reqwest::Method::GET,
+            &format!("/{}/_mapping", encode(&index)),
+            None,
+        )
+        .await
+}
+
+#[command]
+pub async fn search_elastic(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    index: String,
+    query: Option<JsonValue>,
+    sort: Option<JsonValue>,
+    from: Option<u64>,
+    size: Option<u64>,
+    search_after: Option<Vec<JsonValue>>,
+) -> Result<ElasticSearchResult, String> {
+    let client = get_or_create_elastic_client(&app_state, &db_state, connection_id).await?;
+
+    let mut body = Map::new();
+    body.insert(
+        "query".to_string(),
+        query
+            .filter(|q| !q.is_null())
+            .unwrap_or_else(|| json!({ "match_all": {} })),
+    );
+    body.insert(
+        "size".to_string(),
+        json!(size.unwrap_or(ELASTIC_DEFAULT_PAGE_SIZE)),
+    );
+    body.insert("track_total_hits".to_string(), json!(true));
+    if let Some(sort) = sort.filter(|s| !s.is_null()) {
+        body.insert("sort".to_string(), sort);
+    }
+    // Deep pagination uses search_after; shallow pages use from/size
+    match search_after {
+        Some(values) if !values.is_empty() => {
+            body.insert("search_after".to_string(), JsonValue::Array(values));
+        }
+        _ => {
+            body.insert("from".to_string(), json!(from.unwrap_or(0)));
+        }
+    }
+
+    let result = client
+        .request(
+            reqwest::Method::POST,
+            &format!("/{}/_search", encode(&index)),
+            Some(JsonValue::Object(body)),
+        )
+        .await?;
+
+    // ES 7+ returns an object for hits.total, older versions a plain number
+    let total = result
+        .pointer("/hits/total/value")
+        .or_else(|| result.pointer("/hits/total"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let took = result.get("took").and_then(|v| v.as_u64()).unwrap_or(0);
+    let hits = result
+        .pointer("/hits/hits")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(ElasticSearchResult { total, took, hits })
+}
+
+#[command]
+pub async fn get_elastic_cluster_health(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<JsonValue, String> {
+    let client = get_or_create_elastic_client(&app_state, &db_state, connection_id).await?;
+    client
+        .request(reqwest::Method::GET, "/_cluster/health", None)
+        .await
+}
@@ -0,0 +1,311 @@
+use crate::alter_table::{apply_ddl, qualified_name};
+use crate::db::{connection_flavor, DbState};
+use crate::models::{
+    CreateEventArgs, DdlStatementResult, EventDetail, EventInfo, EventList, EventSchedule,
+};
+use crate::mysql_manager;
+use crate::result_export::sql_literal;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use serde_json::Value;
+use sqlx::mysql::MySqlRow;
+use sqlx::{MySqlConnection, Row};
+use tauri::{command, State};
+
+const INTERVAL_UNITS: &[&str] = &[
+    "YEAR",
+    "QUARTER",
+    "MONTH",
+    "WEEK",
+    "DAY",
+    "HOUR",
+    "MINUTE",
+    "SECOND",
+    "YEAR_MONTH",
+    "DAY_HOUR",
+    "DAY_MINUTE",
+    "DAY_SECOND",
+    "HOUR_MINUTE",
+    "HOUR_SECOND",
+    "MINUTE_SECOND",
+];
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 事件只有 MySQL 系支持，并按探测到的服务端能力判断（TiDB 没有事件调度器）
+async fn ensure_events_supported(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<(), String> {
+    if matches!(
+        connection_flavor(db_state, connection_id, "Scheduled events").await?,
+        SqlFlavor::Sqlite
+    ) {
+        return Err("Scheduled events are not supported for sqlite".to_string());
+    }
+    let profile = mysql_manager::get_server_profile_for(app_state, db_state, connection_id).await?;
+    match profile.supports_events {
+        true => Ok(()),
+        false => Err("Scheduled events are not supported by this server".to_string()),
+    }
+}
+
+async fn fetch_events(
+    conn: &mut MySqlConnection,
+    db_name: Option<&str>,
+    name: Option<&str>,
+) -> Result<Vec<EventInfo>, String> {
+    let rows = sqlx::query(
+        "SELECT CAST(EVENT_NAME AS CHAR), CAST(STATUS AS CHAR), CAST(EVENT_TYPE AS CHAR), \
+         CAST(EXECUTE_AT AS CHAR), CAST(INTERVAL_VALUE AS CHAR), CAST(INTERVAL_FIELD AS CHAR), \
+         CAST(STARTS AS CHAR), CAST(ENDS AS CHAR), CAST(ON_COMPLETION AS CHAR), \
+         CAST(LAST_EXECUTED AS CHAR), CAST(EVENT_DEFINITION AS CHAR), CAST(EVENT_COMMENT AS CHAR) \
+         FROM information_schema.EVENTS \
+         WHERE EVENT_SCHEMA = COALESCE(?, DATABASE()) AND (? IS NULL OR EVENT_NAME = ?) \
+         ORDER BY EVENT_NAME",
+    )
+    .bind(db_name)
+    .bind(name)
+    .bind(name)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch events: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| EventInfo {
+            name: text(row, 0).unwrap_or_default(),
+            status: text(row, 1).unwrap_or_default(),
+            event_type: text(row, 2).unwrap_or_default(),
+            execute_at: text(row, 3),
+            interval_value: text(row, 4),
+            interval_field: text(row, 5),
+            starts: text(row, 6),
+            ends: text(row, 7),
+            on_completion: text(row, 8).unwrap_or_default(),
+            last_executed: text(row, 9),
+            definition: text(row, 10).unwrap_or_default(),
+            comment: text(row, 11).filter(|c| !c.is_empty()),
+        })
+        .collect())
+}
+
+// 列出库中的事件，并返回事件调度器的状态
+#[command]
+pub async fn list_events(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Result<EventList, String> {
+    ensure_events_supported(&app_state, &db_state, connection_id).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, db_name.clone())
+            .await?;
+    let scheduler = sqlx::query("SELECT CAST(@@GLOBAL.event_scheduler AS CHAR)")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to read event_scheduler: {}", e))
+        .map(|row| text(&row, 0).unwrap_or_default())?;
+    let events = fetch_events(&mut conn, db_name.as_deref(), None).await?;
+    Ok(EventList { scheduler, events })
+}
+
+// 返回事件的调度信息、事件体和完整的 CREATE EVENT 语句
+#[command]
+pub async fn get_event(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    db_name: Option<String>,
+) -> Result<EventDetail, String> {
+    ensure_events_supported(&app_state, &db_state, connection_id).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, db_name.clone())
+            .await?;
+    let info = fetch_events(&mut conn, db_name.as_deref(), Some(&name))
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Event {} not found", name))?;
+    // 第四列为 Create Event
+    let create_sql = sqlx::raw_sql(&format!(
+        "SHOW CREATE EVENT {}",
+        qualified_name(db_name.as_deref(), &name, SqlFlavor::MySql)
+    ))
+    .fetch_one(&mut *conn)
+    .await
+    .ok()
+    .and_then(|row| text(&row, 3));
+    Ok(EventDetail { info, create_sql })
+}
+
+// 启用或停用事件，直接执行
+#[command]
+pub async fn set_event_enabled(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    enabled: bool,
+    db_name: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    ensure_events_supported(&app_state, &db_state, connection_id).await?;
+    let statement = format!(
+        "ALTER EVENT {} {}",
+        qualified_name(db_name.as_deref(), &name, SqlFlavor::MySql),
+        if enabled { "ENABLE" } else { "DISABLE" }
+    );
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        SqlFlavor::MySql,
+        db_name,
+        std::slice::from_ref(&statement),
+        None,
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
+
+fn string_literal(value: &str) -> String {
+    sql_literal(&Value::String(value.to_string()), SqlFlavor::MySql)
+}
+
+// 时间按字符串字面量传入，如 '2026-01-01 00:00:00'
+fn schedule_sql(schedule: &EventSchedule) -> Result<String, String> {
+    match schedule {
+        EventSchedule::At { at } => Ok(format!("AT {}", string_literal(at.trim()))),
+        EventSchedule::Every {
+            interval,
+            unit,
+            starts,
+            ends,
+        } => {
+            let unit = INTERVAL_UNITS
+                .iter()
+                .find(|u| u.eq_ignore_ascii_case(unit.trim()))
+                .ok_or_else(|| format!("Unsupported interval unit: {}", unit))?;
+            let interval = interval.trim();
+            let interval = match interval.parse::<u64>() {
+                Ok(0) => return Err("Event interval must be positive".to_string()),
+                Ok(value) => value.to_string(),
+                Err(_) => string_literal(interval),
+            };
+            let mut sql = format!("EVERY {} {}", interval, unit);
+            if let Some(starts) = starts {
+                sql.push_str(&format!(" STARTS {}", string_literal(starts.trim())));
+            }
+            if let Some(ends) = ends {
+                sql.push_str(&format!(" ENDS {}", string_literal(ends.trim())));
+            }
+            Ok(sql)
+        }
+    }
+}
+
+// 未确认时只返回 CREATE EVENT 语句供预览。事件体中的 DELETE / DROP 等在生产环境需要确认令牌
+#[command]
+pub async fn create_event(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: CreateEventArgs,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    ensure_events_supported(&app_state, &db_state, connection_id).await?;
+    let body = args.body.trim();
+    if body.is_empty() {
+        return Err("Event body cannot be empty".to_string());
+    }
+    let mut statement = format!(
+        "CREATE EVENT {}{} ON SCHEDULE {} ON COMPLETION {}PRESERVE {}",
+        if args.if_not_exists {
+            "IF NOT EXISTS "
+        } else {
+            ""
+        },
+        qualified_name(args.db_name.as_deref(), &args.name, SqlFlavor::MySql),
+        schedule_sql(&args.schedule)?,
+        if args.preserve { "" } else { "NOT " },
+        if args.enabled == Some(false) {
+            "DISABLE"
+        } else {
+            "ENABLE"
+        }
+    );
+    if let Some(comment) = &args.comment {
+        statement.push_str(&format!(" COMMENT {}", string_literal(comment)));
+    }
+    statement.push_str(&format!(" DO {}", body));
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        SqlFlavor::MySql,
+        args.db_name.clone(),
+        std::slice::from_ref(&statement),
+        confirm_token.as_deref(),
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
+
+// 删除事件：未确认时只返回语句；生产环境还需要确认令牌
+#[command]
+pub async fn drop_event(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    ensure_events_supported(&app_state, &db_state, connection_id).await?;
+    let statement = format!(
+        "DROP EVENT {}",
+        qualified_name(db_name.as_deref(), &name, SqlFlavor::MySql)
+    );
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        SqlFlavor::MySql,
+        db_name,
+        std::slice::from_ref(&statement),
+        confirm_token.as_deref(),
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
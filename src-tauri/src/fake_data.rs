@@ -0,0 +1,1112 @@
+use crate::alter_table::quote_identifier;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::ensure_writable;
+use crate::models::{FakeColumnRule, TimestampDisplay};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::result_cache::invalidate_results;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::pool::PoolConnection;
+use sqlx::{Connection as _, MySql, Row, Sqlite};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{command, Emitter, State};
+
+// 前端监听该事件展示生成进度
+pub const FAKE_DATA_PROGRESS_EVENT: &str = "fake-data-progress";
+
+// 每个事务插入的行数，也是发送进度事件的间隔
+const FAKE_DATA_DEFAULT_BATCH_SIZE: usize = 1_000;
+// 单条 INSERT 的参数个数上限（MySQL 为 65535，SQLite 为 32766）
+const FAKE_DATA_MAX_PARAMS: usize = 30_000;
+// 外键列从被引用表中取样的值个数
+const FAKE_DATA_FOREIGN_KEY_SAMPLE: usize = 1_000;
+
+const FIRST_NAMES: &[&str] = &[
+    "James",
+    "Mary",
+    "John",
+    "Patricia",
+    "Robert",
+    "Jennifer",
+    "Michael",
+    "Linda",
+    "David",
+    "Elizabeth",
+    "William",
+    "Susan",
+    "Richard",
+    "Jessica",
+    "Thomas",
+    "Sarah",
+    "Daniel",
+    "Karen",
+    "Matthew",
+    "Emily",
+    "Wei",
+    "Li",
+    "Hiroshi",
+    "Yuki",
+    "Carlos",
+    "Sofia",
+    "Ahmed",
+    "Fatima",
+];
+const LAST_NAMES: &[&str] = &[
+    "Smith",
+    "Johnson",
+    "Williams",
+    "Brown",
+    "Jones",
+    "Garcia",
+    "Miller",
+    "Davis",
+    "Rodriguez",
+    "Martinez",
+    "Wilson",
+    "Anderson",
+    "Taylor",
+    "Thomas",
+    "Moore",
+    "Martin",
+    "Lee",
+    "Thompson",
+    "White",
+    "Harris",
+    "Wang",
+    "Zhang",
+    "Chen",
+    "Tanaka",
+    "Sato",
+    "Kim",
+    "Silva",
+    "Khan",
+];
+const CITIES: &[&str] = &[
+    "New York",
+    "London",
+    "Paris",
+    "Tokyo",
+    "Shanghai",
+    "Berlin",
+    "Sydney",
+    "Toronto",
+    "Madrid",
+    "Rome",
+    "Seoul",
+    "Singapore",
+    "Chicago",
+    "Amsterdam",
+    "Stockholm",
+    "Dubai",
+    "Mumbai",
+    "São Paulo",
+    "Mexico City",
+    "Cape Town",
+];
+const COUNTRIES: &[&str] = &[
+    "United States",
+    "United Kingdom",
+    "France",
+    "Japan",
+    "China",
+    "Germany",
+    "Australia",
+    "Canada",
+    "Spain",
+    "Italy",
+    "South Korea",
+    "Singapore",
+    "Netherlands",
+    "Sweden",
+    "India",
+    "Brazil",
+    "Mexico",
+    "South Africa",
+];
+const COMPANY_SUFFIXES: &[&str] = &["Inc", "LLC", "Ltd", "Group", "Holdings", "Labs", "Co"];
+const STREETS: &[&str] = &[
+    "Main St", "Oak Ave", "Maple Rd", "Park Ln", "Cedar St", "Elm St", "Pine Ave", "Lake Dr",
+    "Hill Rd", "River Rd",
+];
+const DOMAINS: &[&str] = &["example.com", "example.org", "example.net", "mail.test"];
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "commodo",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FakeDataProgress {
+    pub execution_id: String,
+    pub inserted_rows: u64,
+    pub total_rows: u64,
+    pub done: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FakeDataSummary {
+    pub execution_id: String,
+    pub inserted_rows: u64,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+// splitmix64，生成测试数据不需要密码学强度的随机数
+struct FakeRng(u64);
+
+impl FakeRng {
+    fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // [min, max] 闭区间
+    fn range(&mut self, min: i64, max: i64) -> i64 {
+        if max <= min {
+            return min;
+        }
+        let span = (max - min) as u64 + 1;
+        min + (self.next_u64() % span) as i64
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next_u64() as usize % items.len()]
+    }
+}
+
+enum Generator {
+    Skip,
+    Null,
+    Constant(Value),
+    Values(Vec<Value>),
+    Sequence(i64),
+    Integer(i64, i64),
+    Decimal(f64, f64, u32),
+    Boolean,
+    Date(NaiveDateTime, NaiveDateTime),
+    DateTime(NaiveDateTime, NaiveDateTime),
+    Time,
+    Name,
+    FirstName,
+    LastName,
+    Email,
+    Phone,
+    City,
+    Country,
+    Company,
+    Address,
+    Url,
+    Uuid,
+    Words(usize),
+    Json,
+}
+
+struct ColumnPlan {
+    name: String,
+    generator: Generator,
+    null_ratio: f64,
+    max_length: Option<usize>,
+    // 唯一列的文本值追加随机后缀，降低冲突概率
+    unique: bool,
+}
+
+impl ColumnPlan {
+    fn generate(&mut self, rng: &mut FakeRng) -> Value {
+        if self.null_ratio > 0.0 && rng.unit() < self.null_ratio {
+            return Value::Null;
+        }
+        let value = match &mut self.generator {
+            Generator::Skip | Generator::Null => return Value::Null,
+            Generator::Constant(value) => return value.clone(),
+            Generator::Values(values) => return rng.pick(values).clone(),
+            Generator::Sequence(next) => {
+                *next += 1;
+                return json!(*next - 1);
+            }
+            Generator::Integer(min, max) => return json!(rng.range(*min, *max)),
+            Generator::Decimal(min, max, scale) => {
+                let factor = 10f64.powi(*scale as i32);
+                let value = *min + rng.unit() * (*max - *min);
+                return json!((value * factor).round() / factor);
+            }
+            Generator::Boolean => return json!(rng.next_u64().is_multiple_of(2)),
+            Generator::Date(start, end) => random_datetime(rng, *start, *end)
+                .format("%Y-%m-%d")
+                .to_string(),
+            Generator::DateTime(start, end) => random_datetime(rng, *start, *end)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            Generator::Time => format!(
+                "{:02}:{:02}:{:02}",
+                rng.range(0, 23),
+                rng.range(0, 59),
+                rng.range(0, 59)
+            ),
+            Generator::Name => format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES)),
+            Generator::FirstName => rng.pick(FIRST_NAMES).to_string(),
+            Generator::LastName => rng.pick(LAST_NAMES).to_string(),
+            Generator::Email => format!(
+                "{}.{}{}@{}",
+                rng.pick(FIRST_NAMES).to_lowercase(),
+                rng.pick(LAST_NAMES).to_lowercase(),
+                rng.range(1, 99_999),
+                rng.pick(DOMAINS)
+            ),
+            Generator::Phone => format!(
+                "+1-{:03}-{:03}-{:04}",
+                rng.range(200, 999),
+                rng.range(200, 999),
+                rng.range(0, 9999)
+            ),
+            Generator::City => rng.pick(CITIES).to_string(),
+            Generator::Country => rng.pick(COUNTRIES).to_string(),
+            Generator::Company => {
+                format!("{} {}", rng.pick(LAST_NAMES), rng.pick(COMPANY_SUFFIXES))
+            }
+            Generator::Address => format!("{} {}", rng.range(1, 9999), rng.pick(STREETS)),
+            Generator::Url => format!(
+                "https://{}.{}/{}",
+                rng.pick(WORDS),
+                rng.pick(DOMAINS),
+                rng.pick(WORDS)
+            ),
+            Generator::Uuid => {
+                let (high, low) = (rng.next_u64(), rng.next_u64());
+                format!(
+                    "{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}",
+                    high >> 32,
+                    (high >> 16) & 0xffff,
+                    high & 0x0fff,
+                    ((low >> 48) & 0x3fff) | 0x8000,
+                    low & 0xffff_ffff_ffff
+                )
+            }
+            Generator::Words(count) => {
+                let count = rng.range(1, *count as i64) as usize;
+                let mut text = (0..count)
+                    .map(|_| *rng.pick(WORDS))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if let Some(first) = text.get(..1) {
+                    text = first.to_uppercase() + &text[1..];
+                }
+                text
+            }
+            Generator::Json => {
+                json!({ rng.pick(WORDS).to_string(): rng.range(0, 1000) }).to_string()
+            }
+        };
+        let value = match self.unique {
+            true => format!("{}-{:x}", value, rng.next_u64() & 0xff_ffff),
+            false => value,
+        };
+        // 超长时从末尾截断；唯一列保留后缀
+        let value = match self.max_length {
+            Some(max) if value.chars().count() > max => {
+                let skip = value.chars().count() - max;
+                match self.unique {
+                    true => value.chars().skip(skip).collect(),
+                    false => value.chars().take(max).collect(),
+                }
+            }
+            _ => value,
+        };
+        Value::String(value)
+    }
+}
+
+fn random_datetime(rng: &mut FakeRng, start: NaiveDateTime, end: NaiveDateTime) -> NaiveDateTime {
+    let seconds = (end - start).num_seconds().max(0);
+    start + Duration::seconds(rng.range(0, seconds))
+}
+
+fn parse_datetime(text: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })
+        .map_err(|_| format!("Invalid date: {}", text))
+}
+
+// 默认日期范围：最近五年
+fn date_range(rule: Option<&FakeColumnRule>) -> Result<(NaiveDateTime, NaiveDateTime), String> {
+    let now = Local::now().naive_local();
+    let start = match rule.and_then(|r| r.start.as_deref()) {
+        Some(start) => parse_datetime(start)?,
+        None => now - Duration::days(5 * 365),
+    };
+    let end = match rule.and_then(|r| r.end.as_deref()) {
+        Some(end) => parse_datetime(end)?,
+        None => now,
+    };
+    Ok((start, end))
+}
+
+// 表中一列的信息，供推断生成规则
+struct ColumnMeta {
+    name: String,
+    // 小写的类型名，如 varchar、int、decimal
+    data_type: String,
+    // MySQL 的 COLUMN_TYPE（如 tinyint(1)、enum('a','b')），SQLite 为声明的类型
+    column_type: String,
+    nullable: bool,
+    // 自增 / 生成列 / SQLite 的 INTEGER PRIMARY KEY，不写入
+    generated: bool,
+    primary_key: bool,
+    unique: bool,
+    has_default: bool,
+    max_length: Option<u64>,
+    precision: Option<u64>,
+    scale: Option<u64>,
+    // (库, 表, 列)
+    reference: Option<(Option<String>, String, String)>,
+}
+
+// 解析 enum('a','b') / set('a','b') 的候选值
+pub fn enum_values(column_type: &str) -> Vec<Value> {
+    let inner = column_type
+        .split_once('(')
+        .and_then(|(_, rest)| rest.rsplit_once(')'))
+        .map_or("", |(inner, _)| inner);
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('\'', true) if chars.peek() == Some(&'\'') => {
+                chars.next();
+                current.push('\'');
+            }
+            ('\'', true) => {
+                quoted = false;
+                values.push(Value::String(std::mem::take(&mut current)));
+            }
+            ('\'', false) => quoted = true,
+            (c, true) => current.push(c),
+            _ => {}
+        }
+    }
+    values
+}
+
+fn integer_range(data_type: &str, column_type: &str) -> (i64, i64) {
+    let unsigned = column_type.contains("unsigned");
+    match data_type {
+        "tinyint" if unsigned => (0, 255),
+        "tinyint" => (0, 127),
+        "smallint" => (0, 32_767),
+        "year" => (1990, 2030),
+        _ => (0, 1_000_000),
+    }
+}
+
+fn name_has(name: &str, parts: &[&str]) -> bool {
+    parts.iter().any(|part| name.contains(part))
+}
+
+// 未指定规则的列：先看外键和主键，再按列名猜语义，最后按类型生成
+fn infer_generator(column: &ColumnMeta) -> Option<Generator> {
+    let name = column.name.to_lowercase();
+    let data_type = column.data_type.as_str();
+    let is_text = matches!(
+        data_type,
+        "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext"
+    );
+    let is_integer = matches!(
+        data_type,
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint"
+    );
+    if column.generated {
+        return Some(Generator::Skip);
+    }
+    if column.primary_key && is_text {
+        return Some(Generator::Uuid);
+    }
+    if is_text {
+        let generator = if name_has(&name, &["email", "mail"]) {
+            Generator::Email
+        } else if name_has(&name, &["first_name", "firstname", "given_name"]) {
+            Generator::FirstName
+        } else if name_has(&name, &["last_name", "lastname", "surname", "family_name"]) {
+            Generator::LastName
+        } else if name_has(&name, &["company", "organization", "organisation"]) {
+            Generator::Company
+        } else if name_has(&name, &["phone", "mobile", "tel"]) {
+            Generator::Phone
+        } else if name_has(&name, &["city"]) {
+            Generator::City
+        } else if name_has(&name, &["country"]) {
+            Generator::Country
+        } else if name_has(&name, &["address", "street"]) {
+            Generator::Address
+        } else if name_has(&name, &["url", "website", "link"]) {
+            Generator::Url
+        } else if name_has(&name, &["uuid", "guid"]) {
+            Generator::Uuid
+        } else if name_has(&name, &["name", "user", "author"]) {
+            Generator::Name
+        } else if data_type.ends_with("text") {
+            Generator::Words(30)
+        } else {
+            Generator::Words(4)
+        };
+        return Some(generator);
+    }
+    if column.column_type.starts_with("tinyint(1)") || column.column_type == "bit(1)" {
+        return Some(Generator::Boolean);
+    }
+    if is_integer {
+        if name_has(&name, &["age"]) {
+            return Some(Generator::Integer(18, 80));
+        }
+        if name_has(&name, &["quantity", "qty", "count", "stock"]) {
+            return Some(Generator::Integer(0, 100));
+        }
+        let (min, max) = integer_range(data_type, &column.column_type);
+        return Some(Generator::Integer(min, max));
+    }
+    let generator = match data_type {
+        "decimal" | "numeric" | "float" | "double" | "real" => {
+            let scale = column.scale.unwrap_or(2).min(6) as u32;
+            // decimal(p, s) 的整数部分最多 p - s 位
+            let digits = column
+                .precision
+                .map_or(4, |precision| precision.saturating_sub(u64::from(scale)));
+            let max = (10f64.powi(digits.min(4) as i32) - 1.0).max(0.0);
+            Generator::Decimal(0.0, max, scale)
+        }
+        "bool" | "boolean" | "bit" => Generator::Boolean,
+        "date" => {
+            let (start, end) = date_range(None).ok()?;
+            Generator::Date(start, end)
+        }
+        "datetime" | "timestamp" => {
+            let (start, end) = date_range(None).ok()?;
+            Generator::DateTime(start, end)
+        }
+        "time" => Generator::Time,
+        "year" => Generator::Integer(1990, 2030),
+        "enum" | "set" => Generator::Values(enum_values(&column.column_type)),
+        "json" => Generator::Json,
+        // 二进制、空间等类型交给默认值或 NULL
+        _ => return None,
+    };
+    Some(generator)
+}
+
+fn rule_generator(rule: &FakeColumnRule) -> Result<Option<Generator>, String> {
+    let generator = match rule.kind.as_str() {
+        "skip" => Generator::Skip,
+        "null" => Generator::Null,
+        "constant" => Generator::Constant(rule.value.clone().unwrap_or(Value::Null)),
+        "values" => match rule.values.clone().filter(|values| !values.is_empty()) {
+            Some(values) => Generator::Values(values),
+            None => return Err("Rule values requires at least one value".to_string()),
+        },
+        "sequence" => Generator::Sequence(rule.min.unwrap_or(1.0) as i64),
+        "integer" => Generator::Integer(
+            rule.min.unwrap_or(0.0) as i64,
+            rule.max.unwrap_or(1_000_000.0) as i64,
+        ),
+        "decimal" => Generator::Decimal(rule.min.unwrap_or(0.0), rule.max.unwrap_or(1_000.0), 2),
+        "boolean" => Generator::Boolean,
+        "date" => {
+            let (start, end) = date_range(Some(rule))?;
+            Generator::Date(start, end)
+        }
+        "datetime" => {
+            let (start, end) = date_range(Some(rule))?;
+            Generator::DateTime(start, end)
+        }
+        "time" => Generator::Time,
+        "name" => Generator::Name,
+        "first_name" => Generator::FirstName,
+        "last_name" => Generator::LastName,
+        "email" => Generator::Email,
+        "phone" => Generator::Phone,
+        "city" => Generator::City,
+        "country" => Generator::Country,
+        "company" => Generator::Company,
+        "address" => Generator::Address,
+        "url" => Generator::Url,
+        "uuid" => Generator::Uuid,
+        "word" => Generator::Words(1),
+        "sentence" => Generator::Words(12),
+        "paragraph" => Generator::Words(60),
+        // 外键在取样后生成
+        "foreign_key" => return Ok(None),
+        other => return Err(format!("Unsupported rule kind: {}", other)),
+    };
+    Ok(Some(generator))
+}
+
+// 按 kind 生成一个假值；seed 相同时结果相同（导出脱敏时同一原值总是替换为同一假值）
+pub fn fake_value(kind: &str, seed: u64) -> Result<Value, String> {
+    let rule = FakeColumnRule {
+        kind: kind.to_string(),
+        ..Default::default()
+    };
+    let generator =
+        rule_generator(&rule)?.ok_or_else(|| format!("Unsupported fake data kind: {}", kind))?;
+    let mut plan = ColumnPlan {
+        name: String::new(),
+        generator,
+        null_ratio: 0.0,
+        max_length: None,
+        unique: false,
+    };
+    Ok(plan.generate(&mut FakeRng(seed)))
+}
+
+enum FakeConnection {
+    MySql(PoolConnection<MySql>, TimestampDisplay),
+    Sqlite(PoolConnection<Sqlite>),
+}
+
+impl FakeConnection {
+    fn quote(&self, name: &str) -> String {
+        let flavor = match self {
+            FakeConnection::MySql(..) => SqlFlavor::MySql,
+            FakeConnection::Sqlite(_) => SqlFlavor::Sqlite,
+        };
+        quote_identifier(name, flavor)
+    }
+
+    fn table_ref(&self, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            Some(schema) => format!("{}.{}", self.quote(schema), self.quote(table)),
+            None => self.quote(table),
+        }
+    }
+
+    async fn columns(&mut self, table: &str) -> Result<Vec<ColumnMeta>, String> {
+        match self {
+            FakeConnection::MySql(conn, _) => mysql_columns(conn, table).await,
+            FakeConnection::Sqlite(conn) => sqlite_columns(conn, table).await,
+        }
+    }
+
+    // 查询结果第一列的全部值
+    async fn first_column(&mut self, sql: &str) -> Result<Vec<Value>, String> {
+        let rows: Vec<Map<String, Value>> = match self {
+            FakeConnection::MySql(conn, timestamp_display) => sqlx::query(sql)
+                .fetch_all(&mut **conn)
+                .await
+                .map_err(|e| e.to_string())?
+                .iter()
+                .map(|row| mysql_manager::row_to_json(row, *timestamp_display))
+                .collect(),
+            FakeConnection::Sqlite(conn) => sqlx::query(sql)
+                .fetch_all(&mut **conn)
+                .await
+                .map_err(|e| e.to_string())?
+                .iter()
+                .map(sqlite_manager::row_to_json)
+                .collect(),
+        };
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| row.into_iter().next().map(|(_, value)| value))
+            .collect())
+    }
+
+    // 在一个事务中插入一批行，每条 INSERT 按参数上限拆分
+    async fn insert_batch(
+        &mut self,
+        prefix: &str,
+        placeholders: &str,
+        rows: &[Vec<Value>],
+    ) -> Result<u64, String> {
+        let width = rows.first().map_or(1, Vec::len).max(1);
+        let per_statement = (FAKE_DATA_MAX_PARAMS / width).max(1);
+        let mut inserted = 0;
+        match self {
+            FakeConnection::MySql(conn, _) => {
+                let mut tx = conn
+                    .begin()
+                    .await
+                    .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+                for chunk in rows.chunks(per_statement) {
+                    let sql = format!("{}{}", prefix, vec![placeholders; chunk.len()].join(", "));
+                    let mut query = sqlx::query(&sql);
+                    for value in chunk.iter().flatten() {
+                        query = mysql_manager::bind_json_value(query, value);
+                    }
+                    inserted += query
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| format!("Failed to insert rows: {}", e))?
+                        .rows_affected();
+                }
+                tx.commit()
+                    .await
+                    .map_err(|e| format!("Failed to commit batch: {}", e))?;
+            }
+            FakeConnection::Sqlite(conn) => {
+                let mut tx = conn
+                    .begin()
+                    .await
+                    .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+                for chunk in rows.chunks(per_statement) {
+                    let sql = format!("{}{}", prefix, vec![placeholders; chunk.len()].join(", "));
+                    let mut query = sqlx::query(&sql);
+                    for value in chunk.iter().flatten() {
+                        query = sqlite_manager::bind_json_value(query, value);
+                    }
+                    inserted += query
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| format!("Failed to insert rows: {}", e))?
+                        .rows_affected();
+                }
+                tx.commit()
+                    .await
+                    .map_err(|e| format!("Failed to commit batch: {}", e))?;
+            }
+        }
+        Ok(inserted)
+    }
+}
+
+async fn mysql_columns(
+    conn: &mut PoolConnection<MySql>,
+    table: &str,
+) -> Result<Vec<ColumnMeta>, String> {
+    let rows = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR), CAST(DATA_TYPE AS CHAR), CAST(COLUMN_TYPE AS CHAR), \
+         CAST(IS_NULLABLE AS CHAR), CAST(COLUMN_KEY AS CHAR), CAST(EXTRA AS CHAR), \
+         COLUMN_DEFAULT IS NOT NULL, CHARACTER_MAXIMUM_LENGTH, NUMERIC_PRECISION, NUMERIC_SCALE \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let references = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR), CAST(REFERENCED_TABLE_SCHEMA AS CHAR), \
+         CAST(REFERENCED_TABLE_NAME AS CHAR), CAST(REFERENCED_COLUMN_NAME AS CHAR) \
+         FROM information_schema.KEY_COLUMN_USAGE \
+         WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND REFERENCED_TABLE_NAME IS NOT NULL",
+    )
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+    let mut references: HashMap<String, (Option<String>, String, String)> = references
+        .iter()
+        .filter_map(|row| {
+            Some((
+                row.try_get_unchecked(0).ok()?,
+                (
+                    row.try_get_unchecked(1).ok()?,
+                    row.try_get_unchecked(2).ok()?,
+                    row.try_get_unchecked(3).ok()?,
+                ),
+            ))
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            let name: String = row.try_get_unchecked(0).map_err(|e| e.to_string())?;
+            let data_type: String = row.try_get_unchecked(1).map_err(|e| e.to_string())?;
+            let column_type: String = row.try_get_unchecked(2).map_err(|e| e.to_string())?;
+            let nullable: String = row.try_get_unchecked(3).map_err(|e| e.to_string())?;
+            let key: String = row.try_get_unchecked(4).map_err(|e| e.to_string())?;
+            let extra: String = row.try_get_unchecked(5).map_err(|e| e.to_string())?;
+            let extra = extra.to_lowercase();
+            Ok(ColumnMeta {
+                reference: references.remove(&name),
+                name,
+                data_type: data_type.to_lowercase(),
+                column_type: column_type.to_lowercase(),
+                nullable: nullable == "YES",
+                generated: extra.contains("auto_increment") || extra.contains("generated"),
+                primary_key: key == "PRI",
+                unique: key == "UNI",
+                has_default: row.try_get_unchecked::<i64, _>(6).unwrap_or(0) != 0,
+                max_length: row.try_get_unchecked::<Option<u64>, _>(7).ok().flatten(),
+                precision: row.try_get_unchecked::<Option<u64>, _>(8).ok().flatten(),
+                scale: row.try_get_unchecked::<Option<u64>, _>(9).ok().flatten(),
+            })
+        })
+        .collect()
+}
+
+// SQLite 的类型名按亲和性规则归到 MySQL 风格的类型名上，以便共用推断逻辑
+pub fn sqlite_data_type(declared: &str) -> String {
+    let declared = declared.to_lowercase();
+    let data_type = if declared.contains("bool") {
+        "boolean"
+    } else if declared.contains("datetime") || declared.contains("timestamp") {
+        "datetime"
+    } else if declared.contains("date") {
+        "date"
+    } else if declared.contains("time") {
+        "time"
+    } else if declared.contains("json") {
+        "json"
+    } else if declared.contains("int") {
+        "integer"
+    } else if declared.contains("char") || declared.contains("clob") || declared.is_empty() {
+        "varchar"
+    } else if declared.contains("text") {
+        "text"
+    } else if declared.contains("real") || declared.contains("floa") || declared.contains("doub") {
+        "double"
+    } else if declared.contains("dec") || declared.contains("num") {
+        "decimal"
+    } else {
+        "blob"
+    };
+    data_type.to_string()
+}
+
+async fn sqlite_columns(
+    conn: &mut PoolConnection<Sqlite>,
+    table: &str,
+) -> Result<Vec<ColumnMeta>, String> {
+    let rows = sqlx::query(
+        "SELECT name, type, \"notnull\", dflt_value IS NOT NULL, pk, hidden \
+         FROM pragma_table_xinfo(?) ORDER BY cid",
+    )
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let references =
+        sqlx::query("SELECT \"from\", \"table\", \"to\" FROM pragma_foreign_key_list(?)")
+            .bind(table)
+            .fetch_all(&mut **conn)
+            .await
+            .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+    let mut references: HashMap<String, (Option<String>, String, String)> = references
+        .iter()
+        .filter_map(|row| {
+            let to: Option<String> = row.try_get(2).ok()?;
+            Some((row.try_get(0).ok()?, (None, row.try_get(1).ok()?, to?)))
+        })
+        .collect();
+    let key_count = rows
+        .iter()
+        .filter(|row| row.try_get::<i64, _>(4).unwrap_or(0) > 0)
+        .count();
+
+    rows.iter()
+        .map(|row| {
+            let name: String = row.try_get(0).map_err(|e| e.to_string())?;
+            let declared: String = row.try_get(1).unwrap_or_default();
+            let primary_key = row.try_get::<i64, _>(4).unwrap_or(0) > 0;
+            // INTEGER PRIMARY KEY 是 rowid 的别名，自动分配；hidden 非 0 为生成列
+            let rowid_alias =
+                primary_key && key_count == 1 && declared.eq_ignore_ascii_case("integer");
+            let hidden = row.try_get::<i64, _>(5).unwrap_or(0) != 0;
+            Ok(ColumnMeta {
+                reference: references.remove(&name),
+                name,
+                data_type: sqlite_data_type(&declared),
+                column_type: declared.to_lowercase(),
+                nullable: row.try_get::<i64, _>(2).unwrap_or(0) == 0,
+                generated: rowid_alias || hidden,
+                primary_key,
+                unique: false,
+                has_default: row.try_get::<i64, _>(3).unwrap_or(0) != 0,
+                max_length: None,
+                precision: None,
+                scale: None,
+            })
+        })
+        .collect()
+}
+
+// 为每列确定生成方式：用户规则优先；外键从被引用表取样；非自增的整数主键从当前最大值往后递增
+async fn plan_columns(
+    connection: &mut FakeConnection,
+    table: &str,
+    rules: &HashMap<String, FakeColumnRule>,
+) -> Result<Vec<ColumnPlan>, String> {
+    let columns = connection.columns(table).await?;
+    if columns.is_empty() {
+        return Err(format!("Table {} not found", table));
+    }
+    if let Some(unknown) = rules
+        .keys()
+        .find(|name| !columns.iter().any(|column| &column.name == *name))
+    {
+        return Err(format!("Column {} not found in {}", unknown, table));
+    }
+
+    let mut plans = Vec::new();
+    for column in columns {
+        let rule = rules.get(&column.name);
+        let mut generator = match rule {
+            Some(rule) => rule_generator(rule)?,
+            None => None,
+        };
+        let reference = match rule.and_then(|rule| rule.reference.as_deref()) {
+            Some(reference) => match reference.rsplit_once('.') {
+                Some((table, column)) => Some((None, table.to_string(), column.to_string())),
+                None => return Err(format!("Invalid foreign key reference: {}", reference)),
+            },
+            None if generator.is_none() && !column.generated => column.reference.clone(),
+            None => None,
+        };
+        if let (None, Some((schema, ref_table, ref_column))) = (&generator, reference) {
+            let sql = format!(
+                "SELECT DISTINCT {0} FROM {1} WHERE {0} IS NOT NULL LIMIT {2}",
+                connection.quote(&ref_column),
+                connection.table_ref(schema.as_deref(), &ref_table),
+                FAKE_DATA_FOREIGN_KEY_SAMPLE
+            );
+            let values = connection
+                .first_column(&sql)
+                .await
+                .map_err(|e| format!("Failed to sample {}.{}: {}", ref_table, ref_column, e))?;
+            generator = Some(match (values.is_empty(), column.nullable) {
+                (false, _) => Generator::Values(values),
+                (true, true) => Generator::Null,
+                (true, false) => {
+                    return Err(format!(
+                        "Referenced table {} has no rows to sample for column {}",
+                        ref_table, column.name
+                    ))
+                }
+            });
+        }
+        if generator.is_none() && rule.is_some() {
+            return Err(format!(
+                "Column {} needs a reference for the foreign_key rule",
+                column.name
+            ));
+        }
+        let sequential = !column.generated
+            && (column.primary_key || column.unique)
+            && matches!(
+                column.data_type.as_str(),
+                "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint"
+            );
+        if generator.is_none() && sequential {
+            let sql = format!(
+                "SELECT COALESCE(MAX({}), 0) FROM {}",
+                connection.quote(&column.name),
+                connection.quote(table)
+            );
+            let max = connection
+                .first_column(&sql)
+                .await?
+                .first()
+                .and_then(|value| match value {
+                    Value::String(text) => text.parse().ok(),
+                    value => value.as_i64(),
+                })
+                .unwrap_or(0);
+            generator = Some(Generator::Sequence(max + 1));
+        }
+        let generator = match generator.or_else(|| infer_generator(&column)) {
+            Some(generator) => generator,
+            // 无法生成的可空列或有默认值的列不写入
+            None if column.nullable || column.has_default => Generator::Skip,
+            None => {
+                return Err(format!(
+                    "Cannot generate values for column {} ({}); add a rule for it",
+                    column.name, column.column_type
+                ))
+            }
+        };
+        let text_value = !matches!(
+            generator,
+            Generator::Skip
+                | Generator::Null
+                | Generator::Constant(_)
+                | Generator::Values(_)
+                | Generator::Sequence(_)
+                | Generator::Integer(..)
+                | Generator::Decimal(..)
+                | Generator::Boolean
+        );
+        plans.push(ColumnPlan {
+            null_ratio: rule
+                .and_then(|rule| rule.null_ratio)
+                .filter(|_| column.nullable)
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0),
+            max_length: column.max_length.map(|length| length as usize),
+            unique: text_value
+                && (column.unique || column.primary_key)
+                && !matches!(generator, Generator::Uuid),
+            name: column.name,
+            generator,
+        });
+    }
+    plans.retain(|plan| !matches!(plan.generator, Generator::Skip));
+    if plans.is_empty() {
+        return Err(format!("No columns to generate in {}", table));
+    }
+    Ok(plans)
+}
+
+fn emit_progress(app_state: &AppState, progress: &FakeDataProgress) {
+    if let Some(app) = app_state.app_handle.as_ref() {
+        let _ = app.emit(FAKE_DATA_PROGRESS_EVENT, progress.clone());
+    }
+}
+
+// 向表中插入 row_count 行测试数据。column_rules 按列名指定生成规则，其余列按列名（email、name、
+// phone 等）、类型和外键推断，外键值从被引用表中取样。按 batch_size 分批在事务中插入，每批提交后
+// 发送 fake-data-progress 事件；通过 cancel_query(execution_id) 取消，已提交的批次保留
+#[command]
+pub async fn generate_fake_data(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    row_count: u64,
+    column_rules: Option<HashMap<String, FakeColumnRule>>,
+    db_name: Option<String>,
+    execution_id: Option<String>,
+    batch_size: Option<usize>,
+) -> Result<FakeDataSummary, String> {
+    ensure_writable(&db_state, connection_id).await?;
+    if row_count == 0 {
+        return Err("row_count must be greater than 0".to_string());
+    }
+    let flavor = connection_flavor(&db_state, connection_id, "Generating data").await?;
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let mut connection = match flavor {
+        SqlFlavor::MySql => {
+            let conn =
+                mysql_manager::acquire_connection(&app_state, &db_state, connection_id, db_name)
+                    .await?;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(&app_state, connection_id).await;
+            FakeConnection::MySql(conn, timestamp_display)
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            FakeConnection::Sqlite(conn)
+        }
+    };
+
+    let mut plans =
+        plan_columns(&mut connection, &table, &column_rules.unwrap_or_default()).await?;
+    let prefix = format!(
+        "INSERT INTO {} ({}) VALUES ",
+        connection.quote(&table),
+        plans
+            .iter()
+            .map(|plan| connection.quote(&plan.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let placeholders = format!("({})", vec!["?"; plans.len()].join(", "));
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(execution_id.clone(), cancel.clone());
+    let batch_size = batch_size.unwrap_or(FAKE_DATA_DEFAULT_BATCH_SIZE).max(1) as u64;
+    let mut progress = FakeDataProgress {
+        execution_id: execution_id.clone(),
+        inserted_rows: 0,
+        total_rows: row_count,
+        done: false,
+    };
+    let started = Instant::now();
+    let mut rng = FakeRng::new();
+    let mut outcome = Ok(());
+    while progress.inserted_rows < row_count && !cancel.load(Ordering::Relaxed) {
+        let count = batch_size.min(row_count - progress.inserted_rows);
+        let rows: Vec<Vec<Value>> = (0..count)
+            .map(|_| {
+                plans
+                    .iter_mut()
+                    .map(|plan| plan.generate(&mut rng))
+                    .collect()
+            })
+            .collect();
+        match connection.insert_batch(&prefix, &placeholders, &rows).await {
+            Ok(_) => progress.inserted_rows += count,
+            Err(e) => {
+                outcome = Err(e);
+                break;
+            }
+        }
+        emit_progress(&app_state, &progress);
+    }
+
+    app_state.cancel_flags.lock().await.remove(&execution_id);
+    if progress.inserted_rows > 0 {
+        invalidate_results(&app_state, connection_id).await;
+    }
+    progress.done = true;
+    emit_progress(&app_state, &progress);
+    outcome?;
+
+    Ok(FakeDataSummary {
+        execution_id,
+        inserted_rows: progress.inserted_rows,
+        cancelled: progress.inserted_rows < row_count,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,486 @@
+use crate::alter_table::quote_identifier;
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::{ensure_sql_allowed, ensure_writable};
+use crate::models::{FindReplaceArgs, FindReplaceMatch, FindReplaceResult, TimestampDisplay};
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::result_cache::invalidate_results;
+use crate::sql_classifier::{single_table_write, SqlFlavor, WriteTarget};
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde_json::{Map, Value};
+use sqlx::{Connection as _, MySqlConnection, Row, SqliteConnection};
+use std::time::Instant;
+use tauri::{command, State};
+
+// 每批读取的行数；执行时每批在一个事务中更新
+const FIND_REPLACE_BATCH_SIZE: usize = 500;
+const FIND_REPLACE_DEFAULT_SAMPLE_SIZE: u32 = 50;
+const FIND_REPLACE_MAX_SAMPLE_SIZE: u32 = 500;
+
+struct Replacer {
+    regex: Regex,
+    replace: String,
+    // regex 模式下展开 $1 等分组引用，其余模式按原样替换
+    expand: bool,
+}
+
+impl Replacer {
+    fn new(args: &FindReplaceArgs) -> Result<Self, String> {
+        if args.find.is_empty() {
+            return Err("Search text is required".to_string());
+        }
+        let (pattern, case_insensitive, expand) = match args.mode.as_deref().unwrap_or("plain") {
+            "plain" => (regex::escape(&args.find), false, false),
+            "case_insensitive" => (regex::escape(&args.find), true, false),
+            "regex" => (args.find.clone(), false, true),
+            other => return Err(format!("Unsupported match mode: {}", other)),
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| format!("Invalid regular expression: {}", e))?;
+        Ok(Self {
+            regex,
+            replace: args.replace.clone(),
+            expand,
+        })
+    }
+
+    // 不包含匹配内容时返回 None
+    fn apply(&self, text: &str) -> Option<String> {
+        if !self.regex.is_match(text) {
+            return None;
+        }
+        let replaced = match self.expand {
+            true => self.regex.replace_all(text, self.replace.as_str()),
+            false => self.regex.replace_all(text, NoExpand(&self.replace)),
+        };
+        Some(replaced.into_owned())
+    }
+}
+
+// 借助单表 UPDATE 的解析结果得到表引用和过滤条件，同时保证 where_clause 中没有夹带其他语句
+fn write_target(args: &FindReplaceArgs, flavor: SqlFlavor) -> Result<WriteTarget, String> {
+    let table = args
+        .table
+        .split('.')
+        .map(|part| quote_identifier(part.trim(), flavor))
+        .collect::<Vec<_>>()
+        .join(".");
+    let column = quote_identifier(&args.column, flavor);
+    let mut sql = format!("UPDATE {} SET {} = {}", table, column, column);
+    if let Some(condition) = args
+        .where_clause
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    {
+        sql.push_str(&format!(" WHERE {}", condition));
+    }
+    single_table_write(&sql, flavor)
+        .filter(|target| target.operation == "UPDATE")
+        .ok_or_else(|| "The WHERE clause is not a valid row filter".to_string())
+}
+
+// 按主键分批读取：WHERE 列非空 [AND 条件] [AND 预过滤] [AND (主键) > (上一批最后一行)]
+fn batch_sql(
+    args: &FindReplaceArgs,
+    target: &WriteTarget,
+    key_columns: &[String],
+    flavor: SqlFlavor,
+    after_key: bool,
+) -> String {
+    let column = quote_identifier(&args.column, flavor);
+    let keys = key_columns
+        .iter()
+        .map(|key| quote_identifier(key, flavor))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut conditions = vec![format!("{} IS NOT NULL", column)];
+    if let Some(selection) = &target.selection {
+        conditions.push(format!("({})", selection));
+    }
+    // 普通模式先在服务端按子串过滤（不区分大小写的排序规则下结果会多一些，由客户端再精确匹配）
+    if args.mode.as_deref().unwrap_or("plain") == "plain" {
+        conditions.push(match flavor {
+            SqlFlavor::MySql => format!("LOCATE(?, {}) > 0", column),
+            SqlFlavor::Sqlite => format!("instr({}, ?) > 0", column),
+        });
+    }
+    if after_key {
+        let placeholders = vec!["?"; key_columns.len()].join(", ");
+        conditions.push(format!("({}) > ({})", keys, placeholders));
+    }
+    format!(
+        "SELECT {}, {} FROM {} WHERE {} ORDER BY {} LIMIT {}",
+        keys,
+        column,
+        target.from_clause,
+        conditions.join(" AND "),
+        keys,
+        FIND_REPLACE_BATCH_SIZE
+    )
+}
+
+fn update_sql(
+    args: &FindReplaceArgs,
+    target: &WriteTarget,
+    key_columns: &[String],
+    flavor: SqlFlavor,
+) -> String {
+    let conditions = key_columns
+        .iter()
+        .map(|key| format!("{} = ?", quote_identifier(key, flavor)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    format!(
+        "UPDATE {} SET {} = ? WHERE {}",
+        target.table_ref,
+        quote_identifier(&args.column, flavor),
+        conditions
+    )
+}
+
+async fn mysql_key_columns(
+    conn: &mut MySqlConnection,
+    target: &WriteTarget,
+) -> Result<Vec<String>, String> {
+    let rows = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR) FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? AND COLUMN_KEY = 'PRI' \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(&target.schema)
+    .bind(&target.table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch primary key: {}", e))?;
+    let key_columns: Vec<String> = rows
+        .iter()
+        .filter_map(|row| row.try_get_unchecked(0).ok())
+        .collect();
+    if key_columns.is_empty() {
+        return Err("Find and replace requires a table with a primary key".to_string());
+    }
+    Ok(key_columns)
+}
+
+// 没有主键的表用 rowid 定位行
+async fn sqlite_key_columns(
+    conn: &mut SqliteConnection,
+    target: &WriteTarget,
+) -> Result<Vec<String>, String> {
+    let rows = sqlx::query("SELECT name, pk FROM pragma_table_info(?, ?) WHERE pk > 0 ORDER BY pk")
+        .bind(&target.table)
+        .bind(target.schema.as_deref().unwrap_or("main"))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch primary key: {}", e))?;
+    let key_columns: Vec<String> = rows.iter().filter_map(|row| row.try_get(0).ok()).collect();
+    match key_columns.is_empty() {
+        true => Ok(vec!["rowid".to_string()]),
+        false => Ok(key_columns),
+    }
+}
+
+// 一批行中需要替换的部分，以及用于读取下一批的最后一行主键
+struct Batch {
+    changes: Vec<FindReplaceMatch>,
+    last_key: Option<Map<String, Value>>,
+    len: usize,
+}
+
+fn batch_changes(
+    rows: Vec<Map<String, Value>>,
+    key_columns: &[String],
+    column: &str,
+    replacer: &Replacer,
+) -> Batch {
+    let len = rows.len();
+    let mut last_key = None;
+    let mut changes = Vec::new();
+    for mut row in rows {
+        let before = row.remove(column);
+        let key: Map<String, Value> = key_columns
+            .iter()
+            .map(|key| (key.clone(), row.remove(key).unwrap_or(Value::Null)))
+            .collect();
+        // 只处理文本值
+        if let Some(Value::String(before)) = before {
+            if let Some(after) = replacer.apply(&before) {
+                changes.push(FindReplaceMatch {
+                    key: key.clone(),
+                    before,
+                    after,
+                });
+            }
+        }
+        last_key = Some(key);
+    }
+    Batch {
+        changes,
+        last_key,
+        len,
+    }
+}
+
+// 扫描结果：包含匹配的行数、预览样本、实际修改的行数
+#[derive(Default)]
+struct Scan {
+    matched_rows: u64,
+    sample: Vec<FindReplaceMatch>,
+    updated_rows: u64,
+}
+
+impl Scan {
+    fn add(&mut self, changes: &mut Vec<FindReplaceMatch>, sample_size: usize) {
+        self.matched_rows += changes.len() as u64;
+        let room = sample_size.saturating_sub(self.sample.len());
+        self.sample.extend(changes.drain(..).take(room));
+    }
+}
+
+async fn scan_mysql(
+    conn: &mut MySqlConnection,
+    args: &FindReplaceArgs,
+    target: &WriteTarget,
+    replacer: &Replacer,
+    timestamp_display: TimestampDisplay,
+    sample_size: usize,
+    execute: bool,
+) -> Result<Scan, String> {
+    let key_columns = mysql_key_columns(conn, target).await?;
+    if key_columns.contains(&args.column) {
+        return Err("Find and replace cannot modify primary key columns".to_string());
+    }
+    let update = update_sql(args, target, &key_columns, SqlFlavor::MySql);
+    let mut scan = Scan::default();
+    let mut last_key: Option<Map<String, Value>> = None;
+    loop {
+        let sql = batch_sql(
+            args,
+            target,
+            &key_columns,
+            SqlFlavor::MySql,
+            last_key.is_some(),
+        );
+        let mut query = sqlx::query(&sql);
+        if args.mode.as_deref().unwrap_or("plain") == "plain" {
+            query = query.bind(args.find.as_str());
+        }
+        for value in last_key
+            .iter()
+            .flat_map(|last| key_columns.iter().filter_map(|key| last.get(key)))
+        {
+            query = mysql_manager::bind_json_value(query, value);
+        }
+        let rows = query
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read rows: {}", e))?
+            .iter()
+            .map(|row| mysql_manager::row_to_json(row, timestamp_display))
+            .collect();
+        let mut batch = batch_changes(rows, &key_columns, &args.column, replacer);
+
+        if execute && !batch.changes.is_empty() {
+            let mut tx = conn
+                .begin()
+                .await
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+            for change in &batch.changes {
+                let mut query = sqlx::query(&update).bind(change.after.as_str());
+                // 按主键列的顺序绑定
+                for value in key_columns.iter().filter_map(|key| change.key.get(key)) {
+                    query = mysql_manager::bind_json_value(query, value);
+                }
+                let result = query
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to update row: {}", e))?;
+                scan.updated_rows += result.rows_affected();
+            }
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        }
+        scan.add(&mut batch.changes, sample_size);
+        if batch.len < FIND_REPLACE_BATCH_SIZE {
+            return Ok(scan);
+        }
+        last_key = batch.last_key;
+    }
+}
+
+async fn scan_sqlite(
+    conn: &mut SqliteConnection,
+    args: &FindReplaceArgs,
+    target: &WriteTarget,
+    replacer: &Replacer,
+    sample_size: usize,
+    execute: bool,
+) -> Result<Scan, String> {
+    let key_columns = sqlite_key_columns(conn, target).await?;
+    if key_columns.contains(&args.column) {
+        return Err("Find and replace cannot modify primary key columns".to_string());
+    }
+    let update = update_sql(args, target, &key_columns, SqlFlavor::Sqlite);
+    let mut scan = Scan::default();
+    let mut last_key: Option<Map<String, Value>> = None;
+    loop {
+        let sql = batch_sql(
+            args,
+            target,
+            &key_columns,
+            SqlFlavor::Sqlite,
+            last_key.is_some(),
+        );
+        let mut query = sqlx::query(&sql);
+        if args.mode.as_deref().unwrap_or("plain") == "plain" {
+            query = query.bind(args.find.as_str());
+        }
+        for value in last_key
+            .iter()
+            .flat_map(|last| key_columns.iter().filter_map(|key| last.get(key)))
+        {
+            query = sqlite_manager::bind_json_value(query, value);
+        }
+        let rows = query
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read rows: {}", e))?
+            .iter()
+            .map(sqlite_manager::row_to_json)
+            .collect();
+        let mut batch = batch_changes(rows, &key_columns, &args.column, replacer);
+
+        if execute && !batch.changes.is_empty() {
+            let mut tx = conn
+                .begin()
+                .await
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+            for change in &batch.changes {
+                let mut query = sqlx::query(&update).bind(change.after.as_str());
+                for value in key_columns.iter().filter_map(|key| change.key.get(key)) {
+                    query = sqlite_manager::bind_json_value(query, value);
+                }
+                let result = query
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to update row: {}", e))?;
+                scan.updated_rows += result.rows_affected();
+            }
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        }
+        scan.add(&mut batch.changes, sample_size);
+        if batch.len < FIND_REPLACE_BATCH_SIZE {
+            return Ok(scan);
+        }
+        last_key = batch.last_key;
+    }
+}
+
+// 在某列中查找并替换文本（普通、不区分大小写或正则）。未 confirmed 时只预览，返回包含匹配的
+// 行数和部分行替换前后的值；confirmed 后按主键分批读取，在客户端计算新值并以参数绑定的
+// UPDATE 逐行写回，每批一个事务。中途失败时之前已提交的批次不会回滚
+#[command]
+pub async fn find_replace(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: FindReplaceArgs,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+) -> Result<FindReplaceResult, String> {
+    let execute = confirmed == Some(true);
+    if execute {
+        ensure_writable(&db_state, connection_id).await?;
+    }
+    let flavor = connection_flavor(&db_state, connection_id, "Find and replace").await?;
+    let replacer = Replacer::new(&args)?;
+    let target = write_target(&args, flavor)?;
+    let sample_size = args
+        .sample_size
+        .unwrap_or(FIND_REPLACE_DEFAULT_SAMPLE_SIZE)
+        .min(FIND_REPLACE_MAX_SAMPLE_SIZE) as usize;
+    // 写入历史和做确认检查的代表语句
+    let statement = format!(
+        "UPDATE {} SET {} = ?{}",
+        target.table_ref,
+        quote_identifier(&args.column, flavor),
+        target
+            .selection
+            .as_ref()
+            .map_or(String::new(), |selection| format!(" WHERE {}", selection))
+    );
+    if execute {
+        ensure_sql_allowed(
+            &db_state,
+            connection_id,
+            &statement,
+            confirm_token.as_deref(),
+            true,
+        )
+        .await?;
+    }
+
+    let execution_id = new_execution_id();
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let started = Instant::now();
+    let result = match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                args.db_name.clone(),
+            )
+            .await?;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(&app_state, connection_id).await;
+            scan_mysql(
+                &mut conn,
+                &args,
+                &target,
+                &replacer,
+                timestamp_display,
+                sample_size,
+                execute,
+            )
+            .await
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire connection: {}", e))?;
+            scan_sqlite(&mut conn, &args, &target, &replacer, sample_size, execute).await
+        }
+    };
+    if !execute {
+        let scan = result?;
+        return Ok(FindReplaceResult {
+            matched_rows: scan.matched_rows,
+            sample: scan.sample,
+            updated_rows: None,
+        });
+    }
+
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let outcome = HistoryOutcome::of(&result, |scan| HistoryOutcome::Affected(scan.updated_rows));
+    record_history(&db_state, connection_id, &statement, elapsed, outcome).await;
+    invalidate_results(&app_state, connection_id).await;
+    let scan = result?;
+    Ok(FindReplaceResult {
+        matched_rows: scan.matched_rows,
+        sample: Vec::new(),
+        updated_rows: Some(scan.updated_rows),
+    })
+}
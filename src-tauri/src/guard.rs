@@ -0,0 +1,665 @@
+use crate::db::DbState;
+use crate::models::ConnectionOptions;
+use crate::sql_classifier::parse_statements;
+use sqlparser::ast::Statement;
+use sqlx::types::Json;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+const READ_ONLY_SQL_ERROR: &str =
+    "Connection is read-only: only SELECT/SHOW/DESCRIBE/EXPLAIN statements are allowed";
+
+// 生产环境连接上的破坏性操作未携带确认令牌时返回 "CONFIRMATION_REQUIRED:<token>:<说明>"，
+// 前端向用户确认后把 token 作为 confirm_token 参数重新提交同一个操作
+pub const CONFIRMATION_REQUIRED: &str = "CONFIRMATION_REQUIRED";
+
+// 高危语句（DROP / TRUNCATE / 不带 WHERE 的 UPDATE、DELETE）未带 confirmed 标记时返回
+// "NEEDS_CONFIRMATION:<类型>:<说明>"，类型为 DROP、TRUNCATE、UPDATE_WITHOUT_WHERE 或
+// DELETE_WITHOUT_WHERE，前端向用户确认后带 confirmed = true 重新提交
+pub const NEEDS_CONFIRMATION: &str = "NEEDS_CONFIRMATION";
+
+// 需要二次确认的 Redis 命令
+const REDIS_DESTRUCTIVE_COMMANDS: &[&str] = &["FLUSHDB", "FLUSHALL"];
+
+// 读取连接的只读标记（每次都查本地库，修改配置后立即生效）
+pub async fn is_read_only(db_state: &DbState, connection_id: i64) -> Result<bool, String> {
+    sqlx::query_scalar::<_, bool>("SELECT read_only FROM connections WHERE id = ?")
+        .bind(connection_id)
+        .fetch_optional(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+        .ok_or_else(|| "Connection not found".to_string())
+}
+
+// 读取连接的 options（每次都查本地库，修改配置后立即生效）
+pub async fn connection_options(
+    db_state: &DbState,
+    connection_id: i64,
+) -> Result<ConnectionOptions, String> {
+    sqlx::query_scalar::<_, Json<ConnectionOptions>>("SELECT options FROM connections WHERE id = ?")
+        .bind(connection_id)
+        .fetch_optional(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+        .map(|options| options.0)
+        .ok_or_else(|| "Connection not found".to_string())
+}
+
+// 安全模式的 LIMIT 行数（connections.options.safe_limit），未启用时为 None
+pub async fn safe_limit(db_state: &DbState, connection_id: i64) -> Result<Option<u64>, String> {
+    let options = connection_options(db_state, connection_id).await?;
+    Ok(options.safe_limit.filter(|n| *n > 0))
+}
+
+// 只读连接拒绝一切写操作（memcached set/delete 等没有语句可分析的场景）
+pub async fn ensure_writable(db_state: &DbState, connection_id: i64) -> Result<(), String> {
+    if is_read_only(db_state, connection_id).await? {
+        return Err("Connection is read-only: write operations are not allowed".to_string());
+    }
+    Ok(())
+}
+
+// 是否为生产环境连接
+async fn is_production(db_state: &DbState, connection_id: i64) -> Result<bool, String> {
+    let environment =
+        sqlx::query_scalar::<_, Option<String>>("SELECT environment FROM connections WHERE id = ?")
+            .bind(connection_id)
+            .fetch_optional(&db_state.pool)
+            .await
+            .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+            .ok_or_else(|| "Connection not found".to_string())?;
+    Ok(environment.as_deref() == Some("prod"))
+}
+
+// 确认令牌与连接和具体操作绑定，确认过的操作不能换成别的语句再提交
+fn confirmation_token(connection_id: i64, operation: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    connection_id.hash(&mut hasher);
+    operation.trim().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+// 生产环境连接上的破坏性操作必须携带匹配的确认令牌
+async fn ensure_confirmed(
+    db_state: &DbState,
+    connection_id: i64,
+    operation: &str,
+    description: &str,
+    confirm_token: Option<&str>,
+) -> Result<(), String> {
+    if !is_production(db_state, connection_id).await? {
+        return Ok(());
+    }
+    let token = confirmation_token(connection_id, operation);
+    if confirm_token == Some(token.as_str()) {
+        return Ok(());
+    }
+    Err(format!(
+        "{}:{}:{} on a production connection requires confirmation",
+        CONFIRMATION_REQUIRED, token, description
+    ))
+}
+
+// 只读连接上只允许执行只读 SQL；生产环境的 DROP / TRUNCATE / DELETE 需要确认令牌；
+// 任何连接上的高危语句都需要 confirmed 标记（生产环境已通过令牌确认的视为已确认）
+pub async fn ensure_sql_allowed(
+    db_state: &DbState,
+    connection_id: i64,
+    sql: &str,
+    confirm_token: Option<&str>,
+    confirmed: bool,
+) -> Result<(), String> {
+    if is_read_only(db_state, connection_id).await? && !is_read_only_sql(sql) {
+        return Err(READ_ONLY_SQL_ERROR.to_string());
+    }
+    if let Some(keyword) = destructive_sql_keyword(sql) {
+        ensure_confirmed(db_state, connection_id, sql, keyword, confirm_token).await?;
+    }
+    if confirmed || confirm_token == Some(confirmation_token(connection_id, sql).as_str()) {
+        return Ok(());
+    }
+    if let Some((kind, description)) = dangerous_sql_kind(sql) {
+        return Err(format!(
+            "{}:{}:{} requires confirmation",
+            NEEDS_CONFIRMATION, kind, description
+        ));
+    }
+    Ok(())
+}
+
+// 无人值守执行的 SQL（定时任务引用的片段每次执行前重新渲染，内容可能在保存后被修改）
+// 没有确认的机会：生产环境的破坏性语句和高危语句直接拒绝
+pub async fn ensure_unattended_sql_allowed(
+    db_state: &DbState,
+    connection_id: i64,
+    sql: &str,
+) -> Result<(), String> {
+    if is_read_only(db_state, connection_id).await? && !is_read_only_sql(sql) {
+        return Err(READ_ONLY_SQL_ERROR.to_string());
+    }
+    if let Some(keyword) = destructive_sql_keyword(sql) {
+        if is_production(db_state, connection_id).await? {
+            return Err(format!(
+                "{} on a production connection cannot run unattended",
+                keyword
+            ));
+        }
+    }
+    if let Some((_, description)) = dangerous_sql_kind(sql) {
+        return Err(format!("{} cannot run unattended", description));
+    }
+    Ok(())
+}
+
+// 执行 .sql 文件：只读连接直接拒绝；生产环境连接需要与文件路径绑定的确认令牌
+pub async fn ensure_sql_file_allowed(
+    db_state: &DbState,
+    connection_id: i64,
+    path: &str,
+    confirm_token: Option<&str>,
+) -> Result<(), String> {
+    ensure_writable(db_state, connection_id).await?;
+    let operation = format!("SOURCE {}", path);
+    ensure_confirmed(
+        db_state,
+        connection_id,
+        &operation,
+        "Running a SQL file",
+        confirm_token,
+    )
+    .await
+}
+
+// 删除 / 清空分区以 ALTER TABLE 开头，不会被当作破坏性语句识别：生产环境同样需要确认令牌
+pub async fn ensure_partition_change_allowed(
+    db_state: &DbState,
+    connection_id: i64,
+    sql: &str,
+    description: &str,
+    confirm_token: Option<&str>,
+) -> Result<(), String> {
+    ensure_writable(db_state, connection_id).await?;
+    ensure_confirmed(db_state, connection_id, sql, description, confirm_token).await
+}
+
+// 只读连接上只允许执行只读的 Redis 命令；生产环境的 FLUSHDB / FLUSHALL 需要确认
+pub async fn ensure_redis_command_allowed(
+    db_state: &DbState,
+    connection_id: i64,
+    command: &str,
+    args: &[String],
+    confirm_token: Option<&str>,
+) -> Result<(), String> {
+    if is_read_only(db_state, connection_id).await? && is_redis_write_command(command, args) {
+        return Err(format!(
+            "Connection is read-only: {} is a write command",
+            command.to_uppercase()
+        ));
+    }
+    let command_upper = command.to_uppercase();
+    if REDIS_DESTRUCTIVE_COMMANDS.contains(&command_upper.as_str()) {
+        let operation = std::iter::once(command_upper.clone())
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        ensure_confirmed(
+            db_state,
+            connection_id,
+            &operation,
+            &command_upper,
+            confirm_token,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+// 辅助函数：把 SQL 拆成语句，每条语句是大写关键字/标识符和少量符号组成的 token 序列
+// 字符串、引号标识符和注释会被跳过；MySQL 的 /*! ... */ 可执行注释按正文处理
+fn tokenize_statements(sql: &str) -> Vec<Vec<String>> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut statements = Vec::new();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => {
+                // 跳过字符串和引号标识符（支持 '' 与 \' 转义）
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == '\\' && c != '`' {
+                        i += 2;
+                        continue;
+                    }
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                tokens.push("''".to_string());
+                i += 1;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'!') {
+                    // /*!50001 ... */：去掉标记和版本号，内容照常分析
+                    i += 3;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                } else {
+                    i += 2;
+                    while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                        i += 1;
+                    }
+                    i += 2;
+                }
+            }
+            '*' if chars.get(i + 1) == Some(&'/') => {
+                // 可执行注释的结尾
+                i += 2;
+            }
+            ';' => {
+                if !tokens.is_empty() {
+                    statements.push(std::mem::take(&mut tokens));
+                }
+                i += 1;
+            }
+            '=' | '(' => {
+                tokens.push(c.to_string());
+                i += 1;
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '@' | '$' | '.') => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '@' | '$' | '.'))
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(word.to_uppercase());
+            }
+            _ => i += 1,
+        }
+    }
+    if !tokens.is_empty() {
+        statements.push(tokens);
+    }
+    statements
+}
+
+// 只读 PRAGMA：不带赋值的查询类 PRAGMA
+const READ_ONLY_PRAGMA_FUNCTIONS: &[&str] = &[
+    "TABLE_INFO",
+    "TABLE_XINFO",
+    "TABLE_LIST",
+    "INDEX_LIST",
+    "INDEX_INFO",
+    "INDEX_XINFO",
+    "FOREIGN_KEY_LIST",
+    "FOREIGN_KEY_CHECK",
+    "INTEGRITY_CHECK",
+    "QUICK_CHECK",
+];
+
+fn is_read_only_statement(tokens: &[String]) -> bool {
+    let has = |word: &str| tokens.iter().any(|t| t == word);
+    let Some(first) = tokens.first() else {
+        return true;
+    };
+
+    match first.as_str() {
+        // WITH 可能引出 UPDATE/DELETE；SELECT ... INTO OUTFILE 会写服务器文件
+        "SELECT" | "WITH" | "VALUES" | "TABLE" | "(" => ![
+            "INSERT", "UPDATE", "DELETE", "REPLACE", "MERGE", "OUTFILE", "DUMPFILE",
+        ]
+        .iter()
+        .any(|w| has(w)),
+        "SHOW" | "HELP" | "USE" | "BEGIN" | "COMMIT" | "ROLLBACK" => true,
+        "START" => tokens.get(1).map(String::as_str) == Some("TRANSACTION") && !has("WRITE"),
+        // EXPLAIN ANALYZE 会真正执行语句，需要检查后面的语句本身
+        "EXPLAIN" | "DESCRIBE" | "DESC" => match tokens.get(1).map(String::as_str) {
+            Some("ANALYZE") => is_read_only_statement(&tokens[2..]),
+            _ => true,
+        },
+        // 只允许会话级变量，不允许修改全局配置或解除只读事务
+        "SET" => !tokens.iter().any(|t| {
+            matches!(
+                t.as_str(),
+                "GLOBAL" | "PERSIST" | "PERSIST_ONLY" | "PASSWORD" | "DEFAULT" | "WRITE"
+            ) || t.starts_with("@@GLOBAL")
+                || t.starts_with("@@PERSIST")
+                || t.contains("READ_ONLY")
+        }),
+        "PRAGMA" => {
+            if has("=") {
+                return false;
+            }
+            match tokens.iter().position(|t| t == "(") {
+                Some(pos) => {
+                    let name = tokens[pos - 1].rsplit('.').next().unwrap_or_default();
+                    READ_ONLY_PRAGMA_FUNCTIONS.contains(&name)
+                }
+                None => true,
+            }
+        }
+        _ => false,
+    }
+}
+
+// 判断 SQL（可能包含多条语句）是否全部是只读语句
+pub fn is_read_only_sql(sql: &str) -> bool {
+    tokenize_statements(sql)
+        .iter()
+        .all(|tokens| is_read_only_statement(tokens))
+}
+
+// 返回 SQL 中第一条破坏性语句的关键字（DROP / TRUNCATE / DELETE，包括 WITH ... DELETE）
+fn destructive_sql_keyword(sql: &str) -> Option<&'static str> {
+    tokenize_statements(sql)
+        .iter()
+        .find_map(|tokens| match tokens.first().map(String::as_str) {
+            Some("DROP") => Some("DROP"),
+            Some("TRUNCATE") => Some("TRUNCATE"),
+            Some("DELETE") => Some("DELETE"),
+            Some("WITH") if tokens.iter().any(|t| t == "DELETE") => Some("DELETE"),
+            _ => None,
+        })
+}
+
+const UPDATE_WITHOUT_WHERE: (&str, &str) = ("UPDATE_WITHOUT_WHERE", "UPDATE without WHERE");
+const DELETE_WITHOUT_WHERE: (&str, &str) = ("DELETE_WITHOUT_WHERE", "DELETE without WHERE");
+
+// 返回 SQL 中第一条需要确认的高危语句：(类型, 说明)
+fn dangerous_sql_kind(sql: &str) -> Option<(&'static str, &'static str)> {
+    let statements = tokenize_statements(sql);
+    let dropped = statements
+        .iter()
+        .find_map(|tokens| match tokens.first().map(String::as_str) {
+            Some("DROP") => Some(("DROP", "DROP")),
+            Some("TRUNCATE") => Some(("TRUNCATE", "TRUNCATE")),
+            _ => None,
+        });
+    if dropped.is_some() {
+        return dropped;
+    }
+
+    match parse_statements(sql) {
+        // 解析成功时只看语句本身的 WHERE，子查询里的 WHERE 不算
+        Some(parsed) => parsed.iter().find_map(|statement| match statement {
+            Statement::Update(update) if update.selection.is_none() => Some(UPDATE_WITHOUT_WHERE),
+            Statement::Delete(delete) if delete.selection.is_none() => Some(DELETE_WITHOUT_WHERE),
+            _ => None,
+        }),
+        // 方言特有语法无法解析时按 token 判断：整条语句里没有 WHERE
+        None => statements.iter().find_map(|tokens| {
+            let kind = match tokens.first().map(String::as_str) {
+                Some("UPDATE") => UPDATE_WITHOUT_WHERE,
+                Some("DELETE") => DELETE_WITHOUT_WHERE,
+                Some("WITH") if tokens.iter().any(|t| t == "UPDATE") => UPDATE_WITHOUT_WHERE,
+                Some("WITH") if tokens.iter().any(|t| t == "DELETE") => DELETE_WITHOUT_WHERE,
+                _ => return None,
+            };
+            (!tokens.iter().any(|t| t == "WHERE")).then_some(kind)
+        }),
+    }
+}
+
+// Redis 写命令（包括管理类命令）
+const REDIS_WRITE_COMMANDS: &[&str] = &[
+    "SET",
+    "SETNX",
+    "SETEX",
+    "PSETEX",
+    "MSET",
+    "MSETNX",
+    "GETSET",
+    "GETDEL",
+    "GETEX",
+    "APPEND",
+    "SETRANGE",
+    "INCR",
+    "INCRBY",
+    "INCRBYFLOAT",
+    "DECR",
+    "DECRBY",
+    "DEL",
+    "UNLINK",
+    "EXPIRE",
+    "EXPIREAT",
+    "PEXPIRE",
+    "PEXPIREAT",
+    "PERSIST",
+    "RENAME",
+    "RENAMENX",
+    "MOVE",
+    "COPY",
+    "RESTORE",
+    "MIGRATE",
+    "SORT",
+    "HSET",
+    "HSETNX",
+    "HMSET",
+    "HDEL",
+    "HINCRBY",
+    "HINCRBYFLOAT",
+    "HEXPIRE",
+    "HPEXPIRE",
+    "HEXPIREAT",
+    "HPEXPIREAT",
+    "HPERSIST",
+    "HGETDEL",
+    "HGETEX",
+    "HSETEX",
+    "LPUSH",
+    "RPUSH",
+    "LPUSHX",
+    "RPUSHX",
+    "LPOP",
+    "RPOP",
+    "LSET",
+    "LREM",
+    "LTRIM",
+    "LINSERT",
+    "LMOVE",
+    "BLMOVE",
+    "RPOPLPUSH",
+    "BRPOPLPUSH",
+    "BLPOP",
+    "BRPOP",
+    "LMPOP",
+    "BLMPOP",
+    "SADD",
+    "SREM",
+    "SPOP",
+    "SMOVE",
+    "SINTERSTORE",
+    "SUNIONSTORE",
+    "SDIFFSTORE",
+    "ZADD",
+    "ZREM",
+    "ZINCRBY",
+    "ZPOPMIN",
+    "ZPOPMAX",
+    "BZPOPMIN",
+    "BZPOPMAX",
+    "ZMPOP",
+    "BZMPOP",
+    "ZREMRANGEBYSCORE",
+    "ZREMRANGEBYRANK",
+    "ZREMRANGEBYLEX",
+    "ZINTERSTORE",
+    "ZUNIONSTORE",
+    "ZDIFFSTORE",
+    "ZRANGESTORE",
+    "GEOADD",
+    "GEORADIUS",
+    "GEORADIUSBYMEMBER",
+    "GEOSEARCHSTORE",
+    "PFADD",
+    "PFMERGE",
+    "XADD",
+    "XDEL",
+    "XTRIM",
+    "XGROUP",
+    "XACK",
+    "XCLAIM",
+    "XAUTOCLAIM",
+    "XSETID",
+    "XREADGROUP",
+    "SETBIT",
+    "BITOP",
+    "BITFIELD",
+    "FLUSHDB",
+    "FLUSHALL",
+    "SWAPDB",
+    "EVAL",
+    "EVALSHA",
+    "FCALL",
+    "PUBLISH",
+    "SPUBLISH",
+    "SHUTDOWN",
+    "SAVE",
+    "BGSAVE",
+    "BGREWRITEAOF",
+    "REPLICAOF",
+    "SLAVEOF",
+    "FAILOVER",
+    "DEBUG",
+    "MODULE",
+];
+
+// 带子命令的命令：只有列出的子命令是只读的
+fn redis_read_only_subcommands(command: &str) -> Option<&'static [&'static str]> {
+    match command {
+        "CONFIG" => Some(&["GET", "HELP"]),
+        "ACL" => Some(&[
+            "WHOAMI", "LIST", "USERS", "GETUSER", "CAT", "LOG", "DRYRUN", "HELP",
+        ]),
+        "SCRIPT" => Some(&["EXISTS", "HELP"]),
+        "FUNCTION" => Some(&["LIST", "DUMP", "STATS", "HELP"]),
+        "CLUSTER" => Some(&[
+            "INFO",
+            "NODES",
+            "SLOTS",
+            "SHARDS",
+            "MYID",
+            "KEYSLOT",
+            "COUNTKEYSINSLOT",
+            "GETKEYSINSLOT",
+            "REPLICAS",
+            "HELP",
+        ]),
+        "CLIENT" => Some(&[
+            "LIST", "INFO", "GETNAME", "ID", "SETNAME", "SETINFO", "HELP",
+        ]),
+        "SLOWLOG" | "LATENCY" => Some(&["GET", "LEN", "LATEST", "HISTORY", "DOCTOR", "HELP"]),
+        _ => None,
+    }
+}
+
+// 模块命令（如 JSON.SET、FT.SEARCH）按动词判断
+const REDIS_MODULE_READ_VERBS: &[&str] = &[
+    "GET",
+    "MGET",
+    "TYPE",
+    "STRLEN",
+    "OBJKEYS",
+    "OBJLEN",
+    "ARRLEN",
+    "ARRINDEX",
+    "RESP",
+    "INFO",
+    "SEARCH",
+    "AGGREGATE",
+    "EXPLAIN",
+    "PROFILE",
+    "RO_QUERY",
+    "RANGE",
+    "REVRANGE",
+    "MRANGE",
+    "MREVRANGE",
+    "EXISTS",
+    "MEXISTS",
+    "CARD",
+    "COUNT",
+    "LIST",
+    "_LIST",
+    "QUERYINDEX",
+];
+
+pub fn is_redis_write_command(command: &str, args: &[String]) -> bool {
+    let command = command.trim().to_uppercase();
+    if let Some(allowed) = redis_read_only_subcommands(&command) {
+        let sub = args.first().map(|a| a.to_uppercase()).unwrap_or_default();
+        return !allowed.contains(&sub.as_str());
+    }
+    if let Some((_, verb)) = command.split_once('.') {
+        return !REDIS_MODULE_READ_VERBS.contains(&verb);
+    }
+    REDIS_WRITE_COMMANDS.contains(&command.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每条语句的 token 以空格连接，便于比较
+    fn tokens(sql: &str) -> Vec<String> {
+        tokenize_statements(sql)
+            .into_iter()
+            .map(|statement| statement.join(" "))
+            .collect()
+    }
+
+    #[test]
+    fn splits_statements_outside_strings_and_comments() {
+        assert_eq!(
+            tokens("select 'a;b' ; delete from t -- x;y\n; # z;\n /* w; */"),
+            ["SELECT ''", "DELETE FROM T"]
+        );
+        assert_eq!(
+            tokens("UPDATE `a;b` SET c = \"d;e\";;"),
+            ["UPDATE '' SET C = ''"]
+        );
+    }
+
+    #[test]
+    fn handles_escaped_quotes() {
+        assert_eq!(
+            tokens("SELECT 'it\\'s; x', 'a''b; DROP'; SELECT 1"),
+            ["SELECT '' ''", "SELECT 1"]
+        );
+    }
+
+    #[test]
+    fn executable_comments_are_code() {
+        assert_eq!(
+            tokens("/*!40101 SET NAMES utf8 */; /*!50001 DROP VIEW v*/"),
+            ["SET NAMES UTF8", "DROP VIEW V"]
+        );
+    }
+
+    #[test]
+    fn keeps_qualified_names_and_variables_whole() {
+        assert_eq!(
+            tokens("insert into db.t(a) values (@x)"),
+            ["INSERT INTO DB.T ( A VALUES ( @X"]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::alter_table::{apply_ddl, qualified_name, quote_identifier};
+use crate::db::{connection_flavor, DbState};
+use crate::models::{CreateIndexArgs, DdlStatementResult, IndexInfo, IndexSpec};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::table_structure::{mysql_indexes, sqlite_indexes};
+use crate::{mysql_manager, sqlite_manager};
+use tauri::{command, State};
+
+pub fn default_index_name(table: &str, columns: &[String], unique: bool) -> String {
+    format!(
+        "{}_{}_{}",
+        if unique { "uq" } else { "idx" },
+        table,
+        columns.join("_")
+    )
+}
+
+// 返回放在 INDEX / KEY 前的关键字和列清单后的 USING 子句
+pub fn index_kind(
+    spec: &IndexSpec,
+    flavor: SqlFlavor,
+) -> Result<(&'static str, &'static str), String> {
+    let unique = if spec.unique { "UNIQUE " } else { "" };
+    let Some(index_type) = &spec.index_type else {
+        return Ok((unique, ""));
+    };
+    if matches!(flavor, SqlFlavor::Sqlite) {
+        return Err("SQLite does not support index types".to_string());
+    }
+    match index_type.trim().to_ascii_uppercase().as_str() {
+        "BTREE" => Ok((unique, " USING BTREE")),
+        "HASH" => Ok((unique, " USING HASH")),
+        "FULLTEXT" | "SPATIAL" if spec.unique => Err(format!(
+            "{} indexes cannot be unique",
+            index_type.trim().to_ascii_uppercase()
+        )),
+        "FULLTEXT" => Ok(("FULLTEXT ", "")),
+        "SPATIAL" => Ok(("SPATIAL ", "")),
+        _ => Err(format!("Unsupported index type: {}", index_type)),
+    }
+}
+
+async fn fetch_indexes(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    flavor: SqlFlavor,
+    table: &str,
+    db_name: Option<String>,
+) -> Result<Vec<IndexInfo>, String> {
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            mysql_indexes(&mut conn, db_name.as_deref(), table).await
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            sqlite_indexes(&mut conn, table).await
+        }
+    }
+}
+
+// 列出表上的索引（含主键），主键排在最前
+#[command]
+pub async fn list_indexes(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    db_name: Option<String>,
+) -> Result<Vec<IndexInfo>, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Index management").await?;
+    fetch_indexes(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        &table,
+        db_name,
+    )
+    .await
+}
+
+// 未确认时只返回 CREATE INDEX 语句供预览，confirmed 为 true 时执行
+#[command]
+pub async fn create_index(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: CreateIndexArgs,
+    confirmed: Option<bool>,
+) -> Result<DdlStatementResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Index management").await?;
+    if args.index.columns.is_empty() {
+        return Err("An index needs at least one column".to_string());
+    }
+    let (prefix, using) = index_kind(&args.index, flavor)?;
+    let name =
+        args.index.name.clone().unwrap_or_else(|| {
+            default_index_name(&args.table, &args.index.columns, args.index.unique)
+        });
+    let columns = args
+        .index
+        .columns
+        .iter()
+        .map(|c| quote_identifier(c, flavor))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let statement = format!(
+        "CREATE {}INDEX {} ON {} ({}){}",
+        prefix,
+        quote_identifier(&name, flavor),
+        qualified_name(args.db_name.as_deref(), &args.table, flavor),
+        columns,
+        using
+    );
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        args.db_name,
+        std::slice::from_ref(&statement),
+        None,
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
+
+// 删除普通索引。主键和 SQLite 为 UNIQUE 约束自动创建的索引只能通过修改表结构删除；
+// 生产环境需要确认令牌
+#[command]
+pub async fn drop_index(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    index_name: String,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Index management").await?;
+    let indexes = fetch_indexes(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        &table,
+        db_name.clone(),
+    )
+    .await?;
+    let index = indexes
+        .iter()
+        .find(|index| index.name.eq_ignore_ascii_case(&index_name))
+        .ok_or_else(|| format!("Index {} not found on {}", index_name, table))?;
+    if index.primary {
+        return Err("The primary key cannot be dropped as an index".to_string());
+    }
+    if matches!(flavor, SqlFlavor::Sqlite) && index.name.starts_with("sqlite_autoindex_") {
+        return Err(format!(
+            "Index {} belongs to a UNIQUE constraint and cannot be dropped",
+            index.name
+        ));
+    }
+    let statement = match flavor {
+        SqlFlavor::MySql => format!(
+            "DROP INDEX {} ON {}",
+            quote_identifier(&index.name, flavor),
+            qualified_name(db_name.as_deref(), &table, flavor)
+        ),
+        SqlFlavor::Sqlite => format!("DROP INDEX {}", quote_identifier(&index.name, flavor)),
+    };
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        db_name,
+        std::slice::from_ref(&statement),
+        confirm_token.as_deref(),
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
@@ -0,0 +1,95 @@
+use crate::state::AppState;
+use sqlx::{Connection as _, MySqlPool};
+use std::collections::HashMap;
+use tokio::time::{interval, Duration, Instant};
+
+// 后台检查的间隔，以及每个连接默认/最短的保活间隔
+const KEEP_ALIVE_TICK_SECS: u64 = 30;
+const KEEP_ALIVE_DEFAULT_INTERVAL_SECS: u64 = 300;
+const KEEP_ALIVE_MIN_INTERVAL_SECS: u64 = 30;
+
+struct KeepAliveEntry {
+    interval: Duration,
+    last_ping: Instant,
+}
+
+// 定期 ping 缓存的 MySQL 连接池，避免长时间空闲后被服务端（wait_timeout）或中间网络设备
+// 断开，导致回到应用后的第一条查询报 "server has gone away"。Redis 每条命令都新建连接，
+// 没有需要保活的空闲连接
+pub fn spawn_keep_alive(app_state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(KEEP_ALIVE_TICK_SECS));
+        let mut entries: HashMap<String, KeepAliveEntry> = HashMap::new();
+
+        loop {
+            ticker.tick().await;
+
+            let mysql_pools: Vec<(String, MySqlPool)> = app_state
+                .pools
+                .lock()
+                .await
+                .iter()
+                .map(|(key, pool)| (key.clone(), pool.clone()))
+                .collect();
+
+            // 已关闭的连接不再跟踪
+            entries.retain(|key, _| mysql_pools.iter().any(|(k, _)| k == key));
+
+            for (key, pool) in mysql_pools {
+                if pool.is_closed() {
+                    continue;
+                }
+                match entries.get_mut(&key) {
+                    Some(entry) => {
+                        if entry.last_ping.elapsed() >= entry.interval {
+                            ping_mysql_pool(&pool).await;
+                            entry.last_ping = Instant::now();
+                        }
+                    }
+                    // 新出现的连接池先读取 wait_timeout 确定保活间隔
+                    None => {
+                        let interval = mysql_keep_alive_interval(&pool).await;
+                        entries.insert(
+                            key,
+                            KeepAliveEntry {
+                                interval,
+                                last_ping: Instant::now(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    });
+}
+
+// 保活间隔取 wait_timeout 的一半，且不超过默认间隔
+async fn mysql_keep_alive_interval(pool: &MySqlPool) -> Duration {
+    let wait_timeout =
+        sqlx::query_scalar::<_, i64>("SELECT CAST(@@SESSION.wait_timeout AS SIGNED)")
+            .fetch_one(pool)
+            .await
+            .map(|secs| secs.max(0) as u64)
+            .unwrap_or(KEEP_ALIVE_DEFAULT_INTERVAL_SECS * 2);
+    let secs = (wait_timeout / 2).clamp(
+        KEEP_ALIVE_MIN_INTERVAL_SECS,
+        KEEP_ALIVE_DEFAULT_INTERVAL_SECS,
+    );
+    Duration::from_secs(secs)
+}
+
+// 逐个取出空闲连接并 ping；ping 失败的连接直接关闭，由连接池按需重建
+async fn ping_mysql_pool(pool: &MySqlPool) {
+    let mut connections = Vec::new();
+    for _ in 0..pool.num_idle() {
+        match pool.try_acquire() {
+            Some(conn) => connections.push(conn),
+            None => break,
+        }
+    }
+    for mut conn in connections {
+        if conn.ping().await.is_err() {
+            let _ = conn.detach().close().await;
+        }
+    }
+}
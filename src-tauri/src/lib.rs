@@ -1,22 +1,179 @@
+mod alter_table;
+mod autocomplete;
+mod bulk_update;
+mod cassandra_manager;
+mod charset_audit;
+mod chart;
+mod clickhouse_manager;
+mod code_gen;
+mod column_profile;
+mod connection_manager;
+mod connection_stats;
+mod connection_store;
+mod couchbase_manager;
+mod create_table;
+mod data_sync;
+mod data_transfer;
+mod database_admin;
+mod database_list;
+mod database_search;
 mod db;
+mod dialect;
+mod duckdb_manager;
+mod dynamo_manager;
+mod elastic_manager;
+mod event_manager;
+mod fake_data;
+mod find_replace;
+mod guard;
+mod index_manager;
+mod keep_alive;
 mod memcached_manager;
 mod models;
+mod mongo_manager;
 mod mysql_manager;
+mod neo4j_manager;
+mod object_search;
+mod partition_manager;
+mod query_digest;
+mod query_history;
+mod query_queue;
+mod reconnect;
+mod redact;
 mod redis_manager;
+mod relationship_graph;
+mod result_cache;
+mod result_diff;
+mod result_export;
+mod result_spill;
+mod rocksdb_manager;
+mod routine_manager;
+mod row_editor;
+mod scheduler;
+mod schema_diff;
+mod secret_provider;
+mod server_status;
+mod session;
+mod slow_log;
+mod snippets;
+mod sql_classifier;
+mod sql_file_runner;
+mod sql_formatter;
 mod sqlite_manager;
+mod ssh_tunnel;
 mod state;
+mod table_browser;
+mod table_dependencies;
+mod table_maintenance;
+mod table_structure;
+mod trigger_manager;
+mod undo;
+mod user_admin;
+mod vault;
+mod view_manager;
 
+use alter_table::alter_table;
+use autocomplete::get_autocomplete_metadata;
+use bulk_update::bulk_update;
+use cassandra_manager::{
+    execute_cql, get_cassandra_table_columns, list_cassandra_keyspaces, list_cassandra_tables,
+};
+use charset_audit::audit_charsets;
+use chart::run_chart_query;
+use clickhouse_manager::{execute_clickhouse_sql, stream_clickhouse_sql};
+use code_gen::generate_code;
+use column_profile::profile_column;
+use connection_manager::{
+    close_all_connections, close_connection, diagnose_connection, test_connection,
+};
+use connection_stats::get_connection_stats;
+use connection_store::{
+    create_connection, create_connection_group, delete_connection, delete_connection_group,
+    export_connections, import_connections, list_connection_groups, list_connection_tags,
+    list_connections, move_connection, move_connection_group, parse_connection_uri,
+    reorder_connection_groups, reorder_connections, update_connection, update_connection_group,
+};
+use couchbase_manager::{
+    execute_n1ql, get_couchbase_document, list_couchbase_buckets, list_couchbase_collections,
+};
+use create_table::create_table;
+use data_sync::compare_table_data;
+use data_transfer::transfer_data;
+use database_admin::{create_database, drop_database};
+use database_list::{get_table_stats, list_databases, list_tables};
+use database_search::search_database;
 use db::{get_db_path, DB_FILE_NAME};
+use duckdb_manager::execute_duckdb_sql;
+use dynamo_manager::{
+    delete_dynamo_item, get_dynamo_item, list_dynamo_tables, put_dynamo_item, query_dynamo_table,
+    scan_dynamo_table,
+};
+use elastic_manager::{
+    get_elastic_cluster_health, get_elastic_mapping, list_elastic_indices, search_elastic,
+};
+use event_manager::{create_event, drop_event, get_event, list_events, set_event_enabled};
+use fake_data::generate_fake_data;
+use find_replace::find_replace;
+use index_manager::{create_index, drop_index, list_indexes};
 use memcached_manager::{
     delete_memcached_key, get_memcached_keys, get_memcached_value, set_memcached_value,
 };
-use mysql_manager::execute_sql;
+use mongo_manager::{
+    list_mongo_collections, list_mongo_databases, mongo_aggregate, mongo_find,
+};
+use mysql_manager::{
+    close_result_stream, count_query_rows, execute_sql, execute_sql_multi, execute_sql_streaming,
+    fetch_blob, fetch_more, get_server_profile, use_database, validate_sql,
+};
+use neo4j_manager::{execute_cypher, get_neo4j_schema};
+use object_search::search_objects;
+use partition_manager::{add_partition, drop_partitions, get_partitions, truncate_partitions};
+use query_digest::get_query_digest_report;
+use query_history::{pin_query_history, purge_query_history, search_query_history};
+use query_queue::{cancel_query, drop_queued_query};
 use redis_manager::{
     execute_redis_command, execute_redis_pipeline, get_keys_details, get_redis_keys,
     scan_hash_values, scan_list_values, scan_set_members, scan_zset_members,
 };
-use sqlite_manager::execute_sqlite_sql;
+use relationship_graph::get_relationship_graph;
+use result_cache::clear_result_cache;
+use result_diff::{diff_results, store_query_result};
+use result_export::export_result;
+use result_spill::{fetch_result_page, release_result};
+use rocksdb_manager::{get_rocksdb_value, list_rocksdb_column_families, scan_rocksdb_keys};
+use routine_manager::{execute_routine, get_routine, list_routines};
+use row_editor::{delete_row, insert_row, update_row};
+use scheduler::{
+    create_scheduled_query, delete_scheduled_query, list_scheduled_queries,
+    list_scheduled_query_runs, update_scheduled_query,
+};
+use schema_diff::diff_schemas;
+use server_status::{get_server_status, get_server_variables, set_server_variable};
+use session::{close_session, open_session};
+use slow_log::read_slow_log;
+use snippets::{
+    create_snippet, delete_snippet, list_snippets, resolve_snippet, search_snippets, update_snippet,
+};
+use sql_file_runner::run_sql_file;
+use sql_formatter::format_sql;
+use sqlite_manager::{count_sqlite_query_rows, execute_sqlite_sql, validate_sqlite_sql};
+use ssh_tunnel::{trust_ssh_host_key, unlock_ssh_key};
 use state::AppState;
+use table_browser::browse_table;
+use table_dependencies::{dump_tables, truncate_tables};
+use table_maintenance::run_table_maintenance;
+use table_structure::get_table_structure;
+use trigger_manager::{create_trigger, drop_trigger, get_trigger_ddl, list_triggers};
+use undo::undo_last_change;
+use user_admin::{
+    change_user_password, create_user, drop_user, grant_privileges, list_users, revoke_privileges,
+    show_user_grants,
+};
+use vault::{
+    disable_master_password, get_master_password_status, lock_master_password, set_master_password,
+    unlock_master_password,
+};
+use view_manager::{alter_view, create_view, drop_view, get_view_definition, list_views};
 use tauri::Manager;
 use tauri_plugin_sql::{Migration, MigrationKind};
 
@@ -28,11 +185,92 @@ fn get_migrations() -> Vec<Migration> {
             sql: include_str!("../migrations/0001_initial_tables.sql"),
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 2,
+            description: "add_ssh_tunnel_columns",
+            sql: include_str!("../migrations/0002_ssh_tunnel.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 3,
+            description: "add_ssh_auth_method",
+            sql: include_str!("../migrations/0003_ssh_auth_method.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "add_tls_settings",
+            sql: include_str!("../migrations/0004_tls_settings.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "create_master_password",
+            sql: include_str!("../migrations/0005_master_password.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "add_connection_folders_and_tags",
+            sql: include_str!("../migrations/0006_connection_folders_tags.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "add_read_only",
+            sql: include_str!("../migrations/0007_read_only.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add_connection_options",
+            sql: include_str!("../migrations/0008_connection_options.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add_environment",
+            sql: include_str!("../migrations/0009_environment.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "add_connection_usage",
+            sql: include_str!("../migrations/0010_connection_usage.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "create_query_history",
+            sql: include_str!("../migrations/0011_query_history.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "create_snippets",
+            sql: include_str!("../migrations/0012_snippets.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "create_undo_snapshots",
+            sql: include_str!("../migrations/0013_undo_snapshots.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "create_scheduled_queries",
+            sql: include_str!("../migrations/0014_scheduled_queries.sql"),
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // rustls 同时启用了 ring 和 aws-lc-rs，需要显式指定默认的加密实现
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
     let migrations = get_migrations();
 
     tauri::Builder::default()
@@ -49,7 +287,16 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
             // 初始化全局状态
-            app.manage(AppState::new());
+            let mut app_state = AppState::new();
+            app_state.app_handle = Some(app.handle().clone());
+            result_spill::remove_stale_spill_files(&app_state);
+            // 主密码空闲超时自动上锁
+            vault::spawn_auto_lock(app_state.clone());
+            // 定期 ping 缓存的连接，避免空闲断开
+            keep_alive::spawn_keep_alive(app_state.clone());
+            app.manage(app_state);
+
+            let app_handle = app.handle().clone();
 
             // 初始化数据库连接池 (迁移已由 Tauri SQL 插件处理)
             tauri::async_runtime::block_on(async move {
@@ -62,11 +309,15 @@ pub fn run() {
                     }
                 }
             });
+            // 按计划执行定时查询（依赖上面初始化的本地库）
+            scheduler::spawn_scheduler(app_handle);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_db_path,
             execute_sql,
+            get_server_profile,
+            use_database,
             execute_sqlite_sql,
             execute_redis_command,
             execute_redis_pipeline,
@@ -79,8 +330,179 @@ pub fn run() {
             get_memcached_keys,
             get_memcached_value,
             set_memcached_value,
-            delete_memcached_key
+            delete_memcached_key,
+            list_mongo_databases,
+            list_mongo_collections,
+            mongo_find,
+            mongo_aggregate,
+            execute_clickhouse_sql,
+            stream_clickhouse_sql,
+            list_cassandra_keyspaces,
+            list_cassandra_tables,
+            get_cassandra_table_columns,
+            execute_cql,
+            execute_duckdb_sql,
+            list_elastic_indices,
+            get_elastic_mapping,
+            search_elastic,
+            get_elastic_cluster_health,
+            list_dynamo_tables,
+            scan_dynamo_table,
+            query_dynamo_table,
+            get_dynamo_item,
+            put_dynamo_item,
+            delete_dynamo_item,
+            list_couchbase_buckets,
+            list_couchbase_collections,
+            get_couchbase_document,
+            execute_n1ql,
+            list_rocksdb_column_families,
+            scan_rocksdb_keys,
+            get_rocksdb_value,
+            execute_cypher,
+            get_neo4j_schema,
+            unlock_ssh_key,
+            trust_ssh_host_key,
+            test_connection,
+            diagnose_connection,
+            close_connection,
+            close_all_connections,
+            get_master_password_status,
+            set_master_password,
+            unlock_master_password,
+            lock_master_password,
+            disable_master_password,
+            list_connections,
+            create_connection,
+            update_connection,
+            delete_connection,
+            move_connection,
+            reorder_connections,
+            list_connection_tags,
+            list_connection_groups,
+            create_connection_group,
+            update_connection_group,
+            move_connection_group,
+            reorder_connection_groups,
+            delete_connection_group,
+            export_connections,
+            import_connections,
+            parse_connection_uri,
+            get_connection_stats,
+            drop_queued_query,
+            cancel_query,
+            close_result_stream,
+            execute_sql_streaming,
+            fetch_more,
+            pin_query_history,
+            purge_query_history,
+            search_query_history,
+            create_snippet,
+            delete_snippet,
+            list_snippets,
+            resolve_snippet,
+            search_snippets,
+            update_snippet,
+            format_sql,
+            execute_sql_multi,
+            fetch_blob,
+            undo_last_change,
+            validate_sql,
+            validate_sqlite_sql,
+            count_query_rows,
+            count_sqlite_query_rows,
+            clear_result_cache,
+            fetch_result_page,
+            release_result,
+            run_sql_file,
+            open_session,
+            close_session,
+            store_query_result,
+            diff_results,
+            list_scheduled_queries,
+            create_scheduled_query,
+            update_scheduled_query,
+            delete_scheduled_query,
+            list_scheduled_query_runs,
+            run_chart_query,
+            search_database,
+            bulk_update,
+            find_replace,
+            generate_fake_data,
+            generate_code,
+            export_result,
+            profile_column,
+            list_databases,
+            list_tables,
+            get_table_stats,
+            get_table_structure,
+            browse_table,
+            insert_row,
+            update_row,
+            delete_row,
+            alter_table,
+            create_table,
+            list_indexes,
+            create_index,
+            drop_index,
+            create_database,
+            drop_database,
+            list_views,
+            get_view_definition,
+            create_view,
+            alter_view,
+            drop_view,
+            list_routines,
+            get_routine,
+            execute_routine,
+            list_triggers,
+            get_trigger_ddl,
+            create_trigger,
+            drop_trigger,
+            list_events,
+            get_event,
+            set_event_enabled,
+            create_event,
+            drop_event,
+            get_partitions,
+            add_partition,
+            drop_partitions,
+            truncate_partitions,
+            diff_schemas,
+            compare_table_data,
+            transfer_data,
+            get_relationship_graph,
+            get_autocomplete_metadata,
+            run_table_maintenance,
+            audit_charsets,
+            truncate_tables,
+            dump_tables,
+            search_objects,
+            get_server_status,
+            get_server_variables,
+            set_server_variable,
+            list_users,
+            show_user_grants,
+            create_user,
+            drop_user,
+            change_user_password,
+            grant_privileges,
+            revoke_privileges,
+            read_slow_log,
+            get_query_digest_report
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 退出时写入尚未保存的连接使用记录，并删除落盘结果的临时文件
+            if let tauri::RunEvent::Exit = event {
+                let app_state = app.state::<AppState>();
+                if let Some(db_state) = app.try_state::<db::DbState>() {
+                    tauri::async_runtime::block_on(connection_stats::flush_usage(
+                        &app_state, &db_state,
+                    ));
+                }
+                tauri::async_runtime::block_on(result_spill::remove_spill_file(&app_state));
+            }
+        });
 }
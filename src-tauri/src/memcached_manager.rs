@@ -1,10 +1,16 @@
+use crate::connection_stats::record_query;
 use crate::db::DbState;
+use crate::guard::ensure_writable;
 use crate::models::Connection;
+use crate::redact::redact_error;
+use crate::ssh_tunnel::resolve_endpoint;
 use crate::state::AppState;
+use crate::vault::reveal_secrets;
 use flate2::read::ZlibDecoder;
 use memcache::Client;
 use serde::{Deserialize, Serialize};
 use std::io::Read;
+use std::time::Instant;
 use tauri::{command, State};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,18 +20,36 @@ pub struct MemcachedKey {
     pub expiration: i64, // Unix timestamp
 }
 
-fn get_memcached_url(connection: &Connection) -> String {
-    let host = connection.host.as_deref().unwrap_or("localhost");
-    let port = connection.port.unwrap_or(11211);
+pub fn get_memcached_url(host: &str, port: u16) -> String {
     // memcache crate uses "memcache://host:port"
     format!("memcache://{}:{}", host, port)
 }
 
+// Resolve the address to dial, going through the SSH tunnel when one is configured
+pub async fn get_memcached_endpoint(
+    app_state: &AppState,
+    connection: &Connection,
+) -> Result<(String, u16), String> {
+    let host = connection.host.as_deref().unwrap_or("localhost");
+    let port = connection.port.unwrap_or(11211) as u16;
+    resolve_endpoint(app_state, connection, host, port).await
+}
+
 // Helper to get client from cache or create new
 // Note: memcache crate Client is synchronous. We might need to be careful.
-// Ideally we should store it in AppState but the crate's Client might not be Clone or Send/Sync the way we want?
-// memcache::Client is Send + Sync.
-fn get_or_create_client(db_state: &DbState, connection_id: i64) -> Result<Client, String> {
+// memcache::Client is Clone + Send + Sync (it wraps r2d2 pools), so it is cached in AppState.
+fn get_or_create_client(
+    app_state: &AppState,
+    db_state: &DbState,
+    connection_id: i64,
+) -> Result<Client, String> {
+    if let Some(client) = tauri::async_runtime::block_on(async {
+        let clients = app_state.memcached_clients.lock().await;
+        clients.get(&connection_id).cloned()
+    }) {
+        return Ok(client);
+    }
+
     // Let's try to fetch connection details first
     let connection = tauri::async_runtime::block_on(async {
         sqlx::query_as::<_, Connection>(
@@ -37,31 +61,44 @@ fn get_or_create_client(db_state: &DbState, connection_id: i64) -> Result<Client
     })
     .map_err(|e| format!("Failed to fetch connection info: {}", e))?
     .ok_or("Connection not found")?;
+    let connection = tauri::async_runtime::block_on(reveal_secrets(app_state, connection))?;
 
     if connection.db_type != "memcached" {
         return Err("Only Memcached is supported for this operation".to_string());
     }
 
-    let url = get_memcached_url(&connection);
-    let client =
-        Client::connect(url).map_err(|e| format!("Failed to connect to Memcached: {}", e))?;
+    let (host, port) =
+        tauri::async_runtime::block_on(get_memcached_endpoint(app_state, &connection))?;
+    let mut url = get_memcached_url(&host, port);
+    if let Some(secs) = connection.options.connect_timeout_secs {
+        url = format!("{}?connect_timeout={}", url, secs);
+    }
+    let client = Client::connect(url).map_err(|e| {
+        redact_error(format!("Failed to connect to Memcached: {}", e), &connection)
+    })?;
+
+    tauri::async_runtime::block_on(async {
+        let mut clients = app_state.memcached_clients.lock().await;
+        clients.insert(connection_id, client.clone());
+    });
 
     Ok(client)
 }
 
 #[command]
 pub async fn get_memcached_keys(
-    _app_state: State<'_, AppState>,
+    app_state: State<'_, AppState>,
     db_state: State<'_, DbState>,
     connection_id: i64,
     filter: Option<String>,
 ) -> Result<Vec<MemcachedKey>, String> {
     // Since memcache ops are blocking, we use spawn_blocking
+    let app_state_cloned = app_state.inner().clone();
     let db_state_cloned = db_state.inner().clone();
 
     // Check connection first using memcache crate
     tauri::async_runtime::spawn_blocking(move || {
-        let client = get_or_create_client(&db_state_cloned, connection_id)?;
+        let client = get_or_create_client(&app_state_cloned, &db_state_cloned, connection_id)?;
         // Simple connectivity check
         client
             .stats()
@@ -74,7 +111,7 @@ pub async fn get_memcached_keys(
     // NOTE: Since `memcache` crate doesn't support key listing easily,
     // I will implement a raw TCP helper for listing keys.
 
-    let raw_keys = list_keys_via_tcp(&db_state, connection_id).await?;
+    let raw_keys = list_keys_via_tcp(&app_state, &db_state, connection_id).await?;
 
     let mut result = Vec::new();
     let filter_str = filter.unwrap_or_default().to_lowercase();
@@ -94,6 +131,7 @@ pub async fn get_memcached_keys(
 
 // Helper to list keys via raw TCP
 async fn list_keys_via_tcp(
+    app_state: &State<'_, AppState>,
     db_state: &State<'_, DbState>,
     connection_id: i64,
 ) -> Result<Vec<String>, String> {
@@ -105,9 +143,9 @@ async fn list_keys_via_tcp(
     .await
     .map_err(|e| e.to_string())?
     .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
 
-    let host = connection.host.as_deref().unwrap_or("localhost");
-    let port = connection.port.unwrap_or(11211);
+    let (host, port) = get_memcached_endpoint(app_state, &connection).await?;
     let addr = format!("{}:{}", host, port);
 
     use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -188,15 +226,17 @@ async fn list_keys_via_tcp(
 
 #[command]
 pub async fn get_memcached_value(
-    _app_state: State<'_, AppState>,
+    app_state: State<'_, AppState>,
     db_state: State<'_, DbState>,
     connection_id: i64,
     key: String,
 ) -> Result<String, String> {
+    let app_state_cloned = app_state.inner().clone();
     let db_state_cloned = db_state.inner().clone();
 
+    let started = Instant::now();
     let value = tauri::async_runtime::spawn_blocking(move || {
-        let client = get_or_create_client(&db_state_cloned, connection_id)?;
+        let client = get_or_create_client(&app_state_cloned, &db_state_cloned, connection_id)?;
         // Use Vec<u8> to get raw bytes
         let val: Option<Vec<u8>> = client.get(&key).map_err(|e| e.to_string())?;
 
@@ -245,49 +285,60 @@ pub async fn get_memcached_value(
         }
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| e.to_string())?;
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
 
-    Ok(value.unwrap_or_else(|| "(nil)".to_string()))
+    Ok(value?.unwrap_or_else(|| "(nil)".to_string()))
 }
 
 #[command]
 pub async fn set_memcached_value(
-    _app_state: State<'_, AppState>,
+    app_state: State<'_, AppState>,
     db_state: State<'_, DbState>,
     connection_id: i64,
     key: String,
     value: String,
     ttl: u32,
 ) -> Result<(), String> {
+    ensure_writable(&db_state, connection_id).await?;
+
+    let app_state_cloned = app_state.inner().clone();
     let db_state_cloned = db_state.inner().clone();
 
-    tauri::async_runtime::spawn_blocking(move || {
-        let client = get_or_create_client(&db_state_cloned, connection_id)?;
+    let started = Instant::now();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let client = get_or_create_client(&app_state_cloned, &db_state_cloned, connection_id)?;
         client.set(&key, value, ttl).map_err(|e| e.to_string())?;
         Ok::<_, String>(())
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| e.to_string())?;
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
 
-    Ok(())
+    result
 }
 
 #[command]
 pub async fn delete_memcached_key(
-    _app_state: State<'_, AppState>,
+    app_state: State<'_, AppState>,
     db_state: State<'_, DbState>,
     connection_id: i64,
     key: String,
 ) -> Result<(), String> {
+    ensure_writable(&db_state, connection_id).await?;
+
+    let app_state_cloned = app_state.inner().clone();
     let db_state_cloned = db_state.inner().clone();
 
-    tauri::async_runtime::spawn_blocking(move || {
-        let client = get_or_create_client(&db_state_cloned, connection_id)?;
+    let started = Instant::now();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let client = get_or_create_client(&app_state_cloned, &db_state_cloned, connection_id)?;
         client.delete(&key).map_err(|e| e.to_string())?;
         Ok::<_, String>(())
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| e.to_string())?;
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
 
-    Ok(())
+    result
 }
@@ -1,19 +1,1190 @@
 use chrono::NaiveDateTime;
+use crate::secret_provider::SecretSource;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use sqlx::FromRow;
+use sqlx::pool::PoolOptions;
+use sqlx::types::Json;
+use sqlx::{Database, FromRow};
+use std::collections::HashMap;
+use std::time::Duration;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ColumnInfo {
     pub name: String,
     pub type_name: String,
+    // 前端的展示方式提示："json" 表示值为结构化 JSON，可按树形展示
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_hint: Option<String>,
+    // 以下为列在来源表中的定义，只在结果列能对应回某张表的列时提供（目前为 MySQL 单表查询）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+    #[serde(default)]
+    pub primary_key: bool,
+    #[serde(default)]
+    pub auto_increment: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+    // 字符类型的最大长度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u64>,
+    // 数值类型的精度 / 小数位数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SqlResult {
     pub columns: Vec<ColumnInfo>,
     pub rows: Vec<Map<String, Value>>,
     pub affected_rows: u64,
+    // 语句执行耗时（不含结果转换）
+    #[serde(default)]
+    pub duration_ms: u64,
+    // 服务端返回的警告（如 MySQL 的 SHOW WARNINGS：截断、隐式类型转换等），MySQL 需在执行时指定 with_warnings
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    // INSERT 生成的自增主键 / rowid，其他语句为空
+    #[serde(default)]
+    pub last_insert_id: Option<u64>,
+    // 安全模式自动追加的 LIMIT，未改写语句时为空
+    #[serde(default)]
+    pub applied_limit: Option<u64>,
+    // 结果来自结果缓存（未重新执行）
+    #[serde(default)]
+    pub from_cache: bool,
+    // 结果集过大时写入了临时文件，rows 只有首页，其余通过 fetch_result_page 读取
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spilled: Option<SpilledResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpilledResult {
+    pub result_id: String,
+    pub total_rows: u64,
+}
+
+// execute_sql_streaming 返回的第一页，后续页通过 fetch_more(handle) 获取
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamedResult {
+    pub handle: String,
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Map<String, Value>>,
+    pub done: bool,
+    // 安全模式自动追加的 LIMIT，未改写语句时为空
+    #[serde(default)]
+    pub applied_limit: Option<u64>,
+}
+
+// validate_sql / validate_sqlite_sql 的检查结果，语句本身不会被执行
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SqlValidation {
+    pub valid: bool,
+    // 服务端返回的语法错误、表或列不存在等
+    pub error: Option<String>,
+    // FROM / JOIN 中引用的表，db.table 形式保留库名
+    pub tables: Vec<String>,
+    // MySQL 为 EXPLAIN 估计的扫描行数；SQLite 为单表 UPDATE / DELETE 实际匹配的行数。无法估计时为空
+    pub estimated_rows: Option<u64>,
+}
+
+// 分页表格的总行数：估计值不执行查询，精确值需要执行 COUNT(*)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RowCount {
+    pub estimated_rows: Option<u64>,
+    pub exact_rows: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResultPage {
+    pub rows: Vec<Map<String, Value>>,
+    pub done: bool,
+}
+
+// diff_results 的一侧：执行 sql（可指定库），或读取 store_query_result 保存的结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffSource {
+    pub sql: Option<String>,
+    pub db_name: Option<String>,
+    pub result_id: Option<String>,
+}
+
+// 键相同但其他列不同的行
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RowChange {
+    pub key: Map<String, Value>,
+    pub before: Map<String, Value>,
+    pub after: Map<String, Value>,
+    pub changed_columns: Vec<String>,
+}
+
+// 以 key_columns 为键对比两个结果：added 只在右侧，removed 只在左侧
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResultDiff {
+    pub key_columns: Vec<String>,
+    pub added: Vec<Map<String, Value>>,
+    pub removed: Vec<Map<String, Value>>,
+    pub changed: Vec<RowChange>,
+    pub unchanged: u64,
+}
+
+// run_chart_query 的参数：数据来源为 table 或只读 sql 二选一
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChartQueryArgs {
+    pub table: Option<String>, // 可带库名 / schema 前缀，如 "db.orders"
+    pub sql: Option<String>,
+    pub db_name: Option<String>,
+    pub group_by: String,
+    // "count" / "count_distinct" / "sum" / "avg" / "min" / "max"
+    pub aggregate: String,
+    // count 可不指定，其他聚合必须指定
+    pub value_column: Option<String>,
+    // 按时间分桶："minute" / "hour" / "day" / "week" / "month" / "year"，为空时按原值分组
+    pub bucket: Option<String>,
+}
+
+// 按 label 排序的数据点，labels 与 values 一一对应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChartSeries {
+    pub labels: Vec<Value>,
+    pub values: Vec<Option<f64>>,
+    // 分组数超过上限时只返回前面的部分
+    pub truncated: bool,
+}
+
+// search_database 的选项，均可省略
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DatabaseSearchOptions {
+    pub db_name: Option<String>,
+    // 只搜索这些表
+    pub tables: Option<Vec<String>>,
+    // 搜索的列类型："text" / "json" / "number" / "date"，默认 text 和 json
+    pub column_types: Option<Vec<String>>,
+    // true 时按整个值相等匹配，默认按包含匹配
+    pub exact: Option<bool>,
+    pub limit_per_table: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkUpdateSet {
+    pub column: String,
+    // SQL 表达式，如 "price * 1.1"、"'archived'"
+    pub expression: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkUpdateArgs {
+    pub db_name: Option<String>,
+    pub table: String, // 可带库名 / schema 前缀
+    pub set: Vec<BulkUpdateSet>,
+    // WHERE 之后的条件，为空表示更新整张表
+    pub where_clause: Option<String>,
+    pub sample_size: Option<u32>,
+}
+
+// 预览中的一行：before 为当前值，after 为按 SET 表达式计算后的值
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateSample {
+    pub before: Map<String, Value>,
+    pub after: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateResult {
+    pub sql: String,
+    // 满足条件的行数（执行时为事务中加锁后统计的行数）
+    pub matched_rows: u64,
+    // 预览时返回
+    pub sample: Vec<BulkUpdateSample>,
+    // 执行后返回
+    pub affected_rows: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FindReplaceArgs {
+    pub db_name: Option<String>,
+    pub table: String, // 可带库名 / schema 前缀
+    pub column: String,
+    pub find: String,
+    // regex 模式下可用 $1 / ${name} 引用分组
+    pub replace: String,
+    pub mode: Option<String>, // "plain"（默认） / "case_insensitive" / "regex"
+    // 额外的行过滤条件（WHERE 之后的部分）
+    pub where_clause: Option<String>,
+    pub sample_size: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FindReplaceMatch {
+    // 主键列 → 值（没有主键的 SQLite 表为 rowid）
+    pub key: Map<String, Value>,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FindReplaceResult {
+    // 包含匹配内容的行数
+    pub matched_rows: u64,
+    // 预览时返回
+    pub sample: Vec<FindReplaceMatch>,
+    // 执行后返回实际修改的行数
+    pub updated_rows: Option<u64>,
+}
+
+// generate_fake_data 中单列的生成规则，未指定的列按列名、类型和外键推断
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FakeColumnRule {
+    // "skip" / "null" / "constant" / "values" / "sequence" / "integer" / "decimal" / "boolean" /
+    // "date" / "datetime" / "time" / "name" / "first_name" / "last_name" / "email" / "phone" /
+    // "city" / "country" / "company" / "address" / "url" / "uuid" / "word" / "sentence" /
+    // "paragraph" / "foreign_key"
+    pub kind: String,
+    // integer / decimal 的范围，sequence 的起始值
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    // constant 的值
+    pub value: Option<Value>,
+    // values 的候选值
+    pub values: Option<Vec<Value>>,
+    // date / datetime 的范围，格式 YYYY-MM-DD 或 YYYY-MM-DD HH:MM:SS
+    pub start: Option<String>,
+    pub end: Option<String>,
+    // foreign_key 的来源，"table.column"
+    pub reference: Option<String>,
+    // 生成 NULL 的比例（0 ~ 1）
+    pub null_ratio: Option<f64>,
+}
+
+// generate_code 的结果，file_name 为建议的文件名
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeneratedCode {
+    pub target: String,
+    pub file_name: String,
+    pub content: String,
+}
+
+// 导出时单列的脱敏规则
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MaskingRule {
+    // "hash"（加盐 SHA-256）/ "redact"（替换为 *）/ "fake"（替换为假数据）/ "null"
+    pub kind: String,
+    // redact 时保留开头 / 结尾的字符数，如只显示卡号后四位
+    pub keep_start: Option<usize>,
+    pub keep_end: Option<usize>,
+    // fake 时生成的数据类型，取值同 FakeColumnRule.kind（如 "email"、"name"），默认 "word"
+    pub fake: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultExportArgs {
+    pub sql: String,
+    pub db_name: Option<String>,
+    pub format: String, // "csv" / "json" / "sql"
+    pub path: String,
+    // sql 格式 INSERT 语句中的表名，默认取查询的来源表
+    pub table_name: Option<String>,
+    // 列名 → 脱敏规则
+    pub masking: Option<HashMap<String, MaskingRule>>,
+    // hash / fake 使用的盐，同一盐下相同的原值得到相同的结果，可保留关联关系
+    pub salt: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValueFrequency {
+    pub value: Value,
+    pub count: u64,
+}
+
+// profile_column 的结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnProfile {
+    pub total_rows: u64,
+    pub null_count: u64,
+    // null_count / total_rows，空表为 0
+    pub null_rate: f64,
+    pub distinct_count: u64,
+    pub min: Value,
+    pub max: Value,
+    // 值的平均字符长度（NULL 不计入）
+    pub avg_length: Option<f64>,
+    // 出现次数最多的非 NULL 值，按次数降序
+    pub top_values: Vec<ValueFrequency>,
+}
+
+// list_databases 的一项；拿不到大小或键数量时为 None
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatabaseInfo {
+    pub name: String,
+    pub size_bytes: Option<u64>,
+    // 仅 Redis：该库的键数量
+    pub key_count: Option<u64>,
+}
+
+// list_tables 的一项；table_type 为 "table" 或 "view"，视图没有引擎和大小
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub name: String,
+    pub table_type: String,
+    pub engine: Option<String>,
+    // MySQL 为 information_schema 的估算值（InnoDB 误差可能较大），SQLite 取自 sqlite_stat1
+    pub row_estimate: Option<u64>,
+    pub data_size_bytes: Option<u64>,
+    pub comment: Option<String>,
+}
+
+// get_table_stats 的结果；SQLite 没有引擎和更新时间，大小来自 dbstat（未启用时为空）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableStats {
+    pub name: String,
+    pub engine: Option<String>,
+    pub data_size_bytes: Option<u64>,
+    pub index_size_bytes: Option<u64>,
+    // 已分配但未使用的空间（MySQL DATA_FREE）
+    pub free_bytes: Option<u64>,
+    pub row_estimate: Option<u64>,
+    // 指定 exact 时执行 COUNT(*) 得到
+    pub exact_rows: Option<u64>,
+    // 下一个自增值；SQLite 只有 AUTOINCREMENT 表才有
+    pub auto_increment: Option<u64>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+// get_table_structure 的列定义；column_type 为完整类型（如 varchar(255)、int unsigned）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableColumn {
+    pub name: String,
+    pub column_type: String,
+    pub nullable: bool,
+    pub default_value: Option<String>,
+    // auto_increment、on update CURRENT_TIMESTAMP、生成列等附加属性
+    pub extra: Option<String>,
+    pub primary_key: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexInfo {
+    pub name: String,
+    // 按索引中的顺序；表达式索引的成员为 None
+    pub columns: Vec<Option<String>>,
+    pub unique: bool,
+    pub primary: bool,
+    pub index_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForeignKeyInfo {
+    // SQLite 不保存外键名
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    pub referenced_schema: Option<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    pub on_update: Option<String>,
+    pub on_delete: Option<String>,
+}
+
+// constraint_type 为 "PRIMARY KEY"、"UNIQUE"、"FOREIGN KEY" 或 "CHECK"；
+// CHECK 约束的 definition 为表达式，其余为 None
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstraintInfo {
+    pub name: Option<String>,
+    pub constraint_type: String,
+    pub columns: Vec<String>,
+    pub definition: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableStructure {
+    pub columns: Vec<TableColumn>,
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    pub constraints: Vec<ConstraintInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BrowseSort {
+    pub column: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+// browse_table 的过滤条件。operator 为 eq / ne / lt / lte / gt / gte、like / not_like（value 为
+// 原样的 LIKE 模式）、contains / not_contains / starts_with / ends_with（value 按字面匹配）、
+// in / not_in（value 为数组）或 is_null / is_not_null（忽略 value）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BrowseFilter {
+    pub column: String,
+    pub operator: String,
+    #[serde(default)]
+    pub value: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BrowseResult {
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Map<String, Value>>,
+    // 满足过滤条件的总行数
+    pub total_rows: u64,
+    // 从 1 开始
+    pub page: u32,
+    pub page_size: u32,
+}
+
+// insert_row / update_row / delete_row 的结果；last_insert_id 仅 insert_row 返回
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RowEditResult {
+    pub sql: String,
+    pub affected_rows: u64,
+    pub last_insert_id: Option<u64>,
+}
+
+// alter_table 中的列定义；data_type 和 default_value 为原样拼入 DDL 的 SQL 片段，
+// 如 "varchar(64)"、"'active'"、"CURRENT_TIMESTAMP"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub data_type: String,
+    #[serde(default)]
+    pub not_null: bool,
+    pub default_value: Option<String>,
+    // 仅 MySQL
+    #[serde(default)]
+    pub auto_increment: bool,
+    // 仅 MySQL
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlterOperation {
+    // 默认加在最后；first / after 指定位置
+    AddColumn {
+        definition: ColumnDefinition,
+        #[serde(default)]
+        first: bool,
+        after: Option<String>,
+    },
+    DropColumn {
+        column: String,
+    },
+    // definition.name 与 column 不同时同时重命名
+    ModifyColumn {
+        column: String,
+        definition: ColumnDefinition,
+    },
+    RenameColumn {
+        column: String,
+        new_name: String,
+    },
+    RenameTable {
+        new_name: String,
+    },
+    SetDefault {
+        column: String,
+        default_value: String,
+    },
+    DropDefault {
+        column: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlterTableArgs {
+    pub db_name: Option<String>,
+    pub table: String,
+    // 按顺序应用，之后的操作使用前面重命名后的表名和列名
+    pub operations: Vec<AlterOperation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlterTableResult {
+    // 生成的 DDL，按执行顺序
+    pub statements: Vec<String>,
+    pub executed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexSpec {
+    // 为空时按 idx_{表}_{列} / uq_{表}_{列} 命名
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub unique: bool,
+    // 仅 MySQL：BTREE / HASH / FULLTEXT / SPATIAL
+    pub index_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ForeignKeySpec {
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    // CASCADE / SET NULL / SET DEFAULT / RESTRICT / NO ACTION
+    pub on_delete: Option<String>,
+    pub on_update: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateTableArgs {
+    pub db_name: Option<String>,
+    pub table: String,
+    pub columns: Vec<ColumnDefinition>,
+    #[serde(default)]
+    pub primary_key: Vec<String>,
+    #[serde(default)]
+    pub indexes: Vec<IndexSpec>,
+    #[serde(default)]
+    pub foreign_keys: Vec<ForeignKeySpec>,
+    #[serde(default)]
+    pub if_not_exists: bool,
+    // 以下仅 MySQL
+    pub engine: Option<String>,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTableResult {
+    // 生成的 DDL，按执行顺序
+    pub statements: Vec<String>,
+    pub executed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateIndexArgs {
+    pub db_name: Option<String>,
+    pub table: String,
+    pub index: IndexSpec,
+}
+
+// 单条 DDL 的预览 / 执行结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DdlStatementResult {
+    pub statement: String,
+    pub executed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViewInfo {
+    pub name: String,
+    // 视图的 SELECT 部分
+    pub definition: String,
+    // FROM / JOIN 中引用的表或视图，其他库的带库名前缀
+    pub referenced_tables: Vec<String>,
+    // 以下仅 MySQL
+    pub updatable: Option<bool>,
+    pub check_option: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViewDefinition {
+    pub name: String,
+    pub definition: String,
+    // 完整的 CREATE VIEW 语句（MySQL 为 SHOW CREATE VIEW 的结果）
+    pub create_sql: String,
+    pub referenced_tables: Vec<String>,
+    pub updatable: Option<bool>,
+    pub check_option: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ViewArgs {
+    pub db_name: Option<String>,
+    pub name: String,
+    // 单条 SELECT 语句
+    pub select_sql: String,
+    // 仅 MySQL：CASCADED / LOCAL，对应 WITH ... CHECK OPTION
+    pub check_option: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TriggerInfo {
+    pub name: String,
+    pub table: String,
+    // BEFORE / AFTER / INSTEAD OF（仅 SQLite）
+    pub timing: String,
+    // INSERT / UPDATE / DELETE
+    pub event: String,
+    // 触发时执行的语句（MySQL 为 ACTION_STATEMENT，SQLite 为 BEGIN ... END）
+    pub statement: String,
+    pub created: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TriggerArgs {
+    pub db_name: Option<String>,
+    pub name: String,
+    pub table: String,
+    pub timing: String,
+    pub event: String,
+    // MySQL 为单条语句或 BEGIN ... END；SQLite 不带 BEGIN 时自动包上
+    pub body: String,
+    // 以下仅 SQLite：UPDATE OF 的列和 WHEN 条件
+    #[serde(default)]
+    pub update_of: Vec<String>,
+    pub when: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateDatabaseArgs {
+    pub name: String,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+    #[serde(default)]
+    pub if_not_exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoutineInfo {
+    pub name: String,
+    // PROCEDURE / FUNCTION
+    pub routine_type: String,
+    // 仅函数
+    pub return_type: Option<String>,
+    pub deterministic: bool,
+    // CONTAINS SQL / NO SQL / READS SQL DATA / MODIFIES SQL DATA
+    pub data_access: Option<String>,
+    // DEFINER / INVOKER
+    pub security_type: Option<String>,
+    pub comment: Option<String>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoutineParameter {
+    pub position: i64,
+    pub name: String,
+    // IN / OUT / INOUT，函数参数为 IN
+    pub mode: String,
+    pub data_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoutineDetail {
+    pub info: RoutineInfo,
+    pub parameters: Vec<RoutineParameter>,
+    // 没有查看权限时为空
+    pub body: Option<String>,
+    pub create_sql: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExecuteRoutineArgs {
+    pub db_name: Option<String>,
+    pub name: String,
+    pub routine_type: String,
+    // 按参数名传入 IN / INOUT 参数的值，OUT 参数忽略
+    #[serde(default)]
+    pub arguments: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoutineResult {
+    // 实际执行的 CALL / SELECT 语句
+    pub statement: String,
+    // 存储过程返回的全部结果集
+    pub result_sets: Vec<SqlResult>,
+    // OUT / INOUT 参数执行后的值
+    pub out_values: Map<String, Value>,
+    // 函数的返回值
+    pub return_value: Option<Value>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventInfo {
+    pub name: String,
+    // ENABLED / DISABLED / SLAVESIDE_DISABLED
+    pub status: String,
+    // ONE TIME / RECURRING
+    pub event_type: String,
+    // 一次性事件的执行时间
+    pub execute_at: Option<String>,
+    // 周期事件的间隔，如 "1" + "DAY"
+    pub interval_value: Option<String>,
+    pub interval_field: Option<String>,
+    pub starts: Option<String>,
+    pub ends: Option<String>,
+    // PRESERVE / NOT PRESERVE
+    pub on_completion: String,
+    pub last_executed: Option<String>,
+    pub definition: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventList {
+    // @@event_scheduler：ON / OFF / DISABLED，只有 ON 时事件才会执行
+    pub scheduler: String,
+    pub events: Vec<EventInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventDetail {
+    pub info: EventInfo,
+    pub create_sql: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EventSchedule {
+    // 在指定时间执行一次
+    At {
+        at: String,
+    },
+    // 每隔 interval 个 unit 执行，unit 如 DAY / HOUR / DAY_HOUR（复合单位的间隔写作 "1 12"）
+    Every {
+        interval: String,
+        unit: String,
+        starts: Option<String>,
+        ends: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateEventArgs {
+    pub db_name: Option<String>,
+    pub name: String,
+    pub schedule: EventSchedule,
+    // 单条语句或 BEGIN ... END
+    pub body: String,
+    // 执行完后保留事件（ON COMPLETION PRESERVE）
+    #[serde(default)]
+    pub preserve: bool,
+    // 未设置时为 ENABLE
+    pub enabled: Option<bool>,
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub if_not_exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartitionInfo {
+    pub name: String,
+    // 有子分区时每个子分区一行
+    pub subpartition_name: Option<String>,
+    pub position: i64,
+    // RANGE 为 LESS THAN 的上界，LIST 为值列表
+    pub description: Option<String>,
+    pub row_estimate: Option<u64>,
+    pub data_size_bytes: Option<u64>,
+    pub index_size_bytes: Option<u64>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TablePartitions {
+    // RANGE / LIST / HASH / KEY / RANGE COLUMNS / LIST COLUMNS / LINEAR HASH / LINEAR KEY
+    pub method: String,
+    pub expression: Option<String>,
+    pub subpartition_method: Option<String>,
+    pub subpartition_expression: Option<String>,
+    pub partitions: Vec<PartitionInfo>,
+}
+
+// diff_schemas 比较的一端；MySQL 的 db_name 为空时使用连接的默认库
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaTarget {
+    pub connection_id: i64,
+    pub db_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaDifference {
+    // "missing_table" / "extra_table" / "missing_column" / "extra_column" / "column_changed" /
+    // "primary_key_changed" / "missing_index" / "extra_index" / "index_changed"
+    pub kind: String,
+    pub table: String,
+    // 列名或索引名；表级差异为 None
+    pub object: Option<String>,
+    // 两端的定义，不存在的一端为 None
+    pub source: Option<String>,
+    pub target: Option<String>,
+    // 让目标与源一致的语句；无法直接生成时为空（如 SQLite 修改列需要重建表）
+    pub statements: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphColumn {
+    pub name: String,
+    pub column_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphTable {
+    pub name: String,
+    pub columns: Vec<GraphColumn>,
+}
+
+// 外键边：from 为引用方（子表），to 为被引用方（父表）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelationshipEdge {
+    // SQLite 不保存外键名
+    pub name: Option<String>,
+    pub from_table: String,
+    pub from_columns: Vec<String>,
+    // 引用其他库的表时为该库名
+    pub to_schema: Option<String>,
+    pub to_table: String,
+    pub to_columns: Vec<String>,
+    // 外键列都允许 NULL 时关系是可选的
+    pub optional: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelationshipGraph {
+    pub tables: Vec<GraphTable>,
+    pub edges: Vec<RelationshipEdge>,
+    // 按 format 生成的 Mermaid erDiagram 或 Graphviz DOT 文本
+    pub diagram: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutocompleteColumn {
+    pub name: String,
+    pub data_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutocompleteTable {
+    pub name: String,
+    pub columns: Vec<AutocompleteColumn>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutocompleteMetadata {
+    pub databases: Vec<String>,
+    // tables / routines 所属的库，未指定且连接没有当前库时为 None
+    pub database: Option<String>,
+    pub tables: Vec<AutocompleteTable>,
+    // 存储过程和函数名（SQLite 为空）
+    pub routines: Vec<String>,
+    // 本次返回的是否为缓存内容
+    pub cached: bool,
+}
+
+// 字符集审计中的一列；mismatch 为 "charset"（字符集不同）/ "collation"（只有排序规则不同）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnCharset {
+    pub name: String,
+    pub column_type: String,
+    pub charset: String,
+    pub collation: String,
+    pub mismatch: Option<String>,
+}
+
+// 表的默认字符集 / 排序规则和其中的字符类型列
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableCharset {
+    pub name: String,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+    pub mismatch: Option<String>,
+    pub columns: Vec<ColumnCharset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CharsetAudit {
+    pub database: String,
+    pub default_charset: String,
+    pub default_collation: String,
+    // 对比的目标，未指定时为库的默认值
+    pub target_charset: String,
+    pub target_collation: String,
+    pub tables: Vec<TableCharset>,
+    // 表默认值或任一列不一致的表数，以及不一致的列数
+    pub mismatched_tables: usize,
+    pub mismatched_columns: usize,
+    // 转换为目标字符集的语句
+    pub statements: Vec<String>,
+    pub executed: bool,
+}
+
+// truncate_tables 的结果：order 为执行顺序（引用方在前），statements 为预览或已执行的语句
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TruncateTablesResult {
+    pub order: Vec<String>,
+    pub statements: Vec<String>,
+    // 选中的表之间有循环或自引用外键时，执行期间关闭外键检查
+    pub foreign_key_checks_disabled: bool,
+    pub executed: bool,
+}
+
+// dump_tables 的参数；include_data 默认为 true，drop_existing 为 true 时先写入 DROP TABLE IF EXISTS
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableDumpArgs {
+    pub db_name: Option<String>,
+    pub tables: Vec<String>,
+    pub path: String,
+    pub include_data: Option<bool>,
+    pub drop_existing: Option<bool>,
+}
+
+// search_objects 的一项；object_type 为 table / view / column / procedure / function / trigger，
+// 列和触发器的 table 为所属的表，data_type 只有列才有
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectMatch {
+    pub object_type: String,
+    pub database: String,
+    pub table: Option<String>,
+    pub name: String,
+    pub data_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectSearchResult {
+    pub matches: Vec<ObjectMatch>,
+    // 超过上限时只返回前面的部分
+    pub truncated: bool,
+}
+
+// get_server_status 的一次采样；前端把上一次的结果原样传回，用于计算两次采样之间的速率
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerStatus {
+    pub sampled_at_ms: i64,
+    // SHOW GLOBAL STATUS 的全部变量
+    pub status: HashMap<String, String>,
+    #[serde(default)]
+    pub rates: Option<ServerStatusRates>,
+}
+
+// 两次采样之间的每秒速率；threads_* 为本次采样时的当前值
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerStatusRates {
+    pub interval_secs: f64,
+    // 按 Questions 计算，只统计客户端发来的语句
+    pub queries_per_sec: f64,
+    pub selects_per_sec: f64,
+    pub inserts_per_sec: f64,
+    pub updates_per_sec: f64,
+    pub deletes_per_sec: f64,
+    pub transactions_per_sec: f64,
+    pub connections_per_sec: f64,
+    pub bytes_received_per_sec: f64,
+    pub bytes_sent_per_sec: f64,
+    pub slow_queries: i64,
+    pub threads_connected: i64,
+    pub threads_running: i64,
+    // 所有数值变量中有变化的差值（计数器和瞬时值都在内）
+    pub deltas: HashMap<String, i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerVariable {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatabaseUser {
+    pub user: String,
+    pub host: String,
+    pub auth_plugin: Option<String>,
+}
+
+// grant_privileges / revoke_privileges 的参数。未指定 database 时为全局权限（*.*），
+// 只指定 database 时为库级权限，再指定 table 时为表级权限；columns 非空时为列级权限
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrivilegeArgs {
+    pub user: String,
+    // 默认为 '%'
+    pub host: Option<String>,
+    // 如 SELECT、INSERT、ALL PRIVILEGES，MySQL 8 的动态权限如 BACKUP_ADMIN 也可以
+    pub privileges: Vec<String>,
+    pub database: Option<String>,
+    pub table: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<String>,
+    // 授权时加 WITH GRANT OPTION，回收时一并回收 GRANT OPTION
+    #[serde(default)]
+    pub grant_option: bool,
+}
+
+// read_slow_log 中一类查询（按指纹归并）的汇总；example 为其中耗时最长的一条原始语句
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlowQueryDigest {
+    pub fingerprint: String,
+    pub example: String,
+    pub database: Option<String>,
+    pub count: u64,
+    pub total_time_secs: f64,
+    pub avg_time_secs: f64,
+    pub max_time_secs: f64,
+    pub total_lock_secs: f64,
+    pub rows_sent: u64,
+    pub rows_examined: u64,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlowLogReport {
+    // "table"（mysql.slow_log）或 "file"
+    pub source: String,
+    // 读取的日志文件路径，source 为 table 时为空
+    pub file_path: Option<String>,
+    // 服务端当前是否开启了慢查询日志
+    pub slow_log_enabled: bool,
+    pub entries_scanned: u64,
+    pub entries_matched: u64,
+    // 按总耗时从高到低排列
+    pub digests: Vec<SlowQueryDigest>,
+    // 指纹数超过上限时只返回前面的部分
+    pub truncated: bool,
+}
+
+// events_statements_summary_by_digest 的一行，耗时单位为毫秒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueryDigestStat {
+    pub schema_name: Option<String>,
+    pub digest: Option<String>,
+    // 规范化后的语句，超出 performance_schema_max_digest_length 时被截断
+    pub digest_text: Option<String>,
+    pub exec_count: u64,
+    pub total_latency_ms: f64,
+    pub avg_latency_ms: f64,
+    pub max_latency_ms: f64,
+    pub lock_time_ms: f64,
+    pub rows_examined: u64,
+    pub rows_sent: u64,
+    pub rows_affected: u64,
+    pub errors: u64,
+    pub no_index_used: u64,
+    // 占统计范围内总耗时的百分比
+    pub latency_percent: f64,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryDigestReport {
+    pub total_latency_ms: f64,
+    pub total_executions: u64,
+    pub by_total_latency: Vec<QueryDigestStat>,
+    pub by_rows_examined: Vec<QueryDigestStat>,
+    pub by_exec_count: Vec<QueryDigestStat>,
+}
+
+// transfer_data 的参数：把 source 的 tables 复制到 target，目标端没有的表按源表结构创建
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferArgs {
+    pub source: SchemaTarget,
+    pub target: SchemaTarget,
+    pub tables: Vec<String>,
+    // 目标端已有同名表时先删除再重建；否则向已有表追加数据
+    #[serde(default)]
+    pub drop_existing: bool,
+    // 每条 INSERT 的行数，默认 1000
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub differences: Vec<SchemaDifference>,
+    // 所有差异的同步语句，按执行顺序
+    pub statements: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct QueryHistory {
+    pub id: i64,
+    pub connection_id: i64,
+    pub query_text: String,
+    pub duration_ms: i64,
+    pub row_count: Option<i64>,
+    pub affected_rows: Option<i64>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub pinned: bool,
+    pub executed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryHistoryPage {
+    pub items: Vec<QueryHistory>,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Snippet {
+    pub id: i64,
+    pub name: String,
+    pub content: String,
+    pub language: String, // "sql" / "redis"
+    pub folder: Option<String>,
+    pub description: Option<String>,
+    pub connection_id: Option<i64>, // 为空表示全局片段
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+    // content 中的 ${name} 占位符，按出现顺序去重
+    #[sqlx(skip)]
+    pub placeholders: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnippetArgs {
+    pub name: String,
+    pub content: String,
+    pub language: Option<String>,
+    pub folder: Option<String>,
+    pub description: Option<String>,
+    pub connection_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ScheduledQuery {
+    pub id: i64,
+    pub name: String,
+    pub connection_id: i64,
+    pub db_name: Option<String>,
+    // sql 与 snippet_id 二选一；片段在每次执行时读取，占位符取默认值
+    pub sql: Option<String>,
+    pub snippet_id: Option<i64>,
+    pub schedule: String, // 5 段 cron 表达式，按本地时间计算
+    pub enabled: bool,
+    pub next_run_at: Option<NaiveDateTime>, // UTC
+    pub last_run_at: Option<NaiveDateTime>,
+    pub last_status: Option<String>, // "success" / "failed"
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduledQueryArgs {
+    pub name: String,
+    pub connection_id: i64,
+    pub db_name: Option<String>,
+    pub sql: Option<String>,
+    pub snippet_id: Option<i64>,
+    pub schedule: String,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ScheduledQueryRun {
+    pub id: i64,
+    pub schedule_id: i64,
+    pub started_at: NaiveDateTime,
+    pub duration_ms: i64,
+    pub status: String,
+    pub affected_rows: i64,
+    pub row_count: i64,
+    // 结果集的前若干行
+    pub rows: Option<Json<Vec<Map<String, Value>>>>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -29,4 +1200,195 @@ pub struct Connection {
     pub created_at: NaiveDateTime,
     pub sort_order: i32,
     pub group_id: Option<i64>,
+    pub ssh_enabled: bool,
+    pub ssh_host: Option<String>,
+    pub ssh_port: Option<i32>,
+    pub ssh_username: Option<String>,
+    pub ssh_password: Option<String>,
+    pub ssh_key_path: Option<String>,
+    pub ssh_auth_method: String, // "password", "key" or "agent"
+    pub ssl_mode: Option<String>, // e.g., "disabled", "required", "verify_ca"
+    pub ssl_ca_path: Option<String>,
+    pub ssl_cert_path: Option<String>,
+    pub ssl_key_path: Option<String>,
+    pub tags: Json<Vec<String>>,
+    pub read_only: bool, // 只读连接，后端拒绝写操作
+    pub options: Json<ConnectionOptions>,
+    pub environment: Option<String>, // "dev"、"staging" 或 "prod"
+    pub last_used_at: Option<NaiveDateTime>,
+    pub use_count: i64, // 累计执行的查询/命令次数
+}
+
+// 表格浏览会反复执行同样的模板查询（表结构、分页等），比 sqlx 默认的 100 条多留一些
+const DEFAULT_STATEMENT_CACHE_SIZE: usize = 256;
+
+// 连接的扩展配置，存放在 connections.options（JSON）中
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ConnectionOptions {
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+    pub time_zone: Option<String>, // 例如 "+08:00"、"Asia/Shanghai"
+    pub redis_db: Option<u32>,     // Redis 库号，优先于 database 字段
+    pub connect_timeout_secs: Option<u64>,
+    pub application_name: Option<String>,
+    pub socket_path: Option<String>, // 本地 unix socket，设置后忽略 host / port
+    pub password_source: Option<SecretSource>, // 设置后从外部读取密码，不在本地保存
+    // 连接池配置（MySQL / SQLite），未设置时使用默认值
+    pub max_connections: Option<u32>,
+    pub min_connections: Option<u32>,
+    pub acquire_timeout_secs: Option<u64>, // 未设置时沿用 connect_timeout_secs
+    pub idle_timeout_secs: Option<u64>,
+    // 每个连接缓存的预处理语句数（按 SQL 文本 LRU 淘汰），0 表示不缓存
+    pub statement_cache_size: Option<usize>,
+    pub statement_timeout_secs: Option<u64>, // 单条语句的最长执行时间（仅 MySQL / MariaDB / TiDB）
+    // TIMESTAMP 的显示方式（MySQL 系）："local" 本地时间、"local_offset" 本地时间并带偏移、
+    // "utc"、"server" 按会话时区原样显示。未设置时，设置了 time_zone 为 "server"，否则为 "local"；
+    // 非 server 模式下会话时区固定为 UTC，time_zone 不生效
+    pub timestamp_display: Option<String>,
+    // 安全模式（MySQL 系 / SQLite）：没有 LIMIT 的单条 SELECT 自动追加 LIMIT n，未设置或 0 表示关闭
+    pub safe_limit: Option<u64>,
+    // 结果缓存（MySQL 系 / SQLite）：相同的查询在该秒数内直接返回缓存的结果，未设置或 0 表示关闭
+    pub result_cache_ttl_secs: Option<u64>,
+    // 未识别的配置项原样保留
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ConnectionOptions {
+    pub fn socket_path(&self) -> Option<&str> {
+        self.socket_path.as_deref().filter(|p| !p.trim().is_empty())
+    }
+
+    pub fn time_zone(&self) -> Option<&str> {
+        self.time_zone.as_deref().filter(|t| !t.trim().is_empty())
+    }
+
+    pub fn timestamp_display(&self) -> TimestampDisplay {
+        match self.timestamp_display.as_deref() {
+            Some("local") => TimestampDisplay::Local,
+            Some("local_offset") => TimestampDisplay::LocalWithOffset,
+            Some("utc") => TimestampDisplay::Utc,
+            Some("server") => TimestampDisplay::Server,
+            _ if self.time_zone().is_some() => TimestampDisplay::Server,
+            _ => TimestampDisplay::Local,
+        }
+    }
+
+    pub fn statement_cache_capacity(&self) -> usize {
+        self.statement_cache_size
+            .unwrap_or(DEFAULT_STATEMENT_CACHE_SIZE)
+    }
+
+    pub fn connect_timeout(&self, default_secs: u64) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs.unwrap_or(default_secs))
+    }
+
+    // 按连接配置生成连接池参数
+    pub fn pool_options<DB: Database>(&self, default_timeout_secs: u64) -> PoolOptions<DB> {
+        let max_connections = self.max_connections.unwrap_or(5).max(1);
+        let min_connections = self.min_connections.unwrap_or(0).min(max_connections);
+        let acquire_timeout = self
+            .acquire_timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| self.connect_timeout(default_timeout_secs));
+        let mut pool_options = PoolOptions::<DB>::new()
+            .max_connections(max_connections)
+            .min_connections(min_connections)
+            .acquire_timeout(acquire_timeout);
+        if let Some(secs) = self.idle_timeout_secs {
+            pool_options = pool_options.idle_timeout(Duration::from_secs(secs));
+        }
+        pool_options
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampDisplay {
+    #[default]
+    Local,
+    LocalWithOffset,
+    Utc,
+    Server,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ConnectionGroup {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub sort_order: i32,
+    pub created_at: NaiveDateTime,
+    pub parent_id: Option<i64>, // 为空表示顶层分组
+}
+
+// 新建/编辑连接时前端提交的参数（未保存的连接也可以直接用于测试连接）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CreateConnectionArgs {
+    pub name: String,
+    pub db_type: String,
+    pub host: Option<String>,
+    pub port: Option<i32>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+    pub group_id: Option<i64>,
+    #[serde(default)]
+    pub ssh_enabled: bool,
+    pub ssh_host: Option<String>,
+    pub ssh_port: Option<i32>,
+    pub ssh_username: Option<String>,
+    pub ssh_password: Option<String>,
+    pub ssh_key_path: Option<String>,
+    pub ssh_auth_method: Option<String>,
+    pub ssl_mode: Option<String>,
+    pub ssl_ca_path: Option<String>,
+    pub ssl_cert_path: Option<String>,
+    pub ssl_key_path: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub options: ConnectionOptions,
+    pub environment: Option<String>,
+}
+
+impl CreateConnectionArgs {
+    // 转换为未落库的 Connection，id 由调用方指定
+    pub fn into_connection(self, id: i64) -> Connection {
+        Connection {
+            id,
+            name: self.name,
+            db_type: self.db_type,
+            host: self.host,
+            port: self.port,
+            username: self.username,
+            password: self.password,
+            database: self.database,
+            created_at: chrono::Local::now().naive_local(),
+            sort_order: 0,
+            group_id: self.group_id,
+            ssh_enabled: self.ssh_enabled,
+            ssh_host: self.ssh_host,
+            ssh_port: self.ssh_port,
+            ssh_username: self.ssh_username,
+            ssh_password: self.ssh_password,
+            ssh_key_path: self.ssh_key_path,
+            ssh_auth_method: self
+                .ssh_auth_method
+                .unwrap_or_else(|| "password".to_string()),
+            ssl_mode: self.ssl_mode,
+            ssl_ca_path: self.ssl_ca_path,
+            ssl_cert_path: self.ssl_cert_path,
+            ssl_key_path: self.ssl_key_path,
+            tags: Json(self.tags),
+            read_only: self.read_only,
+            options: Json(self.options),
+            environment: self.environment,
+            last_used_at: None,
+            use_count: 0,
+        }
+    }
 }
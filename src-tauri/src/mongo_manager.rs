@@ -0,0 +1,271 @@
+use crate::db::DbState;
+use crate::models::{Connection, DatabaseInfo};
+use crate::redact::redact_credentials;
+use crate::ssh_tunnel::resolve_endpoint;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use mongodb::bson::{Bson, Document};
+use mongodb::options::ClientOptions;
+use mongodb::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::future::{Future, IntoFuture};
+use tauri::{command, State};
+use tokio::time::{timeout, Duration};
+use urlencoding::encode;
+
+const MONGO_COMMAND_TIMEOUT_SECS: u64 = 30;
+const MONGO_DEFAULT_FIND_LIMIT: i64 = 100;
+const MONGO_DEFAULT_AGGREGATE_LIMIT: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MongoResult {
+    pub documents: Vec<JsonValue>,
+}
+
+async fn get_or_create_mongo_client(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Client, String> {
+    // 1. Check cache
+    {
+        let clients = app_state.mongo_clients.lock().await;
+        if let Some(client) = clients.get(&connection_id) {
+            return Ok(client.clone());
+        }
+    }
+
+    // 2. Fetch connection info
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
+
+    if connection.db_type != "mongodb" {
+        return Err("Only MongoDB is supported for this operation".to_string());
+    }
+
+    // 3. Build connection URL (the default database doubles as the auth source)
+    let host = connection.host.as_deref().ok_or("Host is required")?;
+    let port = connection.port.unwrap_or(27017) as u16;
+    let (host, port) = resolve_endpoint(app_state, &connection, host, port).await?;
+    let ssh_enabled = connection.ssh_enabled;
+    let extra = connection.options.0.clone();
+    let username = connection.username.unwrap_or_default();
+    let password = connection.password.unwrap_or_default();
+    let database = connection.database.unwrap_or_default();
+
+    let url = if !username.is_empty() {
+        format!(
+            "mongodb://{}:{}@{}:{}/{}",
+            encode(&username),
+            encode(&password),
+            host,
+            port,
+            database
+        )
+    } else {
+        format!("mongodb://{}:{}/{}", host, port, database)
+    };
+
+    // 4. Create Client
+    let mut options = ClientOptions::parse(url)
+        .await
+        .map_err(|e| redact_credentials(&format!("Failed to parse MongoDB options: {}", e)))?;
+    options.app_name = Some(
+        extra
+            .application_name
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| "xDB".to_string()),
+    );
+    if let Some(secs) = extra.connect_timeout_secs {
+        options.connect_timeout = Some(Duration::from_secs(secs));
+    }
+    options.server_selection_timeout = Some(Duration::from_secs(10));
+    // Replica set discovery would bypass the tunnel, so talk to the forwarded node only
+    if ssh_enabled {
+        options.direct_connection = Some(true);
+    }
+
+    let client = Client::with_options(options)
+        .map_err(|e| redact_credentials(&format!("Failed to create MongoDB client: {}", e)))?;
+
+    // 5. Cache client
+    let mut clients = app_state.mongo_clients.lock().await;
+    clients.insert(connection_id, client.clone());
+
+    Ok(client)
+}
+
+async fn query_with_timeout<T, F>(future: F, context: &str) -> Result<T, String>
+where
+    F: Future<Output = Result<T, mongodb::error::Error>>,
+{
+    match timeout(Duration::from_secs(MONGO_COMMAND_TIMEOUT_SECS), future).await {
+        Ok(result) => result.map_err(|e| format!("{} failed: {}", context, e)),
+        Err(_) => Err(format!(
+            "{} timed out after {}s",
+            context, MONGO_COMMAND_TIMEOUT_SECS
+        )),
+    }
+}
+
+// Parse a (possibly extended) JSON object coming from the frontend into a BSON document
+fn json_to_document(value: JsonValue, what: &str) -> Result<Document, String> {
+    match Bson::try_from(value) {
+        Ok(Bson::Document(doc)) => Ok(doc),
+        Ok(_) => Err(format!("{} must be a JSON object", what)),
+        Err(e) => Err(format!("Invalid {}: {}", what, e)),
+    }
+}
+
+fn optional_document(value: Option<JsonValue>, what: &str) -> Result<Option<Document>, String> {
+    match value {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(v) => json_to_document(v, what).map(Some),
+    }
+}
+
+// Drain a cursor into relaxed extended JSON, stopping after `limit` documents
+async fn collect_documents(
+    mut cursor: mongodb::Cursor<Document>,
+    limit: usize,
+) -> Result<Vec<JsonValue>, String> {
+    let mut documents = Vec::new();
+    while documents.len() < limit
+        && query_with_timeout(cursor.advance(), "MongoDB cursor").await?
+    {
+        let doc = cursor
+            .deserialize_current()
+            .map_err(|e| format!("Failed to decode document: {}", e))?;
+        documents.push(Bson::Document(doc).into_relaxed_extjson());
+    }
+    Ok(documents)
+}
+
+#[command]
+pub async fn list_mongo_databases(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<String>, String> {
+    let client = get_or_create_mongo_client(&app_state, &db_state, connection_id).await?;
+    let mut names =
+        query_with_timeout(client.list_database_names().into_future(), "List databases").await?;
+    names.sort();
+    Ok(names)
+}
+
+// list_databases 使用：带磁盘占用的数据库列表
+pub async fn list_mongo_database_sizes(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<DatabaseInfo>, String> {
+    let client = get_or_create_mongo_client(app_state, db_state, connection_id).await?;
+    let specs = query_with_timeout(client.list_databases().into_future(), "List databases").await?;
+    let mut databases: Vec<DatabaseInfo> = specs
+        .into_iter()
+        .map(|spec| DatabaseInfo {
+            name: spec.name,
+            size_bytes: Some(spec.size_on_disk),
+            key_count: None,
+        })
+        .collect();
+    databases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(databases)
+}
+
+#[command]
+pub async fn list_mongo_collections(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    database: String,
+) -> Result<Vec<String>, String> {
+    let client = get_or_create_mongo_client(&app_state, &db_state, connection_id).await?;
+    let mut names = query_with_timeout(
+        client.database(&database).list_collection_names().into_future(),
+        "List collections",
+    )
+    .await?;
+    names.sort();
+    Ok(names)
+}
+
+#[command]
+pub async fn mongo_find(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    database: String,
+    collection: String,
+    filter: Option<JsonValue>,
+    projection: Option<JsonValue>,
+    sort: Option<JsonValue>,
+    skip: Option<u64>,
+    limit: Option<i64>,
+) -> Result<MongoResult, String> {
+    let client = get_or_create_mongo_client(&app_state, &db_state, connection_id).await?;
+    let coll = client
+        .database(&database)
+        .collection::<Document>(&collection);
+
+    let filter = optional_document(filter, "filter")?.unwrap_or_default();
+    let limit = limit.unwrap_or(MONGO_DEFAULT_FIND_LIMIT);
+
+    let mut find = coll.find(filter).limit(limit);
+    if let Some(projection) = optional_document(projection, "projection")? {
+        find = find.projection(projection);
+    }
+    if let Some(sort) = optional_document(sort, "sort")? {
+        find = find.sort(sort);
+    }
+    if let Some(skip) = skip {
+        find = find.skip(skip);
+    }
+
+    let cursor = query_with_timeout(find.into_future(), "MongoDB find").await?;
+    // A limit of 0 means "no limit" in MongoDB
+    let max_documents = match limit {
+        0 => usize::MAX,
+        n => n.unsigned_abs() as usize,
+    };
+    let documents = collect_documents(cursor, max_documents).await?;
+
+    Ok(MongoResult { documents })
+}
+
+#[command]
+pub async fn mongo_aggregate(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    database: String,
+    collection: String,
+    pipeline: Vec<JsonValue>,
+    limit: Option<usize>,
+) -> Result<MongoResult, String> {
+    let client = get_or_create_mongo_client(&app_state, &db_state, connection_id).await?;
+    let coll = client
+        .database(&database)
+        .collection::<Document>(&collection);
+
+    let stages = pipeline
+        .into_iter()
+        .map(|stage| json_to_document(stage, "pipeline stage"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let cursor =
+        query_with_timeout(coll.aggregate(stages).into_future(), "MongoDB aggregate").await?;
+    let documents =
+        collect_documents(cursor, limit.unwrap_or(MONGO_DEFAULT_AGGREGATE_LIMIT)).await?;
+
+    Ok(MongoResult { documents })
+}
@@ -1,13 +1,90 @@
+use crate::alter_table::{qualified_name, quote_identifier};
+use crate::autocomplete::{cached_table_columns, invalidate_metadata, store_table_columns};
+use crate::connection_stats::record_query;
 use crate::db::DbState;
-use crate::models::{ColumnInfo, Connection, SqlResult};
+use crate::dialect::{detect_server_profile, ServerProfile};
+use crate::guard::{ensure_sql_allowed, safe_limit};
+use crate::models::{
+    ColumnInfo, Connection, ResultPage, RowCount, SpilledResult, SqlResult, SqlValidation,
+    StreamedResult, TimestampDisplay,
+};
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::query_queue::{
+    acquire_query_slot, finish_running_query, new_execution_id, register_running_query,
+    RunningQuery,
+};
+use crate::reconnect::reconnect_with_backoff;
+use crate::redact::redact_error;
+use crate::result_cache::{cached_results, invalidate_results, result_cache_ttl, store_results};
+use crate::result_spill::RowSpooler;
+use crate::session::{mysql_session, QueryConnection};
+use crate::sql_classifier::{
+    changes_schema, is_explainable, is_insert, is_read_query, referenced_tables, returns_rows,
+    single_table_source, with_safe_limit, without_paging, SqlFlavor,
+};
+use crate::ssh_tunnel::resolve_endpoint;
 use crate::state::AppState;
+use crate::undo::{capture_mysql_snapshot, save_snapshot};
+use crate::vault::reveal_secrets;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
 use rust_decimal::Decimal;
-use serde_json::{Map, Value};
-use sqlx::mysql::{MySqlPoolOptions, MySqlRow};
-use sqlx::{Column, MySqlPool, Row, Statement, TypeInfo};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::mysql::{
+    MySqlArguments, MySqlColumn, MySqlConnectOptions, MySqlPoolOptions, MySqlRow, MySqlSslMode,
+};
+use sqlx::pool::PoolConnection;
+use sqlx::query::Query;
+use sqlx::{
+    Column, Connection as _, Either, Executor, MySql, MySqlConnection, MySqlPool, Row, Statement,
+    TypeInfo,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{command, State};
-use urlencoding::encode;
+use tokio::sync::{mpsc, Mutex};
+
+// 流式查询每页默认行数，以及后台读取时最多缓冲的行数（缓冲满时暂停读取）
+const STREAM_DEFAULT_BATCH_SIZE: usize = 500;
+const STREAM_BUFFER_ROWS: usize = 2_000;
+// 缓冲区已满且超过该时间没有读取时视为句柄已被遗弃，关闭连接并释放名额
+const STREAM_IDLE_TIMEOUT_SECS: u64 = 300;
+
+// 流式查询的结果句柄：后台任务持有连接逐行读取，通过 channel 交给 fetch_more
+pub struct ResultStream {
+    connection_id: i64,
+    receiver: Arc<Mutex<mpsc::Receiver<Result<MySqlRow, String>>>>,
+    timestamp_display: TimestampDisplay,
+}
+
+const MYSQL_CONNECT_TIMEOUT_SECS: u64 = 30;
+
+// 超过该长度的二进制值在结果中只返回占位信息（长度 + 开头字节），内容通过 fetch_blob 按需获取
+const BLOB_INLINE_MAX_BYTES: usize = 1024;
+const BLOB_PREVIEW_BYTES: usize = 16;
+// fetch_blob 以 base64 返回的上限，更大的值写入临时文件
+const BLOB_BASE64_MAX_BYTES: usize = 8 * 1024 * 1024;
+
+pub const BINARY_TYPES: &[&str] = &[
+    "BINARY",
+    "VARBINARY",
+    "BLOB",
+    "TINYBLOB",
+    "MEDIUMBLOB",
+    "LONGBLOB",
+];
+
+#[derive(Debug, Serialize)]
+pub struct BlobData {
+    pub length: usize,
+    pub base64: Option<String>,
+    // 写入文件时的路径（save_path 或临时文件）
+    pub path: Option<String>,
+}
 
 // 辅助函数：获取或创建 MySQL 连接池
 async fn get_or_create_pool(
@@ -16,11 +93,7 @@ async fn get_or_create_pool(
     connection_id: i64,
     db_name: Option<String>,
 ) -> Result<MySqlPool, String> {
-    let cache_key = if let Some(ref db) = db_name {
-        format!("{}:{}", connection_id, db)
-    } else {
-        connection_id.to_string()
-    };
+    let cache_key = pool_cache_key(connection_id, db_name.as_deref());
 
     {
         let pools = app_state.pools.lock().await;
@@ -39,27 +112,66 @@ async fn get_or_create_pool(
     .await
     .map_err(|e| format!("Failed to fetch connection info: {}", e))?
     .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
 
-    if connection.db_type != "mysql" {
+    // MariaDB / TiDB 走同样的 MySQL 协议
+    if !matches!(connection.db_type.as_str(), "mysql" | "mariadb" | "tidb") {
         return Err("Only MySQL is supported for now".to_string());
     }
 
-    let host = connection.host.ok_or("Host is required")?;
-    let port = connection.port.unwrap_or(3306);
-    let username = connection.username.unwrap_or_else(|| "root".to_string());
-    let password = connection.password.unwrap_or_default();
-    let database_to_use = db_name.or(connection.database).unwrap_or_default();
+    let options = build_connect_options(app_state, &connection, db_name).await?;
 
-    let url = format!(
-        "mysql://{}:{}@{}:{}/{}",
-        encode(&username), encode(&password), host, port, database_to_use
-    );
+    let mut pool_options: MySqlPoolOptions =
+        connection.options.pool_options(MYSQL_CONNECT_TIMEOUT_SECS);
 
-    let pool = MySqlPoolOptions::new()
-        .max_connections(5)
-        .connect(&url)
+    // 每个新连接建立后需要执行的会话设置
+    let mut session_statements = Vec::new();
+    // 只读连接在会话层面也设为只读事务（TiDB 默认不支持该语句，只依赖语句检查）
+    if connection.read_only && connection.db_type != "tidb" {
+        session_statements.push("SET SESSION TRANSACTION READ ONLY".to_string());
+    }
+    // 语句超时：MariaDB 单位为秒，MySQL / TiDB 单位为毫秒（MySQL 只对 SELECT 生效）
+    if let Some(secs) = connection.options.statement_timeout_secs.filter(|s| *s > 0) {
+        session_statements.push(if connection.db_type == "mariadb" {
+            format!("SET SESSION max_statement_time = {}", secs)
+        } else {
+            format!("SET SESSION max_execution_time = {}", secs * 1000)
+        });
+    }
+    if !session_statements.is_empty() {
+        pool_options = pool_options.after_connect(move |conn, _meta| {
+            let statements = session_statements.clone();
+            Box::pin(async move {
+                for statement in statements {
+                    conn.execute(statement.as_str()).await?;
+                }
+                Ok(())
+            })
+        });
+    }
+    let pool = pool_options
+        .connect_with(options)
+        .await
+        .map_err(|e| redact_error(format!("Failed to connect to MySQL: {}", e), &connection))?;
+
+    // 首次连接时探测服务端类型，探测失败不影响正常使用
+    let has_profile = app_state
+        .server_profiles
+        .lock()
         .await
-        .map_err(|e| format!("Failed to connect to MySQL: {}", e))?;
+        .contains_key(&connection_id);
+    if !has_profile {
+        if let Ok(profile) = detect_server_profile(&pool).await {
+            let mut profiles = app_state.server_profiles.lock().await;
+            profiles.insert(connection_id, profile);
+        }
+    }
+
+    app_state
+        .timestamp_displays
+        .lock()
+        .await
+        .insert(connection_id, connection.options.timestamp_display());
 
     let mut pools = app_state.pools.lock().await;
     pools.insert(cache_key, pool.clone());
@@ -67,6 +179,168 @@ async fn get_or_create_pool(
     Ok(pool)
 }
 
+// 连接池缓存 key：未指定库时为 "{id}"，否则为 "{id}:{db}"
+fn pool_cache_key(connection_id: i64, db_name: Option<&str>) -> String {
+    match db_name {
+        Some(db) => format!("{}:{}", connection_id, db),
+        None => connection_id.to_string(),
+    }
+}
+
+// 库被删除后关闭该库的连接池；当前库是它时回到连接的默认库
+pub async fn release_database_pool(app_state: &AppState, connection_id: i64, db_name: &str) {
+    let pool = app_state
+        .pools
+        .lock()
+        .await
+        .remove(&pool_cache_key(connection_id, Some(db_name)));
+    if let Some(pool) = pool {
+        pool.close().await;
+    }
+    let mut active_databases = app_state.active_databases.lock().await;
+    if active_databases.get(&connection_id).map(String::as_str) == Some(db_name) {
+        active_databases.remove(&connection_id);
+    }
+}
+
+// 连接池里的连接全部失效时的错误（服务端重启、网络中断等），重建连接池可以恢复
+fn is_connection_lost(error: &sqlx::Error) -> bool {
+    matches!(
+        error,
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed
+    )
+}
+
+// 辅助函数：从连接池取一个连接；缓存的连接池已失效时丢弃并按指数退避重建
+pub async fn acquire_connection(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Result<PoolConnection<MySql>, String> {
+    let cache_key = pool_cache_key(connection_id, db_name.as_deref());
+    let was_cached = app_state.pools.lock().await.contains_key(&cache_key);
+
+    let pool = get_or_create_pool(app_state, db_state, connection_id, db_name.clone()).await?;
+    let error = match pool.acquire().await {
+        Ok(conn) => return Ok(conn),
+        Err(e) => e,
+    };
+    if !was_cached || !is_connection_lost(&error) {
+        return Err(format!("Failed to acquire MySQL connection: {}", error));
+    }
+
+    reconnect_with_backoff(app_state, connection_id, || {
+        let cache_key = &cache_key;
+        let db_name = db_name.clone();
+        async move {
+            app_state.pools.lock().await.remove(cache_key);
+            let pool = get_or_create_pool(app_state, db_state, connection_id, db_name).await?;
+            pool.acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire MySQL connection: {}", e))
+        }
+    })
+    .await
+}
+
+// 辅助函数：根据连接配置构建连接参数（unix socket / SSH 隧道、默认库、TLS）
+pub async fn build_connect_options(
+    app_state: &AppState,
+    connection: &Connection,
+    db_name: Option<String>,
+) -> Result<MySqlConnectOptions, String> {
+    let username = connection.username.as_deref().unwrap_or("root");
+    let password = connection.password.as_deref().unwrap_or_default();
+    let database_to_use = db_name.or(connection.database.clone()).unwrap_or_default();
+
+    let mut options = MySqlConnectOptions::new()
+        .username(username)
+        .password(password);
+    // 设置了 socket 路径时直接连本机 socket，不使用 host / port 和 SSH 隧道
+    if let Some(socket_path) = connection.options.socket_path() {
+        options = options.socket(socket_path);
+    } else {
+        let host = connection.host.as_deref().ok_or("Host is required")?;
+        let port = connection.port.unwrap_or(3306) as u16;
+        // 启用 SSH 隧道时改为连接本地转发端口
+        let (host, port) = resolve_endpoint(app_state, connection, host, port).await?;
+        options = options.host(&host).port(port);
+    }
+    if !database_to_use.is_empty() {
+        options = options.database(&database_to_use);
+    }
+
+    let extra = &connection.options;
+    if let Some(charset) = extra.charset.as_deref().filter(|c| !c.is_empty()) {
+        options = options.charset(charset);
+    }
+    if let Some(collation) = extra.collation.as_deref().filter(|c| !c.is_empty()) {
+        options = options.collation(collation);
+    }
+    // TIMESTAMP 按会话时区返回：server 模式使用配置的时区（未配置则沿用服务端默认），
+    // 其余模式固定为 UTC，读出后再换算
+    let session_time_zone = match extra.timestamp_display() {
+        TimestampDisplay::Server => extra.time_zone().map(str::to_string),
+        _ => Some("+00:00".to_string()),
+    };
+    options = options
+        .timezone(session_time_zone)
+        .statement_cache_capacity(extra.statement_cache_capacity());
+    apply_tls_options(options, connection)
+}
+
+// 辅助函数：获取连接的服务端能力信息（必要时先建立连接）
+pub async fn get_server_profile_for(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<ServerProfile, String> {
+    let pool = get_or_create_pool(app_state, db_state, connection_id, None).await?;
+
+    if let Some(profile) = app_state.server_profiles.lock().await.get(&connection_id) {
+        return Ok(profile.clone());
+    }
+
+    let profile = detect_server_profile(&pool).await?;
+    let mut profiles = app_state.server_profiles.lock().await;
+    profiles.insert(connection_id, profile.clone());
+    Ok(profile)
+}
+
+// 辅助函数：根据连接配置设置 TLS（CA 校验与客户端证书双向认证）
+fn apply_tls_options(
+    mut options: MySqlConnectOptions,
+    connection: &Connection,
+) -> Result<MySqlConnectOptions, String> {
+    let ca_path = connection.ssl_ca_path.as_deref().filter(|p| !p.is_empty());
+    let cert_path = connection.ssl_cert_path.as_deref().filter(|p| !p.is_empty());
+    let key_path = connection.ssl_key_path.as_deref().filter(|p| !p.is_empty());
+
+    if cert_path.is_some() != key_path.is_some() {
+        return Err("Client certificate and key must be provided together".to_string());
+    }
+
+    // 配置了客户端证书但未指定模式时，至少要求 TLS，避免证书被静默忽略
+    let ssl_mode = match connection.ssl_mode.as_deref().filter(|m| !m.is_empty()) {
+        Some(mode) => mode
+            .parse::<MySqlSslMode>()
+            .map_err(|e| format!("Invalid SSL mode: {}", e))?,
+        None if cert_path.is_some() => MySqlSslMode::Required,
+        None => MySqlSslMode::Preferred,
+    };
+    options = options.ssl_mode(ssl_mode);
+
+    if let Some(ca_path) = ca_path {
+        options = options.ssl_ca(ca_path);
+    }
+    if let (Some(cert_path), Some(key_path)) = (cert_path, key_path) {
+        options = options.ssl_client_cert(cert_path).ssl_client_key(key_path);
+    }
+
+    Ok(options)
+}
+
 // 辅助：Vec<u8> 转字符串，非 UTF-8 则转 hex
 fn bytes_to_value(v: Vec<u8>) -> Value {
     match String::from_utf8(v.clone()) {
@@ -78,6 +352,23 @@ fn bytes_to_value(v: Vec<u8>) -> Value {
     }
 }
 
+// 辅助：大二进制值只返回占位对象，避免整块内容传给前端
+fn blob_to_value(v: Vec<u8>) -> Value {
+    if v.len() <= BLOB_INLINE_MAX_BYTES {
+        return bytes_to_value(v);
+    }
+    let preview: String = v
+        .iter()
+        .take(BLOB_PREVIEW_BYTES)
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    json!({
+        "type": "blob",
+        "length": v.len(),
+        "preview": format!("0x{}", preview),
+    })
+}
+
 // 辅助：解析 MySQL 内部几何格式 (4字节SRID + WKB) → WKT 可读文本
 fn geometry_bytes_to_wkt(data: &[u8]) -> String {
     if data.len() < 9 {
@@ -100,85 +391,268 @@ fn geometry_bytes_to_wkt(data: &[u8]) -> String {
     })
 }
 
-fn read_f64_le(data: &[u8], offset: usize) -> Option<f64> {
-    if offset + 8 > data.len() {
-        return None;
-    }
-    Some(f64::from_le_bytes(
-        data[offset..offset + 8].try_into().ok()?,
-    ))
+// WKB 读取器，字节序由每个几何体开头的标志字节决定（0x01=LE, 0x00=BE）
+struct WkbReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    little_endian: bool,
 }
 
-fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
-    if offset + 4 > data.len() {
-        return None;
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.data.get(self.pos..self.pos + N)?.try_into().ok()?;
+        self.pos += N;
+        Some(bytes)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes = self.take::<4>()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self) -> Option<f64> {
+        let bytes = self.take::<8>()?;
+        Some(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_point(&mut self) -> Option<String> {
+        let x = self.read_f64()?;
+        let y = self.read_f64()?;
+        Some(format!("{} {}", x, y))
+    }
+
+    // 读取 n 个元素并拼成 "(a,b,...)"，空集合为 " EMPTY"
+    fn read_list(&mut self, mut item: impl FnMut(&mut Self) -> Option<String>) -> Option<String> {
+        let n = self.read_u32()? as usize;
+        if n == 0 {
+            return Some(" EMPTY".to_string());
+        }
+        let items = (0..n).map(|_| item(self)).collect::<Option<Vec<_>>>()?;
+        Some(format!("({})", items.join(",")))
+    }
+
+    fn read_points(&mut self) -> Option<String> {
+        self.read_list(Self::read_point)
+    }
+
+    // 读取一个完整几何体，返回 (类型名, WKT 主体)
+    fn read_geometry(&mut self) -> Option<(&'static str, String)> {
+        self.little_endian = match self.take::<1>()?[0] {
+            0x00 => false,
+            0x01 => true,
+            _ => return None,
+        };
+        let geometry = match self.read_u32()? {
+            1 => ("POINT", format!("({})", self.read_point()?)),
+            2 => ("LINESTRING", self.read_points()?),
+            3 => ("POLYGON", self.read_list(Self::read_points)?),
+            4 => ("MULTIPOINT", self.read_list(Self::read_member)?),
+            5 => ("MULTILINESTRING", self.read_list(Self::read_member)?),
+            6 => ("MULTIPOLYGON", self.read_list(Self::read_member)?),
+            7 => ("GEOMETRYCOLLECTION", self.read_list(Self::read_wkt)?),
+            _ => return None,
+        };
+        Some(geometry)
+    }
+
+    fn read_wkt(&mut self) -> Option<String> {
+        let (tag, body) = self.read_geometry()?;
+        Some(format!("{}{}", tag, body))
+    }
+
+    // MULTI* 的成员是带字节序头的完整几何体，WKT 中只保留主体
+    fn read_member(&mut self) -> Option<String> {
+        self.read_geometry().map(|(_, body)| body)
     }
-    Some(u32::from_le_bytes(
-        data[offset..offset + 4].try_into().ok()?,
-    ))
 }
 
 fn wkb_to_wkt(wkb: &[u8]) -> Option<String> {
-    if wkb.is_empty() {
-        return None;
-    }
-    // byte 0: byte order (0x01=LE, 0x00=BE), 只处理 LE
-    if wkb[0] != 0x01 {
-        return None;
-    }
-    let geom_type = read_u32_le(wkb, 1)?;
-    match geom_type {
-        1 => {
-            // Point
-            let x = read_f64_le(wkb, 5)?;
-            let y = read_f64_le(wkb, 13)?;
-            Some(format!("POINT({} {})", x, y))
-        }
-        2 => {
-            // LineString
-            let n = read_u32_le(wkb, 5)? as usize;
-            let mut pts = Vec::with_capacity(n);
-            for i in 0..n {
-                let off = 9 + i * 16;
-                let x = read_f64_le(wkb, off)?;
-                let y = read_f64_le(wkb, off + 8)?;
-                pts.push(format!("{} {}", x, y));
-            }
-            Some(format!("LINESTRING({})", pts.join(",")))
-        }
-        3 => {
-            // Polygon
-            let num_rings = read_u32_le(wkb, 5)? as usize;
-            let mut rings = Vec::new();
-            let mut off = 9;
-            for _ in 0..num_rings {
-                let n = read_u32_le(wkb, off)? as usize;
-                off += 4;
-                let mut pts = Vec::with_capacity(n);
-                for _ in 0..n {
-                    let x = read_f64_le(wkb, off)?;
-                    let y = read_f64_le(wkb, off + 8)?;
-                    pts.push(format!("{} {}", x, y));
-                    off += 16;
-                }
-                rings.push(format!("({})", pts.join(",")));
-            }
-            Some(format!("POLYGON({})", rings.join(",")))
-        }
-        _ => None, // 其他复杂几何类型暂不解析
+    WkbReader {
+        data: wkb,
+        pos: 0,
+        little_endian: true,
     }
+    .read_wkt()
 }
 
 fn format_mysql_datetime(value: NaiveDateTime) -> String {
     value.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
-fn format_mysql_timestamp(value: NaiveDateTime) -> String {
-    value.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string()
+// JavaScript Number.MAX_SAFE_INTEGER
+const JS_MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+fn signed_to_value(v: i64) -> Value {
+    if v.unsigned_abs() <= JS_MAX_SAFE_INTEGER {
+        Value::Number(v.into())
+    } else {
+        Value::String(v.to_string())
+    }
+}
+
+fn unsigned_to_value(v: u64) -> Value {
+    if v <= JS_MAX_SAFE_INTEGER {
+        Value::Number(v.into())
+    } else {
+        Value::String(v.to_string())
+    }
+}
+
+// TIMESTAMP 的值为会话时区时间（非 server 模式即 UTC），按显示方式换算
+fn format_mysql_timestamp(value: NaiveDateTime, display: TimestampDisplay) -> String {
+    match display {
+        TimestampDisplay::Utc | TimestampDisplay::Server => format_mysql_datetime(value),
+        TimestampDisplay::Local => value
+            .and_utc()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+        TimestampDisplay::LocalWithOffset => value
+            .and_utc()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S %:z")
+            .to_string(),
+    }
+}
+
+pub async fn timestamp_display_of(app_state: &AppState, connection_id: i64) -> TimestampDisplay {
+    app_state
+        .timestamp_displays
+        .lock()
+        .await
+        .get(&connection_id)
+        .copied()
+        .unwrap_or_default()
+}
+
+pub fn column_info(col: &MySqlColumn) -> ColumnInfo {
+    let type_name = col.type_info().name();
+    ColumnInfo {
+        name: col.name().to_string(),
+        type_name: type_name.to_string(),
+        render_hint: if type_name == "JSON" {
+            Some("json".to_string())
+        } else if BINARY_TYPES.contains(&type_name) {
+            Some("binary".to_string())
+        } else {
+            None
+        },
+        ..Default::default()
+    }
+}
+
+// 读取表中各列的定义（可空、主键、默认值等），name 为列在表中的名字
+async fn table_column_definitions(
+    conn: &mut MySqlConnection,
+    schema: Option<&str>,
+    table: &str,
+) -> Result<Vec<ColumnInfo>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT CAST(TABLE_SCHEMA AS CHAR), CAST(COLUMN_NAME AS CHAR), CAST(IS_NULLABLE AS CHAR), \
+         CAST(COLUMN_KEY AS CHAR), CAST(COLUMN_DEFAULT AS CHAR), CAST(EXTRA AS CHAR), \
+         CHARACTER_MAXIMUM_LENGTH, NUMERIC_PRECISION, NUMERIC_SCALE \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?",
+    )
+    .bind(schema)
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let text = |i: usize| row.try_get_unchecked::<Option<String>, _>(i).ok().flatten();
+            let number = |i: usize| row.try_get_unchecked::<Option<u64>, _>(i).ok().flatten();
+            ColumnInfo {
+                name: text(1).unwrap_or_default(),
+                schema: text(0),
+                table: Some(table.to_string()),
+                nullable: text(2).map(|v| v == "YES"),
+                primary_key: text(3).as_deref() == Some("PRI"),
+                default_value: text(4),
+                auto_increment: text(5).is_some_and(|v| v.contains("auto_increment")),
+                max_length: number(6),
+                precision: number(7),
+                scale: number(8),
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+// 单表查询时补充列在表中的定义（可空、主键、默认值等），前端据此标记可编辑的单元格和主键。
+// 表定义缓存在自动补全的元数据中（DDL 后失效），只有首次查询某张表时读取 INFORMATION_SCHEMA；
+// 读取失败时保留驱动提供的信息
+async fn enrich_column_info(
+    app_state: &AppState,
+    connection_id: i64,
+    conn: &mut MySqlConnection,
+    db_name: Option<&str>,
+    sql: &str,
+    columns: &mut [ColumnInfo],
+) {
+    let Some(source) = single_table_source(sql, SqlFlavor::MySql) else {
+        return;
+    };
+    let schema = source.schema.as_deref().or(db_name);
+    let definitions =
+        match cached_table_columns(app_state, connection_id, schema, &source.table).await {
+            Some(definitions) => definitions,
+            None => {
+                let Ok(definitions) = table_column_definitions(conn, schema, &source.table).await
+                else {
+                    return;
+                };
+                store_table_columns(
+                    app_state,
+                    connection_id,
+                    schema,
+                    &source.table,
+                    definitions.clone(),
+                )
+                .await;
+                definitions
+            }
+        };
+
+    for column in columns.iter_mut() {
+        let source_name = match source
+            .columns
+            .iter()
+            .find(|(output, _)| output.eq_ignore_ascii_case(&column.name))
+        {
+            Some((_, source_name)) => source_name.as_str(),
+            None if source.wildcard => column.name.as_str(),
+            None => continue,
+        };
+        let Some(definition) = definitions
+            .iter()
+            .find(|definition| definition.name.eq_ignore_ascii_case(source_name))
+        else {
+            continue;
+        };
+        column.schema = definition.schema.clone();
+        column.table = definition.table.clone();
+        column.nullable = definition.nullable;
+        column.primary_key = definition.primary_key;
+        column.default_value = definition.default_value.clone();
+        column.auto_increment = definition.auto_increment;
+        column.max_length = definition.max_length;
+        column.precision = definition.precision;
+        column.scale = definition.scale;
+    }
 }
 
 // 将 MySQL 的 Row 转换为 JSON Object，按类型分组精确解码
-fn row_to_json(row: &MySqlRow) -> Map<String, Value> {
+pub fn row_to_json(row: &MySqlRow, timestamp_display: TimestampDisplay) -> Map<String, Value> {
     let mut json_row = Map::new();
 
     for (i, column) in row.columns().iter().enumerate() {
@@ -186,39 +660,16 @@ fn row_to_json(row: &MySqlRow) -> Map<String, Value> {
         let type_name = column.type_info().name();
 
         let value: Value = match type_name {
-            // 有符号整数
-            "BOOLEAN" | "TINYINT" => row
-                .try_get::<i8, _>(i)
-                .map(|v| Value::Number(v.into()))
-                .unwrap_or(Value::Null),
-            "SMALLINT" => row
-                .try_get::<i16, _>(i)
-                .map(|v| Value::Number(v.into()))
-                .unwrap_or(Value::Null),
-            "MEDIUMINT" | "INT" | "INTEGER" => row
-                .try_get::<i32, _>(i)
-                .map(|v| Value::Number(v.into()))
-                .unwrap_or(Value::Null),
-            "BIGINT" => row
-                .try_get::<i64, _>(i)
-                .map(|v| Value::String(v.to_string()))
-                .unwrap_or(Value::Null),
-            // 无符号整数
-            "TINYINT UNSIGNED" => row
-                .try_get::<u8, _>(i)
-                .map(|v| Value::Number(v.into()))
-                .unwrap_or(Value::Null),
-            "SMALLINT UNSIGNED" => row
-                .try_get::<u16, _>(i)
-                .map(|v| Value::Number(v.into()))
+            // 整数：按宽度不定长解码（TINYINT(1) 的 BOOLEAN 也走这里），
+            // 超出 JS 安全整数范围的以字符串返回
+            "BOOLEAN" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "INTEGER" | "BIGINT" => row
+                .try_get_unchecked::<i64, _>(i)
+                .map(signed_to_value)
                 .unwrap_or(Value::Null),
-            "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "INTEGER UNSIGNED" => row
-                .try_get::<u32, _>(i)
-                .map(|v| Value::Number(v.into()))
-                .unwrap_or(Value::Null),
-            "BIGINT UNSIGNED" => row
-                .try_get::<u64, _>(i)
-                .map(|v| Value::String(v.to_string()))
+            "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED"
+            | "INTEGER UNSIGNED" | "BIGINT UNSIGNED" => row
+                .try_get_unchecked::<u64, _>(i)
+                .map(unsigned_to_value)
                 .unwrap_or(Value::Null),
             // 浮点
             "FLOAT" => row
@@ -235,16 +686,21 @@ fn row_to_json(row: &MySqlRow) -> Map<String, Value> {
                 .try_get::<f64, _>(i)
                 .map(Value::from)
                 .unwrap_or(Value::Null),
-            // 高精度 → 字符串保精度
+            // 高精度 → 字符串保精度。协议中 DECIMAL 本身以十进制文本传输，直接取原文，
+            // 保留标度（如 1.50）且不受 rust_decimal 28 位有效数字的限制
             "DECIMAL" | "NEWDECIMAL" => row
-                .try_get::<Decimal, _>(i)
-                .map(|v| Value::String(v.to_string()))
-                .or_else(|_| row.try_get::<String, _>(i).map(Value::String))
+                .try_get_unchecked::<String, _>(i)
+                .map(Value::String)
+                .or_else(|_| {
+                    row.try_get::<Decimal, _>(i)
+                        .map(|v| Value::String(v.to_string()))
+                })
                 .unwrap_or(Value::Null),
             // 字符串族
+            // SET 以逗号分隔的成员列表返回（服务端常以 CHAR 类型加 SET 标志下发）
             "VARCHAR" | "CHAR" | "TEXT" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM"
             | "SET" => row
-                .try_get::<String, _>(i)
+                .try_get_unchecked::<String, _>(i)
                 .map(Value::String)
                 .unwrap_or(Value::Null),
             // 日期时间
@@ -254,11 +710,8 @@ fn row_to_json(row: &MySqlRow) -> Map<String, Value> {
                 .unwrap_or(Value::Null),
             "TIMESTAMP" => row
                 .try_get::<NaiveDateTime, _>(i)
-                .map(|v| Value::String(format_mysql_timestamp(v)))
-                .or_else(|_| {
-                    row.try_get::<DateTime<Utc>, _>(i)
-                        .map(|v| Value::String(v.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string()))
-                })
+                .or_else(|_| row.try_get::<DateTime<Utc>, _>(i).map(|v| v.naive_utc()))
+                .map(|v| Value::String(format_mysql_timestamp(v, timestamp_display)))
                 .unwrap_or(Value::Null),
             "DATE" => row
                 .try_get::<NaiveDate, _>(i)
@@ -274,7 +727,14 @@ fn row_to_json(row: &MySqlRow) -> Map<String, Value> {
                 .or_else(|_| row.try_get::<u16, _>(i).map(|v| Value::Number(v.into())))
                 .unwrap_or(Value::Null),
             // JSON → 直接传原生 JSON
-            "JSON" => row.try_get::<Value, _>(i).unwrap_or(Value::Null),
+            // 文本协议或驱动无法直接解码时按原文解析，解析失败保留字符串
+            "JSON" => row
+                .try_get::<Value, _>(i)
+                .or_else(|_| {
+                    row.try_get_unchecked::<String, _>(i)
+                        .map(|text| serde_json::from_str(&text).unwrap_or(Value::String(text)))
+                })
+                .unwrap_or(Value::Null),
             // BINARY 定长：去掉尾部 \0 补齐再转换
             "BINARY" => row
                 .try_get::<Vec<u8>, _>(i)
@@ -287,29 +747,23 @@ fn row_to_json(row: &MySqlRow) -> Map<String, Value> {
                         .into_iter()
                         .rev()
                         .collect();
-                    bytes_to_value(trimmed)
+                    blob_to_value(trimmed)
                 })
                 .unwrap_or(Value::Null),
             // 变长二进制族
             "VARBINARY" | "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" => row
                 .try_get::<Vec<u8>, _>(i)
-                .map(bytes_to_value)
+                .map(blob_to_value)
                 .unwrap_or(Value::Null),
-            // BIT → 二进制字符串
+            // BIT → 去掉前导零的位串，BIT(1) 即 "0" / "1"
             "BIT" => row
-                .try_get::<Vec<u8>, _>(i)
-                .map(|v| {
-                    Value::String(
-                        v.iter()
-                            .map(|b| format!("{:08b}", b))
-                            .collect::<Vec<_>>()
-                            .join(""),
-                    )
-                })
-                .or_else(|_| {
-                    row.try_get::<u64, _>(i)
-                        .map(|v| Value::String(format!("{:b}", v)))
-                })
+                .try_get_unchecked::<u64, _>(i)
+                .map(|v| Value::String(format!("{:b}", v)))
+                .unwrap_or(Value::Null),
+            // 空间类型：MySQL 内部格式（SRID + WKB）→ WKT
+            "GEOMETRY" => row
+                .try_get_unchecked::<Vec<u8>, _>(i)
+                .map(|v| Value::String(geometry_bytes_to_wkt(&v)))
                 .unwrap_or(Value::Null),
             // 未知类型：逐个尝试
             _ => {
@@ -340,6 +794,62 @@ fn row_to_json(row: &MySqlRow) -> Map<String, Value> {
     json_row
 }
 
+// 未指定库时使用 use_database 切换的当前库
+async fn resolve_db_name(
+    app_state: &AppState,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Option<String> {
+    match db_name.filter(|d| !d.is_empty()) {
+        Some(db) => Some(db),
+        None => app_state
+            .active_databases
+            .lock()
+            .await
+            .get(&connection_id)
+            .cloned(),
+    }
+}
+
+// 按 JSON 值的类型绑定参数
+pub fn bind_json_value<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    value: &Value,
+) -> Query<'q, MySql, MySqlArguments> {
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Bool(b) => query.bind(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(v), _) => query.bind(v),
+            (None, Some(v)) => query.bind(v),
+            _ => query.bind(n.as_f64()),
+        },
+        Value::String(text) => query.bind(text.clone()),
+        other => query.bind(other.to_string()),
+    }
+}
+
+// 读取上一条语句产生的警告，格式为 "Warning 1265: Data truncated ..."；读取失败时忽略。
+// sqlx 不公开 OK 包中的警告数，无法只在有警告时读取，因此 execute_sql 只在 with_warnings 为 true 时调用，
+// 避免每次执行多一次往返
+pub async fn fetch_warnings(conn: &mut MySqlConnection) -> Vec<String> {
+    let Ok(rows) = sqlx::query("SHOW WARNINGS").fetch_all(&mut *conn).await else {
+        return Vec::new();
+    };
+    rows.iter()
+        .map(|row| {
+            let level = row.try_get::<String, _>(0).unwrap_or_default();
+            let code = row
+                .try_get::<u32, _>(1)
+                .map(i64::from)
+                .or_else(|_| row.try_get::<i64, _>(1))
+                .unwrap_or_default();
+            let message = row.try_get::<String, _>(2).unwrap_or_default();
+            format!("{} {}: {}", level, code, message)
+        })
+        .collect()
+}
+
 #[command]
 pub async fn execute_sql(
     app_state: State<'_, AppState>,
@@ -347,71 +857,848 @@ pub async fn execute_sql(
     connection_id: i64,
     sql: String,
     db_name: Option<String>,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+    execution_id: Option<String>,
+    snapshot: Option<bool>,
+    session_id: Option<String>,
+    with_warnings: Option<bool>,
 ) -> Result<SqlResult, String> {
-    // Use the db_name to get/create a pool connected to that specific DB
-    let pool = get_or_create_pool(&app_state, &db_state, connection_id, db_name.clone()).await?;
+    let results = run_sql(
+        &app_state,
+        &db_state,
+        connection_id,
+        &sql,
+        db_name,
+        confirm_token,
+        confirmed,
+        execution_id,
+        snapshot,
+        session_id,
+        with_warnings.unwrap_or(false),
+    )
+    .await?;
+    results
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Query returned no result".to_string())
+}
+
+// 与 execute_sql 相同，但返回全部结果集（如存储过程中的多个 SELECT）
+#[command]
+pub async fn execute_sql_multi(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+    db_name: Option<String>,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+    execution_id: Option<String>,
+    snapshot: Option<bool>,
+    session_id: Option<String>,
+    with_warnings: Option<bool>,
+) -> Result<Vec<SqlResult>, String> {
+    run_sql(
+        &app_state,
+        &db_state,
+        connection_id,
+        &sql,
+        db_name,
+        confirm_token,
+        confirmed,
+        execution_id,
+        snapshot,
+        session_id,
+        with_warnings.unwrap_or(false),
+    )
+    .await
+}
 
-    // Explicitly acquire connection?
-    // Actually, if the POOL is already connected to the right DB, we don't need to manually acquire and USE.
-    // However, execute_sql normally used `pool` directly.
-    // Let's use `pool` directly unless we really want a transaction or something.
-    // But wait, user queries might affect session state? usually fine.
+// 执行中逐行收集的一个结果集，rows 在落盘时只有首页，row_count 为实际行数
+struct StreamedResultSet {
+    columns: Vec<ColumnInfo>,
+    rows: Vec<Map<String, Value>>,
+    spilled: Option<SpilledResult>,
+    row_count: usize,
+    affected_rows: u64,
+}
 
-    // No need to USE db;
+// 执行 SQL，每个结果集对应一个 SqlResult；不返回结果集的语句只有一个 SqlResult（影响行数）。
+// with_warnings 为 true 时在第一个结果中附带 SHOW WARNINGS 的内容
+async fn run_sql(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    sql: &str,
+    db_name: Option<String>,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+    execution_id: Option<String>,
+    snapshot: Option<bool>,
+    session_id: Option<String>,
+    with_warnings: bool,
+) -> Result<Vec<SqlResult>, String> {
+    ensure_sql_allowed(
+        db_state,
+        connection_id,
+        sql,
+        confirm_token.as_deref(),
+        confirmed.unwrap_or(false),
+    )
+    .await?;
+    let db_name = resolve_db_name(app_state, connection_id, db_name).await;
 
-    // 判断是查询还是执行
-    let sql_upper = sql.trim().to_uppercase();
-    if sql_upper.starts_with("SELECT")
-        || sql_upper.starts_with("SHOW")
-        || sql_upper.starts_with("DESCRIBE")
-        || sql_upper.starts_with("EXPLAIN")
-    {
-        let rows = sqlx::query(&sql)
-            .fetch_all(&pool)
+    // 开启结果缓存时，TTL 内相同的只读查询直接返回缓存的结果。
+    // 会话中的查询可能依赖会话状态（未提交的事务、会话变量），不使用缓存
+    let cache_ttl = match session_id.is_none() && is_read_query(sql, SqlFlavor::MySql) {
+        true => result_cache_ttl(db_state, connection_id).await?,
+        false => None,
+    };
+    if cache_ttl.is_some() {
+        if let Some(results) =
+            cached_results(app_state, connection_id, db_name.as_deref(), sql).await
+        {
+            return Ok(results);
+        }
+    }
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(app_state, connection_id, &execution_id).await?;
+
+    // Use the db_name to get/create a pool connected to that specific DB.
+    // The connection is acquired explicitly so a dead pool is rebuilt before the query runs.
+    // 指定 session_id 时在会话独占的连接上执行，db_name 以会话中 USE 的库为准
+    let mut conn = match &session_id {
+        Some(session_id) => mysql_session(app_state, session_id, connection_id).await?,
+        None => QueryConnection::Pooled(
+            acquire_connection(app_state, db_state, connection_id, db_name.clone()).await?,
+        ),
+    };
+    let db_name = match session_id {
+        Some(_) => sqlx::query_scalar::<_, Option<String>>("SELECT DATABASE()")
+            .fetch_one(&mut *conn)
             .await
-            .map_err(|e| format!("Query execution failed: {}", e))?;
+            .unwrap_or(db_name),
+        None => db_name,
+    };
+    let timestamp_display = timestamp_display_of(app_state, connection_id).await;
+
+    // No need to USE db;
 
+    register_mysql_query(
+        app_state,
+        &mut conn,
+        &execution_id,
+        connection_id,
+        db_name.clone(),
+    )
+    .await;
+
+    // 判断是查询还是执行
+    let started = Instant::now();
+    if returns_rows(sql, SqlFlavor::MySql) {
+        // 安全模式下没有 LIMIT 的 SELECT 按改写后的语句执行，历史中仍记录原语句
+        let limited = safe_limit(db_state, connection_id)
+            .await?
+            .and_then(|n| Some((with_safe_limit(sql, SqlFlavor::MySql, n)?, n)));
+        let (query_sql, mut applied_limit) = match &limited {
+            Some((limited_sql, n)) => (limited_sql.as_str(), Some(*n)),
+            None => (sql, None),
+        };
+        // 存储过程等会返回多个结果集，fetch_many 会依次产出每个结果集的行和结束标记。
+        // 行逐条交给 RowSpooler，超过阈值的结果集直接写入临时文件，不在内存中保留完整结果
+        let mut result_sets: Vec<StreamedResultSet> = Vec::new();
         let mut columns = Vec::new();
-        let mut result_rows = Vec::new();
-
-        if let Some(first_row) = rows.first() {
-            for col in first_row.columns() {
-                columns.push(ColumnInfo {
-                    name: col.name().to_string(),
-                    type_name: col.type_info().name().to_string(),
-                });
-            }
-        } else {
-            // Try to prepare the statement to fetch column metadata if there are no rows
-            if let Ok(stmt) = sqlx::Executor::prepare(&pool, sql.as_str()).await {
-                for col in stmt.columns() {
-                    columns.push(ColumnInfo {
-                        name: col.name().to_string(),
-                        type_name: col.type_info().name().to_string(),
-                    });
+        let mut spooler = RowSpooler::new(app_state, connection_id);
+        let mut set_rows = 0;
+        let streamed: Result<usize, String> = async {
+            let mut stream = (&mut *conn).fetch_many(sqlx::query(query_sql));
+            let mut total_rows = 0;
+            while let Some(step) = stream
+                .try_next()
+                .await
+                .map_err(|e| format!("Query execution failed: {}", e))?
+            {
+                match step {
+                    Either::Left(done) => {
+                        let finished = std::mem::replace(
+                            &mut spooler,
+                            RowSpooler::new(app_state, connection_id),
+                        );
+                        let (rows, spilled) = finished.finish().await?;
+                        result_sets.push(StreamedResultSet {
+                            columns: std::mem::take(&mut columns),
+                            rows,
+                            spilled,
+                            row_count: std::mem::take(&mut set_rows),
+                            affected_rows: done.rows_affected(),
+                        });
+                    }
+                    Either::Right(row) => {
+                        if set_rows == 0 {
+                            columns = row.columns().iter().map(column_info).collect();
+                        }
+                        set_rows += 1;
+                        total_rows += 1;
+                        spooler.push(row_to_json(&row, timestamp_display)).await?;
+                    }
                 }
             }
+            Ok(total_rows)
         }
+        .await;
+        finish_running_query(app_state, &execution_id).await;
+        let elapsed = started.elapsed();
+        record_query(app_state, db_state, connection_id, elapsed).await;
+        let outcome = HistoryOutcome::of(&streamed, |count| HistoryOutcome::Rows(*count));
+        record_history(db_state, connection_id, sql, elapsed, outcome).await;
+        streamed?;
+        let mut warnings = match with_warnings {
+            true => fetch_warnings(&mut conn).await,
+            false => Vec::new(),
+        };
 
-        for row in rows {
-            result_rows.push(row_to_json(&row));
+        // 每个结果集的行之后跟着一个 QueryResult，CALL 最后还有一个不带行的状态结果
+        if set_rows > 0 {
+            let (rows, spilled) = spooler.finish().await?;
+            result_sets.push(StreamedResultSet {
+                columns,
+                rows,
+                spilled,
+                row_count: set_rows,
+                affected_rows: 0,
+            });
+        }
+        if result_sets.len() > 1 && result_sets.last().is_some_and(|set| set.row_count == 0) {
+            result_sets.pop();
         }
 
-        Ok(SqlResult {
-            columns,
-            rows: result_rows,
-            affected_rows: 0,
-        })
+        let single = result_sets.len() <= 1;
+        let mut results = Vec::with_capacity(result_sets.len().max(1));
+        for set in result_sets {
+            let StreamedResultSet {
+                mut columns,
+                rows,
+                spilled,
+                row_count,
+                affected_rows,
+            } = set;
+            if row_count == 0 && single {
+                // Try to prepare the statement to fetch column metadata if there are no rows
+                if let Ok(stmt) = conn.prepare(query_sql).await {
+                    for col in stmt.columns() {
+                        columns.push(column_info(col));
+                    }
+                }
+            }
+            if single {
+                enrich_column_info(
+                    app_state,
+                    connection_id,
+                    &mut conn,
+                    db_name.as_deref(),
+                    query_sql,
+                    &mut columns,
+                )
+                .await;
+            }
+
+            results.push(SqlResult {
+                columns,
+                rows,
+                affected_rows,
+                duration_ms: elapsed.as_millis() as u64,
+                // 警告属于整条语句，只放在第一个结果集上
+                warnings: std::mem::take(&mut warnings),
+                last_insert_id: None,
+                applied_limit: applied_limit.take(),
+                from_cache: false,
+                spilled,
+            });
+        }
+        if let Some(ttl) = cache_ttl {
+            store_results(
+                app_state,
+                connection_id,
+                db_name.as_deref(),
+                sql,
+                &results,
+                ttl,
+            )
+            .await;
+        }
+        Ok(results)
     } else {
-        let result = sqlx::query(&sql)
-            .execute(&pool)
-            .await
-            .map_err(|e| format!("Statement execution failed: {}", e))?;
+        // 数据表格提交的 UPDATE / DELETE 先快照受影响的行，供 undo_last_change 恢复
+        let change_snapshot = match snapshot {
+            Some(true) => capture_mysql_snapshot(&mut conn, sql).await,
+            _ => None,
+        };
+        let result = sqlx::query(sql).execute(&mut *conn).await;
+        finish_running_query(app_state, &execution_id).await;
+        let elapsed = started.elapsed();
+        record_query(app_state, db_state, connection_id, elapsed).await;
+        let result = result.map_err(|e| format!("Statement execution failed: {}", e));
+        let outcome = HistoryOutcome::of(&result, |r| HistoryOutcome::Affected(r.rows_affected()));
+        record_history(db_state, connection_id, sql, elapsed, outcome).await;
+        let result = result?;
+        invalidate_results(app_state, connection_id).await;
+        if changes_schema(sql) {
+            invalidate_metadata(app_state, connection_id).await;
+        }
+        if let Some(change_snapshot) = change_snapshot {
+            save_snapshot(db_state, connection_id, db_name.as_deref(), change_snapshot).await;
+        }
+        let warnings = match with_warnings {
+            true => fetch_warnings(&mut conn).await,
+            false => Vec::new(),
+        };
+        // 没有自增列时 LAST_INSERT_ID 为 0
+        let last_insert_id = (is_insert(sql, SqlFlavor::MySql) && result.last_insert_id() > 0)
+            .then(|| result.last_insert_id());
 
-        Ok(SqlResult {
+        Ok(vec![SqlResult {
             columns: vec![],
             rows: vec![],
             affected_rows: result.rows_affected(),
-        })
+            duration_ms: elapsed.as_millis() as u64,
+            warnings,
+            last_insert_id,
+            applied_limit: None,
+            from_cache: false,
+            spilled: None,
+        }])
     }
 }
+
+// 不执行语句，只检查语法、引用的表和列是否存在。SELECT / INSERT / UPDATE / DELETE 通过
+// EXPLAIN 检查并取 rows 列的最大值作为估计扫描行数，其他语句只在服务端 prepare
+#[command]
+pub async fn validate_sql(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    sql: String,
+) -> Result<SqlValidation, String> {
+    let mut conn = acquire_connection(&app_state, &db_state, connection_id, db_name).await?;
+    let tables = referenced_tables(&sql, SqlFlavor::MySql);
+
+    let checked = if is_explainable(&sql, SqlFlavor::MySql) {
+        sqlx::query(&format!("EXPLAIN {}", sql))
+            .fetch_all(&mut *conn)
+            .await
+            .map(|plan| {
+                plan.iter()
+                    .filter_map(|row| row.try_get_unchecked::<Option<u64>, _>("rows").ok())
+                    .flatten()
+                    .max()
+            })
+    } else {
+        conn.prepare(sql.as_str()).await.map(|_| None)
+    };
+    Ok(match checked {
+        Ok(estimated_rows) => SqlValidation {
+            valid: true,
+            error: None,
+            tables,
+            estimated_rows,
+        },
+        Err(e) => SqlValidation {
+            valid: false,
+            error: Some(e.to_string()),
+            tables,
+            estimated_rows: None,
+        },
+    })
+}
+
+// EXPLAIN 估计的结果行数：最外层查询（id = 1）中各表 rows × filtered% 的乘积
+async fn estimate_rows(conn: &mut MySqlConnection, sql: &str) -> Option<u64> {
+    let plan = sqlx::query(&format!("EXPLAIN {}", sql))
+        .fetch_all(&mut *conn)
+        .await
+        .ok()?;
+    let mut estimate: Option<f64> = None;
+    for row in &plan {
+        let id = row.try_get_unchecked::<Option<u64>, _>("id");
+        let rows = row.try_get_unchecked::<Option<u64>, _>("rows");
+        let (Ok(Some(1)), Ok(Some(rows))) = (id, rows) else {
+            continue;
+        };
+        // MariaDB 的 EXPLAIN 没有 filtered 列
+        let filtered = row
+            .try_get_unchecked::<Option<f32>, _>("filtered")
+            .ok()
+            .flatten()
+            .unwrap_or(100.0);
+        estimate = Some(estimate.unwrap_or(1.0) * rows as f64 * f64::from(filtered) / 100.0);
+    }
+    estimate.map(|n| n.round() as u64)
+}
+
+// 分页表格的总行数。sql 为表格的查询语句，ORDER BY / LIMIT 会被去掉；默认只返回 EXPLAIN
+// 的估计值，exact 为 true 时再执行一次 COUNT(*)，可通过 execution_id 取消
+#[command]
+pub async fn count_query_rows(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    sql: String,
+    exact: Option<bool>,
+    execution_id: Option<String>,
+) -> Result<RowCount, String> {
+    let base_sql =
+        without_paging(&sql, SqlFlavor::MySql).ok_or("Only SELECT statements can be counted")?;
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let db_name = resolve_db_name(&app_state, connection_id, db_name).await;
+    let mut conn =
+        acquire_connection(&app_state, &db_state, connection_id, db_name.clone()).await?;
+
+    let estimated_rows = estimate_rows(&mut conn, &base_sql).await;
+    if !exact.unwrap_or(false) {
+        return Ok(RowCount {
+            estimated_rows,
+            exact_rows: None,
+        });
+    }
+
+    register_mysql_query(&app_state, &mut conn, &execution_id, connection_id, db_name).await;
+    let count_sql = format!("SELECT COUNT(*) FROM ({}) AS xdb_count", base_sql);
+    let count = sqlx::query_scalar::<_, i64>(&count_sql)
+        .fetch_one(&mut *conn)
+        .await;
+    finish_running_query(&app_state, &execution_id).await;
+    let count = count.map_err(|e| format!("Failed to count rows: {}", e))?;
+
+    Ok(RowCount {
+        estimated_rows,
+        exact_rows: u64::try_from(count).ok(),
+    })
+}
+
+// 按主键读取某一行的二进制列。pk 为 列名 → 值；指定 save_path 时写入该文件，
+// 否则小于上限的以 base64 返回，更大的写入临时文件后返回路径
+#[command]
+pub async fn fetch_blob(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    table: String,
+    pk: Map<String, Value>,
+    column: String,
+    save_path: Option<String>,
+) -> Result<BlobData, String> {
+    if pk.is_empty() {
+        return Err("Primary key values are required".to_string());
+    }
+    let db_name = resolve_db_name(&app_state, connection_id, db_name).await;
+    let table_ref = qualified_name(db_name.as_deref(), &table, SqlFlavor::MySql);
+    // <=> 为 NULL 安全的等值比较
+    let conditions = pk
+        .keys()
+        .map(|key| format!("{} <=> ?", quote_identifier(key, SqlFlavor::MySql)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {} LIMIT 2",
+        quote_identifier(&column, SqlFlavor::MySql),
+        table_ref,
+        conditions
+    );
+
+    let mut query = sqlx::query(&sql);
+    for value in pk.values() {
+        query = bind_json_value(query, value);
+    }
+    let mut conn = acquire_connection(&app_state, &db_state, connection_id, db_name).await?;
+    let rows = query
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch blob: {}", e))?;
+    let row = match rows.as_slice() {
+        [row] => row,
+        [] => return Err("Row not found".to_string()),
+        _ => return Err("Primary key matches more than one row".to_string()),
+    };
+    let bytes = row
+        .try_get_unchecked::<Option<Vec<u8>>, _>(0)
+        .map_err(|e| format!("Failed to read blob: {}", e))?
+        .unwrap_or_default();
+
+    let path = match save_path.filter(|p| !p.is_empty()) {
+        Some(path) => Some(PathBuf::from(path)),
+        None if bytes.len() > BLOB_BASE64_MAX_BYTES => {
+            Some(std::env::temp_dir().join(format!("xdb-blob-{}.bin", new_execution_id())))
+        }
+        None => None,
+    };
+    match path {
+        Some(path) => {
+            tokio::fs::write(&path, &bytes)
+                .await
+                .map_err(|e| format!("Failed to write blob to {}: {}", path.display(), e))?;
+            Ok(BlobData {
+                length: bytes.len(),
+                base64: None,
+                path: Some(path.to_string_lossy().to_string()),
+            })
+        }
+        None => Ok(BlobData {
+            length: bytes.len(),
+            base64: Some(BASE64.encode(&bytes)),
+            path: None,
+        }),
+    }
+}
+
+// 记录执行查询的连接线程 id，cancel_query 通过 KILL QUERY 中断
+pub async fn register_mysql_query(
+    app_state: &AppState,
+    conn: &mut MySqlConnection,
+    execution_id: &str,
+    connection_id: i64,
+    db_name: Option<String>,
+) {
+    if let Ok(thread_id) = sqlx::query_scalar::<_, u64>("SELECT CONNECTION_ID()")
+        .fetch_one(&mut *conn)
+        .await
+    {
+        register_running_query(
+            app_state,
+            execution_id,
+            RunningQuery::MySql {
+                connection_id,
+                db_name,
+                thread_id,
+            },
+        )
+        .await;
+    }
+}
+
+// 在一个事务中依次执行语句，任一语句没有影响到行时整体回滚（用于 undo_last_change）
+pub async fn execute_in_transaction(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    statements: &[String],
+) -> Result<(), String> {
+    let mut conn = acquire_connection(app_state, db_state, connection_id, db_name).await?;
+    let mut tx = conn
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+    for statement in statements {
+        let result = sqlx::query(statement)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to undo change: {}", e))?;
+        if result.rows_affected() == 0 {
+            return Err("Failed to undo change: the row has been modified or removed".to_string());
+        }
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+// 通过同一连接池的另一个连接中断指定线程上正在执行的语句，连接本身保留
+pub async fn kill_mysql_query(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    thread_id: u64,
+) -> Result<(), String> {
+    let pool = get_or_create_pool(app_state, db_state, connection_id, db_name).await?;
+    sqlx::query(&format!("KILL QUERY {}", thread_id))
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to cancel query: {}", e))?;
+    Ok(())
+}
+
+// 流式执行查询：后台任务逐行读取结果，先返回第一页，其余通过 fetch_more 分页获取，
+// 大结果集不会一次性载入内存。handle 即执行 id，可用于 cancel_query
+#[command]
+pub async fn execute_sql_streaming(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+    db_name: Option<String>,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+    execution_id: Option<String>,
+    batch_size: Option<usize>,
+    session_id: Option<String>,
+) -> Result<StreamedResult, String> {
+    ensure_sql_allowed(
+        &db_state,
+        connection_id,
+        &sql,
+        confirm_token.as_deref(),
+        confirmed.unwrap_or(false),
+    )
+    .await?;
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+
+    let db_name = resolve_db_name(&app_state, connection_id, db_name).await;
+    // 指定 session_id 时在会话独占的连接上执行，db_name 以会话中 USE 的库为准
+    let mut conn = match &session_id {
+        Some(session_id) => mysql_session(&app_state, session_id, connection_id).await?,
+        None => QueryConnection::Pooled(
+            acquire_connection(&app_state, &db_state, connection_id, db_name.clone()).await?,
+        ),
+    };
+    let db_name = match session_id {
+        Some(_) => sqlx::query_scalar::<_, Option<String>>("SELECT DATABASE()")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap_or(db_name),
+        None => db_name,
+    };
+
+    // 安全模式下没有 LIMIT 的 SELECT 按改写后的语句执行，历史中仍记录原语句
+    let (query_sql, applied_limit) = match returns_rows(&sql, SqlFlavor::MySql) {
+        true => match safe_limit(&db_state, connection_id)
+            .await?
+            .and_then(|n| Some((with_safe_limit(&sql, SqlFlavor::MySql, n)?, n)))
+        {
+            Some((limited_sql, n)) => (limited_sql, Some(n)),
+            None => (sql.clone(), None),
+        },
+        false => (sql.clone(), None),
+    };
+
+    // 列信息通过 prepare 获取，结果为空时也能展示表头
+    let mut columns: Vec<ColumnInfo> = conn
+        .prepare(query_sql.as_str())
+        .await
+        .map_err(|e| format!("Query execution failed: {}", e))?
+        .columns()
+        .iter()
+        .map(column_info)
+        .collect();
+    enrich_column_info(
+        &app_state,
+        connection_id,
+        &mut conn,
+        db_name.as_deref(),
+        &sql,
+        &mut columns,
+    )
+    .await;
+
+    register_mysql_query(&app_state, &mut conn, &execution_id, connection_id, db_name).await;
+
+    let timestamp_display = timestamp_display_of(&app_state, connection_id).await;
+    let (sender, receiver) = mpsc::channel(STREAM_BUFFER_ROWS);
+    app_state.result_streams.lock().await.insert(
+        execution_id.clone(),
+        ResultStream {
+            connection_id,
+            receiver: Arc::new(Mutex::new(receiver)),
+            timestamp_display,
+        },
+    );
+
+    let task_app_state = app_state.inner().clone();
+    let task_db_state = db_state.inner().clone();
+    let task_execution_id = execution_id.clone();
+    tauri::async_runtime::spawn(async move {
+        // 名额在读取结束后才释放
+        let _permit = permit;
+        let started = Instant::now();
+        let mut abandoned = false;
+        let mut row_count = 0;
+        let mut error = None;
+        {
+            let mut rows = sqlx::query(&query_sql).fetch(&mut *conn);
+            loop {
+                let next = tokio::select! {
+                    next = rows.next() => next,
+                    // 结果句柄已关闭，不再读取
+                    _ = sender.closed() => {
+                        abandoned = true;
+                        break;
+                    }
+                };
+                let Some(row) = next else {
+                    break;
+                };
+                let row = row.map_err(|e| format!("Query execution failed: {}", e));
+                match &row {
+                    Ok(_) => row_count += 1,
+                    Err(e) => error = Some(e.clone()),
+                }
+                let sent = tokio::time::timeout(
+                    Duration::from_secs(STREAM_IDLE_TIMEOUT_SECS),
+                    sender.send(row),
+                )
+                .await;
+                if !matches!(sent, Ok(Ok(()))) {
+                    abandoned = true;
+                    break;
+                }
+                if error.is_some() {
+                    break;
+                }
+            }
+        }
+        finish_running_query(&task_app_state, &task_execution_id).await;
+        let elapsed = started.elapsed();
+        record_query(&task_app_state, &task_db_state, connection_id, elapsed).await;
+        let outcome = match &error {
+            Some(e) => HistoryOutcome::Failed(e),
+            None => HistoryOutcome::Rows(row_count),
+        };
+        record_history(&task_db_state, connection_id, &sql, elapsed, outcome).await;
+        if !is_read_query(&sql, SqlFlavor::MySql) {
+            invalidate_results(&task_app_state, connection_id).await;
+            if changes_schema(&sql) {
+                invalidate_metadata(&task_app_state, connection_id).await;
+            }
+        }
+        // 未读完的结果集会阻塞该连接，直接关闭而不是归还连接池；
+        // 会话连接不能关闭，剩余的结果在下次使用时读完
+        if abandoned {
+            task_app_state
+                .result_streams
+                .lock()
+                .await
+                .remove(&task_execution_id);
+            if let QueryConnection::Pooled(conn) = conn {
+                let _ = conn.close().await;
+            }
+        }
+    });
+
+    let page = fetch_stream_page(
+        &app_state,
+        &execution_id,
+        batch_size.unwrap_or(STREAM_DEFAULT_BATCH_SIZE),
+    )
+    .await?;
+    Ok(StreamedResult {
+        handle: execution_id,
+        columns,
+        rows: page.rows,
+        done: page.done,
+        applied_limit,
+    })
+}
+
+// 从结果句柄中读取最多 n 行；读完或出错时自动关闭句柄
+async fn fetch_stream_page(
+    app_state: &AppState,
+    handle: &str,
+    n: usize,
+) -> Result<ResultPage, String> {
+    let (receiver, timestamp_display) = app_state
+        .result_streams
+        .lock()
+        .await
+        .get(handle)
+        .map(|stream| (stream.receiver.clone(), stream.timestamp_display))
+        .ok_or("Result handle not found or already closed")?;
+
+    let mut rows = Vec::new();
+    let mut done = false;
+    {
+        let mut receiver = receiver.lock().await;
+        while rows.len() < n.max(1) {
+            match receiver.recv().await {
+                Some(Ok(row)) => rows.push(row_to_json(&row, timestamp_display)),
+                Some(Err(e)) => {
+                    app_state.result_streams.lock().await.remove(handle);
+                    return Err(e);
+                }
+                None => {
+                    done = true;
+                    break;
+                }
+            }
+        }
+    }
+    if done {
+        app_state.result_streams.lock().await.remove(handle);
+    }
+    Ok(ResultPage { rows, done })
+}
+
+#[command]
+pub async fn fetch_more(
+    app_state: State<'_, AppState>,
+    handle: String,
+    n: Option<usize>,
+) -> Result<ResultPage, String> {
+    fetch_stream_page(&app_state, &handle, n.unwrap_or(STREAM_DEFAULT_BATCH_SIZE)).await
+}
+
+// 关闭某个连接的全部结果句柄，后台任务随即停止读取并关闭各自的连接
+pub async fn close_connection_streams(app_state: &AppState, connection_id: i64) {
+    app_state
+        .result_streams
+        .lock()
+        .await
+        .retain(|_, stream| stream.connection_id != connection_id);
+}
+
+// 提前关闭结果句柄：后台任务停止读取并关闭连接
+#[command]
+pub async fn close_result_stream(
+    app_state: State<'_, AppState>,
+    handle: String,
+) -> Result<(), String> {
+    app_state.result_streams.lock().await.remove(&handle);
+    Ok(())
+}
+
+#[command]
+pub async fn get_server_profile(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<ServerProfile, String> {
+    get_server_profile_for(&app_state, &db_state, connection_id).await
+}
+
+// 切换当前会话的默认库，之后未指定 db_name 的 execute_sql 都在该库上执行
+#[command]
+pub async fn use_database(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db: String,
+) -> Result<(), String> {
+    let db = db.trim().to_string();
+    if db.is_empty() {
+        app_state
+            .active_databases
+            .lock()
+            .await
+            .remove(&connection_id);
+        return Ok(());
+    }
+
+    // 建立该库的连接池，库不存在或无权限时直接报错
+    let pool = get_or_create_pool(&app_state, &db_state, connection_id, Some(db.clone())).await?;
+    pool.acquire()
+        .await
+        .map_err(|e| format!("Failed to use database {}: {}", db, e))?;
+
+    app_state
+        .active_databases
+        .lock()
+        .await
+        .insert(connection_id, db);
+    Ok(())
+}
@@ -0,0 +1,325 @@
+use crate::connection_stats::record_query;
+use crate::db::DbState;
+use crate::models::{ColumnInfo, Connection, SqlResult};
+use crate::ssh_tunnel::resolve_endpoint;
+use crate::state::AppState;
+use crate::vault::reveal_secrets;
+use neo4rs::{BoltType, ConfigBuilder, Graph, Query};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+use std::collections::HashMap;
+use std::time::Instant;
+use tauri::{command, State};
+use tokio::time::timeout;
+
+const NEO4J_CONNECT_TIMEOUT_SECS: u64 = 10;
+// Integers beyond this range lose precision in JavaScript and are sent as strings
+const JS_MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Neo4jSchema {
+    pub labels: Vec<String>,
+    pub relationship_types: Vec<String>,
+    pub property_keys: Vec<String>,
+}
+
+async fn get_or_create_graph(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Graph, String> {
+    // 1. Check cache
+    {
+        let graphs = app_state.neo4j_graphs.lock().await;
+        if let Some(graph) = graphs.get(&connection_id) {
+            return Ok(graph.clone());
+        }
+    }
+
+    // 2. Fetch connection info
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
+
+    if connection.db_type != "neo4j" {
+        return Err("Only Neo4j is supported for this operation".to_string());
+    }
+
+    // 3. Build config (host may already carry a bolt:// or neo4j:// scheme)
+    let host = connection.host.as_deref().ok_or("Host is required")?;
+    let uri = if host.contains("://") {
+        host.to_string()
+    } else {
+        let port = connection.port.unwrap_or(7687) as u16;
+        let (host, port) = resolve_endpoint(app_state, &connection, host, port).await?;
+        format!("{}:{}", host, port)
+    };
+
+    let connect_timeout = connection.options.connect_timeout(NEO4J_CONNECT_TIMEOUT_SECS);
+    let mut builder = ConfigBuilder::default()
+        .uri(uri)
+        .user(connection.username.unwrap_or_default())
+        .password(connection.password.unwrap_or_default());
+    if let Some(database) = connection.database.filter(|d| !d.is_empty()) {
+        builder = builder.db(database);
+    }
+    let config = builder
+        .build()
+        .map_err(|e| format!("Invalid Neo4j configuration: {}", e))?;
+
+    // 4. Connect
+    let graph = timeout(
+        connect_timeout,
+        Graph::connect(config),
+    )
+    .await
+    .map_err(|_| "Connection to Neo4j timed out".to_string())?
+    .map_err(|e| format!("Failed to connect to Neo4j: {}", e))?;
+
+    // 5. Cache graph
+    let mut graphs = app_state.neo4j_graphs.lock().await;
+    graphs.insert(connection_id, graph.clone());
+
+    Ok(graph)
+}
+
+fn integer_to_json(v: i64) -> JsonValue {
+    if (-JS_MAX_SAFE_INTEGER..=JS_MAX_SAFE_INTEGER).contains(&v) {
+        JsonValue::from(v)
+    } else {
+        JsonValue::String(v.to_string())
+    }
+}
+
+fn bolt_map_to_json(map: &neo4rs::BoltMap) -> JsonValue {
+    let mut object = Map::new();
+    for (key, value) in &map.value {
+        object.insert(key.value.clone(), bolt_to_json(value));
+    }
+    JsonValue::Object(object)
+}
+
+fn node_to_json(node: &neo4rs::BoltNode) -> JsonValue {
+    json!({
+        "_type": "node",
+        "id": integer_to_json(node.id.value),
+        "labels": node.labels.value.iter().map(bolt_to_json).collect::<Vec<_>>(),
+        "properties": bolt_map_to_json(&node.properties),
+    })
+}
+
+// Convert a Bolt value to JSON; graph entities keep their ids, labels/type and properties
+fn bolt_to_json(value: &BoltType) -> JsonValue {
+    match value {
+        BoltType::Null(_) => JsonValue::Null,
+        BoltType::Boolean(b) => JsonValue::Bool(b.value),
+        BoltType::Integer(i) => integer_to_json(i.value),
+        BoltType::Float(f) => JsonValue::from(f.value),
+        BoltType::String(s) => JsonValue::String(s.value.clone()),
+        BoltType::Bytes(b) => {
+            let hex: String = b.value.iter().map(|b| format!("{:02X}", b)).collect();
+            JsonValue::String(format!("0x{}", hex))
+        }
+        BoltType::List(list) => JsonValue::Array(list.value.iter().map(bolt_to_json).collect()),
+        BoltType::Map(map) => bolt_map_to_json(map),
+        BoltType::Node(node) => node_to_json(node),
+        BoltType::Relation(rel) => json!({
+            "_type": "relationship",
+            "id": integer_to_json(rel.id.value),
+            "type": rel.typ.value,
+            "start_node_id": integer_to_json(rel.start_node_id.value),
+            "end_node_id": integer_to_json(rel.end_node_id.value),
+            "properties": bolt_map_to_json(&rel.properties),
+        }),
+        BoltType::UnboundedRelation(rel) => json!({
+            "_type": "relationship",
+            "id": integer_to_json(rel.id.value),
+            "type": rel.typ.value,
+            "properties": bolt_map_to_json(&rel.properties),
+        }),
+        BoltType::Path(path) => json!({
+            "_type": "path",
+            "nodes": path.nodes.value.iter().map(bolt_to_json).collect::<Vec<_>>(),
+            "relationships": path.rels.value.iter().map(bolt_to_json).collect::<Vec<_>>(),
+            "indices": path.indices.value.iter().map(bolt_to_json).collect::<Vec<_>>(),
+        }),
+        BoltType::Point2D(p) => json!({
+            "srid": p.sr_id.value,
+            "x": p.x.value,
+            "y": p.y.value,
+        }),
+        BoltType::Point3D(p) => json!({
+            "srid": p.sr_id.value,
+            "x": p.x.value,
+            "y": p.y.value,
+            "z": p.z.value,
+        }),
+        BoltType::Date(d) => chrono::NaiveDate::try_from(d)
+            .map(|d| JsonValue::String(d.to_string()))
+            .unwrap_or(JsonValue::Null),
+        BoltType::Time(t) => {
+            let (time, offset): (chrono::NaiveTime, chrono::FixedOffset) = t.into();
+            JsonValue::String(format!("{}{}", time, offset))
+        }
+        BoltType::LocalTime(t) => JsonValue::String(chrono::NaiveTime::from(t).to_string()),
+        BoltType::DateTime(dt) => chrono::DateTime::<chrono::FixedOffset>::try_from(dt)
+            .map(|dt| JsonValue::String(dt.to_rfc3339()))
+            .unwrap_or(JsonValue::Null),
+        BoltType::LocalDateTime(dt) => chrono::NaiveDateTime::try_from(dt)
+            .map(|dt| JsonValue::String(dt.format("%Y-%m-%d %H:%M:%S%.f").to_string()))
+            .unwrap_or(JsonValue::Null),
+        BoltType::DateTimeZoneId(dt) => {
+            let tz = dt.tz_id().to_string();
+            chrono::NaiveDateTime::try_from(dt)
+                .map(|dt| {
+                    JsonValue::String(format!("{}[{}]", dt.format("%Y-%m-%dT%H:%M:%S%.f"), tz))
+                })
+                .unwrap_or(JsonValue::Null)
+        }
+        BoltType::Duration(d) => {
+            let duration: std::time::Duration = d.clone().into();
+            JsonValue::String(format!("PT{}S", duration.as_secs_f64()))
+        }
+    }
+}
+
+fn bolt_type_name(value: &BoltType) -> &'static str {
+    match value {
+        BoltType::Null(_) => "null",
+        BoltType::Boolean(_) => "boolean",
+        BoltType::Integer(_) => "integer",
+        BoltType::Float(_) => "float",
+        BoltType::String(_) => "string",
+        BoltType::Bytes(_) => "bytes",
+        BoltType::List(_) => "list",
+        BoltType::Map(_) => "map",
+        BoltType::Node(_) => "node",
+        BoltType::Relation(_) | BoltType::UnboundedRelation(_) => "relationship",
+        BoltType::Path(_) => "path",
+        BoltType::Point2D(_) | BoltType::Point3D(_) => "point",
+        BoltType::Date(_) => "date",
+        BoltType::Time(_) => "time",
+        BoltType::LocalTime(_) => "localtime",
+        BoltType::DateTime(_) | BoltType::DateTimeZoneId(_) => "datetime",
+        BoltType::LocalDateTime(_) => "localdatetime",
+        BoltType::Duration(_) => "duration",
+    }
+}
+
+async fn run_cypher(graph: &Graph, query: Query) -> Result<Vec<HashMap<String, BoltType>>, String> {
+    let mut stream = graph
+        .execute(query)
+        .await
+        .map_err(|e| format!("Cypher execution failed: {}", e))?;
+
+    let mut records = Vec::new();
+    while let Some(row) = stream
+        .next()
+        .await
+        .map_err(|e| format!("Failed to fetch Cypher results: {}", e))?
+    {
+        let record = row
+            .to_strict::<HashMap<String, BoltType>>()
+            .map_err(|e| format!("Failed to decode Cypher record: {}", e))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+async fn list_single_column(
+    graph: &Graph,
+    cypher: &str,
+    column: &str,
+) -> Result<Vec<String>, String> {
+    let records = run_cypher(graph, Query::new(cypher.to_string())).await?;
+    let mut values: Vec<String> = records
+        .iter()
+        .filter_map(|r| match r.get(column) {
+            Some(BoltType::String(s)) => Some(s.value.clone()),
+            _ => None,
+        })
+        .collect();
+    values.sort();
+    Ok(values)
+}
+
+#[command]
+pub async fn execute_cypher(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    cypher: String,
+    params: Option<Map<String, JsonValue>>,
+) -> Result<SqlResult, String> {
+    let graph = get_or_create_graph(&app_state, &db_state, connection_id).await?;
+
+    let mut query = Query::new(cypher);
+    for (name, value) in params.unwrap_or_default() {
+        let value = BoltType::try_from(value)
+            .map_err(|e| format!("Invalid value for parameter {}: {}", name, e))?;
+        query = query.param(&name, value);
+    }
+
+    let started = Instant::now();
+    let records = run_cypher(&graph, query).await;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let records = records?;
+
+    // Bolt records are exposed as maps, so columns are listed in name order
+    let mut columns: Vec<ColumnInfo> = Vec::new();
+    let mut rows = Vec::with_capacity(records.len());
+    for record in &records {
+        let mut row = Map::new();
+        for (name, value) in record {
+            if !columns.iter().any(|c| &c.name == name) {
+                columns.push(ColumnInfo {
+                    name: name.clone(),
+                    type_name: bolt_type_name(value).to_string(),
+                    ..Default::default()
+                });
+            }
+            row.insert(name.clone(), bolt_to_json(value));
+        }
+        rows.push(row);
+    }
+    columns.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(SqlResult {
+        columns,
+        rows,
+        affected_rows: 0,
+        duration_ms: elapsed.as_millis() as u64,
+        warnings: vec![],
+        last_insert_id: None,
+        applied_limit: None,
+        from_cache: false,
+        spilled: None,
+    })
+}
+
+#[command]
+pub async fn get_neo4j_schema(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Neo4jSchema, String> {
+    let graph = get_or_create_graph(&app_state, &db_state, connection_id).await?;
+
+    let labels = list_single_column(&graph, "CALL db.labels()", "label").await?;
+    let relationship_types =
+        list_single_column(&graph, "CALL db.relationshipTypes()", "relationshipType").await?;
+    let property_keys = list_single_column(&graph, "CALL db.propertyKeys()", "propertyKey").await?;
+
+    Ok(Neo4jSchema {
+        labels,
+        relationship_types,
+        property_keys,
+    })
+}
@@ -0,0 +1,205 @@
+use crate::alter_table::quote_identifier;
+use crate::database_search::like_pattern;
+use crate::db::{connection_flavor, DbState};
+use crate::models::{ObjectMatch, ObjectSearchResult};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use tauri::{command, State};
+
+const OBJECT_SEARCH_DEFAULT_LIMIT: u32 = 500;
+const OBJECT_SEARCH_MAX_LIMIT: u32 = 5000;
+
+// 默认不搜索的 MySQL 系统库
+const MYSQL_SYSTEM_SCHEMAS: &str =
+    "('information_schema', 'mysql', 'performance_schema', 'sys', 'metrics_schema')";
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 四个来源各绑定一次名称模式；按名称匹配时忽略大小写
+fn mysql_search_sql(include_system: bool) -> String {
+    let schema_filter = |column: &str| match include_system {
+        true => String::new(),
+        false => format!(" AND {} NOT IN {}", column, MYSQL_SYSTEM_SCHEMAS),
+    };
+    format!(
+        "SELECT object_type, db, tbl, name, data_type FROM ( \
+         SELECT CAST(CASE WHEN TABLE_TYPE LIKE '%VIEW%' THEN 'view' ELSE 'table' END AS CHAR) \
+         AS object_type, CAST(TABLE_SCHEMA AS CHAR) AS db, CAST(NULL AS CHAR) AS tbl, \
+         CAST(TABLE_NAME AS CHAR) AS name, CAST(NULL AS CHAR) AS data_type \
+         FROM information_schema.TABLES \
+         WHERE LOWER(CAST(TABLE_NAME AS CHAR)) LIKE ? ESCAPE '!'{} \
+         UNION ALL SELECT CAST('column' AS CHAR), CAST(TABLE_SCHEMA AS CHAR), \
+         CAST(TABLE_NAME AS CHAR), CAST(COLUMN_NAME AS CHAR), CAST(COLUMN_TYPE AS CHAR) \
+         FROM information_schema.COLUMNS \
+         WHERE LOWER(CAST(COLUMN_NAME AS CHAR)) LIKE ? ESCAPE '!'{} \
+         UNION ALL SELECT CAST(LOWER(ROUTINE_TYPE) AS CHAR), CAST(ROUTINE_SCHEMA AS CHAR), \
+         CAST(NULL AS CHAR), CAST(ROUTINE_NAME AS CHAR), CAST(NULL AS CHAR) \
+         FROM information_schema.ROUTINES \
+         WHERE LOWER(CAST(ROUTINE_NAME AS CHAR)) LIKE ? ESCAPE '!'{} \
+         UNION ALL SELECT CAST('trigger' AS CHAR), CAST(TRIGGER_SCHEMA AS CHAR), \
+         CAST(EVENT_OBJECT_TABLE AS CHAR), CAST(TRIGGER_NAME AS CHAR), CAST(NULL AS CHAR) \
+         FROM information_schema.TRIGGERS \
+         WHERE LOWER(CAST(TRIGGER_NAME AS CHAR)) LIKE ? ESCAPE '!'{} \
+         ) o ORDER BY db, COALESCE(tbl, name), tbl IS NOT NULL, object_type, name LIMIT ?",
+        schema_filter("TABLE_SCHEMA"),
+        schema_filter("TABLE_SCHEMA"),
+        schema_filter("ROUTINE_SCHEMA"),
+        schema_filter("TRIGGER_SCHEMA"),
+    )
+}
+
+async fn search_mysql(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    pattern: &str,
+    include_system: bool,
+    limit: u32,
+) -> Result<Vec<ObjectMatch>, String> {
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, None).await?;
+    let rows = sqlx::query(&mysql_search_sql(include_system))
+        .bind(pattern)
+        .bind(pattern)
+        .bind(pattern)
+        .bind(pattern)
+        .bind(limit + 1)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to search objects: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| ObjectMatch {
+            object_type: text(row, 0).unwrap_or_default(),
+            database: text(row, 1).unwrap_or_default(),
+            table: text(row, 2),
+            name: text(row, 3).unwrap_or_default(),
+            data_type: text(row, 4),
+        })
+        .collect())
+}
+
+// SQLite 搜索 main / temp 和 ATTACH 的库，没有存储过程；LIKE 默认忽略 ASCII 大小写
+async fn search_sqlite(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    pattern: &str,
+    limit: u32,
+) -> Result<Vec<ObjectMatch>, String> {
+    let pool = sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+    let schemas =
+        sqlx::query_scalar::<_, String>("SELECT name FROM pragma_database_list ORDER BY seq")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to list databases: {}", e))?;
+    let mut matches = Vec::new();
+    for schema in schemas {
+        let quoted = quote_identifier(&schema, SqlFlavor::Sqlite);
+        let objects = sqlx::query_as::<_, (String, String, String)>(&format!(
+            "SELECT type, name, tbl_name FROM {}.sqlite_master \
+             WHERE type IN ('table', 'view', 'trigger') AND name NOT LIKE 'sqlite!_%' ESCAPE '!' \
+             AND name LIKE ? ESCAPE '!'",
+            quoted
+        ))
+        .bind(pattern)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to search objects: {}", e))?;
+        matches.extend(objects.into_iter().map(|(kind, name, table)| ObjectMatch {
+            table: (kind == "trigger").then_some(table),
+            object_type: kind,
+            database: schema.clone(),
+            name,
+            data_type: None,
+        }));
+        let columns = sqlx::query_as::<_, (String, String, String)>(&format!(
+            "SELECT m.name, p.name, p.type FROM {}.sqlite_master m \
+             JOIN pragma_table_info(m.name, ?) p \
+             WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite!_%' ESCAPE '!' \
+             AND p.name LIKE ? ESCAPE '!'",
+            quoted
+        ))
+        .bind(&schema)
+        .bind(pattern)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to search columns: {}", e))?;
+        matches.extend(
+            columns
+                .into_iter()
+                .map(|(table, name, data_type)| ObjectMatch {
+                    object_type: "column".to_string(),
+                    database: schema.clone(),
+                    table: Some(table),
+                    name,
+                    data_type: Some(data_type),
+                }),
+        );
+        if matches.len() > limit as usize {
+            break;
+        }
+    }
+    // 与 MySQL 相同：同一张表的对象排在一起，表本身在其列和触发器之前
+    matches.sort_by(|a, b| {
+        let key = |m: &ObjectMatch| {
+            (
+                m.database.clone(),
+                m.table.clone().unwrap_or_else(|| m.name.clone()),
+                m.table.is_some(),
+                m.object_type.clone(),
+                m.name.clone(),
+            )
+        };
+        key(a).cmp(&key(b))
+    });
+    Ok(matches)
+}
+
+// 在连接可访问的所有库中按名称（包含匹配，忽略大小写）搜索表、视图、列、存储过程 / 函数和触发器，
+// 返回对象类型和所在的库 / 表。MySQL 默认跳过系统库，include_system 为 true 时一并搜索
+#[command]
+pub async fn search_objects(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    pattern: String,
+    include_system: Option<bool>,
+    limit: Option<u32>,
+) -> Result<ObjectSearchResult, String> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err("Search pattern is empty".to_string());
+    }
+    let flavor = connection_flavor(&db_state, connection_id, "Object search").await?;
+    let like = like_pattern(&pattern.to_lowercase());
+    let limit = limit
+        .unwrap_or(OBJECT_SEARCH_DEFAULT_LIMIT)
+        .clamp(1, OBJECT_SEARCH_MAX_LIMIT);
+    let mut matches = match flavor {
+        SqlFlavor::MySql => {
+            search_mysql(
+                &app_state,
+                &db_state,
+                connection_id,
+                &like,
+                include_system.unwrap_or(false),
+                limit,
+            )
+            .await?
+        }
+        SqlFlavor::Sqlite => {
+            search_sqlite(&app_state, &db_state, connection_id, &like, limit).await?
+        }
+    };
+    let truncated = matches.len() > limit as usize;
+    matches.truncate(limit as usize);
+    Ok(ObjectSearchResult { matches, truncated })
+}
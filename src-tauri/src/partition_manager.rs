@@ -0,0 +1,326 @@
+use crate::alter_table::{apply_ddl, check_fragment, qualified_name, quote_identifier};
+use crate::db::{connection_flavor, DbState};
+use crate::guard::ensure_partition_change_allowed;
+use crate::models::{DdlStatementResult, PartitionInfo, TablePartitions};
+use crate::mysql_manager;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use tauri::{command, State};
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+fn number(row: &MySqlRow, index: usize) -> Option<u64> {
+    row.try_get_unchecked::<Option<u64>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 分区只有 MySQL 系支持
+async fn ensure_mysql(db_state: &DbState, connection_id: i64) -> Result<(), String> {
+    match connection_flavor(db_state, connection_id, "Partitions").await? {
+        SqlFlavor::MySql => Ok(()),
+        SqlFlavor::Sqlite => Err("Partitions are not supported for sqlite".to_string()),
+    }
+}
+
+async fn fetch_partitions(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    table: &str,
+    db_name: Option<String>,
+) -> Result<TablePartitions, String> {
+    ensure_mysql(db_state, connection_id).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, db_name.clone())
+            .await?;
+    let rows = sqlx::query(
+        "SELECT CAST(PARTITION_NAME AS CHAR), CAST(SUBPARTITION_NAME AS CHAR), \
+         CAST(PARTITION_ORDINAL_POSITION AS SIGNED), CAST(PARTITION_METHOD AS CHAR), \
+         CAST(PARTITION_EXPRESSION AS CHAR), CAST(SUBPARTITION_METHOD AS CHAR), \
+         CAST(SUBPARTITION_EXPRESSION AS CHAR), CAST(PARTITION_DESCRIPTION AS CHAR), \
+         CAST(TABLE_ROWS AS UNSIGNED), CAST(DATA_LENGTH AS UNSIGNED), \
+         CAST(INDEX_LENGTH AS UNSIGNED), CAST(PARTITION_COMMENT AS CHAR) \
+         FROM information_schema.PARTITIONS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY PARTITION_ORDINAL_POSITION, SUBPARTITION_ORDINAL_POSITION",
+    )
+    .bind(db_name.as_deref())
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch partitions: {}", e))?;
+    // 未分区的表只有一行，PARTITION_NAME 为 NULL
+    let first = rows
+        .first()
+        .ok_or_else(|| format!("Table {} not found", table))?;
+    let method = text(first, 3).ok_or_else(|| format!("Table {} is not partitioned", table))?;
+    let partitions = rows
+        .iter()
+        .map(|row| PartitionInfo {
+            name: text(row, 0).unwrap_or_default(),
+            subpartition_name: text(row, 1),
+            position: row.try_get_unchecked::<i64, _>(2).unwrap_or_default(),
+            description: text(row, 7),
+            row_estimate: number(row, 8),
+            data_size_bytes: number(row, 9),
+            index_size_bytes: number(row, 10),
+            comment: text(row, 11).filter(|c| !c.is_empty()),
+        })
+        .collect();
+    Ok(TablePartitions {
+        method,
+        expression: text(first, 4),
+        subpartition_method: text(first, 5),
+        subpartition_expression: text(first, 6),
+        partitions,
+    })
+}
+
+// 返回分区方式、分区表达式以及每个分区的行数估计和大小
+#[command]
+pub async fn get_partitions(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    db_name: Option<String>,
+) -> Result<TablePartitions, String> {
+    fetch_partitions(&app_state, &db_state, connection_id, &table, db_name).await
+}
+
+// 新增分区：RANGE 的 values 为上界（或 MAXVALUE），LIST 为值列表，HASH / KEY 不需要 values。
+// 未确认时只返回语句供预览
+#[command]
+pub async fn add_partition(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    name: String,
+    values: Option<String>,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+) -> Result<DdlStatementResult, String> {
+    let partitioning = fetch_partitions(
+        &app_state,
+        &db_state,
+        connection_id,
+        &table,
+        db_name.clone(),
+    )
+    .await?;
+    let method = partitioning.method.to_ascii_uppercase();
+    let values = values.as_deref().map(str::trim);
+    if let Some(values) = values {
+        check_fragment("Partition values", values)?;
+    }
+    let definition = match (method.split_whitespace().next(), values) {
+        (Some("RANGE"), Some(values)) if values.eq_ignore_ascii_case("MAXVALUE") => {
+            " VALUES LESS THAN MAXVALUE".to_string()
+        }
+        (Some("RANGE"), Some(values)) => format!(" VALUES LESS THAN ({})", values),
+        (Some("LIST"), Some(values)) => format!(" VALUES IN ({})", values),
+        (Some("RANGE" | "LIST"), None) => {
+            return Err(format!("{} partitions require values", method))
+        }
+        (_, Some(_)) => return Err(format!("{} partitions do not take values", method)),
+        (_, None) => String::new(),
+    };
+    let statement = format!(
+        "ALTER TABLE {} ADD PARTITION (PARTITION {}{})",
+        qualified_name(db_name.as_deref(), &table, SqlFlavor::MySql),
+        quote_identifier(&name, SqlFlavor::MySql),
+        definition
+    );
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        SqlFlavor::MySql,
+        db_name,
+        std::slice::from_ref(&statement),
+        None,
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
+
+// 生成 DROP / TRUNCATE PARTITION 语句，先确认分区存在
+async fn partition_statement(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    table: &str,
+    action: &str,
+    partitions: &[String],
+    db_name: Option<&str>,
+) -> Result<String, String> {
+    let partitioning = fetch_partitions(
+        app_state,
+        db_state,
+        connection_id,
+        table,
+        db_name.map(str::to_string),
+    )
+    .await?;
+    if partitions.is_empty() {
+        return Err("No partitions specified".to_string());
+    }
+    for partition in partitions {
+        if !partitioning
+            .partitions
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(partition))
+        {
+            return Err(format!("Partition {} not found on {}", partition, table));
+        }
+    }
+    let method = partitioning.method.to_ascii_uppercase();
+    if action == "DROP" && !(method.starts_with("RANGE") || method.starts_with("LIST")) {
+        return Err(format!(
+            "DROP PARTITION only applies to RANGE and LIST partitioning, not {}",
+            method
+        ));
+    }
+    Ok(format!(
+        "ALTER TABLE {} {} PARTITION {}",
+        qualified_name(db_name, table, SqlFlavor::MySql),
+        action,
+        partitions
+            .iter()
+            .map(|p| quote_identifier(p, SqlFlavor::MySql))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+// DROP / TRUNCATE PARTITION 会丢弃分区中的数据，生产环境需要确认令牌
+async fn run_partition_change(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    action: &str,
+    statement: String,
+    db_name: Option<String>,
+    confirm_token: Option<&str>,
+) -> Result<DdlStatementResult, String> {
+    ensure_partition_change_allowed(
+        db_state,
+        connection_id,
+        &statement,
+        &format!("{} PARTITION", action),
+        confirm_token,
+    )
+    .await?;
+    apply_ddl(
+        app_state,
+        db_state,
+        connection_id,
+        SqlFlavor::MySql,
+        db_name,
+        std::slice::from_ref(&statement),
+        confirm_token,
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
+
+// 删除分区（仅 RANGE / LIST）：未确认时只返回语句供预览
+#[command]
+pub async fn drop_partitions(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    partitions: Vec<String>,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let statement = partition_statement(
+        &app_state,
+        &db_state,
+        connection_id,
+        &table,
+        "DROP",
+        &partitions,
+        db_name.as_deref(),
+    )
+    .await?;
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+    run_partition_change(
+        &app_state,
+        &db_state,
+        connection_id,
+        "DROP",
+        statement,
+        db_name,
+        confirm_token.as_deref(),
+    )
+    .await
+}
+
+// 清空分区中的数据：未确认时只返回语句供预览
+#[command]
+pub async fn truncate_partitions(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    partitions: Vec<String>,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let statement = partition_statement(
+        &app_state,
+        &db_state,
+        connection_id,
+        &table,
+        "TRUNCATE",
+        &partitions,
+        db_name.as_deref(),
+    )
+    .await?;
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+    run_partition_change(
+        &app_state,
+        &db_state,
+        connection_id,
+        "TRUNCATE",
+        statement,
+        db_name,
+        confirm_token.as_deref(),
+    )
+    .await
+}
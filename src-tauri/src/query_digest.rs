@@ -0,0 +1,198 @@
+use crate::db::{connection_flavor, DbState};
+use crate::models::{QueryDigestReport, QueryDigestStat};
+use crate::mysql_manager;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use sqlx::mysql::MySqlRow;
+use sqlx::pool::PoolConnection;
+use sqlx::{MySql, Row};
+use tauri::{command, State};
+
+const DIGEST_REPORT_DEFAULT_LIMIT: u32 = 20;
+const DIGEST_REPORT_MAX_LIMIT: u32 = 200;
+
+// performance_schema 的计时单位为皮秒
+const PICOS_PER_MS: f64 = 1_000_000_000.0;
+
+// 默认不统计在系统库中执行的语句（如监控工具自身的查询）
+const SYSTEM_SCHEMAS: &str = "('mysql', 'information_schema', 'performance_schema', 'sys')";
+
+const DIGEST_COLUMNS: &str = "CAST(SCHEMA_NAME AS CHAR), CAST(DIGEST AS CHAR), \
+     CAST(DIGEST_TEXT AS CHAR), CAST(COUNT_STAR AS CHAR), CAST(SUM_TIMER_WAIT AS CHAR), \
+     CAST(AVG_TIMER_WAIT AS CHAR), CAST(MAX_TIMER_WAIT AS CHAR), CAST(SUM_LOCK_TIME AS CHAR), \
+     CAST(SUM_ROWS_EXAMINED AS CHAR), CAST(SUM_ROWS_SENT AS CHAR), \
+     CAST(SUM_ROWS_AFFECTED AS CHAR), CAST(SUM_ERRORS AS CHAR), \
+     CAST(SUM_NO_INDEX_USED AS CHAR), CAST(FIRST_SEEN AS CHAR), CAST(LAST_SEEN AS CHAR)";
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 计数和计时都是 BIGINT UNSIGNED，按字符串读取后解析，避免超出 i64
+fn count(row: &MySqlRow, index: usize) -> u64 {
+    text(row, index).and_then(|t| t.parse().ok()).unwrap_or(0)
+}
+
+fn millis(row: &MySqlRow, index: usize) -> f64 {
+    text(row, index)
+        .and_then(|t| t.parse::<f64>().ok())
+        .unwrap_or(0.0)
+        / PICOS_PER_MS
+}
+
+// schema 过滤条件：指定 db_name 时只统计该库，否则按 include_system 决定是否排除系统库
+fn filter_sql(db_name: Option<&str>, include_system: bool) -> String {
+    match (db_name, include_system) {
+        (Some(_), _) => " WHERE SCHEMA_NAME = ?".to_string(),
+        (None, true) => String::new(),
+        (None, false) => format!(
+            " WHERE (SCHEMA_NAME IS NULL OR SCHEMA_NAME NOT IN {})",
+            SYSTEM_SCHEMAS
+        ),
+    }
+}
+
+async fn top_digests(
+    conn: &mut PoolConnection<MySql>,
+    filter: &str,
+    db_name: Option<&str>,
+    order_by: &str,
+    limit: u32,
+    total_latency_ms: f64,
+) -> Result<Vec<QueryDigestStat>, String> {
+    let sql = format!(
+        "SELECT {} FROM performance_schema.events_statements_summary_by_digest{} \
+         ORDER BY {} DESC LIMIT ?",
+        DIGEST_COLUMNS, filter, order_by
+    );
+    let mut query = sqlx::query(&sql);
+    if let Some(db_name) = db_name {
+        query = query.bind(db_name);
+    }
+    let rows = query
+        .bind(limit)
+        .fetch_all(&mut **conn)
+        .await
+        .map_err(|e| format!("Failed to fetch statement digests: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let total = millis(row, 4);
+            QueryDigestStat {
+                schema_name: text(row, 0),
+                digest: text(row, 1),
+                digest_text: text(row, 2),
+                exec_count: count(row, 3),
+                total_latency_ms: total,
+                avg_latency_ms: millis(row, 5),
+                max_latency_ms: millis(row, 6),
+                lock_time_ms: millis(row, 7),
+                rows_examined: count(row, 8),
+                rows_sent: count(row, 9),
+                rows_affected: count(row, 10),
+                errors: count(row, 11),
+                no_index_used: count(row, 12),
+                latency_percent: match total_latency_ms > 0.0 {
+                    true => total / total_latency_ms * 100.0,
+                    false => 0.0,
+                },
+                first_seen: text(row, 13),
+                last_seen: text(row, 14),
+            }
+        })
+        .collect())
+}
+
+// 从 performance_schema.events_statements_summary_by_digest 中取总耗时、扫描行数和执行次数
+// 最高的语句（统计从服务端启动或上次清空汇总表起累计）。db_name 只统计该库；
+// 未指定时默认排除系统库，include_system 为 true 时一并统计
+#[command]
+pub async fn get_query_digest_report(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    include_system: Option<bool>,
+    limit: Option<u32>,
+) -> Result<QueryDigestReport, String> {
+    if matches!(
+        connection_flavor(&db_state, connection_id, "Query digest report").await?,
+        SqlFlavor::Sqlite
+    ) {
+        return Err("Query digest report is not supported for sqlite".to_string());
+    }
+    let profile =
+        mysql_manager::get_server_profile_for(&app_state, &db_state, connection_id).await?;
+    if !profile.supports_performance_schema {
+        return Err("Query digest report requires performance_schema".to_string());
+    }
+    let limit = limit
+        .unwrap_or(DIGEST_REPORT_DEFAULT_LIMIT)
+        .clamp(1, DIGEST_REPORT_MAX_LIMIT);
+    let db_name = db_name.filter(|d| !d.trim().is_empty());
+
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, None).await?;
+    // 服务端支持时也可能在配置中关闭（MariaDB 默认关闭）
+    let enabled = sqlx::query_scalar::<_, i64>("SELECT CAST(@@performance_schema AS SIGNED)")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to check performance_schema: {}", e))?;
+    if enabled != 1 {
+        return Err("performance_schema is disabled on this server".to_string());
+    }
+
+    let filter = filter_sql(db_name.as_deref(), include_system.unwrap_or(false));
+    let totals_sql = format!(
+        "SELECT CAST(SUM(SUM_TIMER_WAIT) AS CHAR), CAST(SUM(COUNT_STAR) AS CHAR) \
+         FROM performance_schema.events_statements_summary_by_digest{}",
+        filter
+    );
+    let mut totals = sqlx::query(&totals_sql);
+    if let Some(db_name) = &db_name {
+        totals = totals.bind(db_name);
+    }
+    let totals = totals
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch statement digests: {}", e))?;
+    let total_latency_ms = millis(&totals, 0);
+
+    let db_name = db_name.as_deref();
+    let by_total_latency = top_digests(
+        &mut conn,
+        &filter,
+        db_name,
+        "SUM_TIMER_WAIT",
+        limit,
+        total_latency_ms,
+    )
+    .await?;
+    let by_rows_examined = top_digests(
+        &mut conn,
+        &filter,
+        db_name,
+        "SUM_ROWS_EXAMINED",
+        limit,
+        total_latency_ms,
+    )
+    .await?;
+    let by_exec_count = top_digests(
+        &mut conn,
+        &filter,
+        db_name,
+        "COUNT_STAR",
+        limit,
+        total_latency_ms,
+    )
+    .await?;
+    Ok(QueryDigestReport {
+        total_latency_ms,
+        total_executions: count(&totals, 1),
+        by_total_latency,
+        by_rows_examined,
+        by_exec_count,
+    })
+}
@@ -0,0 +1,160 @@
+use crate::db::DbState;
+use crate::models::{QueryHistory, QueryHistoryPage};
+use crate::redact::redact_credentials;
+use std::time::Duration;
+use tauri::{command, State};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+// 一次执行的结果：返回的行数 / 影响的行数 / 无计数（如 Redis 命令）/ 失败原因
+pub enum HistoryOutcome<'a> {
+    Rows(usize),
+    Affected(u64),
+    Done,
+    Failed(&'a str),
+}
+
+impl<'a> HistoryOutcome<'a> {
+    pub fn of<T>(result: &'a Result<T, String>, ok: impl FnOnce(&T) -> HistoryOutcome<'a>) -> Self {
+        match result {
+            Ok(value) => ok(value),
+            Err(e) => HistoryOutcome::Failed(e),
+        }
+    }
+}
+
+// 记录一条执行历史；写入失败不影响查询本身
+pub async fn record_history(
+    db_state: &DbState,
+    connection_id: i64,
+    query_text: &str,
+    elapsed: Duration,
+    outcome: HistoryOutcome<'_>,
+) {
+    let (row_count, affected_rows, error) = match outcome {
+        HistoryOutcome::Rows(n) => (Some(n as i64), None, None),
+        HistoryOutcome::Affected(n) => (None, Some(n as i64), None),
+        HistoryOutcome::Done => (None, None, None),
+        HistoryOutcome::Failed(e) => (None, None, Some(e)),
+    };
+    let _ = sqlx::query(
+        "INSERT INTO query_history \
+         (connection_id, query_text, duration_ms, row_count, affected_rows, success, error) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(connection_id)
+    .bind(redact_credentials(query_text))
+    .bind(elapsed.as_millis() as i64)
+    .bind(row_count)
+    .bind(affected_rows)
+    .bind(error.is_none())
+    .bind(error)
+    .execute(&db_state.pool)
+    .await;
+}
+
+// Redis 命令写入历史时的文本，AUTH 的参数不落盘
+pub fn redis_command_text(command: &str, args: &[String]) -> String {
+    if command.eq_ignore_ascii_case("AUTH") {
+        return format!("{} ***", command);
+    }
+    std::iter::once(command)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 按连接、关键字过滤历史，置顶的排在前面，其余按执行时间倒序；page 从 1 开始
+#[command]
+pub async fn search_query_history(
+    db_state: State<'_, DbState>,
+    connection_id: Option<i64>,
+    keyword: Option<String>,
+    pinned_only: Option<bool>,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<QueryHistoryPage, String> {
+    let pattern = keyword
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .map(|k| format!("%{}%", k));
+    let pinned_only = pinned_only.unwrap_or(false);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = (page.unwrap_or(1).max(1) - 1) * page_size;
+
+    const FILTER: &str = "WHERE (? IS NULL OR connection_id = ?) \
+                          AND (? IS NULL OR query_text LIKE ?) \
+                          AND (? = 0 OR pinned = 1)";
+
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM query_history {}", FILTER))
+        .bind(connection_id)
+        .bind(connection_id)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(pinned_only)
+        .fetch_one(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to search query history: {}", e))?;
+
+    let items = sqlx::query_as::<_, QueryHistory>(&format!(
+        "SELECT * FROM query_history {} \
+         ORDER BY pinned DESC, executed_at DESC, id DESC LIMIT ? OFFSET ?",
+        FILTER
+    ))
+    .bind(connection_id)
+    .bind(connection_id)
+    .bind(&pattern)
+    .bind(&pattern)
+    .bind(pinned_only)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to search query history: {}", e))?;
+
+    Ok(QueryHistoryPage { items, total })
+}
+
+#[command]
+pub async fn pin_query_history(
+    db_state: State<'_, DbState>,
+    id: i64,
+    pinned: bool,
+) -> Result<(), String> {
+    let result = sqlx::query("UPDATE query_history SET pinned = ? WHERE id = ?")
+        .bind(pinned)
+        .bind(id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to update query history: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err("Query history entry not found".to_string());
+    }
+    Ok(())
+}
+
+// 清理历史（置顶的保留）：可限定连接，older_than_days 为空时清理全部，返回删除的条数
+#[command]
+pub async fn purge_query_history(
+    db_state: State<'_, DbState>,
+    connection_id: Option<i64>,
+    older_than_days: Option<i64>,
+) -> Result<u64, String> {
+    let cutoff = older_than_days.map(|days| format!("-{} days", days.max(0)));
+    let result = sqlx::query(
+        "DELETE FROM query_history WHERE pinned = 0 \
+         AND (? IS NULL OR connection_id = ?) \
+         AND (? IS NULL OR executed_at < datetime('now', ?))",
+    )
+    .bind(connection_id)
+    .bind(connection_id)
+    .bind(&cutoff)
+    .bind(&cutoff)
+    .execute(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to purge query history: {}", e))?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,227 @@
+use crate::db::DbState;
+use crate::mysql_manager::kill_mysql_query;
+use crate::sqlite_manager::SqliteInterruptHandle;
+use crate::state::AppState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{command, Emitter, State};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+// 同一连接上同时执行的查询数上限，超出的查询排队等待
+const MAX_CONCURRENT_QUERIES_PER_CONNECTION: usize = 4;
+
+// 前端监听该事件展示排队位置
+pub const QUERY_QUEUE_EVENT: &str = "query-queue";
+
+static NEXT_EXECUTION_ID: AtomicU64 = AtomicU64::new(1);
+
+// 每个连接的排队状态：信号量控制并发，waiting 按排队顺序记录等待中的执行
+pub struct QueryQueue {
+    semaphore: Arc<Semaphore>,
+    waiting: Vec<(String, Arc<Notify>)>,
+}
+
+impl QueryQueue {
+    fn new() -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_QUERIES_PER_CONNECTION)),
+            waiting: Vec::new(),
+        }
+    }
+}
+
+// 正在执行的查询，cancel_query 据此中断
+pub enum RunningQuery {
+    // MySQL 执行查询的连接线程 id，通过另一个连接发送 KILL QUERY
+    MySql {
+        connection_id: i64,
+        db_name: Option<String>,
+        thread_id: u64,
+    },
+    // SQLite 连接句柄，通过 sqlite3_interrupt 中断
+    Sqlite(SqliteInterruptHandle),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryQueueEvent {
+    pub connection_id: i64,
+    pub execution_id: String,
+    // "queued" / "running" / "dropped"
+    pub status: String,
+    // 排队中的位置，从 1 开始
+    pub position: Option<usize>,
+}
+
+// 前端未指定执行 id 时生成一个
+pub fn new_execution_id() -> String {
+    format!("exec-{}", NEXT_EXECUTION_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+fn emit_queue_event(
+    app_state: &AppState,
+    connection_id: i64,
+    execution_id: &str,
+    status: &str,
+    position: Option<usize>,
+) {
+    if let Some(app) = app_state.app_handle.as_ref() {
+        let _ = app.emit(
+            QUERY_QUEUE_EVENT,
+            QueryQueueEvent {
+                connection_id,
+                execution_id: execution_id.to_string(),
+                status: status.to_string(),
+                position,
+            },
+        );
+    }
+}
+
+// 排队变化后通知所有等待中的执行各自的新位置
+async fn emit_positions(app_state: &AppState, connection_id: i64) {
+    let waiting: Vec<String> = match app_state.query_queues.lock().await.get(&connection_id) {
+        Some(queue) => queue.waiting.iter().map(|(id, _)| id.clone()).collect(),
+        None => return,
+    };
+    for (index, execution_id) in waiting.iter().enumerate() {
+        emit_queue_event(
+            app_state,
+            connection_id,
+            execution_id,
+            "queued",
+            Some(index + 1),
+        );
+    }
+}
+
+// 获取该连接的执行名额；名额已满时排队，直到轮到或被 drop_queued_query 移出队列
+pub async fn acquire_query_slot(
+    app_state: &AppState,
+    connection_id: i64,
+    execution_id: &str,
+) -> Result<OwnedSemaphorePermit, String> {
+    let (semaphore, cancel) = {
+        let mut queues = app_state.query_queues.lock().await;
+        let queue = queues.entry(connection_id).or_insert_with(QueryQueue::new);
+        if let Ok(permit) = queue.semaphore.clone().try_acquire_owned() {
+            drop(queues);
+            // 前端通过 running 事件拿到执行 id，用于 cancel_query
+            emit_queue_event(app_state, connection_id, execution_id, "running", None);
+            return Ok(permit);
+        }
+        let cancel = Arc::new(Notify::new());
+        queue
+            .waiting
+            .push((execution_id.to_string(), cancel.clone()));
+        (queue.semaphore.clone(), cancel)
+    };
+    emit_positions(app_state, connection_id).await;
+
+    let outcome = tokio::select! {
+        permit = semaphore.acquire_owned() => permit.map_err(|e| e.to_string()),
+        _ = cancel.notified() => Err("Query was removed from the queue".to_string()),
+    };
+
+    if let Some(queue) = app_state.query_queues.lock().await.get_mut(&connection_id) {
+        queue.waiting.retain(|(id, _)| id != execution_id);
+    }
+    let status = if outcome.is_ok() {
+        "running"
+    } else {
+        "dropped"
+    };
+    emit_queue_event(app_state, connection_id, execution_id, status, None);
+    emit_positions(app_state, connection_id).await;
+    outcome
+}
+
+// 查询开始执行时登记，执行结束（无论成功失败）后移除
+pub async fn register_running_query(app_state: &AppState, execution_id: &str, query: RunningQuery) {
+    app_state
+        .running_queries
+        .lock()
+        .await
+        .insert(execution_id.to_string(), query);
+}
+
+pub async fn finish_running_query(app_state: &AppState, execution_id: &str) {
+    app_state.running_queries.lock().await.remove(execution_id);
+}
+
+// 唤醒排队中的查询使其以 "removed from the queue" 结束，返回是否找到
+async fn drop_waiting(
+    app_state: &AppState,
+    connection_id: Option<i64>,
+    execution_id: &str,
+) -> bool {
+    let queues = app_state.query_queues.lock().await;
+    let cancel = queues
+        .iter()
+        .filter(|(id, _)| connection_id.is_none_or(|c| c == **id))
+        .find_map(|(_, queue)| {
+            queue
+                .waiting
+                .iter()
+                .find(|(id, _)| id == execution_id)
+                .map(|(_, cancel)| cancel.clone())
+        });
+    match cancel {
+        Some(cancel) => {
+            cancel.notify_one();
+            true
+        }
+        None => false,
+    }
+}
+
+// 把尚在排队的查询移出队列，返回是否找到该查询（已开始执行的查询不受影响）
+#[command]
+pub async fn drop_queued_query(
+    app_state: State<'_, AppState>,
+    connection_id: i64,
+    execution_id: String,
+) -> Result<bool, String> {
+    Ok(drop_waiting(&app_state, Some(connection_id), &execution_id).await)
+}
+
+// 取消查询：排队中的直接移出队列，执行中的 MySQL 查询发送 KILL QUERY，SQLite 查询调用 interrupt，
+// run_sql_file / search_database 同时停止执行后续语句。
+// 返回是否找到该查询（已执行完的查询返回 false）
+#[command]
+pub async fn cancel_query(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    execution_id: String,
+) -> Result<bool, String> {
+    if drop_waiting(&app_state, None, &execution_id).await {
+        return Ok(true);
+    }
+    // run_sql_file / search_database / generate_fake_data / export_result 在当前语句（批次、行）结束后停止
+    if let Some(cancel) = app_state.cancel_flags.lock().await.get(&execution_id) {
+        cancel.store(true, Ordering::Relaxed);
+    }
+
+    let target = {
+        let running = app_state.running_queries.lock().await;
+        match running.get(&execution_id) {
+            Some(RunningQuery::MySql {
+                connection_id,
+                db_name,
+                thread_id,
+            }) => Some((*connection_id, db_name.clone(), *thread_id)),
+            Some(RunningQuery::Sqlite(handle)) => {
+                handle.interrupt();
+                return Ok(true);
+            }
+            None => None,
+        }
+    };
+    match target {
+        Some((connection_id, db_name, thread_id)) => {
+            kill_mysql_query(&app_state, &db_state, connection_id, db_name, thread_id).await?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
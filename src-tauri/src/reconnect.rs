@@ -0,0 +1,91 @@
+use crate::state::AppState;
+use serde::Serialize;
+use std::future::Future;
+use tauri::Emitter;
+use tokio::time::{sleep, Duration};
+
+// 前端监听该事件展示连接状态
+pub const CONNECTION_STATUS_EVENT: &str = "connection-status";
+
+// 重建连接的最多尝试次数，以及指数退避的初始/最大间隔
+const RECONNECT_MAX_ATTEMPTS: u32 = 5;
+const RECONNECT_INITIAL_DELAY_MS: u64 = 500;
+const RECONNECT_MAX_DELAY_MS: u64 = 8_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionStatusEvent {
+    pub connection_id: i64,
+    // "connected" / "reconnecting" / "failed"
+    pub status: String,
+    pub attempt: u32,
+    pub error: Option<String>,
+}
+
+pub fn emit_connection_status(
+    app_state: &AppState,
+    connection_id: i64,
+    status: &str,
+    attempt: u32,
+    error: Option<String>,
+) {
+    let Some(app) = app_state.app_handle.as_ref() else {
+        return;
+    };
+    let _ = app.emit(
+        CONNECTION_STATUS_EVENT,
+        ConnectionStatusEvent {
+            connection_id,
+            status: status.to_string(),
+            attempt,
+            error,
+        },
+    );
+}
+
+// 缓存的连接已失效时调用：按指数退避反复执行 connect 重建连接，
+// 过程中发送 reconnecting / connected / failed 事件，全部失败时返回最后一次的错误
+pub async fn reconnect_with_backoff<T, F, Fut>(
+    app_state: &AppState,
+    connection_id: i64,
+    mut connect: F,
+) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let mut delay = Duration::from_millis(RECONNECT_INITIAL_DELAY_MS);
+    let mut last_error = String::new();
+
+    for attempt in 1..=RECONNECT_MAX_ATTEMPTS {
+        emit_connection_status(
+            app_state,
+            connection_id,
+            "reconnecting",
+            attempt,
+            (!last_error.is_empty()).then(|| last_error.clone()),
+        );
+        match connect().await {
+            Ok(value) => {
+                emit_connection_status(app_state, connection_id, "connected", attempt, None);
+                return Ok(value);
+            }
+            Err(e) => last_error = e,
+        }
+        if attempt < RECONNECT_MAX_ATTEMPTS {
+            sleep(delay).await;
+            delay = (delay * 2).min(Duration::from_millis(RECONNECT_MAX_DELAY_MS));
+        }
+    }
+
+    emit_connection_status(
+        app_state,
+        connection_id,
+        "failed",
+        RECONNECT_MAX_ATTEMPTS,
+        Some(last_error.clone()),
+    );
+    Err(format!(
+        "Connection lost and {} reconnect attempts failed: {}",
+        RECONNECT_MAX_ATTEMPTS, last_error
+    ))
+}
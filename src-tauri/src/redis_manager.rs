@@ -1,10 +1,18 @@
+use crate::connection_stats::record_query;
 use crate::db::DbState;
-use crate::models::Connection;
+use crate::guard::ensure_redis_command_allowed;
+use crate::models::{Connection, DatabaseInfo};
+use crate::query_history::{record_history, redis_command_text, HistoryOutcome};
+use crate::reconnect::reconnect_with_backoff;
+use crate::redact::{redact_credentials, redact_error};
+use crate::ssh_tunnel::resolve_endpoint;
 use crate::state::AppState;
-use redis::FromRedisValue;
+use crate::vault::reveal_secrets;
+use redis::{ClientTlsConfig, FromRedisValue, TlsCertificates};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::future::Future;
+use std::time::Instant;
 use tauri::{command, State};
 use tokio::time::{timeout, Duration};
 use urlencoding::encode;
@@ -60,21 +68,21 @@ async fn get_or_create_redis_client(
     .await
     .map_err(|e| format!("Failed to fetch connection info: {}", e))?
     .ok_or("Connection not found")?;
+    let connection = reveal_secrets(app_state, connection).await?;
 
     if connection.db_type != "redis" {
         return Err("Only Redis is supported for this operation".to_string());
     }
 
-    // 3. Resolve effective DB index
-    let db_index = if let Some(db_idx) = db {
-        db_idx
-    } else {
-        connection
+    // 3. Resolve effective DB index (options.redis_db takes precedence over the database field)
+    let db_index = match db.or(connection.options.redis_db) {
+        Some(db_idx) => db_idx,
+        None => connection
             .database
             .as_deref()
             .unwrap_or("0")
             .parse::<u32>()
-            .unwrap_or(0)
+            .unwrap_or(0),
     };
 
     // 4. Check cache again with resolved db_index
@@ -86,35 +94,203 @@ async fn get_or_create_redis_client(
         }
     }
 
-    // 5. Build connection URL
-    let host = connection.host.ok_or("Host is required")?;
-    let port = connection.port.unwrap_or(6379);
-    let password = connection.password.unwrap_or_default();
+    // 5. Create Client
+    let client = create_redis_client(app_state, &connection, db_index).await?;
+
+    // 6. Cache client
+    let mut clients = app_state.redis_clients.lock().await;
+    clients.insert(key, client.clone());
+
+    Ok(client)
+}
+
+// Build a client for the connection (unix socket or SSH tunnel, TLS and ACL auth applied) without caching it
+pub async fn create_redis_client(
+    app_state: &AppState,
+    connection: &Connection,
+    db_index: u32,
+) -> Result<redis::Client, String> {
+    let username = connection.username.clone().unwrap_or_default();
+    let password = connection.password.clone().unwrap_or_default();
 
-    let url = if !password.is_empty() {
-        format!("redis://:{}@{}:{}/{}", encode(&password), host, port, db_index)
+    let (target, tls_certs) = if let Some(socket_path) = connection.options.socket_path() {
+        // A local unix socket bypasses host/port, SSH tunnel and TLS
+        (RedisTarget::Unix(socket_path.to_string()), None)
     } else {
-        format!("redis://{}:{}/{}", host, port, db_index)
+        let host = connection.host.as_deref().ok_or("Host is required")?;
+        let port = connection.port.unwrap_or(6379) as u16;
+        let (host, port) = resolve_endpoint(app_state, connection, host, port).await?;
+
+        // TLS is driven by ssl_mode: "required" encrypts without verifying the server
+        // certificate, "verify_ca"/"verify_identity" verify it against the trust store or CA file
+        let ssl_mode = connection.ssl_mode.as_deref().unwrap_or_default();
+        let use_tls = matches!(ssl_mode, "required" | "verify_ca" | "verify_identity");
+        let scheme = if use_tls { "rediss" } else { "redis" };
+        let fragment = if ssl_mode == "required" { "#insecure" } else { "" };
+        let endpoint = format!("{}:{}/{}{}", host, port, db_index, fragment);
+
+        let tls_certs = if use_tls {
+            load_tls_certificates(connection)?
+        } else {
+            None
+        };
+        (RedisTarget::Tcp { scheme, endpoint }, tls_certs)
     };
+    let url = target.url(&username, &password, db_index);
+    let mut client = open_redis_client(url, tls_certs.clone())?;
+
+    // Redis 6+ ACL users authenticate with AUTH <username> <password>. If that is rejected,
+    // fall back to password-only AUTH (the "default" user) so connections that stored a
+    // username before ACLs were supported keep working.
+    if !username.is_empty() {
+        if let Err(e) = client.get_multiplexed_async_connection().await {
+            if e.kind() != redis::ErrorKind::AuthenticationFailed {
+                return Err(redact_error(
+                    format!("Failed to connect to Redis: {}", e),
+                    connection,
+                ));
+            }
+            let fallback = open_redis_client(target.url("", &password, db_index), tls_certs)?;
+            fallback
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(|e| {
+                    redact_error(format!("Failed to connect to Redis: {}", e), connection)
+                })?;
+            client = fallback;
+        }
+    }
 
-    // 6. Create Client
-    let client =
-        redis::Client::open(url).map_err(|e| format!("Failed to create Redis client: {}", e))?;
+    Ok(client)
+}
 
-    // 7. Cache client
-    let mut clients = app_state.redis_clients.lock().await;
-    clients.insert(key, client.clone());
+enum RedisTarget {
+    Tcp {
+        scheme: &'static str,
+        endpoint: String,
+    },
+    Unix(String),
+}
 
-    Ok(client)
+impl RedisTarget {
+    // Build the connection URL, including ACL username and password when set
+    fn url(&self, username: &str, password: &str, db_index: u32) -> String {
+        match self {
+            RedisTarget::Tcp { scheme, endpoint } => {
+                match (username.is_empty(), password.is_empty()) {
+                    (true, true) => format!("{}://{}", scheme, endpoint),
+                    (true, false) => format!("{}://:{}@{}", scheme, encode(password), endpoint),
+                    (false, true) => format!("{}://{}@{}", scheme, encode(username), endpoint),
+                    (false, false) => format!(
+                        "{}://{}:{}@{}",
+                        scheme,
+                        encode(username),
+                        encode(password),
+                        endpoint
+                    ),
+                }
+            }
+            // redis+unix:// carries credentials and db index in the query string
+            RedisTarget::Unix(socket_path) => {
+                let mut url = format!("redis+unix://{}?db={}", socket_path, db_index);
+                if !username.is_empty() {
+                    url.push_str(&format!("&user={}", encode(username)));
+                }
+                if !password.is_empty() {
+                    url.push_str(&format!("&pass={}", encode(password)));
+                }
+                url
+            }
+        }
+    }
+}
+
+fn open_redis_client(
+    url: String,
+    tls_certs: Option<TlsCertificates>,
+) -> Result<redis::Client, String> {
+    match tls_certs {
+        Some(certs) => redis::Client::build_with_tls(url, certs),
+        None => redis::Client::open(url),
+    }
+    // The URL carries the password, so never echo it back in the error
+    .map_err(|e| redact_credentials(&format!("Failed to create Redis client: {}", e)))
+}
+
+// Load the optional CA and client certificate files configured for the connection
+fn load_tls_certificates(connection: &Connection) -> Result<Option<TlsCertificates>, String> {
+    let read = |path: &str, what: &str| {
+        std::fs::read(path).map_err(|e| format!("Failed to read {} {}: {}", what, path, e))
+    };
+
+    let root_cert = match connection.ssl_ca_path.as_deref().filter(|p| !p.is_empty()) {
+        Some(path) => Some(read(path, "CA certificate")?),
+        None => None,
+    };
+    let client_tls = match (
+        connection.ssl_cert_path.as_deref().filter(|p| !p.is_empty()),
+        connection.ssl_key_path.as_deref().filter(|p| !p.is_empty()),
+    ) {
+        (Some(cert), Some(key)) => Some(ClientTlsConfig {
+            client_cert: read(cert, "client certificate")?,
+            client_key: read(key, "client key")?,
+        }),
+        (None, None) => None,
+        _ => return Err("Client certificate and key must be provided together".to_string()),
+    };
+
+    if root_cert.is_none() && client_tls.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(TlsCertificates {
+        client_tls,
+        root_cert,
+    }))
 }
 
-async fn get_redis_connection_with_retry(
-    client: &redis::Client,
+// Open a multiplexed connection. If the cached client no longer connects (server restart,
+// dead SSH tunnel), drop it and rebuild with backoff, emitting connection-status events.
+async fn get_redis_connection(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db: Option<u32>,
 ) -> Result<redis::aio::MultiplexedConnection, String> {
-    client
-        .get_multiplexed_async_connection()
+    let prefix = format!("{}:", connection_id);
+    let was_cached = app_state
+        .redis_clients
+        .lock()
         .await
-        .map_err(|e| format!("Failed to get Redis connection: {}", e))
+        .keys()
+        .any(|key| key.starts_with(&prefix));
+
+    let client = get_or_create_redis_client(app_state, db_state, connection_id, db).await?;
+    let error = match client.get_multiplexed_async_connection().await {
+        Ok(con) => return Ok(con),
+        Err(e) => e,
+    };
+    // A freshly built client or rejected credentials will not recover by retrying
+    if !was_cached || error.kind() == redis::ErrorKind::AuthenticationFailed {
+        return Err(format!("Failed to get Redis connection: {}", error));
+    }
+
+    reconnect_with_backoff(app_state, connection_id, || {
+        let prefix = &prefix;
+        async move {
+            app_state
+                .redis_clients
+                .lock()
+                .await
+                .retain(|key, _| !key.starts_with(prefix));
+            let client =
+                get_or_create_redis_client(app_state, db_state, connection_id, db).await?;
+            client
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(|e| format!("Failed to get Redis connection: {}", e))
+        }
+    })
+    .await
 }
 
 async fn query_with_timeout<T, F>(future: F, context: &str) -> Result<T, String>
@@ -130,6 +306,67 @@ where
     }
 }
 
+// 解析 INFO keyspace 的 "db0:keys=12,expires=0,avg_ttl=0" 行
+fn parse_keyspace(info: &str) -> Vec<(u32, u64)> {
+    info.lines()
+        .filter_map(|line| {
+            let (db, fields) = line.trim().strip_prefix("db")?.split_once(':')?;
+            let keys = fields
+                .split(',')
+                .find_map(|field| field.strip_prefix("keys="))?;
+            Some((db.parse().ok()?, keys.parse().ok()?))
+        })
+        .collect()
+}
+
+// 列出 Redis 的逻辑库及各库键数量。INFO keyspace 只包含非空库，
+// 能读取 CONFIG databases 时补齐空库；托管 Redis 常禁用 CONFIG，此时只返回非空库
+pub async fn list_redis_databases(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<DatabaseInfo>, String> {
+    let mut con = get_redis_connection(app_state, db_state, connection_id, None).await?;
+    let info: String = query_with_timeout(
+        redis::cmd("INFO").arg("keyspace").query_async(&mut con),
+        "Redis INFO keyspace",
+    )
+    .await?;
+    let keyspace = parse_keyspace(&info);
+
+    let configured = query_with_timeout(
+        redis::cmd("CONFIG")
+            .arg("GET")
+            .arg("databases")
+            .query_async::<Vec<String>>(&mut con),
+        "Redis CONFIG GET",
+    )
+    .await
+    .ok()
+    .and_then(|pair| pair.get(1).and_then(|n| n.parse::<u32>().ok()));
+
+    let databases = match configured {
+        Some(count) => (0..count)
+            .map(|db| {
+                let keys = keyspace
+                    .iter()
+                    .find(|(index, _)| *index == db)
+                    .map_or(0, |(_, keys)| *keys);
+                (db, keys)
+            })
+            .collect(),
+        None => keyspace,
+    };
+    Ok(databases
+        .into_iter()
+        .map(|(db, keys)| DatabaseInfo {
+            name: format!("db{}", db),
+            size_bytes: None,
+            key_count: Some(keys),
+        })
+        .collect())
+}
+
 // 辅助函数：从 pipeline 结果解析 KeyDetail
 fn parse_key_details_from_pipeline(keys: &[String], results: &[redis::Value]) -> Vec<KeyDetail> {
     keys.iter()
@@ -195,19 +432,32 @@ pub async fn execute_redis_command(
     command: String,
     args: Vec<String>,
     db: Option<u32>,
+    confirm_token: Option<String>,
 ) -> Result<RedisResult, String> {
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-
-    // Use multiplexed async connection as recommended by warning
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    ensure_redis_command_allowed(
+        &db_state,
+        connection_id,
+        &command,
+        &args,
+        confirm_token.as_deref(),
+    )
+    .await?;
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
+    let history_text = redis_command_text(&command, &args);
 
     let mut cmd = redis::cmd(&command);
     for arg in args {
         cmd.arg(arg);
     }
 
-    let result: redis::Value =
-        query_with_timeout(cmd.query_async(&mut con), "Redis command").await?;
+    let started = Instant::now();
+    let result: Result<redis::Value, String> =
+        query_with_timeout(cmd.query_async(&mut con), "Redis command").await;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let outcome = HistoryOutcome::of(&result, |_| HistoryOutcome::Done);
+    record_history(&db_state, connection_id, &history_text, elapsed, outcome).await;
+    let result = result?;
 
     let json_result = redis_value_to_json(result);
 
@@ -234,9 +484,19 @@ pub async fn execute_redis_pipeline(
     connection_id: i64,
     commands: Vec<PipelineCommand>,
     db: Option<u32>,
+    confirm_token: Option<String>,
 ) -> Result<PipelineResult, String> {
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    for cmd in &commands {
+        ensure_redis_command_allowed(
+            &db_state,
+            connection_id,
+            &cmd.command,
+            &cmd.args,
+            confirm_token.as_deref(),
+        )
+        .await?;
+    }
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
 
     let mut pipe = redis::pipe();
     for cmd in &commands {
@@ -247,8 +507,20 @@ pub async fn execute_redis_pipeline(
         pipe.add_command(redis_cmd);
     }
 
-    let results: Vec<redis::Value> =
-        query_with_timeout(pipe.query_async(&mut con), "Pipeline").await?;
+    let started = Instant::now();
+    let results: Result<Vec<redis::Value>, String> =
+        query_with_timeout(pipe.query_async(&mut con), "Pipeline").await;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    // 整个 pipeline 记为一条历史，每条命令一行
+    let history_text = commands
+        .iter()
+        .map(|cmd| redis_command_text(&cmd.command, &cmd.args))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let outcome = HistoryOutcome::of(&results, |results| HistoryOutcome::Rows(results.len()));
+    record_history(&db_state, connection_id, &history_text, elapsed, outcome).await;
+    let results = results?;
 
     let json_results: Vec<JsonValue> = results.into_iter().map(redis_value_to_json).collect();
 
@@ -267,9 +539,7 @@ pub async fn get_redis_keys(
     pattern: Option<String>,
     db: Option<u32>,
 ) -> Result<ScanResult, String> {
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
 
     let count = count.unwrap_or(100);
     let pattern = pattern.unwrap_or_else(|| "*".to_string());
@@ -319,8 +589,7 @@ pub async fn get_keys_details(
         return Ok(vec![]);
     }
 
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
 
     let mut pipe = redis::pipe();
     for key in &keys {
@@ -346,8 +615,7 @@ pub async fn scan_hash_values(
     pattern: Option<String>,
     db: Option<u32>,
 ) -> Result<ValueScanResult, String> {
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
     execute_scan_command(
         &mut con,
         "HSCAN",
@@ -370,8 +638,7 @@ pub async fn scan_set_members(
     pattern: Option<String>,
     db: Option<u32>,
 ) -> Result<ValueScanResult, String> {
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
     execute_scan_command(
         &mut con,
         "SSCAN",
@@ -394,8 +661,7 @@ pub async fn scan_zset_members(
     pattern: Option<String>,
     db: Option<u32>,
 ) -> Result<ValueScanResult, String> {
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
     execute_scan_command(
         &mut con,
         "ZSCAN",
@@ -417,8 +683,7 @@ pub async fn scan_list_values(
     end: i64,
     db: Option<u32>,
 ) -> Result<RedisResult, String> {
-    let client = get_or_create_redis_client(&app_state, &db_state, connection_id, db).await?;
-    let mut con = get_redis_connection_with_retry(&client).await?;
+    let mut con = get_redis_connection(&app_state, &db_state, connection_id, db).await?;
 
     let mut cmd = redis::cmd("LRANGE");
     cmd.arg(&key).arg(start).arg(end);
@@ -433,7 +698,7 @@ pub async fn scan_list_values(
     })
 }
 
-fn redis_value_to_json(v: redis::Value) -> JsonValue {
+pub fn redis_value_to_json(v: redis::Value) -> JsonValue {
     match &v {
         redis::Value::Nil => JsonValue::Null,
         redis::Value::Int(i) => JsonValue::Number((*i).into()),
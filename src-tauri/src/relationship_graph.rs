@@ -0,0 +1,363 @@
+use crate::db::{connection_flavor, DbState};
+use crate::models::{GraphColumn, GraphTable, RelationshipEdge, RelationshipGraph};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use tauri::{command, State};
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 同一外键的列相邻时合并为一条边
+fn push_edge_column(
+    edges: &mut Vec<RelationshipEdge>,
+    edge: RelationshipEdge,
+    same_key: impl Fn(&RelationshipEdge) -> bool,
+) {
+    match edges.last_mut() {
+        Some(last) if same_key(last) => {
+            last.from_columns.extend(edge.from_columns);
+            last.to_columns.extend(edge.to_columns);
+        }
+        _ => edges.push(edge),
+    }
+}
+
+async fn mysql_graph(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Result<(Vec<GraphTable>, Vec<RelationshipEdge>), String> {
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, db_name.clone())
+            .await?;
+    let rows = sqlx::query(
+        "SELECT CAST(c.TABLE_NAME AS CHAR), CAST(c.COLUMN_NAME AS CHAR), \
+         CAST(c.COLUMN_TYPE AS CHAR), CAST(c.IS_NULLABLE AS CHAR), CAST(c.COLUMN_KEY AS CHAR) \
+         FROM information_schema.COLUMNS c JOIN information_schema.TABLES t \
+         ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME \
+         WHERE c.TABLE_SCHEMA = COALESCE(?, DATABASE()) AND t.TABLE_TYPE = 'BASE TABLE' \
+         ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION",
+    )
+    .bind(db_name.as_deref())
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let mut tables: Vec<GraphTable> = Vec::new();
+    for row in &rows {
+        let table = text(row, 0).unwrap_or_default();
+        let column = GraphColumn {
+            name: text(row, 1).unwrap_or_default(),
+            column_type: text(row, 2).unwrap_or_default(),
+            nullable: text(row, 3).as_deref() == Some("YES"),
+            primary_key: text(row, 4).as_deref() == Some("PRI"),
+        };
+        match tables.last_mut() {
+            Some(last) if last.name == table => last.columns.push(column),
+            _ => tables.push(GraphTable {
+                name: table,
+                columns: vec![column],
+            }),
+        }
+    }
+
+    let rows = sqlx::query(
+        "SELECT CAST(CONSTRAINT_NAME AS CHAR), CAST(TABLE_NAME AS CHAR), CAST(COLUMN_NAME AS CHAR), \
+         CAST(REFERENCED_TABLE_SCHEMA AS CHAR), CAST(REFERENCED_TABLE_NAME AS CHAR), \
+         CAST(REFERENCED_COLUMN_NAME AS CHAR), CAST(TABLE_SCHEMA AS CHAR) \
+         FROM information_schema.KEY_COLUMN_USAGE \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND REFERENCED_TABLE_NAME IS NOT NULL \
+         ORDER BY TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION",
+    )
+    .bind(db_name.as_deref())
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+    let mut edges = Vec::new();
+    for row in &rows {
+        let schema = text(row, 6);
+        let referenced_schema = text(row, 3);
+        let edge = RelationshipEdge {
+            name: text(row, 0),
+            from_table: text(row, 1).unwrap_or_default(),
+            from_columns: vec![text(row, 2).unwrap_or_default()],
+            to_schema: referenced_schema.filter(|s| Some(s) != schema.as_ref()),
+            to_table: text(row, 4).unwrap_or_default(),
+            to_columns: vec![text(row, 5).unwrap_or_default()],
+            optional: false,
+        };
+        let (name, table) = (edge.name.clone(), edge.from_table.clone());
+        push_edge_column(&mut edges, edge, |last| {
+            last.name == name && last.from_table == table
+        });
+    }
+    Ok((tables, edges))
+}
+
+async fn sqlite_graph(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<(Vec<GraphTable>, Vec<RelationshipEdge>), String> {
+    let pool = sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+    let rows = sqlx::query_as::<_, (String, String, String, i64, i64)>(
+        "SELECT m.name, p.name, p.type, p.\"notnull\", p.pk \
+         FROM sqlite_master m JOIN pragma_table_info(m.name) p \
+         WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' ORDER BY m.name, p.cid",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let mut tables: Vec<GraphTable> = Vec::new();
+    for (table, name, column_type, not_null, pk) in rows {
+        let column = GraphColumn {
+            name,
+            column_type,
+            nullable: not_null == 0 && pk == 0,
+            primary_key: pk > 0,
+        };
+        match tables.last_mut() {
+            Some(last) if last.name == table => last.columns.push(column),
+            _ => tables.push(GraphTable {
+                name: table,
+                columns: vec![column],
+            }),
+        }
+    }
+
+    let rows = sqlx::query_as::<_, (String, i64, String, String, Option<String>)>(
+        "SELECT m.name, f.id, f.\"table\", f.\"from\", f.\"to\" \
+         FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) f \
+         WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' ORDER BY m.name, f.id, f.seq",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+    let mut edges = Vec::new();
+    let mut current: Option<(String, i64)> = None;
+    for (table, id, parent, from, to) in rows {
+        // REFERENCES parent 省略列名时引用父表主键
+        let to = to.unwrap_or_else(|| {
+            let position = edges
+                .last()
+                .filter(|_| current.as_ref() == Some(&(table.clone(), id)))
+                .map_or(0, |edge: &RelationshipEdge| edge.to_columns.len());
+            tables
+                .iter()
+                .find(|t| t.name == parent)
+                .and_then(|t| t.columns.iter().filter(|c| c.primary_key).nth(position))
+                .map(|c| c.name.clone())
+                .unwrap_or_default()
+        });
+        let same = current.as_ref() == Some(&(table.clone(), id));
+        current = Some((table.clone(), id));
+        let edge = RelationshipEdge {
+            name: None,
+            from_table: table,
+            from_columns: vec![from],
+            to_schema: None,
+            to_table: parent,
+            to_columns: vec![to],
+            optional: false,
+        };
+        push_edge_column(&mut edges, edge, |_| same);
+    }
+    Ok((tables, edges))
+}
+
+// Mermaid 的实体名、属性名和类型只能是单词，其余字符替换为下划线
+fn mermaid_word(text: &str) -> String {
+    let word: String = text
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '(' | ')' => c,
+            _ => '_',
+        })
+        .collect();
+    if word.is_empty() {
+        "_".to_string()
+    } else {
+        word
+    }
+}
+
+fn edge_target(edge: &RelationshipEdge) -> String {
+    match &edge.to_schema {
+        Some(schema) => format!("{}.{}", schema, edge.to_table),
+        None => edge.to_table.clone(),
+    }
+}
+
+fn mermaid(tables: &[GraphTable], edges: &[RelationshipEdge]) -> String {
+    let mut lines = vec!["erDiagram".to_string()];
+    for table in tables {
+        lines.push(format!("    {} {{", mermaid_word(&table.name)));
+        for column in &table.columns {
+            let foreign = edges
+                .iter()
+                .any(|e| e.from_table == table.name && e.from_columns.contains(&column.name));
+            let keys = match (column.primary_key, foreign) {
+                (true, true) => " PK, FK",
+                (true, false) => " PK",
+                (false, true) => " FK",
+                (false, false) => "",
+            };
+            lines.push(format!(
+                "        {} {}{}",
+                mermaid_word(&column.column_type),
+                mermaid_word(&column.name),
+                keys
+            ));
+        }
+        lines.push("    }".to_string());
+    }
+    for edge in edges {
+        // 父表一侧：外键可为空时为零或一
+        let parent = if edge.optional { "|o" } else { "||" };
+        let label = edge
+            .name
+            .clone()
+            .unwrap_or_else(|| edge.from_columns.join(", "));
+        lines.push(format!(
+            "    {} {}--o{{ {} : \"{}\"",
+            mermaid_word(&edge_target(edge)),
+            parent,
+            mermaid_word(&edge.from_table),
+            label.replace('"', "'")
+        ));
+    }
+    lines.join("\n")
+}
+
+fn dot_id(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// 列的端口名用序号，避免转义列名
+fn dot_port(tables: &[GraphTable], table: &str, column: Option<&String>) -> String {
+    let position = tables
+        .iter()
+        .find(|t| t.name == table)
+        .and_then(|t| column.and_then(|column| t.columns.iter().position(|c| &c.name == column)));
+    match position {
+        Some(position) => format!("{}:c{}", dot_id(table), position),
+        None => dot_id(table),
+    }
+}
+
+fn dot(tables: &[GraphTable], edges: &[RelationshipEdge]) -> String {
+    let mut lines = vec![
+        "digraph schema {".to_string(),
+        "  rankdir=LR;".to_string(),
+        "  node [shape=plaintext];".to_string(),
+    ];
+    for table in tables {
+        let mut label = format!(
+            "<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">\
+             <tr><td bgcolor=\"lightgrey\"><b>{}</b></td></tr>",
+            html_escape(&table.name)
+        );
+        for (i, column) in table.columns.iter().enumerate() {
+            let name = match column.primary_key {
+                true => format!("<u>{}</u>", html_escape(&column.name)),
+                false => html_escape(&column.name),
+            };
+            label.push_str(&format!(
+                "<tr><td port=\"c{}\" align=\"left\">{} : {}</td></tr>",
+                i,
+                name,
+                html_escape(&column.column_type)
+            ));
+        }
+        label.push_str("</table>");
+        lines.push(format!("  {} [label=<{}>];", dot_id(&table.name), label));
+    }
+    for edge in edges {
+        let target = match &edge.to_schema {
+            Some(_) => dot_id(&edge_target(edge)),
+            None => dot_port(tables, &edge.to_table, edge.to_columns.first()),
+        };
+        let mut attributes = Vec::new();
+        if let Some(name) = &edge.name {
+            attributes.push(format!("label={}", dot_id(name)));
+        }
+        if edge.optional {
+            attributes.push("style=dashed".to_string());
+        }
+        lines.push(format!(
+            "  {} -> {}{};",
+            dot_port(tables, &edge.from_table, edge.from_columns.first()),
+            target,
+            match attributes.is_empty() {
+                true => String::new(),
+                false => format!(" [{}]", attributes.join(", ")),
+            }
+        ));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+// 读取库中的表（含列）和外键关系；SQLite 只读取 main 库
+pub async fn load_relationships(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    flavor: SqlFlavor,
+) -> Result<(Vec<GraphTable>, Vec<RelationshipEdge>), String> {
+    let (tables, mut edges) = match flavor {
+        SqlFlavor::MySql => mysql_graph(app_state, db_state, connection_id, db_name).await?,
+        SqlFlavor::Sqlite => sqlite_graph(app_state, db_state, connection_id).await?,
+    };
+    for edge in &mut edges {
+        edge.optional = tables
+            .iter()
+            .find(|t| t.name == edge.from_table)
+            .is_some_and(|t| {
+                edge.from_columns
+                    .iter()
+                    .all(|name| t.columns.iter().any(|c| &c.name == name && c.nullable))
+            });
+    }
+    Ok((tables, edges))
+}
+
+// 返回库中的表（含列）和外键关系，供前端绘制 ER 图。format 为 "mermaid" 或 "dot" 时
+// 同时生成 Mermaid erDiagram / Graphviz DOT 文本
+#[command]
+pub async fn get_relationship_graph(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+    format: Option<String>,
+) -> Result<RelationshipGraph, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Relationship graphs").await?;
+    let (tables, edges) =
+        load_relationships(&app_state, &db_state, connection_id, db_name, flavor).await?;
+    let diagram = match format.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        None => None,
+        Some("mermaid") => Some(mermaid(&tables, &edges)),
+        Some("dot") | Some("graphviz") => Some(dot(&tables, &edges)),
+        Some(other) => return Err(format!("Unsupported diagram format: {}", other)),
+    };
+    Ok(RelationshipGraph {
+        tables,
+        edges,
+        diagram,
+    })
+}
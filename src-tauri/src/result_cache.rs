@@ -0,0 +1,115 @@
+use crate::db::DbState;
+use crate::guard::connection_options;
+use crate::models::SqlResult;
+use crate::state::AppState;
+use std::time::{Duration, Instant};
+use tauri::{command, State};
+
+// 行数超过该值的结果和落盘的结果不缓存，避免占用过多内存
+const RESULT_CACHE_MAX_ROWS: usize = 10_000;
+// 缓存条目数上限，超出时先淘汰最早过期的
+const RESULT_CACHE_MAX_ENTRIES: usize = 100;
+
+// (连接 id, 库名, SQL)
+pub type ResultCacheKey = (i64, Option<String>, String);
+
+pub struct CachedResult {
+    results: Vec<SqlResult>,
+    expires_at: Instant,
+}
+
+// 结果缓存的 TTL（connections.options.result_cache_ttl_secs），未启用时为 None
+pub async fn result_cache_ttl(
+    db_state: &DbState,
+    connection_id: i64,
+) -> Result<Option<Duration>, String> {
+    let options = connection_options(db_state, connection_id).await?;
+    Ok(options
+        .result_cache_ttl_secs
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs))
+}
+
+fn cache_key(connection_id: i64, db_name: Option<&str>, sql: &str) -> ResultCacheKey {
+    (
+        connection_id,
+        db_name.map(str::to_string),
+        sql.trim().to_string(),
+    )
+}
+
+// 取未过期的缓存结果，返回的结果带 from_cache 标记
+pub async fn cached_results(
+    app_state: &AppState,
+    connection_id: i64,
+    db_name: Option<&str>,
+    sql: &str,
+) -> Option<Vec<SqlResult>> {
+    let key = cache_key(connection_id, db_name, sql);
+    let mut cache = app_state.result_cache.lock().await;
+    let entry = cache.get(&key)?;
+    if entry.expires_at <= Instant::now() {
+        cache.remove(&key);
+        return None;
+    }
+    let mut results = entry.results.clone();
+    for result in &mut results {
+        result.from_cache = true;
+    }
+    Some(results)
+}
+
+pub async fn store_results(
+    app_state: &AppState,
+    connection_id: i64,
+    db_name: Option<&str>,
+    sql: &str,
+    results: &[SqlResult],
+    ttl: Duration,
+) {
+    let rows: usize = results.iter().map(|result| result.rows.len()).sum();
+    if rows > RESULT_CACHE_MAX_ROWS || results.iter().any(|result| result.spilled.is_some()) {
+        return;
+    }
+    let now = Instant::now();
+    let mut cache = app_state.result_cache.lock().await;
+    cache.retain(|_, entry| entry.expires_at > now);
+    if cache.len() >= RESULT_CACHE_MAX_ENTRIES {
+        if let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.expires_at)
+            .map(|(key, _)| key.clone())
+        {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(
+        cache_key(connection_id, db_name, sql),
+        CachedResult {
+            results: results.to_vec(),
+            expires_at: now + ttl,
+        },
+    );
+}
+
+// 连接上执行了写操作或连接被关闭时，丢弃该连接的全部缓存
+pub async fn invalidate_results(app_state: &AppState, connection_id: i64) {
+    app_state
+        .result_cache
+        .lock()
+        .await
+        .retain(|(id, _, _), _| *id != connection_id);
+}
+
+// 清除结果缓存：指定连接时只清除该连接的，否则全部清除
+#[command]
+pub async fn clear_result_cache(
+    app_state: State<'_, AppState>,
+    connection_id: Option<i64>,
+) -> Result<(), String> {
+    match connection_id {
+        Some(connection_id) => invalidate_results(&app_state, connection_id).await,
+        None => app_state.result_cache.lock().await.clear(),
+    }
+    Ok(())
+}
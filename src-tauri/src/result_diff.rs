@@ -0,0 +1,210 @@
+use crate::db::{connection_flavor, DbState};
+use crate::models::{DiffSource, ResultDiff, RowChange, SpilledResult};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::result_spill::{spilled_rows, RowSpooler};
+use crate::sql_classifier::{is_read_query, SqlFlavor};
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use futures_util::TryStreamExt;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+use tauri::{command, State};
+
+// 参与对比的单个结果最多读取的行数，对比在内存中进行
+const DIFF_MAX_ROWS: usize = 500_000;
+
+type Rows = Vec<Map<String, Value>>;
+
+fn too_large() -> String {
+    format!("Result exceeds {} rows and cannot be diffed", DIFF_MAX_ROWS)
+}
+
+// 执行只读查询并读出全部行
+async fn query_rows(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    sql: &str,
+    db_name: Option<String>,
+) -> Result<Rows, String> {
+    let flavor = connection_flavor(db_state, connection_id, "Diffing results").await?;
+    if !is_read_query(sql, flavor) {
+        return Err("Only read-only queries can be diffed".to_string());
+    }
+
+    let execution_id = new_execution_id();
+    let _permit = acquire_query_slot(app_state, connection_id, &execution_id).await?;
+    let mut rows = Vec::new();
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn =
+                mysql_manager::acquire_connection(app_state, db_state, connection_id, db_name)
+                    .await?;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(app_state, connection_id).await;
+            let mut stream = sqlx::query(sql).fetch(&mut *conn);
+            while let Some(row) = stream
+                .try_next()
+                .await
+                .map_err(|e| format!("Query execution failed: {}", e))?
+            {
+                if rows.len() >= DIFF_MAX_ROWS {
+                    return Err(too_large());
+                }
+                rows.push(mysql_manager::row_to_json(&row, timestamp_display));
+            }
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let mut stream = sqlx::query(sql).fetch(&pool);
+            while let Some(row) = stream
+                .try_next()
+                .await
+                .map_err(|e| format!("Query execution failed: {}", e))?
+            {
+                if rows.len() >= DIFF_MAX_ROWS {
+                    return Err(too_large());
+                }
+                rows.push(sqlite_manager::row_to_json(&row));
+            }
+        }
+    }
+    Ok(rows)
+}
+
+async fn source_rows(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    source: DiffSource,
+) -> Result<Rows, String> {
+    match (source.result_id, source.sql) {
+        (Some(result_id), _) => spilled_rows(app_state, &result_id, DIFF_MAX_ROWS).await,
+        (None, Some(sql)) => {
+            query_rows(app_state, db_state, connection_id, &sql, source.db_name).await
+        }
+        (None, None) => Err("Either sql or result_id is required".to_string()),
+    }
+}
+
+// 行的键：各键列值的 JSON 数组
+fn row_key(row: &Map<String, Value>, key_columns: &[String]) -> Result<String, String> {
+    let values = key_columns
+        .iter()
+        .map(|column| {
+            row.get(column)
+                .ok_or_else(|| format!("Key column {} not found in result", column))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    serde_json::to_string(&values).map_err(|e| e.to_string())
+}
+
+fn index_rows(
+    rows: &[Map<String, Value>],
+    key_columns: &[String],
+) -> Result<HashMap<String, usize>, String> {
+    let mut index = HashMap::with_capacity(rows.len());
+    for (i, row) in rows.iter().enumerate() {
+        let key = row_key(row, key_columns)?;
+        if index.insert(key.clone(), i).is_some() {
+            return Err(format!(
+                "Duplicate key {} in result; choose columns that identify each row",
+                key
+            ));
+        }
+    }
+    Ok(index)
+}
+
+fn diff_rows(left: Rows, right: Rows, key_columns: Vec<String>) -> Result<ResultDiff, String> {
+    let left_index = index_rows(&left, &key_columns)?;
+    let right_index = index_rows(&right, &key_columns)?;
+
+    let removed = left
+        .iter()
+        .filter(|row| row_key(row, &key_columns).is_ok_and(|key| !right_index.contains_key(&key)))
+        .cloned()
+        .collect();
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    let mut unchanged = 0;
+    // 按右侧（新结果）的行序输出
+    for after in right {
+        let key = row_key(&after, &key_columns)?;
+        let Some(&i) = left_index.get(&key) else {
+            added.push(after);
+            continue;
+        };
+        let before = &left[i];
+        // 只在一侧存在的列也算作变化
+        let changed_columns: Vec<String> = before
+            .keys()
+            .chain(after.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|column| before.get(*column) != after.get(*column))
+            .cloned()
+            .collect();
+        if changed_columns.is_empty() {
+            unchanged += 1;
+            continue;
+        }
+        let key = key_columns
+            .iter()
+            .filter_map(|column| Some((column.clone(), after.get(column)?.clone())))
+            .collect();
+        changed.push(RowChange {
+            key,
+            before: before.clone(),
+            after,
+            changed_columns,
+        });
+    }
+
+    Ok(ResultDiff {
+        key_columns,
+        added,
+        removed,
+        changed,
+        unchanged,
+    })
+}
+
+// 执行只读查询并把全部行保存到临时库，返回的 result_id 可作为 diff_results 的一侧。
+// 典型用法：迁移前保存结果，迁移后用同一查询与之对比；用完后调用 release_result 释放
+#[command]
+pub async fn store_query_result(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+    db_name: Option<String>,
+) -> Result<SpilledResult, String> {
+    let rows = query_rows(&app_state, &db_state, connection_id, &sql, db_name).await?;
+    let mut spooler = RowSpooler::new(&app_state, connection_id).always_spill();
+    for row in rows {
+        spooler.push(row).await?;
+    }
+    let (_, spilled) = spooler.finish().await?;
+    spilled.ok_or_else(|| "Failed to store result".to_string())
+}
+
+// 以 key_columns 为键对比两个结果，返回新增、删除和修改的行。
+// 两侧各自为一条只读查询（如同一查询在两个库上执行）或 store_query_result 保存的结果
+#[command]
+pub async fn diff_results(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    key_columns: Vec<String>,
+    left: DiffSource,
+    right: DiffSource,
+) -> Result<ResultDiff, String> {
+    if key_columns.is_empty() {
+        return Err("At least one key column is required".to_string());
+    }
+    let left = source_rows(&app_state, &db_state, connection_id, left).await?;
+    let right = source_rows(&app_state, &db_state, connection_id, right).await?;
+    diff_rows(left, right, key_columns)
+}
@@ -0,0 +1,381 @@
+use crate::alter_table::quote_identifier;
+use crate::db::{connection_flavor, DbState};
+use crate::fake_data::fake_value;
+use crate::models::{MaskingRule, ResultExportArgs};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::sql_classifier::{is_read_query, single_table_source, SqlFlavor};
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use sqlx::{Column, Row};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, State};
+use tokio::fs::File;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+#[derive(Debug, Serialize)]
+pub struct ResultExportSummary {
+    pub execution_id: String,
+    pub path: String,
+    pub rows: u64,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+// 按列应用脱敏规则。NULL 保持为 NULL
+struct Masker {
+    rules: HashMap<String, MaskingRule>,
+    salt: String,
+}
+
+impl Masker {
+    fn new(rules: HashMap<String, MaskingRule>, salt: Option<String>) -> Result<Self, String> {
+        for (column, rule) in &rules {
+            match rule.kind.as_str() {
+                "hash" | "redact" | "null" => {}
+                // 预先生成一次，尽早报告不支持的假数据类型
+                "fake" => {
+                    fake_value(rule.fake.as_deref().unwrap_or("word"), 0)
+                        .map_err(|e| format!("Masking rule for {}: {}", column, e))?;
+                }
+                other => {
+                    return Err(format!(
+                        "Unsupported masking rule for {}: {}",
+                        column, other
+                    ))
+                }
+            }
+        }
+        Ok(Self {
+            rules,
+            salt: salt.unwrap_or_default(),
+        })
+    }
+
+    // 结果中必须包含所有配置了规则的列，避免列名写错导致数据未脱敏就被导出
+    fn check_columns(&self, columns: &[String]) -> Result<(), String> {
+        match self.rules.keys().find(|name| !columns.contains(name)) {
+            Some(missing) => Err(format!("Masked column {} not found in result", missing)),
+            None => Ok(()),
+        }
+    }
+
+    fn digest(&self, text: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update(text.as_bytes());
+        hasher.finalize().into()
+    }
+
+    fn apply(&self, column: &str, value: Value) -> Result<Value, String> {
+        let Some(rule) = self.rules.get(column) else {
+            return Ok(value);
+        };
+        if value.is_null() {
+            return Ok(value);
+        }
+        let text = match value {
+            Value::String(text) => text,
+            other => other.to_string(),
+        };
+        let masked = match rule.kind.as_str() {
+            "hash" => Value::String(
+                self.digest(&text)
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect(),
+            ),
+            "redact" => {
+                let chars: Vec<char> = text.chars().collect();
+                let keep_start = rule.keep_start.unwrap_or(0);
+                let keep_end = rule.keep_end.unwrap_or(0);
+                // 保留的部分覆盖整个值时全部遮盖
+                let keep = keep_start + keep_end < chars.len();
+                Value::String(
+                    chars
+                        .iter()
+                        .enumerate()
+                        .map(|(i, c)| {
+                            match keep && (i < keep_start || i >= chars.len() - keep_end) {
+                                true => *c,
+                                false => '*',
+                            }
+                        })
+                        .collect(),
+                )
+            }
+            "fake" => {
+                let digest = self.digest(&text);
+                let seed = u64::from_le_bytes(digest[..8].try_into().unwrap_or_default());
+                fake_value(rule.fake.as_deref().unwrap_or("word"), seed)?
+            }
+            _ => Value::Null,
+        };
+        Ok(masked)
+    }
+}
+
+enum Format {
+    Csv,
+    Json,
+    Sql(String),
+}
+
+struct ExportWriter {
+    format: Format,
+    flavor: SqlFlavor,
+    file: BufWriter<File>,
+    columns: Vec<String>,
+    rows: u64,
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) || text.starts_with(' ') || text.ends_with(' ') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+pub fn sql_literal(value: &Value, flavor: SqlFlavor) -> String {
+    let text = match value {
+        Value::Null => return "NULL".to_string(),
+        Value::Bool(b) => return if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Number(n) => return n.to_string(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    match flavor {
+        // MySQL 默认把反斜杠当作转义符
+        SqlFlavor::MySql => format!("'{}'", text.replace('\\', "\\\\").replace('\'', "''")),
+        SqlFlavor::Sqlite => format!("'{}'", text.replace('\'', "''")),
+    }
+}
+
+impl ExportWriter {
+    async fn write(&mut self, text: &str) -> Result<(), String> {
+        self.file
+            .write_all(text.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write export file: {}", e))
+    }
+
+    async fn start(&mut self, columns: Vec<String>) -> Result<(), String> {
+        self.columns = columns;
+        match &self.format {
+            // 带 BOM，Excel 打开时按 UTF-8 识别
+            Format::Csv => {
+                let header: Vec<String> = self
+                    .columns
+                    .iter()
+                    .map(|c| csv_field(&Value::String(c.clone())))
+                    .collect();
+                self.write(&format!("\u{feff}{}\r\n", header.join(",")))
+                    .await
+            }
+            Format::Json => self.write("[\n").await,
+            Format::Sql(_) => Ok(()),
+        }
+    }
+
+    async fn write_row(&mut self, values: Vec<Value>) -> Result<(), String> {
+        let line = match &self.format {
+            Format::Csv => {
+                let fields: Vec<String> = values.iter().map(csv_field).collect();
+                format!("{}\r\n", fields.join(","))
+            }
+            // 手工拼对象以保持列顺序
+            Format::Json => {
+                let fields: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(&values)
+                    .map(|(column, value)| format!("{}: {}", Value::String(column.clone()), value))
+                    .collect();
+                let separator = if self.rows == 0 { "" } else { ",\n" };
+                format!("{}  {{{}}}", separator, fields.join(", "))
+            }
+            Format::Sql(table) => {
+                let columns: Vec<String> = self
+                    .columns
+                    .iter()
+                    .map(|c| quote_identifier(c, self.flavor))
+                    .collect();
+                let values: Vec<String> =
+                    values.iter().map(|v| sql_literal(v, self.flavor)).collect();
+                format!(
+                    "INSERT INTO {} ({}) VALUES ({});\n",
+                    table,
+                    columns.join(", "),
+                    values.join(", ")
+                )
+            }
+        };
+        self.rows += 1;
+        self.write(&line).await
+    }
+
+    async fn finish(mut self) -> Result<(), String> {
+        if let Format::Json = self.format {
+            let tail = if self.rows == 0 { "[]\n" } else { "\n]\n" };
+            self.write(tail).await?;
+        }
+        self.file
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write export file: {}", e))
+    }
+}
+
+// 导出一行：第一行时写表头并检查脱敏列，之后按表头的列序取值
+async fn export_row(
+    writer: &mut ExportWriter,
+    masker: &Masker,
+    columns: &[String],
+    mut row: Map<String, Value>,
+) -> Result<(), String> {
+    if writer.rows == 0 && writer.columns.is_empty() {
+        masker.check_columns(columns)?;
+        writer.start(columns.to_vec()).await?;
+    }
+    let values = columns
+        .iter()
+        .map(|column| masker.apply(column, row.remove(column).unwrap_or(Value::Null)))
+        .collect::<Result<Vec<_>, _>>()?;
+    writer.write_row(values).await
+}
+
+// 流式执行只读查询并写入 CSV / JSON / SQL INSERT 文件，不把整个结果读入内存。
+// masking 中的列在写出前脱敏（哈希、遮盖、替换为假数据或置空），配置了规则但结果中不存在的列
+// 会报错。通过 cancel_query(execution_id) 取消，已写出的部分保留在文件中
+#[command]
+pub async fn export_result(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: ResultExportArgs,
+    execution_id: Option<String>,
+) -> Result<ResultExportSummary, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Exporting results").await?;
+    if !is_read_query(&args.sql, flavor) {
+        return Err("Only read-only queries can be exported".to_string());
+    }
+    let format = match args.format.as_str() {
+        "csv" => Format::Csv,
+        "json" => Format::Json,
+        "sql" => {
+            let table = match &args.table_name {
+                Some(table) => table.clone(),
+                None => single_table_source(&args.sql, flavor)
+                    .map(|source| source.table)
+                    .ok_or("table_name is required to export this query as SQL")?,
+            };
+            Format::Sql(
+                table
+                    .split('.')
+                    .map(|part| quote_identifier(part.trim(), flavor))
+                    .collect::<Vec<_>>()
+                    .join("."),
+            )
+        }
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+    let masker = Masker::new(args.masking.clone().unwrap_or_default(), args.salt.clone())?;
+    let file = File::create(&args.path)
+        .await
+        .map_err(|e| format!("Failed to create export file: {}", e))?;
+    let mut writer = ExportWriter {
+        format,
+        flavor,
+        file: BufWriter::new(file),
+        columns: Vec::new(),
+        rows: 0,
+    };
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(execution_id.clone(), cancel.clone());
+
+    let started = Instant::now();
+    let mut columns: Vec<String> = Vec::new();
+    let outcome: Result<(), String> = async {
+        match flavor {
+            SqlFlavor::MySql => {
+                let mut conn = mysql_manager::acquire_connection(
+                    &app_state,
+                    &db_state,
+                    connection_id,
+                    args.db_name.clone(),
+                )
+                .await?;
+                let timestamp_display =
+                    mysql_manager::timestamp_display_of(&app_state, connection_id).await;
+                let mut stream = sqlx::query(&args.sql).fetch(&mut *conn);
+                while let Some(row) = stream
+                    .try_next()
+                    .await
+                    .map_err(|e| format!("Query execution failed: {}", e))?
+                {
+                    if columns.is_empty() {
+                        columns = row.columns().iter().map(|c| c.name().to_string()).collect();
+                    }
+                    let row = mysql_manager::row_to_json(&row, timestamp_display);
+                    export_row(&mut writer, &masker, &columns, row).await?;
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                }
+            }
+            SqlFlavor::Sqlite => {
+                let pool = sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id)
+                    .await?;
+                let mut stream = sqlx::query(&args.sql).fetch(&pool);
+                while let Some(row) = stream
+                    .try_next()
+                    .await
+                    .map_err(|e| format!("Query execution failed: {}", e))?
+                {
+                    if columns.is_empty() {
+                        columns = row.columns().iter().map(|c| c.name().to_string()).collect();
+                    }
+                    let row = sqlite_manager::row_to_json(&row);
+                    export_row(&mut writer, &masker, &columns, row).await?;
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    .await;
+    app_state.cancel_flags.lock().await.remove(&execution_id);
+    let rows = writer.rows;
+    let finished = writer.finish().await;
+    outcome?;
+    finished?;
+
+    Ok(ResultExportSummary {
+        execution_id,
+        path: args.path,
+        rows,
+        cancelled: cancel.load(Ordering::Relaxed),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
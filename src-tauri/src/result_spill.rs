@@ -0,0 +1,362 @@
+use crate::models::{ResultPage, SpilledResult};
+use crate::state::AppState;
+use serde_json::{Map, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sqlx::SqlitePool;
+use std::fs::OpenOptions;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{command, Manager, State};
+
+// 单个结果集超过任一阈值时改为写入临时库，前端通过 fetch_result_page 分页读取
+const SPILL_MAX_ROWS: usize = 50_000;
+const SPILL_MAX_BYTES: usize = 64 * 1024 * 1024;
+// 落盘后随结果一起返回的首页行数
+const SPILL_FIRST_PAGE_ROWS: usize = 1_000;
+// 每批写入临时库的行数
+const SPILL_BATCH_ROWS: usize = 1_000;
+const SPILL_DEFAULT_PAGE_SIZE: usize = 1_000;
+
+static NEXT_RESULT_ID: AtomicU64 = AtomicU64::new(1);
+
+const SPILL_FILE_PREFIX: &str = "result-spill-";
+
+// 临时文件放在应用缓存目录下，取不到时用系统临时目录
+fn spill_dir(app_state: &AppState) -> PathBuf {
+    app_state
+        .app_handle
+        .as_ref()
+        .and_then(|app| app.path().app_cache_dir().ok())
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+fn spill_path(app_state: &AppState) -> PathBuf {
+    spill_dir(app_state).join(format!("{}{}.db", SPILL_FILE_PREFIX, std::process::id()))
+}
+
+// 按进程号命名的临时文件，只有当前用户可读写
+fn create_spill_file(app_state: &AppState) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(spill_dir(app_state))
+        .map_err(|e| format!("Failed to create result spill file: {}", e))?;
+    let path = spill_path(app_state);
+    // 进程号被复用时留下的旧文件
+    let _ = std::fs::remove_file(&path);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options
+        .open(&path)
+        .map_err(|e| format!("Failed to create result spill file: {}", e))?;
+    Ok(path)
+}
+
+// 启动时删除之前的进程（崩溃或被强制结束）留下的临时文件
+pub fn remove_stale_spill_files(app_state: &AppState) {
+    let Ok(entries) = std::fs::read_dir(spill_dir(app_state)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(SPILL_FILE_PREFIX) && name.ends_with(".db") {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+// 退出时关闭临时库并删除文件，落盘的结果不保留到进程之外
+pub async fn remove_spill_file(app_state: &AppState) {
+    let Some(pool) = app_state.spill_pool.lock().await.take() else {
+        return;
+    };
+    app_state.spilled_results.lock().await.clear();
+    pool.close().await;
+    let _ = std::fs::remove_file(spill_path(app_state));
+}
+
+// 进程内首次落盘时创建临时库。只用一个连接，各结果的写入依次进行
+async fn spill_pool(app_state: &AppState) -> Result<SqlitePool, String> {
+    let mut spill_pool = app_state.spill_pool.lock().await;
+    if let Some(pool) = spill_pool.as_ref() {
+        return Ok(pool.clone());
+    }
+    let path = create_spill_file(app_state)?;
+    let options = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Off)
+        .synchronous(SqliteSynchronous::Off);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to create result spill file: {}", e))?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS spilled_rows ( \
+         result_id TEXT NOT NULL, row_index INTEGER NOT NULL, row TEXT NOT NULL, \
+         PRIMARY KEY (result_id, row_index)) WITHOUT ROWID",
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to create result spill file: {}", e))?;
+    *spill_pool = Some(pool.clone());
+    Ok(pool)
+}
+
+struct Spill {
+    pool: SqlitePool,
+    result_id: String,
+    total_rows: u64,
+    pending: Vec<String>,
+}
+
+impl Spill {
+    async fn flush(&mut self) -> Result<(), String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to write result spill file: {}", e))?;
+        for row in self.pending.drain(..) {
+            sqlx::query("INSERT INTO spilled_rows (result_id, row_index, row) VALUES (?, ?, ?)")
+                .bind(&self.result_id)
+                .bind(self.total_rows as i64)
+                .bind(row)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to write result spill file: {}", e))?;
+            self.total_rows += 1;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to write result spill file: {}", e))
+    }
+
+    async fn push(&mut self, row: &Map<String, Value>) -> Result<(), String> {
+        self.pending
+            .push(serde_json::to_string(row).map_err(|e| e.to_string())?);
+        if self.pending.len() >= SPILL_BATCH_ROWS {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+// 逐行收集结果集：未超过阈值时全部留在内存，超过后全部写入临时库，内存中只保留首页
+pub struct RowSpooler<'a> {
+    app_state: &'a AppState,
+    connection_id: i64,
+    rows: Vec<Map<String, Value>>,
+    bytes: usize,
+    spill: Option<Spill>,
+    // 不论大小都写入临时库（store_query_result 保存结果供之后对比）
+    always_spill: bool,
+}
+
+impl<'a> RowSpooler<'a> {
+    pub fn new(app_state: &'a AppState, connection_id: i64) -> Self {
+        Self {
+            app_state,
+            connection_id,
+            rows: Vec::new(),
+            bytes: 0,
+            spill: None,
+            always_spill: false,
+        }
+    }
+
+    pub fn always_spill(mut self) -> Self {
+        self.always_spill = true;
+        self
+    }
+
+    pub async fn push(&mut self, row: Map<String, Value>) -> Result<(), String> {
+        if let Some(spill) = self.spill.as_mut() {
+            return spill.push(&row).await;
+        }
+        self.bytes += serde_json::to_string(&row).map_or(0, |json| json.len());
+        self.rows.push(row);
+        if self.always_spill || self.rows.len() > SPILL_MAX_ROWS || self.bytes > SPILL_MAX_BYTES {
+            self.start_spill().await?;
+        }
+        Ok(())
+    }
+
+    async fn start_spill(&mut self) -> Result<(), String> {
+        let pool = spill_pool(self.app_state).await?;
+        let result_id = format!("result-{}", NEXT_RESULT_ID.fetch_add(1, Ordering::Relaxed));
+        self.app_state
+            .spilled_results
+            .lock()
+            .await
+            .insert(result_id.clone(), self.connection_id);
+        let mut spill = Spill {
+            pool,
+            result_id,
+            total_rows: 0,
+            pending: Vec::new(),
+        };
+        for row in &self.rows {
+            spill.push(row).await?;
+        }
+        self.rows.truncate(SPILL_FIRST_PAGE_ROWS);
+        self.spill = Some(spill);
+        Ok(())
+    }
+
+    // 返回内存中的行（落盘时为首页）和落盘信息
+    pub async fn finish(
+        mut self,
+    ) -> Result<(Vec<Map<String, Value>>, Option<SpilledResult>), String> {
+        if self.always_spill && self.spill.is_none() {
+            self.start_spill().await?;
+        }
+        let Some(mut spill) = self.spill.take() else {
+            return Ok((self.rows, None));
+        };
+        spill.flush().await?;
+        Ok((
+            self.rows,
+            Some(SpilledResult {
+                result_id: spill.result_id,
+                total_rows: spill.total_rows,
+            }),
+        ))
+    }
+}
+
+async fn delete_spilled_rows(app_state: &AppState, result_ids: Vec<String>) {
+    let Some(pool) = app_state.spill_pool.lock().await.clone() else {
+        return;
+    };
+    for result_id in result_ids {
+        let _ = sqlx::query("DELETE FROM spilled_rows WHERE result_id = ?")
+            .bind(result_id)
+            .execute(&pool)
+            .await;
+    }
+}
+
+// 连接关闭时释放该连接的落盘结果
+pub async fn release_connection_results(app_state: &AppState, connection_id: i64) {
+    let result_ids: Vec<String> = {
+        let mut spilled = app_state.spilled_results.lock().await;
+        let ids: Vec<String> = spilled
+            .iter()
+            .filter(|(_, id)| **id == connection_id)
+            .map(|(result_id, _)| result_id.clone())
+            .collect();
+        for result_id in &ids {
+            spilled.remove(result_id);
+        }
+        ids
+    };
+    delete_spilled_rows(app_state, result_ids).await;
+}
+
+// 断开全部连接时释放全部落盘结果
+pub async fn release_all_results(app_state: &AppState) {
+    let result_ids: Vec<String> = app_state
+        .spilled_results
+        .lock()
+        .await
+        .drain()
+        .map(|(result_id, _)| result_id)
+        .collect();
+    delete_spilled_rows(app_state, result_ids).await;
+}
+
+// 读取落盘结果的全部行，超过 max_rows 行时报错
+pub async fn spilled_rows(
+    app_state: &AppState,
+    result_id: &str,
+    max_rows: usize,
+) -> Result<Vec<Map<String, Value>>, String> {
+    if !app_state
+        .spilled_results
+        .lock()
+        .await
+        .contains_key(result_id)
+    {
+        return Err("Result not found or already released".to_string());
+    }
+    let pool = spill_pool(app_state).await?;
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT row FROM spilled_rows WHERE result_id = ? ORDER BY row_index LIMIT ?",
+    )
+    .bind(result_id)
+    .bind(max_rows as i64 + 1)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read result: {}", e))?;
+    if rows.len() > max_rows {
+        return Err(format!("Result exceeds {} rows", max_rows));
+    }
+    rows.iter()
+        .map(|row| serde_json::from_str(row).map_err(|e| e.to_string()))
+        .collect()
+}
+
+// 读取落盘结果的一页，offset 从 0 开始
+#[command]
+pub async fn fetch_result_page(
+    app_state: State<'_, AppState>,
+    result_id: String,
+    offset: u64,
+    limit: Option<usize>,
+) -> Result<ResultPage, String> {
+    if !app_state
+        .spilled_results
+        .lock()
+        .await
+        .contains_key(&result_id)
+    {
+        return Err("Result not found or already released".to_string());
+    }
+    let limit = limit.unwrap_or(SPILL_DEFAULT_PAGE_SIZE).max(1);
+    let pool = spill_pool(&app_state).await?;
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT row FROM spilled_rows WHERE result_id = ? AND row_index >= ? \
+         ORDER BY row_index LIMIT ?",
+    )
+    .bind(&result_id)
+    .bind(offset as i64)
+    .bind(limit as i64 + 1)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read result page: {}", e))?;
+
+    let done = rows.len() <= limit;
+    let rows = rows
+        .iter()
+        .take(limit)
+        .map(|row| serde_json::from_str(row).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ResultPage { rows, done })
+}
+
+// 释放落盘结果（结果页关闭时调用）；不指定 result_id 时释放全部
+#[command]
+pub async fn release_result(
+    app_state: State<'_, AppState>,
+    result_id: Option<String>,
+) -> Result<(), String> {
+    match result_id {
+        Some(result_id) => {
+            if app_state
+                .spilled_results
+                .lock()
+                .await
+                .remove(&result_id)
+                .is_some()
+            {
+                delete_spilled_rows(&app_state, vec![result_id]).await;
+            }
+        }
+        None => release_all_results(&app_state).await,
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use crate::db::DbState;
+use crate::models::Connection;
+use crate::state::AppState;
+use rocksdb::{Direction, IteratorMode, Options, DB};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{command, State};
+
+const ROCKSDB_DEFAULT_PAGE_SIZE: usize = 100;
+const ROCKSDB_MAX_PAGE_SIZE: usize = 1000;
+// 列表页只返回值的前 4KB，完整值通过 get_rocksdb_value 获取
+const ROCKSDB_VALUE_PREVIEW_BYTES: usize = 4096;
+
+#[derive(Clone)]
+pub struct RocksDbStore {
+    db: Arc<DB>,
+    column_families: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RocksDbEntry {
+    pub key_hex: String,
+    pub key_utf8: Option<String>,
+    pub value_hex: String,
+    pub value_utf8: Option<String>,
+    pub value_size: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RocksDbScanResult {
+    pub entries: Vec<RocksDbEntry>,
+    // 下一页起点（最后一个 key 的 hex），为空表示已经到底
+    pub next_cursor: Option<String>,
+}
+
+// 辅助函数：以只读模式打开 RocksDB/LevelDB 目录
+async fn get_or_open_db(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<RocksDbStore, String> {
+    // 1. 先检查缓存
+    {
+        let stores = app_state.rocksdb_stores.lock().await;
+        if let Some(store) = stores.get(&connection_id) {
+            return Ok(store.clone());
+        }
+    }
+
+    // 2. 从 SQLite 读取连接配置
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+
+    if connection.db_type != "rocksdb" && connection.db_type != "leveldb" {
+        return Err("Only RocksDB/LevelDB is supported for this operation".to_string());
+    }
+
+    // 3. connection.database 存储数据目录路径
+    let db_path = connection.database.ok_or("Database path is required")?;
+
+    // 4. 只读打开所有列族（LevelDB 目录没有列族信息，退回到 default）
+    let store = tauri::async_runtime::spawn_blocking(move || {
+        let opts = Options::default();
+        let column_families =
+            DB::list_cf(&opts, &db_path).unwrap_or_else(|_| vec!["default".to_string()]);
+        DB::open_cf_for_read_only(&opts, &db_path, &column_families, false).map(|db| {
+            RocksDbStore {
+                db: Arc::new(db),
+                column_families,
+            }
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to open RocksDB directory: {}", e))?;
+
+    // 5. 存入缓存
+    let mut stores = app_state.rocksdb_stores.lock().await;
+    stores.insert(connection_id, store.clone());
+
+    Ok(store)
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.trim();
+    let hex = hex
+        .strip_prefix("0x")
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return Err("Hex string must have an even number of digits".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| format!("Invalid hex string: {}", hex))
+        })
+        .collect()
+}
+
+// 辅助函数：按 key_format（utf8 / hex）把前端传入的 key 转成字节
+fn parse_key(key: &str, key_format: Option<&str>) -> Result<Vec<u8>, String> {
+    match key_format.unwrap_or("utf8") {
+        "hex" => hex_to_bytes(key),
+        "utf8" => Ok(key.as_bytes().to_vec()),
+        other => Err(format!("Unsupported key format: {}", other)),
+    }
+}
+
+fn to_entry(key: &[u8], value: &[u8], preview_limit: Option<usize>) -> RocksDbEntry {
+    let shown = match preview_limit {
+        Some(limit) if value.len() > limit => &value[..limit],
+        _ => value,
+    };
+    RocksDbEntry {
+        key_hex: bytes_to_hex(key),
+        key_utf8: std::str::from_utf8(key).ok().map(str::to_string),
+        value_hex: bytes_to_hex(shown),
+        value_utf8: std::str::from_utf8(shown).ok().map(str::to_string),
+        value_size: value.len(),
+        truncated: shown.len() < value.len(),
+    }
+}
+
+#[command]
+pub async fn list_rocksdb_column_families(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<String>, String> {
+    let store = get_or_open_db(&app_state, &db_state, connection_id).await?;
+    Ok(store.column_families)
+}
+
+#[command]
+pub async fn scan_rocksdb_keys(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    column_family: Option<String>,
+    prefix: Option<String>,
+    prefix_format: Option<String>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+) -> Result<RocksDbScanResult, String> {
+    let db = get_or_open_db(&app_state, &db_state, connection_id).await?.db;
+
+    let prefix = match prefix.as_deref().filter(|p| !p.is_empty()) {
+        Some(p) => parse_key(p, prefix_format.as_deref())?,
+        None => Vec::new(),
+    };
+    let cursor = match cursor.as_deref().filter(|c| !c.is_empty()) {
+        Some(c) => Some(hex_to_bytes(c)?),
+        None => None,
+    };
+    let limit = limit
+        .unwrap_or(ROCKSDB_DEFAULT_PAGE_SIZE)
+        .clamp(1, ROCKSDB_MAX_PAGE_SIZE);
+    let cf_name = column_family.unwrap_or_else(|| "default".to_string());
+
+    // RocksDB 为同步 API，放到阻塞线程池中执行
+    tauri::async_runtime::spawn_blocking(move || {
+        let cf = db
+            .cf_handle(&cf_name)
+            .ok_or_else(|| format!("Column family not found: {}", cf_name))?;
+
+        // 有游标时从游标处继续，否则从前缀起点开始
+        let start = cursor.clone().unwrap_or_else(|| prefix.clone());
+        let iter = db.iterator_cf(cf, IteratorMode::From(&start, Direction::Forward));
+
+        let mut entries = Vec::new();
+        let mut has_more = false;
+        for item in iter {
+            let (key, value) = item.map_err(|e| format!("Failed to iterate keys: {}", e))?;
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            if cursor.as_deref() == Some(&key[..]) {
+                continue;
+            }
+            if entries.len() >= limit {
+                has_more = true;
+                break;
+            }
+            entries.push(to_entry(&key, &value, Some(ROCKSDB_VALUE_PREVIEW_BYTES)));
+        }
+
+        let next_cursor = if has_more {
+            entries.last().map(|e| e.key_hex.clone())
+        } else {
+            None
+        };
+        Ok(RocksDbScanResult {
+            entries,
+            next_cursor,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[command]
+pub async fn get_rocksdb_value(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    column_family: Option<String>,
+    key: String,
+    key_format: Option<String>,
+) -> Result<Option<RocksDbEntry>, String> {
+    let db = get_or_open_db(&app_state, &db_state, connection_id).await?.db;
+    let key = parse_key(&key, key_format.as_deref())?;
+    let cf_name = column_family.unwrap_or_else(|| "default".to_string());
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let cf = db
+            .cf_handle(&cf_name)
+            .ok_or_else(|| format!("Column family not found: {}", cf_name))?;
+        let value = db
+            .get_cf(cf, &key)
+            .map_err(|e| format!("Failed to read key: {}", e))?;
+        Ok(value.map(|v| to_entry(&key, &v, None)))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
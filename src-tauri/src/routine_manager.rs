@@ -0,0 +1,372 @@
+use crate::alter_table::qualified_name;
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::ensure_sql_allowed;
+use crate::models::{
+    ExecuteRoutineArgs, RoutineDetail, RoutineInfo, RoutineParameter, RoutineResult, SqlResult,
+};
+use crate::mysql_manager;
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::query_queue::{acquire_query_slot, finish_running_query, new_execution_id};
+use crate::result_cache::invalidate_results;
+use crate::result_spill::RowSpooler;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use futures_util::TryStreamExt;
+use serde_json::{Map, Value};
+use sqlx::mysql::MySqlRow;
+use sqlx::{Either, Executor, MySqlConnection, Row};
+use std::time::Instant;
+use tauri::{command, State};
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 存储过程和函数只有 MySQL 系支持，并按探测到的服务端能力判断（TiDB 不支持）
+async fn ensure_routines_supported(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<(), String> {
+    if matches!(
+        connection_flavor(db_state, connection_id, "Stored routines").await?,
+        SqlFlavor::Sqlite
+    ) {
+        return Err("Stored routines are not supported for sqlite".to_string());
+    }
+    let profile = mysql_manager::get_server_profile_for(app_state, db_state, connection_id).await?;
+    match profile.supports_routines {
+        true => Ok(()),
+        false => Err("Stored routines are not supported by this server".to_string()),
+    }
+}
+
+fn routine_type(value: &str) -> Result<&'static str, String> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "PROCEDURE" => Ok("PROCEDURE"),
+        "FUNCTION" => Ok("FUNCTION"),
+        _ => Err(format!("Unknown routine type: {}", value)),
+    }
+}
+
+async fn fetch_routines(
+    conn: &mut MySqlConnection,
+    db_name: Option<&str>,
+    filter: Option<(&str, &str)>,
+) -> Result<Vec<(RoutineInfo, Option<String>)>, String> {
+    let (name, kind) = filter.unzip();
+    let rows = sqlx::query(
+        "SELECT CAST(ROUTINE_NAME AS CHAR), CAST(ROUTINE_TYPE AS CHAR), \
+         CAST(DTD_IDENTIFIER AS CHAR), CAST(IS_DETERMINISTIC AS CHAR), \
+         CAST(SQL_DATA_ACCESS AS CHAR), CAST(SECURITY_TYPE AS CHAR), \
+         CAST(ROUTINE_COMMENT AS CHAR), CAST(CREATED AS CHAR), CAST(LAST_ALTERED AS CHAR), \
+         CAST(ROUTINE_DEFINITION AS CHAR) \
+         FROM information_schema.ROUTINES \
+         WHERE ROUTINE_SCHEMA = COALESCE(?, DATABASE()) \
+         AND (? IS NULL OR ROUTINE_NAME = ?) AND (? IS NULL OR ROUTINE_TYPE = ?) \
+         ORDER BY ROUTINE_TYPE, ROUTINE_NAME",
+    )
+    .bind(db_name)
+    .bind(name)
+    .bind(name)
+    .bind(kind)
+    .bind(kind)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch routines: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let info = RoutineInfo {
+                name: text(row, 0).unwrap_or_default(),
+                routine_type: text(row, 1).unwrap_or_default(),
+                return_type: text(row, 2).filter(|t| !t.is_empty()),
+                deterministic: text(row, 3).as_deref() == Some("YES"),
+                data_access: text(row, 4),
+                security_type: text(row, 5),
+                comment: text(row, 6).filter(|c| !c.is_empty()),
+                created: text(row, 7),
+                modified: text(row, 8),
+            };
+            (info, text(row, 9))
+        })
+        .collect())
+}
+
+async fn fetch_parameters(
+    conn: &mut MySqlConnection,
+    db_name: Option<&str>,
+    name: &str,
+    kind: &str,
+) -> Result<Vec<RoutineParameter>, String> {
+    // ORDINAL_POSITION 为 0 的是函数的返回值
+    let rows = sqlx::query(
+        "SELECT CAST(ORDINAL_POSITION AS SIGNED), CAST(PARAMETER_NAME AS CHAR), \
+         CAST(PARAMETER_MODE AS CHAR), CAST(DTD_IDENTIFIER AS CHAR) \
+         FROM information_schema.PARAMETERS \
+         WHERE SPECIFIC_SCHEMA = COALESCE(?, DATABASE()) AND SPECIFIC_NAME = ? \
+         AND ROUTINE_TYPE = ? AND ORDINAL_POSITION > 0 \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(db_name)
+    .bind(name)
+    .bind(kind)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch routine parameters: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| RoutineParameter {
+            position: row.try_get_unchecked::<i64, _>(0).unwrap_or_default(),
+            name: text(row, 1).unwrap_or_default(),
+            mode: text(row, 2).unwrap_or_else(|| "IN".to_string()),
+            data_type: text(row, 3).unwrap_or_default(),
+        })
+        .collect())
+}
+
+// 列出库中的存储过程和函数
+#[command]
+pub async fn list_routines(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Result<Vec<RoutineInfo>, String> {
+    ensure_routines_supported(&app_state, &db_state, connection_id).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, db_name.clone())
+            .await?;
+    let routines = fetch_routines(&mut conn, db_name.as_deref(), None).await?;
+    Ok(routines.into_iter().map(|(info, _)| info).collect())
+}
+
+// 返回存储过程 / 函数的参数、函数体和完整的建立语句
+#[command]
+pub async fn get_routine(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    routine_type: String,
+    db_name: Option<String>,
+) -> Result<RoutineDetail, String> {
+    ensure_routines_supported(&app_state, &db_state, connection_id).await?;
+    let kind = self::routine_type(&routine_type)?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, db_name.clone())
+            .await?;
+    let (info, body) = fetch_routines(&mut conn, db_name.as_deref(), Some((&name, kind)))
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("{} {} not found", kind.to_lowercase(), name))?;
+    let parameters = fetch_parameters(&mut conn, db_name.as_deref(), &name, kind).await?;
+    // 第三列为 Create Procedure / Create Function，没有权限时为 NULL
+    let create_sql = sqlx::query(&format!(
+        "SHOW CREATE {} {}",
+        kind,
+        qualified_name(db_name.as_deref(), &name, SqlFlavor::MySql)
+    ))
+    .fetch_optional(&mut *conn)
+    .await
+    .ok()
+    .flatten()
+    .and_then(|row| text(&row, 2));
+    Ok(RoutineDetail {
+        info,
+        parameters,
+        body,
+        create_sql,
+    })
+}
+
+// 按参数名取实参；IN / INOUT 参数缺少值时报错
+fn argument<'a>(
+    args: &'a ExecuteRoutineArgs,
+    parameter: &RoutineParameter,
+) -> Result<&'a Value, String> {
+    args.arguments
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&parameter.name))
+        .map(|(_, value)| value)
+        .ok_or_else(|| format!("Missing value for parameter {}", parameter.name))
+}
+
+// 执行存储过程或函数。存储过程的 OUT / INOUT 参数通过会话变量 @xdb_arg_{位置} 传递，
+// 执行后在同一连接上读回；返回全部结果集
+#[command]
+pub async fn execute_routine(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: ExecuteRoutineArgs,
+    execution_id: Option<String>,
+) -> Result<RoutineResult, String> {
+    ensure_routines_supported(&app_state, &db_state, connection_id).await?;
+    let kind = routine_type(&args.routine_type)?;
+    let mut conn = mysql_manager::acquire_connection(
+        &app_state,
+        &db_state,
+        connection_id,
+        args.db_name.clone(),
+    )
+    .await?;
+    let parameters = fetch_parameters(&mut conn, args.db_name.as_deref(), &args.name, kind).await?;
+    let routine = qualified_name(args.db_name.as_deref(), &args.name, SqlFlavor::MySql);
+
+    // (占位符, 绑定值)：IN 参数直接绑定，OUT / INOUT 参数使用会话变量
+    let mut placeholders = Vec::with_capacity(parameters.len());
+    let mut bound = Vec::new();
+    let mut variables = Vec::new();
+    for parameter in &parameters {
+        let variable = format!("@xdb_arg_{}", parameter.position);
+        match parameter.mode.as_str() {
+            "OUT" => {
+                sqlx::query(&format!("SET {} = NULL", variable))
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        format!("Failed to prepare parameter {}: {}", parameter.name, e)
+                    })?;
+                placeholders.push(variable.clone());
+                variables.push((parameter.name.clone(), variable));
+            }
+            "INOUT" => {
+                let value = argument(&args, parameter)?;
+                mysql_manager::bind_json_value(
+                    sqlx::query(&format!("SET {} = ?", variable)),
+                    value,
+                )
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to prepare parameter {}: {}", parameter.name, e))?;
+                placeholders.push(variable.clone());
+                variables.push((parameter.name.clone(), variable));
+            }
+            _ => {
+                placeholders.push("?".to_string());
+                bound.push(argument(&args, parameter)?.clone());
+            }
+        }
+    }
+    let statement = match kind {
+        "FUNCTION" => format!("SELECT {}({}) AS result", routine, placeholders.join(", ")),
+        _ => format!("CALL {}({})", routine, placeholders.join(", ")),
+    };
+    // 只读连接上不允许 CALL（存储过程可能写入）；函数按 SELECT 判断
+    ensure_sql_allowed(&db_state, connection_id, &statement, None, false).await?;
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    mysql_manager::register_mysql_query(
+        &app_state,
+        &mut conn,
+        &execution_id,
+        connection_id,
+        args.db_name.clone(),
+    )
+    .await;
+    let timestamp_display = mysql_manager::timestamp_display_of(&app_state, connection_id).await;
+
+    let started = Instant::now();
+    let query = bound
+        .iter()
+        .fold(sqlx::query(&statement), mysql_manager::bind_json_value);
+    let steps: Result<Vec<_>, _> = (&mut *conn).fetch_many(query).try_collect().await;
+    finish_running_query(&app_state, &execution_id).await;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let steps = steps.map_err(|e| format!("Routine execution failed: {}", e));
+    let outcome = HistoryOutcome::of(&steps, |steps| {
+        HistoryOutcome::Rows(steps.iter().filter(|step| step.is_right()).count())
+    });
+    record_history(&db_state, connection_id, &statement, elapsed, outcome).await;
+    let steps = steps?;
+    if kind == "PROCEDURE" {
+        invalidate_results(&app_state, connection_id).await;
+    }
+    let mut warnings = mysql_manager::fetch_warnings(&mut conn).await;
+
+    // 与 execute_sql 相同：每个结果集之后跟一个 QueryResult，CALL 最后还有一个状态结果
+    let mut result_sets: Vec<(Vec<MySqlRow>, u64)> = Vec::new();
+    let mut current = Vec::new();
+    for step in steps {
+        match step {
+            Either::Left(done) => {
+                result_sets.push((std::mem::take(&mut current), done.rows_affected()))
+            }
+            Either::Right(row) => current.push(row),
+        }
+    }
+    if !current.is_empty() {
+        result_sets.push((current, 0));
+    }
+
+    let mut return_value = None;
+    let mut results = Vec::new();
+    for (rows, affected_rows) in result_sets {
+        if kind == "FUNCTION" {
+            return_value = rows.first().and_then(|row| {
+                mysql_manager::row_to_json(row, timestamp_display).remove("result")
+            });
+            continue;
+        }
+        // 只有状态、没有列的结果（CALL 结尾或过程中的写语句）不作为结果集返回
+        let Some(first_row) = rows.first() else {
+            continue;
+        };
+        let columns = first_row
+            .columns()
+            .iter()
+            .map(mysql_manager::column_info)
+            .collect();
+        let mut spooler = RowSpooler::new(&app_state, connection_id);
+        for row in &rows {
+            spooler
+                .push(mysql_manager::row_to_json(row, timestamp_display))
+                .await?;
+        }
+        let (rows, spilled) = spooler.finish().await?;
+        results.push(SqlResult {
+            columns,
+            rows,
+            affected_rows,
+            duration_ms: elapsed.as_millis() as u64,
+            warnings: std::mem::take(&mut warnings),
+            last_insert_id: None,
+            applied_limit: None,
+            from_cache: false,
+            spilled,
+        });
+    }
+
+    let mut out_values = Map::new();
+    if !variables.is_empty() {
+        let select = variables
+            .iter()
+            .map(|(_, variable)| variable.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let row = sqlx::query(&format!("SELECT {}", select))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read OUT parameters: {}", e))?;
+        let values = mysql_manager::row_to_json(&row, timestamp_display);
+        for (name, variable) in variables {
+            let value = values.get(&variable).cloned().unwrap_or(Value::Null);
+            out_values.insert(name, value);
+        }
+    }
+
+    Ok(RoutineResult {
+        statement,
+        result_sets: results,
+        out_values,
+        return_value,
+        duration_ms: elapsed.as_millis() as u64,
+    })
+}
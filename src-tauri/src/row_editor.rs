@@ -0,0 +1,483 @@
+use crate::alter_table::quote_identifier;
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::{ensure_sql_allowed, ensure_writable};
+use crate::models::RowEditResult;
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::result_cache::invalidate_results;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::{Map, Value};
+use sqlx::pool::PoolConnection;
+use sqlx::{Acquire, MySql, Row, Sqlite};
+use std::time::Instant;
+use tauri::{command, State};
+
+#[derive(Clone, Copy, PartialEq)]
+enum RowEdit {
+    Insert,
+    Update,
+    Delete,
+}
+
+// 表中的一列：(列名, 是否主键)
+type TableColumn = (String, bool);
+
+// 生成的语句。key_sql 为在事务中先锁定并统计目标行的语句，insert 没有
+struct EditStatement {
+    sql: String,
+    binds: Vec<Value>,
+    key_sql: Option<String>,
+    key_binds: Vec<Value>,
+}
+
+fn resolve_column<'a>(columns: &'a [TableColumn], name: &str) -> Result<&'a TableColumn, String> {
+    columns
+        .iter()
+        .find(|(column, _)| column == name)
+        .or_else(|| {
+            columns
+                .iter()
+                .find(|(column, _)| column.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| format!("Unknown column: {}", name))
+}
+
+// 主键条件必须恰好覆盖表的全部主键列，保证只定位到一行；
+// 没有主键的 SQLite 表可用 rowid 定位
+fn key_condition(
+    columns: &[TableColumn],
+    key: &Map<String, Value>,
+    flavor: SqlFlavor,
+) -> Result<(String, Vec<Value>), String> {
+    let primary_key: Vec<&String> = columns
+        .iter()
+        .filter(|(_, pk)| *pk)
+        .map(|(name, _)| name)
+        .collect();
+    if key.is_empty() {
+        return Err("Primary key values are required".to_string());
+    }
+    if key.values().any(Value::is_null) {
+        return Err("Primary key values cannot be NULL".to_string());
+    }
+    if primary_key.is_empty() {
+        return match (flavor, key.get("rowid")) {
+            (SqlFlavor::Sqlite, Some(rowid)) if key.len() == 1 => {
+                Ok(("rowid = ?".to_string(), vec![rowid.clone()]))
+            }
+            _ => Err("Table has no primary key; rows cannot be edited individually".to_string()),
+        };
+    }
+
+    let mut conditions = Vec::with_capacity(primary_key.len());
+    let mut binds = Vec::with_capacity(primary_key.len());
+    for column in &primary_key {
+        let value = key
+            .iter()
+            .find(|(name, _)| resolve_column(columns, name).is_ok_and(|(c, _)| c == *column))
+            .map(|(_, value)| value)
+            .ok_or_else(|| format!("Missing primary key column: {}", column))?;
+        conditions.push(format!("{} = ?", quote_identifier(column, flavor)));
+        binds.push(value.clone());
+    }
+    if key.len() != primary_key.len() {
+        return Err("Key must contain only the primary key columns".to_string());
+    }
+    Ok((conditions.join(" AND "), binds))
+}
+
+fn build_statement(
+    edit: RowEdit,
+    table_ref: &str,
+    columns: &[TableColumn],
+    key: &Map<String, Value>,
+    values: &Map<String, Value>,
+    flavor: SqlFlavor,
+) -> Result<EditStatement, String> {
+    let mut assigned = Vec::with_capacity(values.len());
+    let mut binds = Vec::with_capacity(values.len() + key.len());
+    for (name, value) in values {
+        let (column, _) = resolve_column(columns, name)?;
+        assigned.push(quote_identifier(column, flavor));
+        binds.push(value.clone());
+    }
+
+    if edit == RowEdit::Insert {
+        let sql = match (assigned.is_empty(), flavor) {
+            (true, SqlFlavor::MySql) => format!("INSERT INTO {} () VALUES ()", table_ref),
+            (true, SqlFlavor::Sqlite) => format!("INSERT INTO {} DEFAULT VALUES", table_ref),
+            (false, _) => format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table_ref,
+                assigned.join(", "),
+                vec!["?"; assigned.len()].join(", ")
+            ),
+        };
+        return Ok(EditStatement {
+            sql,
+            binds,
+            key_sql: None,
+            key_binds: Vec::new(),
+        });
+    }
+
+    let (condition, key_binds) = key_condition(columns, key, flavor)?;
+    let sql = match edit {
+        RowEdit::Update => {
+            if assigned.is_empty() {
+                return Err("No columns to update".to_string());
+            }
+            let assignments: Vec<String> = assigned.iter().map(|c| format!("{} = ?", c)).collect();
+            binds.extend(key_binds.iter().cloned());
+            format!(
+                "UPDATE {} SET {} WHERE {}",
+                table_ref,
+                assignments.join(", "),
+                condition
+            )
+        }
+        _ => {
+            binds = key_binds.clone();
+            format!("DELETE FROM {} WHERE {}", table_ref, condition)
+        }
+    };
+    let lock = match flavor {
+        SqlFlavor::MySql => " FOR UPDATE",
+        SqlFlavor::Sqlite => "",
+    };
+    Ok(EditStatement {
+        sql,
+        binds,
+        key_sql: Some(format!(
+            "SELECT COUNT(*) FROM {} WHERE {}{}",
+            table_ref, condition, lock
+        )),
+        key_binds,
+    })
+}
+
+fn matched_one(matched: i64) -> Result<(), String> {
+    match matched {
+        1 => Ok(()),
+        0 => Err("Row not found: it may have been changed or deleted".to_string()),
+        n => Err(format!(
+            "Key matches {} rows; refusing to edit more than one row",
+            n
+        )),
+    }
+}
+
+async fn mysql_table_columns(
+    conn: &mut PoolConnection<MySql>,
+    db_name: Option<&str>,
+    table: &str,
+) -> Result<Vec<TableColumn>, String> {
+    let rows = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR), CAST(COLUMN_KEY AS CHAR) \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(db_name)
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    rows.iter()
+        .map(|row| {
+            let name: String = row.try_get_unchecked(0).map_err(|e| e.to_string())?;
+            let key: Option<String> = row.try_get_unchecked(1).map_err(|e| e.to_string())?;
+            Ok((name, key.as_deref() == Some("PRI")))
+        })
+        .collect()
+}
+
+async fn sqlite_table_columns(
+    conn: &mut PoolConnection<Sqlite>,
+    table: &str,
+) -> Result<Vec<TableColumn>, String> {
+    let rows = sqlx::query_as::<_, (String, i64)>(
+        "SELECT name, pk FROM pragma_table_info(?) ORDER BY cid",
+    )
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    Ok(rows.into_iter().map(|(name, pk)| (name, pk > 0)).collect())
+}
+
+// 返回 (执行的语句, 影响行数, 自增 id)；执行失败时语句也用于记录历史
+async fn execute_edit(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    edit: RowEdit,
+    table: &str,
+    db_name: Option<String>,
+    key: &Map<String, Value>,
+    values: &Map<String, Value>,
+    confirm_token: Option<&str>,
+) -> Result<(String, Result<(u64, Option<u64>), String>), String> {
+    let flavor = connection_flavor(db_state, connection_id, "Row editing").await?;
+    let table_ref = match (&db_name, flavor) {
+        (Some(db), SqlFlavor::MySql) => format!(
+            "{}.{}",
+            quote_identifier(db, flavor),
+            quote_identifier(table, flavor)
+        ),
+        _ => quote_identifier(table, flavor),
+    };
+
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            let columns = mysql_table_columns(&mut conn, db_name.as_deref(), table).await?;
+            if columns.is_empty() {
+                return Err(format!("Table {} not found", table));
+            }
+            let statement = build_statement(edit, &table_ref, &columns, key, values, flavor)?;
+            ensure_sql_allowed(
+                db_state,
+                connection_id,
+                &statement.sql,
+                confirm_token,
+                false,
+            )
+            .await?;
+            let result = async {
+                let mut tx = conn
+                    .begin()
+                    .await
+                    .map_err(|e| format!("Failed to start transaction: {}", e))?;
+                if let Some(key_sql) = &statement.key_sql {
+                    let mut query = sqlx::query(key_sql);
+                    for value in &statement.key_binds {
+                        query = mysql_manager::bind_json_value(query, value);
+                    }
+                    let matched: i64 = query
+                        .fetch_one(&mut *tx)
+                        .await
+                        .and_then(|row| row.try_get(0))
+                        .map_err(|e| format!("Failed to lock row: {}", e))?;
+                    matched_one(matched)?;
+                }
+                let mut query = sqlx::query(&statement.sql);
+                for value in &statement.binds {
+                    query = mysql_manager::bind_json_value(query, value);
+                }
+                let done = query
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to {} row: {}", verb(edit), e))?;
+                // MySQL 的 UPDATE 在值未变化时影响行数为 0；行已在上面锁定并确认存在
+                let affected = match edit {
+                    RowEdit::Update => done.rows_affected().max(1),
+                    _ => done.rows_affected(),
+                };
+                if affected != 1 {
+                    return Err(format!("Expected 1 affected row, got {}", affected));
+                }
+                tx.commit()
+                    .await
+                    .map_err(|e| format!("Failed to commit: {}", e))?;
+                let last_insert_id = (edit == RowEdit::Insert && done.last_insert_id() > 0)
+                    .then_some(done.last_insert_id());
+                Ok((affected, last_insert_id))
+            }
+            .await;
+            Ok((statement.sql, result))
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            let columns = sqlite_table_columns(&mut conn, table).await?;
+            if columns.is_empty() {
+                return Err(format!("Table {} not found", table));
+            }
+            let statement = build_statement(edit, &table_ref, &columns, key, values, flavor)?;
+            ensure_sql_allowed(
+                db_state,
+                connection_id,
+                &statement.sql,
+                confirm_token,
+                false,
+            )
+            .await?;
+            let result = async {
+                let mut tx = conn
+                    .begin()
+                    .await
+                    .map_err(|e| format!("Failed to start transaction: {}", e))?;
+                if let Some(key_sql) = &statement.key_sql {
+                    let mut query = sqlx::query(key_sql);
+                    for value in &statement.key_binds {
+                        query = sqlite_manager::bind_json_value(query, value);
+                    }
+                    let matched: i64 = query
+                        .fetch_one(&mut *tx)
+                        .await
+                        .and_then(|row| row.try_get(0))
+                        .map_err(|e| format!("Failed to lock row: {}", e))?;
+                    matched_one(matched)?;
+                }
+                let mut query = sqlx::query(&statement.sql);
+                for value in &statement.binds {
+                    query = sqlite_manager::bind_json_value(query, value);
+                }
+                let done = query
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to {} row: {}", verb(edit), e))?;
+                if done.rows_affected() != 1 {
+                    return Err(format!(
+                        "Expected 1 affected row, got {}",
+                        done.rows_affected()
+                    ));
+                }
+                tx.commit()
+                    .await
+                    .map_err(|e| format!("Failed to commit: {}", e))?;
+                let last_insert_id = match edit {
+                    RowEdit::Insert => u64::try_from(done.last_insert_rowid()).ok(),
+                    _ => None,
+                };
+                Ok((1, last_insert_id))
+            }
+            .await;
+            Ok((statement.sql, result))
+        }
+    }
+}
+
+fn verb(edit: RowEdit) -> &'static str {
+    match edit {
+        RowEdit::Insert => "insert",
+        RowEdit::Update => "update",
+        RowEdit::Delete => "delete",
+    }
+}
+
+async fn edit_row(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    edit: RowEdit,
+    table: String,
+    db_name: Option<String>,
+    key: Map<String, Value>,
+    values: Map<String, Value>,
+    confirm_token: Option<String>,
+) -> Result<RowEditResult, String> {
+    ensure_writable(&db_state, connection_id).await?;
+    let started = Instant::now();
+    let (sql, result) = execute_edit(
+        &app_state,
+        &db_state,
+        connection_id,
+        edit,
+        &table,
+        db_name,
+        &key,
+        &values,
+        confirm_token.as_deref(),
+    )
+    .await?;
+    let elapsed = started.elapsed();
+    record_query(&app_state, &db_state, connection_id, elapsed).await;
+    let outcome = HistoryOutcome::of(&result, |(affected, _)| HistoryOutcome::Affected(*affected));
+    record_history(&db_state, connection_id, &sql, elapsed, outcome).await;
+    let (affected_rows, last_insert_id) = result?;
+    invalidate_results(&app_state, connection_id).await;
+    Ok(RowEditResult {
+        sql,
+        affected_rows,
+        last_insert_id,
+    })
+}
+
+// 数据表格中新增一行：values 为列名到值的映射，未给出的列使用默认值
+#[command]
+pub async fn insert_row(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    values: Map<String, Value>,
+    db_name: Option<String>,
+) -> Result<RowEditResult, String> {
+    edit_row(
+        app_state,
+        db_state,
+        connection_id,
+        RowEdit::Insert,
+        table,
+        db_name,
+        Map::new(),
+        values,
+        None,
+    )
+    .await
+}
+
+// 按主键修改一行：key 为全部主键列的值（无主键的 SQLite 表可传 rowid），values 为修改的列。
+// 在事务中先锁定并确认恰好匹配一行，再执行 UPDATE
+#[command]
+pub async fn update_row(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    key: Map<String, Value>,
+    values: Map<String, Value>,
+    db_name: Option<String>,
+) -> Result<RowEditResult, String> {
+    edit_row(
+        app_state,
+        db_state,
+        connection_id,
+        RowEdit::Update,
+        table,
+        db_name,
+        key,
+        values,
+        None,
+    )
+    .await
+}
+
+// 按主键删除一行，要求同 update_row；生产环境连接需要确认令牌
+#[command]
+pub async fn delete_row(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    key: Map<String, Value>,
+    db_name: Option<String>,
+    confirm_token: Option<String>,
+) -> Result<RowEditResult, String> {
+    edit_row(
+        app_state,
+        db_state,
+        connection_id,
+        RowEdit::Delete,
+        table,
+        db_name,
+        key,
+        Map::new(),
+        confirm_token,
+    )
+    .await
+}
@@ -0,0 +1,606 @@
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::{
+    ensure_sql_allowed, ensure_unattended_sql_allowed, ensure_writable, is_read_only_sql,
+};
+use crate::models::{ScheduledQuery, ScheduledQueryArgs, ScheduledQueryRun};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::result_cache::invalidate_results;
+use crate::snippets::{fetch_snippet_row, render_snippet};
+use crate::sql_classifier::{is_read_query, SqlFlavor};
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use chrono::{
+    DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Timelike, Utc,
+};
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::types::Json;
+use sqlx::{Either, Executor, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+
+pub const SCHEDULED_QUERY_EVENT: &str = "scheduled-query-run";
+
+// 后台检查到期查询的间隔；cron 的最小粒度为分钟
+const SCHEDULER_TICK_SECS: u64 = 15;
+// 每次执行保存的结果行数，以及每个定时查询保留的执行记录数
+const SCHEDULE_KEEP_ROWS: usize = 1_000;
+const SCHEDULE_KEEP_RUNS: i64 = 100;
+const SCHEDULE_DEFAULT_RUNS_LIMIT: i64 = 20;
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledQueryEvent {
+    pub schedule_id: i64,
+    pub run_id: Option<i64>,
+    // "success" / "failed"
+    pub status: String,
+    pub error: Option<String>,
+}
+
+// 5 段 cron 表达式（分 时 日 月 周），每段解析为可取值的位图
+struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    // 0 为周日
+    weekdays: u64,
+    // 日和周都有限定时满足其一即可（与 crontab 一致）
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+// 解析一段：逗号分隔的 *、n、a-b，可带 /step；names 为从 min 开始的名称（JAN、SUN 等）
+fn parse_cron_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(i) => Ok(i as u32 + min),
+            None => s
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid cron value: {}", s)),
+        }
+    };
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("Invalid cron step: {}", part)),
+            },
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            // "5/15" 表示从 5 开始每 15 个单位
+            None if step > 1 => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("Cron value out of range: {}", part));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+impl CronSchedule {
+    fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(
+                "Schedule must be a cron expression with 5 fields: minute hour day month weekday"
+                    .to_string(),
+            );
+        };
+        // 周的 7 也表示周日
+        let weekdays = parse_cron_field(weekday, 0, 7, WEEKDAY_NAMES)?;
+        Ok(Self {
+            minutes: parse_cron_field(minute, 0, 59, &[])?,
+            hours: parse_cron_field(hour, 0, 23, &[])?,
+            days: parse_cron_field(day, 1, 31, &[])?,
+            months: parse_cron_field(month, 1, 12, MONTH_NAMES)?,
+            weekdays: (weekdays | weekdays >> 7) & 0x7f,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    // after 之后的下一个触发时间（本地时间）；夏令时跳过的时间不触发
+    fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut t = after.naive_local().with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        // 最长的周期是 2 月 29 日，最多相隔 8 年
+        let limit = t + TimeDelta::days(366 * 8);
+        while t < limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = match t.month() {
+                    12 => (t.year() + 1, 1),
+                    month => (t.year(), month + 1),
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + TimeDelta::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += TimeDelta::minutes(1);
+            } else {
+                match Local.from_local_datetime(&t).earliest() {
+                    Some(local) => return Some(local),
+                    None => t += TimeDelta::minutes(1),
+                }
+            }
+        }
+        None
+    }
+}
+
+// 下一次执行的 UTC 时间
+fn next_run_at(schedule: &str) -> Option<NaiveDateTime> {
+    CronSchedule::parse(schedule)
+        .ok()?
+        .next_after(Local::now())
+        .map(|next| next.with_timezone(&Utc).naive_utc())
+}
+
+// 要执行的 SQL：直接保存的 sql，或片段的当前内容（占位符取默认值）
+async fn schedule_sql(
+    pool: &SqlitePool,
+    sql: Option<&str>,
+    snippet_id: Option<i64>,
+) -> Result<String, String> {
+    match (sql, snippet_id) {
+        (Some(sql), None) if !sql.trim().is_empty() => Ok(sql.to_string()),
+        (None, Some(snippet_id)) => {
+            let snippet = fetch_snippet_row(pool, snippet_id).await?;
+            if snippet.language != "sql" {
+                return Err("Only SQL snippets can be scheduled".to_string());
+            }
+            render_snippet(&snippet.content, &HashMap::new())
+        }
+        _ => Err("Either sql or snippet_id is required".to_string()),
+    }
+}
+
+#[derive(Default)]
+struct RunOutcome {
+    affected_rows: u64,
+    row_count: u64,
+    rows: Vec<Map<String, Value>>,
+}
+
+impl RunOutcome {
+    fn push(&mut self, row: Map<String, Value>) {
+        self.row_count += 1;
+        if self.rows.len() < SCHEDULE_KEEP_ROWS {
+            self.rows.push(row);
+        }
+    }
+}
+
+async fn execute_schedule(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    schedule: &ScheduledQuery,
+) -> Result<RunOutcome, String> {
+    let connection_id = schedule.connection_id;
+    let sql = schedule_sql(&db_state.pool, schedule.sql.as_deref(), schedule.snippet_id).await?;
+    // 直接保存的 SQL 保存时已检查过，执行前再检查连接是否已改为只读；
+    // 片段的内容可能在保存后被修改，每次都重新检查
+    if schedule.snippet_id.is_some() {
+        ensure_unattended_sql_allowed(db_state, connection_id, &sql).await?;
+    } else if !is_read_only_sql(&sql) {
+        ensure_writable(db_state, connection_id).await?;
+    }
+    let flavor = connection_flavor(db_state, connection_id, "Scheduled queries").await?;
+
+    let execution_id = new_execution_id();
+    let _permit = acquire_query_slot(app_state, connection_id, &execution_id).await?;
+    let started = Instant::now();
+    let mut outcome = RunOutcome::default();
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                connection_id,
+                schedule.db_name.clone(),
+            )
+            .await?;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(app_state, connection_id).await;
+            let mut steps = conn.fetch_many(sqlx::raw_sql(&sql));
+            while let Some(step) = steps
+                .try_next()
+                .await
+                .map_err(|e| format!("Query execution failed: {}", e))?
+            {
+                match step {
+                    Either::Left(done) => outcome.affected_rows += done.rows_affected(),
+                    Either::Right(row) => {
+                        outcome.push(mysql_manager::row_to_json(&row, timestamp_display))
+                    }
+                }
+            }
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let mut steps = pool.fetch_many(sqlx::raw_sql(&sql));
+            while let Some(step) = steps
+                .try_next()
+                .await
+                .map_err(|e| format!("Query execution failed: {}", e))?
+            {
+                match step {
+                    Either::Left(done) => outcome.affected_rows += done.rows_affected(),
+                    Either::Right(row) => outcome.push(sqlite_manager::row_to_json(&row)),
+                }
+            }
+        }
+    }
+    record_query(app_state, db_state, connection_id, started.elapsed()).await;
+    if !is_read_query(&sql, flavor) {
+        invalidate_results(app_state, connection_id).await;
+    }
+    Ok(outcome)
+}
+
+// 执行一次定时查询，保存执行记录并通知前端
+async fn run_schedule(app: &AppHandle, schedule: ScheduledQuery) {
+    let app_state = app.state::<AppState>();
+    let db_state = app.state::<DbState>();
+    let started_at = Utc::now().naive_utc();
+    let started = Instant::now();
+    let outcome = execute_schedule(&app_state, &db_state, &schedule).await;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    let (status, error) = match &outcome {
+        Ok(_) => ("success", None),
+        Err(e) => ("failed", Some(e.clone())),
+    };
+    let outcome = outcome.unwrap_or_default();
+    let run_id = sqlx::query(
+        "INSERT INTO scheduled_query_runs \
+         (schedule_id, started_at, duration_ms, status, affected_rows, row_count, rows, error) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(schedule.id)
+    .bind(started_at)
+    .bind(duration_ms)
+    .bind(status)
+    .bind(outcome.affected_rows as i64)
+    .bind(outcome.row_count as i64)
+    .bind((!outcome.rows.is_empty()).then_some(Json(&outcome.rows)))
+    .bind(&error)
+    .execute(&db_state.pool)
+    .await
+    .map(|result| result.last_insert_rowid())
+    .ok();
+    let _ = sqlx::query(
+        "DELETE FROM scheduled_query_runs WHERE schedule_id = ? AND id NOT IN \
+         (SELECT id FROM scheduled_query_runs WHERE schedule_id = ? ORDER BY id DESC LIMIT ?)",
+    )
+    .bind(schedule.id)
+    .bind(schedule.id)
+    .bind(SCHEDULE_KEEP_RUNS)
+    .execute(&db_state.pool)
+    .await;
+    let _ =
+        sqlx::query("UPDATE scheduled_queries SET last_run_at = ?, last_status = ? WHERE id = ?")
+            .bind(started_at)
+            .bind(status)
+            .bind(schedule.id)
+            .execute(&db_state.pool)
+            .await;
+
+    let _ = app.emit(
+        SCHEDULED_QUERY_EVENT,
+        ScheduledQueryEvent {
+            schedule_id: schedule.id,
+            run_id,
+            status: status.to_string(),
+            error,
+        },
+    );
+}
+
+// 后台定期执行到期的定时查询。应用未运行期间错过的触发只在启动后补执行一次；
+// 上一次执行尚未结束时跳过本次触发
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(SCHEDULER_TICK_SECS));
+        let running: Arc<Mutex<HashSet<i64>>> = Arc::default();
+
+        loop {
+            ticker.tick().await;
+            let Some(db_state) = app.try_state::<DbState>() else {
+                continue;
+            };
+            let Ok(due) = sqlx::query_as::<_, ScheduledQuery>(
+                "SELECT * FROM scheduled_queries \
+                 WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?",
+            )
+            .bind(Utc::now().naive_utc())
+            .fetch_all(&db_state.pool)
+            .await
+            else {
+                continue;
+            };
+
+            for schedule in due {
+                let _ = sqlx::query("UPDATE scheduled_queries SET next_run_at = ? WHERE id = ?")
+                    .bind(next_run_at(&schedule.schedule))
+                    .bind(schedule.id)
+                    .execute(&db_state.pool)
+                    .await;
+                if !running.lock().await.insert(schedule.id) {
+                    continue;
+                }
+                let app = app.clone();
+                let running = running.clone();
+                tauri::async_runtime::spawn(async move {
+                    let schedule_id = schedule.id;
+                    run_schedule(&app, schedule).await;
+                    running.lock().await.remove(&schedule_id);
+                });
+            }
+        }
+    });
+}
+
+// 保存前检查：cron 表达式、连接类型，以及 SQL 在该连接上是否允许执行
+// （生产环境的破坏性语句需要确认令牌，高危语句需要 confirmed）。
+// 片段在执行时重新渲染，确认无法覆盖之后的内容，按无人值守执行检查
+async fn validate_args(
+    db_state: &DbState,
+    args: &ScheduledQueryArgs,
+    confirm_token: Option<&str>,
+    confirmed: bool,
+) -> Result<(), String> {
+    if args.name.trim().is_empty() {
+        return Err("Scheduled query name is required".to_string());
+    }
+    CronSchedule::parse(&args.schedule)?;
+    connection_flavor(db_state, args.connection_id, "Scheduled queries").await?;
+    let sql = schedule_sql(&db_state.pool, args.sql.as_deref(), args.snippet_id).await?;
+    match args.snippet_id {
+        Some(_) => ensure_unattended_sql_allowed(db_state, args.connection_id, &sql).await,
+        None => {
+            ensure_sql_allowed(db_state, args.connection_id, &sql, confirm_token, confirmed).await
+        }
+    }
+}
+
+async fn fetch_schedule_row(pool: &SqlitePool, id: i64) -> Result<ScheduledQuery, String> {
+    sqlx::query_as::<_, ScheduledQuery>("SELECT * FROM scheduled_queries WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch scheduled query: {}", e))?
+        .ok_or_else(|| "Scheduled query not found".to_string())
+}
+
+// 列出定时查询，指定连接时只返回该连接的
+#[command]
+pub async fn list_scheduled_queries(
+    db_state: State<'_, DbState>,
+    connection_id: Option<i64>,
+) -> Result<Vec<ScheduledQuery>, String> {
+    sqlx::query_as::<_, ScheduledQuery>(
+        "SELECT * FROM scheduled_queries WHERE (? IS NULL OR connection_id = ?) ORDER BY name ASC",
+    )
+    .bind(connection_id)
+    .bind(connection_id)
+    .fetch_all(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to list scheduled queries: {}", e))
+}
+
+#[command]
+pub async fn create_scheduled_query(
+    db_state: State<'_, DbState>,
+    args: ScheduledQueryArgs,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+) -> Result<ScheduledQuery, String> {
+    validate_args(
+        &db_state,
+        &args,
+        confirm_token.as_deref(),
+        confirmed.unwrap_or(false),
+    )
+    .await?;
+    let enabled = args.enabled.unwrap_or(true);
+    let pool = &db_state.pool;
+    let result = sqlx::query(
+        "INSERT INTO scheduled_queries \
+         (name, connection_id, db_name, sql, snippet_id, schedule, enabled, next_run_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(args.name.trim())
+    .bind(args.connection_id)
+    .bind(&args.db_name)
+    .bind(&args.sql)
+    .bind(args.snippet_id)
+    .bind(args.schedule.trim())
+    .bind(enabled)
+    .bind(enabled.then(|| next_run_at(&args.schedule)).flatten())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create scheduled query: {}", e))?;
+
+    fetch_schedule_row(pool, result.last_insert_rowid()).await
+}
+
+// 修改定时查询（含启用 / 停用），下一次执行时间按新的 cron 表达式重新计算
+#[command]
+pub async fn update_scheduled_query(
+    db_state: State<'_, DbState>,
+    schedule_id: i64,
+    args: ScheduledQueryArgs,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+) -> Result<ScheduledQuery, String> {
+    validate_args(
+        &db_state,
+        &args,
+        confirm_token.as_deref(),
+        confirmed.unwrap_or(false),
+    )
+    .await?;
+    let enabled = args.enabled.unwrap_or(true);
+    let pool = &db_state.pool;
+    let result = sqlx::query(
+        "UPDATE scheduled_queries SET name = ?, connection_id = ?, db_name = ?, sql = ?, \
+         snippet_id = ?, schedule = ?, enabled = ?, next_run_at = ?, \
+         updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(args.name.trim())
+    .bind(args.connection_id)
+    .bind(&args.db_name)
+    .bind(&args.sql)
+    .bind(args.snippet_id)
+    .bind(args.schedule.trim())
+    .bind(enabled)
+    .bind(enabled.then(|| next_run_at(&args.schedule)).flatten())
+    .bind(schedule_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update scheduled query: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err("Scheduled query not found".to_string());
+    }
+
+    fetch_schedule_row(pool, schedule_id).await
+}
+
+#[command]
+pub async fn delete_scheduled_query(
+    db_state: State<'_, DbState>,
+    schedule_id: i64,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM scheduled_query_runs WHERE schedule_id = ?")
+        .bind(schedule_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete scheduled query runs: {}", e))?;
+    sqlx::query("DELETE FROM scheduled_queries WHERE id = ?")
+        .bind(schedule_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete scheduled query: {}", e))?;
+    Ok(())
+}
+
+// 最近的执行记录，按时间倒序
+#[command]
+pub async fn list_scheduled_query_runs(
+    db_state: State<'_, DbState>,
+    schedule_id: i64,
+    limit: Option<i64>,
+) -> Result<Vec<ScheduledQueryRun>, String> {
+    sqlx::query_as::<_, ScheduledQueryRun>(
+        "SELECT * FROM scheduled_query_runs WHERE schedule_id = ? ORDER BY id DESC LIMIT ?",
+    )
+    .bind(schedule_id)
+    .bind(limit.unwrap_or(SCHEDULE_DEFAULT_RUNS_LIMIT).max(1))
+    .fetch_all(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to list scheduled query runs: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn day_and_weekday_match_either_when_both_restricted() {
+        // 每月 13 号或每周五
+        let schedule = CronSchedule::parse("0 0 13 * 5").unwrap();
+        assert!(schedule.day_matches(date(2026, 10, 13))); // 周二
+        assert!(schedule.day_matches(date(2026, 10, 16))); // 周五
+        assert!(!schedule.day_matches(date(2026, 10, 14)));
+    }
+
+    #[test]
+    fn unrestricted_field_does_not_widen_match() {
+        let monthly = CronSchedule::parse("0 0 1 * *").unwrap();
+        assert!(monthly.day_matches(date(2026, 11, 1)));
+        assert!(!monthly.day_matches(date(2026, 11, 2)));
+
+        let fridays = CronSchedule::parse("0 0 * * FRI").unwrap();
+        assert!(fridays.day_matches(date(2026, 10, 16)));
+        assert!(!fridays.day_matches(date(2026, 10, 17)));
+
+        // */2 以 * 开头，与 crontab 一样视为不限定
+        let every_other = CronSchedule::parse("0 0 */2 * 1").unwrap();
+        assert!(!every_other.day_matches(date(2026, 10, 13)));
+        assert!(every_other.day_matches(date(2026, 10, 19)));
+    }
+
+    #[test]
+    fn weekday_seven_is_sunday() {
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert!(sunday.day_matches(date(2026, 10, 18)));
+        assert!(!sunday.day_matches(date(2026, 10, 17)));
+        assert_eq!(
+            sunday.weekdays,
+            CronSchedule::parse("0 0 * * 0").unwrap().weekdays
+        );
+
+        let weekend = CronSchedule::parse("0 0 * * 6-7").unwrap();
+        assert!(weekend.day_matches(date(2026, 10, 17)));
+        assert!(weekend.day_matches(date(2026, 10, 18)));
+        assert!(!weekend.day_matches(date(2026, 10, 19)));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * FOO *",
+        ] {
+            assert!(CronSchedule::parse(expression).is_err(), "{}", expression);
+        }
+    }
+}
@@ -0,0 +1,562 @@
+use crate::alter_table::{qualified_name, quote_identifier, quote_list};
+use crate::db::{connection_flavor, DbState};
+use crate::index_manager::index_kind;
+use crate::models::{
+    IndexInfo, IndexSpec, SchemaDiff, SchemaDifference, SchemaTarget, TableColumn, TableStructure,
+};
+use crate::result_export::sql_literal;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use crate::table_structure::{mysql_structure, sqlite_structure};
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::Value;
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use std::collections::BTreeMap;
+use tauri::{command, State};
+
+// 无需加引号的默认值：已带引号的字面量（MariaDB）、NULL、位值和时间函数
+const RAW_DEFAULT_PREFIXES: &[&str] = &[
+    "'",
+    "NULL",
+    "B'",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "NOW(",
+    "LOCALTIME",
+];
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+struct Schema {
+    flavor: SqlFlavor,
+    db_name: Option<String>,
+    // 表名 -> (结构, 建表语句)
+    tables: BTreeMap<String, (TableStructure, String)>,
+}
+
+async fn load_schema(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    target: &SchemaTarget,
+) -> Result<Schema, String> {
+    let connection_id = target.connection_id;
+    let flavor = connection_flavor(db_state, connection_id, "Schema comparison").await?;
+    let db_name = target.db_name.clone().filter(|db| !db.trim().is_empty());
+    let mut tables = BTreeMap::new();
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            let names: Vec<String> = sqlx::query(
+                "SELECT CAST(TABLE_NAME AS CHAR) FROM information_schema.TABLES \
+                 WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_TYPE = 'BASE TABLE' \
+                 ORDER BY TABLE_NAME",
+            )
+            .bind(db_name.as_deref())
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to list tables: {}", e))?
+            .iter()
+            .filter_map(|row| text(row, 0))
+            .collect();
+            drop(conn);
+            for name in names {
+                let mut conn = mysql_manager::acquire_connection(
+                    app_state,
+                    db_state,
+                    connection_id,
+                    db_name.clone(),
+                )
+                .await?;
+                let create_sql = sqlx::query(&format!(
+                    "SHOW CREATE TABLE {}",
+                    qualified_name(db_name.as_deref(), &name, flavor)
+                ))
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to fetch definition of {}: {}", name, e))
+                .map(|row| text(&row, 1).unwrap_or_default())?;
+                let structure = mysql_structure(conn, db_name.as_deref(), &name).await?;
+                tables.insert(name, (structure, create_sql));
+            }
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let rows = sqlx::query_as::<_, (String, String)>(
+                "SELECT name, sql FROM sqlite_master \
+                 WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            )
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to list tables: {}", e))?;
+            for (name, create_sql) in rows {
+                let conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+                let structure = sqlite_structure(conn, &name).await?;
+                tables.insert(name, (structure, create_sql));
+            }
+        }
+    }
+    Ok(Schema {
+        flavor,
+        db_name,
+        tables,
+    })
+}
+
+fn difference(
+    kind: &str,
+    table: &str,
+    object: Option<&str>,
+    source: Option<String>,
+    target: Option<String>,
+    statements: Vec<String>,
+) -> SchemaDifference {
+    SchemaDifference {
+        kind: kind.to_string(),
+        table: table.to_string(),
+        object: object.map(str::to_string),
+        source,
+        target,
+        statements,
+    }
+}
+
+// 生成列的表达式不在 information_schema.COLUMNS 的读取范围内，无法生成语句
+pub fn is_generated(column: &TableColumn) -> bool {
+    column.extra.as_deref().is_some_and(is_generated_extra)
+}
+
+// MySQL 8 的 EXTRA 中表达式默认值为 DEFAULT_GENERATED，生成列为 VIRTUAL / STORED GENERATED
+pub fn is_generated_extra(extra: &str) -> bool {
+    let extra = extra.to_ascii_uppercase();
+    extra.contains("GENERATED") && !extra.contains("DEFAULT_GENERATED")
+}
+
+// information_schema 中的默认值不区分字面量和表达式：MySQL 8 用 DEFAULT_GENERATED 标记表达式
+fn mysql_default(default_value: &str, expression: bool) -> String {
+    let upper = default_value.to_ascii_uppercase();
+    if RAW_DEFAULT_PREFIXES.iter().any(|p| upper.starts_with(p)) {
+        default_value.to_string()
+    } else if expression {
+        format!("({})", default_value)
+    } else {
+        sql_literal(&Value::String(default_value.to_string()), SqlFlavor::MySql)
+    }
+}
+
+// 列定义，同时用于比较两端的差异
+fn column_sql(column: &TableColumn, flavor: SqlFlavor) -> String {
+    let mut sql = format!(
+        "{} {}",
+        quote_identifier(&column.name, flavor),
+        column.column_type
+    );
+    let extra = column.extra.as_deref().unwrap_or_default();
+    match flavor {
+        SqlFlavor::MySql => {
+            sql.push_str(if column.nullable {
+                " NULL"
+            } else {
+                " NOT NULL"
+            });
+            if let Some(default_value) = &column.default_value {
+                let expression = extra.contains("DEFAULT_GENERATED");
+                sql.push_str(&format!(
+                    " DEFAULT {}",
+                    mysql_default(default_value, expression)
+                ));
+            }
+            // auto_increment、on update CURRENT_TIMESTAMP 等按原样保留
+            let extra = extra.replace("DEFAULT_GENERATED", "");
+            if !extra.trim().is_empty() {
+                sql.push_str(&format!(" {}", extra.trim()));
+            }
+            if let Some(comment) = &column.comment {
+                sql.push_str(&format!(
+                    " COMMENT {}",
+                    sql_literal(&Value::String(comment.clone()), SqlFlavor::MySql)
+                ));
+            }
+        }
+        SqlFlavor::Sqlite => {
+            if !column.nullable && !column.primary_key {
+                sql.push_str(" NOT NULL");
+            }
+            // pragma_table_info 返回的默认值就是原始 SQL 文本
+            if let Some(default_value) = &column.default_value {
+                sql.push_str(&format!(" DEFAULT {}", default_value));
+            }
+        }
+    }
+    sql
+}
+
+pub fn primary_key(structure: &TableStructure) -> Option<&Vec<String>> {
+    structure
+        .constraints
+        .iter()
+        .find(|c| c.constraint_type == "PRIMARY KEY")
+        .map(|c| &c.columns)
+}
+
+// 主键单独比较；SQLite 的 sqlite_autoindex_ 属于表定义，不能单独创建
+pub fn secondary_indexes(structure: &TableStructure) -> BTreeMap<&str, &IndexInfo> {
+    structure
+        .indexes
+        .iter()
+        .filter(|index| !index.primary && !index.name.starts_with("sqlite_autoindex_"))
+        .map(|index| (index.name.as_str(), index))
+        .collect()
+}
+
+fn index_summary(index: &IndexInfo) -> String {
+    let columns = index
+        .columns
+        .iter()
+        .map(|c| c.as_deref().unwrap_or("<expression>"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut summary = format!(
+        "{}INDEX ({})",
+        if index.unique { "UNIQUE " } else { "" },
+        columns
+    );
+    if let Some(index_type) = &index.index_type {
+        summary.push_str(&format!(" {}", index_type));
+    }
+    summary
+}
+
+// 表达式索引拿不到定义，返回 None
+pub fn create_index_sql(index: &IndexInfo, table_sql: &str, flavor: SqlFlavor) -> Option<String> {
+    let columns = index.columns.iter().cloned().collect::<Option<Vec<_>>>()?;
+    let spec = IndexSpec {
+        name: Some(index.name.clone()),
+        columns,
+        unique: index.unique,
+        index_type: match flavor {
+            SqlFlavor::MySql => index
+                .index_type
+                .clone()
+                .filter(|t| !t.eq_ignore_ascii_case("BTREE")),
+            SqlFlavor::Sqlite => None,
+        },
+    };
+    let (prefix, using) = index_kind(&spec, flavor).ok()?;
+    Some(format!(
+        "CREATE {}INDEX {} ON {} ({}){}",
+        prefix,
+        quote_identifier(&index.name, flavor),
+        table_sql,
+        quote_list(&spec.columns, flavor),
+        using
+    ))
+}
+
+fn drop_index_sql(name: &str, table_sql: &str, flavor: SqlFlavor) -> String {
+    match flavor {
+        SqlFlavor::MySql => format!(
+            "DROP INDEX {} ON {}",
+            quote_identifier(name, flavor),
+            table_sql
+        ),
+        SqlFlavor::Sqlite => format!("DROP INDEX {}", quote_identifier(name, flavor)),
+    }
+}
+
+// SHOW CREATE TABLE 带有源库当前的 AUTO_INCREMENT 计数，同步结构时去掉
+fn strip_auto_increment(create_sql: &str) -> String {
+    let Some(start) = create_sql.rfind(" AUTO_INCREMENT=") else {
+        return create_sql.to_string();
+    };
+    let digits = create_sql[start + " AUTO_INCREMENT=".len()..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .count();
+    format!(
+        "{}{}",
+        &create_sql[..start],
+        &create_sql[start + " AUTO_INCREMENT=".len() + digits..]
+    )
+}
+
+fn create_table_statements(
+    name: &str,
+    structure: &TableStructure,
+    create_sql: &str,
+    target: &Schema,
+) -> Vec<String> {
+    let flavor = target.flavor;
+    let table_sql = qualified_name(target.db_name.as_deref(), name, flavor);
+    match flavor {
+        // SHOW CREATE TABLE 已包含索引，表名不带库名
+        SqlFlavor::MySql => vec![strip_auto_increment(&create_sql.replacen(
+            &format!("CREATE TABLE {}", quote_identifier(name, flavor)),
+            &format!("CREATE TABLE {}", table_sql),
+            1,
+        ))],
+        SqlFlavor::Sqlite => std::iter::once(create_sql.to_string())
+            .chain(
+                secondary_indexes(structure)
+                    .values()
+                    .filter_map(|index| create_index_sql(index, &table_sql, flavor)),
+            )
+            .collect(),
+    }
+}
+
+fn diff_columns(
+    name: &str,
+    source: &TableStructure,
+    target: &TableStructure,
+    table_sql: &str,
+    flavor: SqlFlavor,
+    differences: &mut Vec<SchemaDifference>,
+) {
+    let mut previous: Option<&str> = None;
+    for column in &source.columns {
+        let definition = column_sql(column, flavor);
+        match target.columns.iter().find(|c| c.name == column.name) {
+            None => {
+                let mut statement = format!("ALTER TABLE {} ADD COLUMN {}", table_sql, definition);
+                if matches!(flavor, SqlFlavor::MySql) {
+                    match previous {
+                        Some(after) => statement
+                            .push_str(&format!(" AFTER {}", quote_identifier(after, flavor))),
+                        None => statement.push_str(" FIRST"),
+                    }
+                }
+                let statements = if is_generated(column) {
+                    Vec::new()
+                } else {
+                    vec![statement]
+                };
+                differences.push(difference(
+                    "missing_column",
+                    name,
+                    Some(&column.name),
+                    Some(definition),
+                    None,
+                    statements,
+                ));
+            }
+            Some(existing) => {
+                let current = column_sql(existing, flavor);
+                if !current.eq_ignore_ascii_case(&definition) {
+                    // SQLite 不支持修改列，需要重建表
+                    let statements = match flavor {
+                        SqlFlavor::MySql if !is_generated(column) => vec![format!(
+                            "ALTER TABLE {} MODIFY COLUMN {}",
+                            table_sql, definition
+                        )],
+                        _ => Vec::new(),
+                    };
+                    differences.push(difference(
+                        "column_changed",
+                        name,
+                        Some(&column.name),
+                        Some(definition),
+                        Some(current),
+                        statements,
+                    ));
+                }
+            }
+        }
+        previous = Some(&column.name);
+    }
+    for column in &target.columns {
+        if source.columns.iter().all(|c| c.name != column.name) {
+            differences.push(difference(
+                "extra_column",
+                name,
+                Some(&column.name),
+                None,
+                Some(column_sql(column, flavor)),
+                vec![format!(
+                    "ALTER TABLE {} DROP COLUMN {}",
+                    table_sql,
+                    quote_identifier(&column.name, flavor)
+                )],
+            ));
+        }
+    }
+}
+
+fn diff_primary_key(
+    name: &str,
+    source: &TableStructure,
+    target: &TableStructure,
+    table_sql: &str,
+    flavor: SqlFlavor,
+    differences: &mut Vec<SchemaDifference>,
+) {
+    let (source_key, target_key) = (primary_key(source), primary_key(target));
+    if source_key == target_key {
+        return;
+    }
+    let describe =
+        |key: Option<&Vec<String>>| key.map(|k| format!("PRIMARY KEY ({})", k.join(", ")));
+    let statements = match flavor {
+        SqlFlavor::MySql => {
+            let mut actions = Vec::new();
+            if target_key.is_some() {
+                actions.push("DROP PRIMARY KEY".to_string());
+            }
+            if let Some(key) = source_key {
+                actions.push(format!("ADD PRIMARY KEY ({})", quote_list(key, flavor)));
+            }
+            vec![format!("ALTER TABLE {} {}", table_sql, actions.join(", "))]
+        }
+        // SQLite 的主键只能通过重建表修改
+        SqlFlavor::Sqlite => Vec::new(),
+    };
+    differences.push(difference(
+        "primary_key_changed",
+        name,
+        None,
+        describe(source_key),
+        describe(target_key),
+        statements,
+    ));
+}
+
+fn diff_indexes(
+    name: &str,
+    source: &TableStructure,
+    target: &TableStructure,
+    table_sql: &str,
+    flavor: SqlFlavor,
+    differences: &mut Vec<SchemaDifference>,
+) {
+    let source_indexes = secondary_indexes(source);
+    let target_indexes = secondary_indexes(target);
+    for (index_name, index) in &source_indexes {
+        let summary = index_summary(index);
+        let create = create_index_sql(index, table_sql, flavor);
+        match target_indexes.get(index_name) {
+            None => differences.push(difference(
+                "missing_index",
+                name,
+                Some(index_name),
+                Some(summary),
+                None,
+                create.into_iter().collect(),
+            )),
+            Some(existing) => {
+                let current = index_summary(existing);
+                if current != summary {
+                    let statements = match create {
+                        Some(create) => {
+                            vec![drop_index_sql(index_name, table_sql, flavor), create]
+                        }
+                        None => Vec::new(),
+                    };
+                    differences.push(difference(
+                        "index_changed",
+                        name,
+                        Some(index_name),
+                        Some(summary),
+                        Some(current),
+                        statements,
+                    ));
+                }
+            }
+        }
+    }
+    for (index_name, index) in &target_indexes {
+        if !source_indexes.contains_key(index_name) {
+            differences.push(difference(
+                "extra_index",
+                name,
+                Some(index_name),
+                None,
+                Some(index_summary(index)),
+                vec![drop_index_sql(index_name, table_sql, flavor)],
+            ));
+        }
+    }
+}
+
+// 差异按新建表、修改表、删除表的顺序排列，同步语句按同样的顺序执行
+fn diff(source: &Schema, target: &Schema) -> Vec<SchemaDifference> {
+    let flavor = target.flavor;
+    let mut created = Vec::new();
+    let mut changed = Vec::new();
+    let mut dropped = Vec::new();
+    for (name, (structure, create_sql)) in &source.tables {
+        let Some((existing, _)) = target.tables.get(name) else {
+            created.push(difference(
+                "missing_table",
+                name,
+                None,
+                Some(create_sql.clone()),
+                None,
+                create_table_statements(name, structure, create_sql, target),
+            ));
+            continue;
+        };
+        let table_sql = qualified_name(target.db_name.as_deref(), name, flavor);
+        diff_columns(name, structure, existing, &table_sql, flavor, &mut changed);
+        diff_primary_key(name, structure, existing, &table_sql, flavor, &mut changed);
+        diff_indexes(name, structure, existing, &table_sql, flavor, &mut changed);
+    }
+    for (name, (_, create_sql)) in &target.tables {
+        if !source.tables.contains_key(name) {
+            dropped.push(difference(
+                "extra_table",
+                name,
+                None,
+                None,
+                Some(create_sql.clone()),
+                vec![format!(
+                    "DROP TABLE {}",
+                    qualified_name(target.db_name.as_deref(), name, flavor)
+                )],
+            ));
+        }
+    }
+    created.into_iter().chain(changed).chain(dropped).collect()
+}
+
+// 比较两个连接（或同一连接的两个库）的表结构：缺少 / 多出的表、列定义、主键和索引的差异，
+// 并给出让 target 与 source 一致的语句。只生成语句，不执行；两端须同为 MySQL 系或同为 SQLite
+#[command]
+pub async fn diff_schemas(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    source: SchemaTarget,
+    target: SchemaTarget,
+) -> Result<SchemaDiff, String> {
+    let source = load_schema(&app_state, &db_state, &source).await?;
+    let target = load_schema(&app_state, &db_state, &target).await?;
+    if !matches!(
+        (source.flavor, target.flavor),
+        (SqlFlavor::MySql, SqlFlavor::MySql) | (SqlFlavor::Sqlite, SqlFlavor::Sqlite)
+    ) {
+        return Err("Schemas can only be compared between databases of the same kind".to_string());
+    }
+    let differences = diff(&source, &target);
+    let statements = differences
+        .iter()
+        .flat_map(|d| d.statements.iter().cloned())
+        .collect();
+    Ok(SchemaDiff {
+        differences,
+        statements,
+    })
+}
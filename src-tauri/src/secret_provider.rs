@@ -0,0 +1,154 @@
+use aws_config::BehaviorVersion;
+use aws_sdk_secretsmanager::config::Region;
+use aws_sdk_secretsmanager::error::DisplayErrorContext;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+const VAULT_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+// 连接密码的外部来源，保存在 connections.options.password_source 中，
+// 每次建立连接时读取，密码本身不写入本地数据库
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum SecretSource {
+    // 读取 xDB 进程的环境变量
+    Env {
+        name: String,
+    },
+    // HashiCorp Vault KV（v1 / v2 均可），path 形如 "secret/data/mysql/prod"
+    Vault {
+        address: Option<String>, // 为空时读取 VAULT_ADDR
+        path: String,
+        key: String,
+        token_env: Option<String>, // 存放 token 的环境变量名，默认 VAULT_TOKEN
+        namespace: Option<String>,
+    },
+    // AWS Secrets Manager，凭证走默认链（环境变量、~/.aws、SSO 等）
+    AwsSecretsManager {
+        secret_id: String,
+        region: Option<String>,
+        profile: Option<String>,
+        key: Option<String>, // 密钥值是 JSON 时取其中的字段，为空则使用整个字符串
+    },
+}
+
+pub async fn resolve_secret(source: &SecretSource) -> Result<String, String> {
+    match source {
+        SecretSource::Env { name } => std::env::var(name)
+            .map_err(|_| format!("Environment variable {} is not set", name)),
+        SecretSource::Vault {
+            address,
+            path,
+            key,
+            token_env,
+            namespace,
+        } => {
+            resolve_vault_secret(
+                address.as_deref(),
+                path,
+                key,
+                token_env.as_deref(),
+                namespace.as_deref(),
+            )
+            .await
+        }
+        SecretSource::AwsSecretsManager {
+            secret_id,
+            region,
+            profile,
+            key,
+        } => {
+            resolve_aws_secret(secret_id, region.as_deref(), profile.as_deref(), key.as_deref())
+                .await
+        }
+    }
+}
+
+async fn resolve_vault_secret(
+    address: Option<&str>,
+    path: &str,
+    key: &str,
+    token_env: Option<&str>,
+    namespace: Option<&str>,
+) -> Result<String, String> {
+    let address = match address.filter(|a| !a.is_empty()) {
+        Some(address) => address.to_string(),
+        None => std::env::var("VAULT_ADDR").map_err(|_| "Vault address is not configured")?,
+    };
+    let token_env = token_env.filter(|t| !t.is_empty()).unwrap_or("VAULT_TOKEN");
+    let token = std::env::var(token_env)
+        .map_err(|_| format!("Environment variable {} is not set", token_env))?;
+
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(VAULT_REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let url = format!(
+        "{}/v1/{}",
+        address.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+    let mut request = http.get(&url).header("X-Vault-Token", token);
+    if let Some(namespace) = namespace.filter(|n| !n.is_empty()) {
+        request = request.header("X-Vault-Namespace", namespace);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Vault: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Vault returned {} for {}", status, path));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Vault response: {}", e))?;
+
+    // KV v2 把数据放在 data.data，KV v1 直接放在 data
+    let data = &body["data"];
+    let value = data["data"].get(key).or_else(|| data.get(key));
+    match value {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(other) if !other.is_null() => Ok(other.to_string()),
+        _ => Err(format!("Key {} not found in Vault secret {}", key, path)),
+    }
+}
+
+async fn resolve_aws_secret(
+    secret_id: &str,
+    region: Option<&str>,
+    profile: Option<&str>,
+    key: Option<&str>,
+) -> Result<String, String> {
+    let mut loader = aws_config::defaults(BehaviorVersion::latest());
+    if let Some(region) = region.filter(|r| !r.is_empty()) {
+        loader = loader.region(Region::new(region.to_string()));
+    }
+    if let Some(profile) = profile.filter(|p| !p.is_empty()) {
+        loader = loader.profile_name(profile);
+    }
+    let client = aws_sdk_secretsmanager::Client::new(&loader.load().await);
+
+    let output = client
+        .get_secret_value()
+        .secret_id(secret_id)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to read AWS secret: {}", DisplayErrorContext(&e)))?;
+    let secret = output
+        .secret_string()
+        .ok_or("AWS secret has no string value")?;
+
+    let Some(key) = key.filter(|k| !k.is_empty()) else {
+        return Ok(secret.to_string());
+    };
+    let json: Value = serde_json::from_str(secret)
+        .map_err(|e| format!("AWS secret is not a JSON object: {}", e))?;
+    match json.get(key) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(other) if !other.is_null() => Ok(other.to_string()),
+        _ => Err(format!("Key {} not found in AWS secret {}", key, secret_id)),
+    }
+}
@@ -0,0 +1,256 @@
+use crate::database_search::like_pattern;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::ensure_sql_allowed;
+use crate::models::{ServerStatus, ServerStatusRates, ServerVariable};
+use crate::mysql_manager::{self, get_server_profile_for};
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::result_export::sql_literal;
+use crate::session::{mysql_session, QueryConnection};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use serde_json::Value;
+use sqlx::mysql::MySqlRow;
+use sqlx::{MySql, Row};
+use std::collections::HashMap;
+use std::time::Instant;
+use tauri::{command, State};
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 状态和系统变量只有 MySQL 系有
+async fn ensure_mysql(db_state: &DbState, connection_id: i64) -> Result<(), String> {
+    match connection_flavor(db_state, connection_id, "Server status").await? {
+        SqlFlavor::MySql => Ok(()),
+        SqlFlavor::Sqlite => Err("Server status is not supported for sqlite".to_string()),
+    }
+}
+
+// global 或 session，默认 global
+fn parse_scope(scope: Option<&str>) -> Result<&'static str, String> {
+    match scope.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        None | Some("") | Some("global") => Ok("GLOBAL"),
+        Some("session") => Ok("SESSION"),
+        Some(other) => Err(format!("Unsupported variable scope: {}", other)),
+    }
+}
+
+// 指定 session_id 时使用会话独占的连接，会话变量只有在该连接上才有意义
+async fn scoped_connection(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    session_id: Option<&str>,
+) -> Result<QueryConnection<MySql>, String> {
+    match session_id {
+        Some(session_id) => mysql_session(app_state, session_id, connection_id).await,
+        None => Ok(QueryConnection::Pooled(
+            mysql_manager::acquire_connection(app_state, db_state, connection_id, None).await?,
+        )),
+    }
+}
+
+fn numeric_status(status: &HashMap<String, String>) -> HashMap<&str, i64> {
+    status
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.parse::<i64>().ok()?)))
+        .collect()
+}
+
+// 两次采样的间隔无效或服务端在此期间重启（Uptime 变小）时不计算速率
+fn status_rates(previous: &ServerStatus, current: &ServerStatus) -> Option<ServerStatusRates> {
+    let interval_secs = (current.sampled_at_ms - previous.sampled_at_ms) as f64 / 1000.0;
+    if interval_secs <= 0.0 {
+        return None;
+    }
+    let before = numeric_status(&previous.status);
+    let after = numeric_status(&current.status);
+    let value = |values: &HashMap<&str, i64>, name: &str| values.get(name).copied().unwrap_or(0);
+    if value(&after, "Uptime") < value(&before, "Uptime") {
+        return None;
+    }
+    let delta = |name: &str| value(&after, name) - value(&before, name);
+    let per_sec =
+        |names: &[&str]| names.iter().map(|name| delta(name)).sum::<i64>() as f64 / interval_secs;
+    let deltas = after
+        .iter()
+        .filter_map(|(name, now)| {
+            let diff = now - before.get(name)?;
+            (diff != 0).then(|| (name.to_string(), diff))
+        })
+        .collect();
+    Some(ServerStatusRates {
+        interval_secs,
+        queries_per_sec: per_sec(&["Questions"]),
+        selects_per_sec: per_sec(&["Com_select"]),
+        inserts_per_sec: per_sec(&["Com_insert", "Com_insert_select"]),
+        updates_per_sec: per_sec(&["Com_update", "Com_update_multi"]),
+        deletes_per_sec: per_sec(&["Com_delete", "Com_delete_multi"]),
+        transactions_per_sec: per_sec(&["Com_commit", "Com_rollback"]),
+        connections_per_sec: per_sec(&["Connections"]),
+        bytes_received_per_sec: per_sec(&["Bytes_received"]),
+        bytes_sent_per_sec: per_sec(&["Bytes_sent"]),
+        slow_queries: delta("Slow_queries"),
+        threads_connected: value(&after, "Threads_connected"),
+        threads_running: value(&after, "Threads_running"),
+        deltas,
+    })
+}
+
+// 读取 SHOW GLOBAL STATUS。传入上一次的返回值时同时计算这段时间内的 QPS、各类语句、
+// 流量和连接的每秒速率，以及所有数值变量的差值，供监控面板定时轮询
+#[command]
+pub async fn get_server_status(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    previous: Option<ServerStatus>,
+) -> Result<ServerStatus, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let profile = get_server_profile_for(&app_state, &db_state, connection_id).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, None).await?;
+    let rows = sqlx::query(&profile.global_status_sql)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch server status: {}", e))?;
+    let mut current = ServerStatus {
+        sampled_at_ms: chrono::Utc::now().timestamp_millis(),
+        status: rows
+            .iter()
+            .filter_map(|row| Some((text(row, 0)?, text(row, 1).unwrap_or_default())))
+            .collect(),
+        rates: None,
+    };
+    current.rates = previous
+        .as_ref()
+        .and_then(|previous| status_rates(previous, &current));
+    Ok(current)
+}
+
+// 读取系统变量，scope 为 global（默认）或 session；filter 按名称包含匹配。
+// 会话变量指定 session_id 时读取该会话连接上的值，否则为连接池中任一连接的值
+#[command]
+pub async fn get_server_variables(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    scope: Option<String>,
+    filter: Option<String>,
+    session_id: Option<String>,
+) -> Result<Vec<ServerVariable>, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let sql = match parse_scope(scope.as_deref())? {
+        "GLOBAL" => {
+            get_server_profile_for(&app_state, &db_state, connection_id)
+                .await?
+                .global_variables_sql
+        }
+        _ => "SHOW SESSION VARIABLES".to_string(),
+    };
+    let filter = filter.filter(|f| !f.trim().is_empty());
+    let sql = match filter {
+        Some(_) => format!("{} WHERE Variable_name LIKE ? ESCAPE '!'", sql),
+        None => sql,
+    };
+    let mut conn =
+        scoped_connection(&app_state, &db_state, connection_id, session_id.as_deref()).await?;
+    let rows = sqlx::query(&sql)
+        .bind(filter.map(|f| like_pattern(f.trim())))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch server variables: {}", e))?;
+    let mut variables: Vec<ServerVariable> = rows
+        .iter()
+        .filter_map(|row| {
+            Some(ServerVariable {
+                name: text(row, 0)?,
+                value: text(row, 1),
+            })
+        })
+        .collect();
+    variables.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(variables)
+}
+
+// 变量名只允许字母、数字、下划线和组件变量中的点，直接拼入语句
+fn check_variable_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.ends_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    match valid {
+        true => Ok(()),
+        false => Err(format!("Invalid variable name: {}", name)),
+    }
+}
+
+// 数字和 ON / OFF / DEFAULT 等关键字原样拼入，其余按字符串处理
+fn variable_literal(value: &str) -> String {
+    let value = value.trim();
+    let keyword = ["ON", "OFF", "DEFAULT", "TRUE", "FALSE"]
+        .iter()
+        .any(|k| value.eq_ignore_ascii_case(k));
+    if keyword || value.parse::<i64>().is_ok() || value.parse::<f64>().is_ok_and(f64::is_finite) {
+        value.to_string()
+    } else {
+        sql_literal(&Value::String(value.to_string()), SqlFlavor::MySql)
+    }
+}
+
+// 修改系统变量并返回修改后的值。GLOBAL 修改影响整个服务端（只读连接会被拒绝），重启后失效；
+// SESSION 修改只对指定会话的连接有效，因此必须提供 session_id
+#[command]
+pub async fn set_server_variable(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    value: String,
+    scope: Option<String>,
+    session_id: Option<String>,
+    confirm_token: Option<String>,
+) -> Result<ServerVariable, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let name = name.trim().to_string();
+    check_variable_name(&name)?;
+    let scope = parse_scope(scope.as_deref())?;
+    if scope == "SESSION" && session_id.is_none() {
+        return Err("Session variables can only be set on an open session".to_string());
+    }
+    let sql = format!("SET {} {} = {}", scope, name, variable_literal(&value));
+    ensure_sql_allowed(
+        &db_state,
+        connection_id,
+        &sql,
+        confirm_token.as_deref(),
+        false,
+    )
+    .await?;
+
+    let mut conn =
+        scoped_connection(&app_state, &db_state, connection_id, session_id.as_deref()).await?;
+    let started = Instant::now();
+    let result = sqlx::query(&sql)
+        .execute(&mut *conn)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to set variable: {}", e));
+    let outcome = HistoryOutcome::of(&result, |_| HistoryOutcome::Done);
+    record_history(&db_state, connection_id, &sql, started.elapsed(), outcome).await;
+    result?;
+
+    let value = sqlx::query(&format!("SELECT CAST(@@{}.{} AS CHAR)", scope, name))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to read variable: {}", e))?;
+    Ok(ServerVariable {
+        value: text(&value, 0),
+        name,
+    })
+}
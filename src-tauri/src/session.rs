@@ -0,0 +1,174 @@
+use crate::db::{connection_db_type, DbState};
+use crate::mysql_manager::acquire_connection;
+use crate::sqlite_manager::get_or_create_pool;
+use crate::state::AppState;
+use sqlx::pool::PoolConnection;
+use sqlx::{Connection as _, Database, MySql, MySqlConnection, Sqlite, SqliteConnection};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{command, State};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+// 会话独占的连接，不归还连接池；同一会话中的查询依次执行
+pub enum SessionConnection {
+    MySql(Arc<Mutex<MySqlConnection>>),
+    Sqlite(Arc<Mutex<SqliteConnection>>),
+}
+
+// 编辑器标签页的查询会话：事务、USE 的库、会话变量都保留在自己的连接上
+pub struct QuerySession {
+    pub connection_id: i64,
+    pub connection: SessionConnection,
+}
+
+// 执行查询使用的连接：连接池中的连接，或会话独占的连接
+pub enum QueryConnection<DB: Database> {
+    Pooled(PoolConnection<DB>),
+    Session(OwnedMutexGuard<DB::Connection>),
+}
+
+impl<DB: Database> Deref for QueryConnection<DB> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            QueryConnection::Pooled(conn) => conn,
+            QueryConnection::Session(conn) => conn,
+        }
+    }
+}
+
+impl<DB: Database> DerefMut for QueryConnection<DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            QueryConnection::Pooled(conn) => conn,
+            QueryConnection::Session(conn) => conn,
+        }
+    }
+}
+
+fn session_not_found(session_id: &str) -> String {
+    format!(
+        "Session {} not found or belongs to another connection",
+        session_id
+    )
+}
+
+// 取会话的 MySQL 连接（等待该会话中正在执行的查询结束）
+pub async fn mysql_session(
+    app_state: &AppState,
+    session_id: &str,
+    connection_id: i64,
+) -> Result<QueryConnection<MySql>, String> {
+    let conn = match app_state.sessions.lock().await.get(session_id) {
+        Some(QuerySession {
+            connection_id: id,
+            connection: SessionConnection::MySql(conn),
+        }) if *id == connection_id => conn.clone(),
+        _ => return Err(session_not_found(session_id)),
+    };
+    Ok(QueryConnection::Session(conn.lock_owned().await))
+}
+
+pub async fn sqlite_session(
+    app_state: &AppState,
+    session_id: &str,
+    connection_id: i64,
+) -> Result<QueryConnection<Sqlite>, String> {
+    let conn = match app_state.sessions.lock().await.get(session_id) {
+        Some(QuerySession {
+            connection_id: id,
+            connection: SessionConnection::Sqlite(conn),
+        }) if *id == connection_id => conn.clone(),
+        _ => return Err(session_not_found(session_id)),
+    };
+    Ok(QueryConnection::Session(conn.lock_owned().await))
+}
+
+async fn close_session_connection(connection: SessionConnection) {
+    // 仍有查询持有连接时由最后一个持有者释放
+    match connection {
+        SessionConnection::MySql(conn) => {
+            if let Ok(conn) = Arc::try_unwrap(conn) {
+                let _ = conn.into_inner().close().await;
+            }
+        }
+        SessionConnection::Sqlite(conn) => {
+            if let Ok(conn) = Arc::try_unwrap(conn) {
+                let _ = conn.into_inner().close().await;
+            }
+        }
+    }
+}
+
+// 连接关闭时关闭它的全部会话
+pub async fn close_connection_sessions(app_state: &AppState, connection_id: Option<i64>) {
+    let sessions: Vec<QuerySession> = {
+        let mut sessions = app_state.sessions.lock().await;
+        let ids: Vec<String> = sessions
+            .iter()
+            .filter(|(_, session)| connection_id.is_none_or(|id| id == session.connection_id))
+            .map(|(session_id, _)| session_id.clone())
+            .collect();
+        ids.iter().filter_map(|id| sessions.remove(id)).collect()
+    };
+    for session in sessions {
+        close_session_connection(session.connection).await;
+    }
+}
+
+// 为编辑器标签页打开独占连接（MySQL 系 / SQLite），返回会话 id。
+// 执行 SQL 时传入 session_id 即在该连接上执行；标签页关闭时调用 close_session
+#[command]
+pub async fn open_session(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Result<String, String> {
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    let connection = match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            let conn = acquire_connection(&app_state, &db_state, connection_id, db_name).await?;
+            SessionConnection::MySql(Arc::new(Mutex::new(conn.detach())))
+        }
+        "sqlite" => {
+            let pool = get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            SessionConnection::Sqlite(Arc::new(Mutex::new(conn.detach())))
+        }
+        other => return Err(format!("Sessions are not supported for {}", other)),
+    };
+
+    let session_id = format!(
+        "session-{}",
+        NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    app_state.sessions.lock().await.insert(
+        session_id.clone(),
+        QuerySession {
+            connection_id,
+            connection,
+        },
+    );
+    Ok(session_id)
+}
+
+// 关闭会话：未提交的事务随连接关闭回滚
+#[command]
+pub async fn close_session(
+    app_state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), String> {
+    let session = app_state.sessions.lock().await.remove(&session_id);
+    if let Some(session) = session {
+        close_session_connection(session.connection).await;
+    }
+    Ok(())
+}
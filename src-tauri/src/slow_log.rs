@@ -0,0 +1,555 @@
+use crate::db::{connection_db_type, DbState};
+use crate::models::{SlowLogReport, SlowQueryDigest};
+use crate::mysql_manager;
+use crate::state::AppState;
+use chrono::{DateTime, Local, NaiveDateTime};
+use futures_util::TryStreamExt;
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+const SLOW_LOG_DEFAULT_LIMIT: usize = 100;
+const SLOW_LOG_MAX_LIMIT: usize = 1000;
+
+// 一条慢查询记录
+#[derive(Default)]
+struct SlowLogEntry {
+    time: Option<NaiveDateTime>,
+    database: Option<String>,
+    query_time: f64,
+    lock_time: f64,
+    rows_sent: u64,
+    rows_examined: u64,
+    sql: String,
+}
+
+// 时间范围 [from, to)，两端都可以不限
+struct TimeRange {
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+}
+
+impl TimeRange {
+    fn contains(&self, time: Option<NaiveDateTime>) -> bool {
+        match time {
+            Some(time) => {
+                self.from.is_none_or(|from| time >= from) && self.to.is_none_or(|to| time < to)
+            }
+            // 没有时间的记录只在不限时间时保留
+            None => self.from.is_none() && self.to.is_none(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SlowLogStats {
+    entries_scanned: u64,
+    entries_matched: u64,
+    digests: HashMap<String, SlowQueryDigest>,
+}
+
+impl SlowLogStats {
+    fn add(&mut self, entry: SlowLogEntry, range: &TimeRange) {
+        self.entries_scanned += 1;
+        let sql = entry.sql.trim().trim_end_matches(';').trim_end();
+        if sql.is_empty() || !range.contains(entry.time) {
+            return;
+        }
+        self.entries_matched += 1;
+        let fingerprint = fingerprint(sql);
+        let digest = self
+            .digests
+            .entry(fingerprint.clone())
+            .or_insert_with(|| SlowQueryDigest {
+                fingerprint,
+                example: String::new(),
+                database: None,
+                count: 0,
+                total_time_secs: 0.0,
+                avg_time_secs: 0.0,
+                max_time_secs: 0.0,
+                total_lock_secs: 0.0,
+                rows_sent: 0,
+                rows_examined: 0,
+                first_seen: None,
+                last_seen: None,
+            });
+        digest.count += 1;
+        digest.total_time_secs += entry.query_time;
+        digest.total_lock_secs += entry.lock_time;
+        digest.rows_sent += entry.rows_sent;
+        digest.rows_examined += entry.rows_examined;
+        if digest.example.is_empty() || entry.query_time > digest.max_time_secs {
+            digest.max_time_secs = entry.query_time;
+            digest.example = sql.to_string();
+        }
+        if digest.database.is_none() {
+            digest.database = entry.database;
+        }
+        if let Some(time) = entry.time {
+            digest.first_seen = Some(digest.first_seen.map_or(time, |t| t.min(time)));
+            digest.last_seen = Some(digest.last_seen.map_or(time, |t| t.max(time)));
+        }
+    }
+}
+
+// 查询指纹：去掉注释，字符串和数字替换为 ?，关键字和标识符转小写，合并空白，
+// 再把 IN 列表和 VALUES 的多行合并为一项，使只有参数不同的语句归为一类
+fn fingerprint(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let push_space = |out: &mut String| {
+        if !out.is_empty() && !out.ends_with(' ') && !out.ends_with('(') {
+            out.push(' ');
+        }
+    };
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            push_space(&mut out);
+            i += 1;
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+            push_space(&mut out);
+        } else if c == '#'
+            || (c == '-' && next == Some('-') && chars.get(i + 2).is_none_or(|c| c.is_whitespace()))
+        {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            push_space(&mut out);
+        } else if c == '\'' || c == '"' {
+            // 反斜杠转义和连写的两个引号都不结束字符串
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\\' {
+                    i += 2;
+                    continue;
+                }
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            out.push('?');
+        } else if c == '`' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '`' {
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            out.extend(chars[start..i].iter().flat_map(|c| c.to_lowercase()));
+        } else if c.is_ascii_digit() {
+            // 整数、小数、科学计数法和 0x 十六进制
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            out.push('?');
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            // 整个标识符一起读取，t1 中的 1 不当作数字
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                out.extend(chars[i].to_lowercase());
+                i += 1;
+            }
+        } else if matches!(c, ',' | '(' | ')') {
+            // 括号和逗号前的空白统一去掉，in(1) 与 in (1) 归为一类
+            if out.ends_with(' ') {
+                out.pop();
+            }
+            out.push(c);
+            if c == ',' {
+                out.push(' ');
+            }
+            i += 1;
+        } else {
+            out.extend(c.to_lowercase());
+            i += 1;
+        }
+    }
+    let mut out = out.trim().to_string();
+    // 只合并 IN 列表中的参数，SELECT 列表、函数参数的个数不同仍是不同的语句
+    while out.contains(" in(?, ?") {
+        out = out.replace(" in(?, ?", " in(?");
+    }
+    // VALUES 后相同的多行合并为一行
+    if let Some(start) = out.find(" values(").map(|i| i + " values".len()) {
+        if let Some(end) = out[start..].find(')').map(|i| start + i + 1) {
+            let row = format!(",{}", &out[start..end]);
+            while out[end..].starts_with(&row) {
+                out.replace_range(end..end + row.len(), "");
+            }
+        }
+    }
+    out
+}
+
+// 把 "[+-]HH:MM:SS[.ffffff]" 形式的 TIME 转为秒数，小时可以超过 24
+fn time_secs(text: &str) -> f64 {
+    let mut total = 0.0;
+    for part in text.trim().trim_start_matches('-').split(':') {
+        total = total * 60.0 + part.parse::<f64>().unwrap_or(0.0);
+    }
+    total
+}
+
+fn parse_range_time(label: &str, text: Option<String>) -> Result<Option<NaiveDateTime>, String> {
+    let Some(text) = text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(&text, format).ok())
+    .map(Some)
+    .ok_or_else(|| format!("Invalid {} time: {}", label, text))
+}
+
+// "# Time:" 行：MySQL 5.7+ 为 ISO 8601（log_timestamps 为 UTC 时带 Z），
+// MariaDB 和旧版 MySQL 为 "YYMMDD HH:MM:SS"，小时可能只有一位
+fn parse_log_time(text: &str) -> Option<NaiveDateTime> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Local).naive_local());
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(&text, "%y%m%d %H:%M:%S"))
+        .ok()
+}
+
+// "# Query_time: 1.5  Lock_time: 0.0 Rows_sent: 1  Rows_examined: 10" 以及
+// MariaDB 的 "# Thread_id: 8  Schema: test  QC_hit: No"
+fn parse_header_fields(header: &str, entry: &mut SlowLogEntry, database: &mut Option<String>) {
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    for pair in tokens.windows(2) {
+        let value = pair[1];
+        match pair[0] {
+            "Query_time:" => entry.query_time = value.parse().unwrap_or(0.0),
+            "Lock_time:" => entry.lock_time = value.parse().unwrap_or(0.0),
+            "Rows_sent:" => entry.rows_sent = value.parse().unwrap_or(0),
+            "Rows_examined:" => entry.rows_examined = value.parse().unwrap_or(0),
+            "Schema:" if !value.ends_with(':') => {
+                *database = Some(value.to_string());
+                entry.database = database.clone();
+            }
+            _ => {}
+        }
+    }
+}
+
+// 按行解析慢查询日志文件：每条记录以若干 "#" 开头的行开始，之后是可选的 use / SET timestamp
+// 和语句本身。文件可能很大，边读边汇总；非 UTF-8 内容按有损方式转换
+async fn scan_slow_log_file(
+    path: &Path,
+    range: &TimeRange,
+    stats: &mut SlowLogStats,
+) -> Result<(), String> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open slow query log {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let mut buf = Vec::new();
+    // MariaDB 只在时间变化时写 "# Time:"，use 也只在库变化时写，后续记录沿用
+    let mut header_time = None;
+    let mut database = None;
+    let mut entry = SlowLogEntry::default();
+    loop {
+        buf.clear();
+        let read = reader
+            .read_until(b'\n', &mut buf)
+            .await
+            .map_err(|e| format!("Failed to read slow query log: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if let Some(header) = line.strip_prefix('#') {
+            if !entry.sql.is_empty() {
+                let next = SlowLogEntry {
+                    time: header_time,
+                    database: database.clone(),
+                    ..Default::default()
+                };
+                stats.add(std::mem::replace(&mut entry, next), range);
+            }
+            let header = header.trim();
+            match header.strip_prefix("Time:") {
+                Some(time) => {
+                    header_time = parse_log_time(time.trim());
+                    entry.time = header_time;
+                }
+                None => parse_header_fields(header, &mut entry, &mut database),
+            }
+            continue;
+        }
+        // 服务端启动时写入的说明行
+        if line.contains(", Version: ")
+            || line.starts_with("Tcp port:")
+            || (line.starts_with("Time ") && line.contains("Command"))
+        {
+            continue;
+        }
+        if let Some(timestamp) = line.strip_prefix("SET timestamp=") {
+            if let Ok(secs) = timestamp.trim_end_matches(';').parse::<i64>() {
+                entry.time = DateTime::from_timestamp(secs, 0)
+                    .map(|time| time.with_timezone(&Local).naive_local());
+            }
+            continue;
+        }
+        if entry.sql.is_empty() {
+            let used = line
+                .strip_prefix("use ")
+                .and_then(|rest| rest.strip_suffix(';'))
+                .map(|name| name.trim().trim_matches('`'))
+                .filter(|name| !name.is_empty() && !name.contains(char::is_whitespace));
+            if let Some(name) = used {
+                database = Some(name.to_string());
+                entry.database = database.clone();
+                continue;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+        }
+        if !entry.sql.is_empty() {
+            entry.sql.push('\n');
+        }
+        entry.sql.push_str(line);
+    }
+    if !entry.sql.is_empty() {
+        stats.add(entry, range);
+    }
+    Ok(())
+}
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// log_output 含 TABLE 时记录在 mysql.slow_log 中
+async fn scan_slow_log_table(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    range: &TimeRange,
+    stats: &mut SlowLogStats,
+) -> Result<(), String> {
+    let format =
+        |time: Option<NaiveDateTime>| time.map(|t| t.format("%Y-%m-%d %H:%M:%S%.f").to_string());
+    let (from, to) = (format(range.from), format(range.to));
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, None).await?;
+    let mut rows = sqlx::query(
+        "SELECT CAST(start_time AS CHAR), CAST(query_time AS CHAR), CAST(lock_time AS CHAR), \
+         CAST(rows_sent AS SIGNED), CAST(rows_examined AS SIGNED), CAST(db AS CHAR), \
+         CAST(CONVERT(sql_text USING utf8mb4) AS CHAR) FROM mysql.slow_log \
+         WHERE (? IS NULL OR start_time >= ?) AND (? IS NULL OR start_time < ?)",
+    )
+    .bind(&from)
+    .bind(&from)
+    .bind(&to)
+    .bind(&to)
+    .fetch(&mut *conn);
+    while let Some(row) = rows
+        .try_next()
+        .await
+        .map_err(|e| format!("Failed to read mysql.slow_log: {}", e))?
+    {
+        let count = |index: usize| {
+            row.try_get_unchecked::<Option<i64>, _>(index)
+                .ok()
+                .flatten()
+                .unwrap_or(0)
+                .max(0) as u64
+        };
+        let entry = SlowLogEntry {
+            time: text(&row, 0)
+                .and_then(|t| NaiveDateTime::parse_from_str(&t, "%Y-%m-%d %H:%M:%S%.f").ok()),
+            query_time: text(&row, 1).map(|t| time_secs(&t)).unwrap_or(0.0),
+            lock_time: text(&row, 2).map(|t| time_secs(&t)).unwrap_or(0.0),
+            rows_sent: count(3),
+            rows_examined: count(4),
+            database: text(&row, 5).filter(|db| !db.is_empty()),
+            sql: text(&row, 6).unwrap_or_default(),
+        };
+        stats.add(entry, range);
+    }
+    Ok(())
+}
+
+// 读取并解析慢查询日志，按查询指纹汇总次数、耗时、扫描行数等。source 为 table 时读
+// mysql.slow_log，为 file 时读日志文件；未指定时按服务端的 log_output 选择。
+// 日志文件在服务端机器上，只有与本机相同（或 file_path 指向复制到本地的文件）时才能读取。
+// from / to 按 "YYYY-MM-DD HH:MM:SS" 过滤记录时间（本地时间）
+#[command]
+pub async fn read_slow_log(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    source: Option<String>,
+    file_path: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<usize>,
+) -> Result<SlowLogReport, String> {
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    if !matches!(db_type.as_str(), "mysql" | "mariadb") {
+        return Err(format!("Slow query log is not supported for {}", db_type));
+    }
+    let range = TimeRange {
+        from: parse_range_time("start", from)?,
+        to: parse_range_time("end", to)?,
+    };
+    let limit = limit
+        .unwrap_or(SLOW_LOG_DEFAULT_LIMIT)
+        .clamp(1, SLOW_LOG_MAX_LIMIT);
+    let file_path = file_path.filter(|p| !p.trim().is_empty());
+
+    let settings = {
+        let mut conn =
+            mysql_manager::acquire_connection(&app_state, &db_state, connection_id, None).await?;
+        sqlx::query(
+            "SELECT CAST(@@GLOBAL.log_output AS CHAR), CAST(@@GLOBAL.slow_query_log_file AS CHAR), \
+             CAST(@@GLOBAL.slow_query_log AS SIGNED), CAST(@@GLOBAL.datadir AS CHAR)",
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch slow log settings: {}", e))?
+    };
+    let log_output = text(&settings, 0).unwrap_or_default();
+    let slow_log_enabled = settings
+        .try_get_unchecked::<Option<i64>, _>(2)
+        .ok()
+        .flatten()
+        == Some(1);
+    let source = match source.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        Some("table") => "table",
+        Some("file") => "file",
+        None | Some("") if file_path.is_some() => "file",
+        None | Some("") if log_output.to_ascii_uppercase().contains("TABLE") => "table",
+        None | Some("") => "file",
+        Some(other) => return Err(format!("Unsupported slow log source: {}", other)),
+    };
+
+    let mut stats = SlowLogStats::default();
+    let file_path = match source {
+        "table" => {
+            scan_slow_log_table(&app_state, &db_state, connection_id, &range, &mut stats).await?;
+            None
+        }
+        _ => {
+            // 相对路径相对于服务端的数据目录
+            let path = match file_path {
+                Some(path) => PathBuf::from(path),
+                None => {
+                    let path = text(&settings, 1)
+                        .filter(|p| !p.is_empty())
+                        .ok_or("Slow query log file is not configured")?;
+                    let path = PathBuf::from(path);
+                    match (path.is_relative(), text(&settings, 3)) {
+                        (true, Some(datadir)) => Path::new(&datadir).join(path),
+                        _ => path,
+                    }
+                }
+            };
+            scan_slow_log_file(&path, &range, &mut stats).await?;
+            Some(path.to_string_lossy().into_owned())
+        }
+    };
+
+    let mut digests: Vec<SlowQueryDigest> = stats
+        .digests
+        .into_values()
+        .map(|mut digest| {
+            digest.avg_time_secs = digest.total_time_secs / digest.count as f64;
+            digest
+        })
+        .collect();
+    digests.sort_by(|a, b| {
+        b.total_time_secs
+            .total_cmp(&a.total_time_secs)
+            .then_with(|| a.fingerprint.cmp(&b.fingerprint))
+    });
+    let truncated = digests.len() > limit;
+    digests.truncate(limit);
+    Ok(SlowLogReport {
+        source: source.to_string(),
+        file_path,
+        slow_log_enabled,
+        entries_scanned: stats.entries_scanned,
+        entries_matched: stats.entries_matched,
+        digests,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapses_in_lists_and_values_rows() {
+        assert_eq!(
+            fingerprint("SELECT * FROM t WHERE id IN (1, 2, 3)"),
+            fingerprint("select * from t where id in(4)")
+        );
+        assert_eq!(
+            fingerprint("SELECT * FROM t WHERE id IN (1, 2, 3)"),
+            "select * from t where id in(?)"
+        );
+        assert_eq!(
+            fingerprint("INSERT INTO t VALUES (1, 'a'), (2, 'b'), (3, 'c')"),
+            "insert into t values(?, ?)"
+        );
+        assert_eq!(
+            fingerprint("INSERT INTO t (a) VALUES (1),(2)"),
+            fingerprint("INSERT INTO t (a) VALUES (9)")
+        );
+        assert_eq!(
+            fingerprint("SELECT a FROM t WHERE x IN ('a','b') AND y IN ( 1 ,2 )"),
+            "select a from t where x in(?) and y in(?)"
+        );
+        // IN 之外的参数列表不合并
+        assert_eq!(
+            fingerprint("SELECT min(a, b), 1, 2 FROM t"),
+            "select min(a, b), ?, ? from t"
+        );
+    }
+
+    #[test]
+    fn normalizes_literals_comments_and_whitespace() {
+        assert_eq!(
+            fingerprint("SELECT  *\n FROM t1 /* c */ WHERE a = 'it''s' AND b = 1.5e3 # x\n"),
+            "select * from t1 where a = ? and b = ?"
+        );
+        assert_eq!(
+            fingerprint("SELECT 'a\\'b', 0x1F FROM `MyTable` -- c"),
+            "select ?, ? from `mytable`"
+        );
+        // -- 后不是空白时不是注释
+        assert_eq!(fingerprint("SELECT 1--1"), "select ?--?");
+    }
+}
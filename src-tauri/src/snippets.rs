@@ -0,0 +1,261 @@
+use crate::db::DbState;
+use crate::models::{Snippet, SnippetArgs};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use tauri::{command, State};
+
+const SNIPPET_LANGUAGES: &[&str] = &["sql", "redis"];
+
+// content 中的一段：普通文本，或 ${name} / ${name:默认值} 占位符；$${ 表示字面量 ${
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder {
+        name: &'a str,
+        default: Option<&'a str>,
+    },
+}
+
+fn parse_segments(content: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("${") {
+        if start > 0 && rest.as_bytes()[start - 1] == b'$' {
+            segments.push(Segment::Text(&rest[..start - 1]));
+            segments.push(Segment::Text("${"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let inner = &rest[start + 2..start + 2 + len];
+        let (name, default) = match inner.split_once(':') {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (inner.trim(), None),
+        };
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        if valid {
+            segments.push(Segment::Text(&rest[..start]));
+            segments.push(Segment::Placeholder { name, default });
+        } else {
+            segments.push(Segment::Text(&rest[..start + 3 + len]));
+        }
+        rest = &rest[start + 3 + len..];
+    }
+    segments.push(Segment::Text(rest));
+    segments
+}
+
+fn placeholders_of(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in parse_segments(content) {
+        if let Segment::Placeholder { name, .. } = segment {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+fn with_placeholders(mut snippet: Snippet) -> Snippet {
+    snippet.placeholders = placeholders_of(&snippet.content);
+    snippet
+}
+
+fn validate_args(args: &SnippetArgs) -> Result<(), String> {
+    if args.name.trim().is_empty() {
+        return Err("Snippet name is required".to_string());
+    }
+    if args.content.trim().is_empty() {
+        return Err("Snippet content is required".to_string());
+    }
+    if let Some(language) = &args.language {
+        if !SNIPPET_LANGUAGES.contains(&language.as_str()) {
+            return Err(format!("Unsupported snippet language: {}", language));
+        }
+    }
+    Ok(())
+}
+
+// 目录路径统一为 "a/b" 形式，空路径表示根目录
+fn normalize_folder(folder: Option<String>) -> Option<String> {
+    folder
+        .map(|f| {
+            f.split('/')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .filter(|f| !f.is_empty())
+}
+
+pub async fn fetch_snippet_row(pool: &SqlitePool, id: i64) -> Result<Snippet, String> {
+    sqlx::query_as::<_, Snippet>("SELECT * FROM snippets WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch snippet: {}", e))?
+        .ok_or_else(|| "Snippet not found".to_string())
+        .map(with_placeholders)
+}
+
+// 列出片段：指定连接时返回该连接的片段和全局片段，否则只返回全局片段；folder 包含子目录
+#[command]
+pub async fn list_snippets(
+    db_state: State<'_, DbState>,
+    connection_id: Option<i64>,
+    folder: Option<String>,
+) -> Result<Vec<Snippet>, String> {
+    let folder = normalize_folder(folder);
+    let sub_folders = folder.as_ref().map(|f| format!("{}/%", f));
+    let snippets = sqlx::query_as::<_, Snippet>(
+        "SELECT * FROM snippets \
+         WHERE (connection_id IS NULL OR connection_id = ?) \
+         AND (? IS NULL OR folder = ? OR folder LIKE ?) \
+         ORDER BY folder ASC, name ASC",
+    )
+    .bind(connection_id)
+    .bind(&folder)
+    .bind(&folder)
+    .bind(&sub_folders)
+    .fetch_all(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to list snippets: {}", e))?;
+    Ok(snippets.into_iter().map(with_placeholders).collect())
+}
+
+// 按名称、内容、描述搜索片段，范围同 list_snippets
+#[command]
+pub async fn search_snippets(
+    db_state: State<'_, DbState>,
+    keyword: String,
+    connection_id: Option<i64>,
+) -> Result<Vec<Snippet>, String> {
+    let pattern = format!("%{}%", keyword.trim());
+    let snippets = sqlx::query_as::<_, Snippet>(
+        "SELECT * FROM snippets \
+         WHERE (connection_id IS NULL OR connection_id = ?) \
+         AND (name LIKE ? OR content LIKE ? OR description LIKE ?) \
+         ORDER BY name ASC",
+    )
+    .bind(connection_id)
+    .bind(&pattern)
+    .bind(&pattern)
+    .bind(&pattern)
+    .fetch_all(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to search snippets: {}", e))?;
+    Ok(snippets.into_iter().map(with_placeholders).collect())
+}
+
+#[command]
+pub async fn create_snippet(
+    db_state: State<'_, DbState>,
+    args: SnippetArgs,
+) -> Result<Snippet, String> {
+    validate_args(&args)?;
+    let pool = &db_state.pool;
+    let result = sqlx::query(
+        "INSERT INTO snippets (name, content, language, folder, description, connection_id) \
+         VALUES (?, ?, COALESCE(?, 'sql'), ?, ?, ?)",
+    )
+    .bind(args.name.trim())
+    .bind(&args.content)
+    .bind(&args.language)
+    .bind(normalize_folder(args.folder))
+    .bind(&args.description)
+    .bind(args.connection_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create snippet: {}", e))?;
+
+    fetch_snippet_row(pool, result.last_insert_rowid()).await
+}
+
+#[command]
+pub async fn update_snippet(
+    db_state: State<'_, DbState>,
+    snippet_id: i64,
+    args: SnippetArgs,
+) -> Result<Snippet, String> {
+    validate_args(&args)?;
+    let pool = &db_state.pool;
+    let result = sqlx::query(
+        "UPDATE snippets SET name = ?, content = ?, language = COALESCE(?, language), \
+         folder = ?, description = ?, connection_id = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE id = ?",
+    )
+    .bind(args.name.trim())
+    .bind(&args.content)
+    .bind(&args.language)
+    .bind(normalize_folder(args.folder))
+    .bind(&args.description)
+    .bind(args.connection_id)
+    .bind(snippet_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update snippet: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err("Snippet not found".to_string());
+    }
+
+    fetch_snippet_row(pool, snippet_id).await
+}
+
+#[command]
+pub async fn delete_snippet(db_state: State<'_, DbState>, snippet_id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM snippets WHERE id = ?")
+        .bind(snippet_id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete snippet: {}", e))?;
+    Ok(())
+}
+
+// 替换 content 中的占位符，没有值也没有默认值的占位符会报错
+pub fn render_snippet(content: &str, values: &HashMap<String, String>) -> Result<String, String> {
+    let mut resolved = String::with_capacity(content.len());
+    let mut missing: Vec<&str> = Vec::new();
+    for segment in parse_segments(content) {
+        match segment {
+            Segment::Text(text) => resolved.push_str(text),
+            Segment::Placeholder { name, default } => match values.get(name).map(String::as_str) {
+                Some(value) => resolved.push_str(value),
+                None => match default {
+                    Some(default) => resolved.push_str(default),
+                    None if !missing.contains(&name) => missing.push(name),
+                    None => {}
+                },
+            },
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing values for placeholders: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(resolved)
+}
+
+// 执行前替换占位符：传 snippet_id 读取保存的片段，或直接传 content（编辑器中未保存的内容）。
+// 值按原样替换，不做引号转义；没有值也没有默认值的占位符会报错
+#[command]
+pub async fn resolve_snippet(
+    db_state: State<'_, DbState>,
+    snippet_id: Option<i64>,
+    content: Option<String>,
+    values: HashMap<String, String>,
+) -> Result<String, String> {
+    let content = match (snippet_id, content) {
+        (Some(id), _) => fetch_snippet_row(&db_state.pool, id).await?.content,
+        (None, Some(content)) => content,
+        (None, None) => return Err("Either snippet_id or content is required".to_string()),
+    };
+    render_snippet(&content, &values)
+}
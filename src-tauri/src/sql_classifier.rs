@@ -0,0 +1,597 @@
+use sqlparser::ast::{
+    Expr, FromTable, GroupByExpr, ObjectName, Query, SelectItem, SetExpr, Statement, TableFactor,
+    TableObject, TableWithJoins, UpdateTableFromKind,
+};
+use sqlparser::dialect::{Dialect, MySqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Location, Token, Tokenizer};
+
+#[derive(Debug, Clone, Copy)]
+pub enum SqlFlavor {
+    MySql,
+    Sqlite,
+}
+
+// 解析失败（方言特有语法等）时按首个关键字判断
+const ROW_RETURNING_KEYWORDS: &[&str] = &[
+    "SELECT", "WITH", "VALUES", "TABLE", "SHOW", "DESCRIBE", "DESC", "EXPLAIN", "CALL", "PRAGMA",
+    "HELP",
+];
+
+// 判断 SQL 是否会返回结果集：返回结果集的用 fetch 读取行，否则用 execute 取影响行数。
+// 多条语句时只要有一条返回结果集即按查询处理
+pub fn returns_rows(sql: &str, flavor: SqlFlavor) -> bool {
+    match Parser::parse_sql(dialect_of(flavor), sql) {
+        Ok(statements) => statements.iter().any(statement_returns_rows),
+        Err(_) => first_keyword(sql)
+            .is_some_and(|keyword| ROW_RETURNING_KEYWORDS.contains(&keyword.as_str())),
+    }
+}
+
+// 调用方不区分方言时使用：先按 MySQL 解析，失败再按 SQLite
+pub fn parse_statements(sql: &str) -> Option<Vec<Statement>> {
+    [SqlFlavor::MySql, SqlFlavor::Sqlite]
+        .into_iter()
+        .find_map(|flavor| Parser::parse_sql(dialect_of(flavor), sql).ok())
+}
+
+fn dialect_of(flavor: SqlFlavor) -> &'static dyn Dialect {
+    match flavor {
+        SqlFlavor::MySql => &MySqlDialect {},
+        SqlFlavor::Sqlite => &SQLiteDialect {},
+    }
+}
+
+fn statement_returns_rows(statement: &Statement) -> bool {
+    match statement {
+        // SELECT / WITH ... SELECT / VALUES / TABLE / UNION 等
+        Statement::Query(_)
+        | Statement::Call(_)
+        | Statement::Explain { .. }
+        | Statement::ExplainTable { .. }
+        | Statement::Pragma { .. }
+        | Statement::ShowFunctions { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowStatus { .. }
+        | Statement::ShowVariables { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowCatalogs { .. }
+        | Statement::ShowDatabases { .. }
+        | Statement::ShowProcessList { .. }
+        | Statement::ShowSchemas { .. }
+        | Statement::ShowCharset(_)
+        | Statement::ShowObjects(_)
+        | Statement::ShowTables { .. }
+        | Statement::ShowViews { .. }
+        | Statement::ShowCollation { .. } => true,
+        // SQLite 的 INSERT / UPDATE / DELETE ... RETURNING
+        Statement::Insert(insert) => insert.returning.is_some(),
+        Statement::Update(update) => update.returning.is_some(),
+        Statement::Delete(delete) => delete.returning.is_some(),
+        _ => false,
+    }
+}
+
+// 跳过开头的空白、注释（-- / # / /* */）和左括号，返回第一个关键字（大写）
+pub fn first_keyword(sql: &str) -> Option<String> {
+    let mut rest = sql;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '(');
+        if rest.starts_with("--") || rest.starts_with('#') {
+            rest = rest.find('\n').map(|i| &rest[i + 1..]).unwrap_or("");
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.find("*/").map(|i| &comment[i + 2..]).unwrap_or("");
+        } else {
+            break;
+        }
+    }
+    let keyword: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    (!keyword.is_empty()).then(|| keyword.to_uppercase())
+}
+
+// 单条 SELECT / INSERT / REPLACE / UPDATE / DELETE，可以用 EXPLAIN 查看执行计划
+pub fn is_explainable(sql: &str, flavor: SqlFlavor) -> bool {
+    matches!(
+        Parser::parse_sql(dialect_of(flavor), sql).as_deref(),
+        Ok([Statement::Query(_)
+            | Statement::Insert(_)
+            | Statement::Update(_)
+            | Statement::Delete(_)])
+    )
+}
+
+// 只读查询：每条语句都是 SELECT（不含 SELECT ... INTO 和 FOR UPDATE 等锁定读），
+// 结果可以缓存；CALL、SHOW 等不算
+pub fn is_read_query(sql: &str, flavor: SqlFlavor) -> bool {
+    let Ok(statements) = Parser::parse_sql(dialect_of(flavor), sql) else {
+        return false;
+    };
+    !statements.is_empty() && statements.iter().all(|statement| match statement {
+        Statement::Query(query) => {
+            query.locks.is_empty()
+                && !matches!(query.body.as_ref(), SetExpr::Select(select) if select.into.is_some())
+        }
+        _ => false,
+    })
+}
+
+// 单条只读 SELECT（含 WITH / UNION），可以作为视图定义
+pub fn is_single_select(sql: &str, flavor: SqlFlavor) -> bool {
+    matches!(
+        Parser::parse_sql(dialect_of(flavor), sql).as_deref(),
+        Ok([Statement::Query(_)])
+    ) && is_read_query(sql, flavor)
+}
+
+// 判断 SQL 是否为 INSERT / REPLACE，用于决定是否返回 last_insert_id
+pub fn is_insert(sql: &str, flavor: SqlFlavor) -> bool {
+    match Parser::parse_sql(dialect_of(flavor), sql) {
+        Ok(statements) => matches!(statements.last(), Some(Statement::Insert(_))),
+        Err(_) => {
+            first_keyword(sql).is_some_and(|keyword| keyword == "INSERT" || keyword == "REPLACE")
+        }
+    }
+}
+
+// 按首个关键字判断 SQL 是否会改变库表结构（CREATE / ALTER / DROP / RENAME），
+// 用于让自动补全的元数据缓存失效
+pub fn changes_schema(sql: &str) -> bool {
+    matches!(
+        first_keyword(sql).as_deref(),
+        Some("CREATE" | "ALTER" | "DROP" | "RENAME")
+    )
+}
+
+// 单表 SELECT 的来源：结果列可以对应回表中的列
+#[derive(Debug)]
+pub struct SelectSource {
+    pub schema: Option<String>,
+    pub table: String,
+    // 结果列名 → 表中的列名；SELECT * 时为空，结果列名即表列名
+    pub columns: Vec<(String, String)>,
+    pub wildcard: bool,
+}
+
+// 解析单表 SELECT 的来源表和列映射。多表 JOIN、子查询、UNION、GROUP BY 等
+// 结果与表中的行不一一对应的查询返回 None
+pub fn single_table_source(sql: &str, flavor: SqlFlavor) -> Option<SelectSource> {
+    let statements = Parser::parse_sql(dialect_of(flavor), sql).ok()?;
+    let [Statement::Query(query)] = statements.as_slice() else {
+        return None;
+    };
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return None;
+    };
+    let grouped =
+        !matches!(&select.group_by, GroupByExpr::Expressions(exprs, _) if exprs.is_empty());
+    if grouped || select.distinct.is_some() || select.having.is_some() || select.from.len() != 1 {
+        return None;
+    }
+    let from = &select.from[0];
+    if !from.joins.is_empty() {
+        return None;
+    }
+    let TableFactor::Table { name, alias, .. } = &from.relation else {
+        return None;
+    };
+    let (schema, table) = split_object_name(name)?;
+    let alias = alias.as_ref().map(|a| a.name.value.as_str());
+
+    let mut source = SelectSource {
+        schema,
+        table,
+        columns: Vec::new(),
+        wildcard: false,
+    };
+    for item in &select.projection {
+        match item {
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => source.wildcard = true,
+            SelectItem::UnnamedExpr(expr) => {
+                if let Some(column) = column_of(expr, &source.table, alias) {
+                    source.columns.push((column.clone(), column));
+                }
+            }
+            SelectItem::ExprWithAlias { expr, alias: name } => {
+                if let Some(column) = column_of(expr, &source.table, alias) {
+                    source.columns.push((name.value.clone(), column));
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(source)
+}
+
+// db.table → (Some(db), table)；table → (None, table)
+fn split_object_name(name: &ObjectName) -> Option<(Option<String>, String)> {
+    let mut parts: Vec<String> = name
+        .0
+        .iter()
+        .filter_map(|part| part.as_ident().map(|ident| ident.value.clone()))
+        .collect();
+    let table = parts.pop()?;
+    Some((parts.pop(), table))
+}
+
+// 投影表达式为本表的列（col / t.col / alias.col）时返回列名
+fn column_of(expr: &Expr, table: &str, alias: Option<&str>) -> Option<String> {
+    match expr {
+        Expr::Identifier(ident) => Some(ident.value.clone()),
+        Expr::CompoundIdentifier(idents) => {
+            let (column, qualifier) = idents.split_last()?;
+            let owner = &qualifier.last()?.value;
+            (owner == table || Some(owner.as_str()) == alias).then(|| column.value.clone())
+        }
+        Expr::Nested(inner) => column_of(inner, table, alias),
+        _ => None,
+    }
+}
+
+// 安全模式：单条 SELECT 没有 LIMIT / FETCH 时在末尾追加 LIMIT n，返回改写后的 SQL；
+// 不需要改写（非查询、已有限制、SELECT ... INTO、FOR UPDATE 等）时返回 None。
+// 追加位置为最后一个有效 token 之后，原文中的注释、换行保持不变
+pub fn with_safe_limit(sql: &str, flavor: SqlFlavor, limit: u64) -> Option<String> {
+    let dialect = dialect_of(flavor);
+    let statements = Parser::parse_sql(dialect, sql).ok()?;
+    let [Statement::Query(query)] = statements.as_slice() else {
+        return None;
+    };
+    if query.limit_clause.is_some() || query.fetch.is_some() || !query.locks.is_empty() {
+        return None;
+    }
+    match query.body.as_ref() {
+        SetExpr::Select(select) if select.into.is_none() => {}
+        SetExpr::SetOperation { .. } => {}
+        _ => return None,
+    }
+
+    let tokens = Tokenizer::new(dialect, sql).tokenize_with_location().ok()?;
+    let last = tokens.iter().rev().find(|t| {
+        !matches!(
+            t.token,
+            Token::Whitespace(_) | Token::SemiColon | Token::EOF
+        )
+    })?;
+    let end = byte_offset(sql, last.span.end)?;
+    Some(format!("{} LIMIT {}{}", &sql[..end], limit, &sql[end..]))
+}
+
+// 去掉单条查询末尾的 ORDER BY / LIMIT / OFFSET，用于统计分页前的总行数。
+// 非查询语句、SELECT ... INTO 等返回 None
+pub fn without_paging(sql: &str, flavor: SqlFlavor) -> Option<String> {
+    let mut statements = Parser::parse_sql(dialect_of(flavor), sql).ok()?;
+    let [Statement::Query(query)] = statements.as_mut_slice() else {
+        return None;
+    };
+    if let SetExpr::Select(select) = query.body.as_ref() {
+        if select.into.is_some() {
+            return None;
+        }
+    }
+    query.order_by = None;
+    query.limit_clause = None;
+    query.fetch = None;
+    Some(query.to_string())
+}
+
+// tokenizer 的位置为 1 起始的行号、字符列号，转换为字节偏移
+fn byte_offset(sql: &str, location: Location) -> Option<usize> {
+    let line_start = match location.line {
+        0 => return None,
+        1 => 0,
+        n => sql.match_indices('\n').nth(n as usize - 2)?.0 + 1,
+    };
+    let line = &sql[line_start..];
+    let column = (location.column as usize).checked_sub(1)?;
+    let offset = line
+        .char_indices()
+        .nth(column)
+        .map_or(line.len(), |(i, _)| i);
+    Some(line_start + offset)
+}
+
+// 单表 UPDATE / DELETE 的目标表和条件，用于执行前快照受影响的行
+#[derive(Debug)]
+pub struct WriteTarget {
+    // "UPDATE" / "DELETE"
+    pub operation: &'static str,
+    pub schema: Option<String>,
+    pub table: String,
+    // 原语句中的表引用（保留引号）；from_clause 另带别名，供快照查询使用
+    pub table_ref: String,
+    pub from_clause: String,
+    pub selection: Option<String>,
+}
+
+// 解析单条单表 UPDATE / DELETE。多表、带 ORDER BY / LIMIT 等无法用同样条件查出受影响行的返回 None
+pub fn single_table_write(sql: &str, flavor: SqlFlavor) -> Option<WriteTarget> {
+    let statements = Parser::parse_sql(dialect_of(flavor), sql).ok()?;
+    let (operation, relation, selection) = match statements.as_slice() {
+        [Statement::Update(update)]
+            if update.from.is_none()
+                && update.table.joins.is_empty()
+                && update.order_by.is_empty()
+                && update.limit.is_none() =>
+        {
+            ("UPDATE", &update.table.relation, &update.selection)
+        }
+        [Statement::Delete(delete)]
+            if delete.tables.is_empty()
+                && delete.using.is_none()
+                && delete.order_by.is_empty()
+                && delete.limit.is_none() =>
+        {
+            let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = &delete.from;
+            let [from] = from.as_slice() else {
+                return None;
+            };
+            if !from.joins.is_empty() {
+                return None;
+            }
+            ("DELETE", &from.relation, &delete.selection)
+        }
+        _ => return None,
+    };
+    let TableFactor::Table { name, .. } = relation else {
+        return None;
+    };
+    let (schema, table) = split_object_name(name)?;
+    Some(WriteTarget {
+        operation,
+        schema,
+        table,
+        table_ref: name.to_string(),
+        from_clause: relation.to_string(),
+        selection: selection.as_ref().map(ToString::to_string),
+    })
+}
+
+// 语句 FROM / JOIN 中引用的表（含派生表、CTE 中的表，去掉 CTE 名本身），按出现顺序去重。
+// WHERE 等表达式里的子查询不计入；解析失败时返回空列表
+pub fn referenced_tables(sql: &str, flavor: SqlFlavor) -> Vec<String> {
+    let mut tables = Vec::new();
+    if let Ok(statements) = Parser::parse_sql(dialect_of(flavor), sql) {
+        for statement in &statements {
+            collect_statement_tables(statement, &mut tables);
+        }
+    }
+    tables
+}
+
+fn collect_statement_tables(statement: &Statement, tables: &mut Vec<String>) {
+    match statement {
+        Statement::Query(query) => collect_query_tables(query, tables),
+        Statement::Insert(insert) => {
+            if let TableObject::TableName(name) = &insert.table {
+                push_table(name, tables);
+            }
+            if let Some(source) = &insert.source {
+                collect_query_tables(source, tables);
+            }
+        }
+        Statement::Update(update) => {
+            collect_from_tables(std::slice::from_ref(&update.table), tables);
+            if let Some(
+                UpdateTableFromKind::BeforeSet(from) | UpdateTableFromKind::AfterSet(from),
+            ) = &update.from
+            {
+                collect_from_tables(from, tables);
+            }
+        }
+        Statement::Delete(delete) => {
+            let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = &delete.from;
+            collect_from_tables(from, tables);
+            if let Some(using) = &delete.using {
+                collect_from_tables(using, tables);
+            }
+        }
+        Statement::Explain { statement, .. } => collect_statement_tables(statement, tables),
+        _ => {}
+    }
+}
+
+fn collect_query_tables(query: &Query, tables: &mut Vec<String>) {
+    let mut cte_names = Vec::new();
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            collect_query_tables(&cte.query, tables);
+            cte_names.push(cte.alias.name.value.clone());
+        }
+    }
+    let mut body_tables = Vec::new();
+    collect_set_expr_tables(&query.body, &mut body_tables);
+    for table in body_tables {
+        if !cte_names.contains(&table) && !tables.contains(&table) {
+            tables.push(table);
+        }
+    }
+}
+
+fn collect_set_expr_tables(body: &SetExpr, tables: &mut Vec<String>) {
+    match body {
+        SetExpr::Select(select) => collect_from_tables(&select.from, tables),
+        SetExpr::Query(query) => collect_query_tables(query, tables),
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_set_expr_tables(left, tables);
+            collect_set_expr_tables(right, tables);
+        }
+        SetExpr::Insert(statement) | SetExpr::Update(statement) | SetExpr::Delete(statement) => {
+            collect_statement_tables(statement, tables)
+        }
+        SetExpr::Table(table) => {
+            if let Some(name) = &table.table_name {
+                let name = match &table.schema_name {
+                    Some(schema) => format!("{}.{}", schema, name),
+                    None => name.clone(),
+                };
+                if !tables.contains(&name) {
+                    tables.push(name);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_from_tables(from: &[TableWithJoins], tables: &mut Vec<String>) {
+    for table in from {
+        collect_factor_tables(&table.relation, tables);
+        for join in &table.joins {
+            collect_factor_tables(&join.relation, tables);
+        }
+    }
+}
+
+fn collect_factor_tables(factor: &TableFactor, tables: &mut Vec<String>) {
+    match factor {
+        TableFactor::Table { name, .. } => push_table(name, tables),
+        TableFactor::Derived { subquery, .. } => collect_query_tables(subquery, tables),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => collect_from_tables(std::slice::from_ref(table_with_joins.as_ref()), tables),
+        _ => {}
+    }
+}
+
+// 表名去掉引号，db.table 形式保留库名
+fn push_table(name: &ObjectName, tables: &mut Vec<String>) {
+    let Some((schema, table)) = split_object_name(name) else {
+        return;
+    };
+    let table = match schema {
+        Some(schema) => format!("{}.{}", schema, table),
+        None => table,
+    };
+    if !tables.contains(&table) {
+        tables.push(table);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_rows_for_queries() {
+        for sql in [
+            "SELECT 1",
+            "  /* c */ select * from t",
+            "WITH x AS (SELECT 1) SELECT * FROM x",
+            "SELECT 1 UNION SELECT 2",
+            "SHOW TABLES",
+            "EXPLAIN SELECT * FROM t",
+            "CALL p()",
+            "INSERT INTO t VALUES (1); SELECT 1",
+        ] {
+            assert!(returns_rows(sql, SqlFlavor::MySql), "{}", sql);
+        }
+        assert!(returns_rows("PRAGMA table_info(t)", SqlFlavor::Sqlite));
+    }
+
+    #[test]
+    fn returns_rows_false_for_writes() {
+        for sql in [
+            "UPDATE t SET a = 1",
+            "DELETE FROM t",
+            "INSERT INTO t VALUES (1)",
+            "CREATE TABLE t (id INT)",
+            "SET @a = 1",
+        ] {
+            assert!(!returns_rows(sql, SqlFlavor::MySql), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn returns_rows_falls_back_to_first_keyword() {
+        // 解析失败时按首个关键字判断，跳过注释
+        assert!(returns_rows(
+            "-- c\nSHOW ENGINE ??? STATUS",
+            SqlFlavor::MySql
+        ));
+        assert!(!returns_rows("FLUSH ??? TABLES", SqlFlavor::MySql));
+    }
+
+    #[test]
+    fn safe_limit_appends_after_last_token() {
+        assert_eq!(
+            with_safe_limit("SELECT * FROM t", SqlFlavor::MySql, 100).as_deref(),
+            Some("SELECT * FROM t LIMIT 100")
+        );
+        assert_eq!(
+            with_safe_limit("SELECT * FROM t ;  -- tail\n", SqlFlavor::MySql, 5).as_deref(),
+            Some("SELECT * FROM t LIMIT 5 ;  -- tail\n")
+        );
+        assert_eq!(
+            with_safe_limit("SELECT '中文'\nFROM t -- 注释", SqlFlavor::MySql, 10).as_deref(),
+            Some("SELECT '中文'\nFROM t LIMIT 10 -- 注释")
+        );
+        assert_eq!(
+            with_safe_limit("SELECT 1 UNION SELECT 2", SqlFlavor::Sqlite, 3).as_deref(),
+            Some("SELECT 1 UNION SELECT 2 LIMIT 3")
+        );
+    }
+
+    #[test]
+    fn safe_limit_skips_limited_and_non_queries() {
+        for sql in [
+            "SELECT * FROM t LIMIT 10",
+            "SELECT * FROM t LIMIT 10 OFFSET 5",
+            "SELECT * FROM t FOR UPDATE",
+            "SELECT * INTO @x FROM t",
+            "SELECT 1; SELECT 2",
+            "UPDATE t SET a = 1",
+            "SHOW TABLES",
+            "not sql at all",
+        ] {
+            assert_eq!(with_safe_limit(sql, SqlFlavor::MySql, 100), None, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn single_table_write_parses_update_and_delete() {
+        let update = single_table_write("UPDATE t SET a = 1 WHERE id = 2", SqlFlavor::MySql)
+            .expect("update target");
+        assert_eq!(update.operation, "UPDATE");
+        assert_eq!(update.schema, None);
+        assert_eq!(update.table, "t");
+        assert_eq!(update.selection.as_deref(), Some("id = 2"));
+
+        let delete = single_table_write("DELETE FROM db.t AS x WHERE x.id = 1", SqlFlavor::MySql)
+            .expect("delete target");
+        assert_eq!(delete.operation, "DELETE");
+        assert_eq!(delete.schema.as_deref(), Some("db"));
+        assert_eq!(delete.table, "t");
+        assert_eq!(delete.table_ref, "db.t");
+        assert_eq!(delete.from_clause, "db.t AS x");
+        assert_eq!(delete.selection.as_deref(), Some("x.id = 1"));
+
+        let quoted = single_table_write("DELETE FROM `my t`", SqlFlavor::MySql).expect("target");
+        assert_eq!(quoted.table, "my t");
+        assert_eq!(quoted.table_ref, "`my t`");
+        assert_eq!(quoted.selection, None);
+    }
+
+    #[test]
+    fn single_table_write_rejects_unsnapshottable_writes() {
+        for sql in [
+            "UPDATE t SET a = 1 ORDER BY id LIMIT 1",
+            "DELETE FROM t LIMIT 1",
+            "UPDATE a JOIN b ON a.id = b.id SET a.x = b.x",
+            "DELETE a FROM a JOIN b ON a.id = b.id",
+            "DELETE FROM a USING a, b WHERE a.id = b.id",
+            "UPDATE t SET a = 1; UPDATE t SET a = 2",
+            "INSERT INTO t VALUES (1)",
+            "SELECT * FROM t",
+        ] {
+            assert!(
+                single_table_write(sql, SqlFlavor::MySql).is_none(),
+                "{}",
+                sql
+            );
+        }
+    }
+}
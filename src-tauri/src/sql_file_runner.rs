@@ -0,0 +1,509 @@
+use crate::autocomplete::invalidate_metadata;
+use crate::db::{connection_db_type, DbState};
+use crate::guard::ensure_sql_file_allowed;
+use crate::mysql_manager::{acquire_connection, register_mysql_query};
+use crate::query_queue::{
+    acquire_query_slot, finish_running_query, new_execution_id, register_running_query,
+    RunningQuery,
+};
+use crate::result_cache::invalidate_results;
+use crate::sql_classifier::{first_keyword, SqlFlavor};
+use crate::sqlite_manager::{get_or_create_pool, SqliteInterruptHandle};
+use crate::state::AppState;
+use libsqlite3_sys::sqlite3_complete;
+use serde::Serialize;
+use sqlx::pool::PoolConnection;
+use sqlx::{Executor, MySql, Sqlite};
+use std::ffi::CString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, Emitter, State};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+// 前端监听该事件展示执行进度
+pub const SQL_FILE_PROGRESS_EVENT: &str = "sql-file-progress";
+
+// 每个事务中执行的语句数，也是发送进度事件的间隔
+const SQL_FILE_DEFAULT_BATCH_SIZE: usize = 200;
+// 结果中保留的错误数
+const SQL_FILE_MAX_REPORTED_ERRORS: usize = 100;
+// 错误中附带的语句长度上限（字符）
+const SQL_FILE_ERROR_STATEMENT_CHARS: usize = 200;
+
+// 文件自带的事务控制语句，出现后不再由这里分批开启事务
+const TRANSACTION_KEYWORDS: &[&str] = &["BEGIN", "START", "COMMIT", "ROLLBACK", "END"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SqlFileError {
+    // 语句序号，从 1 开始
+    pub statement_index: u64,
+    pub statement: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SqlFileProgress {
+    pub execution_id: String,
+    pub statements_executed: u64,
+    pub errors: u64,
+    // 已读取的字节数 / 文件总字节数
+    pub position: u64,
+    pub total_bytes: u64,
+    pub last_error: Option<SqlFileError>,
+    pub done: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SqlFileSummary {
+    pub execution_id: String,
+    pub statements_executed: u64,
+    pub errors: Vec<SqlFileError>,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+// 按行切分 .sql 文件中的语句：跳过引号和注释中的分隔符，支持 MySQL 客户端的 DELIMITER 命令；
+// SQLite 用 sqlite3_complete 判断语句是否完整（触发器体内的分号不结束语句）
+struct StatementSplitter {
+    flavor: SqlFlavor,
+    delimiter: String,
+    buffer: String,
+    quote: Option<char>,
+    block_comment: bool,
+    // 缓冲区中除空白和注释外是否还有内容（/*! */ 可执行注释算作内容）
+    has_code: bool,
+}
+
+// MySQL 的 -- 注释后必须跟空白（1--1 是 1 - -1），另外支持 #；SQLite 的 -- 总是注释
+fn is_line_comment(rest: &str, mysql: bool) -> bool {
+    match rest.strip_prefix("--") {
+        Some(after) => !mysql || after.chars().next().is_none_or(char::is_whitespace),
+        None => mysql && rest.starts_with('#'),
+    }
+}
+
+impl StatementSplitter {
+    fn new(flavor: SqlFlavor) -> Self {
+        Self {
+            flavor,
+            delimiter: ";".to_string(),
+            buffer: String::new(),
+            quote: None,
+            block_comment: false,
+            has_code: false,
+        }
+    }
+
+    // 读入一行（含换行符），返回在这一行结束的语句
+    fn push_line(&mut self, line: &str) -> Vec<String> {
+        let mut statements = Vec::new();
+        let mysql = matches!(self.flavor, SqlFlavor::MySql);
+        if mysql && !self.has_code && self.quote.is_none() && !self.block_comment {
+            let trimmed = line.trim();
+            if let Some(delimiter) = trimmed
+                .get(..10)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("DELIMITER "))
+                .map(|_| trimmed[10..].trim())
+                .filter(|delimiter| !delimiter.is_empty())
+            {
+                self.delimiter = delimiter.to_string();
+                self.buffer.clear();
+                return statements;
+            }
+        }
+
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let mut len = c.len_utf8();
+            if self.block_comment {
+                if rest.starts_with("*/") {
+                    self.block_comment = false;
+                    len = 2;
+                }
+            } else if let Some(quote) = self.quote {
+                if c == '\\' && quote != '`' && mysql {
+                    len += rest[len..].chars().next().map_or(0, char::len_utf8);
+                } else if c == quote {
+                    self.quote = None;
+                }
+            } else if rest.starts_with("/*") {
+                self.block_comment = true;
+                self.has_code |= rest.starts_with("/*!");
+                len = 2;
+            } else if is_line_comment(rest, mysql) {
+                self.buffer.push_str(rest);
+                break;
+            } else if rest.starts_with(self.delimiter.as_str()) {
+                let complete = self.has_code && self.is_complete();
+                rest = &rest[self.delimiter.len()..];
+                if complete {
+                    statements.push(std::mem::take(&mut self.buffer).trim().to_string());
+                    self.has_code = false;
+                } else if self.has_code {
+                    self.buffer.push_str(&self.delimiter);
+                } else {
+                    self.buffer.clear();
+                }
+                continue;
+            } else {
+                if matches!(c, '\'' | '"' | '`') {
+                    self.quote = Some(c);
+                }
+                self.has_code |= !c.is_whitespace();
+            }
+            self.buffer.push_str(&rest[..len]);
+            rest = &rest[len..];
+        }
+        statements
+    }
+
+    fn is_complete(&self) -> bool {
+        match self.flavor {
+            SqlFlavor::MySql => true,
+            SqlFlavor::Sqlite => CString::new(format!("{};", self.buffer))
+                .map_or(true, |sql| unsafe { sqlite3_complete(sql.as_ptr()) != 0 }),
+        }
+    }
+
+    // 文件末尾没有分隔符的最后一条语句
+    fn finish(self) -> Option<String> {
+        self.has_code.then(|| self.buffer.trim().to_string())
+    }
+}
+
+enum FileConnection {
+    MySql(PoolConnection<MySql>),
+    Sqlite(PoolConnection<Sqlite>),
+}
+
+impl FileConnection {
+    // 按文本协议执行，dump 文件中的语句不一定都支持预处理
+    async fn execute(&mut self, sql: &str) -> Result<(), String> {
+        let result = match self {
+            FileConnection::MySql(conn) => conn.execute(sqlx::raw_sql(sql)).await.map(|_| ()),
+            FileConnection::Sqlite(conn) => conn.execute(sqlx::raw_sql(sql)).await.map(|_| ()),
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
+
+struct FileRun<'a> {
+    app_state: &'a AppState,
+    connection: FileConnection,
+    batch_size: usize,
+    continue_on_error: bool,
+    cancel: Arc<AtomicBool>,
+    // 当前事务中已执行的语句数
+    in_batch: usize,
+    // 文件自己控制事务
+    manual_transactions: bool,
+    progress: SqlFileProgress,
+    errors: Vec<SqlFileError>,
+}
+
+impl FileRun<'_> {
+    fn emit(&self) {
+        if let Some(app) = self.app_state.app_handle.as_ref() {
+            let _ = app.emit(SQL_FILE_PROGRESS_EVENT, self.progress.clone());
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    async fn commit_batch(&mut self) -> Result<(), String> {
+        if self.in_batch > 0 {
+            self.in_batch = 0;
+            self.connection
+                .execute("COMMIT")
+                .await
+                .map_err(|e| format!("Failed to commit batch: {}", e))?;
+        }
+        Ok(())
+    }
+
+    async fn rollback_batch(&mut self) {
+        if self.in_batch > 0 {
+            self.in_batch = 0;
+            let _ = self.connection.execute("ROLLBACK").await;
+        }
+    }
+
+    async fn execute(&mut self, statement: String) -> Result<(), String> {
+        let keyword = first_keyword(&statement).unwrap_or_default();
+        if TRANSACTION_KEYWORDS.contains(&keyword.as_str()) {
+            if !self.manual_transactions {
+                self.commit_batch().await?;
+                self.manual_transactions = true;
+            }
+        } else if !self.manual_transactions && self.in_batch == 0 {
+            self.connection
+                .execute("BEGIN")
+                .await
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        }
+        if !self.manual_transactions {
+            self.in_batch += 1;
+        }
+
+        let statement_index = self.progress.statements_executed + self.progress.errors + 1;
+        match self.connection.execute(&statement).await {
+            Ok(()) => self.progress.statements_executed += 1,
+            // 取消时 KILL QUERY / interrupt 中断的语句不算错误
+            Err(_) if self.cancelled() => return Ok(()),
+            Err(message) => {
+                self.progress.errors += 1;
+                let error = SqlFileError {
+                    statement_index,
+                    statement: statement
+                        .chars()
+                        .take(SQL_FILE_ERROR_STATEMENT_CHARS)
+                        .collect(),
+                    message: message.clone(),
+                };
+                self.progress.last_error = Some(error.clone());
+                if self.errors.len() < SQL_FILE_MAX_REPORTED_ERRORS {
+                    self.errors.push(error);
+                }
+                if !self.continue_on_error {
+                    self.rollback_batch().await;
+                    return Err(format!("Statement {} failed: {}", statement_index, message));
+                }
+            }
+        }
+
+        if statement_index.is_multiple_of(self.batch_size as u64) {
+            self.commit_batch().await?;
+            self.emit();
+        }
+        Ok(())
+    }
+}
+
+// 流式读取并执行 .sql 文件（MySQL 系 / SQLite）。语句按 batch_size 分批在事务中执行，每批提交后发送
+// sql-file-progress 事件；文件自带 BEGIN / COMMIT 时改为按文件的事务执行。出错时默认回滚当前批次并停止，
+// continue_on_error 为 true 时记录错误后继续。通过 cancel_query(execution_id) 取消，已提交的批次保留
+#[command]
+pub async fn run_sql_file(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    path: String,
+    db_name: Option<String>,
+    confirm_token: Option<String>,
+    execution_id: Option<String>,
+    batch_size: Option<usize>,
+    continue_on_error: Option<bool>,
+) -> Result<SqlFileSummary, String> {
+    ensure_sql_file_allowed(&db_state, connection_id, &path, confirm_token.as_deref()).await?;
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| format!("Failed to open SQL file: {}", e))?;
+    let total_bytes = file.metadata().await.map_or(0, |metadata| metadata.len());
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let (flavor, connection) = match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            let mut conn =
+                acquire_connection(&app_state, &db_state, connection_id, db_name.clone()).await?;
+            // 文件中可能有 USE / SET 等改变会话状态的语句，执行完不放回连接池
+            conn.close_on_drop();
+            register_mysql_query(&app_state, &mut conn, &execution_id, connection_id, db_name)
+                .await;
+            (SqlFlavor::MySql, FileConnection::MySql(conn))
+        }
+        "sqlite" => {
+            let pool = get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            conn.close_on_drop();
+            let handle = SqliteInterruptHandle::of(&mut conn).await?;
+            register_running_query(&app_state, &execution_id, RunningQuery::Sqlite(handle)).await;
+            (SqlFlavor::Sqlite, FileConnection::Sqlite(conn))
+        }
+        other => return Err(format!("Running SQL files is not supported for {}", other)),
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(execution_id.clone(), cancel.clone());
+    let mut run = FileRun {
+        app_state: &app_state,
+        connection,
+        batch_size: batch_size.unwrap_or(SQL_FILE_DEFAULT_BATCH_SIZE).max(1),
+        continue_on_error: continue_on_error.unwrap_or(false),
+        cancel,
+        in_batch: 0,
+        manual_transactions: false,
+        progress: SqlFileProgress {
+            execution_id: execution_id.clone(),
+            statements_executed: 0,
+            errors: 0,
+            position: 0,
+            total_bytes,
+            last_error: None,
+            done: false,
+        },
+        errors: Vec::new(),
+    };
+
+    let started = Instant::now();
+    let mut reader = BufReader::new(file);
+    let mut splitter = StatementSplitter::new(flavor);
+    let mut line = Vec::new();
+    let mut outcome: Result<(), String> = Ok(());
+    let mut eof = false;
+    while outcome.is_ok() && !eof && !run.cancelled() {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) => eof = true,
+            Ok(read) => {
+                run.progress.position += read as u64;
+                for statement in splitter.push_line(&String::from_utf8_lossy(&line)) {
+                    outcome = run.execute(statement).await;
+                    if outcome.is_err() || run.cancelled() {
+                        break;
+                    }
+                }
+            }
+            Err(e) => outcome = Err(format!("Failed to read SQL file: {}", e)),
+        }
+    }
+    if eof && !run.cancelled() {
+        if let Some(statement) = splitter.finish() {
+            outcome = run.execute(statement).await;
+        }
+    }
+    if outcome.is_ok() && !run.cancelled() {
+        outcome = run.commit_batch().await;
+    } else {
+        run.rollback_batch().await;
+    }
+
+    finish_running_query(&app_state, &execution_id).await;
+    app_state.cancel_flags.lock().await.remove(&execution_id);
+    invalidate_results(&app_state, connection_id).await;
+    invalidate_metadata(&app_state, connection_id).await;
+    run.progress.done = true;
+    run.emit();
+    outcome?;
+    let cancelled = run.cancelled();
+
+    Ok(SqlFileSummary {
+        execution_id,
+        statements_executed: run.progress.statements_executed,
+        errors: run.errors,
+        cancelled,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(sql: &str, flavor: SqlFlavor) -> Vec<String> {
+        let mut splitter = StatementSplitter::new(flavor);
+        let mut statements: Vec<String> = sql
+            .split_inclusive('\n')
+            .flat_map(|line| splitter.push_line(line))
+            .collect();
+        statements.extend(splitter.finish());
+        statements
+    }
+
+    #[test]
+    fn splits_on_delimiter_outside_quotes() {
+        assert_eq!(
+            split(
+                "SELECT 'a;b', \"c;d\", `e;f`; SELECT 2;\n",
+                SqlFlavor::MySql
+            ),
+            ["SELECT 'a;b', \"c;d\", `e;f`", "SELECT 2"]
+        );
+        assert_eq!(
+            split("SELECT 'it\\'s;'; SELECT 'x''y;';\n", SqlFlavor::MySql),
+            ["SELECT 'it\\'s;'", "SELECT 'x''y;'"]
+        );
+        assert_eq!(
+            split("SELECT 'a\nb;c';\n", SqlFlavor::MySql),
+            ["SELECT 'a\nb;c'"]
+        );
+    }
+
+    #[test]
+    fn skips_delimiters_in_comments() {
+        assert_eq!(
+            split(
+                "SELECT 1; -- one; two\nSELECT 2; # three;\n/* four;\nfive; */ SELECT 3;\n",
+                SqlFlavor::MySql
+            ),
+            [
+                "SELECT 1",
+                "-- one; two\nSELECT 2",
+                "# three;\n/* four;\nfive; */ SELECT 3"
+            ]
+        );
+        // 只有注释的部分不产生语句
+        assert!(split("-- nothing;\n/* here; */;\n", SqlFlavor::MySql).is_empty());
+    }
+
+    #[test]
+    fn mysql_double_dash_needs_whitespace() {
+        assert_eq!(
+            split("SELECT 1--1; SELECT 2;--\nSELECT 3;\n", SqlFlavor::MySql),
+            ["SELECT 1--1", "SELECT 2", "--\nSELECT 3"]
+        );
+        assert_eq!(
+            split("SELECT 1--1;\nSELECT 2;\n", SqlFlavor::Sqlite),
+            ["SELECT 1--1;\nSELECT 2"]
+        );
+    }
+
+    #[test]
+    fn handles_delimiter_command() {
+        let sql = "DELIMITER $$\n\
+                   CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END$$\n\
+                   delimiter ;\n\
+                   CALL p();\n";
+        assert_eq!(
+            split(sql, SqlFlavor::MySql),
+            [
+                "CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END",
+                "CALL p()"
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_last_statement_without_delimiter() {
+        assert_eq!(
+            split("SELECT 1;\nSELECT 2\n", SqlFlavor::MySql),
+            ["SELECT 1", "SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn sqlite_trigger_body_is_one_statement() {
+        let sql = "CREATE TRIGGER t AFTER INSERT ON a BEGIN\n\
+                   INSERT INTO b VALUES (1);\n\
+                   END;\n\
+                   SELECT 1;\n";
+        assert_eq!(
+            split(sql, SqlFlavor::Sqlite),
+            [
+                "CREATE TRIGGER t AFTER INSERT ON a BEGIN\nINSERT INTO b VALUES (1);\nEND",
+                "SELECT 1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use sqlformat::{Dialect, FormatOptions, Indent, QueryParams};
+use tauri::command;
+
+const DEFAULT_INDENT_WIDTH: u8 = 2;
+const MAX_INDENT_WIDTH: u8 = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct FormatSqlOptions {
+    // "upper" / "lower" / "preserve"，默认 upper
+    pub keyword_case: Option<String>,
+    pub indent_width: Option<u8>,
+    pub use_tabs: bool,
+    // 多条语句之间的空行数
+    pub lines_between_queries: Option<u8>,
+}
+
+// 格式化 SQL。dialect 传连接的 db_type：SQLite 额外识别 [标识符] 和 @参数，
+// MySQL 系（反引号、# 注释）按通用语法处理
+#[command]
+pub fn format_sql(
+    sql: String,
+    dialect: Option<String>,
+    options: Option<FormatSqlOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let uppercase = match options.keyword_case.as_deref().unwrap_or("upper") {
+        "upper" => Some(true),
+        "lower" => Some(false),
+        "preserve" => None,
+        other => return Err(format!("Unsupported keyword case: {}", other)),
+    };
+    let indent = if options.use_tabs {
+        Indent::Tabs
+    } else {
+        Indent::Spaces(
+            options
+                .indent_width
+                .unwrap_or(DEFAULT_INDENT_WIDTH)
+                .clamp(1, MAX_INDENT_WIDTH),
+        )
+    };
+    let dialect = match dialect.as_deref() {
+        Some("sqlite") => Dialect::SQLServer,
+        _ => Dialect::Generic,
+    };
+
+    let format_options = FormatOptions {
+        indent,
+        uppercase,
+        lines_between_queries: options.lines_between_queries.unwrap_or(1).max(1),
+        dialect,
+        ..FormatOptions::default()
+    };
+    Ok(sqlformat::format(&sql, &QueryParams::None, &format_options))
+}
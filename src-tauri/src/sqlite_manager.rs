@@ -1,13 +1,60 @@
+use crate::autocomplete::invalidate_metadata;
+use crate::connection_stats::record_query;
 use crate::db::DbState;
-use crate::models::{ColumnInfo, Connection, SqlResult};
+use crate::guard::{ensure_sql_allowed, safe_limit};
+use crate::models::{ColumnInfo, Connection, RowCount, SqlResult, SqlValidation};
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::query_queue::{
+    acquire_query_slot, finish_running_query, new_execution_id, register_running_query,
+    RunningQuery,
+};
+use crate::result_cache::{cached_results, invalidate_results, result_cache_ttl, store_results};
+use crate::result_spill::RowSpooler;
+use crate::session::{sqlite_session, QueryConnection};
+use crate::sql_classifier::{
+    changes_schema, is_insert, is_read_query, referenced_tables, returns_rows, single_table_write,
+    with_safe_limit, without_paging, SqlFlavor,
+};
 use crate::state::AppState;
+use crate::undo::{capture_sqlite_snapshot, save_snapshot};
+use futures_util::TryStreamExt;
+use libsqlite3_sys::{sqlite3, sqlite3_interrupt};
 use serde_json::{Map, Value};
-use sqlx::sqlite::{SqlitePoolOptions, SqliteRow};
-use sqlx::{Column, Row, SqlitePool, Statement, TypeInfo};
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions, SqliteConnection, SqliteRow};
+use sqlx::{Column, Executor, Row, Sqlite, SqlitePool, Statement, TypeInfo};
+use std::ptr::NonNull;
+use std::str::FromStr;
+use std::time::Instant;
 use tauri::{command, State};
 
+const SQLITE_BUSY_TIMEOUT_SECS: u64 = 5;
+
+// 执行中查询的 sqlite3 句柄，用于从其他线程中断查询
+pub struct SqliteInterruptHandle(NonNull<sqlite3>);
+
+// sqlite3_interrupt 可以在任意线程调用；句柄只在查询执行期间登记，
+// 执行结束后先移除再归还连接，不会访问已关闭的连接
+unsafe impl Send for SqliteInterruptHandle {}
+unsafe impl Sync for SqliteInterruptHandle {}
+
+impl SqliteInterruptHandle {
+    pub async fn of(conn: &mut SqliteConnection) -> Result<Self, String> {
+        let handle = conn
+            .lock_handle()
+            .await
+            .map_err(|e| format!("Failed to lock SQLite connection: {}", e))?
+            .as_raw_handle();
+        Ok(Self(handle))
+    }
+
+    pub fn interrupt(&self) {
+        unsafe { sqlite3_interrupt(self.0.as_ptr()) };
+    }
+}
+
 // 辅助函数：获取或创建 SQLite 连接池
-async fn get_or_create_pool(
+pub async fn get_or_create_pool(
     app_state: &State<'_, AppState>,
     db_state: &State<'_, DbState>,
     connection_id: i64,
@@ -40,11 +87,18 @@ async fn get_or_create_pool(
     // connection.database 存储文件路径
     let db_path = connection.database.ok_or("Database path is required")?;
     let url = format!("sqlite://{}", db_path);
+    // 只读连接以只读方式打开数据库文件
+    let options = SqliteConnectOptions::from_str(&url)
+        .map_err(|e| format!("Invalid SQLite path: {}", e))?
+        .read_only(connection.read_only)
+        .busy_timeout(connection.options.connect_timeout(SQLITE_BUSY_TIMEOUT_SECS))
+        .statement_cache_capacity(connection.options.statement_cache_capacity());
 
     // 4. 创建连接池
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&url)
+    let pool = connection
+        .options
+        .pool_options::<Sqlite>(SQLITE_BUSY_TIMEOUT_SECS)
+        .connect_with(options)
         .await
         .map_err(|e| format!("Failed to connect to SQLite: {}", e))?;
 
@@ -57,7 +111,7 @@ async fn get_or_create_pool(
 
 // 将 SQLite 的 Row 转换为 JSON Object
 // 不做类型特定转换，通过 try 链自动探测并保持原生 JSON 类型
-fn row_to_json(row: &SqliteRow) -> Map<String, Value> {
+pub fn row_to_json(row: &SqliteRow) -> Map<String, Value> {
     let mut json_row = Map::new();
 
     for (i, column) in row.columns().iter().enumerate() {
@@ -81,65 +135,292 @@ fn row_to_json(row: &SqliteRow) -> Map<String, Value> {
     json_row
 }
 
+// 按 JSON 值的类型绑定参数
+pub fn bind_json_value<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    value: &Value,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Bool(b) => query.bind(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(v) => query.bind(v),
+            None => query.bind(n.as_f64()),
+        },
+        Value::String(text) => query.bind(text.clone()),
+        other => query.bind(other.to_string()),
+    }
+}
+
 #[command]
 pub async fn execute_sqlite_sql(
     app_state: State<'_, AppState>,
     db_state: State<'_, DbState>,
     connection_id: i64,
     sql: String,
+    confirm_token: Option<String>,
+    confirmed: Option<bool>,
+    execution_id: Option<String>,
+    snapshot: Option<bool>,
+    session_id: Option<String>,
 ) -> Result<SqlResult, String> {
-    let pool = get_or_create_pool(&app_state, &db_state, connection_id).await?;
+    ensure_sql_allowed(
+        &db_state,
+        connection_id,
+        &sql,
+        confirm_token.as_deref(),
+        confirmed.unwrap_or(false),
+    )
+    .await?;
 
-    let sql_upper = sql.trim().to_uppercase();
-    if sql_upper.starts_with("SELECT")
-        || sql_upper.starts_with("PRAGMA")
-        || sql_upper.starts_with("EXPLAIN")
-    {
-        let rows = sqlx::query(&sql)
-            .fetch_all(&pool)
-            .await
-            .map_err(|e| format!("Query execution failed: {}", e))?;
+    // 开启结果缓存时，TTL 内相同的只读查询直接返回缓存的结果；会话中的查询不使用缓存
+    let cache_ttl = match session_id.is_none() && is_read_query(&sql, SqlFlavor::Sqlite) {
+        true => result_cache_ttl(&db_state, connection_id).await?,
+        false => None,
+    };
+    if cache_ttl.is_some() {
+        if let Some(mut results) = cached_results(&app_state, connection_id, None, &sql).await {
+            if let Some(result) = results.pop() {
+                return Ok(result);
+            }
+        }
+    }
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    // 指定 session_id 时在会话独占的连接上执行
+    let mut conn = match &session_id {
+        Some(session_id) => sqlite_session(&app_state, session_id, connection_id).await?,
+        None => {
+            let pool = get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            QueryConnection::Pooled(conn)
+        }
+    };
 
+    // 登记连接句柄，cancel_query 通过 sqlite3_interrupt 中断
+    let handle = SqliteInterruptHandle::of(&mut conn).await?;
+    register_running_query(&app_state, &execution_id, RunningQuery::Sqlite(handle)).await;
+
+    let started = Instant::now();
+    if returns_rows(&sql, SqlFlavor::Sqlite) {
+        // 安全模式下没有 LIMIT 的 SELECT 按改写后的语句执行，历史中仍记录原语句
+        let limited = safe_limit(&db_state, connection_id)
+            .await?
+            .and_then(|n| Some((with_safe_limit(&sql, SqlFlavor::Sqlite, n)?, n)));
+        let (query_sql, applied_limit) = match &limited {
+            Some((limited_sql, n)) => (limited_sql.as_str(), Some(*n)),
+            None => (sql.as_str(), None),
+        };
+        // 逐行交给 RowSpooler，超过阈值的结果直接写入临时文件，不在内存中保留完整结果
         let mut columns = Vec::new();
-        let mut result_rows = Vec::new();
-
-        if let Some(first_row) = rows.first() {
-            for col in first_row.columns() {
-                columns.push(ColumnInfo {
-                    name: col.name().to_string(),
-                    type_name: col.type_info().name().to_string(),
-                });
+        let mut spooler = RowSpooler::new(&app_state, connection_id);
+        let streamed: Result<usize, String> = async {
+            let mut stream = sqlx::query(query_sql).fetch(&mut *conn);
+            let mut row_count = 0;
+            while let Some(row) = stream
+                .try_next()
+                .await
+                .map_err(|e| format!("Query execution failed: {}", e))?
+            {
+                if row_count == 0 {
+                    for col in row.columns() {
+                        columns.push(ColumnInfo {
+                            name: col.name().to_string(),
+                            type_name: col.type_info().name().to_string(),
+                            ..Default::default()
+                        });
+                    }
+                }
+                row_count += 1;
+                spooler.push(row_to_json(&row)).await?;
             }
-        } else {
-            if let Ok(stmt) = sqlx::Executor::prepare(&pool, sql.as_str()).await {
+            Ok(row_count)
+        }
+        .await;
+        finish_running_query(&app_state, &execution_id).await;
+        let elapsed = started.elapsed();
+        record_query(&app_state, &db_state, connection_id, elapsed).await;
+        let outcome = HistoryOutcome::of(&streamed, |count| HistoryOutcome::Rows(*count));
+        record_history(&db_state, connection_id, &sql, elapsed, outcome).await;
+        if streamed? == 0 {
+            if let Ok(stmt) = conn.prepare(query_sql).await {
                 for col in stmt.columns() {
                     columns.push(ColumnInfo {
                         name: col.name().to_string(),
                         type_name: col.type_info().name().to_string(),
+                        ..Default::default()
                     });
                 }
             }
         }
+        let (rows, spilled) = spooler.finish().await?;
 
-        for row in rows {
-            result_rows.push(row_to_json(&row));
-        }
-
-        Ok(SqlResult {
+        let result = SqlResult {
             columns,
-            rows: result_rows,
+            rows,
             affected_rows: 0,
-        })
+            duration_ms: elapsed.as_millis() as u64,
+            warnings: vec![],
+            last_insert_id: None,
+            applied_limit,
+            from_cache: false,
+            spilled,
+        };
+        if let Some(ttl) = cache_ttl {
+            let results = std::slice::from_ref(&result);
+            store_results(&app_state, connection_id, None, &sql, results, ttl).await;
+        }
+        Ok(result)
     } else {
-        let result = sqlx::query(&sql)
-            .execute(&pool)
-            .await
-            .map_err(|e| format!("Statement execution failed: {}", e))?;
+        // 数据表格提交的 UPDATE / DELETE 先快照受影响的行，供 undo_last_change 恢复
+        let change_snapshot = match snapshot {
+            Some(true) => capture_sqlite_snapshot(&mut conn, &sql).await,
+            _ => None,
+        };
+        let result = sqlx::query(&sql).execute(&mut *conn).await;
+        finish_running_query(&app_state, &execution_id).await;
+        let elapsed = started.elapsed();
+        record_query(&app_state, &db_state, connection_id, elapsed).await;
+        let result = result.map_err(|e| format!("Statement execution failed: {}", e));
+        let outcome = HistoryOutcome::of(&result, |r| HistoryOutcome::Affected(r.rows_affected()));
+        record_history(&db_state, connection_id, &sql, elapsed, outcome).await;
+        let result = result?;
+        invalidate_results(&app_state, connection_id).await;
+        if changes_schema(&sql) {
+            invalidate_metadata(&app_state, connection_id).await;
+        }
+        if let Some(change_snapshot) = change_snapshot {
+            save_snapshot(&db_state, connection_id, None, change_snapshot).await;
+        }
+        // 未插入任何行时 last_insert_rowid 仍是之前的值，不返回
+        let last_insert_id = (is_insert(&sql, SqlFlavor::Sqlite) && result.rows_affected() > 0)
+            .then(|| u64::try_from(result.last_insert_rowid()).ok())
+            .flatten();
 
         Ok(SqlResult {
             columns: vec![],
             rows: vec![],
             affected_rows: result.rows_affected(),
+            duration_ms: elapsed.as_millis() as u64,
+            warnings: vec![],
+            last_insert_id,
+            applied_limit: None,
+            from_cache: false,
+            spilled: None,
         })
     }
 }
+
+// 不执行语句，只用 prepare 检查语法、引用的表和列是否存在。
+// SQLite 没有行数估计，单表 UPDATE / DELETE 按同样的条件统计将受影响的行数
+#[command]
+pub async fn validate_sqlite_sql(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+) -> Result<SqlValidation, String> {
+    let pool = get_or_create_pool(&app_state, &db_state, connection_id).await?;
+    let tables = referenced_tables(&sql, SqlFlavor::Sqlite);
+    if let Err(e) = pool.prepare(sql.as_str()).await {
+        return Ok(SqlValidation {
+            valid: false,
+            error: Some(e.to_string()),
+            tables,
+            estimated_rows: None,
+        });
+    }
+
+    let estimated_rows = match single_table_write(&sql, SqlFlavor::Sqlite) {
+        Some(target) => {
+            let mut count_sql = format!("SELECT COUNT(*) FROM {}", target.from_clause);
+            if let Some(selection) = &target.selection {
+                count_sql.push_str(&format!(" WHERE {}", selection));
+            }
+            sqlx::query_scalar::<_, i64>(&count_sql)
+                .fetch_one(&pool)
+                .await
+                .ok()
+                .and_then(|n| u64::try_from(n).ok())
+        }
+        None => None,
+    };
+    Ok(SqlValidation {
+        valid: true,
+        error: None,
+        tables,
+        estimated_rows,
+    })
+}
+
+// 分页表格的总行数。SQLite 没有行数估计，exact 为 true 时执行 COUNT(*)（去掉 ORDER BY / LIMIT），
+// 否则两项均为空
+#[command]
+pub async fn count_sqlite_query_rows(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    sql: String,
+    exact: Option<bool>,
+    execution_id: Option<String>,
+) -> Result<RowCount, String> {
+    let base_sql =
+        without_paging(&sql, SqlFlavor::Sqlite).ok_or("Only SELECT statements can be counted")?;
+    if !exact.unwrap_or(false) {
+        return Ok(RowCount {
+            estimated_rows: None,
+            exact_rows: None,
+        });
+    }
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let pool = get_or_create_pool(&app_state, &db_state, connection_id).await?;
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+    let handle = SqliteInterruptHandle::of(&mut conn).await?;
+    register_running_query(&app_state, &execution_id, RunningQuery::Sqlite(handle)).await;
+    let count_sql = format!("SELECT COUNT(*) FROM ({}) AS xdb_count", base_sql);
+    let count = sqlx::query_scalar::<_, i64>(&count_sql)
+        .fetch_one(&mut *conn)
+        .await;
+    finish_running_query(&app_state, &execution_id).await;
+    let count = count.map_err(|e| format!("Failed to count rows: {}", e))?;
+
+    Ok(RowCount {
+        estimated_rows: None,
+        exact_rows: u64::try_from(count).ok(),
+    })
+}
+
+// 在一个事务中依次执行语句，任一语句没有影响到行时整体回滚（用于 undo_last_change）
+pub async fn execute_in_transaction(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    statements: &[String],
+) -> Result<(), String> {
+    let pool = get_or_create_pool(app_state, db_state, connection_id).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+    for statement in statements {
+        let result = sqlx::query(statement)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to undo change: {}", e))?;
+        if result.rows_affected() == 0 {
+            return Err("Failed to undo change: the row has been modified or removed".to_string());
+        }
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
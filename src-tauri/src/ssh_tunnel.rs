@@ -0,0 +1,415 @@
+use crate::db::DbState;
+use crate::models::Connection;
+use crate::state::AppState;
+use russh::client::{self, Handle};
+use russh::keys::agent::client::AgentClient;
+use russh::keys::agent::AgentIdentity;
+use russh::keys::known_hosts::learn_known_hosts;
+use russh::keys::{
+    check_known_hosts, load_secret_key, HashAlg, PrivateKey, PrivateKeyWithHashAlg, PublicKey,
+    PublicKeyOrCertificate,
+};
+use std::sync::{Arc, Mutex as StdMutex};
+use tauri::{command, State};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration};
+
+const SSH_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+// 一条本地端口转发：127.0.0.1:local_port -> (SSH 跳板机) -> 目标 host:port
+pub struct SshTunnel {
+    pub local_port: u16,
+    session: Arc<Handle<TunnelHandler>>,
+    task: JoinHandle<()>,
+}
+
+impl SshTunnel {
+    fn is_alive(&self) -> bool {
+        !self.task.is_finished() && !self.session.is_closed()
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// 主机密钥未记录在 known_hosts 中时返回 "SSH_HOST_KEY_UNKNOWN:<SHA256 指纹>"，
+// 前端请用户核对指纹后调用 trust_ssh_host_key，下次连接时写入 known_hosts
+pub const SSH_HOST_KEY_UNKNOWN: &str = "SSH_HOST_KEY_UNKNOWN";
+
+// check_server_key 拒绝主机密钥的原因，握手失败后据此生成错误信息
+enum HostKeyRejection {
+    Unknown(String),
+    Changed,
+    Failed(String),
+}
+
+pub struct TunnelHandler {
+    host: String,
+    port: u16,
+    // 用户已确认信任的指纹
+    trusted_fingerprint: Option<String>,
+    rejection: Arc<StdMutex<Option<HostKeyRejection>>>,
+}
+
+impl TunnelHandler {
+    fn verify(&self, key: &PublicKey) -> Result<(), HostKeyRejection> {
+        match check_known_hosts(&self.host, self.port, key) {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                let fingerprint = key.fingerprint(HashAlg::Sha256).to_string();
+                if self.trusted_fingerprint.as_deref() != Some(fingerprint.as_str()) {
+                    return Err(HostKeyRejection::Unknown(fingerprint));
+                }
+                learn_known_hosts(&self.host, self.port, key).map_err(|e| {
+                    HostKeyRejection::Failed(format!(
+                        "Failed to save SSH host key to known_hosts: {}",
+                        e
+                    ))
+                })
+            }
+            Err(russh::keys::Error::KeyChanged { .. }) => Err(HostKeyRejection::Changed),
+            Err(e) => Err(HostKeyRejection::Failed(format!(
+                "Failed to check SSH known_hosts: {}",
+                e
+            ))),
+        }
+    }
+}
+
+impl client::Handler for TunnelHandler {
+    type Error = russh::Error;
+
+    // 只接受 ~/.ssh/known_hosts 中记录的主机密钥，或用户已核对指纹的未知主机（同时写入 known_hosts）；
+    // 密钥与记录不一致或无法读取 known_hosts 时拒绝
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKeyOrCertificate,
+    ) -> Result<bool, Self::Error> {
+        let key = match server_public_key {
+            PublicKeyOrCertificate::PublicKey { key, .. } => key.clone(),
+            PublicKeyOrCertificate::Certificate(cert) => PublicKey::from(cert.public_key().clone()),
+        };
+        match self.verify(&key) {
+            Ok(()) => Ok(true),
+            Err(rejection) => {
+                if let Ok(mut slot) = self.rejection.lock() {
+                    *slot = Some(rejection);
+                }
+                Ok(false)
+            }
+        }
+    }
+}
+
+// 加密私钥缺少口令时返回该错误，前端据此弹出口令输入框并调用 unlock_ssh_key
+pub const SSH_KEY_PASSPHRASE_REQUIRED: &str = "SSH_KEY_PASSPHRASE_REQUIRED";
+
+// 辅助函数：读取私钥，加密私钥需要口令
+fn load_private_key(key_path: &str, passphrase: Option<&str>) -> Result<PrivateKey, String> {
+    match load_secret_key(key_path, passphrase) {
+        Ok(key) => Ok(key),
+        Err(russh::keys::Error::KeyIsEncrypted) => Err(SSH_KEY_PASSPHRASE_REQUIRED.to_string()),
+        Err(e) => Err(format!("Failed to load SSH private key: {}", e)),
+    }
+}
+
+// 辅助函数：依次尝试 ssh-agent 中的公钥
+async fn authenticate_with_agent(
+    session: &mut Handle<TunnelHandler>,
+    username: &str,
+) -> Result<bool, String> {
+    #[cfg(unix)]
+    let mut agent = AgentClient::connect_env()
+        .await
+        .map_err(|e| format!("Failed to connect to SSH agent: {}", e))?;
+    #[cfg(windows)]
+    let mut agent = AgentClient::connect_named_pipe(r"\\.\pipe\openssh-ssh-agent")
+        .await
+        .map_err(|e| format!("Failed to connect to SSH agent: {}", e))?;
+
+    let identities = agent
+        .request_identities()
+        .await
+        .map_err(|e| format!("Failed to list SSH agent keys: {}", e))?;
+    if identities.is_empty() {
+        return Err("SSH agent has no keys loaded".to_string());
+    }
+
+    let hash_alg = session
+        .best_supported_rsa_hash()
+        .await
+        .ok()
+        .flatten()
+        .flatten();
+    for identity in identities {
+        let AgentIdentity::PublicKey { key, .. } = identity else {
+            continue;
+        };
+        let result = session
+            .authenticate_publickey_with(username, key, hash_alg, &mut agent)
+            .await
+            .map_err(|e| format!("SSH authentication failed: {}", e))?;
+        if result.success() {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+// 辅助函数：建立 SSH 会话并完成认证
+async fn open_session(
+    connection: &Connection,
+    passphrase: Option<&str>,
+    trusted_fingerprint: Option<String>,
+) -> Result<Handle<TunnelHandler>, String> {
+    let ssh_host = connection
+        .ssh_host
+        .clone()
+        .filter(|h| !h.is_empty())
+        .ok_or("SSH host is required")?;
+    let ssh_port = connection.ssh_port.unwrap_or(22) as u16;
+    let ssh_username = connection
+        .ssh_username
+        .clone()
+        .filter(|u| !u.is_empty())
+        .ok_or("SSH username is required")?;
+
+    let config = Arc::new(client::Config::default());
+    let rejection = Arc::new(StdMutex::new(None));
+    let handler = TunnelHandler {
+        host: ssh_host.clone(),
+        port: ssh_port,
+        trusted_fingerprint,
+        rejection: rejection.clone(),
+    };
+
+    let connected = timeout(
+        Duration::from_secs(SSH_CONNECT_TIMEOUT_SECS),
+        client::connect(config, (ssh_host.as_str(), ssh_port), handler),
+    )
+    .await
+    .map_err(|_| "SSH connection timed out".to_string())?;
+    let rejection = rejection.lock().ok().and_then(|mut slot| slot.take());
+    let mut session = match (connected, rejection) {
+        (Ok(session), _) => session,
+        (Err(_), Some(HostKeyRejection::Unknown(fingerprint))) => {
+            return Err(format!("{}:{}", SSH_HOST_KEY_UNKNOWN, fingerprint))
+        }
+        (Err(_), Some(HostKeyRejection::Changed)) => {
+            return Err(format!(
+                "SSH host key for {}:{} does not match known_hosts",
+                ssh_host, ssh_port
+            ))
+        }
+        (Err(_), Some(HostKeyRejection::Failed(message))) => return Err(message),
+        (Err(e), None) => return Err(format!("Failed to connect to SSH host: {}", e)),
+    };
+
+    let success = match connection.ssh_auth_method.as_str() {
+        "agent" => authenticate_with_agent(&mut session, &ssh_username).await?,
+        "key" => {
+            let key_path = connection
+                .ssh_key_path
+                .as_deref()
+                .filter(|p| !p.is_empty())
+                .ok_or("SSH private key path is required")?;
+            let key = load_private_key(key_path, passphrase)?;
+            let hash_alg = session
+                .best_supported_rsa_hash()
+                .await
+                .ok()
+                .flatten()
+                .flatten();
+            session
+                .authenticate_publickey(
+                    ssh_username,
+                    PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg),
+                )
+                .await
+                .map_err(|e| format!("SSH authentication failed: {}", e))?
+                .success()
+        }
+        _ => session
+            .authenticate_password(
+                ssh_username,
+                connection.ssh_password.clone().unwrap_or_default(),
+            )
+            .await
+            .map_err(|e| format!("SSH authentication failed: {}", e))?
+            .success(),
+    };
+
+    if !success {
+        return Err("SSH authentication failed".to_string());
+    }
+
+    Ok(session)
+}
+
+async fn open_tunnel(
+    connection: &Connection,
+    passphrase: Option<&str>,
+    trusted_fingerprint: Option<String>,
+    target_host: String,
+    target_port: u16,
+) -> Result<SshTunnel, String> {
+    let session = Arc::new(open_session(connection, passphrase, trusted_fingerprint).await?);
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| format!("Failed to bind local tunnel port: {}", e))?;
+    let local_port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to bind local tunnel port: {}", e))?
+        .port();
+
+    // 每个本地连接各开一个 direct-tcpip 通道转发到目标地址
+    let forward_session = session.clone();
+    let task = tokio::spawn(async move {
+        while let Ok((mut socket, peer)) = listener.accept().await {
+            let session = forward_session.clone();
+            let target_host = target_host.clone();
+            // 通道打开失败时直接关闭本地连接，客户端会收到连接错误
+            tokio::spawn(async move {
+                if let Ok(channel) = session
+                    .channel_open_direct_tcpip(
+                        target_host,
+                        target_port as u32,
+                        peer.ip().to_string(),
+                        peer.port() as u32,
+                    )
+                    .await
+                {
+                    let mut stream = channel.into_stream();
+                    let _ = tokio::io::copy_bidirectional(&mut socket, &mut stream).await;
+                }
+            });
+        }
+    });
+
+    Ok(SshTunnel {
+        local_port,
+        session,
+        task,
+    })
+}
+
+// 返回实际应连接的地址：未启用 SSH 时原样返回，否则返回本地转发端口
+pub async fn resolve_endpoint(
+    app_state: &AppState,
+    connection: &Connection,
+    host: &str,
+    port: u16,
+) -> Result<(String, u16), String> {
+    if !connection.ssh_enabled {
+        return Ok((host.to_string(), port));
+    }
+
+    let key = format!("{}:{}:{}", connection.id, host, port);
+    if let Some(tunnel) = app_state.ssh_tunnels.lock().await.get(&key) {
+        if tunnel.is_alive() {
+            return Ok(("127.0.0.1".to_string(), tunnel.local_port));
+        }
+    }
+
+    // SSH 握手可能很慢，不持有 ssh_tunnels 锁，避免阻塞其他连接
+    let passphrase = app_state
+        .ssh_key_passphrases
+        .lock()
+        .await
+        .get(&connection.id)
+        .cloned();
+    let trusted_fingerprint = app_state
+        .ssh_trusted_host_keys
+        .lock()
+        .await
+        .get(&connection.id)
+        .cloned();
+    let tunnel = open_tunnel(
+        connection,
+        passphrase.as_deref(),
+        trusted_fingerprint,
+        host.to_string(),
+        port,
+    )
+    .await?;
+    app_state
+        .ssh_trusted_host_keys
+        .lock()
+        .await
+        .remove(&connection.id);
+
+    // 并发建立的隧道只保留先完成的一条
+    let mut tunnels = app_state.ssh_tunnels.lock().await;
+    if let Some(existing) = tunnels.get(&key) {
+        if existing.is_alive() {
+            return Ok(("127.0.0.1".to_string(), existing.local_port));
+        }
+    }
+    let local_port = tunnel.local_port;
+    tunnels.insert(key, tunnel);
+
+    Ok(("127.0.0.1".to_string(), local_port))
+}
+
+// 关闭某个连接的全部隧道
+pub async fn close_tunnels(app_state: &AppState, connection_id: i64) {
+    let prefix = format!("{}:", connection_id);
+    let mut tunnels = app_state.ssh_tunnels.lock().await;
+    tunnels.retain(|key, _| !key.starts_with(&prefix));
+}
+
+// 校验并在内存中保存私钥口令（不写入 connections 表），之后重建隧道时使用
+#[command]
+pub async fn unlock_ssh_key(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    passphrase: String,
+) -> Result<(), String> {
+    let connection = sqlx::query_as::<_, Connection>(
+        "SELECT * FROM connections WHERE id = ?",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch connection info: {}", e))?
+    .ok_or("Connection not found")?;
+
+    let key_path = connection
+        .ssh_key_path
+        .filter(|p| !p.is_empty())
+        .ok_or("SSH private key path is required")?;
+    load_secret_key(&key_path, Some(&passphrase))
+        .map_err(|e| format!("Failed to unlock SSH private key: {}", e))?;
+
+    let mut passphrases = app_state.ssh_key_passphrases.lock().await;
+    passphrases.insert(connection_id, passphrase);
+
+    Ok(())
+}
+
+// 用户核对指纹后信任 known_hosts 中没有记录的主机密钥（SSH_HOST_KEY_UNKNOWN 错误中的指纹），
+// 下次建立隧道时若服务端密钥指纹一致则写入 ~/.ssh/known_hosts
+#[command]
+pub async fn trust_ssh_host_key(
+    app_state: State<'_, AppState>,
+    connection_id: i64,
+    fingerprint: String,
+) -> Result<(), String> {
+    let fingerprint = fingerprint.trim();
+    if !fingerprint.starts_with("SHA256:") {
+        return Err(format!("Invalid SSH host key fingerprint: {}", fingerprint));
+    }
+    app_state
+        .ssh_trusted_host_keys
+        .lock()
+        .await
+        .insert(connection_id, fingerprint.to_string());
+    Ok(())
+}
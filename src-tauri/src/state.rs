@@ -1,21 +1,109 @@
+use crate::autocomplete::MetadataCache;
+use crate::clickhouse_manager::ClickHouseClient;
+use crate::connection_stats::{PendingUsage, QueryStats};
+use crate::couchbase_manager::CouchbaseClient;
+use crate::dialect::ServerProfile;
+use crate::duckdb_manager::SharedDuckDbConnection;
+use crate::elastic_manager::ElasticClient;
+use crate::models::TimestampDisplay;
+use crate::mysql_manager::ResultStream;
+use crate::query_queue::{QueryQueue, RunningQuery};
+use crate::result_cache::{CachedResult, ResultCacheKey};
+use crate::rocksdb_manager::RocksDbStore;
+use crate::session::QuerySession;
+use crate::ssh_tunnel::SshTunnel;
+use crate::vault::MasterKey;
 use sqlx::{MySqlPool, SqlitePool};
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use tauri::AppHandle;
 use tokio::sync::Mutex;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pools: Arc<Mutex<HashMap<String, MySqlPool>>>,
+    // use_database 设置的当前库（MySQL 系）
+    pub active_databases: Arc<Mutex<HashMap<i64, String>>>,
     pub sqlite_pools: Arc<Mutex<HashMap<i64, SqlitePool>>>,
     pub redis_clients: Arc<Mutex<HashMap<String, redis::Client>>>,
+    pub memcached_clients: Arc<Mutex<HashMap<i64, memcache::Client>>>,
+    pub mongo_clients: Arc<Mutex<HashMap<i64, mongodb::Client>>>,
+    pub clickhouse_clients: Arc<Mutex<HashMap<i64, ClickHouseClient>>>,
+    pub cassandra_sessions: Arc<Mutex<HashMap<i64, Arc<scylla::client::session::Session>>>>,
+    pub duckdb_connections: Arc<Mutex<HashMap<i64, SharedDuckDbConnection>>>,
+    pub elastic_clients: Arc<Mutex<HashMap<i64, ElasticClient>>>,
+    pub dynamo_clients: Arc<Mutex<HashMap<i64, aws_sdk_dynamodb::Client>>>,
+    pub couchbase_clients: Arc<Mutex<HashMap<i64, CouchbaseClient>>>,
+    pub rocksdb_stores: Arc<Mutex<HashMap<i64, RocksDbStore>>>,
+    pub neo4j_graphs: Arc<Mutex<HashMap<i64, neo4rs::Graph>>>,
+    pub server_profiles: Arc<Mutex<HashMap<i64, ServerProfile>>>,
+    // MySQL 系连接的 TIMESTAMP 显示方式，创建连接池时按连接配置写入
+    pub timestamp_displays: Arc<Mutex<HashMap<i64, TimestampDisplay>>>,
+    pub ssh_tunnels: Arc<Mutex<HashMap<String, SshTunnel>>>,
+    pub ssh_key_passphrases: Arc<Mutex<HashMap<i64, String>>>,
+    // 用户核对后信任的 SSH 主机密钥指纹（known_hosts 中没有记录的主机），key 为连接 id
+    pub ssh_trusted_host_keys: Arc<Mutex<HashMap<i64, String>>>,
+    pub master_key: Arc<Mutex<Option<MasterKey>>>,
+    pub query_stats: Arc<Mutex<HashMap<i64, QueryStats>>>,
+    // 等待写入 connections 表的最近使用时间和累计次数
+    pub pending_usage: Arc<Mutex<HashMap<i64, PendingUsage>>>,
+    // 每个连接的并发限制和排队状态
+    pub query_queues: Arc<Mutex<HashMap<i64, QueryQueue>>>,
+    // 执行中的查询，key 为执行 id
+    pub running_queries: Arc<Mutex<HashMap<String, RunningQuery>>>,
+    // execute_sql_streaming 打开的结果句柄
+    pub result_streams: Arc<Mutex<HashMap<String, ResultStream>>>,
+    // 开启了结果缓存的连接的查询结果
+    pub result_cache: Arc<Mutex<HashMap<ResultCacheKey, CachedResult>>>,
+    // 落盘结果使用的临时库（首次落盘时创建）和落盘结果 id → 连接 id
+    pub spill_pool: Arc<Mutex<Option<SqlitePool>>>,
+    pub spilled_results: Arc<Mutex<HashMap<String, i64>>>,
+    // 执行中的 run_sql_file / search_database 的取消标记，key 为执行 id
+    pub cancel_flags: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    // 编辑器标签页的查询会话，key 为会话 id
+    pub sessions: Arc<Mutex<HashMap<String, QuerySession>>>,
+    // 自动补全用的库表元数据，key 为连接 id
+    pub autocomplete_cache: Arc<Mutex<HashMap<i64, MetadataCache>>>,
+    // 用于向前端发送事件（连接状态等），setup 时设置
+    pub app_handle: Option<AppHandle>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             pools: Arc::new(Mutex::new(HashMap::new())),
+            active_databases: Arc::new(Mutex::new(HashMap::new())),
             sqlite_pools: Arc::new(Mutex::new(HashMap::new())),
             redis_clients: Arc::new(Mutex::new(HashMap::new())),
+            memcached_clients: Arc::new(Mutex::new(HashMap::new())),
+            mongo_clients: Arc::new(Mutex::new(HashMap::new())),
+            clickhouse_clients: Arc::new(Mutex::new(HashMap::new())),
+            cassandra_sessions: Arc::new(Mutex::new(HashMap::new())),
+            duckdb_connections: Arc::new(Mutex::new(HashMap::new())),
+            elastic_clients: Arc::new(Mutex::new(HashMap::new())),
+            dynamo_clients: Arc::new(Mutex::new(HashMap::new())),
+            couchbase_clients: Arc::new(Mutex::new(HashMap::new())),
+            rocksdb_stores: Arc::new(Mutex::new(HashMap::new())),
+            neo4j_graphs: Arc::new(Mutex::new(HashMap::new())),
+            server_profiles: Arc::new(Mutex::new(HashMap::new())),
+            timestamp_displays: Arc::new(Mutex::new(HashMap::new())),
+            ssh_tunnels: Arc::new(Mutex::new(HashMap::new())),
+            ssh_key_passphrases: Arc::new(Mutex::new(HashMap::new())),
+            ssh_trusted_host_keys: Arc::new(Mutex::new(HashMap::new())),
+            master_key: Arc::new(Mutex::new(None)),
+            query_stats: Arc::new(Mutex::new(HashMap::new())),
+            pending_usage: Arc::new(Mutex::new(HashMap::new())),
+            query_queues: Arc::new(Mutex::new(HashMap::new())),
+            running_queries: Arc::new(Mutex::new(HashMap::new())),
+            result_streams: Arc::new(Mutex::new(HashMap::new())),
+            result_cache: Arc::new(Mutex::new(HashMap::new())),
+            spill_pool: Arc::new(Mutex::new(None)),
+            spilled_results: Arc::new(Mutex::new(HashMap::new())),
+            cancel_flags: Arc::new(Mutex::new(HashMap::new())),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            autocomplete_cache: Arc::new(Mutex::new(HashMap::new())),
+            app_handle: None,
         }
     }
 }
@@ -0,0 +1,445 @@
+use crate::alter_table::quote_identifier;
+use crate::connection_stats::record_query;
+use crate::db::{connection_flavor, DbState};
+use crate::models::{BrowseFilter, BrowseResult, BrowseSort, ColumnInfo};
+use crate::query_queue::{
+    acquire_query_slot, finish_running_query, new_execution_id, register_running_query,
+    RunningQuery,
+};
+use crate::sql_classifier::SqlFlavor;
+use crate::sqlite_manager::SqliteInterruptHandle;
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use serde_json::Value;
+use sqlx::pool::PoolConnection;
+use sqlx::{Column, Executor, MySql, Row, Sqlite, Statement, TypeInfo};
+use std::time::Instant;
+use tauri::{command, State};
+
+const BROWSE_DEFAULT_PAGE_SIZE: u32 = 100;
+const BROWSE_MAX_PAGE_SIZE: u32 = 1000;
+
+// 表中列的定义，用于校验排序 / 过滤的列名并补充结果列信息
+struct SourceColumn {
+    name: String,
+    schema: Option<String>,
+    nullable: bool,
+    primary_key: bool,
+    default_value: Option<String>,
+    auto_increment: bool,
+}
+
+struct BrowseQuery {
+    page_sql: String,
+    count_sql: String,
+    // 过滤条件的参数，两条语句共用
+    binds: Vec<Value>,
+}
+
+// 列名按表中的定义校验，精确匹配优先，其次忽略大小写
+fn resolve_column<'a>(columns: &'a [SourceColumn], name: &str) -> Result<&'a SourceColumn, String> {
+    columns
+        .iter()
+        .find(|c| c.name == name)
+        .or_else(|| columns.iter().find(|c| c.name.eq_ignore_ascii_case(name)))
+        .ok_or_else(|| format!("Unknown column: {}", name))
+}
+
+// contains / starts_with 等按字面匹配，用 ! 转义 LIKE 通配符（MySQL 的反斜杠转义受 sql_mode 影响）
+fn like_literal(value: &Value, operator: &str) -> Result<String, String> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return Err(format!("Filter {} requires a text value", operator)),
+    };
+    Ok(text
+        .replace('!', "!!")
+        .replace('%', "!%")
+        .replace('_', "!_"))
+}
+
+fn filter_condition(
+    filter: &BrowseFilter,
+    column: &str,
+    binds: &mut Vec<Value>,
+) -> Result<String, String> {
+    let operator = filter.operator.to_lowercase();
+    let comparison = match operator.as_str() {
+        "eq" => Some("="),
+        "ne" => Some("<>"),
+        "lt" => Some("<"),
+        "lte" => Some("<="),
+        "gt" => Some(">"),
+        "gte" => Some(">="),
+        "like" => Some("LIKE"),
+        "not_like" => Some("NOT LIKE"),
+        _ => None,
+    };
+    if let Some(comparison) = comparison {
+        match &filter.value {
+            Value::Null => {
+                return Err(format!(
+                    "Filter {} on {} requires a value; use is_null / is_not_null for NULL",
+                    operator, filter.column
+                ))
+            }
+            Value::Array(_) | Value::Object(_) => {
+                return Err(format!("Filter {} requires a scalar value", operator))
+            }
+            value => binds.push(value.clone()),
+        }
+        return Ok(format!("{} {} ?", column, comparison));
+    }
+
+    match operator.as_str() {
+        "contains" | "not_contains" | "starts_with" | "ends_with" => {
+            let literal = like_literal(&filter.value, &operator)?;
+            let pattern = match operator.as_str() {
+                "starts_with" => format!("{}%", literal),
+                "ends_with" => format!("%{}", literal),
+                _ => format!("%{}%", literal),
+            };
+            binds.push(Value::String(pattern));
+            let not = if operator == "not_contains" {
+                "NOT "
+            } else {
+                ""
+            };
+            Ok(format!("{} {}LIKE ? ESCAPE '!'", column, not))
+        }
+        "in" | "not_in" => {
+            let values = match &filter.value {
+                Value::Array(values) if !values.is_empty() => values,
+                _ => return Err(format!("Filter {} requires a non-empty array", operator)),
+            };
+            binds.extend(values.iter().cloned());
+            let not = if operator == "not_in" { "NOT " } else { "" };
+            Ok(format!(
+                "{} {}IN ({})",
+                column,
+                not,
+                vec!["?"; values.len()].join(", ")
+            ))
+        }
+        "is_null" => Ok(format!("{} IS NULL", column)),
+        "is_not_null" => Ok(format!("{} IS NOT NULL", column)),
+        other => Err(format!("Unsupported filter operator: {}", other)),
+    }
+}
+
+// 标识符全部来自表的列定义并加引号，过滤值全部作为参数绑定
+fn build_query(
+    table_ref: &str,
+    columns: &[SourceColumn],
+    sort: &[BrowseSort],
+    filters: &[BrowseFilter],
+    flavor: SqlFlavor,
+    page_size: u32,
+    offset: u64,
+) -> Result<BrowseQuery, String> {
+    let mut binds = Vec::new();
+    let conditions = filters
+        .iter()
+        .map(|filter| {
+            let column = resolve_column(columns, &filter.column)?;
+            filter_condition(filter, &quote_identifier(&column.name, flavor), &mut binds)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
+    let mut order = sort
+        .iter()
+        .map(|s| {
+            let column = resolve_column(columns, &s.column)?;
+            let direction = if s.descending { "DESC" } else { "ASC" };
+            Ok(format!(
+                "{} {}",
+                quote_identifier(&column.name, flavor),
+                direction
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    // 未指定排序时按主键排序，保证翻页稳定
+    if order.is_empty() {
+        order = columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| quote_identifier(&c.name, flavor))
+            .collect();
+    }
+    let order_clause = if order.is_empty() {
+        String::new()
+    } else {
+        format!(" ORDER BY {}", order.join(", "))
+    };
+
+    Ok(BrowseQuery {
+        page_sql: format!(
+            "SELECT * FROM {}{}{} LIMIT {} OFFSET {}",
+            table_ref, where_clause, order_clause, page_size, offset
+        ),
+        count_sql: format!("SELECT COUNT(*) FROM {}{}", table_ref, where_clause),
+        binds,
+    })
+}
+
+fn apply_source_columns(columns: &mut [ColumnInfo], source: &[SourceColumn], table: &str) {
+    for column in columns.iter_mut() {
+        let Some(definition) = source.iter().find(|c| c.name == column.name) else {
+            continue;
+        };
+        column.schema = definition.schema.clone();
+        column.table = Some(table.to_string());
+        column.nullable = Some(definition.nullable);
+        column.primary_key = definition.primary_key;
+        column.default_value = definition.default_value.clone();
+        column.auto_increment = definition.auto_increment;
+    }
+}
+
+async fn mysql_source_columns(
+    conn: &mut PoolConnection<MySql>,
+    db_name: Option<&str>,
+    table: &str,
+) -> Result<Vec<SourceColumn>, String> {
+    let rows = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR), CAST(TABLE_SCHEMA AS CHAR), CAST(IS_NULLABLE AS CHAR), \
+         CAST(COLUMN_KEY AS CHAR), CAST(COLUMN_DEFAULT AS CHAR), CAST(EXTRA AS CHAR) \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(db_name)
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let text = |i: usize| row.try_get_unchecked::<Option<String>, _>(i).ok().flatten();
+            SourceColumn {
+                name: text(0).unwrap_or_default(),
+                schema: text(1),
+                nullable: text(2).as_deref() == Some("YES"),
+                primary_key: text(3).as_deref() == Some("PRI"),
+                default_value: text(4),
+                auto_increment: text(5).is_some_and(|v| v.contains("auto_increment")),
+            }
+        })
+        .collect())
+}
+
+async fn sqlite_source_columns(
+    conn: &mut PoolConnection<Sqlite>,
+    table: &str,
+) -> Result<Vec<SourceColumn>, String> {
+    let rows = sqlx::query_as::<_, (String, String, bool, Option<String>, i64)>(
+        "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?) ORDER BY cid",
+    )
+    .bind(table)
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let key_count = rows.iter().filter(|row| row.4 > 0).count();
+    Ok(rows
+        .into_iter()
+        .map(
+            |(name, declared, not_null, default_value, key_position)| SourceColumn {
+                name,
+                schema: None,
+                nullable: !not_null && key_position == 0,
+                primary_key: key_position > 0,
+                default_value,
+                // INTEGER PRIMARY KEY 是 rowid 的别名，自动分配
+                auto_increment: key_position > 0
+                    && key_count == 1
+                    && declared.eq_ignore_ascii_case("integer"),
+            },
+        )
+        .collect())
+}
+
+// 分页浏览表数据：排序和过滤的列名按表定义校验并加引号，过滤值全部参数绑定，
+// 返回当前页的行和满足条件的总行数。page 从 1 开始；可通过 cancel_query(execution_id) 取消
+#[command]
+pub async fn browse_table(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    sort: Option<Vec<BrowseSort>>,
+    filters: Option<Vec<BrowseFilter>>,
+    db_name: Option<String>,
+    execution_id: Option<String>,
+) -> Result<BrowseResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Table browsing").await?;
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size
+        .unwrap_or(BROWSE_DEFAULT_PAGE_SIZE)
+        .clamp(1, BROWSE_MAX_PAGE_SIZE);
+    let offset = u64::from(page - 1) * u64::from(page_size);
+    let sort = sort.unwrap_or_default();
+    let filters = filters.unwrap_or_default();
+    let table_ref = match (&db_name, flavor) {
+        (Some(db), SqlFlavor::MySql) => format!(
+            "{}.{}",
+            quote_identifier(db, flavor),
+            quote_identifier(&table, flavor)
+        ),
+        _ => quote_identifier(&table, flavor),
+    };
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let started = Instant::now();
+    let (columns, rows, total_rows) = match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            let source = mysql_source_columns(&mut conn, db_name.as_deref(), &table).await?;
+            if source.is_empty() {
+                return Err(format!("Table {} not found", table));
+            }
+            let query = build_query(
+                &table_ref, &source, &sort, &filters, flavor, page_size, offset,
+            )?;
+            mysql_manager::register_mysql_query(
+                &app_state,
+                &mut conn,
+                &execution_id,
+                connection_id,
+                db_name,
+            )
+            .await;
+            let timestamp_display =
+                mysql_manager::timestamp_display_of(&app_state, connection_id).await;
+            let result = async {
+                let mut count = sqlx::query(&query.count_sql);
+                for value in &query.binds {
+                    count = mysql_manager::bind_json_value(count, value);
+                }
+                let total: i64 = count
+                    .fetch_one(&mut *conn)
+                    .await
+                    .and_then(|row| row.try_get(0))
+                    .map_err(|e| format!("Failed to count rows: {}", e))?;
+                let mut page_query = sqlx::query(&query.page_sql);
+                for value in &query.binds {
+                    page_query = mysql_manager::bind_json_value(page_query, value);
+                }
+                let rows = page_query
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| format!("Failed to fetch rows: {}", e))?;
+                let mut columns: Vec<ColumnInfo> = match rows.first() {
+                    Some(row) => row
+                        .columns()
+                        .iter()
+                        .map(mysql_manager::column_info)
+                        .collect(),
+                    None => conn
+                        .prepare(&query.page_sql)
+                        .await
+                        .map(|stmt| {
+                            stmt.columns()
+                                .iter()
+                                .map(mysql_manager::column_info)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                };
+                apply_source_columns(&mut columns, &source, &table);
+                Ok::<_, String>((
+                    columns,
+                    rows.iter()
+                        .map(|row| mysql_manager::row_to_json(row, timestamp_display))
+                        .collect(),
+                    u64::try_from(total).unwrap_or(0),
+                ))
+            }
+            .await;
+            finish_running_query(&app_state, &execution_id).await;
+            result?
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            let source = sqlite_source_columns(&mut conn, &table).await?;
+            if source.is_empty() {
+                return Err(format!("Table {} not found", table));
+            }
+            let query = build_query(
+                &table_ref, &source, &sort, &filters, flavor, page_size, offset,
+            )?;
+            let handle = SqliteInterruptHandle::of(&mut conn).await?;
+            register_running_query(&app_state, &execution_id, RunningQuery::Sqlite(handle)).await;
+            let result = async {
+                let mut count = sqlx::query(&query.count_sql);
+                for value in &query.binds {
+                    count = sqlite_manager::bind_json_value(count, value);
+                }
+                let total: i64 = count
+                    .fetch_one(&mut *conn)
+                    .await
+                    .and_then(|row| row.try_get(0))
+                    .map_err(|e| format!("Failed to count rows: {}", e))?;
+                let mut page_query = sqlx::query(&query.page_sql);
+                for value in &query.binds {
+                    page_query = sqlite_manager::bind_json_value(page_query, value);
+                }
+                let rows = page_query
+                    .fetch_all(&mut *conn)
+                    .await
+                    .map_err(|e| format!("Failed to fetch rows: {}", e))?;
+                let column_info = |col: &sqlx::sqlite::SqliteColumn| ColumnInfo {
+                    name: col.name().to_string(),
+                    type_name: col.type_info().name().to_string(),
+                    ..Default::default()
+                };
+                let mut columns: Vec<ColumnInfo> = match rows.first() {
+                    Some(row) => row.columns().iter().map(column_info).collect(),
+                    None => conn
+                        .prepare(&query.page_sql)
+                        .await
+                        .map(|stmt| stmt.columns().iter().map(column_info).collect())
+                        .unwrap_or_default(),
+                };
+                apply_source_columns(&mut columns, &source, &table);
+                Ok::<_, String>((
+                    columns,
+                    rows.iter().map(sqlite_manager::row_to_json).collect(),
+                    u64::try_from(total).unwrap_or(0),
+                ))
+            }
+            .await;
+            finish_running_query(&app_state, &execution_id).await;
+            result?
+        }
+    };
+    record_query(&app_state, &db_state, connection_id, started.elapsed()).await;
+
+    Ok(BrowseResult {
+        columns,
+        rows,
+        total_rows,
+        page,
+        page_size,
+    })
+}
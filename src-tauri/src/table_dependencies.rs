@@ -0,0 +1,520 @@
+use crate::alter_table::{
+    apply_ddl, qualified_name, quote_identifier, FOREIGN_KEYS_OFF, FOREIGN_KEYS_ON,
+    FOREIGN_KEY_CHECKS_OFF, FOREIGN_KEY_CHECKS_ON,
+};
+use crate::data_sync::{load_structure, SideConnection};
+use crate::data_transfer::{cell_literal, cell_rows};
+use crate::db::{connection_flavor, DbState};
+use crate::models::{RelationshipEdge, SchemaTarget, TableDumpArgs, TruncateTablesResult};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::relationship_graph::load_relationships;
+use crate::result_export::sql_literal;
+use crate::schema_diff::is_generated;
+use crate::sql_classifier::SqlFlavor;
+use crate::sqlite_manager;
+use crate::state::AppState;
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, Emitter, State};
+use tokio::fs::File;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+// 前端监听该事件展示导出进度
+pub const TABLE_DUMP_PROGRESS_EVENT: &str = "table-dump-progress";
+
+// 每条 INSERT 包含的行数
+const DUMP_ROWS_PER_INSERT: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableDumpProgress {
+    pub execution_id: String,
+    pub table: String,
+    // 当前表的序号，从 1 开始
+    pub table_index: usize,
+    pub table_count: usize,
+    pub table_rows: u64,
+    pub done: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableDumpResult {
+    pub table: String,
+    pub rows: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableDumpSummary {
+    pub execution_id: String,
+    pub path: String,
+    // 按写入顺序（被引用的表在前）
+    pub tables: Vec<TableDumpResult>,
+    pub foreign_key_checks_disabled: bool,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+pub struct DependencyOrder {
+    // 被引用的表（父表）在前；环上的表按传入顺序放在最后
+    pub order: Vec<String>,
+    pub cyclic: bool,
+    pub self_referencing: bool,
+}
+
+fn unique_tables(tables: Vec<String>) -> Result<Vec<String>, String> {
+    let mut seen = HashSet::new();
+    let tables: Vec<String> = tables
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty() && seen.insert(t.clone()))
+        .collect();
+    if tables.is_empty() {
+        return Err("No tables selected".to_string());
+    }
+    Ok(tables)
+}
+
+// 只考虑选中的表之间、同一库内的外键
+pub fn dependency_order(tables: &[String], edges: &[RelationshipEdge]) -> DependencyOrder {
+    let selected: HashSet<&str> = tables.iter().map(String::as_str).collect();
+    let mut parents: HashMap<&str, HashSet<&str>> = tables
+        .iter()
+        .map(|table| (table.as_str(), HashSet::new()))
+        .collect();
+    let mut self_referencing = false;
+    for edge in edges.iter().filter(|e| e.to_schema.is_none()) {
+        let (child, parent) = (edge.from_table.as_str(), edge.to_table.as_str());
+        if !selected.contains(child) || !selected.contains(parent) {
+            continue;
+        }
+        if child == parent {
+            self_referencing = true;
+        } else if let Some(set) = parents.get_mut(child) {
+            set.insert(parent);
+        }
+    }
+
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut order = Vec::with_capacity(tables.len());
+    loop {
+        let ready: Vec<&String> = tables
+            .iter()
+            .filter(|t| !placed.contains(t.as_str()))
+            .filter(|t| parents[t.as_str()].iter().all(|p| placed.contains(p)))
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        for table in ready {
+            placed.insert(table);
+            order.push(table.clone());
+        }
+    }
+    let cyclic = order.len() < tables.len();
+    order.extend(
+        tables
+            .iter()
+            .filter(|t| !placed.contains(t.as_str()))
+            .cloned(),
+    );
+    DependencyOrder {
+        order,
+        cyclic,
+        self_referencing,
+    }
+}
+
+// 按外键依赖顺序清空多张表（引用方先清空）：MySQL 为 TRUNCATE TABLE，选中的表之间有外键时
+// 执行期间关闭 FOREIGN_KEY_CHECKS；SQLite 在一个事务中 DELETE 并重置 AUTOINCREMENT 计数，
+// 只有循环外键才关闭外键检查。未选中的表引用了要清空的表时拒绝执行（关闭检查会留下孤儿行），
+// 只检查同一库中的引用。未确认时只返回语句供预览；生产环境需要确认令牌
+#[command]
+pub async fn truncate_tables(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    tables: Vec<String>,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<TruncateTablesResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Truncating tables").await?;
+    let tables = unique_tables(tables)?;
+    let (_, edges) = load_relationships(
+        &app_state,
+        &db_state,
+        connection_id,
+        db_name.clone(),
+        flavor,
+    )
+    .await?;
+    let selected: HashSet<&str> = tables.iter().map(String::as_str).collect();
+    if let Some(edge) = edges.iter().find(|e| {
+        e.to_schema.is_none()
+            && selected.contains(e.to_table.as_str())
+            && !selected.contains(e.from_table.as_str())
+    }) {
+        return Err(format!(
+            "Cannot truncate {}: it is referenced by {}, which is not selected",
+            edge.to_table, edge.from_table
+        ));
+    }
+
+    let dependencies = dependency_order(&tables, &edges);
+    let order: Vec<String> = dependencies.order.into_iter().rev().collect();
+    let related = edges.iter().any(|e| {
+        e.to_schema.is_none()
+            && selected.contains(e.to_table.as_str())
+            && selected.contains(e.from_table.as_str())
+    });
+    let mut statements = Vec::new();
+    let foreign_key_checks_disabled = match flavor {
+        SqlFlavor::MySql => {
+            // InnoDB 不允许 TRUNCATE 被外键引用的表，即使引用方已经清空
+            if related {
+                statements.push(FOREIGN_KEY_CHECKS_OFF.to_string());
+            }
+            statements.extend(order.iter().map(|table| {
+                format!(
+                    "TRUNCATE TABLE {}",
+                    qualified_name(db_name.as_deref(), table, flavor)
+                )
+            }));
+            if related {
+                statements.push(FOREIGN_KEY_CHECKS_ON.to_string());
+            }
+            related
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let foreign_keys_enabled = sqlx::query_scalar::<_, i64>("PRAGMA foreign_keys")
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| format!("Failed to read foreign_keys: {}", e))?
+                == 1;
+            let has_sequence = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'sqlite_sequence'",
+            )
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| format!("Failed to read sqlite_master: {}", e))?
+                > 0;
+            // 自引用的行在同一条 DELETE 中删除，只有循环外键需要关闭检查
+            let disable = foreign_keys_enabled && dependencies.cyclic;
+            if disable {
+                statements.push(FOREIGN_KEYS_OFF.to_string());
+            }
+            statements.extend(
+                order
+                    .iter()
+                    .map(|table| format!("DELETE FROM {}", quote_identifier(table, flavor))),
+            );
+            if has_sequence {
+                statements.push(format!(
+                    "DELETE FROM sqlite_sequence WHERE name IN ({})",
+                    order
+                        .iter()
+                        .map(|table| sql_literal(&table.clone().into(), flavor))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            if disable {
+                statements.push(FOREIGN_KEYS_ON.to_string());
+            }
+            disable
+        }
+    };
+
+    if confirmed != Some(true) {
+        return Ok(TruncateTablesResult {
+            order,
+            statements,
+            foreign_key_checks_disabled,
+            executed: false,
+        });
+    }
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        db_name,
+        &statements,
+        confirm_token.as_deref(),
+    )
+    .await?;
+    Ok(TruncateTablesResult {
+        order,
+        statements,
+        foreign_key_checks_disabled,
+        executed: true,
+    })
+}
+
+fn text_column(row: &sqlx::mysql::MySqlRow, index: usize) -> String {
+    row.try_get_unchecked::<String, _>(index)
+        .ok()
+        .or_else(|| {
+            row.try_get_unchecked::<Vec<u8>, _>(index)
+                .ok()
+                .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+        })
+        .unwrap_or_default()
+}
+
+// 表的建立语句：MySQL 为 SHOW CREATE TABLE（含索引），SQLite 为 sqlite_master 中的表和索引
+async fn create_statements(
+    conn: &mut SideConnection,
+    db_name: Option<&str>,
+    table: &str,
+) -> Result<Vec<String>, String> {
+    match conn {
+        SideConnection::MySql(conn) => {
+            let row = sqlx::query(&format!(
+                "SHOW CREATE TABLE {}",
+                qualified_name(db_name, table, SqlFlavor::MySql)
+            ))
+            .fetch_one(&mut **conn)
+            .await
+            .map_err(|e| format!("Failed to read definition of {}: {}", table, e))?;
+            Ok(vec![text_column(&row, 1)])
+        }
+        SideConnection::Sqlite(conn) => sqlx::query_scalar::<_, String>(
+            "SELECT sql FROM sqlite_master \
+             WHERE tbl_name = ? AND type IN ('table', 'index') AND sql IS NOT NULL \
+             ORDER BY type = 'index', name",
+        )
+        .bind(table)
+        .fetch_all(&mut **conn)
+        .await
+        .map_err(|e| format!("Failed to read definition of {}: {}", table, e)),
+    }
+}
+
+struct Dump<'a> {
+    app_state: &'a AppState,
+    flavor: SqlFlavor,
+    file: BufWriter<File>,
+    cancel: Arc<AtomicBool>,
+    progress: TableDumpProgress,
+}
+
+impl Dump<'_> {
+    async fn write(&mut self, text: &str) -> Result<(), String> {
+        self.file
+            .write_all(text.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write dump file: {}", e))
+    }
+
+    fn emit(&self) {
+        if let Some(app) = self.app_state.app_handle.as_ref() {
+            let _ = app.emit(TABLE_DUMP_PROGRESS_EVENT, self.progress.clone());
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    // 流式读取表中的行，每 DUMP_ROWS_PER_INSERT 行写成一条 INSERT
+    async fn write_rows(
+        &mut self,
+        conn: &mut SideConnection,
+        select_sql: &str,
+        insert_sql: &str,
+    ) -> Result<u64, String> {
+        let mut rows = cell_rows(conn, select_sql);
+        let mut values = Vec::with_capacity(DUMP_ROWS_PER_INSERT);
+        let mut count = 0;
+        loop {
+            let row = rows
+                .try_next()
+                .await
+                .map_err(|e| format!("Failed to read rows: {}", e))?;
+            let end = row.is_none() || self.cancelled();
+            if let Some(row) = row.filter(|_| !end) {
+                let literals: Vec<String> = row
+                    .iter()
+                    .map(|cell| cell_literal(cell, self.flavor))
+                    .collect();
+                values.push(format!("({})", literals.join(", ")));
+            }
+            if values.len() >= DUMP_ROWS_PER_INSERT || (end && !values.is_empty()) {
+                count += values.len() as u64;
+                let statement = format!("{} VALUES\n{};\n", insert_sql, values.join(",\n"));
+                self.write(&statement).await?;
+                values.clear();
+                self.progress.table_rows = count;
+                self.emit();
+            }
+            if end {
+                return Ok(count);
+            }
+        }
+    }
+}
+
+// 把多张表的结构和数据导出为 SQL 文件，按外键依赖排序（被引用的表在前），导入时不会因外键失败。
+// 选中的表之间有循环或自引用外键时，文件首尾关闭 / 恢复外键检查。SQLite 只支持 main 库。
+// 每写出一批行发送 table-dump-progress 事件，通过 cancel_query(execution_id) 取消，
+// 已写出的部分保留在文件中
+#[command]
+pub async fn dump_tables(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: TableDumpArgs,
+    execution_id: Option<String>,
+) -> Result<TableDumpSummary, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Dumping tables").await?;
+    let tables = unique_tables(args.tables.clone())?;
+    let db_name = args.db_name.clone();
+    let (_, edges) = load_relationships(
+        &app_state,
+        &db_state,
+        connection_id,
+        db_name.clone(),
+        flavor,
+    )
+    .await?;
+    let dependencies = dependency_order(&tables, &edges);
+    let disable_checks = dependencies.cyclic || dependencies.self_referencing;
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let target = SchemaTarget {
+        connection_id,
+        db_name: db_name.clone(),
+    };
+    let mut conn = SideConnection::open(&app_state, &db_state, &target, flavor).await?;
+    let file = File::create(&args.path)
+        .await
+        .map_err(|e| format!("Failed to create dump file: {}", e))?;
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(execution_id.clone(), cancel.clone());
+    let mut dump = Dump {
+        app_state: &app_state,
+        flavor,
+        file: BufWriter::new(file),
+        cancel,
+        progress: TableDumpProgress {
+            execution_id: execution_id.clone(),
+            table: String::new(),
+            table_index: 0,
+            table_count: tables.len(),
+            table_rows: 0,
+            done: false,
+        },
+    };
+
+    let started = Instant::now();
+    let mut results = Vec::new();
+    let outcome: Result<(), String> = async {
+        let header = match (flavor, disable_checks) {
+            (SqlFlavor::MySql, true) => {
+                format!("SET NAMES utf8mb4;\n{};\n\n", FOREIGN_KEY_CHECKS_OFF)
+            }
+            (SqlFlavor::MySql, false) => "SET NAMES utf8mb4;\n\n".to_string(),
+            // foreign_keys 在事务中设置无效，需要放在 BEGIN 之前
+            (SqlFlavor::Sqlite, true) => format!("{};\nBEGIN TRANSACTION;\n\n", FOREIGN_KEYS_OFF),
+            (SqlFlavor::Sqlite, false) => "BEGIN TRANSACTION;\n\n".to_string(),
+        };
+        dump.write(&header).await?;
+        // 引用方先删除
+        if args.drop_existing.unwrap_or(false) {
+            for table in dependencies.order.iter().rev() {
+                let statement = format!(
+                    "DROP TABLE IF EXISTS {};\n",
+                    quote_identifier(table, flavor)
+                );
+                dump.write(&statement).await?;
+            }
+            dump.write("\n").await?;
+        }
+
+        for (index, table) in dependencies.order.iter().enumerate() {
+            if dump.cancelled() {
+                break;
+            }
+            dump.progress.table = table.clone();
+            dump.progress.table_index = index + 1;
+            dump.progress.table_rows = 0;
+            dump.emit();
+
+            for statement in create_statements(&mut conn, db_name.as_deref(), table).await? {
+                dump.write(&format!("{};\n", statement)).await?;
+            }
+            dump.write("\n").await?;
+            if !args.include_data.unwrap_or(true) {
+                results.push(TableDumpResult {
+                    table: table.clone(),
+                    rows: 0,
+                });
+                continue;
+            }
+
+            // 生成列的值由表达式计算，不能写入
+            let structure = load_structure(&app_state, &db_state, &target, flavor, table).await?;
+            let columns: Vec<String> = structure
+                .columns
+                .iter()
+                .filter(|c| !is_generated(c))
+                .map(|c| quote_identifier(&c.name, flavor))
+                .collect();
+            let select_sql = format!(
+                "SELECT {} FROM {}",
+                columns.join(", "),
+                qualified_name(db_name.as_deref(), table, flavor)
+            );
+            let insert_sql = format!(
+                "INSERT INTO {} ({})",
+                quote_identifier(table, flavor),
+                columns.join(", ")
+            );
+            let rows = dump.write_rows(&mut conn, &select_sql, &insert_sql).await?;
+            dump.write("\n").await?;
+            results.push(TableDumpResult {
+                table: table.clone(),
+                rows,
+            });
+        }
+
+        let footer = match (flavor, disable_checks) {
+            (SqlFlavor::MySql, true) => format!("{};\n", FOREIGN_KEY_CHECKS_ON),
+            (SqlFlavor::MySql, false) => String::new(),
+            (SqlFlavor::Sqlite, true) => format!("COMMIT;\n{};\n", FOREIGN_KEYS_ON),
+            (SqlFlavor::Sqlite, false) => "COMMIT;\n".to_string(),
+        };
+        dump.write(&footer).await?;
+        dump.file
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write dump file: {}", e))
+    }
+    .await;
+
+    app_state.cancel_flags.lock().await.remove(&execution_id);
+    dump.progress.done = true;
+    dump.emit();
+    outcome?;
+
+    Ok(TableDumpSummary {
+        execution_id,
+        path: args.path,
+        tables: results,
+        foreign_key_checks_disabled: disable_checks,
+        cancelled: dump.cancelled(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,299 @@
+use crate::alter_table::{qualified_name, quote_identifier};
+use crate::connection_stats::record_query;
+use crate::data_sync::SideConnection;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::ensure_writable;
+use crate::models::SchemaTarget;
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::query_queue::{acquire_query_slot, new_execution_id};
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use serde::Serialize;
+use sqlx::Row;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, Emitter, State};
+
+// 前端监听该事件展示维护进度
+pub const TABLE_MAINTENANCE_PROGRESS_EVENT: &str = "table-maintenance-progress";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableMaintenanceProgress {
+    pub execution_id: String,
+    pub operation: String,
+    // 正在处理的表，VACUUM 为库名
+    pub table: String,
+    // 当前表的序号，从 1 开始
+    pub table_index: usize,
+    pub table_count: usize,
+    pub done: bool,
+}
+
+// 服务端返回的一条消息，msg_type 为 status / info / note / warning / error
+#[derive(Debug, Serialize)]
+pub struct MaintenanceMessage {
+    pub msg_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableMaintenanceResult {
+    pub table: String,
+    pub statement: String,
+    // "ok" / "warning" / "error"
+    pub status: String,
+    pub messages: Vec<MaintenanceMessage>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableMaintenanceSummary {
+    pub execution_id: String,
+    pub operation: String,
+    pub tables: Vec<TableMaintenanceResult>,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+// 检查操作和选项，返回 (语句关键字, 选项)。MySQL 的 LOCAL 放在 TABLE 之前，其余选项放在表名之后
+fn maintenance_keyword(
+    operation: &str,
+    mode: Option<&str>,
+    flavor: SqlFlavor,
+) -> Result<(&'static str, Option<String>), String> {
+    let operation = operation.to_ascii_lowercase();
+    let mode = mode
+        .map(|mode| mode.trim().to_ascii_uppercase())
+        .filter(|mode| !mode.is_empty());
+    let (keyword, modes): (&str, &[&str]) = match (operation.as_str(), flavor) {
+        ("analyze", SqlFlavor::MySql) => ("ANALYZE", &["LOCAL"]),
+        ("optimize", SqlFlavor::MySql) => ("OPTIMIZE", &["LOCAL"]),
+        ("check", SqlFlavor::MySql) => (
+            "CHECK",
+            &[
+                "QUICK",
+                "FAST",
+                "MEDIUM",
+                "EXTENDED",
+                "CHANGED",
+                "FOR UPGRADE",
+            ],
+        ),
+        ("repair", SqlFlavor::MySql) => ("REPAIR", &["QUICK", "EXTENDED", "USE_FRM"]),
+        ("analyze", SqlFlavor::Sqlite) => ("ANALYZE", &[]),
+        ("vacuum", SqlFlavor::Sqlite) => ("VACUUM", &[]),
+        // integrity_check 默认，QUICK 为 quick_check
+        ("check", SqlFlavor::Sqlite) => ("CHECK", &["QUICK"]),
+        (other, _) => {
+            return Err(format!(
+                "Unsupported maintenance operation for this connection: {}",
+                other
+            ))
+        }
+    };
+    match mode {
+        Some(mode) if !modes.contains(&mode.as_str()) => Err(format!(
+            "Unsupported option for {}: {}",
+            keyword.to_lowercase(),
+            mode
+        )),
+        mode => Ok((keyword, mode)),
+    }
+}
+
+fn maintenance_statement(
+    keyword: &str,
+    mode: Option<&str>,
+    db_name: Option<&str>,
+    table: &str,
+    flavor: SqlFlavor,
+) -> String {
+    match flavor {
+        SqlFlavor::MySql => {
+            let name = qualified_name(db_name, table, flavor);
+            match mode {
+                Some("LOCAL") => format!("{} LOCAL TABLE {}", keyword, name),
+                Some(mode) => format!("{} TABLE {} {}", keyword, name, mode),
+                None => format!("{} TABLE {}", keyword, name),
+            }
+        }
+        SqlFlavor::Sqlite => {
+            // SQLite 的库名为 main / temp 或 ATTACH 的别名
+            let schema = quote_identifier(db_name.unwrap_or("main"), flavor);
+            match keyword {
+                "VACUUM" => format!("VACUUM {}", schema),
+                "CHECK" => format!(
+                    "PRAGMA {}.{}({})",
+                    schema,
+                    match mode {
+                        Some(_) => "quick_check",
+                        None => "integrity_check",
+                    },
+                    quote_identifier(table, flavor)
+                ),
+                _ => format!("ANALYZE {}.{}", schema, quote_identifier(table, flavor)),
+            }
+        }
+    }
+}
+
+// MySQL 每张表返回 Table / Op / Msg_type / Msg_text 行，最后一行 status 为 OK 或
+// "Table is already up to date" 时成功；SQLite 的检查结果只有一行 "ok" 时成功
+async fn run_maintenance(
+    conn: &mut SideConnection,
+    statement: &str,
+) -> Result<Vec<MaintenanceMessage>, String> {
+    let messages = match conn {
+        SideConnection::MySql(conn) => sqlx::query(statement)
+            .fetch_all(&mut **conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|row| {
+                let text = |index: usize| {
+                    row.try_get_unchecked::<Option<String>, _>(index)
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default()
+                };
+                MaintenanceMessage {
+                    msg_type: text(2).to_lowercase(),
+                    text: text(3),
+                }
+            })
+            .collect(),
+        SideConnection::Sqlite(conn) => sqlx::query(statement)
+            .fetch_all(&mut **conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|row| {
+                let text: String = row.try_get(0).unwrap_or_default();
+                MaintenanceMessage {
+                    msg_type: match text.as_str() {
+                        "ok" => "status".to_string(),
+                        _ => "error".to_string(),
+                    },
+                    text,
+                }
+            })
+            .collect(),
+    };
+    Ok(messages)
+}
+
+fn maintenance_status(messages: &[MaintenanceMessage]) -> &'static str {
+    let failed = messages.iter().any(|m| m.msg_type == "error")
+        || messages.iter().filter(|m| m.msg_type == "status").any(|m| {
+            !m.text.eq_ignore_ascii_case("ok")
+                && !m.text.eq_ignore_ascii_case("Table is already up to date")
+        });
+    if failed {
+        "error"
+    } else if messages.iter().any(|m| m.msg_type == "warning") {
+        "warning"
+    } else {
+        "ok"
+    }
+}
+
+// 对多张表依次执行维护操作：MySQL 为 analyze / optimize / check / repair，SQLite 为
+// analyze / vacuum / check（integrity_check）。mode 为可选的选项，如 MySQL CHECK 的
+// QUICK / EXTENDED、ANALYZE 的 LOCAL。单张表失败不会中断，结果中按表返回解析后的状态；
+// 每张表开始时发送 table-maintenance-progress 事件，cancel_query(execution_id) 在表之间生效
+#[command]
+pub async fn run_table_maintenance(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    operation: String,
+    tables: Vec<String>,
+    db_name: Option<String>,
+    mode: Option<String>,
+    execution_id: Option<String>,
+) -> Result<TableMaintenanceSummary, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Table maintenance").await?;
+    let (keyword, mode) = maintenance_keyword(&operation, mode.as_deref(), flavor)?;
+    // VACUUM 作用于整个库，不需要指定表
+    let tables = match keyword {
+        "VACUUM" => vec![db_name.clone().unwrap_or_else(|| "main".to_string())],
+        _ if tables.is_empty() => return Err("No tables selected".to_string()),
+        _ => tables,
+    };
+    if keyword != "CHECK" {
+        ensure_writable(&db_state, connection_id).await?;
+    }
+
+    let execution_id = execution_id.unwrap_or_else(new_execution_id);
+    let _permit = acquire_query_slot(&app_state, connection_id, &execution_id).await?;
+    let target = SchemaTarget {
+        connection_id,
+        db_name: db_name.clone(),
+    };
+    let mut conn = SideConnection::open(&app_state, &db_state, &target, flavor).await?;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    app_state
+        .cancel_flags
+        .lock()
+        .await
+        .insert(execution_id.clone(), cancel.clone());
+    let emit = |progress: &TableMaintenanceProgress| {
+        if let Some(app) = app_state.app_handle.as_ref() {
+            let _ = app.emit(TABLE_MAINTENANCE_PROGRESS_EVENT, progress.clone());
+        }
+    };
+    let mut progress = TableMaintenanceProgress {
+        execution_id: execution_id.clone(),
+        operation: keyword.to_lowercase(),
+        table: String::new(),
+        table_index: 0,
+        table_count: tables.len(),
+        done: false,
+    };
+    let started = Instant::now();
+    let mut results = Vec::new();
+    for (index, table) in tables.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        progress.table = table.clone();
+        progress.table_index = index + 1;
+        emit(&progress);
+
+        let statement =
+            maintenance_statement(keyword, mode.as_deref(), db_name.as_deref(), table, flavor);
+        let table_started = Instant::now();
+        let result = run_maintenance(&mut conn, &statement).await;
+        let elapsed = table_started.elapsed();
+        record_query(&app_state, &db_state, connection_id, elapsed).await;
+        let outcome = HistoryOutcome::of(&result, |_| HistoryOutcome::Done);
+        record_history(&db_state, connection_id, &statement, elapsed, outcome).await;
+        let messages = result.unwrap_or_else(|e| {
+            vec![MaintenanceMessage {
+                msg_type: "error".to_string(),
+                text: e,
+            }]
+        });
+        results.push(TableMaintenanceResult {
+            table: table.clone(),
+            statement,
+            status: maintenance_status(&messages).to_string(),
+            messages,
+            duration_ms: elapsed.as_millis() as u64,
+        });
+    }
+
+    app_state.cancel_flags.lock().await.remove(&execution_id);
+    progress.done = true;
+    emit(&progress);
+
+    Ok(TableMaintenanceSummary {
+        execution_id,
+        operation: progress.operation,
+        tables: results,
+        cancelled: cancel.load(Ordering::Relaxed),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,555 @@
+use crate::db::{connection_db_type, DbState};
+use crate::models::{ConstraintInfo, ForeignKeyInfo, IndexInfo, TableColumn, TableStructure};
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use sqlx::mysql::MySqlRow;
+use sqlx::pool::PoolConnection;
+use sqlx::{MySql, MySqlConnection, Row, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+use tauri::{command, State};
+
+// information_schema 的文本列在部分版本中以二进制返回，统一按 CAST AS CHAR 读取
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+pub async fn mysql_indexes(
+    conn: &mut MySqlConnection,
+    db_name: Option<&str>,
+    table: &str,
+) -> Result<Vec<IndexInfo>, String> {
+    // 主键排在最前，同一索引的列按 SEQ_IN_INDEX 相邻
+    let rows = sqlx::query(
+        "SELECT CAST(INDEX_NAME AS CHAR), CAST(NON_UNIQUE AS SIGNED), CAST(COLUMN_NAME AS CHAR), \
+         CAST(INDEX_TYPE AS CHAR) \
+         FROM information_schema.STATISTICS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY INDEX_NAME = 'PRIMARY' DESC, INDEX_NAME, SEQ_IN_INDEX",
+    )
+    .bind(db_name)
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch indexes: {}", e))?;
+    let mut indexes: Vec<IndexInfo> = Vec::new();
+    for row in &rows {
+        let name = text(row, 0).unwrap_or_default();
+        // 函数索引（MySQL 8.0.13+）的 COLUMN_NAME 为 NULL
+        let column = text(row, 2);
+        match indexes.last_mut() {
+            Some(index) if index.name == name => index.columns.push(column),
+            _ => indexes.push(IndexInfo {
+                primary: name == "PRIMARY",
+                unique: row.try_get_unchecked::<i64, _>(1).unwrap_or(1) == 0,
+                index_type: non_empty(text(row, 3)),
+                columns: vec![column],
+                name,
+            }),
+        }
+    }
+    Ok(indexes)
+}
+
+pub async fn mysql_structure(
+    mut conn: PoolConnection<MySql>,
+    db_name: Option<&str>,
+    table: &str,
+) -> Result<TableStructure, String> {
+    let rows = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR), CAST(COLUMN_TYPE AS CHAR), CAST(IS_NULLABLE AS CHAR), \
+         CAST(COLUMN_DEFAULT AS CHAR), CAST(EXTRA AS CHAR), CAST(COLUMN_KEY AS CHAR), \
+         CAST(COLUMN_COMMENT AS CHAR) \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(db_name)
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    let columns: Vec<TableColumn> = rows
+        .iter()
+        .map(|row| TableColumn {
+            name: text(row, 0).unwrap_or_default(),
+            column_type: text(row, 1).unwrap_or_default(),
+            nullable: text(row, 2).as_deref() == Some("YES"),
+            default_value: text(row, 3),
+            extra: non_empty(text(row, 4)),
+            primary_key: text(row, 5).as_deref() == Some("PRI"),
+            comment: non_empty(text(row, 6)),
+        })
+        .collect();
+    if columns.is_empty() {
+        return Err(format!("Table {} not found", table));
+    }
+
+    let indexes = mysql_indexes(&mut conn, db_name, table).await?;
+
+    let rows = sqlx::query(
+        "SELECT CAST(k.CONSTRAINT_NAME AS CHAR), CAST(k.COLUMN_NAME AS CHAR), \
+         CAST(k.REFERENCED_TABLE_SCHEMA AS CHAR), CAST(k.REFERENCED_TABLE_NAME AS CHAR), \
+         CAST(k.REFERENCED_COLUMN_NAME AS CHAR), CAST(r.UPDATE_RULE AS CHAR), \
+         CAST(r.DELETE_RULE AS CHAR) \
+         FROM information_schema.KEY_COLUMN_USAGE k \
+         JOIN information_schema.REFERENTIAL_CONSTRAINTS r \
+         ON r.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA AND r.CONSTRAINT_NAME = k.CONSTRAINT_NAME \
+         AND r.TABLE_NAME = k.TABLE_NAME \
+         WHERE k.TABLE_SCHEMA = COALESCE(?, DATABASE()) AND k.TABLE_NAME = ? \
+         AND k.REFERENCED_TABLE_NAME IS NOT NULL \
+         ORDER BY k.CONSTRAINT_NAME, k.ORDINAL_POSITION",
+    )
+    .bind(db_name)
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+    let mut foreign_keys: Vec<ForeignKeyInfo> = Vec::new();
+    for row in &rows {
+        let name = text(row, 0);
+        let column = text(row, 1).unwrap_or_default();
+        let referenced_column = text(row, 4).unwrap_or_default();
+        match foreign_keys.last_mut() {
+            Some(key) if key.name == name => {
+                key.columns.push(column);
+                key.referenced_columns.push(referenced_column);
+            }
+            _ => foreign_keys.push(ForeignKeyInfo {
+                name,
+                columns: vec![column],
+                referenced_schema: text(row, 2),
+                referenced_table: text(row, 3).unwrap_or_default(),
+                referenced_columns: vec![referenced_column],
+                on_update: text(row, 5),
+                on_delete: text(row, 6),
+            }),
+        }
+    }
+
+    let rows = sqlx::query(
+        "SELECT CAST(CONSTRAINT_NAME AS CHAR), CAST(CONSTRAINT_TYPE AS CHAR) \
+         FROM information_schema.TABLE_CONSTRAINTS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY FIELD(CONSTRAINT_TYPE, 'PRIMARY KEY', 'UNIQUE', 'FOREIGN KEY', 'CHECK'), \
+         CONSTRAINT_NAME",
+    )
+    .bind(db_name)
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch constraints: {}", e))?;
+    // CHECK_CONSTRAINTS 从 MySQL 8.0.16 / MariaDB 10.2 开始提供，旧版本没有 CHECK 定义
+    let checks: HashMap<String, String> = sqlx::query(
+        "SELECT CAST(CONSTRAINT_NAME AS CHAR), CAST(CHECK_CLAUSE AS CHAR) \
+         FROM information_schema.CHECK_CONSTRAINTS \
+         WHERE CONSTRAINT_SCHEMA = COALESCE(?, DATABASE())",
+    )
+    .bind(db_name)
+    .fetch_all(&mut *conn)
+    .await
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|row| Some((text(row, 0)?, text(row, 1)?)))
+    .collect();
+    let constraints = rows
+        .iter()
+        .map(|row| {
+            let name = text(row, 0);
+            let constraint_type = text(row, 1).unwrap_or_default();
+            // 主键和唯一约束与同名索引对应，外键取自上面的外键列表
+            let columns = match constraint_type.as_str() {
+                "PRIMARY KEY" | "UNIQUE" => indexes
+                    .iter()
+                    .find(|index| Some(&index.name) == name.as_ref())
+                    .map(|index| index.columns.iter().flatten().cloned().collect())
+                    .unwrap_or_default(),
+                "FOREIGN KEY" => foreign_keys
+                    .iter()
+                    .find(|key| key.name == name)
+                    .map(|key| key.columns.clone())
+                    .unwrap_or_default(),
+                _ => Vec::new(),
+            };
+            ConstraintInfo {
+                definition: match constraint_type.as_str() {
+                    "CHECK" => name.as_ref().and_then(|n| checks.get(n)).cloned(),
+                    _ => None,
+                },
+                name,
+                constraint_type,
+                columns,
+            }
+        })
+        .collect();
+
+    Ok(TableStructure {
+        columns,
+        indexes,
+        foreign_keys,
+        constraints,
+    })
+}
+
+#[derive(PartialEq)]
+pub enum TokenKind {
+    Word,
+    Quoted,
+    Punct(u8),
+}
+
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+    // 引号包裹的标识符去掉引号后的值
+    pub value: String,
+}
+
+// 粗略的 SQL 词法切分，用于在 CREATE TABLE 语句中定位 CHECK 约束和列定义
+pub fn tokenize(sql: &str) -> Vec<Token> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if sql[i..].starts_with("--") {
+            i = sql[i..].find('\n').map_or(bytes.len(), |n| i + n + 1);
+        } else if sql[i..].starts_with("/*") {
+            i = sql[i + 2..].find("*/").map_or(bytes.len(), |n| i + n + 4);
+        } else if matches!(c, b'\'' | b'"' | b'`' | b'[') {
+            let close = if c == b'[' { b']' } else { c };
+            let start = i;
+            let mut value = String::new();
+            i += 1;
+            let mut segment = i;
+            while i < bytes.len() {
+                if bytes[i] == close {
+                    value.push_str(&sql[segment..i]);
+                    // 引号重复两次表示转义
+                    if close != b']' && bytes.get(i + 1) == Some(&close) {
+                        i += 2;
+                        segment = i - 1;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(bytes.len());
+            tokens.push(Token {
+                kind: TokenKind::Quoted,
+                start,
+                end: i,
+                value,
+            });
+        } else if c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80 {
+            let start = i;
+            while i < bytes.len()
+                && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] >= 0x80)
+            {
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Word,
+                start,
+                end: i,
+                value: sql[start..i].to_string(),
+            });
+        } else {
+            tokens.push(Token {
+                kind: TokenKind::Punct(c),
+                start: i,
+                end: i + 1,
+                value: String::new(),
+            });
+            i += 1;
+        }
+    }
+    tokens
+}
+
+// SQLite 不提供 CHECK 约束的元数据，只能从建表语句中解析：
+// 返回 (约束名, 表达式)，约束名来自前面的 CONSTRAINT name
+fn sqlite_check_constraints(sql: &str) -> Vec<(Option<String>, String)> {
+    let tokens = tokenize(sql);
+    let is_word =
+        |t: &Token, word: &str| t.kind == TokenKind::Word && t.value.eq_ignore_ascii_case(word);
+    let mut checks = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if !is_word(token, "CHECK")
+            || tokens.get(i + 1).map(|t| &t.kind) != Some(&TokenKind::Punct(b'('))
+        {
+            continue;
+        }
+        let mut depth = 0;
+        let mut close = None;
+        for (j, t) in tokens.iter().enumerate().skip(i + 1) {
+            match t.kind {
+                TokenKind::Punct(b'(') => depth += 1,
+                TokenKind::Punct(b')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(j);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(close) = close else { continue };
+        let definition = sql[tokens[i + 1].end..tokens[close].start]
+            .trim()
+            .to_string();
+        let name = match i.checked_sub(2).map(|k| (&tokens[k], &tokens[k + 1])) {
+            Some((keyword, name)) if is_word(keyword, "CONSTRAINT") => Some(name.value.clone()),
+            _ => None,
+        };
+        checks.push((name, definition));
+    }
+    checks
+}
+
+pub async fn sqlite_indexes(
+    conn: &mut SqliteConnection,
+    table: &str,
+) -> Result<Vec<IndexInfo>, String> {
+    // origin：c 为 CREATE INDEX 创建，u 为 UNIQUE 约束，pk 为主键（rowid 表的整数主键没有索引）
+    let index_rows = sqlx::query_as::<_, (String, bool, String)>(
+        "SELECT name, \"unique\", origin FROM pragma_index_list(?) ORDER BY origin = 'pk' DESC, name",
+    )
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch indexes: {}", e))?;
+    let mut indexes = Vec::with_capacity(index_rows.len());
+    for (name, unique, origin) in index_rows {
+        // 表达式索引的列名为 NULL
+        let index_columns = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT name FROM pragma_index_info(?) ORDER BY seqno",
+        )
+        .bind(&name)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch index columns: {}", e))?;
+        indexes.push(IndexInfo {
+            name,
+            columns: index_columns,
+            unique,
+            primary: origin == "pk",
+            index_type: None,
+        });
+    }
+    Ok(indexes)
+}
+
+pub async fn sqlite_structure(
+    mut conn: PoolConnection<Sqlite>,
+    table: &str,
+) -> Result<TableStructure, String> {
+    let rows = sqlx::query(
+        "SELECT name, type, \"notnull\", dflt_value, pk, hidden \
+         FROM pragma_table_xinfo(?) ORDER BY cid",
+    )
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+    if rows.is_empty() {
+        return Err(format!("Table {} not found", table));
+    }
+    let key_count = rows
+        .iter()
+        .filter(|row| row.try_get::<i64, _>(4).unwrap_or(0) > 0)
+        .count();
+    let mut primary_key: Vec<(i64, String)> = Vec::new();
+    let mut columns = Vec::new();
+    for row in &rows {
+        let name: String = row.try_get(0).map_err(|e| e.to_string())?;
+        let declared: String = row.try_get(1).unwrap_or_default();
+        let key_position = row.try_get::<i64, _>(4).unwrap_or(0);
+        // hidden：1 为虚拟表的隐藏列，2 / 3 为 VIRTUAL / STORED 生成列
+        let extra = match row.try_get::<i64, _>(5).unwrap_or(0) {
+            1 => continue,
+            2 => Some("VIRTUAL GENERATED".to_string()),
+            3 => Some("STORED GENERATED".to_string()),
+            // INTEGER PRIMARY KEY 是 rowid 的别名，自动分配
+            _ if key_position > 0 && key_count == 1 && declared.eq_ignore_ascii_case("integer") => {
+                Some("auto_increment".to_string())
+            }
+            _ => None,
+        };
+        if key_position > 0 {
+            primary_key.push((key_position, name.clone()));
+        }
+        columns.push(TableColumn {
+            name,
+            column_type: declared,
+            nullable: row.try_get::<i64, _>(2).unwrap_or(0) == 0 && key_position == 0,
+            default_value: row.try_get(3).unwrap_or_default(),
+            extra,
+            primary_key: key_position > 0,
+            comment: None,
+        });
+    }
+    primary_key.sort();
+
+    let indexes = sqlite_indexes(&mut conn, table).await?;
+    // UNIQUE 约束对应的自动索引以 sqlite_autoindex_ 命名（主键的自动索引标记为 primary）
+    let unique_constraints: Vec<Vec<String>> = indexes
+        .iter()
+        .filter(|index| {
+            index.unique && !index.primary && index.name.starts_with("sqlite_autoindex_")
+        })
+        .map(|index| index.columns.iter().flatten().cloned().collect())
+        .collect();
+
+    let key_rows = sqlx::query_as::<
+        _,
+        (
+            i64,
+            String,
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        ),
+    >(
+        "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete \
+         FROM pragma_foreign_key_list(?) ORDER BY id, seq",
+    )
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+    let mut foreign_keys: Vec<ForeignKeyInfo> = Vec::new();
+    let mut current_id = None;
+    // REFERENCES parent 省略列名时引用父表主键，"to" 为 NULL
+    let mut implicit: Vec<(usize, usize)> = Vec::new();
+    for (id, parent, from, to, on_update, on_delete) in key_rows {
+        if current_id != Some(id) {
+            current_id = Some(id);
+            foreign_keys.push(ForeignKeyInfo {
+                name: None,
+                columns: Vec::new(),
+                referenced_schema: None,
+                referenced_table: parent,
+                referenced_columns: Vec::new(),
+                on_update,
+                on_delete,
+            });
+        }
+        let key_index = foreign_keys.len() - 1;
+        let key = &mut foreign_keys[key_index];
+        if to.is_none() {
+            implicit.push((key_index, key.columns.len()));
+        }
+        key.columns.push(from);
+        key.referenced_columns.push(to.unwrap_or_default());
+    }
+    for (key_index, position) in implicit {
+        let parent = foreign_keys[key_index].referenced_table.clone();
+        let parent_key = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk",
+        )
+        .bind(&parent)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap_or_default();
+        if let Some(column) = parent_key.get(position) {
+            foreign_keys[key_index].referenced_columns[position] = column.clone();
+        }
+    }
+
+    let create_sql = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?",
+    )
+    .bind(table)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch table definition: {}", e))?
+    .flatten()
+    .unwrap_or_default();
+
+    let mut constraints = Vec::new();
+    if !primary_key.is_empty() {
+        constraints.push(ConstraintInfo {
+            name: None,
+            constraint_type: "PRIMARY KEY".to_string(),
+            columns: primary_key.into_iter().map(|(_, name)| name).collect(),
+            definition: None,
+        });
+    }
+    for columns in unique_constraints {
+        constraints.push(ConstraintInfo {
+            name: None,
+            constraint_type: "UNIQUE".to_string(),
+            columns,
+            definition: None,
+        });
+    }
+    for key in &foreign_keys {
+        constraints.push(ConstraintInfo {
+            name: None,
+            constraint_type: "FOREIGN KEY".to_string(),
+            columns: key.columns.clone(),
+            definition: None,
+        });
+    }
+    for (name, definition) in sqlite_check_constraints(&create_sql) {
+        constraints.push(ConstraintInfo {
+            name,
+            constraint_type: "CHECK".to_string(),
+            columns: Vec::new(),
+            definition: Some(definition),
+        });
+    }
+
+    Ok(TableStructure {
+        columns,
+        indexes,
+        foreign_keys,
+        constraints,
+    })
+}
+
+// 返回表的列、索引、外键和约束，供“结构”页签展示
+#[command]
+pub async fn get_table_structure(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: String,
+    db_name: Option<String>,
+) -> Result<TableStructure, String> {
+    let db_type = connection_db_type(&db_state, connection_id).await?;
+    match db_type.as_str() {
+        "mysql" | "mariadb" | "tidb" => {
+            let conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            mysql_structure(conn, db_name.as_deref(), &table).await
+        }
+        "sqlite" => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            sqlite_structure(conn, &table).await
+        }
+        other => Err(format!("Table structure is not supported for {}", other)),
+    }
+}
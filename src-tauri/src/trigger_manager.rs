@@ -0,0 +1,359 @@
+use crate::alter_table::{apply_ddl, check_fragment, qualified_name, quote_identifier};
+use crate::db::{connection_flavor, DbState};
+use crate::models::{DdlStatementResult, TriggerArgs, TriggerInfo};
+use crate::sql_classifier::{first_keyword, SqlFlavor};
+use crate::state::AppState;
+use crate::table_structure::{tokenize, TokenKind};
+use crate::{mysql_manager, sqlite_manager};
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use tauri::{command, State};
+
+// MySQL 系按探测到的服务端能力判断（TiDB 不支持触发器）
+async fn trigger_flavor(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+) -> Result<SqlFlavor, String> {
+    let flavor = connection_flavor(db_state, connection_id, "Triggers").await?;
+    if matches!(flavor, SqlFlavor::MySql)
+        && !mysql_manager::get_server_profile_for(app_state, db_state, connection_id)
+            .await?
+            .supports_triggers
+    {
+        return Err("Triggers are not supported by this server".to_string());
+    }
+    Ok(flavor)
+}
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// SQLite 不提供触发器的元数据，从建立语句中取出时机、事件和 BEGIN ... END 部分
+fn parse_sqlite_trigger(create_sql: &str) -> (String, String, String) {
+    let tokens = tokenize(create_sql);
+    let mut timing = "BEFORE".to_string();
+    let mut event = String::new();
+    let mut statement = String::new();
+    let mut after_on = false;
+    for token in &tokens {
+        if token.kind != TokenKind::Word {
+            continue;
+        }
+        let word = token.value.to_ascii_uppercase();
+        match word.as_str() {
+            "ON" => after_on = true,
+            "BEFORE" | "AFTER" if !after_on => timing = word,
+            "INSTEAD" if !after_on => timing = "INSTEAD OF".to_string(),
+            "INSERT" | "UPDATE" | "DELETE" if !after_on && event.is_empty() => event = word,
+            "BEGIN" if after_on => {
+                statement = create_sql[token.start..].trim().to_string();
+                break;
+            }
+            _ => {}
+        }
+    }
+    (timing, event, statement)
+}
+
+async fn fetch_triggers(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    flavor: SqlFlavor,
+    db_name: Option<String>,
+    table: Option<&str>,
+    name: Option<&str>,
+) -> Result<Vec<(TriggerInfo, Option<String>)>, String> {
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                app_state,
+                db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            let rows = sqlx::query(
+                "SELECT CAST(TRIGGER_NAME AS CHAR), CAST(EVENT_OBJECT_TABLE AS CHAR), \
+                 CAST(ACTION_TIMING AS CHAR), CAST(EVENT_MANIPULATION AS CHAR), \
+                 CAST(ACTION_STATEMENT AS CHAR), CAST(CREATED AS CHAR) \
+                 FROM information_schema.TRIGGERS \
+                 WHERE TRIGGER_SCHEMA = COALESCE(?, DATABASE()) \
+                 AND (? IS NULL OR EVENT_OBJECT_TABLE = ?) AND (? IS NULL OR TRIGGER_NAME = ?) \
+                 ORDER BY EVENT_OBJECT_TABLE, ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER",
+            )
+            .bind(db_name.as_deref())
+            .bind(table)
+            .bind(table)
+            .bind(name)
+            .bind(name)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to fetch triggers: {}", e))?;
+            Ok(rows
+                .iter()
+                .map(|row| {
+                    let trigger = TriggerInfo {
+                        name: text(row, 0).unwrap_or_default(),
+                        table: text(row, 1).unwrap_or_default(),
+                        timing: text(row, 2).unwrap_or_default(),
+                        event: text(row, 3).unwrap_or_default(),
+                        statement: text(row, 4).unwrap_or_default(),
+                        created: text(row, 5),
+                    };
+                    (trigger, None)
+                })
+                .collect())
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(app_state, db_state, connection_id).await?;
+            let rows = sqlx::query_as::<_, (String, String, String)>(
+                "SELECT name, tbl_name, sql FROM sqlite_master \
+                 WHERE type = 'trigger' AND (? IS NULL OR tbl_name = ?) AND (? IS NULL OR name = ?) \
+                 ORDER BY tbl_name, name",
+            )
+            .bind(table)
+            .bind(table)
+            .bind(name)
+            .bind(name)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to fetch triggers: {}", e))?;
+            Ok(rows
+                .into_iter()
+                .map(|(name, table, create_sql)| {
+                    let (timing, event, statement) = parse_sqlite_trigger(&create_sql);
+                    let trigger = TriggerInfo {
+                        name,
+                        table,
+                        timing,
+                        event,
+                        statement,
+                        created: None,
+                    };
+                    (trigger, Some(create_sql))
+                })
+                .collect())
+        }
+    }
+}
+
+// 列出库中（或指定表上）的触发器
+#[command]
+pub async fn list_triggers(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    table: Option<String>,
+    db_name: Option<String>,
+) -> Result<Vec<TriggerInfo>, String> {
+    let flavor = trigger_flavor(&app_state, &db_state, connection_id).await?;
+    let triggers = fetch_triggers(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        db_name,
+        table.as_deref(),
+        None,
+    )
+    .await?;
+    Ok(triggers.into_iter().map(|(trigger, _)| trigger).collect())
+}
+
+// 返回完整的 CREATE TRIGGER 语句
+#[command]
+pub async fn get_trigger_ddl(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    db_name: Option<String>,
+) -> Result<String, String> {
+    let flavor = trigger_flavor(&app_state, &db_state, connection_id).await?;
+    match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            // SHOW CREATE TRIGGER 不能预处理；第三列为 SQL Original Statement
+            let row = sqlx::raw_sql(&format!(
+                "SHOW CREATE TRIGGER {}",
+                qualified_name(db_name.as_deref(), &name, flavor)
+            ))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to fetch trigger definition: {}", e))?;
+            text(&row, 2).ok_or_else(|| format!("Trigger {} has no definition", name))
+        }
+        SqlFlavor::Sqlite => fetch_triggers(
+            &app_state,
+            &db_state,
+            connection_id,
+            flavor,
+            db_name,
+            None,
+            Some(&name),
+        )
+        .await?
+        .into_iter()
+        .next()
+        .and_then(|(_, create_sql)| create_sql)
+        .ok_or_else(|| format!("Trigger {} not found", name)),
+    }
+}
+
+fn keyword<'a>(label: &str, value: &str, allowed: &[&'a str]) -> Result<&'a str, String> {
+    let normalized = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    allowed
+        .iter()
+        .find(|a| a.eq_ignore_ascii_case(&normalized))
+        .copied()
+        .ok_or_else(|| format!("Unsupported trigger {}: {}", label, value))
+}
+
+fn create_trigger_sql(args: &TriggerArgs, flavor: SqlFlavor) -> Result<String, String> {
+    let body = args.body.trim();
+    if body.is_empty() {
+        return Err("Trigger body cannot be empty".to_string());
+    }
+    let event = keyword("event", &args.event, &["INSERT", "UPDATE", "DELETE"])?;
+    match flavor {
+        SqlFlavor::MySql => {
+            if !args.update_of.is_empty() || args.when.is_some() {
+                return Err("MySQL triggers do not support UPDATE OF or WHEN".to_string());
+            }
+            let timing = keyword("timing", &args.timing, &["BEFORE", "AFTER"])?;
+            // 触发器与表必须在同一个库
+            Ok(format!(
+                "CREATE TRIGGER {} {} {} ON {} FOR EACH ROW {}",
+                qualified_name(args.db_name.as_deref(), &args.name, flavor),
+                timing,
+                event,
+                qualified_name(args.db_name.as_deref(), &args.table, flavor),
+                body
+            ))
+        }
+        SqlFlavor::Sqlite => {
+            let timing = keyword("timing", &args.timing, &["BEFORE", "AFTER", "INSTEAD OF"])?;
+            let mut sql = format!(
+                "CREATE TRIGGER {} {} {}",
+                quote_identifier(&args.name, flavor),
+                timing,
+                event
+            );
+            if !args.update_of.is_empty() {
+                if event != "UPDATE" {
+                    return Err("UPDATE OF columns require an UPDATE trigger".to_string());
+                }
+                let columns = args
+                    .update_of
+                    .iter()
+                    .map(|c| quote_identifier(c, flavor))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                sql.push_str(&format!(" OF {}", columns));
+            }
+            sql.push_str(&format!(
+                " ON {} FOR EACH ROW",
+                quote_identifier(&args.table, flavor)
+            ));
+            if let Some(when) = &args.when {
+                check_fragment("Trigger WHEN condition", when)?;
+                sql.push_str(&format!(" WHEN {}", when.trim()));
+            }
+            // 只给出语句时包上 BEGIN ... END，最后一条语句补上分号
+            if first_keyword(body).as_deref() == Some("BEGIN") {
+                sql.push_str(&format!("\n{}", body));
+            } else {
+                let separator = if body.ends_with(';') { "" } else { ";" };
+                sql.push_str(&format!("\nBEGIN\n  {}{}\nEND", body, separator));
+            }
+            Ok(sql)
+        }
+    }
+}
+
+// 未确认时只返回 CREATE TRIGGER 语句供预览。触发器体中的 DELETE / DROP 等
+// 在生产环境需要确认令牌
+#[command]
+pub async fn create_trigger(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: TriggerArgs,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let flavor = trigger_flavor(&app_state, &db_state, connection_id).await?;
+    let statement = create_trigger_sql(&args, flavor)?;
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        args.db_name.clone(),
+        std::slice::from_ref(&statement),
+        confirm_token.as_deref(),
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
+
+// 删除触发器：未确认时只返回语句；生产环境还需要确认令牌
+#[command]
+pub async fn drop_trigger(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let flavor = trigger_flavor(&app_state, &db_state, connection_id).await?;
+    let statement = format!(
+        "DROP TRIGGER {}",
+        qualified_name(db_name.as_deref(), &name, flavor)
+    );
+    if confirmed != Some(true) {
+        return Ok(DdlStatementResult {
+            statement,
+            executed: false,
+        });
+    }
+
+    apply_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        db_name,
+        std::slice::from_ref(&statement),
+        confirm_token.as_deref(),
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement,
+        executed: true,
+    })
+}
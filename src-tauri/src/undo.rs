@@ -0,0 +1,340 @@
+use crate::alter_table::quote_identifier;
+use crate::db::{connection_flavor, DbState};
+use crate::guard::ensure_writable;
+use crate::result_cache::invalidate_results;
+use crate::schema_diff::is_generated_extra;
+use crate::sql_classifier::{single_table_write, SqlFlavor, WriteTarget};
+use crate::state::AppState;
+use crate::{mysql_manager, sqlite_manager};
+use serde::Serialize;
+use sqlx::types::Json;
+use sqlx::{FromRow, MySqlConnection, Row, SqliteConnection};
+use tauri::{command, State};
+
+// 受影响行超过该数量时不做快照（也就无法撤销），避免大批量变更占满本地库
+const UNDO_MAX_ROWS: usize = 1_000;
+// 每个连接保留的快照数
+const UNDO_KEEP_PER_CONNECTION: i64 = 50;
+
+// MySQL 中按十六进制字面量保存的类型，其余类型用 QUOTE() 转成字符串字面量
+const MYSQL_HEX_TYPES: &[&str] = &[
+    "binary",
+    "varbinary",
+    "tinyblob",
+    "blob",
+    "mediumblob",
+    "longblob",
+    "bit",
+    "geometry",
+    "point",
+    "linestring",
+    "polygon",
+    "multipoint",
+    "multilinestring",
+    "multipolygon",
+    "geometrycollection",
+    "geomcollection",
+];
+
+// 变更前的行快照。列名已按方言加好引号，值为服务端生成的 SQL 字面量，可直接拼回语句
+pub struct ChangeSnapshot {
+    operation: &'static str,
+    table_ref: String,
+    columns: Vec<String>,
+    primary_key: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+#[derive(FromRow)]
+struct UndoSnapshotRow {
+    id: i64,
+    db_name: Option<String>,
+    operation: String,
+    table_ref: String,
+    columns: Json<Vec<String>>,
+    primary_key: Json<Vec<String>>,
+    rows: Json<Vec<Vec<String>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UndoResult {
+    // 被撤销的操作："UPDATE" / "DELETE"
+    pub operation: String,
+    pub table: String,
+    pub rows: usize,
+}
+
+fn snapshot_query(target: &WriteTarget, expressions: &[String]) -> String {
+    let mut sql = format!(
+        "SELECT {} FROM {}",
+        expressions.join(", "),
+        target.from_clause
+    );
+    if let Some(selection) = &target.selection {
+        sql.push_str(&format!(" WHERE {}", selection));
+    }
+    sql.push_str(&format!(" LIMIT {}", UNDO_MAX_ROWS + 1));
+    sql
+}
+
+// 执行 UPDATE / DELETE 前查出将被修改的行。无法快照（非单表语句、UPDATE 的表没有主键、
+// 行数超过上限等）时返回 None，语句照常执行
+pub async fn capture_mysql_snapshot(
+    conn: &mut MySqlConnection,
+    sql: &str,
+) -> Option<ChangeSnapshot> {
+    let target = single_table_write(sql, SqlFlavor::MySql)?;
+    let table_columns = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR), CAST(DATA_TYPE AS CHAR), CAST(COLUMN_KEY AS CHAR), \
+         CAST(EXTRA AS CHAR) \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? \
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(&target.schema)
+    .bind(&target.table)
+    .fetch_all(&mut *conn)
+    .await
+    .ok()?;
+
+    let mut columns = Vec::new();
+    let mut primary_key = Vec::new();
+    let mut expressions = Vec::new();
+    for row in &table_columns {
+        let name: String = row.try_get_unchecked(0).ok()?;
+        let data_type: String = row.try_get_unchecked(1).ok()?;
+        let key: String = row.try_get_unchecked(2).ok()?;
+        let extra: String = row.try_get_unchecked(3).ok()?;
+        // 生成列不能写入
+        if is_generated_extra(&extra) {
+            continue;
+        }
+        let column = quote_identifier(&name, SqlFlavor::MySql);
+        expressions.push(
+            if MYSQL_HEX_TYPES.contains(&data_type.to_lowercase().as_str()) {
+                format!(
+                    "CASE WHEN {0} IS NULL THEN 'NULL' WHEN LENGTH({0}) = 0 THEN '''''' \
+                 ELSE CONCAT('0x', HEX({0})) END",
+                    column
+                )
+            } else {
+                format!("QUOTE({})", column)
+            },
+        );
+        if key == "PRI" {
+            primary_key.push(column.clone());
+        }
+        columns.push(column);
+    }
+    if columns.is_empty() || (target.operation == "UPDATE" && primary_key.is_empty()) {
+        return None;
+    }
+
+    let rows = sqlx::query(&snapshot_query(&target, &expressions))
+        .fetch_all(&mut *conn)
+        .await
+        .ok()?;
+    let rows = literal_rows(&rows)?;
+    Some(ChangeSnapshot {
+        operation: target.operation,
+        table_ref: target.table_ref,
+        columns,
+        primary_key,
+        rows,
+    })
+}
+
+// 同 capture_mysql_snapshot；没有主键的表用 rowid 定位行
+pub async fn capture_sqlite_snapshot(
+    conn: &mut SqliteConnection,
+    sql: &str,
+) -> Option<ChangeSnapshot> {
+    let target = single_table_write(sql, SqlFlavor::Sqlite)?;
+    let table_columns = sqlx::query("SELECT name, pk FROM pragma_table_info(?, ?) ORDER BY cid")
+        .bind(&target.table)
+        .bind(target.schema.as_deref().unwrap_or("main"))
+        .fetch_all(&mut *conn)
+        .await
+        .ok()?;
+
+    let mut columns = Vec::new();
+    let mut primary_key: Vec<(i64, String)> = Vec::new();
+    for row in &table_columns {
+        let name: String = row.try_get(0).ok()?;
+        let pk: i64 = row.try_get(1).ok()?;
+        let column = quote_identifier(&name, SqlFlavor::Sqlite);
+        if pk > 0 {
+            primary_key.push((pk, column.clone()));
+        }
+        columns.push(column);
+    }
+    if columns.is_empty() {
+        return None;
+    }
+    primary_key.sort();
+    let mut primary_key: Vec<String> = primary_key.into_iter().map(|(_, c)| c).collect();
+    if primary_key.is_empty() {
+        columns.insert(0, "rowid".to_string());
+        primary_key.push("rowid".to_string());
+    }
+
+    let expressions: Vec<String> = columns.iter().map(|c| format!("quote({})", c)).collect();
+    let rows = sqlx::query(&snapshot_query(&target, &expressions))
+        .fetch_all(&mut *conn)
+        .await
+        .ok()?;
+    let rows = literal_rows(&rows)?;
+    Some(ChangeSnapshot {
+        operation: target.operation,
+        table_ref: target.table_ref,
+        columns,
+        primary_key,
+        rows,
+    })
+}
+
+fn literal_rows<R: Row>(rows: &[R]) -> Option<Vec<Vec<String>>>
+where
+    usize: sqlx::ColumnIndex<R>,
+    String: for<'r> sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    if rows.len() > UNDO_MAX_ROWS {
+        return None;
+    }
+    rows.iter()
+        .map(|row| {
+            (0..row.len())
+                .map(|i| row.try_get_unchecked::<String, _>(i).ok())
+                .collect()
+        })
+        .collect()
+}
+
+// 语句执行成功后保存快照，只保留每个连接最近的若干条
+pub async fn save_snapshot(
+    db_state: &DbState,
+    connection_id: i64,
+    db_name: Option<&str>,
+    snapshot: ChangeSnapshot,
+) {
+    if snapshot.rows.is_empty() {
+        return;
+    }
+    let inserted = sqlx::query(
+        "INSERT INTO undo_snapshots \
+         (connection_id, db_name, operation, table_ref, columns, primary_key, rows) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(connection_id)
+    .bind(db_name)
+    .bind(snapshot.operation)
+    .bind(&snapshot.table_ref)
+    .bind(Json(&snapshot.columns))
+    .bind(Json(&snapshot.primary_key))
+    .bind(Json(&snapshot.rows))
+    .execute(&db_state.pool)
+    .await;
+    if inserted.is_ok() {
+        let _ = sqlx::query(
+            "DELETE FROM undo_snapshots WHERE connection_id = ? AND id NOT IN \
+             (SELECT id FROM undo_snapshots WHERE connection_id = ? ORDER BY id DESC LIMIT ?)",
+        )
+        .bind(connection_id)
+        .bind(connection_id)
+        .bind(UNDO_KEEP_PER_CONNECTION)
+        .execute(&db_state.pool)
+        .await;
+    }
+}
+
+// 生成恢复语句：DELETE 重新插入原来的行，UPDATE 按主键把各列改回原值
+fn inverse_statements(snapshot: &UndoSnapshotRow) -> Vec<String> {
+    let columns = &snapshot.columns.0;
+    snapshot
+        .rows
+        .0
+        .iter()
+        .map(|values| {
+            if snapshot.operation == "DELETE" {
+                return format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    snapshot.table_ref,
+                    columns.join(", "),
+                    values.join(", ")
+                );
+            }
+            let pairs: Vec<(&String, &String)> = columns.iter().zip(values).collect();
+            let assignments: Vec<String> = pairs
+                .iter()
+                .map(|(column, value)| format!("{} = {}", column, value))
+                .collect();
+            let conditions: Vec<String> = pairs
+                .iter()
+                .filter(|(column, _)| snapshot.primary_key.0.contains(column))
+                .map(|(column, value)| format!("{} = {}", column, value))
+                .collect();
+            format!(
+                "UPDATE {} SET {} WHERE {}",
+                snapshot.table_ref,
+                assignments.join(", "),
+                conditions.join(" AND ")
+            )
+        })
+        .collect()
+}
+
+// 撤销该连接最近一次通过 snapshot 执行的 UPDATE / DELETE：在一个事务中执行恢复语句，
+// 任一行已不存在（或已被他人改动主键）时整体回滚
+#[command]
+pub async fn undo_last_change(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<UndoResult, String> {
+    ensure_writable(&db_state, connection_id).await?;
+    let snapshot = sqlx::query_as::<_, UndoSnapshotRow>(
+        "SELECT id, db_name, operation, table_ref, columns, primary_key, rows \
+         FROM undo_snapshots WHERE connection_id = ? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(connection_id)
+    .fetch_optional(&db_state.pool)
+    .await
+    .map_err(|e| format!("Failed to fetch undo snapshot: {}", e))?
+    .ok_or("Nothing to undo")?;
+    let flavor = connection_flavor(&db_state, connection_id, "Undo").await?;
+
+    let statements = inverse_statements(&snapshot);
+    match flavor {
+        SqlFlavor::MySql => {
+            mysql_manager::execute_in_transaction(
+                &app_state,
+                &db_state,
+                connection_id,
+                snapshot.db_name.clone(),
+                &statements,
+            )
+            .await?
+        }
+        SqlFlavor::Sqlite => {
+            sqlite_manager::execute_in_transaction(
+                &app_state,
+                &db_state,
+                connection_id,
+                &statements,
+            )
+            .await?
+        }
+    }
+    invalidate_results(&app_state, connection_id).await;
+
+    sqlx::query("DELETE FROM undo_snapshots WHERE id = ?")
+        .bind(snapshot.id)
+        .execute(&db_state.pool)
+        .await
+        .map_err(|e| format!("Failed to remove undo snapshot: {}", e))?;
+    Ok(UndoResult {
+        operation: snapshot.operation,
+        table: snapshot.table_ref,
+        rows: statements.len(),
+    })
+}
@@ -0,0 +1,322 @@
+use crate::alter_table::{qualified_name, quote_identifier};
+use crate::db::{connection_flavor, DbState};
+use crate::guard::ensure_sql_allowed;
+use crate::models::{DatabaseUser, PrivilegeArgs};
+use crate::mysql_manager;
+use crate::query_history::{record_history, HistoryOutcome};
+use crate::result_export::sql_literal;
+use crate::sql_classifier::SqlFlavor;
+use crate::state::AppState;
+use serde_json::Value;
+use sqlx::mysql::MySqlRow;
+use sqlx::Row;
+use std::time::Instant;
+use tauri::{command, State};
+
+// 历史记录和返回给前端的语句中用它代替密码
+const PASSWORD_MASK: &str = "'***'";
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 账号和权限管理只支持 MySQL 系
+async fn ensure_mysql(db_state: &DbState, connection_id: i64) -> Result<(), String> {
+    match connection_flavor(db_state, connection_id, "User management").await? {
+        SqlFlavor::MySql => Ok(()),
+        SqlFlavor::Sqlite => Err("User management is not supported for sqlite".to_string()),
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    sql_literal(&Value::String(value.to_string()), SqlFlavor::MySql)
+}
+
+// 'user'@'host'，host 默认为 '%'
+fn account_name(user: &str, host: Option<&str>) -> Result<String, String> {
+    if user.is_empty() {
+        return Err("User name cannot be empty".to_string());
+    }
+    let host = host.map(str::trim).filter(|h| !h.is_empty()).unwrap_or("%");
+    Ok(format!("{}@{}", string_literal(user), string_literal(host)))
+}
+
+// 权限名只由字母、数字和下划线组成的单词构成（如 CREATE TEMPORARY TABLES、BACKUP_ADMIN），
+// 统一为大写和单个空格后拼入语句；权限是否存在、能否用于该级别由服务端检查
+fn normalize_privilege(privilege: &str) -> Result<String, String> {
+    let words: Vec<String> = privilege
+        .split_whitespace()
+        .map(str::to_ascii_uppercase)
+        .collect();
+    let valid = !words.is_empty()
+        && words
+            .iter()
+            .all(|w| w.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    match valid {
+        true => Ok(words.join(" ")),
+        false => Err(format!("Invalid privilege: {}", privilege)),
+    }
+}
+
+// 生成 GRANT / REVOKE 中 "权限列表 ON 对象" 的部分
+fn privilege_clause(args: &PrivilegeArgs, revoke: bool) -> Result<String, String> {
+    let mut privileges = args
+        .privileges
+        .iter()
+        .map(|p| normalize_privilege(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    if privileges.is_empty() && !(revoke && args.grant_option) {
+        return Err("No privileges selected".to_string());
+    }
+    let database = args.database.as_deref().filter(|d| !d.is_empty());
+    let table = args.table.as_deref().filter(|t| !t.is_empty());
+    if !args.columns.is_empty() {
+        if table.is_none() {
+            return Err("Column privileges require a table".to_string());
+        }
+        let columns = args
+            .columns
+            .iter()
+            .map(|c| quote_identifier(c, SqlFlavor::MySql))
+            .collect::<Vec<_>>()
+            .join(", ");
+        for privilege in privileges.iter_mut() {
+            *privilege = format!("{} ({})", privilege, columns);
+        }
+    }
+    if revoke && args.grant_option {
+        privileges.push("GRANT OPTION".to_string());
+    }
+    let target = match (database, table) {
+        (None, None) => "*.*".to_string(),
+        (None, Some(_)) => return Err("Table privileges require a database".to_string()),
+        (Some(database), None) => format!("{}.*", quote_identifier(database, SqlFlavor::MySql)),
+        (Some(database), Some(table)) => qualified_name(Some(database), table, SqlFlavor::MySql),
+    };
+    Ok(format!("{} ON {}", privileges.join(", "), target))
+}
+
+// 执行账号管理语句：sql 为实际执行的语句，display 为隐去密码后用于检查、历史记录和返回的语句
+async fn execute_account_sql(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    sql: &str,
+    display: &str,
+    confirmed: bool,
+    confirm_token: Option<&str>,
+) -> Result<String, String> {
+    ensure_sql_allowed(db_state, connection_id, display, confirm_token, confirmed).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(app_state, db_state, connection_id, None).await?;
+    let started = Instant::now();
+    let result = sqlx::query(sql)
+        .execute(&mut *conn)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to execute statement: {}", e));
+    let outcome = HistoryOutcome::of(&result, |_| HistoryOutcome::Done);
+    record_history(db_state, connection_id, display, started.elapsed(), outcome).await;
+    result.map(|_| display.to_string())
+}
+
+// 列出服务端的账号（需要 mysql 库的读取权限）
+#[command]
+pub async fn list_users(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+) -> Result<Vec<DatabaseUser>, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, None).await?;
+    let rows = sqlx::query(
+        "SELECT CAST(User AS CHAR), CAST(Host AS CHAR), CAST(plugin AS CHAR) \
+         FROM mysql.user ORDER BY User, Host",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to list users: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| DatabaseUser {
+            user: text(row, 0).unwrap_or_default(),
+            host: text(row, 1).unwrap_or_default(),
+            auth_plugin: text(row, 2).filter(|p| !p.is_empty()),
+        })
+        .collect())
+}
+
+// 返回 SHOW GRANTS 的每一行
+#[command]
+pub async fn show_user_grants(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    user: String,
+    host: Option<String>,
+) -> Result<Vec<String>, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let account = account_name(&user, host.as_deref())?;
+    let mut conn =
+        mysql_manager::acquire_connection(&app_state, &db_state, connection_id, None).await?;
+    let rows = sqlx::query(&format!("SHOW GRANTS FOR {}", account))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to fetch grants: {}", e))?;
+    Ok(rows.iter().filter_map(|row| text(row, 0)).collect())
+}
+
+// 创建账号，password 为空时不设密码。返回执行的语句（密码以 *** 代替）
+#[command]
+pub async fn create_user(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    user: String,
+    host: Option<String>,
+    password: Option<String>,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let account = account_name(&user, host.as_deref())?;
+    let (sql, display) = match password.filter(|p| !p.is_empty()) {
+        Some(password) => (
+            format!(
+                "CREATE USER {} IDENTIFIED BY {}",
+                account,
+                string_literal(&password)
+            ),
+            format!("CREATE USER {} IDENTIFIED BY {}", account, PASSWORD_MASK),
+        ),
+        None => {
+            let sql = format!("CREATE USER {}", account);
+            (sql.clone(), sql)
+        }
+    };
+    execute_account_sql(
+        &app_state,
+        &db_state,
+        connection_id,
+        &sql,
+        &display,
+        false,
+        confirm_token.as_deref(),
+    )
+    .await
+}
+
+// 删除账号，属于高危操作，需要 confirmed（生产环境还需要确认令牌）
+#[command]
+pub async fn drop_user(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    user: String,
+    host: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let sql = format!("DROP USER {}", account_name(&user, host.as_deref())?);
+    execute_account_sql(
+        &app_state,
+        &db_state,
+        connection_id,
+        &sql,
+        &sql,
+        confirmed == Some(true),
+        confirm_token.as_deref(),
+    )
+    .await
+}
+
+// 修改账号密码（ALTER USER ... IDENTIFIED BY）
+#[command]
+pub async fn change_user_password(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    user: String,
+    host: Option<String>,
+    password: String,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let account = account_name(&user, host.as_deref())?;
+    let sql = format!(
+        "ALTER USER {} IDENTIFIED BY {}",
+        account,
+        string_literal(&password)
+    );
+    let display = format!("ALTER USER {} IDENTIFIED BY {}", account, PASSWORD_MASK);
+    execute_account_sql(
+        &app_state,
+        &db_state,
+        connection_id,
+        &sql,
+        &display,
+        false,
+        confirm_token.as_deref(),
+    )
+    .await
+}
+
+// 按结构化参数生成并执行 GRANT，返回执行的语句
+#[command]
+pub async fn grant_privileges(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: PrivilegeArgs,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let mut sql = format!(
+        "GRANT {} TO {}",
+        privilege_clause(&args, false)?,
+        account_name(&args.user, args.host.as_deref())?
+    );
+    if args.grant_option {
+        sql.push_str(" WITH GRANT OPTION");
+    }
+    execute_account_sql(
+        &app_state,
+        &db_state,
+        connection_id,
+        &sql,
+        &sql,
+        false,
+        confirm_token.as_deref(),
+    )
+    .await
+}
+
+// 按结构化参数生成并执行 REVOKE，返回执行的语句。只回收 GRANT OPTION 时 privileges 可以为空
+#[command]
+pub async fn revoke_privileges(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: PrivilegeArgs,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    ensure_mysql(&db_state, connection_id).await?;
+    let sql = format!(
+        "REVOKE {} FROM {}",
+        privilege_clause(&args, true)?,
+        account_name(&args.user, args.host.as_deref())?
+    );
+    execute_account_sql(
+        &app_state,
+        &db_state,
+        connection_id,
+        &sql,
+        &sql,
+        false,
+        confirm_token.as_deref(),
+    )
+    .await
+}
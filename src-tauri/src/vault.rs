@@ -0,0 +1,373 @@
+use crate::db::{DbPool, DbState};
+use crate::models::Connection;
+use crate::secret_provider::resolve_secret;
+use crate::state::AppState;
+use aes_gcm::aead::{Aead, Generate, Nonce};
+use aes_gcm::{Aes256Gcm, KeyInit};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sqlx::SqliteConnection;
+use std::time::Instant;
+use tauri::{command, State};
+use tokio::time::{interval, Duration};
+
+// 加密后的字段以该前缀开头，未加密的旧数据原样读取
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+// 用于校验主密码是否正确的明文
+const VERIFIER_PLAINTEXT: &str = "xDB master password";
+const NONCE_LEN: usize = 12;
+const AUTO_LOCK_IDLE_SECS: u64 = 15 * 60;
+const AUTO_LOCK_CHECK_INTERVAL_SECS: u64 = 30;
+
+// 需要主密码但尚未解锁时返回该错误，前端据此弹出解锁框并调用 unlock_master_password
+pub const MASTER_PASSWORD_LOCKED: &str = "MASTER_PASSWORD_LOCKED";
+
+// 解锁后保存在内存中的密钥，空闲超时后自动清除
+pub struct MasterKey {
+    cipher: Aes256Gcm,
+    last_used: Instant,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MasterPasswordStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+// 辅助函数：argon2 从主密码派生 256 位密钥
+async fn derive_cipher(password: String, salt: Vec<u8>) -> Result<Aes256Gcm, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), &salt, &mut key)
+            .map_err(|e| format!("Failed to derive key: {}", e))?;
+        Aes256Gcm::new_from_slice(&key).map_err(|e| format!("Invalid key: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+// 密文格式：前缀 + base64(nonce || ciphertext)
+fn encrypt_value(cipher: &Aes256Gcm, plaintext: &str) -> Result<String, String> {
+    let nonce = Nonce::<Aes256Gcm>::generate();
+    let mut data = nonce.to_vec();
+    data.extend(
+        cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| "Failed to encrypt value".to_string())?,
+    );
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(data)))
+}
+
+fn decrypt_value(cipher: &Aes256Gcm, value: &str) -> Result<String, String> {
+    let Some(encoded) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let data = BASE64
+        .decode(encoded)
+        .map_err(|e| format!("Corrupted encrypted value: {}", e))?;
+    if data.len() < NONCE_LEN {
+        return Err("Corrupted encrypted value".to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let nonce =
+        Nonce::<Aes256Gcm>::try_from(nonce).map_err(|_| "Corrupted encrypted value".to_string())?;
+    let plaintext = cipher
+        .decrypt(&nonce, ciphertext)
+        .map_err(|_| "Failed to decrypt value".to_string())?;
+    String::from_utf8(plaintext).map_err(|_| "Failed to decrypt value".to_string())
+}
+
+// 解密连接中的密码字段（未加密的连接不需要解锁），并解析外部密码来源
+pub async fn reveal_secrets(
+    app_state: &AppState,
+    mut connection: Connection,
+) -> Result<Connection, String> {
+    let needs_key = [&connection.password, &connection.ssh_password]
+        .iter()
+        .any(|v| v.as_deref().is_some_and(is_encrypted));
+    if needs_key {
+        let mut master_key = app_state.master_key.lock().await;
+        let key = master_key.as_mut().ok_or(MASTER_PASSWORD_LOCKED)?;
+        key.last_used = Instant::now();
+
+        for field in [&mut connection.password, &mut connection.ssh_password] {
+            if let Some(value) = field.as_deref() {
+                *field = Some(decrypt_value(&key.cipher, value)?);
+            }
+        }
+    }
+
+    // 密码托管在外部密钥服务时，每次建立连接前实时读取
+    if let Some(source) = connection.options.password_source.clone() {
+        connection.password = Some(resolve_secret(&source).await?);
+    }
+    Ok(connection)
+}
+
+// 启用主密码时加密要写入 connections 表的密码字段；已是密文的值原样保留。
+// 锁定时返回 MASTER_PASSWORD_LOCKED，前端解锁后重新保存，密码不会以明文写入
+pub async fn seal_secret(
+    app_state: &AppState,
+    pool: &DbPool,
+    value: Option<String>,
+) -> Result<Option<String>, String> {
+    let Some(plaintext) = value.as_deref().filter(|v| !v.is_empty() && !is_encrypted(v)) else {
+        return Ok(value);
+    };
+    if load_master_record(pool).await?.is_none() {
+        return Ok(value);
+    }
+
+    let mut master_key = app_state.master_key.lock().await;
+    let key = master_key.as_mut().ok_or(MASTER_PASSWORD_LOCKED)?;
+    key.last_used = Instant::now();
+    encrypt_value(&key.cipher, plaintext).map(Some)
+}
+
+// 导出文件中的密码使用单独的导出口令加密，与本机主密码无关
+pub struct ExportCipher {
+    cipher: Aes256Gcm,
+}
+
+impl ExportCipher {
+    // 返回 (cipher, base64 盐值, 校验密文)，后两者写入导出文件
+    pub async fn create(password: String) -> Result<(Self, String, String), String> {
+        let salt = <[u8; 16]>::generate().to_vec();
+        let cipher = derive_cipher(password, salt.clone()).await?;
+        let verifier = encrypt_value(&cipher, VERIFIER_PLAINTEXT)?;
+        Ok((Self { cipher }, BASE64.encode(salt), verifier))
+    }
+
+    pub async fn open(password: String, salt: &str, verifier: &str) -> Result<Self, String> {
+        let salt = BASE64
+            .decode(salt)
+            .map_err(|e| format!("Corrupted export file: {}", e))?;
+        let cipher = derive_cipher(password, salt).await?;
+        match decrypt_value(&cipher, verifier) {
+            Ok(plaintext) if plaintext == VERIFIER_PLAINTEXT => Ok(Self { cipher }),
+            _ => Err("Incorrect export password".to_string()),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        encrypt_value(&self.cipher, plaintext)
+    }
+
+    pub fn decrypt(&self, value: &str) -> Result<String, String> {
+        decrypt_value(&self.cipher, value)
+    }
+}
+
+async fn load_master_record(pool: &DbPool) -> Result<Option<(Vec<u8>, String)>, String> {
+    let record = sqlx::query_as::<_, (String, String)>(
+        "SELECT salt, verifier FROM master_password WHERE id = 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read master password settings: {}", e))?;
+
+    match record {
+        Some((salt, verifier)) => {
+            let salt = BASE64
+                .decode(salt)
+                .map_err(|e| format!("Corrupted master password settings: {}", e))?;
+            Ok(Some((salt, verifier)))
+        }
+        None => Ok(None),
+    }
+}
+
+// 辅助函数：校验主密码并返回派生出的密钥
+async fn verify_master_password(pool: &DbPool, password: String) -> Result<Aes256Gcm, String> {
+    let (salt, verifier) = load_master_record(pool)
+        .await?
+        .ok_or("Master password is not set")?;
+    let cipher = derive_cipher(password, salt).await?;
+    match decrypt_value(&cipher, &verifier) {
+        Ok(plaintext) if plaintext == VERIFIER_PLAINTEXT => Ok(cipher),
+        _ => Err("Incorrect master password".to_string()),
+    }
+}
+
+// 辅助函数：对所有连接的密码字段做加密或解密
+// 在调用方的事务中执行，与主密码设置的修改一起提交
+async fn transform_secrets<F>(conn: &mut SqliteConnection, transform: F) -> Result<(), String>
+where
+    F: Fn(&str) -> Result<Option<String>, String>,
+{
+    let rows = sqlx::query_as::<_, (i64, Option<String>, Option<String>)>(
+        "SELECT id, password, ssh_password FROM connections",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to read connections: {}", e))?;
+
+    for (id, password, ssh_password) in rows {
+        let mut changed = false;
+        let mut apply = |value: Option<String>| -> Result<Option<String>, String> {
+            match value.as_deref().filter(|v| !v.is_empty()) {
+                Some(v) => match transform(v)? {
+                    Some(new_value) => {
+                        changed = true;
+                        Ok(Some(new_value))
+                    }
+                    None => Ok(value),
+                },
+                None => Ok(value),
+            }
+        };
+        let password = apply(password)?;
+        let ssh_password = apply(ssh_password)?;
+        if changed {
+            sqlx::query("UPDATE connections SET password = ?, ssh_password = ? WHERE id = ?")
+                .bind(password)
+                .bind(ssh_password)
+                .bind(id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to update connection: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+// 加密所有尚未加密的密码字段
+async fn encrypt_all(conn: &mut SqliteConnection, cipher: &Aes256Gcm) -> Result<(), String> {
+    transform_secrets(conn, |v| {
+        if is_encrypted(v) {
+            Ok(None)
+        } else {
+            encrypt_value(cipher, v).map(Some)
+        }
+    })
+    .await
+}
+
+async fn decrypt_all(conn: &mut SqliteConnection, cipher: &Aes256Gcm) -> Result<(), String> {
+    transform_secrets(conn, |v| {
+        if is_encrypted(v) {
+            decrypt_value(cipher, v).map(Some)
+        } else {
+            Ok(None)
+        }
+    })
+    .await
+}
+
+async fn store_key(app_state: &AppState, cipher: Aes256Gcm) {
+    let mut master_key = app_state.master_key.lock().await;
+    *master_key = Some(MasterKey {
+        cipher,
+        last_used: Instant::now(),
+    });
+}
+
+// 后台定时检查，空闲超时后自动上锁
+pub fn spawn_auto_lock(app_state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(AUTO_LOCK_CHECK_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            let mut master_key = app_state.master_key.lock().await;
+            if master_key
+                .as_ref()
+                .is_some_and(|k| k.last_used.elapsed() >= Duration::from_secs(AUTO_LOCK_IDLE_SECS))
+            {
+                *master_key = None;
+            }
+        }
+    });
+}
+
+#[command]
+pub async fn get_master_password_status(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+) -> Result<MasterPasswordStatus, String> {
+    let enabled = load_master_record(&db_state.pool).await?.is_some();
+    let unlocked = app_state.master_key.lock().await.is_some();
+    Ok(MasterPasswordStatus { enabled, unlocked })
+}
+
+// 启用主密码并加密现有连接的密码
+#[command]
+pub async fn set_master_password(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    password: String,
+) -> Result<(), String> {
+    if password.is_empty() {
+        return Err("Master password cannot be empty".to_string());
+    }
+    if load_master_record(&db_state.pool).await?.is_some() {
+        return Err("Master password is already set".to_string());
+    }
+
+    let salt = <[u8; 16]>::generate().to_vec();
+    let cipher = derive_cipher(password, salt.clone()).await?;
+    let verifier = encrypt_value(&cipher, VERIFIER_PLAINTEXT)?;
+
+    // 主密码设置和密码字段的加密一起提交，中途失败时不会留下无法解密或未加密的数据
+    let mut tx = db_state.pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("INSERT INTO master_password (id, salt, verifier) VALUES (1, ?, ?)")
+        .bind(BASE64.encode(&salt))
+        .bind(verifier)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to save master password settings: {}", e))?;
+    encrypt_all(&mut tx, &cipher).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    store_key(&app_state, cipher).await;
+    Ok(())
+}
+
+#[command]
+pub async fn unlock_master_password(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    password: String,
+) -> Result<(), String> {
+    let cipher = verify_master_password(&db_state.pool, password).await?;
+    // 锁定时 seal_secret 拒绝保存密码；这里加密未经 seal_secret 直接写入本地库的明文
+    let mut tx = db_state.pool.begin().await.map_err(|e| e.to_string())?;
+    encrypt_all(&mut tx, &cipher).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    store_key(&app_state, cipher).await;
+    Ok(())
+}
+
+#[command]
+pub async fn lock_master_password(app_state: State<'_, AppState>) -> Result<(), String> {
+    let mut master_key = app_state.master_key.lock().await;
+    *master_key = None;
+    Ok(())
+}
+
+// 关闭主密码：解密所有连接的密码并删除主密码设置
+#[command]
+pub async fn disable_master_password(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    password: String,
+) -> Result<(), String> {
+    let cipher = verify_master_password(&db_state.pool, password).await?;
+    let mut tx = db_state.pool.begin().await.map_err(|e| e.to_string())?;
+    decrypt_all(&mut tx, &cipher).await?;
+    sqlx::query("DELETE FROM master_password WHERE id = 1")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to remove master password settings: {}", e))?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let mut master_key = app_state.master_key.lock().await;
+    *master_key = None;
+    Ok(())
+}
@@ -0,0 +1,355 @@
+use crate::alter_table::{apply_ddl, qualified_name};
+use crate::db::{connection_flavor, DbState};
+use crate::models::{DdlStatementResult, ViewArgs, ViewDefinition, ViewInfo};
+use crate::sql_classifier::{is_single_select, referenced_tables, SqlFlavor};
+use crate::state::AppState;
+use crate::table_structure::{tokenize, TokenKind};
+use crate::{mysql_manager, sqlite_manager};
+use sqlx::mysql::MySqlRow;
+use sqlx::{MySqlConnection, Row, SqliteConnection};
+use tauri::{command, State};
+
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+// 视图引用的表；MySQL 保存的定义中表名都带库名，和视图同库的去掉库名
+fn dependencies(definition: &str, flavor: SqlFlavor, schema: Option<&str>) -> Vec<String> {
+    referenced_tables(definition, flavor)
+        .into_iter()
+        .map(
+            |table| match schema.and_then(|schema| table.strip_prefix(&format!("{}.", schema))) {
+                Some(name) => name.to_string(),
+                None => table,
+            },
+        )
+        .collect()
+}
+
+// 从 CREATE VIEW 语句中取出 AS 之后的 SELECT，跳过视图名后的列清单
+fn sqlite_view_select(create_sql: &str) -> String {
+    let mut depth = 0;
+    for token in tokenize(create_sql) {
+        match token.kind {
+            TokenKind::Punct(b'(') => depth += 1,
+            TokenKind::Punct(b')') => depth -= 1,
+            TokenKind::Word if depth == 0 && token.value.eq_ignore_ascii_case("AS") => {
+                return create_sql[token.end..].trim().to_string();
+            }
+            _ => {}
+        }
+    }
+    create_sql.to_string()
+}
+
+async fn mysql_views(
+    conn: &mut MySqlConnection,
+    db_name: Option<&str>,
+    name: Option<&str>,
+) -> Result<Vec<ViewInfo>, String> {
+    let rows = sqlx::query(
+        "SELECT CAST(TABLE_SCHEMA AS CHAR), CAST(TABLE_NAME AS CHAR), \
+         CAST(VIEW_DEFINITION AS CHAR), CAST(CHECK_OPTION AS CHAR), CAST(IS_UPDATABLE AS CHAR) \
+         FROM information_schema.VIEWS \
+         WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND (? IS NULL OR TABLE_NAME = ?) \
+         ORDER BY TABLE_NAME",
+    )
+    .bind(db_name)
+    .bind(name)
+    .bind(name)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch views: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let schema = text(row, 0);
+            // 没有 SHOW VIEW 权限时定义为空
+            let definition = text(row, 2).unwrap_or_default();
+            ViewInfo {
+                name: text(row, 1).unwrap_or_default(),
+                referenced_tables: dependencies(&definition, SqlFlavor::MySql, schema.as_deref()),
+                definition,
+                updatable: text(row, 4).map(|v| v == "YES"),
+                check_option: text(row, 3).filter(|v| v != "NONE"),
+            }
+        })
+        .collect())
+}
+
+async fn sqlite_views(
+    conn: &mut SqliteConnection,
+    name: Option<&str>,
+) -> Result<Vec<(ViewInfo, String)>, String> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT name, sql FROM sqlite_master \
+         WHERE type = 'view' AND (? IS NULL OR name = ?) ORDER BY name",
+    )
+    .bind(name)
+    .bind(name)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to fetch views: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(|(name, create_sql)| {
+            let definition = sqlite_view_select(&create_sql);
+            let view = ViewInfo {
+                name,
+                referenced_tables: dependencies(&definition, SqlFlavor::Sqlite, None),
+                definition,
+                updatable: None,
+                check_option: None,
+            };
+            (view, create_sql)
+        })
+        .collect())
+}
+
+// 列出库中的视图及其引用的表
+#[command]
+pub async fn list_views(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    db_name: Option<String>,
+) -> Result<Vec<ViewInfo>, String> {
+    match connection_flavor(&db_state, connection_id, "Views").await? {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            mysql_views(&mut conn, db_name.as_deref(), None).await
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            let views = sqlite_views(&mut conn, None).await?;
+            Ok(views.into_iter().map(|(view, _)| view).collect())
+        }
+    }
+}
+
+// 返回视图的定义、完整的建视图语句和引用的表
+#[command]
+pub async fn get_view_definition(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    db_name: Option<String>,
+) -> Result<ViewDefinition, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Views").await?;
+    let (view, create_sql) = match flavor {
+        SqlFlavor::MySql => {
+            let mut conn = mysql_manager::acquire_connection(
+                &app_state,
+                &db_state,
+                connection_id,
+                db_name.clone(),
+            )
+            .await?;
+            let view = mysql_views(&mut conn, db_name.as_deref(), Some(&name))
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("View {} not found", name))?;
+            let row = sqlx::query(&format!(
+                "SHOW CREATE VIEW {}",
+                qualified_name(db_name.as_deref(), &name, flavor)
+            ))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to fetch view definition: {}", e))?;
+            (view, text(&row, 1).unwrap_or_default())
+        }
+        SqlFlavor::Sqlite => {
+            let pool =
+                sqlite_manager::get_or_create_pool(&app_state, &db_state, connection_id).await?;
+            let mut conn = pool
+                .acquire()
+                .await
+                .map_err(|e| format!("Failed to acquire SQLite connection: {}", e))?;
+            sqlite_views(&mut conn, Some(&name))
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("View {} not found", name))?
+        }
+    };
+    Ok(ViewDefinition {
+        name: view.name,
+        definition: view.definition,
+        create_sql,
+        referenced_tables: view.referenced_tables,
+        updatable: view.updatable,
+        check_option: view.check_option,
+    })
+}
+
+// 返回 "AS SELECT ..." 部分：只接受单条只读 SELECT，避免在定义里夹带其他语句
+fn view_body(args: &ViewArgs, flavor: SqlFlavor) -> Result<String, String> {
+    let select_sql = args.select_sql.trim().trim_end_matches(';').trim_end();
+    if !is_single_select(select_sql, flavor) {
+        return Err("View definition must be a single SELECT statement".to_string());
+    }
+    let check_option = match args.check_option.as_deref().map(str::trim) {
+        None => "",
+        Some(_) if matches!(flavor, SqlFlavor::Sqlite) => {
+            return Err("SQLite does not support WITH CHECK OPTION".to_string())
+        }
+        Some(option) if option.eq_ignore_ascii_case("CASCADED") => " WITH CASCADED CHECK OPTION",
+        Some(option) if option.eq_ignore_ascii_case("LOCAL") => " WITH LOCAL CHECK OPTION",
+        Some(option) => return Err(format!("Unsupported check option: {}", option)),
+    };
+    Ok(format!("AS {}{}", select_sql, check_option))
+}
+
+// 执行视图 DDL，返回的 statement 为按执行顺序以分号连接的语句
+async fn run_view_ddl(
+    app_state: &State<'_, AppState>,
+    db_state: &State<'_, DbState>,
+    connection_id: i64,
+    flavor: SqlFlavor,
+    db_name: Option<String>,
+    statements: Vec<String>,
+    confirm_token: Option<&str>,
+) -> Result<DdlStatementResult, String> {
+    apply_ddl(
+        app_state,
+        db_state,
+        connection_id,
+        flavor,
+        db_name,
+        &statements,
+        confirm_token,
+    )
+    .await?;
+    Ok(DdlStatementResult {
+        statement: statements.join(";\n"),
+        executed: true,
+    })
+}
+
+fn preview(statements: Vec<String>) -> DdlStatementResult {
+    DdlStatementResult {
+        statement: statements.join(";\n"),
+        executed: false,
+    }
+}
+
+// 按 SELECT 创建视图。or_replace 时替换同名视图（SQLite 先删除再创建，生产环境需要确认令牌）。
+// 未确认时只返回语句供预览
+#[command]
+pub async fn create_view(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: ViewArgs,
+    or_replace: Option<bool>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Views").await?;
+    let view = qualified_name(args.db_name.as_deref(), &args.name, flavor);
+    let body = view_body(&args, flavor)?;
+    let or_replace = or_replace == Some(true);
+    let statements = match flavor {
+        SqlFlavor::MySql if or_replace => vec![format!("CREATE OR REPLACE VIEW {} {}", view, body)],
+        SqlFlavor::Sqlite if or_replace => vec![
+            format!("DROP VIEW IF EXISTS {}", view),
+            format!("CREATE VIEW {} {}", view, body),
+        ],
+        _ => vec![format!("CREATE VIEW {} {}", view, body)],
+    };
+    if confirmed != Some(true) {
+        return Ok(preview(statements));
+    }
+    run_view_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        args.db_name.clone(),
+        statements,
+        confirm_token.as_deref(),
+    )
+    .await
+}
+
+// 修改已有视图的定义（SQLite 不支持 ALTER VIEW，在一个事务中删除后重建）。
+// SQLite 上的 DROP VIEW 在生产环境需要确认令牌
+#[command]
+pub async fn alter_view(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    args: ViewArgs,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Views").await?;
+    let view = qualified_name(args.db_name.as_deref(), &args.name, flavor);
+    let body = view_body(&args, flavor)?;
+    let statements = match flavor {
+        SqlFlavor::MySql => vec![format!("ALTER VIEW {} {}", view, body)],
+        SqlFlavor::Sqlite => vec![
+            format!("DROP VIEW {}", view),
+            format!("CREATE VIEW {} {}", view, body),
+        ],
+    };
+    if confirmed != Some(true) {
+        return Ok(preview(statements));
+    }
+    run_view_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        args.db_name.clone(),
+        statements,
+        confirm_token.as_deref(),
+    )
+    .await
+}
+
+// 删除视图：未确认时只返回语句；生产环境还需要确认令牌
+#[command]
+pub async fn drop_view(
+    app_state: State<'_, AppState>,
+    db_state: State<'_, DbState>,
+    connection_id: i64,
+    name: String,
+    db_name: Option<String>,
+    confirmed: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<DdlStatementResult, String> {
+    let flavor = connection_flavor(&db_state, connection_id, "Views").await?;
+    let statements = vec![format!(
+        "DROP VIEW {}",
+        qualified_name(db_name.as_deref(), &name, flavor)
+    )];
+    if confirmed != Some(true) {
+        return Ok(preview(statements));
+    }
+    run_view_ddl(
+        &app_state,
+        &db_state,
+        connection_id,
+        flavor,
+        db_name,
+        statements,
+        confirm_token.as_deref(),
+    )
+    .await
+}
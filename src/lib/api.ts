@@ -86,7 +86,7 @@ export async function invokeScanListValues<T = any>(
 }
 
 export async function invokeSql<T = any>(
-    payload: { connectionId: number, sql: string, dbName?: string }
+    payload: { connectionId: number, sql: string, dbName?: string, withWarnings?: boolean }
 ): Promise<T> {
     return executeWithLogging('mysql', payload.sql, () =>
         invoke<T>("execute_sql", payload)
@@ -23,7 +23,7 @@ async function getDb(): Promise<Database> {
 export async function getAllConnections(): Promise<Connection[]> {
     const database = await getDb();
     const rows = await database.select<Connection[]>(
-        'SELECT id, name, db_type, host, port, username, password, database, created_at, sort_order, group_id FROM connections ORDER BY sort_order ASC, created_at DESC'
+        'SELECT id, name, db_type, host, port, username, password, database, created_at, sort_order, group_id, last_used_at, use_count FROM connections ORDER BY sort_order ASC, created_at DESC'
     );
     return rows;
 }
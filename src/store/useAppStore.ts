@@ -23,6 +23,8 @@ export interface Connection {
   created_at?: string;
   sort_order?: number;
   group_id?: number;
+  last_used_at?: string;
+  use_count?: number;
 }
 
 export type TabType = 'connection' | 'query' | 'table-schema' | 'database-tables' | 'redis-db';